@@ -5,10 +5,14 @@ use goose::config::{Config, ExtensionConfig};
 
 use crate::commands::bench::agent_generator;
 use crate::commands::configure::handle_configure;
+use crate::commands::diff::handle_session_diff;
 use crate::commands::info::handle_info;
 use crate::commands::mcp::run_server;
 use crate::commands::recipe::{handle_deeplink, handle_validate};
-use crate::commands::session::{handle_session_list, handle_session_remove};
+use crate::commands::session::{
+    handle_session_archive, handle_session_remove, handle_session_unarchive,
+};
+use crate::commands::session_list::{handle_session_list, resolve_session_to_resume};
 use crate::logging::setup_logging;
 use crate::recipe::load_recipe;
 use crate::session;
@@ -59,6 +63,21 @@ fn extract_identifier(identifier: Identifier) -> session::Identifier {
     }
 }
 
+/// Store sessions in `dir` when given, then check that a configured session
+/// directory can be written to before any session starts
+fn configure_session_dir(dir: Option<PathBuf>) {
+    if let Some(dir) = dir {
+        std::env::set_var(goose::session::SESSION_DIR_ENV, dir);
+    }
+    if std::env::var_os(goose::session::SESSION_DIR_ENV).is_none() {
+        return;
+    }
+    if let Err(err) = goose::session::check_session_dir() {
+        eprintln!("{}: {}", console::style("Error").red().bold(), err);
+        std::process::exit(1);
+    }
+}
+
 #[derive(Subcommand)]
 enum SessionCommand {
     #[command(about = "List all available sessions")]
@@ -80,6 +99,26 @@ enum SessionCommand {
             long_help = "Sort sessions by date in ascending order (oldest first). Default is descending order (newest first)."
         )]
         ascending: bool,
+
+        #[arg(long = "include-archived", help = "Also list archived sessions")]
+        include_archived: bool,
+    },
+    #[command(about = "Resume a previous session by name")]
+    Resume {
+        #[arg(
+            short,
+            long,
+            value_name = "NAME",
+            help = "Session id or description to resume (partial matches allowed)",
+            long_help = "Resume the session whose id (file base name) or description matches this name. Partial matches are offered for selection. Without a name, choose from all sessions."
+        )]
+        name: Option<String>,
+        #[arg(
+            long = "session-dir",
+            value_name = "PATH",
+            help = "Directory for session files (overrides GOOSE_SESSION_DIR)"
+        )]
+        session_dir: Option<PathBuf>,
     },
     #[command(about = "Remove sessions")]
     Remove {
@@ -93,6 +132,85 @@ enum SessionCommand {
         )]
         regex: String,
     },
+    #[command(about = "Compress sessions into the session archive")]
+    Archive {
+        #[arg(
+            short,
+            long,
+            value_name = "ID",
+            help = "Session id to archive",
+            conflicts_with = "older_than",
+            required_unless_present = "older_than"
+        )]
+        session: Option<String>,
+        #[arg(
+            long = "older-than",
+            value_name = "DAYS",
+            help = "Archive every session not modified in the last DAYS days"
+        )]
+        older_than: Option<u64>,
+    },
+    #[command(about = "Restore an archived session")]
+    Unarchive {
+        #[arg(short, long, value_name = "ID", help = "Session id to unarchive")]
+        session: String,
+    },
+    #[command(about = "Branch a session and send the same message to two models")]
+    Fork {
+        #[arg(short, long, value_name = "ID", help = "Session id to fork")]
+        session: String,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Number of messages of the session to keep in each branch"
+        )]
+        at: usize,
+        #[arg(
+            short,
+            long,
+            value_name = "TEXT",
+            help = "Message to send to both branches"
+        )]
+        message: String,
+        #[arg(
+            long = "model-a",
+            value_name = "PROVIDER:MODEL",
+            help = "Model for the first branch, e.g. openai:gpt-4o"
+        )]
+        model_a: String,
+        #[arg(
+            long = "model-b",
+            value_name = "PROVIDER:MODEL",
+            help = "Model for the second branch"
+        )]
+        model_b: String,
+    },
+    #[command(about = "Import a conversation from another AI tool as a new session")]
+    Import {
+        #[arg(short, long, value_name = "PATH", help = "Export file to import")]
+        file: PathBuf,
+        #[arg(
+            long,
+            value_enum,
+            help = "Format of the export file",
+            long_help = "Format of the export file: the conversations.json of a ChatGPT or Claude data export, or Chat Completions API messages (openai_api) as JSON or JSONL."
+        )]
+        format: session::ImportFormat,
+    },
+    #[command(about = "Compare two sessions that share a start")]
+    Diff {
+        #[arg(long = "session-a", value_name = "ID", help = "First session id")]
+        session_a: String,
+        #[arg(long = "session-b", value_name = "ID", help = "Second session id")]
+        session_b: String,
+        #[arg(
+            long = "from-message",
+            value_name = "N",
+            help = "Index of the message to start the diff from",
+            long_help = "Compare the sessions from this message index instead of from the end of their longest common prefix."
+        )]
+        from_message: Option<usize>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -190,7 +308,7 @@ enum Command {
             short,
             long,
             help = "Resume a previous session (last used or specified by --name)",
-            long_help = "Continue from a previous chat session. If --name or --path is provided, resumes that specific session. Otherwise resumes the last used session."
+            long_help = "Continue from a previous chat session. If --name or --path is provided, resumes that specific session. Otherwise asks which session to resume."
         )]
         resume: bool,
 
@@ -231,6 +349,60 @@ enum Command {
             value_delimiter = ','
         )]
         builtins: Vec<String>,
+
+        /// Custom location for the command history file
+        #[arg(
+            long = "history-file",
+            value_name = "PATH",
+            help = "Path to the command history file (overrides GOOSE_HISTORY_FILE)",
+            long_help = "Store interactive command history in this file instead of the default ~/.config/goose/history.txt. Missing directories are created automatically.",
+            conflicts_with = "no_history"
+        )]
+        history_file: Option<PathBuf>,
+
+        /// Disable command history
+        #[arg(
+            long = "no-history",
+            help = "Disable loading and saving command history for this session"
+        )]
+        no_history: bool,
+
+        /// Only allow these tools
+        #[arg(
+            long = "allow-tools",
+            value_name = "TOOLS",
+            help = "Only allow these tools to be called (e.g., 'developer__shell' or 'developer,memory')",
+            long_help = "Comma-separated list of tools the agent may call in this session. Entries are full tool names (e.g. 'developer__shell') or extension names to allow all of an extension's tools. Any other tool call is denied.",
+            value_delimiter = ','
+        )]
+        allow_tools: Option<Vec<String>>,
+
+        /// Never allow these tools
+        #[arg(
+            long = "deny-tools",
+            value_name = "TOOLS",
+            help = "Deny these tools from being called (e.g., 'developer__shell')",
+            long_help = "Comma-separated list of tools the agent may not call in this session. Entries are full tool names (e.g. 'developer__shell') or extension names to deny all of an extension's tools.",
+            value_delimiter = ','
+        )]
+        deny_tools: Vec<String>,
+
+        /// Log every tool call to a JSONL file
+        #[arg(
+            long = "tool-log",
+            value_name = "PATH",
+            help = "Append a JSON line for every tool call to this file (overrides GOOSE_TOOL_LOG_FILE)"
+        )]
+        tool_log: Option<PathBuf>,
+
+        /// Directory to read and write session files in
+        #[arg(
+            long = "session-dir",
+            value_name = "PATH",
+            help = "Directory for session files (overrides GOOSE_SESSION_DIR)",
+            long_help = "Read and write session files in this directory instead of the default goose data directory. The directory is created if it does not exist."
+        )]
+        session_dir: Option<PathBuf>,
     },
 
     /// Execute commands from an instruction file
@@ -243,7 +415,8 @@ enum Command {
             value_name = "FILE",
             help = "Path to instruction file containing commands. Use - for stdin.",
             conflicts_with = "input_text",
-            conflicts_with = "recipe"
+            conflicts_with = "recipe",
+            conflicts_with = "input_file"
         )]
         instructions: Option<String>,
 
@@ -255,7 +428,8 @@ enum Command {
             help = "Input text to provide to Goose directly",
             long_help = "Input text containing commands for Goose. Use this in lieu of the instructions argument.",
             conflicts_with = "instructions",
-            conflicts_with = "recipe"
+            conflicts_with = "recipe",
+            conflicts_with = "input_file"
         )]
         input_text: Option<String>,
 
@@ -267,10 +441,41 @@ enum Command {
             help = "Path to recipe.yaml file",
             long_help = "Path to a recipe.yaml file that defines a custom agent configuration",
             conflicts_with = "instructions",
-            conflicts_with = "input_text"
+            conflicts_with = "input_text",
+            conflicts_with = "input_file"
         )]
         recipe: Option<String>,
 
+        /// Path to a file of messages to send one after the other
+        #[arg(
+            long = "input-file",
+            value_name = "FILE",
+            help = "Path to a file of messages, one per line, to send one after the other",
+            long_help = "Send each line of the file as a message, waiting for each reply before the next one, and exit after the last. Lines starting with '{' are JSON objects with a 'role' and 'text', and only those with the 'user' role are sent. Replies are separated by '---'.",
+            conflicts_with = "interactive"
+        )]
+        input_file: Option<PathBuf>,
+
+        /// Path to write the final session to as JSON
+        #[arg(
+            long = "output-session",
+            value_name = "FILE",
+            help = "Write the messages of the session to this JSON file after the last input",
+            requires = "input_file"
+        )]
+        output_session: Option<PathBuf>,
+
+        /// Limit the rounds of tool calls in reply to each message
+        #[arg(
+            long = "max-turns",
+            value_name = "N",
+            help = "Stop after N rounds of tool calls in reply to a message",
+            long_help = "Stop the agent once it has made N rounds of tool calls in reply to a message, answering further tool calls with a 'max turns reached' error and exiting with a non-zero status. Guards against agents that keep calling tools.",
+            value_parser = clap::value_parser!(usize),
+            conflicts_with = "interactive"
+        )]
+        max_turns: Option<usize>,
+
         /// Continue in interactive mode after processing input
         #[arg(
             short = 's',
@@ -398,24 +603,105 @@ pub async fn cli() -> Result<()> {
             extensions,
             remote_extensions,
             builtins,
+            history_file,
+            no_history,
+            allow_tools,
+            deny_tools,
+            tool_log,
+            session_dir,
         }) => {
+            configure_session_dir(session_dir);
             return match command {
                 Some(SessionCommand::List {
                     verbose,
                     format,
                     ascending,
+                    include_archived,
                 }) => {
-                    handle_session_list(verbose, format, ascending)?;
+                    handle_session_list(verbose, format, ascending, include_archived)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Archive {
+                    session,
+                    older_than,
+                }) => {
+                    handle_session_archive(session, older_than)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Unarchive { session }) => {
+                    handle_session_unarchive(session)?;
                     Ok(())
                 }
                 Some(SessionCommand::Remove { id, regex }) => {
                     handle_session_remove(id, regex)?;
                     return Ok(());
                 }
+                Some(SessionCommand::Fork {
+                    session,
+                    at,
+                    message,
+                    model_a,
+                    model_b,
+                }) => {
+                    session::handle_session_fork(session, at, message, model_a, model_b).await?;
+                    Ok(())
+                }
+                Some(SessionCommand::Import { file, format }) => {
+                    session::handle_session_import(file, format).await?;
+                    Ok(())
+                }
+                Some(SessionCommand::Diff {
+                    session_a,
+                    session_b,
+                    from_message,
+                }) => {
+                    handle_session_diff(session_a, session_b, from_message)?;
+                    Ok(())
+                }
+                Some(SessionCommand::Resume { name, session_dir }) => {
+                    configure_session_dir(session_dir);
+                    let session_file = resolve_session_to_resume(name).unwrap_or_else(|err| {
+                        eprintln!("{}: {}", console::style("Error").red().bold(), err);
+                        std::process::exit(1);
+                    });
+                    let mut session = build_session(SessionBuilderConfig {
+                        identifier: Some(session::Identifier::Path(session_file)),
+                        resume: true,
+                        extensions,
+                        remote_extensions,
+                        builtins,
+                        extensions_override: None,
+                        additional_system_prompt: None,
+                        debug,
+                        history_file,
+                        no_history,
+                        allow_tools,
+                        deny_tools,
+                        tool_log_file: tool_log,
+                        max_turns: None,
+                    })
+                    .await;
+                    setup_logging(
+                        session.session_file().file_stem().and_then(|s| s.to_str()),
+                        None,
+                    )?;
+                    let _ = session.interactive(None).await;
+                    Ok(())
+                }
                 None => {
+                    // Without a session to resume, let the user pick one as `session resume` does
+                    let identifier = match identifier.map(extract_identifier) {
+                        None if resume => Some(session::Identifier::Path(
+                            resolve_session_to_resume(None).unwrap_or_else(|err| {
+                                eprintln!("{}: {}", console::style("Error").red().bold(), err);
+                                std::process::exit(1);
+                            }),
+                        )),
+                        identifier => identifier,
+                    };
                     // Run session command by default
                     let mut session = build_session(SessionBuilderConfig {
-                        identifier: identifier.map(extract_identifier),
+                        identifier,
                         resume,
                         extensions,
                         remote_extensions,
@@ -423,6 +709,12 @@ pub async fn cli() -> Result<()> {
                         extensions_override: None,
                         additional_system_prompt: None,
                         debug,
+                        history_file,
+                        no_history,
+                        allow_tools,
+                        deny_tools,
+                        tool_log_file: tool_log,
+                        max_turns: None,
                     })
                     .await;
                     setup_logging(
@@ -438,6 +730,9 @@ pub async fn cli() -> Result<()> {
             instructions,
             input_text,
             recipe,
+            input_file,
+            output_session,
+            max_turns,
             interactive,
             identifier,
             resume,
@@ -489,8 +784,13 @@ pub async fn cli() -> Result<()> {
                         additional_system_prompt: Some(recipe.instructions),
                     }
                 }
+                (None, None, None) if input_file.is_some() => InputConfig {
+                    contents: None,
+                    extensions_override: None,
+                    additional_system_prompt: None,
+                },
                 (None, None, None) => {
-                    eprintln!("Error: Must provide either --instructions (-i), --text (-t), or --recipe. Use -i - for stdin.");
+                    eprintln!("Error: Must provide either --instructions (-i), --text (-t), --recipe, or --input-file. Use -i - for stdin.");
                     std::process::exit(1);
                 }
             };
@@ -504,6 +804,12 @@ pub async fn cli() -> Result<()> {
                 extensions_override: input_config.extensions_override,
                 additional_system_prompt: input_config.additional_system_prompt,
                 debug,
+                history_file: None,
+                no_history: false,
+                allow_tools: None,
+                deny_tools: Vec::new(),
+                tool_log_file: None,
+                max_turns,
             })
             .await;
 
@@ -512,10 +818,22 @@ pub async fn cli() -> Result<()> {
                 None,
             )?;
 
-            if interactive {
+            if let Some(input_file) = input_file {
+                let prompts = session::read_prompts(&input_file).unwrap_or_else(|err| {
+                    eprintln!("{}: {:#}", console::style("Error").red().bold(), err);
+                    std::process::exit(1);
+                });
+                if let Err(e) = session.batch(prompts, output_session.as_deref()).await {
+                    eprintln!("{}: {}", console::style("Error").red().bold(), e);
+                    std::process::exit(1);
+                }
+            } else if interactive {
                 let _ = session.interactive(input_config.contents).await;
             } else if let Some(contents) = input_config.contents {
-                let _ = session.headless(contents).await;
+                if let Err(e) = session.headless(contents).await {
+                    eprintln!("{}: {}", console::style("Error").red().bold(), e);
+                    std::process::exit(1);
+                }
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
                 std::process::exit(1);
@@ -40,6 +40,12 @@ pub async fn agent_generator(
         extensions_override: None,
         additional_system_prompt: None,
         debug: false,
+        history_file: None,
+        no_history: true,
+        allow_tools: None,
+        deny_tools: Vec::new(),
+        tool_log_file: None,
+        max_turns: None,
     })
     .await;
 
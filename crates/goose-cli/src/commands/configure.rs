@@ -29,10 +29,18 @@ fn get_display_name(extension_id: &str) -> String {
     match extension_id {
         "developer" => "Developer Tools".to_string(),
         "computercontroller" => "Computer Controller".to_string(),
+        "confluence" => "Confluence".to_string(),
+        "email" => "Email".to_string(),
         "googledrive" => "Google Drive".to_string(),
+        "graphql" => "GraphQL".to_string(),
         "memory" => "Memory".to_string(),
+        "notion" => "Notion".to_string(),
+        "slack" => "Slack".to_string(),
         "tutorial" => "Tutorial".to_string(),
         "jetbrains" => "JetBrains".to_string(),
+        "jira" => "Jira".to_string(),
+        "kubernetes" => "Kubernetes".to_string(),
+        "azure_blob" => "Azure Blob Storage".to_string(),
         // Add other extensions as needed
         _ => {
             extension_id
@@ -482,22 +490,62 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                     "Computer Controller",
                     "controls for webscraping, file caching, and automations",
                 )
+                .item(
+                    "confluence",
+                    "Confluence",
+                    "Search, read and edit Confluence pages - requires CONFLUENCE_URL, CONFLUENCE_USER and CONFLUENCE_API_TOKEN",
+                )
+                .item(
+                    "email",
+                    "Email",
+                    "Send email over SMTP and read it over IMAP - requires GOOSE_EMAIL_* settings",
+                )
                 .item(
                     "googledrive",
                     "Google Drive",
                     "Search and read content from google drive - additional config required",
                 )
+                .item(
+                    "graphql",
+                    "GraphQL",
+                    "Query any GraphQL endpoint - optionally uses GOOSE_GRAPHQL_TOKEN for GOOSE_GRAPHQL_ENDPOINT",
+                )
                 .item(
                     "memory",
                     "Memory",
                     "Tools to save and retrieve durable memories",
                 )
+                .item(
+                    "notion",
+                    "Notion",
+                    "Search, read and edit Notion pages and databases - requires NOTION_API_KEY",
+                )
+                .item(
+                    "slack",
+                    "Slack",
+                    "Read and post Slack messages - requires SLACK_BOT_TOKEN",
+                )
                 .item(
                     "tutorial",
                     "Tutorial",
                     "Access interactive tutorials and guides",
                 )
                 .item("jetbrains", "JetBrains", "Connect to jetbrains IDEs")
+                .item(
+                    "jira",
+                    "Jira",
+                    "Search and edit Jira issues - requires JIRA_URL, JIRA_USER and JIRA_API_TOKEN",
+                )
+                .item(
+                    "kubernetes",
+                    "Kubernetes",
+                    "Inspect and manage the cluster of your current kubeconfig context",
+                )
+                .item(
+                    "azure_blob",
+                    "Azure Blob Storage",
+                    "Manage containers and blobs - requires AZURE_STORAGE_CONNECTION_STRING or AZURE_STORAGE_ACCOUNT and AZURE_STORAGE_KEY",
+                )
                 .interact()?
                 .to_string();
 
@@ -0,0 +1,218 @@
+use anyhow::Result;
+use console::{style, Color};
+use goose::message::{Message, MessageContent};
+use goose::session::{self, Identifier};
+use mcp_core::role::Role;
+use std::path::{Path, PathBuf};
+
+/// How two sessions differ after the messages they share
+#[derive(Debug)]
+pub struct SessionDiff<'a> {
+    /// Number of leading messages the sessions have in common
+    pub shared_prefix: usize,
+    /// Index of the first message shown in the diff
+    pub start: usize,
+    pub only_a: &'a [Message],
+    pub only_b: &'a [Message],
+}
+
+/// Messages are compared on their role and content, as the timestamps of a replayed
+/// conversation differ even when the messages are the same
+fn same_message(a: &Message, b: &Message) -> bool {
+    a.role == b.role && a.content == b.content
+}
+
+/// Compare two sessions, from `from_message` or else from the end of their shared prefix
+pub fn diff_sessions<'a>(
+    a: &'a [Message],
+    b: &'a [Message],
+    from_message: Option<usize>,
+) -> SessionDiff<'a> {
+    let shared_prefix = a
+        .iter()
+        .zip(b)
+        .take_while(|(a, b)| same_message(a, b))
+        .count();
+    let start = from_message.unwrap_or(shared_prefix);
+    SessionDiff {
+        shared_prefix,
+        start,
+        only_a: a.get(start..).unwrap_or_default(),
+        only_b: b.get(start..).unwrap_or_default(),
+    }
+}
+
+/// The role of a message and a summary of each piece of its content
+fn describe_message(message: &Message) -> String {
+    let role = match message.role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+    };
+    let content: Vec<String> = message
+        .content
+        .iter()
+        .map(|content| match content {
+            MessageContent::Text(text) => text.text.clone(),
+            MessageContent::ToolRequest(request) => request.to_readable_string(),
+            MessageContent::ToolResponse(response) => match &response.tool_result {
+                Ok(_) => format!("Tool result for {}", response.id),
+                Err(e) => format!("Tool error for {}: {}", response.id, e),
+            },
+            MessageContent::Thinking(thinking) => format!("Thinking: {}", thinking.thinking),
+            MessageContent::Image(_) => "[image]".to_string(),
+            _ => "[other content]".to_string(),
+        })
+        .collect();
+    format!("{}: {}", role, content.join("\n"))
+}
+
+fn render_messages(label: &str, color: Color, start: usize, messages: &[Message]) -> Vec<String> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(offset, message)| {
+            format!(
+                "{} {} {}",
+                style(format!("[{}]", label)).fg(color).bold(),
+                style(format!("#{}", start + offset)).dim(),
+                style(describe_message(message)).fg(color)
+            )
+        })
+        .collect()
+}
+
+/// Render a diff as the messages only in session A, in red, then those only in
+/// session B, in blue, followed by a summary
+pub fn render_diff(diff: &SessionDiff, name_a: &str, name_b: &str) -> String {
+    let mut lines = vec![
+        format!("{} {}", style("Session A:").red().bold(), name_a),
+        format!("{} {}", style("Session B:").blue().bold(), name_b),
+        String::new(),
+    ];
+    if diff.start != diff.shared_prefix {
+        lines.push(format!("Comparing from message #{}", diff.start));
+        lines.push(String::new());
+    }
+
+    lines.extend(render_messages("A", Color::Red, diff.start, diff.only_a));
+    if !diff.only_a.is_empty() && !diff.only_b.is_empty() {
+        lines.push(String::new());
+    }
+    lines.extend(render_messages("B", Color::Blue, diff.start, diff.only_b));
+    if diff.only_a.is_empty() && diff.only_b.is_empty() {
+        lines.push("The sessions have no divergent messages".to_string());
+    }
+
+    lines.push(String::new());
+    lines.push(format!(
+        "Shared prefix: {} messages, divergent messages: {} in session A, {} in session B",
+        diff.shared_prefix,
+        diff.only_a.len(),
+        diff.only_b.len()
+    ));
+    lines.join("\n")
+}
+
+/// The file of a session given by id, or by the path of its file
+fn session_file(id: &str) -> Result<PathBuf> {
+    let path = if Path::new(id).is_file() {
+        PathBuf::from(id)
+    } else {
+        session::get_path(Identifier::Name(id.to_string()))
+    };
+    // Reading a session creates its file when missing, which a diff should never do
+    if !path.is_file() {
+        return Err(anyhow::anyhow!("Session '{}' not found", id));
+    }
+    Ok(path)
+}
+
+pub fn handle_session_diff(
+    session_a: String,
+    session_b: String,
+    from_message: Option<usize>,
+) -> Result<()> {
+    let messages_a = session::read_messages(&session_file(&session_a)?)?;
+    let messages_b = session::read_messages(&session_file(&session_b)?)?;
+    let diff = diff_sessions(&messages_a, &messages_b, from_message);
+    println!("{}", render_diff(&diff, &session_a, &session_b));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::session::SessionMetadata;
+    use tempfile::TempDir;
+
+    fn shared_start() -> Vec<Message> {
+        vec![
+            Message::user().with_text("Write a haiku about rust"),
+            Message::assistant().with_text("Borrowed, never owned"),
+            Message::user().with_text("Make it about crabs"),
+        ]
+    }
+
+    fn write_session(dir: &TempDir, name: &str, messages: &[Message]) -> String {
+        let path = dir.path().join(format!("{}.jsonl", name));
+        let mut lines = vec![serde_json::to_string(&SessionMetadata::default()).unwrap()];
+        lines.extend(messages.iter().map(|m| serde_json::to_string(m).unwrap()));
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_diff_sessions() {
+        console::set_colors_enabled(false);
+        let dir = TempDir::new().unwrap();
+        let mut a = shared_start();
+        a.push(Message::assistant().with_text("Sideways scuttling"));
+        a.push(Message::user().with_text("Thanks"));
+        let mut b = shared_start();
+        // The same message at a different time is still shared
+        b[0].created += 60;
+        b.push(Message::assistant().with_text("Red shell, safe memory"));
+        let path_a = write_session(&dir, "a", &a);
+        let path_b = write_session(&dir, "b", &b);
+
+        let messages_a = session::read_messages(&session_file(&path_a).unwrap()).unwrap();
+        let messages_b = session::read_messages(&session_file(&path_b).unwrap()).unwrap();
+        let diff = diff_sessions(&messages_a, &messages_b, None);
+        assert_eq!(diff.shared_prefix, 3);
+        assert_eq!(
+            render_diff(&diff, "a", "b"),
+            [
+                "Session A: a",
+                "Session B: b",
+                "",
+                "[A] #3 assistant: Sideways scuttling",
+                "[A] #4 user: Thanks",
+                "",
+                "[B] #3 assistant: Red shell, safe memory",
+                "",
+                "Shared prefix: 3 messages, divergent messages: 2 in session A, 1 in session B",
+            ]
+            .join("\n")
+        );
+
+        let diff = diff_sessions(&messages_a, &messages_b, Some(2));
+        let rendered = render_diff(&diff, "a", "b");
+        assert!(rendered.contains("Comparing from message #2"));
+        assert!(rendered.contains("[A] #2 user: Make it about crabs"));
+        assert!(rendered.contains("[B] #2 user: Make it about crabs"));
+        assert!(
+            rendered.ends_with("3 messages, divergent messages: 3 in session A, 2 in session B")
+        );
+
+        let diff = diff_sessions(&messages_a, &messages_b, Some(10));
+        assert!(diff.only_a.is_empty() && diff.only_b.is_empty());
+    }
+
+    #[test]
+    fn test_missing_session() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("missing.jsonl");
+        assert!(session_file(&path.to_string_lossy()).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -1,7 +1,9 @@
 pub mod bench;
 pub mod configure;
+pub mod diff;
 pub mod info;
 pub mod mcp;
 pub mod recipe;
 pub mod session;
+pub mod session_list;
 pub mod update;
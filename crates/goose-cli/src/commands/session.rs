@@ -1,7 +1,17 @@
 use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use goose::session::info::{get_session_info, SessionInfo, SortOrder};
+use goose::session::{self, SessionMetadata};
 use regex::Regex;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Archived sessions are compressed into this subdirectory of the session directory
+const ARCHIVE_DIR: &str = "archive";
 
 pub fn remove_sessions(sessions: Vec<SessionInfo>) -> Result<()> {
     println!("The following sessions will be removed:");
@@ -16,7 +26,7 @@ pub fn remove_sessions(sessions: Vec<SessionInfo>) -> Result<()> {
 
     if should_delete {
         for session in sessions {
-            fs::remove_file(session.path.clone())
+            session::remove_session(Path::new(&session.path))
                 .with_context(|| format!("Failed to remove session file '{}'", session.path))?;
             println!("Session `{}` removed.", session.id);
         }
@@ -65,53 +75,235 @@ pub fn handle_session_remove(id: String, regex_string: String) -> Result<()> {
     remove_sessions(matched_sessions)
 }
 
-pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Result<()> {
-    let sort_order = if ascending {
-        SortOrder::Ascending
-    } else {
-        SortOrder::Descending
-    };
+fn archive_dir() -> Result<PathBuf> {
+    Ok(session::ensure_session_dir()?.join(ARCHIVE_DIR))
+}
 
-    let sessions = match get_session_info(sort_order) {
-        Ok(sessions) => sessions,
-        Err(e) => {
-            tracing::error!("Failed to list sessions: {:?}", e);
-            return Err(anyhow::anyhow!("Failed to list sessions"));
-        }
+fn archived_path(id: &str) -> Result<PathBuf> {
+    Ok(archive_dir()?.join(format!("{}.jsonl.gz", id)))
+}
+
+/// Move the checkpoints of a session, when it has any, to be next to another session file
+fn move_checkpoints(session_file: &Path, target_session_file: &Path) -> Result<()> {
+    let source = session::checkpoints_path(session_file);
+    if !source.is_file() {
+        return Ok(());
+    }
+    let target = session::checkpoints_path(target_session_file);
+    fs::rename(&source, &target)
+        .with_context(|| format!("Failed to move checkpoints '{}'", source.display()))
+}
+
+/// Compress a session file into the archive directory, removing the original
+pub fn archive_session(id: &str) -> Result<PathBuf> {
+    let source = session::ensure_session_dir()?.join(format!("{}.jsonl", id));
+    if !source.is_file() {
+        return Err(anyhow::anyhow!("Session '{}' not found.", id));
+    }
+    fs::create_dir_all(archive_dir()?)?;
+    let target = archived_path(id)?;
+
+    let mut encoder = GzEncoder::new(File::create(&target)?, Compression::default());
+    io::copy(&mut File::open(&source)?, &mut encoder)?;
+    encoder.finish()?;
+    move_checkpoints(&source, &archive_dir()?.join(format!("{}.jsonl", id)))?;
+    fs::remove_file(&source)
+        .with_context(|| format!("Failed to remove session file '{}'", source.display()))?;
+    Ok(target)
+}
+
+/// Restore an archived session to the session directory, removing the archive
+pub fn unarchive_session(id: &str) -> Result<PathBuf> {
+    let source = archived_path(id)?;
+    if !source.is_file() {
+        return Err(anyhow::anyhow!("Archived session '{}' not found.", id));
+    }
+    let target = session::ensure_session_dir()?.join(format!("{}.jsonl", id));
+    if target.exists() {
+        return Err(anyhow::anyhow!(
+            "Session '{}' already exists outside the archive.",
+            id
+        ));
+    }
+
+    let mut decoder = GzDecoder::new(File::open(&source)?);
+    io::copy(&mut decoder, &mut File::create(&target)?)?;
+    move_checkpoints(&archive_dir()?.join(format!("{}.jsonl", id)), &target)?;
+    fs::remove_file(&source)
+        .with_context(|| format!("Failed to remove archived session '{}'", source.display()))?;
+    Ok(target)
+}
+
+fn archived_metadata(path: &Path) -> Result<SessionMetadata> {
+    let mut line = String::new();
+    BufReader::new(GzDecoder::new(File::open(path)?)).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// The sessions in the archive, whose paths are their compressed files
+pub fn archived_session_info() -> Result<Vec<SessionInfo>> {
+    let dir = archive_dir()?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let Some(id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".jsonl.gz"))
+            .map(String::from)
+        else {
+            continue;
+        };
+        let modified = path
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|time| {
+                chrono::DateTime::<chrono::Utc>::from(time)
+                    .format("%Y-%m-%d %H:%M:%S UTC")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| "Unknown".to_string());
+        let metadata = archived_metadata(&path)
+            .with_context(|| format!("Failed to read archived session '{}'", id))?;
+        sessions.push(SessionInfo {
+            id,
+            path: path.to_string_lossy().to_string(),
+            modified,
+            metadata,
+        });
+    }
+    Ok(sessions)
+}
+
+/// Whether a session was listed from the archive
+pub fn is_archived(session: &SessionInfo) -> bool {
+    session.path.ends_with(".jsonl.gz")
+}
+
+/// Ids of the sessions whose files were last modified more than `days` days ago
+fn sessions_older_than(days: u64) -> Result<Vec<String>> {
+    let cutoff = days
+        .checked_mul(24 * 60 * 60)
+        .and_then(|seconds| SystemTime::now().checked_sub(Duration::from_secs(seconds)));
+    // No file can be older than a cutoff before the earliest representable time
+    let Some(cutoff) = cutoff else {
+        return Ok(Vec::new());
     };
+    Ok(session::list_sessions()?
+        .into_iter()
+        .filter(|(_, path)| {
+            path.metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified < cutoff)
+        })
+        .map(|(id, _)| id)
+        .collect())
+}
 
-    match format.as_str() {
-        "json" => {
-            println!("{}", serde_json::to_string(&sessions)?);
-        }
-        _ => {
-            if sessions.is_empty() {
-                println!("No sessions found");
-                return Ok(());
-            } else {
-                println!("Available sessions:");
-                for SessionInfo {
-                    id,
-                    path,
-                    metadata,
-                    modified,
-                } in sessions
-                {
-                    let description = if metadata.description.is_empty() {
-                        "(none)"
-                    } else {
-                        &metadata.description
-                    };
-                    let output = format!("{} - {} - {}", id, description, modified);
-                    if verbose {
-                        println!("  {}", output);
-                        println!("    Path: {}", path);
-                    } else {
-                        println!("{}", output);
-                    }
-                }
-            }
+pub fn handle_session_archive(id: Option<String>, older_than: Option<u64>) -> Result<()> {
+    let ids = match (id, older_than) {
+        (Some(id), _) => vec![id],
+        (None, Some(days)) => sessions_older_than(days)?,
+        (None, None) => {
+            return Err(anyhow::anyhow!(
+                "Neither --session nor --older-than flags provided."
+            ))
         }
+    };
+    if ids.is_empty() {
+        println!("No sessions to archive.");
+    }
+    for id in ids {
+        archive_session(&id)?;
+        println!("Session `{}` archived.", id);
     }
     Ok(())
 }
+
+pub fn handle_session_unarchive(id: String) -> Result<()> {
+    unarchive_session(&id)?;
+    println!("Session `{}` unarchived.", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session_list::resolve_session_to_resume;
+    use tempfile::TempDir;
+
+    fn write_session(dir: &Path, id: &str, description: &str) -> PathBuf {
+        let mut metadata = SessionMetadata::new(dir.to_path_buf());
+        metadata.description = description.to_string();
+        let path = dir.join(format!("{}.jsonl", id));
+        fs::write(&path, serde_json::to_string(&metadata).unwrap() + "\n").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_archive_and_resume_session() {
+        let dir = TempDir::new().unwrap();
+        temp_env::with_var(session::SESSION_DIR_ENV, Some(dir.path()), || {
+            let path = write_session(dir.path(), "old-session", "Old work");
+            let checkpoints = session::checkpoints_path(&path);
+            fs::write(&checkpoints, "[]").unwrap();
+            let archived_file = archive_session("old-session").unwrap();
+            assert!(!path.exists());
+            assert!(!checkpoints.exists());
+            assert!(dir
+                .path()
+                .join("archive")
+                .join("old-session.checkpoints.json")
+                .is_file());
+            assert_eq!(
+                archived_file,
+                dir.path().join("archive").join("old-session.jsonl.gz")
+            );
+            assert!(archived_file.is_file());
+
+            assert!(get_session_info(SortOrder::Descending).unwrap().is_empty());
+            let archived = archived_session_info().unwrap();
+            assert_eq!(archived.len(), 1);
+            assert_eq!(archived[0].id, "old-session");
+            assert_eq!(archived[0].metadata.description, "Old work");
+            assert!(is_archived(&archived[0]));
+
+            let resumed = resolve_session_to_resume(Some("old-session".to_string())).unwrap();
+            assert_eq!(resumed, path);
+            assert!(!archived_file.exists());
+            assert!(checkpoints.is_file());
+            assert_eq!(
+                session::read_metadata(&path).unwrap().description,
+                "Old work"
+            );
+            assert!(archived_session_info().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_sessions_older_than() {
+        let dir = TempDir::new().unwrap();
+        temp_env::with_var(session::SESSION_DIR_ENV, Some(dir.path()), || {
+            write_session(dir.path(), "recent", "");
+            let stale = write_session(dir.path(), "stale", "");
+            File::options()
+                .write(true)
+                .open(&stale)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(10 * 24 * 60 * 60))
+                .unwrap();
+
+            assert_eq!(sessions_older_than(7).unwrap(), vec!["stale".to_string()]);
+            assert!(sessions_older_than(u64::MAX).unwrap().is_empty());
+            assert!(sessions_older_than(u64::MAX / (24 * 60 * 60))
+                .unwrap()
+                .is_empty());
+            handle_session_archive(None, Some(7)).unwrap();
+            assert!(!stale.exists());
+            assert!(dir.path().join("recent.jsonl").exists());
+        });
+    }
+}
@@ -0,0 +1,254 @@
+use super::session::{archived_session_info, is_archived, unarchive_session};
+use anyhow::Result;
+use goose::session::info::{get_session_info, SessionInfo, SortOrder};
+use std::path::PathBuf;
+
+/// Outcome of looking up a session by name
+#[derive(Debug)]
+pub enum SessionMatch {
+    /// The name identified exactly one session
+    Found(SessionInfo),
+    /// The name partially matched several sessions and the user needs to pick one
+    Ambiguous(Vec<SessionInfo>),
+    /// Nothing matched the name
+    NotFound,
+}
+
+fn describe(session: &SessionInfo) -> &str {
+    if session.metadata.description.is_empty() {
+        "(none)"
+    } else {
+        &session.metadata.description
+    }
+}
+
+/// Format a single session as a line of the `goose session list` output
+pub fn format_session(session: &SessionInfo) -> String {
+    format!(
+        "{} - {} - {} messages - {}{}",
+        session.id,
+        describe(session),
+        session.metadata.message_count,
+        session.modified,
+        if is_archived(session) {
+            " [archived]"
+        } else {
+            ""
+        }
+    )
+}
+
+/// Find sessions for a user provided name
+///
+/// An exact match on the session id (the file's base name) or on the description
+/// wins outright. Otherwise every session whose id or description contains the
+/// name (case insensitive) is a candidate.
+pub fn match_sessions(name: &str, sessions: &[SessionInfo]) -> SessionMatch {
+    if let Some(session) = sessions
+        .iter()
+        .find(|s| s.id == name || s.metadata.description == name)
+    {
+        return SessionMatch::Found(session.clone());
+    }
+
+    let needle = name.to_lowercase();
+    let mut candidates: Vec<SessionInfo> = sessions
+        .iter()
+        .filter(|s| {
+            s.id.to_lowercase().contains(&needle)
+                || s.metadata.description.to_lowercase().contains(&needle)
+        })
+        .cloned()
+        .collect();
+
+    match candidates.len() {
+        0 => SessionMatch::NotFound,
+        1 => SessionMatch::Found(candidates.remove(0)),
+        _ => SessionMatch::Ambiguous(candidates),
+    }
+}
+
+/// Ask the user to pick one of several sessions
+fn select_session(prompt: &str, sessions: &[SessionInfo]) -> Result<SessionInfo> {
+    let mut select = cliclack::select(prompt);
+    for (i, session) in sessions.iter().enumerate() {
+        select = select.item(
+            i,
+            format!("{}. {}", i + 1, describe(session)),
+            format!(
+                "{} - {} messages - {}",
+                session.id, session.metadata.message_count, session.modified
+            ),
+        );
+    }
+    let selected = select.interact()?;
+    Ok(sessions[selected].clone())
+}
+
+/// Unarchive the archived session matching a name so that it can be resumed
+fn resolve_archived_session(name: &str) -> Result<PathBuf> {
+    let archived = archived_session_info()?;
+    let session = match match_sessions(name, &archived) {
+        SessionMatch::Found(session) => session,
+        SessionMatch::Ambiguous(candidates) => select_session(
+            &format!("Multiple archived sessions match '{}', which one?", name),
+            &candidates,
+        )?,
+        SessionMatch::NotFound => {
+            return Err(anyhow::anyhow!("No session matches '{}'", name));
+        }
+    };
+    println!("Unarchiving session `{}`", session.id);
+    unarchive_session(&session.id)
+}
+
+/// Resolve the session file to resume for `goose session resume`
+///
+/// With a name, the session is looked up by id or description, asking the user to
+/// disambiguate partial matches. Without a name the user picks from all sessions
+/// (most recent first) unless there is only one. A name that only matches an archived
+/// session unarchives it.
+pub fn resolve_session_to_resume(name: Option<String>) -> Result<PathBuf> {
+    let sessions = get_session_info(SortOrder::Descending)?;
+    if sessions.is_empty() && name.is_none() {
+        return Err(anyhow::anyhow!("No previous sessions found"));
+    }
+
+    let session = match name {
+        Some(name) => match match_sessions(&name, &sessions) {
+            SessionMatch::Found(session) => session,
+            SessionMatch::Ambiguous(candidates) => select_session(
+                &format!("Multiple sessions match '{}', which one?", name),
+                &candidates,
+            )?,
+            SessionMatch::NotFound => return resolve_archived_session(&name),
+        },
+        None if sessions.len() == 1 => sessions[0].clone(),
+        None => select_session("Which session would you like to resume?", &sessions)?,
+    };
+    Ok(PathBuf::from(session.path))
+}
+
+pub fn handle_session_list(
+    verbose: bool,
+    format: String,
+    ascending: bool,
+    include_archived: bool,
+) -> Result<()> {
+    let sort_order = if ascending {
+        SortOrder::Ascending
+    } else {
+        SortOrder::Descending
+    };
+
+    let mut sessions = match get_session_info(sort_order) {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            tracing::error!("Failed to list sessions: {:?}", e);
+            return Err(anyhow::anyhow!("Failed to list sessions"));
+        }
+    };
+    if include_archived {
+        // Archived sessions follow the active ones, in the same order
+        let mut archived = archived_session_info()?;
+        archived.sort_by(|a, b| a.modified.cmp(&b.modified));
+        if !ascending {
+            archived.reverse();
+        }
+        sessions.extend(archived);
+    }
+
+    match format.as_str() {
+        "json" => {
+            println!("{}", serde_json::to_string(&sessions)?);
+        }
+        _ => {
+            if sessions.is_empty() {
+                println!("No sessions found");
+                return Ok(());
+            } else {
+                println!("Available sessions:");
+                for session in sessions {
+                    let output = format_session(&session);
+                    if verbose {
+                        println!("  {}", output);
+                        println!("    Path: {}", session.path);
+                    } else {
+                        println!("{}", output);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::session::SessionMetadata;
+
+    fn synthetic_session(id: &str, description: &str, message_count: usize) -> SessionInfo {
+        let mut metadata = SessionMetadata::new(PathBuf::from("/tmp"));
+        metadata.description = description.to_string();
+        metadata.message_count = message_count;
+        SessionInfo {
+            id: id.to_string(),
+            path: format!("/sessions/{}.jsonl", id),
+            modified: "2025-01-01 12:00:00 UTC".to_string(),
+            metadata,
+        }
+    }
+
+    fn sessions() -> Vec<SessionInfo> {
+        vec![
+            synthetic_session("20250101_120000", "Refactor parser", 4),
+            synthetic_session("20250102_090000", "Parser benchmarks", 10),
+            synthetic_session("project-x", "", 2),
+        ]
+    }
+
+    #[test]
+    fn test_format_session() {
+        let lines: Vec<String> = sessions().iter().map(format_session).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "20250101_120000 - Refactor parser - 4 messages - 2025-01-01 12:00:00 UTC",
+                "20250102_090000 - Parser benchmarks - 10 messages - 2025-01-01 12:00:00 UTC",
+                "project-x - (none) - 2 messages - 2025-01-01 12:00:00 UTC",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_sessions_exact() {
+        match match_sessions("project-x", &sessions()) {
+            SessionMatch::Found(session) => assert_eq!(session.id, "project-x"),
+            other => panic!("Expected exact match, got {:?}", other),
+        }
+        match match_sessions("Parser benchmarks", &sessions()) {
+            SessionMatch::Found(session) => assert_eq!(session.id, "20250102_090000"),
+            other => panic!("Expected exact match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_match_sessions_fuzzy() {
+        match match_sessions("refactor", &sessions()) {
+            SessionMatch::Found(session) => assert_eq!(session.id, "20250101_120000"),
+            other => panic!("Expected single match, got {:?}", other),
+        }
+        match match_sessions("parser", &sessions()) {
+            SessionMatch::Ambiguous(candidates) => {
+                let ids: Vec<&str> = candidates.iter().map(|s| s.id.as_str()).collect();
+                assert_eq!(ids, vec!["20250101_120000", "20250102_090000"]);
+            }
+            other => panic!("Expected ambiguous match, got {:?}", other),
+        }
+        assert!(matches!(
+            match_sessions("nothing", &sessions()),
+            SessionMatch::NotFound
+        ));
+    }
+}
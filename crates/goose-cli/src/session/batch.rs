@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// A line of a batch file written as JSON
+#[derive(Deserialize)]
+struct BatchLine {
+    role: String,
+    text: String,
+}
+
+/// Parse the prompts of a batch file, one per line
+///
+/// Lines starting with `{` are JSON objects with a `role` and `text`, of which only the user
+/// messages are sent. Other lines are sent as they are, and blank lines are skipped.
+pub fn parse_prompts(contents: &str) -> Result<Vec<String>> {
+    let mut prompts = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !line.starts_with('{') {
+            prompts.push(line.to_string());
+            continue;
+        }
+
+        let entry: BatchLine = serde_json::from_str(line)
+            .with_context(|| format!("Invalid JSON message on line {}", number + 1))?;
+        if entry.role == "user" {
+            prompts.push(entry.text);
+        }
+    }
+    Ok(prompts)
+}
+
+/// Read the prompts of a batch file, see [`parse_prompts`]
+pub fn read_prompts(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read input file {}", path.display()))?;
+    parse_prompts(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_prompts() {
+        let prompts = parse_prompts("list the files\n\n  summarize README.md  \n").unwrap();
+        assert_eq!(prompts, vec!["list the files", "summarize README.md"]);
+    }
+
+    #[test]
+    fn test_parse_json_prompts() {
+        let contents = r#"{"role": "user", "text": "first\nwith two lines"}
+{"role": "assistant", "text": "skipped"}
+{"role": "user", "text": "second"}
+"#;
+        assert_eq!(
+            parse_prompts(contents).unwrap(),
+            vec!["first\nwith two lines", "second"]
+        );
+
+        let err = parse_prompts("hello\n{\"role\": \"user\"}").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
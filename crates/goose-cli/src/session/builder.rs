@@ -6,6 +6,7 @@ use goose::providers::create;
 use goose::session;
 use goose::session::Identifier;
 use mcp_client::transport::Error as McpClientError;
+use std::path::PathBuf;
 use std::process;
 
 use super::output;
@@ -33,6 +34,18 @@ pub struct SessionBuilderConfig {
     pub additional_system_prompt: Option<String>,
     /// Enable debug printing
     pub debug: bool,
+    /// Custom location for the interactive command history file
+    pub history_file: Option<PathBuf>,
+    /// Disable loading and saving the interactive command history
+    pub no_history: bool,
+    /// If set, only these tools may be called during the session
+    pub allow_tools: Option<Vec<String>>,
+    /// Tools that may not be called during the session
+    pub deny_tools: Vec<String>,
+    /// Custom location for the tool call log
+    pub tool_log_file: Option<PathBuf>,
+    /// Stop after this many rounds of tool calls in response to a message
+    pub max_turns: Option<usize>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
@@ -69,7 +82,10 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         } else {
             // Try to resume most recent session
             match session::get_most_recent_session() {
-                Ok(file) => file,
+                Ok(file) => {
+                    output::display_picked_session(&file);
+                    file
+                }
                 Err(_) => {
                     output::render_error("Cannot resume - no previous sessions found");
                     process::exit(1);
@@ -139,6 +155,13 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
 
     // Create new session
     let mut session = Session::new(agent, session_file.clone(), session_config.debug);
+    session.history_file = if session_config.no_history {
+        None
+    } else {
+        Some(super::resolve_history_file(session_config.history_file))
+    };
+    session.tool_log_file = super::resolve_tool_log_file(session_config.tool_log_file);
+    session.max_turns = session_config.max_turns;
 
     // Add extensions if provided
     for extension_str in session_config.extensions {
@@ -164,6 +187,18 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         }
     }
 
+    // Restrict the tools available to the agent if requested
+    if session_config.allow_tools.is_some() || !session_config.deny_tools.is_empty() {
+        session
+            .restrict_tools(
+                session_config
+                    .allow_tools
+                    .map(|tools| tools.into_iter().collect()),
+                session_config.deny_tools.into_iter().collect(),
+            )
+            .await;
+    }
+
     // Add CLI-specific system prompt extension
     session
         .agent
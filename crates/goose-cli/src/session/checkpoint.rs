@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use goose::session::checkpoints_path;
+use serde::{Deserialize, Serialize};
+
+/// Name of the checkpoint created with each session
+pub const START_CHECKPOINT: &str = "start";
+
+/// Prefix of the checkpoints created before each agent response
+pub const AUTO_CHECKPOINT_PREFIX: &str = "auto_";
+
+/// Number of automatic checkpoints kept, the oldest are dropped first
+pub const MAX_AUTO_CHECKPOINTS: usize = 20;
+
+/// A named point in the conversation to roll back to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub name: String,
+    /// Number of messages in the conversation at the checkpoint
+    pub index: usize,
+}
+
+/// Load the checkpoints of a session, which are empty when none were saved
+pub fn load(session_file: &Path) -> Vec<Checkpoint> {
+    let path = checkpoints_path(session_file);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable checkpoints {}: {}", path.display(), e);
+        Vec::new()
+    })
+}
+
+pub fn save(session_file: &Path, checkpoints: &[Checkpoint]) -> Result<()> {
+    let path = checkpoints_path(session_file);
+    let json = serde_json::to_string_pretty(checkpoints)?;
+    std::fs::write(&path, json)
+        .with_context(|| format!("Failed to save checkpoints to {}", path.display()))
+}
+
+/// Add a checkpoint, replacing any with the same name, and drop the oldest automatic
+/// checkpoints beyond [`MAX_AUTO_CHECKPOINTS`]
+pub fn add(checkpoints: &mut Vec<Checkpoint>, checkpoint: Checkpoint) {
+    checkpoints.retain(|existing| existing.name != checkpoint.name);
+    checkpoints.push(checkpoint);
+
+    let is_auto = |checkpoint: &Checkpoint| checkpoint.name.starts_with(AUTO_CHECKPOINT_PREFIX);
+    let mut excess = checkpoints
+        .iter()
+        .filter(|checkpoint| is_auto(checkpoint))
+        .count()
+        .saturating_sub(MAX_AUTO_CHECKPOINTS);
+    checkpoints.retain(|checkpoint| {
+        if excess > 0 && is_auto(checkpoint) {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
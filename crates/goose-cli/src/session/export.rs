@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use goose::message::{Message, MessageContent};
+use mcp_core::content::Content;
+use mcp_core::role::Role;
+
+/// The file a session is exported to when no path is given, in the current directory
+pub fn default_export_path(now: DateTime<Local>) -> PathBuf {
+    PathBuf::from(format!("session-export-{}.md", now.format("%Y%m%d-%H%M%S")))
+}
+
+/// A fenced code block around `text`, with a fence longer than any backtick run inside it
+fn code_block(info: &str, text: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{info}\n{}\n{fence}\n", text.trim_end())
+}
+
+fn render_content(content: &MessageContent) -> Option<String> {
+    match content {
+        MessageContent::Text(text) => Some(format!("{}\n", text.text.trim_end())),
+        MessageContent::ToolRequest(request) => Some(match &request.tool_call {
+            Ok(call) => code_block(
+                "json",
+                &format!(
+                    "// tool call: {}\n{}",
+                    call.name,
+                    serde_json::to_string_pretty(&call.arguments).unwrap_or_default()
+                ),
+            ),
+            Err(e) => code_block("", &format!("Invalid tool call: {}", e)),
+        }),
+        MessageContent::ToolResponse(response) => Some(match &response.tool_result {
+            Ok(contents) => {
+                let output = contents
+                    .iter()
+                    .map(|content| match content {
+                        Content::Text(text) => text.text.clone(),
+                        Content::Image(image) => format!("[image: {}]", image.mime_type),
+                        Content::Resource(resource) => resource.get_text(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                code_block("", &format!("tool output:\n{}", output))
+            }
+            Err(e) => code_block("", &format!("tool error: {}", e)),
+        }),
+        MessageContent::Image(image) => Some(format!("*[image: {}]*\n", image.mime_type)),
+        MessageContent::Thinking(thinking) => Some(format!(
+            "<details><summary>Thinking</summary>\n\n{}\n\n</details>\n",
+            thinking.thinking.trim_end()
+        )),
+        MessageContent::ToolConfirmationRequest(_)
+        | MessageContent::FrontendToolRequest(_)
+        | MessageContent::RedactedThinking(_)
+        | MessageContent::ContextLengthExceeded(_)
+        | MessageContent::ToolProgress(_) => None,
+    }
+}
+
+/// Format a conversation as Markdown, under a header with the session file, date and model
+pub fn to_markdown(
+    messages: &[Message],
+    session_file: &Path,
+    model: &str,
+    date: DateTime<Local>,
+) -> String {
+    let mut markdown = format!(
+        "# Goose session\n\n- Session file: `{}`\n- Date: {}\n- Model: {}\n",
+        session_file.display(),
+        date.format("%Y-%m-%d %H:%M:%S %Z"),
+        model
+    );
+    for message in messages {
+        let parts: Vec<_> = message.content.iter().filter_map(render_content).collect();
+        if parts.is_empty() {
+            continue;
+        }
+        let heading = match message.role {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        };
+        markdown.push_str(&format!("\n### {}\n{}", heading, parts.join("\n")));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_markdown() {
+        let date = Local::now();
+        let messages = vec![
+            Message::user().with_text("List the files"),
+            Message::assistant()
+                .with_text("Let me look.")
+                .with_tool_request(
+                    "1",
+                    Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+                ),
+            Message::user().with_tool_response("1", Ok(vec![Content::text("Cargo.toml\nsrc")])),
+            Message::assistant().with_text("There is a ```Cargo.toml``` and a src directory."),
+        ];
+
+        let markdown = to_markdown(&messages, Path::new("/tmp/s.jsonl"), "gpt-4o", date);
+        assert!(markdown.starts_with("# Goose session\n\n- Session file: `/tmp/s.jsonl`\n"));
+        assert!(markdown.contains("- Model: gpt-4o\n"));
+        assert!(markdown.contains("\n### User\nList the files\n"));
+        assert!(markdown.contains(
+            "\n### Assistant\nLet me look.\n\n```json\n// tool call: developer__shell\n{\n  \"command\": \"ls\"\n}\n```\n"
+        ));
+        assert!(markdown.contains("\n### User\n```\ntool output:\nCargo.toml\nsrc\n```\n"));
+        assert!(markdown
+            .ends_with("\n### Assistant\nThere is a ```Cargo.toml``` and a src directory.\n"));
+    }
+
+    #[test]
+    fn test_code_block_fence_outlasts_content() {
+        assert_eq!(code_block("", "a ``` b"), "````\na ``` b\n````\n");
+        assert_eq!(code_block("json", "{}"), "```json\n{}\n```\n");
+    }
+}
@@ -0,0 +1,311 @@
+use anyhow::{Context, Result};
+use goose::agents::Agent;
+use goose::config::ExtensionConfigManager;
+use goose::message::Message;
+use goose::model::ModelConfig;
+use goose::providers::create;
+use goose::session::{self, Identifier};
+use mcp_core::role::Role;
+use std::path::{Path, PathBuf};
+
+use super::output;
+use super::prompt;
+use super::Session;
+
+/// The reply of one branch of a forked session
+#[derive(Debug)]
+pub struct ForkResult {
+    pub label: String,
+    pub model: String,
+    pub session_file: PathBuf,
+    pub response: String,
+}
+
+/// Split a `provider:model` spec into its provider and model
+fn parse_model_spec(spec: &str) -> Result<(&str, &str)> {
+    match spec.split_once(':') {
+        Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+            Ok((provider, model))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Invalid model '{}', expected <provider>:<model>",
+            spec
+        )),
+    }
+}
+
+/// Copy the first `at` messages of a session, and its metadata, into a new session file
+pub fn branch_session(source: &Path, at: usize, target: &Path) -> Result<()> {
+    let messages = session::read_messages(source)?;
+    if at > messages.len() {
+        return Err(anyhow::anyhow!(
+            "Cannot fork at message {}, the session has {} messages",
+            at,
+            messages.len()
+        ));
+    }
+    let mut metadata = session::read_metadata(source)?;
+    metadata.message_count = at;
+    session::storage::save_messages_with_metadata(target, &metadata, &messages[..at])
+}
+
+/// The first `{stem}-fork-{n}` file next to a session file that does not exist yet
+pub fn fork_path(session_file: &Path) -> PathBuf {
+    let stem = session_file
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    (1..)
+        .map(|n| session_file.with_file_name(format!("{}-fork-{}.jsonl", stem, n)))
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+/// The text of the assistant's replies after the message sent to a branch
+fn response_text(messages: &[Message], at: usize) -> String {
+    messages
+        .iter()
+        .skip(at + 1)
+        .filter(|message| message.role == Role::Assistant)
+        .map(|message| message.as_concat_text())
+        .filter(|text| !text.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Send the same message to each branch in parallel, running them headless
+async fn run_forks(
+    forks: Vec<(String, Session)>,
+    message: &str,
+    at: usize,
+) -> Result<Vec<ForkResult>> {
+    let handles: Vec<_> = forks
+        .into_iter()
+        .map(|(label, mut session)| {
+            let message = message.to_string();
+            tokio::task::spawn(async move {
+                session.quiet = true;
+                let model = session
+                    .agent
+                    .provider()
+                    .await?
+                    .get_model_config()
+                    .model_name;
+                session.headless(message).await?;
+                Ok::<_, anyhow::Error>(ForkResult {
+                    label,
+                    model,
+                    session_file: session.session_file(),
+                    response: response_text(&session.message_history(), at),
+                })
+            })
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for handle in handles {
+        results.push(handle.await??);
+    }
+    Ok(results)
+}
+
+/// An agent with the given model and the configured extensions
+async fn fork_agent(spec: &str) -> Result<Agent> {
+    let (provider, model) = parse_model_spec(spec)?;
+    let agent = Agent::new();
+    agent
+        .update_provider(create(provider, ModelConfig::new(model.to_string()))?)
+        .await?;
+    for extension in ExtensionConfigManager::get_all()?
+        .into_iter()
+        .filter(|ext| ext.enabled)
+    {
+        let name = extension.config.name();
+        agent
+            .add_extension(extension.config)
+            .await
+            .with_context(|| format!("Failed to start extension {}", name))?;
+    }
+    agent.extend_system_prompt(prompt::get_cli_prompt()).await;
+    Ok(agent)
+}
+
+/// Branch a session at message `at` into two new sessions, one per model, send each the
+/// same message and show both replies
+pub async fn handle_session_fork(
+    id: String,
+    at: usize,
+    message: String,
+    model_a: String,
+    model_b: String,
+) -> Result<()> {
+    let source = session::get_path(Identifier::Name(id.clone()));
+    if !source.is_file() {
+        return Err(anyhow::anyhow!("Session '{}' not found.", id));
+    }
+
+    let stamp = session::generate_session_id();
+    let mut forks = Vec::new();
+    for (label, spec) in [("A", &model_a), ("B", &model_b)] {
+        let agent = fork_agent(spec).await?;
+        let target = session::get_path(Identifier::Name(format!(
+            "{}_fork_{}_{}",
+            id,
+            label.to_lowercase(),
+            stamp
+        )));
+        branch_session(&source, at, &target)?;
+        forks.push((label.to_string(), Session::new(agent, target, false)));
+    }
+
+    for result in run_forks(forks, &message, at).await? {
+        output::render_fork(&result.label, &result.model, &result.response);
+        println!("Session: {}\n", result.session_file.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::model::ModelConfig;
+    use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use goose::session::SessionMetadata;
+    use mcp_core::tool::Tool;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Replies naming its model and the last message it was sent
+    struct ModelEchoProvider {
+        model: String,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ModelEchoProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new(self.model.clone())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let last = messages
+                .last()
+                .map(|m| m.as_concat_text())
+                .unwrap_or_default();
+            Ok((
+                Message::assistant().with_text(format!("{} says: {}", self.model, last)),
+                ProviderUsage::new(self.model.clone(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fork_at_message() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("source.jsonl");
+        let mut metadata = SessionMetadata::new(dir.path().to_path_buf());
+        metadata.description = "Colors".to_string();
+        metadata.accumulated_total_tokens = Some(42);
+        session::storage::save_messages_with_metadata(
+            &source,
+            &metadata,
+            &[
+                Message::user().with_text("Name a color"),
+                Message::assistant().with_text("Blue"),
+                Message::user().with_text("Another"),
+                Message::assistant().with_text("Green"),
+            ],
+        )
+        .unwrap();
+
+        let mut session = Session::new(Agent::new(), source.clone(), false);
+        assert!(session.fork(4).await.is_err());
+
+        let fork_file = session.fork(1).await.unwrap();
+        assert_eq!(fork_file, dir.path().join("source-fork-1.jsonl"));
+        assert_eq!(session.session_file(), fork_file);
+        let messages = session::read_messages(&fork_file).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].as_concat_text(), "Blue");
+        assert_eq!(session::read_messages(&source).unwrap().len(), 4);
+        let fork_metadata = session::read_metadata(&fork_file).unwrap();
+        assert_eq!(fork_metadata.working_dir, dir.path());
+        assert_eq!(fork_metadata.description, "Colors");
+        assert_eq!(fork_metadata.accumulated_total_tokens, Some(42));
+        assert_eq!(fork_metadata.message_count, 2);
+
+        // Forking the original again picks the next free name
+        let mut session = Session::new(Agent::new(), source, false);
+        assert_eq!(
+            session.fork(2).await.unwrap(),
+            dir.path().join("source-fork-2.jsonl")
+        );
+    }
+
+    async fn fork_session(dir: &Path, label: &str, model: &str) -> (String, Session) {
+        let agent = Agent::new();
+        agent
+            .update_provider(Arc::new(ModelEchoProvider {
+                model: model.to_string(),
+            }))
+            .await
+            .unwrap();
+        let target = dir.join(format!("source_fork_{}.jsonl", label));
+        branch_session(&dir.join("source.jsonl"), 2, &target).unwrap();
+        (label.to_string(), Session::new(agent, target, false))
+    }
+
+    #[tokio::test]
+    async fn test_fork_session() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("source.jsonl");
+        session::storage::save_messages_with_metadata(
+            &source,
+            &SessionMetadata::new(dir.path().to_path_buf()),
+            &[
+                Message::user().with_text("Name a color"),
+                Message::assistant().with_text("Blue"),
+                Message::user().with_text("Another"),
+                Message::assistant().with_text("Green"),
+            ],
+        )
+        .unwrap();
+
+        let forks = vec![
+            fork_session(dir.path(), "A", "model-a").await,
+            fork_session(dir.path(), "B", "model-b").await,
+        ];
+        let results = run_forks(forks, "Name a fruit", 2).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        for (result, (label, model)) in results.iter().zip([("A", "model-a"), ("B", "model-b")]) {
+            assert_eq!(result.label, label);
+            assert_eq!(result.model, model);
+            assert_eq!(result.response, format!("{} says: Name a fruit", model));
+
+            let messages = session::read_messages(&result.session_file).unwrap();
+            assert_eq!(messages.len(), 4);
+            assert_eq!(messages[1].as_concat_text(), "Blue");
+            assert_eq!(messages[2].as_concat_text(), "Name a fruit");
+        }
+        assert_ne!(results[0].session_file, results[1].session_file);
+        assert_eq!(session::read_messages(&source).unwrap().len(), 4);
+
+        assert!(branch_session(&source, 5, &dir.path().join("too_far.jsonl")).is_err());
+        assert_eq!(fork_path(&source), dir.path().join("source-fork-1.jsonl"));
+        assert!(parse_model_spec("gpt-4o").is_err());
+        assert_eq!(
+            parse_model_spec("openai:gpt-4o").unwrap(),
+            ("openai", "gpt-4o")
+        );
+    }
+}
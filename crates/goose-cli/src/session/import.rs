@@ -0,0 +1,643 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use console::style;
+use goose::message::{Message, MessageContent};
+use goose::session::{self, Identifier};
+use mcp_core::content::Content;
+use mcp_core::role::Role;
+use mcp_core::tool::ToolCall;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// The tools a conversation can be imported from
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// The conversations.json of a ChatGPT data export
+    #[value(name = "chatgpt")]
+    ChatGpt,
+    /// The conversations.json of a Claude data export
+    #[value(name = "claude")]
+    Claude,
+    /// Chat Completions API messages, as JSON or JSONL
+    #[value(name = "openai_api")]
+    OpenAiApi,
+}
+
+/// A conversation read from another tool
+#[derive(Debug)]
+pub struct ImportedConversation {
+    pub title: Option<String>,
+    pub messages: Vec<Message>,
+    /// What was left out because goose cannot represent it, such as calls to foreign tools
+    pub skipped: Vec<String>,
+}
+
+impl ImportedConversation {
+    fn new(title: Option<String>) -> Self {
+        Self {
+            title: title.filter(|title| !title.trim().is_empty()),
+            messages: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    fn push_text(&mut self, role: Role, text: &str, created: Option<i64>) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let mut message = match role {
+            Role::User => Message::user(),
+            Role::Assistant => Message::assistant(),
+        }
+        .with_text(text);
+        if let Some(created) = created {
+            message.created = created;
+        }
+        self.messages.push(message);
+    }
+}
+
+/// Parse the conversations of an export from another tool
+pub fn parse(format: ImportFormat, contents: &str) -> Result<Vec<ImportedConversation>> {
+    let conversations = match format {
+        ImportFormat::ChatGpt => parse_chatgpt(contents)?,
+        ImportFormat::Claude => parse_claude(contents)?,
+        ImportFormat::OpenAiApi => parse_openai_api(contents)?,
+    };
+    Ok(conversations
+        .into_iter()
+        .filter(|conversation| !conversation.messages.is_empty())
+        .collect())
+}
+
+/// An export holds either a single conversation or an array of them
+fn one_or_many<T: serde::de::DeserializeOwned>(contents: &str) -> Result<Vec<T>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        Many(Vec<T>),
+        One(T),
+    }
+    Ok(match serde_json::from_str(contents)? {
+        OneOrMany::Many(items) => items,
+        OneOrMany::One(item) => vec![item],
+    })
+}
+
+#[derive(Deserialize)]
+struct ChatGptConversation {
+    title: Option<String>,
+    #[serde(default)]
+    mapping: HashMap<String, ChatGptNode>,
+    current_node: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatGptNode {
+    message: Option<ChatGptMessage>,
+    parent: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatGptMessage {
+    author: ChatGptAuthor,
+    content: ChatGptContent,
+    create_time: Option<f64>,
+    recipient: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatGptAuthor {
+    role: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatGptContent {
+    content_type: String,
+    #[serde(default)]
+    parts: Vec<Value>,
+}
+
+/// ChatGPT keeps every edit of a conversation as a tree, the conversation shown is the path
+/// from the root to the current node
+fn parse_chatgpt(contents: &str) -> Result<Vec<ImportedConversation>> {
+    let conversations: Vec<ChatGptConversation> =
+        one_or_many(contents).context("Not a ChatGPT conversations export")?;
+
+    let mut imported = Vec::new();
+    for conversation in conversations {
+        let mut path = Vec::new();
+        let mut node_id = conversation.current_node.as_deref();
+        while let Some(node) = node_id.and_then(|id| conversation.mapping.get(id)) {
+            if path.len() > conversation.mapping.len() {
+                return Err(anyhow::anyhow!("The ChatGPT conversation has a cycle"));
+            }
+            path.push(node);
+            node_id = node.parent.as_deref();
+        }
+
+        let mut result = ImportedConversation::new(conversation.title);
+        for message in path
+            .into_iter()
+            .rev()
+            .filter_map(|node| node.message.as_ref())
+        {
+            let created = message.create_time.map(|time| time as i64);
+            let role = match message.author.role.as_str() {
+                "user" => Role::User,
+                "assistant" => Role::Assistant,
+                "tool" => {
+                    let name = message.author.name.as_deref().unwrap_or("unknown");
+                    result
+                        .skipped
+                        .push(format!("result of the ChatGPT tool '{}'", name));
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some(recipient) = message.recipient.as_deref().filter(|r| *r != "all") {
+                result
+                    .skipped
+                    .push(format!("call to the ChatGPT tool '{}'", recipient));
+                continue;
+            }
+            if !matches!(
+                message.content.content_type.as_str(),
+                "text" | "multimodal_text"
+            ) {
+                result.skipped.push(format!(
+                    "{} content of a {} message",
+                    message.content.content_type, message.author.role
+                ));
+                continue;
+            }
+
+            let mut texts = Vec::new();
+            for part in &message.content.parts {
+                match part.as_str() {
+                    Some(text) => texts.push(text),
+                    None => result
+                        .skipped
+                        .push(format!("attachment of a {} message", message.author.role)),
+                }
+            }
+            result.push_text(role, &texts.join("\n"), created);
+        }
+        imported.push(result);
+    }
+    Ok(imported)
+}
+
+#[derive(Deserialize)]
+struct ClaudeConversation {
+    name: Option<String>,
+    #[serde(default)]
+    chat_messages: Vec<ClaudeMessage>,
+}
+
+#[derive(Deserialize)]
+struct ClaudeMessage {
+    sender: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    content: Vec<ClaudeContent>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct ClaudeContent {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+    name: Option<String>,
+}
+
+/// Claude exports list the messages of each conversation in order, split into content blocks
+fn parse_claude(contents: &str) -> Result<Vec<ImportedConversation>> {
+    let conversations: Vec<ClaudeConversation> =
+        one_or_many(contents).context("Not a Claude conversations export")?;
+
+    let mut imported = Vec::new();
+    for conversation in conversations {
+        let mut result = ImportedConversation::new(conversation.name);
+        for message in conversation.chat_messages {
+            let role = match message.sender.as_str() {
+                "human" => Role::User,
+                "assistant" => Role::Assistant,
+                _ => continue,
+            };
+
+            // Older exports only have the text of each message
+            let text = if message.content.is_empty() {
+                message.text
+            } else {
+                let mut texts = Vec::new();
+                for block in &message.content {
+                    match block.kind.as_str() {
+                        "text" => texts.extend(block.text.as_deref()),
+                        "tool_use" => result.skipped.push(format!(
+                            "call to the Claude tool '{}'",
+                            block.name.as_deref().unwrap_or("unknown")
+                        )),
+                        // The result of a skipped tool call
+                        "tool_result" => {}
+                        kind => result.skipped.push(format!("{} content", kind)),
+                    }
+                }
+                texts.join("\n")
+            };
+            let created = message.created_at.map(|time| time.timestamp());
+            result.push_text(role, &text, created);
+        }
+        imported.push(result);
+    }
+    Ok(imported)
+}
+
+#[derive(Deserialize)]
+struct OpenAiMessage {
+    role: String,
+    #[serde(default)]
+    content: Value,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCall>,
+    tool_call_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiToolCall {
+    id: String,
+    function: OpenAiFunction,
+}
+
+#[derive(Deserialize)]
+struct OpenAiFunction {
+    name: String,
+    #[serde(default)]
+    arguments: String,
+}
+
+/// The text of Chat Completions content, either a string or an array of content parts
+fn openai_text(content: &Value, skipped: &mut Vec<String>) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| match part.get("type").and_then(Value::as_str) {
+                Some("text") => part.get("text").and_then(Value::as_str),
+                kind => {
+                    skipped.push(format!("{} content", kind.unwrap_or("unknown")));
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Chat Completions messages, as a JSON array, a request body with `messages`, or JSONL with
+/// either a message per line or a request body per line
+fn parse_openai_api(contents: &str) -> Result<Vec<ImportedConversation>> {
+    let values: Vec<Value> = match serde_json::from_str::<Value>(contents) {
+        Ok(value) => vec![value],
+        Err(_) => contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(number, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid JSON on line {}", number + 1))
+            })
+            .collect::<Result<_>>()?,
+    };
+
+    // Loose messages make up a single conversation
+    let mut conversations: Vec<Vec<Value>> = Vec::new();
+    let mut loose = Vec::new();
+    for value in values {
+        match value {
+            Value::Array(messages) => conversations.push(messages),
+            Value::Object(mut object) if object.contains_key("messages") => {
+                match object.remove("messages") {
+                    Some(Value::Array(messages)) => conversations.push(messages),
+                    _ => return Err(anyhow::anyhow!("'messages' must be an array")),
+                }
+            }
+            message => loose.push(message),
+        }
+    }
+    if !loose.is_empty() {
+        conversations.push(loose);
+    }
+
+    conversations
+        .into_iter()
+        .map(|messages| {
+            let messages: Vec<OpenAiMessage> = serde_json::from_value(Value::Array(messages))
+                .context("Not Chat Completions API messages")?;
+            Ok(openai_conversation(messages))
+        })
+        .collect()
+}
+
+fn openai_conversation(messages: Vec<OpenAiMessage>) -> ImportedConversation {
+    let mut result = ImportedConversation::new(None);
+    // The tool calls that were imported, whose results are imported too
+    let mut imported_calls = HashSet::new();
+
+    for message in messages {
+        let text = openai_text(&message.content, &mut result.skipped);
+        match message.role.as_str() {
+            "user" => result.push_text(Role::User, &text, None),
+            "assistant" => {
+                let mut reply = Message::assistant();
+                if !text.trim().is_empty() {
+                    reply = reply.with_text(text);
+                }
+                for call in message.tool_calls {
+                    match goose_tool_call(&call.function) {
+                        Some(tool_call) => {
+                            imported_calls.insert(call.id.clone());
+                            reply = reply.with_tool_request(call.id, Ok(tool_call));
+                        }
+                        None => result.skipped.push(format!(
+                            "call to the tool '{}', which is not a goose tool",
+                            call.function.name
+                        )),
+                    }
+                }
+                if !reply.content.is_empty() {
+                    result.messages.push(reply);
+                }
+            }
+            "tool" => {
+                let Some(id) = message
+                    .tool_call_id
+                    .filter(|id| imported_calls.contains(id))
+                else {
+                    continue;
+                };
+                let response = MessageContent::tool_response(id, Ok(vec![Content::text(text)]));
+                // Results of the same turn go in one message, as goose sends them
+                match result.messages.last_mut() {
+                    Some(last)
+                        if last.role == Role::User
+                            && last
+                                .content
+                                .iter()
+                                .all(|c| matches!(c, MessageContent::ToolResponse(_))) =>
+                    {
+                        last.content.push(response)
+                    }
+                    _ => result.messages.push(Message::user().with_content(response)),
+                }
+            }
+            // goose builds its own system prompt
+            _ => {}
+        }
+    }
+    result
+}
+
+/// A tool call in goose's schema, named `<extension>__<tool>` with an object of arguments
+fn goose_tool_call(function: &OpenAiFunction) -> Option<ToolCall> {
+    let (extension, tool) = function.name.split_once("__")?;
+    if extension.is_empty() || tool.is_empty() {
+        return None;
+    }
+    let arguments = if function.arguments.trim().is_empty() {
+        Value::Object(Default::default())
+    } else {
+        serde_json::from_str(&function.arguments).ok()?
+    };
+    arguments
+        .is_object()
+        .then(|| ToolCall::new(&function.name, arguments))
+}
+
+/// Import the conversations of `file` into new sessions, returning the session files
+pub async fn import_sessions(
+    file: &Path,
+    format: ImportFormat,
+) -> Result<Vec<(PathBuf, ImportedConversation)>> {
+    let contents = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let conversations = parse(format, &contents)?;
+    if conversations.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No messages to import from {}",
+            file.display()
+        ));
+    }
+
+    let stamp = session::generate_session_id();
+    let several = conversations.len() > 1;
+    let mut imported = Vec::new();
+    for (index, conversation) in conversations.into_iter().enumerate() {
+        let name = if several {
+            format!("import_{}_{}", stamp, index + 1)
+        } else {
+            format!("import_{}", stamp)
+        };
+        let session_file = session::get_path(Identifier::Name(name));
+        session::persist_messages(&session_file, &conversation.messages, None).await?;
+        let mut metadata = session::read_metadata(&session_file)?;
+        metadata.message_count = conversation.messages.len();
+        metadata.description = conversation.title.clone().unwrap_or_default();
+        session::update_metadata(&session_file, &metadata).await?;
+        imported.push((session_file, conversation));
+    }
+    Ok(imported)
+}
+
+/// Import conversations from another tool, so they can be resumed in goose
+pub async fn handle_session_import(file: PathBuf, format: ImportFormat) -> Result<()> {
+    for (session_file, conversation) in import_sessions(&file, format).await? {
+        let id = session_file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        for skipped in &conversation.skipped {
+            eprintln!(
+                "{}: skipped {}",
+                style("Warning").yellow().italic(),
+                skipped
+            );
+        }
+        println!(
+            "Imported {} messages{} into session `{}`. Resume it with `goose session resume --name {}`.",
+            conversation.messages.len(),
+            conversation
+                .title
+                .as_deref()
+                .map(|title| format!(" of \"{}\"", title))
+                .unwrap_or_default(),
+            id,
+            id
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CHATGPT_EXPORT: &str = r#"[{
+        "title": "Rust lifetimes",
+        "current_node": "n5",
+        "mapping": {
+            "root": {"message": null, "parent": null},
+            "n1": {"parent": "root", "message": {
+                "author": {"role": "system"}, "create_time": null,
+                "content": {"content_type": "text", "parts": [""]}}},
+            "n2": {"parent": "n1", "message": {
+                "author": {"role": "user"}, "create_time": 1700000000.5,
+                "content": {"content_type": "text", "parts": ["What is a lifetime?"]}}},
+            "edit": {"parent": "n1", "message": {
+                "author": {"role": "user"}, "create_time": 1700000000.0,
+                "content": {"content_type": "text", "parts": ["An abandoned edit"]}}},
+            "n3": {"parent": "n2", "message": {
+                "author": {"role": "assistant"}, "recipient": "browser",
+                "content": {"content_type": "code", "text": "search('lifetimes')"}}},
+            "n4": {"parent": "n3", "message": {
+                "author": {"role": "tool", "name": "browser"},
+                "content": {"content_type": "text", "parts": ["results"]}}},
+            "n5": {"parent": "n4", "message": {
+                "author": {"role": "assistant"}, "recipient": "all",
+                "content": {"content_type": "text", "parts": ["How long a reference is valid."]}}}
+        }
+    }]"#;
+
+    const CLAUDE_EXPORT: &str = r#"[{
+        "uuid": "c1",
+        "name": "Trip planning",
+        "chat_messages": [
+            {"sender": "human", "text": "Plan a trip to Lisbon", "content": [],
+             "created_at": "2024-05-01T10:00:00Z"},
+            {"sender": "assistant", "created_at": "2024-05-01T10:00:05Z", "content": [
+                {"type": "tool_use", "name": "web_search", "input": {"query": "Lisbon"}},
+                {"type": "tool_result", "content": []},
+                {"type": "text", "text": "Day 1: Alfama"}
+            ]},
+            {"sender": "human", "text": "Thanks", "content": [{"type": "text", "text": "Thanks"}]}
+        ]
+    }]"#;
+
+    const OPENAI_API_JSONL: &str = r#"{"role": "system", "content": "You are helpful"}
+{"role": "user", "content": "List the files here"}
+{"role": "assistant", "content": null, "tool_calls": [{"id": "call_1", "type": "function", "function": {"name": "developer__shell", "arguments": "{\"command\": \"ls\"}"}}, {"id": "call_2", "type": "function", "function": {"name": "get_weather", "arguments": "{}"}}]}
+{"role": "tool", "tool_call_id": "call_1", "content": "Cargo.toml"}
+{"role": "tool", "tool_call_id": "call_2", "content": "sunny"}
+{"role": "assistant", "content": [{"type": "text", "text": "There is a Cargo.toml"}]}
+"#;
+
+    /// Import `contents` into a temporary session directory, returning each session's messages
+    async fn import(
+        format: ImportFormat,
+        contents: &str,
+    ) -> Vec<(session::SessionMetadata, Vec<Message>, Vec<String>)> {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("export.json");
+        std::fs::write(&file, contents).unwrap();
+        let session_dir = temp_dir.path().join("sessions");
+
+        temp_env::async_with_vars([(session::SESSION_DIR_ENV, Some(&session_dir))], async {
+            import_sessions(&file, format)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(session_file, conversation)| {
+                    assert!(session_file.starts_with(&session_dir));
+                    (
+                        session::read_metadata(&session_file).unwrap(),
+                        session::read_messages(&session_file).unwrap(),
+                        conversation.skipped,
+                    )
+                })
+                .collect()
+        })
+        .await
+    }
+
+    fn roles(messages: &[Message]) -> Vec<Role> {
+        messages
+            .iter()
+            .map(|message| message.role.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_import_chatgpt() {
+        let sessions = import(ImportFormat::ChatGpt, CHATGPT_EXPORT).await;
+        assert_eq!(sessions.len(), 1);
+        let (metadata, messages, skipped) = &sessions[0];
+        assert_eq!(metadata.description, "Rust lifetimes");
+        assert_eq!(metadata.message_count, 2);
+        assert_eq!(roles(messages), vec![Role::User, Role::Assistant]);
+        assert_eq!(messages[0].as_concat_text(), "What is a lifetime?");
+        assert_eq!(messages[0].created, 1700000000);
+        assert_eq!(skipped.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_claude() {
+        let sessions = import(ImportFormat::Claude, CLAUDE_EXPORT).await;
+        let (metadata, messages, skipped) = &sessions[0];
+        assert_eq!(metadata.description, "Trip planning");
+        assert_eq!(
+            roles(messages),
+            vec![Role::User, Role::Assistant, Role::User]
+        );
+        assert_eq!(messages[1].as_concat_text(), "Day 1: Alfama");
+        assert_eq!(
+            skipped,
+            &vec!["call to the Claude tool 'web_search'".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_openai_api() {
+        let sessions = import(ImportFormat::OpenAiApi, OPENAI_API_JSONL).await;
+        let (_, messages, skipped) = &sessions[0];
+        assert_eq!(
+            roles(messages),
+            vec![Role::User, Role::Assistant, Role::User, Role::Assistant]
+        );
+
+        // Only the call to a goose tool and its result are kept
+        assert!(matches!(
+            &messages[1].content[..],
+            [MessageContent::ToolRequest(request)]
+                if request.tool_call.as_ref().unwrap().name == "developer__shell"
+        ));
+        assert!(matches!(
+            &messages[2].content[..],
+            [MessageContent::ToolResponse(response)] if response.id == "call_1"
+        ));
+        assert_eq!(
+            skipped,
+            &vec!["call to the tool 'get_weather', which is not a goose tool".to_string()]
+        );
+
+        // A request body with messages is read the same way
+        let body = r#"{"model": "gpt-4o", "messages": [
+            {"role": "user", "content": "Hi"},
+            {"role": "assistant", "content": "Hello"}
+        ]}"#;
+        let sessions = import(ImportFormat::OpenAiApi, body).await;
+        assert_eq!(roles(&sessions[0].1), vec![Role::User, Role::Assistant]);
+    }
+
+    #[test]
+    fn test_parse_invalid_export() {
+        assert!(parse(ImportFormat::Claude, "not json").is_err());
+        assert!(parse(ImportFormat::ChatGpt, "[]").unwrap().is_empty());
+    }
+}
@@ -18,6 +18,14 @@ pub enum InputResult {
     Plan(PlanCommandOptions),
     EndPlan,
     Recipe(Option<String>),
+    Capabilities,
+    ToolStats,
+    Checkpoint(String),
+    Rollback(String),
+    ListCheckpoints,
+    Fork(usize),
+    Export(Option<String>),
+    Clear,
 }
 
 #[derive(Debug)]
@@ -90,7 +98,11 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_MODE: &str = "/mode ";
     const CMD_PLAN: &str = "/plan";
     const CMD_ENDPLAN: &str = "/endplan";
+    const CMD_CHECKPOINT: &str = "/checkpoint ";
+    const CMD_ROLLBACK: &str = "/rollback ";
     const CMD_RECIPE: &str = "/recipe";
+    const CMD_FORK: &str = "/fork";
+    const CMD_EXPORT: &str = "/export";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -100,6 +112,10 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         }
         "/t" => Some(InputResult::ToggleTheme),
         "/prompts" => Some(InputResult::ListPrompts(None)),
+        "/capabilities" => Some(InputResult::Capabilities),
+        "/toolstats" => Some(InputResult::ToolStats),
+        "/checkpoints" => Some(InputResult::ListCheckpoints),
+        "/clear" => Some(InputResult::Clear),
         s if s.starts_with(CMD_PROMPTS) => {
             // Parse arguments for /prompts command
             let args = s.strip_prefix(CMD_PROMPTS).unwrap_or_default();
@@ -132,11 +148,44 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         }
         s if s.starts_with(CMD_PLAN) => parse_plan_command(s[CMD_PLAN.len()..].trim().to_string()),
         s if s == CMD_ENDPLAN => Some(InputResult::EndPlan),
+        s if s == CMD_CHECKPOINT.trim() || s.starts_with(CMD_CHECKPOINT) => Some(
+            parse_checkpoint_command(s, CMD_CHECKPOINT.trim(), InputResult::Checkpoint),
+        ),
+        s if s == CMD_ROLLBACK.trim() || s.starts_with(CMD_ROLLBACK) => Some(
+            parse_checkpoint_command(s, CMD_ROLLBACK.trim(), InputResult::Rollback),
+        ),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
+        s if s == CMD_FORK || s.starts_with("/fork ") => match s[CMD_FORK.len()..].trim().parse() {
+            Ok(index) => Some(InputResult::Fork(index)),
+            Err(_) => {
+                println!("Usage: {} <message_index>", CMD_FORK);
+                Some(InputResult::Retry)
+            }
+        },
+        s if s == CMD_EXPORT || s.starts_with("/export ") => {
+            let path = s[CMD_EXPORT.len()..].trim();
+            Some(InputResult::Export(
+                (!path.is_empty()).then(|| path.to_string()),
+            ))
+        }
         _ => None,
     }
 }
 
+/// Parse the checkpoint name given to `command`, printing the usage when it is missing
+fn parse_checkpoint_command(
+    s: &str,
+    command: &str,
+    result: fn(String) -> InputResult,
+) -> InputResult {
+    let name = s[command.len()..].trim();
+    if name.is_empty() {
+        println!("Usage: {} <name>", command);
+        return InputResult::Retry;
+    }
+    result(name.to_string())
+}
+
 fn parse_recipe_command(s: &str) -> Option<InputResult> {
     const CMD_RECIPE: &str = "/recipe";
 
@@ -238,6 +287,14 @@ fn print_help() {
                         To warm up goose before using '/plan', we recommend setting '/mode approve' & putting appropriate context into goose.
                         The model is used based on $GOOSE_PLANNER_PROVIDER and $GOOSE_PLANNER_MODEL environment variables.
                         If no model is set, the default model is used.
+/capabilities - Show the tools, extensions and model capabilities of the agent
+/checkpoint <name> - Save a checkpoint of the conversation to roll back to
+/rollback <name> - Roll the conversation back to a checkpoint
+/checkpoints - List the checkpoints of the conversation, including 'start' and the automatic ones
+/fork <message_index> - Continue in a new session with the messages up to and including the given index (from 0), keeping this session as it is
+/clear - Remove all messages to start a fresh conversation, keeping the session file and extensions
+/export [path] - Save the conversation as Markdown to the given path, or to session-export-<timestamp>.md
+/toolstats - Show how often each tool was called this session, its errors and average duration
 /endplan - Exit plan mode and return to 'normal' goose mode.
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
@@ -296,6 +353,67 @@ mod tests {
             panic!("Expected AddBuiltin");
         }
 
+        // Test capabilities command
+        assert!(matches!(
+            handle_slash_command("/capabilities"),
+            Some(InputResult::Capabilities)
+        ));
+        assert!(matches!(
+            handle_slash_command("/toolstats"),
+            Some(InputResult::ToolStats)
+        ));
+
+        // Test checkpoint commands
+        assert!(matches!(
+            handle_slash_command("/checkpoint before refactor"),
+            Some(InputResult::Checkpoint(name)) if name == "before refactor"
+        ));
+        assert!(matches!(
+            handle_slash_command("/rollback start"),
+            Some(InputResult::Rollback(name)) if name == "start"
+        ));
+        assert!(matches!(
+            handle_slash_command("/checkpoints"),
+            Some(InputResult::ListCheckpoints)
+        ));
+        assert!(matches!(
+            handle_slash_command("/rollback"),
+            Some(InputResult::Retry)
+        ));
+
+        // Test fork command
+        assert!(matches!(
+            handle_slash_command("/fork 4"),
+            Some(InputResult::Fork(4))
+        ));
+        assert!(matches!(
+            handle_slash_command("/fork"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/fork last"),
+            Some(InputResult::Retry)
+        ));
+
+        // Test clear command
+        assert!(matches!(
+            handle_slash_command("/clear"),
+            Some(InputResult::Clear)
+        ));
+
+        // Test export command
+        assert!(matches!(
+            handle_slash_command("/export"),
+            Some(InputResult::Export(None))
+        ));
+        if let Some(InputResult::Export(Some(path))) = handle_slash_command("/export notes/chat.md")
+        {
+            assert_eq!(path, "notes/chat.md");
+        } else {
+            panic!("Expected Export with a path");
+        }
+        assert!(handle_slash_command("/exports").is_none());
+
         // Test unknown commands
         assert!(handle_slash_command("/unknown").is_none());
     }
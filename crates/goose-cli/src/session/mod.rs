@@ -1,24 +1,34 @@
+mod batch;
 mod builder;
+mod checkpoint;
 mod completion;
+mod export;
+mod fork;
+mod import;
 mod input;
 mod output;
 mod prompt;
 mod thinking;
+mod tool_log;
 
+pub use batch::read_prompts;
 pub use builder::{build_session, SessionBuilderConfig};
+use checkpoint::Checkpoint;
 use console::Color;
+pub use fork::handle_session_fork;
 use goose::permission::permission_confirmation::PrincipalType;
 use goose::permission::Permission;
 use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
 pub use goose::session::Identifier;
+pub use import::{handle_session_import, ImportFormat};
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
 use etcetera::choose_app_strategy;
 use etcetera::AppStrategy;
 use goose::agents::extension::{Envs, ExtensionConfig};
-use goose::agents::{Agent, SessionConfig};
+use goose::agents::{Agent, SessionConfig, ToolRestrictions};
 use goose::config::Config;
 use goose::message::{Message, MessageContent};
 use goose::session;
@@ -28,8 +38,8 @@ use mcp_core::prompt::PromptMessage;
 
 use rand::{distributions::Alphanumeric, Rng};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio;
@@ -47,6 +57,22 @@ pub struct Session {
     completion_cache: Arc<std::sync::RwLock<CompletionCache>>,
     debug: bool, // New field for debug mode
     run_mode: RunMode,
+    // Command history file for the interactive editor, None disables history
+    history_file: Option<PathBuf>,
+    // Tools the agent is limited to in this session, None allows all tools
+    tool_whitelist: Option<HashSet<String>>,
+    // Tools the agent may not use in this session
+    tool_blacklist: HashSet<String>,
+    // Skip rendering the agent's messages, for sessions run alongside others
+    quiet: bool,
+    // File every tool call of the session is logged to as a JSON line, None disables the log
+    tool_log_file: Option<PathBuf>,
+    // Named points in the conversation to roll back to, saved next to the session file
+    checkpoints: Vec<Checkpoint>,
+    // Tokens used by the session so far, as last recorded in its metadata
+    running_token_count: u64,
+    // Rounds of tool calls the agent may make in response to a message, None is unlimited
+    max_turns: Option<usize>,
 }
 
 // Cache structure for completion data
@@ -112,6 +138,14 @@ impl Session {
             }
         };
 
+        let mut checkpoints = checkpoint::load(&session_file);
+        if checkpoints.is_empty() {
+            checkpoints.push(Checkpoint {
+                name: checkpoint::START_CHECKPOINT.to_string(),
+                index: messages.len(),
+            });
+        }
+
         Session {
             agent,
             messages,
@@ -119,7 +153,129 @@ impl Session {
             completion_cache: Arc::new(std::sync::RwLock::new(CompletionCache::new())),
             debug,
             run_mode: RunMode::Normal,
+            history_file: Some(resolve_history_file(None)),
+            tool_whitelist: None,
+            tool_blacklist: HashSet::new(),
+            quiet: false,
+            tool_log_file: resolve_tool_log_file(None),
+            checkpoints,
+            running_token_count: 0,
+            max_turns: None,
+        }
+    }
+
+    /// Save a named checkpoint at the current end of the conversation
+    ///
+    /// A checkpoint with the same name is replaced.
+    pub fn checkpoint(&mut self, name: String) -> Result<()> {
+        checkpoint::add(
+            &mut self.checkpoints,
+            Checkpoint {
+                name,
+                index: self.messages.len(),
+            },
+        );
+        checkpoint::save(&self.session_file, &self.checkpoints)
+    }
+
+    /// Roll the conversation back to a checkpoint, returning the number of messages removed
+    pub async fn rollback(&mut self, name: &str) -> Result<usize> {
+        let index = self
+            .checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.name == name)
+            .map(|checkpoint| checkpoint.index)
+            .ok_or_else(|| anyhow::anyhow!("No checkpoint named '{}'", name))?;
+        if index > self.messages.len() {
+            return Err(anyhow::anyhow!(
+                "Checkpoint '{}' is past the end of the conversation",
+                name
+            ));
+        }
+
+        let removed = self.messages.len() - index;
+        self.messages.truncate(index);
+        session::persist_messages(&self.session_file, &self.messages, None).await?;
+
+        // Checkpoints past the new end of the conversation no longer point anywhere
+        self.checkpoints
+            .retain(|checkpoint| checkpoint.index <= index);
+        checkpoint::save(&self.session_file, &self.checkpoints)?;
+        Ok(removed)
+    }
+
+    /// Remove every message of the conversation, keeping the session file, extensions and
+    /// the checkpoints at its start. Returns the number of messages removed.
+    pub async fn clear(&mut self) -> Result<usize> {
+        let removed = self.messages.len();
+        self.messages.clear();
+        session::persist_messages(&self.session_file, &self.messages, None).await?;
+
+        self.checkpoints.retain(|checkpoint| checkpoint.index == 0);
+        checkpoint::save(&self.session_file, &self.checkpoints)?;
+        Ok(removed)
+    }
+
+    /// Continue the conversation in a new session file with the messages up to and including
+    /// `index`, leaving the current session file as it is. Returns the new session file.
+    pub async fn fork(&mut self, index: usize) -> Result<PathBuf> {
+        if index >= self.messages.len() {
+            return Err(anyhow::anyhow!(
+                "Cannot fork at message {}, the session has {} messages",
+                index,
+                self.messages.len()
+            ));
         }
+
+        let fork_file = fork::fork_path(&self.session_file);
+        fork::branch_session(&self.session_file, index + 1, &fork_file)?;
+        self.messages.truncate(index + 1);
+        self.session_file = fork_file;
+
+        let message_count = self.messages.len();
+        self.checkpoints
+            .retain(|checkpoint| checkpoint.index <= message_count);
+        checkpoint::save(&self.session_file, &self.checkpoints)?;
+        Ok(self.session_file.clone())
+    }
+
+    /// Write the conversation as Markdown to `path`, or to a timestamped file in the current
+    /// directory. Returns the file written.
+    pub async fn export(&self, path: Option<&str>) -> Result<PathBuf> {
+        let now = chrono::Local::now();
+        let path = path
+            .map(PathBuf::from)
+            .unwrap_or_else(|| export::default_export_path(now));
+        let model = self.agent.provider().await?.get_model_config().model_name;
+        let markdown = export::to_markdown(&self.messages, &self.session_file, &model, now);
+        std::fs::write(&path, markdown)
+            .with_context(|| format!("Failed to export the session to {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    /// Restrict which tools the agent may call during this session
+    ///
+    /// Denied tool calls are answered with an error tool response so the model can adjust.
+    /// # Arguments
+    /// * `whitelist` - If set, only these tools (or extensions) are permitted
+    /// * `blacklist` - Tools (or extensions) that are never permitted
+    pub async fn restrict_tools(
+        &mut self,
+        whitelist: Option<HashSet<String>>,
+        blacklist: HashSet<String>,
+    ) {
+        self.tool_whitelist = whitelist;
+        self.tool_blacklist = blacklist;
+        self.agent
+            .set_tool_restrictions(ToolRestrictions {
+                whitelist: self.tool_whitelist.clone(),
+                blacklist: self.tool_blacklist.clone(),
+            })
+            .await;
     }
 
     /// Add a stdio extension to the session
@@ -317,32 +473,35 @@ impl Session {
         let completer = GooseCompleter::new(self.completion_cache.clone());
         editor.set_helper(Some(completer));
 
-        // Create and use a global history file in ~/.config/goose directory
-        // This allows command history to persist across different chat sessions
-        // instead of being tied to each individual session's messages
-        let history_file = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .expect("goose requires a home dir")
-            .in_config_dir("history.txt");
+        // Command history persists across different chat sessions instead of being tied
+        // to each individual session's messages. It lives in the goose config directory
+        // unless overridden by --history-file or GOOSE_HISTORY_FILE, or disabled by --no-history
+        let history_file = self.history_file.clone();
 
-        // Ensure config directory exists
-        if let Some(parent) = history_file.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
+        if let Some(history_file) = &history_file {
+            // Ensure the history directory exists
+            if let Some(parent) = history_file.parent() {
+                if !parent.exists() {
+                    std::fs::create_dir_all(parent)?;
+                }
             }
-        }
 
-        // Load history from the global file
-        if history_file.exists() {
-            if let Err(err) = editor.load_history(&history_file) {
-                eprintln!("Warning: Failed to load command history: {}", err);
+            // Load history from the file
+            if history_file.exists() {
+                if let Err(err) = editor.load_history(history_file) {
+                    eprintln!("Warning: Failed to load command history: {}", err);
+                }
             }
         }
 
-        // Helper function to save history after commands
+        // Helper function to save history after commands. Only new entries are appended
+        // (under a file lock) so concurrent sessions sharing a history file don't clobber it
         let save_history =
             |editor: &mut rustyline::Editor<GooseCompleter, rustyline::history::DefaultHistory>| {
-                if let Err(err) = editor.save_history(&history_file) {
-                    eprintln!("Warning: Failed to save command history: {}", err);
+                if let Some(history_file) = &history_file {
+                    if let Err(err) = editor.append_history(history_file) {
+                        eprintln!("Warning: Failed to save command history: {}", err);
+                    }
                 }
             };
 
@@ -427,6 +586,71 @@ impl Session {
                         Err(e) => output::render_error(&e.to_string()),
                     }
                 }
+                input::InputResult::Capabilities => {
+                    save_history(&mut editor);
+
+                    let capabilities = self.agent.capabilities().await;
+                    output::render_capabilities(&capabilities);
+                    continue;
+                }
+                input::InputResult::Checkpoint(name) => {
+                    save_history(&mut editor);
+
+                    match self.checkpoint(name.clone()) {
+                        Ok(()) => output::render_checkpoint_saved(&name, self.messages.len()),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                    continue;
+                }
+                input::InputResult::Clear => {
+                    save_history(&mut editor);
+
+                    match self.clear().await {
+                        Ok(removed) => output::render_clear(removed),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                    continue;
+                }
+                input::InputResult::Rollback(name) => {
+                    save_history(&mut editor);
+
+                    match self.rollback(&name).await {
+                        Ok(removed) => output::render_rollback(&name, removed),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                    continue;
+                }
+                input::InputResult::Fork(index) => {
+                    save_history(&mut editor);
+
+                    match self.fork(index).await {
+                        Ok(fork_file) => output::render_session_fork(index, &fork_file),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                    continue;
+                }
+                input::InputResult::Export(path) => {
+                    save_history(&mut editor);
+
+                    match self.export(path.as_deref()).await {
+                        Ok(path) => output::render_export(&path),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                    continue;
+                }
+                input::InputResult::ListCheckpoints => {
+                    save_history(&mut editor);
+
+                    output::render_checkpoints(&self.checkpoints, self.messages.len());
+                    continue;
+                }
+                input::InputResult::ToolStats => {
+                    save_history(&mut editor);
+
+                    let stats = self.agent.tool_call_stats().await;
+                    output::render_tool_stats(&stats);
+                    continue;
+                }
                 input::InputResult::GooseMode(mode) => {
                     save_history(&mut editor);
 
@@ -586,7 +810,40 @@ impl Session {
         self.process_message(message).await
     }
 
+    /// Process messages one after the other, separating the replies with `---`, and exit
+    ///
+    /// With `output_session`, the final conversation is also written to that file as JSON.
+    pub async fn batch(
+        &mut self,
+        prompts: Vec<String>,
+        output_session: Option<&Path>,
+    ) -> Result<()> {
+        for (i, prompt) in prompts.into_iter().enumerate() {
+            if i > 0 {
+                println!("---");
+            }
+            self.process_message(prompt).await?;
+        }
+
+        if let Some(path) = output_session {
+            let json = serde_json::to_string_pretty(&self.messages)?;
+            std::fs::write(path, json)
+                .with_context(|| format!("Failed to write the session to {}", path.display()))?;
+        }
+        Ok(())
+    }
+
     async fn process_agent_response(&mut self, interactive: bool) -> Result<()> {
+        // Make rolling back possible without any checkpoints of the user's own
+        let auto_checkpoint = format!(
+            "{}{}",
+            checkpoint::AUTO_CHECKPOINT_PREFIX,
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        );
+        if let Err(e) = self.checkpoint(auto_checkpoint) {
+            tracing::warn!("Failed to save checkpoint: {}", e);
+        }
+
         let session_id = session::Identifier::Path(self.session_file.clone());
         let mut stream = self
             .agent
@@ -600,15 +857,28 @@ impl Session {
             )
             .await?;
 
+        let mut tool_log = self.tool_log_file.as_ref().map(|path| {
+            let session_id = self.session_file.file_stem().unwrap_or_default();
+            tool_log::ToolLog::new(path, &session_id.to_string_lossy())
+        });
+
+        // Rounds of tool calls made so far, limited by max_turns
+        let mut tool_turns = 0;
+
         use futures::StreamExt;
         loop {
             tokio::select! {
                 result = stream.next() => {
                     match result {
                         Some(Ok(message)) => {
+                            // Progress of a running tool only updates the thinking indicator
+                            if let Some(MessageContent::ToolProgress(event)) = message.content.first() {
+                                if interactive {output::render_tool_progress(event)};
+                            }
                             // If it's a confirmation request, get approval but otherwise do not render/persist
-                            if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
+                            else if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
+                                output::render_tool_call_preview(&confirmation.tool_name, &confirmation.arguments);
 
                                 // Format the confirmation prompt
                                 let prompt = "Goose would like to call the above tool, do you allow?".to_string();
@@ -679,8 +949,37 @@ impl Session {
                                 // No need to update description on assistant messages
                                 session::persist_messages(&self.session_file, &self.messages, None).await?;
 
+                                if let Some(tool_log) = tool_log.as_mut() {
+                                    tool_log.observe(&message).await;
+                                }
+
                                 if interactive {output::hide_thinking()};
-                                output::render_message(&message, self.debug);
+                                if !self.quiet {output::render_message(&message, self.debug)};
+                                if interactive && !self.quiet && message.role == mcp_core::role::Role::Assistant {
+                                    match self.get_total_token_usage() {
+                                        Ok(Some(tokens)) => {
+                                            self.running_token_count = u64::try_from(tokens).unwrap_or_default();
+                                            output::render_token_usage(self.running_token_count);
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => tracing::debug!("Failed to read token usage: {}", e),
+                                    }
+                                }
+
+                                if message.role == mcp_core::role::Role::Assistant
+                                    && message.content.iter().any(|content| content.as_tool_request().is_some())
+                                {
+                                    tool_turns += 1;
+                                    if let Some(max_turns) = self.max_turns.filter(|max| tool_turns > *max) {
+                                        drop(stream);
+                                        if interactive {output::hide_thinking()};
+                                        self.stop_at_max_turns().await?;
+                                        return Err(anyhow::anyhow!(
+                                            "Stopped after reaching the limit of {} turns",
+                                            max_turns
+                                        ));
+                                    }
+                                }
                                 if interactive {output::show_thinking()};
                             }
                         }
@@ -713,6 +1012,26 @@ impl Session {
         Ok(())
     }
 
+    /// Answer the tool requests of the last message with an error instead of running them
+    async fn stop_at_max_turns(&mut self) -> Result<()> {
+        let mut response_message = Message::user();
+        if let Some(last) = self.messages.last() {
+            for request in last.content.iter().filter_map(|c| c.as_tool_request()) {
+                response_message.content.push(MessageContent::tool_response(
+                    request.id.clone(),
+                    Err(ToolError::ExecutionError("max turns reached".to_string())),
+                ));
+            }
+        }
+        if !self.quiet {
+            output::render_message(&response_message, self.debug);
+        }
+        self.messages.push(response_message);
+
+        // No need for description update here
+        session::persist_messages(&self.session_file, &self.messages, None).await
+    }
+
     async fn handle_interrupted_messages(&mut self, interrupt: bool) -> Result<()> {
         // First, get any tool requests from the last message if it exists
         let tool_requests = self
@@ -972,6 +1291,38 @@ impl Session {
     }
 }
 
+/// Resolve the location of the interactive command history file
+///
+/// An explicit path (from `--history-file`) takes precedence, then the
+/// `GOOSE_HISTORY_FILE` environment variable, and finally the global
+/// `history.txt` in the goose config directory.
+pub fn resolve_history_file(path: Option<PathBuf>) -> PathBuf {
+    path.or_else(|| {
+        std::env::var("GOOSE_HISTORY_FILE")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    })
+    .unwrap_or_else(|| {
+        choose_app_strategy(crate::APP_STRATEGY.clone())
+            .expect("goose requires a home dir")
+            .in_config_dir("history.txt")
+    })
+}
+
+/// Resolve the location of the tool call log
+///
+/// An explicit path (from `--tool-log`) takes precedence over the `GOOSE_TOOL_LOG_FILE`
+/// environment variable. Without either, tool calls are not logged.
+pub fn resolve_tool_log_file(path: Option<PathBuf>) -> Option<PathBuf> {
+    path.or_else(|| {
+        std::env::var("GOOSE_TOOL_LOG_FILE")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
 fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
     use goose::model::ModelConfig;
     use goose::providers::create;
@@ -1002,3 +1353,339 @@ fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
 
     Ok(reasoner)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::model::ModelConfig;
+    use goose::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use mcp_core::tool::{Tool, ToolCall};
+    use rustyline::history::{DefaultHistory, History};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_history_file_precedence() {
+        let temp_dir = TempDir::new().unwrap();
+        let env_path = temp_dir.path().join("env").join("history.txt");
+        let flag_path = temp_dir.path().join("flag.txt");
+
+        temp_env::with_var("GOOSE_HISTORY_FILE", Some(&env_path), || {
+            assert_eq!(resolve_history_file(None), env_path);
+            assert_eq!(resolve_history_file(Some(flag_path.clone())), flag_path);
+        });
+
+        temp_env::with_var("GOOSE_HISTORY_FILE", None::<&str>, || {
+            assert!(resolve_history_file(None).ends_with("history.txt"));
+        });
+    }
+
+    #[test]
+    fn test_history_file_records_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let history_file = temp_dir.path().join("history.txt");
+
+        temp_env::with_var("GOOSE_HISTORY_FILE", Some(&history_file), || {
+            let path = resolve_history_file(None);
+
+            // Two sessions sharing the same file should both have their commands recorded
+            let mut first = DefaultHistory::new();
+            let mut second = DefaultHistory::new();
+            first.add("list the files here").unwrap();
+            first.append(&path).unwrap();
+            second.add("/mode approve").unwrap();
+            second.append(&path).unwrap();
+            first.add("/prompts").unwrap();
+            first.append(&path).unwrap();
+
+            let mut loaded = DefaultHistory::new();
+            loaded.load(&path).unwrap();
+            let entries: Vec<&str> = loaded.iter().map(String::as_str).collect();
+            assert_eq!(
+                entries,
+                vec!["list the files here", "/mode approve", "/prompts"]
+            );
+        });
+    }
+
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for EchoProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("echo".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text("Hello"),
+                ProviderUsage::new("echo".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_headless_session_uses_session_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let session_dir = temp_dir.path().join("sessions");
+
+        temp_env::async_with_vars([(session::SESSION_DIR_ENV, Some(&session_dir))], async {
+            let agent = Agent::new();
+            agent.update_provider(Arc::new(EchoProvider)).await.unwrap();
+            let session_file = session::get_path(Identifier::Name("headless".to_string()));
+            let mut session = Session::new(agent, session_file, false);
+            session.headless("Hi".to_string()).await.unwrap();
+        })
+        .await;
+
+        let session_file = session_dir.join("headless.jsonl");
+        assert!(session_file.exists());
+        assert_eq!(session::read_messages(&session_file).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let session_file = temp_dir.path().join("checkpointed.jsonl");
+
+        let agent = Agent::new();
+        agent.update_provider(Arc::new(EchoProvider)).await.unwrap();
+        let mut session = Session::new(agent, session_file.clone(), false);
+        session.headless("Hi".to_string()).await.unwrap();
+        assert!(session
+            .checkpoints()
+            .iter()
+            .any(|c| c.name.starts_with(checkpoint::AUTO_CHECKPOINT_PREFIX) && c.index == 1));
+        session.checkpoint("greeted".to_string()).unwrap();
+        session.headless("Hi again".to_string()).await.unwrap();
+        assert_eq!(session.messages.len(), 4);
+
+        assert!(session.rollback("missing").await.is_err());
+        assert_eq!(session.rollback("greeted").await.unwrap(), 2);
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(session::read_messages(&session_file).unwrap().len(), 2);
+
+        // Checkpoints are kept next to the session, without those past the rollback
+        let checkpoints = checkpoint::load(&session_file);
+        assert_eq!(checkpoints, session.checkpoints());
+        assert_eq!(checkpoints[0].name, checkpoint::START_CHECKPOINT);
+        assert!(checkpoints.iter().all(|c| c.index <= 2));
+
+        assert_eq!(
+            session
+                .rollback(checkpoint::START_CHECKPOINT)
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(session.messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let session_file = temp_dir.path().join("batch.jsonl");
+        let output_session = temp_dir.path().join("output.json");
+
+        let agent = Agent::new();
+        agent.update_provider(Arc::new(EchoProvider)).await.unwrap();
+        let mut session = Session::new(agent, session_file.clone(), false);
+        session
+            .batch(
+                vec!["First".to_string(), "Second".to_string()],
+                Some(&output_session),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(session::read_messages(&session_file).unwrap().len(), 4);
+        let messages: Vec<Message> =
+            serde_json::from_str(&std::fs::read_to_string(&output_session).unwrap()).unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[2].as_concat_text(), "Second");
+        assert_eq!(messages[3].as_concat_text(), "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_clear_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let session_file = temp_dir.path().join("cleared.jsonl");
+
+        let agent = Agent::new();
+        agent.update_provider(Arc::new(EchoProvider)).await.unwrap();
+        let mut session = Session::new(agent, session_file.clone(), false);
+        session.headless("Hi".to_string()).await.unwrap();
+        session.checkpoint("greeted".to_string()).unwrap();
+
+        assert_eq!(session.clear().await.unwrap(), 2);
+        assert!(session.messages.is_empty());
+        assert!(session::read_messages(&session_file).unwrap().is_empty());
+        assert!(session.checkpoints().iter().all(|c| c.index == 0));
+
+        // The next message starts a new conversation with the same agent and file
+        session.headless("Hi again".to_string()).await.unwrap();
+        assert_eq!(session::read_messages(&session_file).unwrap().len(), 2);
+        assert_eq!(session.messages[0].as_concat_text(), "Hi again");
+    }
+
+    /// Lists resources once, then replies with text
+    struct ToolCallingProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for ToolCallingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("tool-caller".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let answered = messages.iter().any(|m| {
+                m.content
+                    .iter()
+                    .any(|c| matches!(c, MessageContent::ToolResponse(_)))
+            });
+            let message = if answered {
+                Message::assistant().with_text("Done")
+            } else {
+                Message::assistant().with_tool_request(
+                    "call_1",
+                    Ok(ToolCall::new(
+                        "platform__list_resources",
+                        serde_json::json!({}),
+                    )),
+                )
+            };
+            Ok((
+                message,
+                ProviderUsage::new("tool-caller".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_logged() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("tools.jsonl");
+        let session_file = temp_dir.path().join("audited.jsonl");
+
+        temp_env::async_with_vars(
+            [
+                ("GOOSE_TOOL_LOG_FILE", Some(log_file.as_os_str())),
+                ("GOOSE_MODE", Some("auto".as_ref())),
+            ],
+            async {
+                let agent = Agent::new();
+                agent
+                    .update_provider(Arc::new(ToolCallingProvider))
+                    .await
+                    .unwrap();
+                let mut session = Session::new(agent, session_file, false);
+                session
+                    .headless("List resources".to_string())
+                    .await
+                    .unwrap();
+            },
+        )
+        .await;
+
+        let log = std::fs::read_to_string(&log_file).unwrap();
+        let records: Vec<Value> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record["session_id"], "audited");
+        assert_eq!(record["tool_name"], "platform__list_resources");
+        assert_eq!(record["extension"], "platform");
+        assert_eq!(record["arguments_json"], serde_json::json!({}));
+        assert_eq!(record["result_ok"], true);
+        assert!(record["duration_ms"].is_u64());
+        assert!(record["error_message"].is_null());
+        assert!(
+            chrono::DateTime::parse_from_rfc3339(record["timestamp"].as_str().unwrap()).is_ok()
+        );
+    }
+
+    /// Calls a tool in reply to everything, never finishing on its own
+    struct LoopingProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for LoopingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("looper".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_tool_request(
+                    format!("call_{}", messages.len()),
+                    Ok(ToolCall::new(
+                        "platform__list_resources",
+                        serde_json::json!({}),
+                    )),
+                ),
+                ProviderUsage::new("looper".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_turns_stops_tool_loop() {
+        let temp_dir = TempDir::new().unwrap();
+        let session_file = temp_dir.path().join("looping.jsonl");
+
+        let messages = temp_env::async_with_vars([("GOOSE_MODE", Some("auto"))], async {
+            let agent = Agent::new();
+            agent
+                .update_provider(Arc::new(LoopingProvider))
+                .await
+                .unwrap();
+            let mut session = Session::new(agent, session_file.clone(), false);
+            session.max_turns = Some(3);
+            let err = session
+                .headless("loop forever".to_string())
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("limit of 3 turns"));
+            session.messages
+        })
+        .await;
+
+        // The message, 3 answered tool calls, and a 4th answered with an error
+        assert_eq!(messages.len(), 9);
+        let last = messages.last().unwrap();
+        let response = last.content[0].as_tool_response().unwrap();
+        assert!(matches!(
+            &response.tool_result,
+            Err(ToolError::ExecutionError(msg)) if msg == "max turns reached"
+        ));
+        assert_eq!(session::read_messages(&session_file).unwrap().len(), 9);
+    }
+}
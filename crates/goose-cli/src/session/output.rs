@@ -1,10 +1,15 @@
+use super::checkpoint::Checkpoint;
 use bat::WrappingMode;
 use console::{style, Color};
+use goose::agents::{AgentCapabilities, ToolStats};
 use goose::config::Config;
 use goose::message::{Message, MessageContent, ToolRequest, ToolResponse};
+use goose::session;
 use mcp_core::prompt::PromptArgument;
+use mcp_core::protocol::ToolProgressEvent;
 use mcp_core::tool::ToolCall;
 use serde_json::Value;
+use similar::TextDiff;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
@@ -90,6 +95,12 @@ impl ThinkingIndicator {
             spinner.stop("");
         }
     }
+
+    pub fn set_message(&mut self, message: &str) {
+        if let Some(spinner) = &self.spinner {
+            spinner.set_message(message);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -113,6 +124,28 @@ pub fn hide_thinking() {
     THINKING.with(|t| t.borrow_mut().hide());
 }
 
+/// Show the latest progress of a running tool on the thinking indicator
+pub fn render_tool_progress(event: &ToolProgressEvent) {
+    THINKING.with(|t| t.borrow_mut().set_message(&format_tool_progress(event)));
+}
+
+fn format_tool_progress(event: &ToolProgressEvent) -> String {
+    const MAX_MESSAGE_WIDTH: usize = 60;
+
+    let line = event.message.lines().last().unwrap_or_default().trim();
+    let line = if line.chars().count() > MAX_MESSAGE_WIDTH {
+        let truncated: String = line.chars().take(MAX_MESSAGE_WIDTH - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        line.to_string()
+    };
+
+    match event.progress_percent {
+        Some(percent) => format!("{} [{:.0}%] {}", event.tool_name, percent, line),
+        None => format!("{} {}", event.tool_name, line),
+    }
+}
+
 pub fn render_message(message: &Message, debug: bool) {
     let theme = get_theme();
 
@@ -143,6 +176,14 @@ pub fn render_message(message: &Message, debug: bool) {
     println!();
 }
 
+/// Render the reply of one branch of a forked session under a `## Fork {label} ({model})` heading
+pub fn render_fork(label: &str, model: &str, response: &str) {
+    print_markdown(
+        &format!("## Fork {} ({})\n\n{}\n", label, model, response),
+        get_theme(),
+    );
+}
+
 pub fn render_text(text: &str, color: Option<Color>, dim: bool) {
     let mut styled_text = style(text);
     if dim {
@@ -230,6 +271,26 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
     }
 }
 
+/// Group the digits of a count in threes, e.g. `1 234 567`
+fn format_token_count(tokens: u64) -> String {
+    let digits = tokens.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(' ');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+pub fn render_token_usage(tokens: u64) {
+    println!(
+        "{}",
+        style(format!("[{} tokens used]", format_token_count(tokens))).dim()
+    );
+}
+
 pub fn render_error(message: &str) {
     println!("\n  {} {}\n", style("error:").red().bold(), message);
 }
@@ -279,6 +340,172 @@ pub fn render_prompt_info(info: &PromptInfo) {
     println!();
 }
 
+pub fn render_capabilities(capabilities: &AgentCapabilities) {
+    println!();
+    let (provider, model) = match &capabilities.provider {
+        Some(provider) => (provider.name.as_str(), provider.model.as_str()),
+        None => ("-", "-"),
+    };
+    let context_window = capabilities
+        .context_window
+        .map(|limit| limit.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let yes_no = |flag: bool| if flag { "yes" } else { "no" };
+
+    for (key, value) in [
+        ("provider", provider.to_string()),
+        ("model", model.to_string()),
+        ("context window", context_window),
+        ("vision", yes_no(capabilities.supports_vision).to_string()),
+        (
+            "tool use",
+            yes_no(capabilities.supports_tool_use).to_string(),
+        ),
+    ] {
+        println!("  {} {}", style(format!("{:<16}", key)).dim(), value);
+    }
+
+    println!("\n {}", style("Extensions").green());
+    let name_width = capabilities
+        .extensions
+        .iter()
+        .map(|ext| ext.name.len())
+        .max()
+        .unwrap_or(0);
+    for ext in &capabilities.extensions {
+        let status = if ext.connected {
+            style("connected").green()
+        } else {
+            style("disconnected").red()
+        };
+        println!(
+            "  {} {:<8} {}",
+            style(format!("{:<width$}", ext.name, width = name_width)).cyan(),
+            ext.extension_type,
+            status
+        );
+    }
+
+    println!("\n {}", style("Tools").green());
+    let name_width = capabilities
+        .tools
+        .iter()
+        .map(|tool| tool.name.len())
+        .max()
+        .unwrap_or(0);
+    for tool in &capabilities.tools {
+        let description = tool.description.lines().next().unwrap_or_default();
+        println!(
+            "  {} {}",
+            style(format!("{:<width$}", tool.name, width = name_width)).cyan(),
+            style(description).dim()
+        );
+    }
+    println!();
+}
+
+pub fn render_checkpoint_saved(name: &str, index: usize) {
+    println!(
+        "\n  {} checkpoint {} at message {}\n",
+        style("saved").green(),
+        style(name).cyan(),
+        index
+    );
+}
+
+pub fn render_rollback(name: &str, removed: usize) {
+    println!(
+        "\n  {} to checkpoint {}, removed {} message{}\n",
+        style("rolled back").green(),
+        style(name).cyan(),
+        removed,
+        if removed == 1 { "" } else { "s" }
+    );
+}
+
+pub fn render_clear(removed: usize) {
+    println!(
+        "\n  {} the conversation, removed {} message{}\n",
+        style("cleared").green(),
+        removed,
+        if removed == 1 { "" } else { "s" }
+    );
+}
+
+pub fn render_export(path: &Path) {
+    println!(
+        "\n  {} the conversation to {}\n",
+        style("exported").green(),
+        style(path.display()).cyan()
+    );
+}
+
+pub fn render_session_fork(index: usize, session_file: &Path) {
+    println!(
+        "\n  {} at message {}, continuing in {}\n",
+        style("forked").green(),
+        index,
+        style(session_file.display()).cyan()
+    );
+}
+
+pub fn render_checkpoints(checkpoints: &[Checkpoint], message_count: usize) {
+    println!();
+    let name_width = checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.name.len())
+        .max()
+        .unwrap_or(0);
+    for checkpoint in checkpoints {
+        println!(
+            "  {} {}",
+            style(format!("{:<width$}", checkpoint.name, width = name_width)).cyan(),
+            style(format!("message {}", checkpoint.index)).dim()
+        );
+    }
+    println!(
+        "\n  {}\n",
+        style(format!("The conversation has {} messages", message_count)).dim()
+    );
+}
+
+pub fn render_tool_stats(stats: &HashMap<String, ToolStats>) {
+    println!();
+    if stats.is_empty() {
+        println!("  {}", style("No tools have been called yet").dim());
+        println!();
+        return;
+    }
+
+    let mut stats: Vec<_> = stats.iter().collect();
+    stats.sort_by(|(a_name, a), (b_name, b)| {
+        b.call_count.cmp(&a.call_count).then(a_name.cmp(b_name))
+    });
+    let name_width = stats.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    println!(
+        "  {} {:>6} {:>7} {:>10}",
+        style(format!("{:<width$}", "tool", width = name_width)).dim(),
+        style("calls").dim(),
+        style("errors").dim(),
+        style("avg ms").dim()
+    );
+    for (name, stats) in stats {
+        let errors = format!("{:>7}", stats.error_count);
+        println!(
+            "  {} {:>6} {} {:>10.1}",
+            style(format!("{:<width$}", name, width = name_width)).cyan(),
+            stats.call_count,
+            if stats.error_count > 0 {
+                style(errors).red()
+            } else {
+                style(errors)
+            },
+            stats.avg_duration_ms
+        );
+    }
+    println!();
+}
+
 pub fn render_extension_success(name: &str) {
     println!();
     println!(
@@ -362,6 +589,51 @@ fn render_shell_request(call: &ToolCall, debug: bool) {
     println!();
 }
 
+/// Render what a tool call awaiting confirmation will do, above the approval question
+///
+/// Nothing is rendered for tools without a preview, as their arguments are already shown
+/// with the tool request.
+pub fn render_tool_call_preview(name: &str, args: &Value) {
+    if let Some(preview) = preview_tool_call(name, args) {
+        println!("{}", preview);
+    }
+}
+
+/// A human readable preview of the effect of a tool call, for the tools we know how to describe
+///
+/// This only looks at the arguments (and local file sizes), so it is cheap to compute.
+pub fn preview_tool_call(name: &str, args: &Value) -> Option<String> {
+    let tool = name.rsplit("__").next().unwrap_or(name);
+    let arg = |key: &str| args.get(key).and_then(Value::as_str);
+
+    match tool {
+        "text_editor" if arg("command") == Some("str_replace") => {
+            let path = arg("path")?;
+            let diff = TextDiff::from_lines(arg("old_str")?, arg("new_str")?);
+            Some(diff.unified_diff().header(path, path).to_string())
+        }
+        "shell" => {
+            let command: String = arg("command")?
+                .chars()
+                .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+                .collect();
+            Some(format!("$ {}", command))
+        }
+        "create_file" | "update_file" => {
+            let file = arg("name").or(arg("fileId"))?;
+            let size = match (arg("body"), arg("path")) {
+                (Some(body), _) => body.len().to_string(),
+                (None, Some(path)) => std::fs::metadata(path)
+                    .map(|metadata| metadata.len().to_string())
+                    .unwrap_or_else(|_| "unknown".to_string()),
+                (None, None) => "unknown".to_string(),
+            };
+            Some(format!("{}: {} ({} bytes)", tool, file, size))
+        }
+        _ => None,
+    }
+}
+
 fn render_default_request(call: &ToolCall, debug: bool) {
     print_tool_header(call);
     print_params(&call.arguments, 0, debug);
@@ -552,6 +824,28 @@ pub fn display_session_info(resume: bool, provider: &str, model: &str, session_f
     );
 }
 
+/// Tell the user which session was picked when resuming without naming one
+pub fn display_picked_session(session_file: &Path) {
+    let id = session_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let description = session::read_metadata(session_file)
+        .map(|metadata| metadata.description)
+        .unwrap_or_default();
+    let description = if description.is_empty() {
+        "no description".to_string()
+    } else {
+        description
+    };
+    println!(
+        "{} {} {}",
+        style("resuming most recent session:").dim(),
+        style(id).cyan(),
+        style(format!("({})", description)).dim(),
+    );
+}
+
 pub fn display_greeting() {
     println!("\nGoose is running! Enter your instructions, or try asking what goose can do.\n");
 }
@@ -561,6 +855,14 @@ mod tests {
     use super::*;
     use std::env;
 
+    #[test]
+    fn test_format_token_count() {
+        assert_eq!(format_token_count(0), "0");
+        assert_eq!(format_token_count(999), "999");
+        assert_eq!(format_token_count(1234), "1 234");
+        assert_eq!(format_token_count(1234567), "1 234 567");
+    }
+
     #[test]
     fn test_short_paths_unchanged() {
         assert_eq!(shorten_path("/usr/bin", false), "/usr/bin");
@@ -603,6 +905,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_preview_str_replace_diff() {
+        let args = serde_json::json!({
+            "command": "str_replace",
+            "path": "src/main.rs",
+            "old_str": "fn main() {\n    println!(\"hello\");\n}\n",
+            "new_str": "fn main() {\n    println!(\"goodbye\");\n}\n",
+        });
+        assert_eq!(
+            preview_tool_call("developer__text_editor", &args).unwrap(),
+            "--- src/main.rs\n\
+             +++ src/main.rs\n\
+             @@ -1,3 +1,3 @@\n \
+             fn main() {\n\
+             -    println!(\"hello\");\n\
+             +    println!(\"goodbye\");\n \
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_preview_known_and_unknown_tools() {
+        let shell = serde_json::json!({"command": "ls\u{1b}[2J -la"});
+        assert_eq!(
+            preview_tool_call("developer__shell", &shell).unwrap(),
+            "$ ls[2J -la"
+        );
+
+        let upload =
+            serde_json::json!({"name": "notes.txt", "mimeType": "text/plain", "body": "hello"});
+        assert_eq!(
+            preview_tool_call("google_drive__create_file", &upload).unwrap(),
+            "create_file: notes.txt (5 bytes)"
+        );
+
+        let view = serde_json::json!({"command": "view", "path": "src/main.rs"});
+        assert_eq!(preview_tool_call("developer__text_editor", &view), None);
+
+        let unknown = serde_json::json!({"query": "weather"});
+        assert_eq!(preview_tool_call("weather__forecast", &unknown), None);
+    }
+
+    #[test]
+    fn test_format_tool_progress() {
+        let mut event = ToolProgressEvent {
+            tool_name: "developer__shell".to_string(),
+            progress_percent: None,
+            message: "compiling goose".to_string(),
+        };
+        assert_eq!(
+            format_tool_progress(&event),
+            "developer__shell compiling goose"
+        );
+
+        event.tool_name = "computercontroller__pdf_tool".to_string();
+        event.progress_percent = Some(33.3);
+        event.message = "Processed page 1 of 3".to_string();
+        assert_eq!(
+            format_tool_progress(&event),
+            "computercontroller__pdf_tool [33%] Processed page 1 of 3"
+        );
+
+        event.message = "x".repeat(100);
+        assert!(format_tool_progress(&event).ends_with(&format!("{}...", "x".repeat(57))));
+    }
+
     #[test]
     fn test_long_path_shortening() {
         assert_eq!(
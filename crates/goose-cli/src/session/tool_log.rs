@@ -0,0 +1,104 @@
+use anyhow::Result;
+use goose::message::{Message, MessageContent};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::io::AsyncWriteExt;
+
+/// A tool call made in a session, as written to the tool log
+#[derive(Debug, Serialize)]
+struct ToolLogRecord<'a> {
+    /// When the tool responded, in RFC 3339 format
+    timestamp: String,
+    session_id: &'a str,
+    /// The full (prefixed) tool name, e.g. `developer__shell`
+    tool_name: &'a str,
+    /// The extension providing the tool, when the name is prefixed with one
+    extension: Option<&'a str>,
+    arguments_json: &'a Value,
+    result_ok: bool,
+    duration_ms: u64,
+    error_message: Option<String>,
+}
+
+struct PendingCall {
+    tool_name: String,
+    arguments: Value,
+    started: Instant,
+}
+
+/// Appends a JSON line to a file for every tool call of a session, for audit trails
+pub struct ToolLog {
+    path: PathBuf,
+    session_id: String,
+    /// Requested calls waiting for their response, by request id
+    pending: HashMap<String, PendingCall>,
+}
+
+impl ToolLog {
+    pub fn new(path: &Path, session_id: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            session_id: session_id.to_string(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Note the tool requests of a message and log the calls its tool responses complete
+    pub async fn observe(&mut self, message: &Message) {
+        for content in &message.content {
+            match content {
+                MessageContent::ToolRequest(request) => {
+                    if let Ok(call) = &request.tool_call {
+                        self.pending.insert(
+                            request.id.clone(),
+                            PendingCall {
+                                tool_name: call.name.clone(),
+                                arguments: call.arguments.clone(),
+                                started: Instant::now(),
+                            },
+                        );
+                    }
+                }
+                MessageContent::ToolResponse(response) => {
+                    let Some(call) = self.pending.remove(&response.id) else {
+                        continue;
+                    };
+                    let record = ToolLogRecord {
+                        timestamp: chrono::Utc::now().to_rfc3339(),
+                        session_id: &self.session_id,
+                        tool_name: &call.tool_name,
+                        extension: call.tool_name.split_once("__").map(|(ext, _)| ext),
+                        arguments_json: &call.arguments,
+                        result_ok: response.tool_result.is_ok(),
+                        duration_ms: call.started.elapsed().as_millis() as u64,
+                        error_message: response.tool_result.as_ref().err().map(|e| e.to_string()),
+                    };
+                    if let Err(e) = self.append(&record).await {
+                        tracing::warn!(
+                            "Failed to write tool call to tool log {}: {}",
+                            self.path.display(),
+                            e
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn append(&self, record: &ToolLogRecord<'_>) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
@@ -17,6 +17,8 @@ from ctypes import c_char_p, c_bool, c_uint32, c_void_p, Structure, POINTER
 
 class ProviderType:
     DATABRICKS = 0
+    OPENAI = 1
+    ANTHROPIC = 2
 
 # Platform-specific dynamic lib name
 if platform.system() == "Darwin":
@@ -11,18 +11,98 @@
 #include <stdint.h>
 #include <stdbool.h>
 
+/*
+ Kind of error reported by goose_get_last_error
+ */
+enum goose_GooseErrorCode {
+  /*
+   A required pointer argument was NULL
+   */
+  goose_GooseErrorCode_NullPointer = 1,
+  /*
+   The provider could not be created
+   */
+  goose_GooseErrorCode_ProviderInit = 2,
+  /*
+   No API key was given, nor found in the environment
+   */
+  goose_GooseErrorCode_MissingApiKey = 3,
+  /*
+   No model name was given
+   */
+  goose_GooseErrorCode_MissingModelName = 4,
+  /*
+   No host was given, nor found in the environment
+   */
+  goose_GooseErrorCode_MissingHost = 5,
+  /*
+   The agent's reply stream failed partway
+   */
+  goose_GooseErrorCode_StreamError = 6,
+  /*
+   The agent or provider failed while running
+   */
+  goose_GooseErrorCode_RuntimeError = 7,
+  /*
+   The provider does not support the operation
+   */
+  goose_GooseErrorCode_Unsupported = 8,
+  /*
+   An argument was invalid
+   */
+  goose_GooseErrorCode_InvalidArgument = 9,
+  /*
+   A file could not be read or written
+   */
+  goose_GooseErrorCode_IoError = 10,
+};
+typedef uint32_t goose_GooseErrorCode;
+
+/*
+ Role enum for message participants
+ */
+enum goose_MessageRole {
+  /*
+   User message role
+   */
+  goose_MessageRole_User = 0,
+  /*
+   Assistant message role
+   */
+  goose_MessageRole_Assistant = 1,
+  /*
+   System message role
+   */
+  goose_MessageRole_System = 2,
+};
+typedef uint32_t goose_MessageRole;
+
 /*
  Provider Type enumeration
- Currently only Databricks is supported
  */
 enum goose_ProviderType {
   /*
    Databricks AI provider
    */
   goose_ProviderType_Databricks = 0,
+  /*
+   OpenAI provider
+   */
+  goose_ProviderType_OpenAI = 1,
+  /*
+   Anthropic provider
+   */
+  goose_ProviderType_Anthropic = 2,
 };
 typedef uint32_t goose_ProviderType;
 
+typedef struct goose_Option_AgentStreamErrorCallback goose_Option_AgentStreamErrorCallback;
+
+/*
+ A conversation kept by the caller across agent replies
+ */
+typedef struct goose_SessionFFI goose_SessionFFI;
+
 /*
  Result type for async operations
 
@@ -34,6 +114,17 @@ typedef struct goose_AsyncResult {
   char *error_message;
 } goose_AsyncResult;
 
+/*
+ An error of a goose FFI function
+
+ - code: The kind of error
+ - message: Description of the error
+ */
+typedef struct goose_GooseError {
+  goose_GooseErrorCode code;
+  char *message;
+} goose_GooseError;
+
 /*
  Pointer type for the agent
  */
@@ -42,7 +133,7 @@ typedef goose_Agent *goose_AgentPtr;
 /*
  Provider configuration used to initialize an AI provider
 
- - provider_type: Provider type (0 = Databricks, other values will produce an error)
+ - provider_type: Provider type (0 = Databricks, 1 = OpenAI, 2 = Anthropic)
  - api_key: Provider API key (null for default from environment variables)
  - model_name: Model name to use (null for provider default)
  - host: Provider host URL (null for default from environment variables)
@@ -54,6 +145,39 @@ typedef struct goose_ProviderConfigFFI {
   const char *host;
 } goose_ProviderConfigFFI;
 
+/*
+ Callback receiving the messages of a streamed reply
+
+ - message: A message of the reply as JSON, only valid for the duration of the
+   call, or NULL once the reply is complete
+ - user_data: The user_data given to goose_agent_send_message_stream
+ */
+typedef void (*goose_AgentStreamCallback)(const char *message, void *user_data);
+
+/*
+ Message structure for agent interactions
+
+ - role: Message role (User, Assistant, or System)
+ - content: Text content of the message
+ */
+typedef struct goose_MessageFFI {
+  goose_MessageRole role;
+  const char *content;
+} goose_MessageFFI;
+
+/*
+ Pointer type for a session
+ */
+typedef struct goose_SessionFFI *goose_SessionPtr;
+
+/*
+ Callback receiving log messages
+
+ - level: 1 = ERROR, 2 = WARN, 3 = INFO, 4 = DEBUG, 5 = TRACE
+ - message: The log message, only valid for the duration of the call
+ */
+typedef void (*goose_LogCallback)(uint32_t level, const char *message);
+
 /*
  Free an async result structure
 
@@ -67,6 +191,27 @@ typedef struct goose_ProviderConfigFFI {
  */
 void goose_free_async_result(struct goose_AsyncResult *result);
 
+/*
+ Get the error of the latest failed call on this thread
+
+ Functions that return NULL (or false) on failure record why, which this function
+ returns once: the error is cleared by the call.
+
+ # Returns
+
+ The error, or NULL if there is none. It must be freed with goose_free_error.
+ */
+struct goose_GooseError *goose_get_last_error(void);
+
+/*
+ Free an error returned by goose_get_last_error, including its message
+
+ # Safety
+
+ The error must be a pointer returned by goose_get_last_error, or NULL.
+ */
+void goose_free_error(struct goose_GooseError *error);
+
 /*
  Create a new agent with the given provider configuration
 
@@ -76,7 +221,8 @@ void goose_free_async_result(struct goose_AsyncResult *result);
 
  # Returns
 
- A new agent pointer, or a null pointer if creation failed
+ A new agent pointer, or a null pointer if creation failed, with the reason
+ given by goose_get_last_error
 
  # Safety
 
@@ -85,6 +231,46 @@ void goose_free_async_result(struct goose_AsyncResult *result);
  */
 goose_AgentPtr goose_agent_new(const struct goose_ProviderConfigFFI *config);
 
+/*
+ List the models available from a provider
+
+ This queries the provider's API (serving endpoints for Databricks, the models
+ endpoint for OpenAI), without creating an agent.
+
+ # Parameters
+
+ - provider_type: Provider type
+ - api_key: Provider API key (null for default from environment variables)
+ - host: Provider host URL (null for default from environment variables)
+
+ # Returns
+
+ A C string with a JSON array of model names, or NULL on error, with the reason
+ given by goose_get_last_error.
+ This string must be freed with goose_free_string when no longer needed.
+
+ # Safety
+
+ The api_key and host must be valid C strings or NULL.
+ */
+char *goose_provider_list_models(goose_ProviderType provider_type,
+                                 const char *api_key,
+                                 const char *host);
+
+/*
+ Get the recommended default model of a provider
+
+ # Parameters
+
+ - provider_type: Provider type
+
+ # Returns
+
+ A C string with the model name.
+ This string must be freed with goose_free_string when no longer needed.
+ */
+char *goose_provider_get_default_model(goose_ProviderType provider_type);
+
 /*
  Free an agent
 
@@ -102,12 +288,138 @@ goose_AgentPtr goose_agent_new(const struct goose_ProviderConfigFFI *config);
  */
 void goose_agent_free(goose_AgentPtr agent_ptr);
 
+/*
+ Add a builtin extension to the agent
+
+ Builtin extensions (e.g. "developer") are served by the goose binary. As the
+ library is loaded into another process, set GOOSE_BUILTIN_EXECUTABLE to the
+ path of the goose binary before calling this function.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - name: Name of the builtin extension
+ - out_error: Optional, set to an error message on failure. The message must be
+   freed with goose_free_string
+
+ # Returns
+
+ true if the extension was added, false otherwise
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The name must be a valid C string. out_error must be NULL or a valid pointer.
+ */
+bool goose_agent_add_builtin_extension(goose_AgentPtr agent_ptr,
+                                       const char *name,
+                                       char **out_error);
+
+/*
+ Add an extension to the agent that runs as a child process over stdio
+
+ The extension is named after the command, e.g. "npx" for "/usr/bin/npx".
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - cmd: Command to run
+ - args: Array of args_len command arguments (may be NULL if args_len is 0)
+ - env_keys, env_values: Arrays of env_count environment variable names and values
+   (may be NULL if env_count is 0)
+ - out_error: Optional, set to an error message on failure. The message must be
+   freed with goose_free_string
+
+ # Returns
+
+ true if the extension was added, false otherwise
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The cmd must be a valid C string, and the arrays must hold the given number of
+ valid C strings. out_error must be NULL or a valid pointer.
+ */
+bool goose_agent_add_stdio_extension(goose_AgentPtr agent_ptr,
+                                     const char *cmd,
+                                     const char *const *args,
+                                     uintptr_t args_len,
+                                     const char *const *env_keys,
+                                     const char *const *env_values,
+                                     uintptr_t env_count,
+                                     char **out_error);
+
+/*
+ Add an extension to the agent
+
+ The extension is a builtin (e.g. "developer") when cmd is NULL, and otherwise
+ runs cmd as a child process over stdio. Builtins are served by the goose binary,
+ so set GOOSE_BUILTIN_EXECUTABLE to its path before adding one.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - name: Name of the extension
+ - cmd: Command to run, or NULL for a builtin extension
+ - args: Array of args_len command arguments (may be NULL if args_len is 0)
+ - envs_json: JSON object of environment variables for the command, e.g.
+   {"API_KEY": "..."}, or NULL for none
+
+ # Returns
+
+ An AsyncResult telling whether the extension was added, which must be freed
+ with goose_free_async_result
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The name, and cmd and envs_json when not NULL, must be valid C strings, and
+ args must hold args_len valid C strings.
+ */
+struct goose_AsyncResult *goose_agent_add_extension(goose_AgentPtr agent_ptr,
+                                                    const char *name,
+                                                    const char *cmd,
+                                                    const char *const *args,
+                                                    uintptr_t args_len,
+                                                    const char *envs_json);
+
+/*
+ Add a tool the caller executes itself
+
+ When the model calls the tool, the agent's reply stops with the call pending,
+ see goose_agent_get_pending_tool_calls.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - name: Name of the tool
+ - description: Description of the tool for the model
+ - input_schema: JSON schema of the tool's arguments, or NULL for no arguments
+ - out_error: Optional, set to an error message on failure. The message must be
+   freed with goose_free_string
+
+ # Returns
+
+ true if the tool was added, false otherwise
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The name and description must be valid C strings, and input_schema a valid C
+ string or NULL. out_error must be NULL or a valid pointer.
+ */
+bool goose_agent_add_frontend_tool(goose_AgentPtr agent_ptr,
+                                   const char *name,
+                                   const char *description,
+                                   const char *input_schema,
+                                   char **out_error);
+
 /*
  Send a message to the agent and get the response
 
- This function sends a message to the agent and returns the response.
- Tool handling is not yet supported and will be implemented in a future commit
- so this may change significantly
+ This function sends a message to the agent and returns the response. The
+ reply stops at calls of tools added with goose_agent_add_frontend_tool, which
+ goose_agent_continue resumes.
 
  # Parameters
 
@@ -116,7 +428,9 @@ void goose_agent_free(goose_AgentPtr agent_ptr);
 
  # Returns
 
- A C string with the agent's response, or NULL on error.
+ A C string with the agent's response, or NULL on error, with the reason given
+ by goose_get_last_error. Errors while replying are also included in the
+ response, and the last of them is given by goose_get_last_error.
  This string must be freed with goose_free_string when no longer needed.
 
  # Safety
@@ -126,6 +440,281 @@ void goose_agent_free(goose_AgentPtr agent_ptr);
  */
 char *goose_agent_send_message(goose_AgentPtr agent_ptr, const char *message);
 
+/*
+ Send a message to the agent and stream the response
+
+ Like goose_agent_send_message, but instead of returning the response once it
+ is complete, each message is passed to the callback as it arrives. The call
+ returns once the reply is complete, after a final call of the callback with
+ NULL.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - message: Message to send
+ - callback: Called with each message of the reply as JSON, then with NULL
+ - user_data: Passed unchanged to the callbacks
+ - error_callback: Optional, called with each error of the reply. Without it
+   errors are not reported
+
+ # Returns
+
+ false if the agent could not start replying, in which case the callback is
+ not called and goose_get_last_error reports why, and true otherwise
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The message must be a valid C string.
+ */
+bool goose_agent_send_message_stream(goose_AgentPtr agent_ptr,
+                                     const char *message,
+                                     goose_AgentStreamCallback callback,
+                                     void *user_data,
+                                     struct goose_Option_AgentStreamErrorCallback error_callback);
+
+/*
+ Send a conversation to the agent and get the response
+
+ This allows priming the agent with earlier turns of a conversation. The
+ messages must start with any System messages, which are added to the agent's
+ system prompt. They replace the System messages of earlier calls, and are kept
+ for later calls without System messages. They are followed by User and
+ Assistant messages alternating, starting with a User message.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - messages: Array of message_count messages
+ - out_error: Optional, set to an error message when the messages are invalid.
+   The message must be freed with goose_free_string
+
+ # Returns
+
+ A C string with the agent's response, or NULL on error.
+ This string must be freed with goose_free_string when no longer needed.
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The messages must point to message_count messages with valid C string contents.
+ out_error must be NULL or a valid pointer.
+ */
+char *goose_agent_send_messages(goose_AgentPtr agent_ptr,
+                                const struct goose_MessageFFI *messages,
+                                uintptr_t message_count,
+                                char **out_error);
+
+/*
+ Get the frontend tool calls the agent's reply waits for
+
+ A reply stops when the model calls a tool added with
+ goose_agent_add_frontend_tool, until the caller gives the result with
+ goose_agent_inject_tool_result and resumes it with goose_agent_continue.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+
+ # Returns
+
+ A C string with a JSON array of `{"id", "name", "arguments"}` objects, empty
+ when the reply waits for no more results, or NULL on error. This string must be
+ freed with goose_free_string when no longer needed.
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ */
+char *goose_agent_get_pending_tool_calls(goose_AgentPtr agent_ptr);
+
+/*
+ Give the result of a frontend tool call the agent's reply waits for
+
+ The result is sent to the agent when goose_agent_continue resumes the reply,
+ once every pending call has a result.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - tool_name: Name of the called tool
+ - tool_call_id: Id of the tool call, from goose_agent_get_pending_tool_calls
+ - result: Text result of the tool call
+
+ # Returns
+
+ true if the result was taken, false if there is no pending call with this id
+ and name, with the reason given by goose_get_last_error
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ The tool_name, tool_call_id and result must be valid C strings.
+ */
+bool goose_agent_inject_tool_result(goose_AgentPtr agent_ptr,
+                                    const char *tool_name,
+                                    const char *tool_call_id,
+                                    const char *result);
+
+/*
+ Resume the agent's reply after giving the results of its frontend tool calls
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+
+ # Returns
+
+ A C string with the rest of the agent's response, which may stop at further
+ frontend tool calls, or NULL on error, including when the reply waits for no
+ tool results or still lacks some, with the reason given by goose_get_last_error.
+ This string must be freed with goose_free_string when no longer needed.
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new.
+ */
+char *goose_agent_continue(goose_AgentPtr agent_ptr);
+
+/*
+ Create a new, empty session
+
+ # Returns
+
+ A new session pointer, which must be freed with goose_session_free when no
+ longer needed
+ */
+goose_SessionPtr goose_session_new(void);
+
+/*
+ Free a session
+
+ # Safety
+
+ The session_ptr must be a valid pointer returned by goose_session_new or
+ goose_session_load, or NULL.
+ */
+void goose_session_free(goose_SessionPtr session_ptr);
+
+/*
+ Add a text message to the end of a session
+
+ # Parameters
+
+ - session_ptr: Session pointer
+ - role: User or Assistant, as sessions have no System messages
+ - text: Text of the message
+
+ # Returns
+
+ true if the message was added, false otherwise, with the reason given by
+ goose_get_last_error
+
+ # Safety
+
+ The session_ptr must be a valid pointer returned by goose_session_new or
+ goose_session_load. The text must be a valid C string.
+ */
+bool goose_session_add_message(goose_SessionPtr session_ptr,
+                               goose_MessageRole role,
+                               const char *text);
+
+/*
+ Save a session to a file, in the format of goose CLI sessions
+
+ # Parameters
+
+ - session_ptr: Session pointer
+ - path: Path of the file, which is overwritten
+
+ # Returns
+
+ An AsyncResult telling whether the session was saved, which must be freed
+ with goose_free_async_result
+
+ # Safety
+
+ The session_ptr must be a valid pointer returned by goose_session_new or
+ goose_session_load. The path must be a valid C string.
+ */
+struct goose_AsyncResult *goose_session_save(goose_SessionPtr session_ptr, const char *path);
+
+/*
+ Load a session saved with goose_session_save, or by the goose CLI
+
+ # Parameters
+
+ - path: Path of the session file
+
+ # Returns
+
+ A new session pointer, or NULL on error with the reason given by
+ goose_get_last_error. The session must be freed with goose_session_free.
+
+ # Safety
+
+ The path must be a valid C string.
+ */
+goose_SessionPtr goose_session_load(const char *path);
+
+/*
+ Get the agent's reply to the conversation of a session
+
+ The reply is added to the session, so that adding the next User message and
+ calling this function again continues the conversation.
+
+ # Parameters
+
+ - agent_ptr: Agent pointer
+ - session_ptr: Session pointer, ending with a User message
+
+ # Returns
+
+ A C string with the agent's response, like goose_agent_send_message, or NULL
+ if the session is empty, with the reason given by goose_get_last_error.
+ This string must be freed with goose_free_string when no longer needed.
+
+ # Safety
+
+ The agent_ptr must be a valid pointer returned by goose_agent_new, and the
+ session_ptr one returned by goose_session_new or goose_session_load.
+ */
+char *goose_agent_reply_session(goose_AgentPtr agent_ptr, goose_SessionPtr session_ptr);
+
+/*
+ Register a callback receiving the library's log messages
+
+ Replaces any previously registered callback. Only messages at or above the
+ level set with goose_set_log_level (INFO by default) are passed on.
+
+ # Parameters
+
+ - callback: Function called with the level and text of each log message. It may
+   be called from any thread, including concurrently, so it must be thread-safe.
+
+ # Returns
+
+ true if the callback will receive log messages, false if the host process
+ already installed a tracing subscriber, with the reason given by
+ goose_get_last_error.
+ */
+bool goose_set_log_callback(goose_LogCallback callback);
+
+/*
+ Set the most verbose level of log messages passed to the log callback
+
+ # Parameters
+
+ - min_level: 1 = ERROR, 2 = WARN, 3 = INFO, 4 = DEBUG, 5 = TRACE. Values outside
+   of this range are clamped.
+ */
+void goose_set_log_level(uint32_t min_level);
+
+/*
+ Unregister the log callback
+ */
+void goose_clear_log_callback(void);
+
 /*
  Free a string allocated by goose FFI functions
 
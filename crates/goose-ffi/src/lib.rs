@@ -1,14 +1,36 @@
-use std::ffi::{c_char, CStr, CString};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fmt::Write as _;
+use std::path::Path;
 use std::ptr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 use futures::StreamExt;
+use goose::agents::extension::{Envs, ExtensionConfig};
 use goose::agents::Agent;
-use goose::message::Message;
+use goose::config::{
+    Config, ConfigError, DEFAULT_EXTENSION_DESCRIPTION, DEFAULT_EXTENSION_TIMEOUT,
+};
+use goose::message::{Message, MessageContent};
 use goose::model::ModelConfig;
+use goose::providers::anthropic::AnthropicProvider;
+use goose::providers::base::Provider;
 use goose::providers::databricks::DatabricksProvider;
-use once_cell::sync::OnceCell;
+use goose::providers::openai::OpenAiProvider;
+use goose::session;
+use mcp_core::role::Role;
+use mcp_core::tool::{Tool, ToolCall};
+use mcp_core::Content;
+use once_cell::sync::{Lazy, OnceCell};
 use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
 
 // This class is in alpha and not yet ready for production use
 // and the API is not yet stable. Use at your own risk.
@@ -26,18 +48,44 @@ fn get_runtime() -> &'static Runtime {
 
 /// Pointer type for the agent
 pub type AgentPtr = *mut Agent;
+
+// Each agent's latest reply, keyed by the agent's address
+static REPLIES: Lazy<Mutex<HashMap<usize, AgentReply>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn reply_key(agent: &Agent) -> usize {
+    agent as *const Agent as usize
+}
+
+// The conversation of a reply, and the rest of the reply while it waits for the
+// results of frontend tool calls
+struct AgentReply {
+    conversation: Vec<Message>,
+    suspended: Option<SuspendedReply>,
+}
+
+// A reply stopped at frontend tool calls, whose stream is run by the task
+struct SuspendedReply {
+    messages: mpsc::UnboundedReceiver<Result<Message, String>>,
+    task: JoinHandle<()>,
+    // The calls without a result yet, and the results given for the others
+    pending: Vec<(String, ToolCall)>,
+    results: Vec<(String, String)>,
+}
 /// Provider Type enumeration
-/// Currently only Databricks is supported
 #[repr(u32)]
 #[derive(Debug, Clone, Copy)]
 pub enum ProviderType {
     /// Databricks AI provider
     Databricks = 0,
+    /// OpenAI provider
+    OpenAI = 1,
+    /// Anthropic provider
+    Anthropic = 2,
 }
 
 /// Provider configuration used to initialize an AI provider
 ///
-/// - provider_type: Provider type (0 = Databricks, other values will produce an error)
+/// - provider_type: Provider type (0 = Databricks, 1 = OpenAI, 2 = Anthropic)
 /// - api_key: Provider API key (null for default from environment variables)
 /// - model_name: Model name to use (null for provider default)
 /// - host: Provider host URL (null for default from environment variables)
@@ -49,8 +97,6 @@ pub struct ProviderConfigFFI {
     pub host: *const c_char,
 }
 
-// Extension configuration will be implemented in a future commit
-
 /// Role enum for message participants
 #[repr(u32)]
 #[derive(Debug, Clone, Copy)]
@@ -105,6 +151,100 @@ pub unsafe extern "C" fn goose_free_async_result(result: *mut AsyncResult) {
     }
 }
 
+/// Kind of error reported by goose_get_last_error
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GooseErrorCode {
+    /// A required pointer argument was NULL
+    NullPointer = 1,
+    /// The provider could not be created
+    ProviderInit = 2,
+    /// No API key was given, nor found in the environment
+    MissingApiKey = 3,
+    /// No model name was given
+    MissingModelName = 4,
+    /// No host was given, nor found in the environment
+    MissingHost = 5,
+    /// The agent's reply stream failed partway
+    StreamError = 6,
+    /// The agent or provider failed while running
+    RuntimeError = 7,
+    /// The provider does not support the operation
+    Unsupported = 8,
+    /// An argument was invalid
+    InvalidArgument = 9,
+    /// A file could not be read or written
+    IoError = 10,
+}
+
+/// An error of a goose FFI function
+///
+/// - code: The kind of error
+/// - message: Description of the error
+#[repr(C)]
+#[derive(Debug)]
+pub struct GooseError {
+    pub code: GooseErrorCode,
+    pub message: *mut c_char,
+}
+
+impl GooseError {
+    fn new(code: GooseErrorCode, message: impl AsRef<str>) -> Self {
+        Self {
+            code,
+            message: string_to_c_char(message.as_ref()),
+        }
+    }
+}
+
+impl Drop for GooseError {
+    fn drop(&mut self) {
+        if !self.message.is_null() {
+            unsafe {
+                let _ = CString::from_raw(self.message);
+            }
+        }
+    }
+}
+
+thread_local! {
+    // The error of the latest failed call on this thread
+    static LAST_ERROR: RefCell<Option<GooseError>> = const { RefCell::new(None) };
+}
+
+// Helper function to record the error of a failed call for goose_get_last_error
+fn set_last_error(error: GooseError) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+}
+
+/// Get the error of the latest failed call on this thread
+///
+/// Functions that return NULL (or false) on failure record why, which this function
+/// returns once: the error is cleared by the call.
+///
+/// # Returns
+///
+/// The error, or NULL if there is none. It must be freed with goose_free_error.
+#[no_mangle]
+pub extern "C" fn goose_get_last_error() -> *mut GooseError {
+    match LAST_ERROR.with(|last| last.borrow_mut().take()) {
+        Some(error) => Box::into_raw(Box::new(error)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Free an error returned by goose_get_last_error, including its message
+///
+/// # Safety
+///
+/// The error must be a pointer returned by goose_get_last_error, or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn goose_free_error(error: *mut GooseError) {
+    if !error.is_null() {
+        let _ = Box::from_raw(error);
+    }
+}
+
 /// Create a new agent with the given provider configuration
 ///
 /// # Parameters
@@ -113,7 +253,8 @@ pub unsafe extern "C" fn goose_free_async_result(result: *mut AsyncResult) {
 ///
 /// # Returns
 ///
-/// A new agent pointer, or a null pointer if creation failed
+/// A new agent pointer, or a null pointer if creation failed, with the reason
+/// given by goose_get_last_error
 ///
 /// # Safety
 ///
@@ -123,72 +264,119 @@ pub unsafe extern "C" fn goose_free_async_result(result: *mut AsyncResult) {
 pub unsafe extern "C" fn goose_agent_new(config: *const ProviderConfigFFI) -> AgentPtr {
     // Check for null pointer
     if config.is_null() {
-        eprintln!("Error: config pointer is null");
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "config pointer is null",
+        ));
         return ptr::null_mut();
     }
 
     let config = &*config;
 
-    // We currently only support Databricks provider
-    // This match ensures future compiler errors if new provider types are added without handling
-    match config.provider_type {
-        ProviderType::Databricks => (), // Databricks provider is supported
-    }
-
-    // Get api_key from config or environment
-    let api_key = if !config.api_key.is_null() {
-        CStr::from_ptr(config.api_key).to_string_lossy().to_string()
-    } else {
-        match std::env::var("DATABRICKS_API_KEY") {
-            Ok(key) => key,
-            Err(_) => {
-                eprintln!("Error: api_key not provided and DATABRICKS_API_KEY environment variable not set");
-                return ptr::null_mut();
-            }
-        }
-    };
-
     // Check and get required model_name (no env fallback for model)
     if config.model_name.is_null() {
-        eprintln!("Error: model_name is required but was null");
+        set_last_error(GooseError::new(
+            GooseErrorCode::MissingModelName,
+            "model_name is required but was null",
+        ));
         return ptr::null_mut();
     }
     let model_name = CStr::from_ptr(config.model_name)
         .to_string_lossy()
         .to_string();
 
-    // Get host from config or environment
-    let host = if !config.host.is_null() {
-        CStr::from_ptr(config.host).to_string_lossy().to_string()
-    } else {
-        match std::env::var("DATABRICKS_HOST") {
-            Ok(url) => url,
-            Err(_) => {
-                eprintln!(
-                    "Error: host not provided and DATABRICKS_HOST environment variable not set"
-                );
-                return ptr::null_mut();
-            }
-        }
-    };
-
     // Create model config with model name
     let model_config = ModelConfig::new(model_name);
 
-    // Create Databricks provider with required parameters
-    match DatabricksProvider::from_params(host, api_key, model_config) {
-        Ok(provider) => {
-            let agent = Agent::new();
-            get_runtime().block_on(async {
-                let _ = agent.update_provider(Arc::new(provider)).await;
-            });
-            Box::into_raw(Box::new(agent))
+    let provider = match create_provider(
+        config.provider_type,
+        config.api_key,
+        config.host,
+        model_config,
+    ) {
+        Ok(provider) => provider,
+        Err(e) => {
+            set_last_error(e);
+            return ptr::null_mut();
         }
+    };
+
+    let agent = Agent::new();
+    get_runtime().block_on(async {
+        let _ = agent.update_provider(provider).await;
+    });
+    Box::into_raw(Box::new(agent))
+}
+
+/// List the models available from a provider
+///
+/// This queries the provider's API (serving endpoints for Databricks, the models
+/// endpoint for OpenAI), without creating an agent.
+///
+/// # Parameters
+///
+/// - provider_type: Provider type
+/// - api_key: Provider API key (null for default from environment variables)
+/// - host: Provider host URL (null for default from environment variables)
+///
+/// # Returns
+///
+/// A C string with a JSON array of model names, or NULL on error, with the reason
+/// given by goose_get_last_error.
+/// This string must be freed with goose_free_string when no longer needed.
+///
+/// # Safety
+///
+/// The api_key and host must be valid C strings or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn goose_provider_list_models(
+    provider_type: ProviderType,
+    api_key: *const c_char,
+    host: *const c_char,
+) -> *mut c_char {
+    let model_config = ModelConfig::new(provider_metadata(provider_type).default_model);
+    let provider = match create_provider(provider_type, api_key, host, model_config) {
+        Ok(provider) => provider,
         Err(e) => {
-            eprintln!("Error creating Databricks provider: {:?}", e);
-            ptr::null_mut()
+            set_last_error(e);
+            return ptr::null_mut();
         }
-    }
+    };
+
+    let error = match get_runtime().block_on(provider.fetch_supported_models()) {
+        Ok(Some(models)) => match serde_json::to_string(&models) {
+            Ok(json) => return string_to_c_char(&json),
+            Err(e) => GooseError::new(
+                GooseErrorCode::RuntimeError,
+                format!("Error serializing models: {}", e),
+            ),
+        },
+        Ok(None) => GooseError::new(
+            GooseErrorCode::Unsupported,
+            "provider does not support listing models",
+        ),
+        Err(e) => GooseError::new(
+            GooseErrorCode::RuntimeError,
+            format!("Error listing models: {}", e),
+        ),
+    };
+    set_last_error(error);
+    ptr::null_mut()
+}
+
+/// Get the recommended default model of a provider
+///
+/// # Parameters
+///
+/// - provider_type: Provider type
+///
+/// # Returns
+///
+/// A C string with the model name.
+/// This string must be freed with goose_free_string when no longer needed.
+#[no_mangle]
+pub extern "C" fn goose_provider_get_default_model(provider_type: ProviderType) -> *mut c_char {
+    string_to_c_char(&provider_metadata(provider_type).default_model)
 }
 
 /// Free an agent
@@ -207,15 +395,269 @@ pub unsafe extern "C" fn goose_agent_new(config: *const ProviderConfigFFI) -> Ag
 #[no_mangle]
 pub unsafe extern "C" fn goose_agent_free(agent_ptr: AgentPtr) {
     if !agent_ptr.is_null() {
+        end_reply(&*agent_ptr);
         let _ = Box::from_raw(agent_ptr);
     }
 }
 
+/// Add a builtin extension to the agent
+///
+/// Builtin extensions (e.g. "developer") are served by the goose binary. As the
+/// library is loaded into another process, set GOOSE_BUILTIN_EXECUTABLE to the
+/// path of the goose binary before calling this function.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - name: Name of the builtin extension
+/// - out_error: Optional, set to an error message on failure. The message must be
+///   freed with goose_free_string
+///
+/// # Returns
+///
+/// true if the extension was added, false otherwise
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The name must be a valid C string. out_error must be NULL or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_add_builtin_extension(
+    agent_ptr: AgentPtr,
+    name: *const c_char,
+    out_error: *mut *mut c_char,
+) -> bool {
+    if agent_ptr.is_null() || name.is_null() {
+        set_error(out_error, "agent_ptr and name must not be null");
+        return false;
+    }
+
+    let config = ExtensionConfig::Builtin {
+        name: CStr::from_ptr(name).to_string_lossy().to_string(),
+        display_name: None,
+        timeout: Some(DEFAULT_EXTENSION_TIMEOUT),
+        bundled: None,
+    };
+    add_extension(&*agent_ptr, config, out_error)
+}
+
+/// Add an extension to the agent that runs as a child process over stdio
+///
+/// The extension is named after the command, e.g. "npx" for "/usr/bin/npx".
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - cmd: Command to run
+/// - args: Array of args_len command arguments (may be NULL if args_len is 0)
+/// - env_keys, env_values: Arrays of env_count environment variable names and values
+///   (may be NULL if env_count is 0)
+/// - out_error: Optional, set to an error message on failure. The message must be
+///   freed with goose_free_string
+///
+/// # Returns
+///
+/// true if the extension was added, false otherwise
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The cmd must be a valid C string, and the arrays must hold the given number of
+/// valid C strings. out_error must be NULL or a valid pointer.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn goose_agent_add_stdio_extension(
+    agent_ptr: AgentPtr,
+    cmd: *const c_char,
+    args: *const *const c_char,
+    args_len: usize,
+    env_keys: *const *const c_char,
+    env_values: *const *const c_char,
+    env_count: usize,
+    out_error: *mut *mut c_char,
+) -> bool {
+    if agent_ptr.is_null() || cmd.is_null() {
+        set_error(out_error, "agent_ptr and cmd must not be null");
+        return false;
+    }
+
+    let cmd = CStr::from_ptr(cmd).to_string_lossy().to_string();
+    let strings = (|| {
+        Ok::<_, String>((
+            c_string_array(args, args_len, "args")?,
+            c_string_array(env_keys, env_count, "env_keys")?,
+            c_string_array(env_values, env_count, "env_values")?,
+        ))
+    })();
+    let (args, keys, values) = match strings {
+        Ok(strings) => strings,
+        Err(e) => {
+            set_error(out_error, &e);
+            return false;
+        }
+    };
+
+    let name = Path::new(&cmd)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| cmd.clone());
+    let config = ExtensionConfig::Stdio {
+        name,
+        cmd,
+        args,
+        envs: Envs::new(keys.into_iter().zip(values).collect::<HashMap<_, _>>()),
+        env_keys: Vec::new(),
+        description: Some(DEFAULT_EXTENSION_DESCRIPTION.to_string()),
+        timeout: Some(DEFAULT_EXTENSION_TIMEOUT),
+        bundled: None,
+    };
+    add_extension(&*agent_ptr, config, out_error)
+}
+
+/// Add an extension to the agent
+///
+/// The extension is a builtin (e.g. "developer") when cmd is NULL, and otherwise
+/// runs cmd as a child process over stdio. Builtins are served by the goose binary,
+/// so set GOOSE_BUILTIN_EXECUTABLE to its path before adding one.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - name: Name of the extension
+/// - cmd: Command to run, or NULL for a builtin extension
+/// - args: Array of args_len command arguments (may be NULL if args_len is 0)
+/// - envs_json: JSON object of environment variables for the command, e.g.
+///   {"API_KEY": "..."}, or NULL for none
+///
+/// # Returns
+///
+/// An AsyncResult telling whether the extension was added, which must be freed
+/// with goose_free_async_result
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The name, and cmd and envs_json when not NULL, must be valid C strings, and
+/// args must hold args_len valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_add_extension(
+    agent_ptr: AgentPtr,
+    name: *const c_char,
+    cmd: *const c_char,
+    args: *const *const c_char,
+    args_len: usize,
+    envs_json: *const c_char,
+) -> *mut AsyncResult {
+    if agent_ptr.is_null() || name.is_null() {
+        return async_result(Err("agent_ptr and name must not be null".to_string()));
+    }
+
+    let name = CStr::from_ptr(name).to_string_lossy().to_string();
+    let config = if cmd.is_null() {
+        ExtensionConfig::Builtin {
+            name,
+            display_name: None,
+            timeout: Some(DEFAULT_EXTENSION_TIMEOUT),
+            bundled: None,
+        }
+    } else {
+        let args = match c_string_array(args, args_len, "args") {
+            Ok(args) => args,
+            Err(e) => return async_result(Err(e)),
+        };
+        let envs = if envs_json.is_null() {
+            HashMap::new()
+        } else {
+            let envs_json = CStr::from_ptr(envs_json).to_string_lossy();
+            match serde_json::from_str::<HashMap<String, String>>(&envs_json) {
+                Ok(envs) => envs,
+                Err(e) => return async_result(Err(format!("Invalid envs_json: {}", e))),
+            }
+        };
+        ExtensionConfig::Stdio {
+            name,
+            cmd: CStr::from_ptr(cmd).to_string_lossy().to_string(),
+            args,
+            envs: Envs::new(envs),
+            env_keys: Vec::new(),
+            description: Some(DEFAULT_EXTENSION_DESCRIPTION.to_string()),
+            timeout: Some(DEFAULT_EXTENSION_TIMEOUT),
+            bundled: None,
+        }
+    };
+
+    let result = get_runtime()
+        .block_on((*agent_ptr).add_extension(config))
+        .map_err(|e| format!("Failed to add extension: {}", e));
+    async_result(result)
+}
+
+/// Add a tool the caller executes itself
+///
+/// When the model calls the tool, the agent's reply stops with the call pending,
+/// see goose_agent_get_pending_tool_calls.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - name: Name of the tool
+/// - description: Description of the tool for the model
+/// - input_schema: JSON schema of the tool's arguments, or NULL for no arguments
+/// - out_error: Optional, set to an error message on failure. The message must be
+///   freed with goose_free_string
+///
+/// # Returns
+///
+/// true if the tool was added, false otherwise
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The name and description must be valid C strings, and input_schema a valid C
+/// string or NULL. out_error must be NULL or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_add_frontend_tool(
+    agent_ptr: AgentPtr,
+    name: *const c_char,
+    description: *const c_char,
+    input_schema: *const c_char,
+    out_error: *mut *mut c_char,
+) -> bool {
+    if agent_ptr.is_null() || name.is_null() || description.is_null() {
+        set_error(
+            out_error,
+            "agent_ptr, name and description must not be null",
+        );
+        return false;
+    }
+
+    let input_schema = if input_schema.is_null() {
+        serde_json::json!({"type": "object", "properties": {}})
+    } else {
+        match serde_json::from_str(&CStr::from_ptr(input_schema).to_string_lossy()) {
+            Ok(schema) => schema,
+            Err(e) => {
+                set_error(out_error, &format!("Invalid input_schema: {}", e));
+                return false;
+            }
+        }
+    };
+    let name = CStr::from_ptr(name).to_string_lossy().to_string();
+    let description = CStr::from_ptr(description).to_string_lossy().to_string();
+    let config = ExtensionConfig::Frontend {
+        name: name.clone(),
+        tools: vec![Tool::new(name, description, input_schema, None)],
+        instructions: None,
+        bundled: None,
+    };
+    add_extension(&*agent_ptr, config, out_error)
+}
+
 /// Send a message to the agent and get the response
 ///
-/// This function sends a message to the agent and returns the response.
-/// Tool handling is not yet supported and will be implemented in a future commit
-/// so this may change significantly
+/// This function sends a message to the agent and returns the response. The
+/// reply stops at calls of tools added with goose_agent_add_frontend_tool, which
+/// goose_agent_continue resumes.
 ///
 /// # Parameters
 ///
@@ -224,7 +666,9 @@ pub unsafe extern "C" fn goose_agent_free(agent_ptr: AgentPtr) {
 ///
 /// # Returns
 ///
-/// A C string with the agent's response, or NULL on error.
+/// A C string with the agent's response, or NULL on error, with the reason given
+/// by goose_get_last_error. Errors while replying are also included in the
+/// response, and the last of them is given by goose_get_last_error.
 /// This string must be freed with goose_free_string when no longer needed.
 ///
 /// # Safety
@@ -237,68 +681,1953 @@ pub unsafe extern "C" fn goose_agent_send_message(
     message: *const c_char,
 ) -> *mut c_char {
     if agent_ptr.is_null() || message.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "agent_ptr and message must not be null",
+        ));
         return ptr::null_mut();
     }
 
-    let agent = &mut *agent_ptr;
+    let agent = &*agent_ptr;
     let message = CStr::from_ptr(message).to_string_lossy().to_string();
 
     let messages = vec![Message::user().with_text(&message)];
 
-    // Block on the async call using our global runtime
-    let response = get_runtime().block_on(async {
-        let mut stream = match agent.reply(&messages, None).await {
-            Ok(stream) => stream,
-            Err(e) => return format!("Error getting reply from agent: {}", e),
-        };
+    string_to_c_char(&reply(agent, &messages))
+}
 
-        let mut full_response = String::new();
+/// Callback receiving the messages of a streamed reply
+///
+/// - message: A message of the reply as JSON, only valid for the duration of the
+///   call, or NULL once the reply is complete
+/// - user_data: The user_data given to goose_agent_send_message_stream
+pub type AgentStreamCallback = extern "C" fn(message: *const c_char, user_data: *mut c_void);
 
-        while let Some(message_result) = stream.next().await {
-            match message_result {
-                Ok(message) => {
-                    // Get text or serialize to JSON
-                    // Note: Message doesn't have as_text method, we'll serialize to JSON
-                    if let Ok(json) = serde_json::to_string(&message) {
-                        full_response.push_str(&json);
-                    }
-                }
-                Err(e) => {
-                    full_response.push_str(&format!("\nError in message stream: {}", e));
-                }
+/// Callback receiving the errors of a streamed reply
+///
+/// - error: The error message, only valid for the duration of the call
+/// - user_data: The user_data given to goose_agent_send_message_stream
+pub type AgentStreamErrorCallback = extern "C" fn(error: *const c_char, user_data: *mut c_void);
+
+/// Send a message to the agent and stream the response
+///
+/// Like goose_agent_send_message, but instead of returning the response once it
+/// is complete, each message is passed to the callback as it arrives. The call
+/// returns once the reply is complete, after a final call of the callback with
+/// NULL.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - message: Message to send
+/// - callback: Called with each message of the reply as JSON, then with NULL
+/// - user_data: Passed unchanged to the callbacks
+/// - error_callback: Optional, called with each error of the reply. Without it
+///   errors are not reported
+///
+/// # Returns
+///
+/// false if the agent could not start replying, in which case the callback is
+/// not called and goose_get_last_error reports why, and true otherwise
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The message must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_send_message_stream(
+    agent_ptr: AgentPtr,
+    message: *const c_char,
+    callback: AgentStreamCallback,
+    user_data: *mut c_void,
+    error_callback: Option<AgentStreamErrorCallback>,
+) -> bool {
+    if agent_ptr.is_null() || message.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "agent_ptr and message must not be null",
+        ));
+        return false;
+    }
+
+    let agent = &*agent_ptr;
+    let message = CStr::from_ptr(message).to_string_lossy().to_string();
+    let messages = vec![Message::user().with_text(&message)];
+
+    // Interior nul bytes cannot be passed as a C string
+    let c_string = |text: String| CString::new(text.replace('\0', "")).ok();
+    let result = run_reply(agent, &messages, |chunk| match chunk {
+        Ok(message) => {
+            if let Some(json) = serde_json::to_string(message).ok().and_then(c_string) {
+                callback(json.as_ptr(), user_data);
+            }
+        }
+        Err(e) => {
+            if let (Some(error_callback), Some(error)) = (error_callback, c_string(e)) {
+                error_callback(error.as_ptr(), user_data);
             }
         }
-        full_response
     });
-
-    string_to_c_char(&response)
+    match result {
+        Ok(_) => {
+            callback(ptr::null(), user_data);
+            true
+        }
+        Err(e) => {
+            set_last_error(GooseError::new(GooseErrorCode::RuntimeError, &e));
+            if let (Some(error_callback), Some(error)) = (error_callback, c_string(e)) {
+                error_callback(error.as_ptr(), user_data);
+            }
+            false
+        }
+    }
 }
 
-// Tool schema creation will be implemented in a future commit
-
-/// Free a string allocated by goose FFI functions
+/// Send a conversation to the agent and get the response
 ///
-/// This function frees memory allocated for strings returned by goose FFI functions.
+/// This allows priming the agent with earlier turns of a conversation. The
+/// messages must start with any System messages, which are added to the agent's
+/// system prompt. They replace the System messages of earlier calls, and are kept
+/// for later calls without System messages. They are followed by User and
+/// Assistant messages alternating, starting with a User message.
 ///
 /// # Parameters
 ///
-/// - s: String to free
+/// - agent_ptr: Agent pointer
+/// - messages: Array of message_count messages
+/// - out_error: Optional, set to an error message when the messages are invalid.
+///   The message must be freed with goose_free_string
+///
+/// # Returns
+///
+/// A C string with the agent's response, or NULL on error.
+/// This string must be freed with goose_free_string when no longer needed.
 ///
 /// # Safety
 ///
-/// The string must have been allocated by a goose FFI function, or be NULL.
-/// The string must not be used after calling this function.
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The messages must point to message_count messages with valid C string contents.
+/// out_error must be NULL or a valid pointer.
 #[no_mangle]
-pub unsafe extern "C" fn goose_free_string(s: *mut c_char) {
-    if !s.is_null() {
-        let _ = CString::from_raw(s);
+pub unsafe extern "C" fn goose_agent_send_messages(
+    agent_ptr: AgentPtr,
+    messages: *const MessageFFI,
+    message_count: usize,
+    out_error: *mut *mut c_char,
+) -> *mut c_char {
+    if agent_ptr.is_null() || messages.is_null() {
+        set_error(out_error, "agent_ptr and messages must not be null");
+        return ptr::null_mut();
+    }
+
+    let agent = &*agent_ptr;
+    let (system, messages) = match conversation(std::slice::from_raw_parts(messages, message_count))
+    {
+        Ok(conversation) => conversation,
+        Err(e) => {
+            set_error(out_error, &e);
+            return ptr::null_mut();
+        }
+    };
+
+    if !system.is_empty() {
+        get_runtime().block_on(agent.set_system_prompt_extras(system));
     }
+    string_to_c_char(&reply(agent, &messages))
 }
 
-// Helper function to convert a Rust string to a C char pointer
-fn string_to_c_char(s: &str) -> *mut c_char {
-    match CString::new(s) {
-        Ok(c_string) => c_string.into_raw(),
+// Helper function to split messages into system instructions and the conversation
+unsafe fn conversation(messages: &[MessageFFI]) -> Result<(Vec<String>, Vec<Message>), String> {
+    let mut system = Vec::new();
+    let mut conversation = Vec::new();
+    for (index, message) in messages.iter().enumerate() {
+        if message.content.is_null() {
+            return Err(format!("Message {} has no content", index));
+        }
+        let content = CStr::from_ptr(message.content)
+            .to_string_lossy()
+            .to_string();
+
+        let expected = if conversation.len() % 2 == 0 {
+            MessageRole::User
+        } else {
+            MessageRole::Assistant
+        };
+        match (message.role, expected) {
+            (MessageRole::System, _) if conversation.is_empty() => system.push(content),
+            (MessageRole::System, _) => {
+                return Err(format!(
+                    "Message {} is a System message, which must come before the conversation",
+                    index
+                ))
+            }
+            (MessageRole::User, MessageRole::User) => {
+                conversation.push(Message::user().with_text(content))
+            }
+            (MessageRole::Assistant, MessageRole::Assistant) => {
+                conversation.push(Message::assistant().with_text(content))
+            }
+            (role, expected) => {
+                return Err(format!(
+                    "Message {} is a {:?} message but should be a {:?} message, the conversation must alternate User and Assistant messages starting with a User message",
+                    index, role, expected
+                ))
+            }
+        }
+    }
+
+    if conversation.is_empty() {
+        return Err("At least one User message is required".to_string());
+    }
+    Ok((system, conversation))
+}
+
+// Helper function to get the agent's reply, with each message serialized to JSON.
+// The messages and the reply are kept as the agent's conversation.
+fn reply(agent: &Agent, messages: &[Message]) -> String {
+    reply_with_conversation(agent, messages).0
+}
+
+// Helper function to get the agent's reply like reply, along with the resulting
+// conversation unless the agent could not start replying
+fn reply_with_conversation(agent: &Agent, messages: &[Message]) -> (String, Option<Vec<Message>>) {
+    let mut full_response = String::new();
+    let result = run_reply(agent, messages, collect_response(&mut full_response));
+    match result {
+        Ok(conversation) => (full_response, Some(conversation)),
+        Err(e) => {
+            set_last_error(GooseError::new(GooseErrorCode::RuntimeError, &e));
+            (e, None)
+        }
+    }
+}
+
+// Helper function to get a handler for the messages of a reply, which adds
+// them to the response
+fn collect_response(full_response: &mut String) -> impl FnMut(Result<&Message, String>) + '_ {
+    |chunk| match chunk {
+        Ok(message) => {
+            // Get text or serialize to JSON
+            // Note: Message doesn't have as_text method, we'll serialize to JSON
+            if let Ok(json) = serde_json::to_string(message) {
+                full_response.push_str(&json);
+            }
+        }
+        Err(e) => {
+            full_response.push_str(&format!("\nError in message stream: {}", e));
+            set_last_error(GooseError::new(GooseErrorCode::StreamError, e));
+        }
+    }
+}
+
+// Helper function to get the agent's reply to a conversation, passing each
+// message or error of the stream to the handler as it arrives. The reply stops
+// at calls of frontend tools, which the caller executes, until
+// goose_agent_continue resumes it. The resulting conversation is kept, and
+// also returned.
+fn run_reply(
+    agent: &Agent,
+    messages: &[Message],
+    handle: impl FnMut(Result<&Message, String>),
+) -> Result<Vec<Message>, String> {
+    // A new reply abandons one waiting for tool results
+    end_reply(agent);
+
+    // SAFETY: agents are only freed by goose_agent_free, which ends their reply
+    // first, so the agent outlives the task running the reply stream
+    let agent: &'static Agent = unsafe { &*(agent as *const Agent) };
+    let mut stream = get_runtime()
+        .block_on(agent.reply(messages, None))
+        .map_err(|e| format!("Error getting reply from agent: {}", e))?;
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let task = get_runtime().spawn(async move {
+        while let Some(message) = stream.next().await {
+            if sender.send(message.map_err(|e| e.to_string())).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut conversation = messages.to_vec();
+    let suspended = resume_reply(
+        agent,
+        &mut conversation,
+        SuspendedReply {
+            messages: receiver,
+            task,
+            pending: Vec::new(),
+            results: Vec::new(),
+        },
+        handle,
+    );
+    REPLIES.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        reply_key(agent),
+        AgentReply {
+            conversation: conversation.clone(),
+            suspended,
+        },
+    );
+    Ok(conversation)
+}
+
+// Helper function to pass the messages of a reply to the handler and record
+// them until the reply ends, giving the reply back when it stops at frontend
+// tool calls. The results given for earlier calls are sent first.
+fn resume_reply(
+    agent: &Agent,
+    conversation: &mut Vec<Message>,
+    mut reply: SuspendedReply,
+    mut handle: impl FnMut(Result<&Message, String>),
+) -> Option<SuspendedReply> {
+    get_runtime().block_on(async {
+        for (id, result) in reply.results.drain(..) {
+            agent
+                .handle_tool_result(id, Ok(vec![Content::text(result)]))
+                .await;
+        }
+
+        while let Some(message_result) = reply.messages.recv().await {
+            match message_result {
+                Ok(message) => {
+                    handle(Ok(&message));
+                    reply.pending = frontend_tool_calls(&message);
+                    record(conversation, message);
+                    // The agent waits for the results before going on
+                    if !reply.pending.is_empty() {
+                        return Some(reply);
+                    }
+                }
+                Err(e) => handle(Err(e)),
+            }
+        }
+        None
+    })
+}
+
+// Helper function to forget an agent's latest reply, stopping it if it waits for
+// tool results
+fn end_reply(agent: &Agent) {
+    let reply = REPLIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&reply_key(agent));
+    if let Some(suspended) = reply.and_then(|reply| reply.suspended) {
+        suspended.task.abort();
+        // Wait for the task to end, after which it no longer uses the agent
+        let _ = get_runtime().block_on(suspended.task);
+    }
+}
+
+// Helper function to get the frontend tool calls requested by a message
+fn frontend_tool_calls(message: &Message) -> Vec<(String, ToolCall)> {
+    message
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            MessageContent::FrontendToolRequest(request) => {
+                Some((request.id.clone(), request.tool_call.clone().ok()?))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Helper function to add a streamed message to a conversation, leaving out
+// progress and confirmation requests which are not part of the chat. Frontend
+// tool requests are streamed apart from the assistant message that made them,
+// and go back into it.
+fn record(conversation: &mut Vec<Message>, mut message: Message) {
+    let mut tool_requests = Vec::new();
+    message.content.retain(|content| match content {
+        MessageContent::FrontendToolRequest(request) => {
+            tool_requests.push(MessageContent::tool_request(
+                request.id.clone(),
+                request.tool_call.clone(),
+            ));
+            false
+        }
+        MessageContent::ToolProgress(_) | MessageContent::ToolConfirmationRequest(_) => false,
+        _ => true,
+    });
+    if !message.content.is_empty() {
+        conversation.push(message);
+    }
+    if tool_requests.is_empty() {
+        return;
+    }
+    match conversation.last_mut() {
+        Some(last) if last.role == Role::Assistant => last.content.extend(tool_requests),
+        _ => {
+            let mut request = Message::assistant();
+            request.content = tool_requests;
+            conversation.push(request);
+        }
+    }
+}
+
+/// Get the frontend tool calls the agent's reply waits for
+///
+/// A reply stops when the model calls a tool added with
+/// goose_agent_add_frontend_tool, until the caller gives the result with
+/// goose_agent_inject_tool_result and resumes it with goose_agent_continue.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+///
+/// # Returns
+///
+/// A C string with a JSON array of `{"id", "name", "arguments"}` objects, empty
+/// when the reply waits for no more results, or NULL on error. This string must be
+/// freed with goose_free_string when no longer needed.
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_get_pending_tool_calls(agent_ptr: AgentPtr) -> *mut c_char {
+    if agent_ptr.is_null() {
+        return ptr::null_mut();
+    }
+
+    let replies = REPLIES.lock().unwrap_or_else(|e| e.into_inner());
+    let calls: Vec<_> = replies
+        .get(&reply_key(&*agent_ptr))
+        .and_then(|reply| reply.suspended.as_ref())
+        .map(|suspended| suspended.pending.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|(id, tool_call)| {
+            serde_json::json!({
+                "id": id,
+                "name": tool_call.name,
+                "arguments": tool_call.arguments,
+            })
+        })
+        .collect();
+    match serde_json::to_string(&calls) {
+        Ok(json) => string_to_c_char(&json),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Give the result of a frontend tool call the agent's reply waits for
+///
+/// The result is sent to the agent when goose_agent_continue resumes the reply,
+/// once every pending call has a result.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - tool_name: Name of the called tool
+/// - tool_call_id: Id of the tool call, from goose_agent_get_pending_tool_calls
+/// - result: Text result of the tool call
+///
+/// # Returns
+///
+/// true if the result was taken, false if there is no pending call with this id
+/// and name, with the reason given by goose_get_last_error
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+/// The tool_name, tool_call_id and result must be valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_inject_tool_result(
+    agent_ptr: AgentPtr,
+    tool_name: *const c_char,
+    tool_call_id: *const c_char,
+    result: *const c_char,
+) -> bool {
+    if agent_ptr.is_null() || tool_name.is_null() || tool_call_id.is_null() || result.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "agent_ptr, tool_name, tool_call_id and result must not be null",
+        ));
+        return false;
+    }
+
+    let tool_name = CStr::from_ptr(tool_name).to_string_lossy();
+    let tool_call_id = CStr::from_ptr(tool_call_id).to_string_lossy().to_string();
+    let result = CStr::from_ptr(result).to_string_lossy().to_string();
+
+    let mut replies = REPLIES.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(suspended) = replies
+        .get_mut(&reply_key(&*agent_ptr))
+        .and_then(|reply| reply.suspended.as_mut())
+    else {
+        set_last_error(GooseError::new(
+            GooseErrorCode::InvalidArgument,
+            "the agent's reply is not waiting for tool results",
+        ));
+        return false;
+    };
+    let Some(position) = suspended
+        .pending
+        .iter()
+        .position(|(id, tool_call)| *id == tool_call_id && tool_call.name == tool_name)
+    else {
+        set_last_error(GooseError::new(
+            GooseErrorCode::InvalidArgument,
+            format!("no pending call of {} with id {}", tool_name, tool_call_id),
+        ));
+        return false;
+    };
+    suspended.pending.remove(position);
+    suspended.results.push((tool_call_id, result));
+    true
+}
+
+/// Resume the agent's reply after giving the results of its frontend tool calls
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+///
+/// # Returns
+///
+/// A C string with the rest of the agent's response, which may stop at further
+/// frontend tool calls, or NULL on error, including when the reply waits for no
+/// tool results or still lacks some, with the reason given by goose_get_last_error.
+/// This string must be freed with goose_free_string when no longer needed.
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_continue(agent_ptr: AgentPtr) -> *mut c_char {
+    if agent_ptr.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "agent_ptr must not be null",
+        ));
+        return ptr::null_mut();
+    }
+
+    let agent = &*agent_ptr;
+    let key = reply_key(agent);
+    let mut replies = REPLIES.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(reply) = replies
+        .get_mut(&key)
+        .filter(|reply| reply.suspended.is_some())
+    else {
+        set_last_error(GooseError::new(
+            GooseErrorCode::InvalidArgument,
+            "the agent's reply is not waiting for tool results",
+        ));
+        return ptr::null_mut();
+    };
+    if reply
+        .suspended
+        .as_ref()
+        .is_some_and(|suspended| !suspended.pending.is_empty())
+    {
+        set_last_error(GooseError::new(
+            GooseErrorCode::InvalidArgument,
+            "results are missing for pending tool calls",
+        ));
+        return ptr::null_mut();
+    }
+    let mut conversation = std::mem::take(&mut reply.conversation);
+    let suspended = reply.suspended.take();
+    drop(replies);
+
+    let mut full_response = String::new();
+    let suspended = suspended.and_then(|suspended| {
+        resume_reply(
+            agent,
+            &mut conversation,
+            suspended,
+            collect_response(&mut full_response),
+        )
+    });
+    REPLIES.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        key,
+        AgentReply {
+            conversation,
+            suspended,
+        },
+    );
+    string_to_c_char(&full_response)
+}
+
+/// A conversation kept by the caller across agent replies
+pub struct SessionFFI {
+    messages: Vec<Message>,
+}
+
+/// Pointer type for a session
+pub type SessionPtr = *mut SessionFFI;
+
+/// Create a new, empty session
+///
+/// # Returns
+///
+/// A new session pointer, which must be freed with goose_session_free when no
+/// longer needed
+#[no_mangle]
+pub extern "C" fn goose_session_new() -> SessionPtr {
+    Box::into_raw(Box::new(SessionFFI {
+        messages: Vec::new(),
+    }))
+}
+
+/// Free a session
+///
+/// # Safety
+///
+/// The session_ptr must be a valid pointer returned by goose_session_new or
+/// goose_session_load, or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn goose_session_free(session_ptr: SessionPtr) {
+    if !session_ptr.is_null() {
+        let _ = Box::from_raw(session_ptr);
+    }
+}
+
+/// Add a text message to the end of a session
+///
+/// # Parameters
+///
+/// - session_ptr: Session pointer
+/// - role: User or Assistant, as sessions have no System messages
+/// - text: Text of the message
+///
+/// # Returns
+///
+/// true if the message was added, false otherwise, with the reason given by
+/// goose_get_last_error
+///
+/// # Safety
+///
+/// The session_ptr must be a valid pointer returned by goose_session_new or
+/// goose_session_load. The text must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn goose_session_add_message(
+    session_ptr: SessionPtr,
+    role: MessageRole,
+    text: *const c_char,
+) -> bool {
+    if session_ptr.is_null() || text.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "session_ptr and text must not be null",
+        ));
+        return false;
+    }
+
+    let text = CStr::from_ptr(text).to_string_lossy().to_string();
+    let message = match role {
+        MessageRole::User => Message::user().with_text(text),
+        MessageRole::Assistant => Message::assistant().with_text(text),
+        MessageRole::System => {
+            set_last_error(GooseError::new(
+                GooseErrorCode::InvalidArgument,
+                "sessions cannot hold System messages",
+            ));
+            return false;
+        }
+    };
+    (*session_ptr).messages.push(message);
+    true
+}
+
+/// Save a session to a file, in the format of goose CLI sessions
+///
+/// # Parameters
+///
+/// - session_ptr: Session pointer
+/// - path: Path of the file, which is overwritten
+///
+/// # Returns
+///
+/// An AsyncResult telling whether the session was saved, which must be freed
+/// with goose_free_async_result
+///
+/// # Safety
+///
+/// The session_ptr must be a valid pointer returned by goose_session_new or
+/// goose_session_load. The path must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn goose_session_save(
+    session_ptr: SessionPtr,
+    path: *const c_char,
+) -> *mut AsyncResult {
+    if session_ptr.is_null() || path.is_null() {
+        return async_result(Err("session_ptr and path must not be null".to_string()));
+    }
+
+    let path = CStr::from_ptr(path).to_string_lossy().to_string();
+    let result = get_runtime()
+        .block_on(session::persist_messages(
+            Path::new(&path),
+            &(*session_ptr).messages,
+            None,
+        ))
+        .map_err(|e| format!("Failed to save session to {}: {}", path, e));
+    async_result(result)
+}
+
+/// Load a session saved with goose_session_save, or by the goose CLI
+///
+/// # Parameters
+///
+/// - path: Path of the session file
+///
+/// # Returns
+///
+/// A new session pointer, or NULL on error with the reason given by
+/// goose_get_last_error. The session must be freed with goose_session_free.
+///
+/// # Safety
+///
+/// The path must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn goose_session_load(path: *const c_char) -> SessionPtr {
+    if path.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "path must not be null",
+        ));
+        return ptr::null_mut();
+    }
+
+    let path = CStr::from_ptr(path).to_string_lossy().to_string();
+    // Reading creates missing files, which would hide a wrong path
+    if !Path::new(&path).is_file() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::IoError,
+            format!("No session file at {}", path),
+        ));
+        return ptr::null_mut();
+    }
+    match session::read_messages(Path::new(&path)) {
+        Ok(messages) => Box::into_raw(Box::new(SessionFFI { messages })),
+        Err(e) => {
+            set_last_error(GooseError::new(
+                GooseErrorCode::IoError,
+                format!("Failed to load session from {}: {}", path, e),
+            ));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Get the agent's reply to the conversation of a session
+///
+/// The reply is added to the session, so that adding the next User message and
+/// calling this function again continues the conversation.
+///
+/// # Parameters
+///
+/// - agent_ptr: Agent pointer
+/// - session_ptr: Session pointer, ending with a User message
+///
+/// # Returns
+///
+/// A C string with the agent's response, like goose_agent_send_message, or NULL
+/// if the session is empty, with the reason given by goose_get_last_error.
+/// This string must be freed with goose_free_string when no longer needed.
+///
+/// # Safety
+///
+/// The agent_ptr must be a valid pointer returned by goose_agent_new, and the
+/// session_ptr one returned by goose_session_new or goose_session_load.
+#[no_mangle]
+pub unsafe extern "C" fn goose_agent_reply_session(
+    agent_ptr: AgentPtr,
+    session_ptr: SessionPtr,
+) -> *mut c_char {
+    if agent_ptr.is_null() || session_ptr.is_null() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::NullPointer,
+            "agent_ptr and session_ptr must not be null",
+        ));
+        return ptr::null_mut();
+    }
+
+    let session = &mut *session_ptr;
+    if session.messages.is_empty() {
+        set_last_error(GooseError::new(
+            GooseErrorCode::InvalidArgument,
+            "the session has no messages to reply to",
+        ));
+        return ptr::null_mut();
+    }
+
+    let (response, conversation) = reply_with_conversation(&*agent_ptr, &session.messages);
+    if let Some(conversation) = conversation {
+        session.messages = conversation;
+    }
+    string_to_c_char(&response)
+}
+
+// Tool schema creation will be implemented in a future commit
+
+/// Callback receiving log messages
+///
+/// - level: 1 = ERROR, 2 = WARN, 3 = INFO, 4 = DEBUG, 5 = TRACE
+/// - message: The log message, only valid for the duration of the call
+pub type LogCallback = extern "C" fn(level: u32, message: *const c_char);
+
+// The registered log callback, and the most verbose level it receives
+static LOG_CALLBACK: RwLock<Option<LogCallback>> = RwLock::new(None);
+static LOG_LEVEL: AtomicU32 = AtomicU32::new(3);
+// Whether the subscriber forwarding to the log callback could be installed
+static LOG_SUBSCRIBER: OnceLock<bool> = OnceLock::new();
+
+// Forwards tracing events to the registered log callback
+struct CallbackLayer;
+
+impl<S: Subscriber> Layer<S> for CallbackLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let level = log_level(event.metadata().level());
+        if level > LOG_LEVEL.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(callback) = *LOG_CALLBACK.read().unwrap_or_else(|e| e.into_inner()) else {
+            return;
+        };
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let text = format!("{}{}", visitor.message, visitor.fields);
+        // Interior nul bytes cannot be passed as a C string
+        if let Ok(message) = CString::new(text.replace('\0', "")) {
+            callback(level, message.as_ptr());
+        }
+    }
+}
+
+// Formats an event as its message followed by its other fields
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+fn log_level(level: &Level) -> u32 {
+    match *level {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        Level::TRACE => 5,
+    }
+}
+
+/// Register a callback receiving the library's log messages
+///
+/// Replaces any previously registered callback. Only messages at or above the
+/// level set with goose_set_log_level (INFO by default) are passed on.
+///
+/// # Parameters
+///
+/// - callback: Function called with the level and text of each log message. It may
+///   be called from any thread, including concurrently, so it must be thread-safe.
+///
+/// # Returns
+///
+/// true if the callback will receive log messages, false if the host process
+/// already installed a tracing subscriber, with the reason given by
+/// goose_get_last_error.
+#[no_mangle]
+pub extern "C" fn goose_set_log_callback(callback: LogCallback) -> bool {
+    *LOG_CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = Some(callback);
+    // Fails if the host process already installed a tracing subscriber
+    let installed = *LOG_SUBSCRIBER.get_or_init(|| {
+        tracing_subscriber::registry()
+            .with(CallbackLayer)
+            .try_init()
+            .is_ok()
+    });
+    if !installed {
+        set_last_error(GooseError::new(
+            GooseErrorCode::RuntimeError,
+            "a tracing subscriber is already set, log callbacks will not be called",
+        ));
+    }
+    installed
+}
+
+/// Set the most verbose level of log messages passed to the log callback
+///
+/// # Parameters
+///
+/// - min_level: 1 = ERROR, 2 = WARN, 3 = INFO, 4 = DEBUG, 5 = TRACE. Values outside
+///   of this range are clamped.
+#[no_mangle]
+pub extern "C" fn goose_set_log_level(min_level: u32) {
+    LOG_LEVEL.store(min_level.clamp(1, 5), Ordering::Relaxed);
+}
+
+/// Unregister the log callback
+#[no_mangle]
+pub extern "C" fn goose_clear_log_callback() {
+    *LOG_CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Free a string allocated by goose FFI functions
+///
+/// This function frees memory allocated for strings returned by goose FFI functions.
+///
+/// # Parameters
+///
+/// - s: String to free
+///
+/// # Safety
+///
+/// The string must have been allocated by a goose FFI function, or be NULL.
+/// The string must not be used after calling this function.
+#[no_mangle]
+pub unsafe extern "C" fn goose_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        let _ = CString::from_raw(s);
+    }
+}
+
+// Helper function to get the metadata of a provider type
+fn provider_metadata(provider_type: ProviderType) -> goose::providers::base::ProviderMetadata {
+    match provider_type {
+        ProviderType::Databricks => DatabricksProvider::metadata(),
+        ProviderType::OpenAI => OpenAiProvider::metadata(),
+        ProviderType::Anthropic => AnthropicProvider::metadata(),
+    }
+}
+
+// Helper function to create a provider, with the given api_key and host or
+// those from the config
+unsafe fn create_provider(
+    provider_type: ProviderType,
+    api_key: *const c_char,
+    host: *const c_char,
+    model_config: ModelConfig,
+) -> Result<Arc<dyn Provider>, GooseError> {
+    let (name, provider) = match provider_type {
+        ProviderType::Databricks => {
+            let (api_key, host) = databricks_credentials(api_key, host)?;
+            (
+                "Databricks",
+                DatabricksProvider::from_params(host, api_key, model_config)
+                    .map(|provider| Arc::new(provider) as Arc<dyn Provider>),
+            )
+        }
+        ProviderType::OpenAI => {
+            let (api_key, host) = credentials(
+                api_key,
+                host,
+                "OPENAI_API_KEY",
+                "OPENAI_HOST",
+                "https://api.openai.com",
+            )?;
+            (
+                "OpenAI",
+                OpenAiProvider::from_params(host, api_key, model_config)
+                    .map(|provider| Arc::new(provider) as Arc<dyn Provider>),
+            )
+        }
+        ProviderType::Anthropic => {
+            let (api_key, host) = credentials(
+                api_key,
+                host,
+                "ANTHROPIC_API_KEY",
+                "ANTHROPIC_HOST",
+                "https://api.anthropic.com",
+            )?;
+            (
+                "Anthropic",
+                AnthropicProvider::from_params(host, api_key, model_config)
+                    .map(|provider| Arc::new(provider) as Arc<dyn Provider>),
+            )
+        }
+    };
+
+    provider.map_err(|e| {
+        GooseError::new(
+            GooseErrorCode::ProviderInit,
+            format!("Error creating {} provider: {:?}", name, e),
+        )
+    })
+}
+
+// Helper function to get an api_key and host, from the given strings or the
+// config, with the host falling back to `default_host`
+unsafe fn credentials(
+    api_key: *const c_char,
+    host: *const c_char,
+    api_key_var: &str,
+    host_var: &str,
+    default_host: &str,
+) -> Result<(String, String), GooseError> {
+    let config = Config::global();
+    let api_key = if !api_key.is_null() {
+        CStr::from_ptr(api_key).to_string_lossy().to_string()
+    } else {
+        config.get_secret(api_key_var).map_err(|e| {
+            let code = match e {
+                ConfigError::NotFound(_) => GooseErrorCode::MissingApiKey,
+                _ => GooseErrorCode::ProviderInit,
+            };
+            GooseError::new(
+                code,
+                format!(
+                    "api_key not provided and {} not configured: {}",
+                    api_key_var, e
+                ),
+            )
+        })?
+    };
+
+    let host = if !host.is_null() {
+        CStr::from_ptr(host).to_string_lossy().to_string()
+    } else {
+        config
+            .get_param(host_var)
+            .unwrap_or_else(|_| default_host.to_string())
+    };
+
+    Ok((api_key, host))
+}
+
+// Helper function to get the Databricks api_key and host, from the given
+// strings or the environment
+unsafe fn databricks_credentials(
+    api_key: *const c_char,
+    host: *const c_char,
+) -> Result<(String, String), GooseError> {
+    let api_key = if !api_key.is_null() {
+        CStr::from_ptr(api_key).to_string_lossy().to_string()
+    } else {
+        std::env::var("DATABRICKS_API_KEY").map_err(|_| {
+            GooseError::new(
+                GooseErrorCode::MissingApiKey,
+                "api_key not provided and DATABRICKS_API_KEY environment variable not set",
+            )
+        })?
+    };
+
+    let host = if !host.is_null() {
+        CStr::from_ptr(host).to_string_lossy().to_string()
+    } else {
+        std::env::var("DATABRICKS_HOST").map_err(|_| {
+            GooseError::new(
+                GooseErrorCode::MissingHost,
+                "host not provided and DATABRICKS_HOST environment variable not set",
+            )
+        })?
+    };
+
+    Ok((api_key, host))
+}
+
+// Helper function to add an extension, blocking on the global runtime
+unsafe fn add_extension(
+    agent: &Agent,
+    config: ExtensionConfig,
+    out_error: *mut *mut c_char,
+) -> bool {
+    match get_runtime().block_on(agent.add_extension(config)) {
+        Ok(()) => true,
+        Err(e) => {
+            set_error(out_error, &format!("Failed to add extension: {}", e));
+            false
+        }
+    }
+}
+
+// Helper function to turn a result into an AsyncResult owned by the caller
+fn async_result(result: Result<(), String>) -> *mut AsyncResult {
+    let (succeeded, error_message) = match result {
+        Ok(()) => (true, ptr::null_mut()),
+        Err(e) => (false, string_to_c_char(&e)),
+    };
+    Box::into_raw(Box::new(AsyncResult {
+        succeeded,
+        error_message,
+    }))
+}
+
+// Helper function to report an error through an optional out parameter
+unsafe fn set_error(out_error: *mut *mut c_char, message: &str) {
+    if !out_error.is_null() {
+        *out_error = string_to_c_char(message);
+    }
+}
+
+// Helper function to convert an array of C strings
+unsafe fn c_string_array(
+    array: *const *const c_char,
+    len: usize,
+    name: &str,
+) -> Result<Vec<String>, String> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    if array.is_null() {
+        return Err(format!("{} must not be null", name));
+    }
+    std::slice::from_raw_parts(array, len)
+        .iter()
+        .map(|item| {
+            if item.is_null() {
+                Err(format!("{} must not contain null strings", name))
+            } else {
+                Ok(CStr::from_ptr(*item).to_string_lossy().to_string())
+            }
+        })
+        .collect()
+}
+
+// Helper function to convert a Rust string to a C char pointer
+fn string_to_c_char(s: &str) -> *mut c_char {
+    match CString::new(s) {
+        Ok(c_string) => c_string.into_raw(),
         Err(_) => ptr::null_mut(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    const MODEL: &str = "test-model";
+
+    fn completion(message: Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{"message": message}],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15}
+        }))
+    }
+
+    /// A Databricks endpoint that asks for a shell command, then finishes
+    fn mock_databricks() -> MockServer {
+        get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            let invocations = format!("/serving-endpoints/{}/invocations", MODEL);
+            Mock::given(method("POST"))
+                .and(path(invocations.as_str()))
+                .respond_with(completion(json!({
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "developer__shell",
+                            "arguments": "{\"command\": \"printf 'answer-%s' 42\"}"
+                        }
+                    }]
+                })))
+                .up_to_n_times(1)
+                .with_priority(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path(invocations.as_str()))
+                .respond_with(completion(json!({
+                    "role": "assistant",
+                    "content": "All done"
+                })))
+                .mount(&server)
+                .await;
+            server
+        })
+    }
+
+    static LOGS: Mutex<Vec<(u32, String)>> = Mutex::new(Vec::new());
+
+    extern "C" fn collect_log(level: u32, message: *const c_char) {
+        let message = unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .to_string();
+        LOGS.lock().unwrap().push((level, message));
+    }
+
+    fn collected(marker: &str) -> Vec<(u32, String)> {
+        LOGS.lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, message)| message.contains(marker))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_log_callback() {
+        assert!(goose_set_log_callback(collect_log));
+        goose_set_log_level(2);
+
+        // Logged from a runtime worker thread, as the library does
+        get_runtime().block_on(async {
+            tokio::spawn(async {
+                tracing::warn!(attempt = 2, "ffi log test warning");
+                tracing::info!("ffi log test info");
+            })
+            .await
+            .unwrap();
+        });
+        assert_eq!(
+            collected("ffi log test"),
+            vec![(2, "ffi log test warning attempt=2".to_string())]
+        );
+
+        goose_set_log_level(4);
+        tracing::debug!("ffi log test debug");
+        assert_eq!(
+            collected("ffi log test debug"),
+            vec![(4, "ffi log test debug".to_string())]
+        );
+
+        goose_clear_log_callback();
+        tracing::error!("ffi log test cleared");
+        assert!(collected("ffi log test cleared").is_empty());
+    }
+
+    #[test]
+    fn test_provider_list_models() {
+        let server = get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/api/2.0/serving-endpoints"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "endpoints": [
+                        {"name": "databricks-claude-3-7-sonnet", "task": "llm/v1/chat"},
+                        {"name": "databricks-meta-llama-3-3-70b-instruct", "task": "llm/v1/chat"}
+                    ]
+                })))
+                .mount(&server)
+                .await;
+            server
+        });
+        let api_key = CString::new("test-key").unwrap();
+        let host = CString::new(server.uri()).unwrap();
+
+        unsafe {
+            let models = goose_provider_list_models(
+                ProviderType::Databricks,
+                api_key.as_ptr(),
+                host.as_ptr(),
+            );
+            assert!(!models.is_null());
+            let json = CStr::from_ptr(models).to_string_lossy().to_string();
+            goose_free_string(models);
+            let models: Vec<String> = serde_json::from_str(&json).unwrap();
+            assert_eq!(
+                models,
+                vec![
+                    "databricks-claude-3-7-sonnet",
+                    "databricks-meta-llama-3-3-70b-instruct"
+                ]
+            );
+
+            let default_model = goose_provider_get_default_model(ProviderType::Databricks);
+            assert_eq!(
+                CStr::from_ptr(default_model).to_str().unwrap(),
+                "databricks-meta-llama-3-3-70b-instruct"
+            );
+            goose_free_string(default_model);
+
+            // Failed requests return null
+            let host = CString::new(format!("{}/missing/", server.uri())).unwrap();
+            assert!(goose_provider_list_models(
+                ProviderType::Databricks,
+                api_key.as_ptr(),
+                host.as_ptr()
+            )
+            .is_null());
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_openai_agent() {
+        let server = get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/v1/chat/completions"))
+                .respond_with(completion(json!({
+                    "role": "assistant",
+                    "content": "Hello from OpenAI"
+                })))
+                .mount(&server)
+                .await;
+            server
+        });
+        let api_key = CString::new("test-key").unwrap();
+        let model_name = CString::new("gpt-4o").unwrap();
+        let host = CString::new(server.uri()).unwrap();
+        let config = ProviderConfigFFI {
+            provider_type: ProviderType::OpenAI,
+            api_key: api_key.as_ptr(),
+            model_name: model_name.as_ptr(),
+            host: host.as_ptr(),
+        };
+
+        temp_env::with_vars_unset(["OPENAI_API_KEY", "OPENAI_HOST"], || unsafe {
+            let agent = goose_agent_new(&config);
+            assert!(!agent.is_null());
+            // The given settings are used without changing the environment
+            assert!(std::env::var("OPENAI_API_KEY").is_err());
+            assert!(std::env::var("OPENAI_HOST").is_err());
+            let message = CString::new("Hello").unwrap();
+            let response = goose_agent_send_message(agent, message.as_ptr());
+            assert!(!response.is_null());
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            assert!(text.contains("Hello from OpenAI"), "{}", text);
+            goose_agent_free(agent);
+
+            let default_model = goose_provider_get_default_model(ProviderType::Anthropic);
+            assert_eq!(
+                CStr::from_ptr(default_model).to_str().unwrap(),
+                AnthropicProvider::metadata().default_model
+            );
+            goose_free_string(default_model);
+        });
+    }
+
+    /// Replies with the system prompt of each request
+    struct EchoSystemPrompt;
+
+    impl Respond for EchoSystemPrompt {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = request.body_json().unwrap();
+            let system = body["messages"][0]["content"].as_str().unwrap_or_default();
+            completion(json!({
+                "role": "assistant",
+                "content": format!("System prompt: {}", system)
+            }))
+        }
+    }
+
+    fn agent_for(server: &MockServer) -> AgentPtr {
+        let api_key = CString::new("test-key").unwrap();
+        let model_name = CString::new(MODEL).unwrap();
+        let host = CString::new(server.uri()).unwrap();
+        let config = ProviderConfigFFI {
+            provider_type: ProviderType::Databricks,
+            api_key: api_key.as_ptr(),
+            model_name: model_name.as_ptr(),
+            host: host.as_ptr(),
+        };
+        let agent = unsafe { goose_agent_new(&config) };
+        assert!(!agent.is_null());
+        agent
+    }
+
+    #[test]
+    fn test_send_messages() {
+        let server = get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path(format!("/serving-endpoints/{}/invocations", MODEL)))
+                .respond_with(EchoSystemPrompt)
+                .mount(&server)
+                .await;
+            server
+        });
+        let agent = agent_for(&server);
+
+        let system = CString::new("Always answer like a pirate").unwrap();
+        let user = CString::new("Hello").unwrap();
+        let message = |role, content: &CString| MessageFFI {
+            role,
+            content: content.as_ptr(),
+        };
+
+        unsafe {
+            let messages = [
+                message(MessageRole::System, &system),
+                message(MessageRole::User, &user),
+            ];
+            let mut error = ptr::null_mut();
+            let response = goose_agent_send_messages(agent, messages.as_ptr(), 2, &mut error);
+            assert!(error.is_null());
+            assert!(!response.is_null());
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            assert!(text.contains("System prompt: "), "{}", text);
+            assert!(text.contains("Always answer like a pirate"), "{}", text);
+
+            // System messages replace those of earlier calls
+            let other = CString::new("Always answer in French").unwrap();
+            let messages = [
+                message(MessageRole::System, &other),
+                message(MessageRole::User, &user),
+            ];
+            let response = goose_agent_send_messages(agent, messages.as_ptr(), 2, &mut error);
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            assert!(text.contains("Always answer in French"), "{}", text);
+            assert!(!text.contains("pirate"), "{}", text);
+
+            for invalid in [
+                vec![message(MessageRole::Assistant, &user)],
+                vec![
+                    message(MessageRole::User, &user),
+                    message(MessageRole::User, &user),
+                ],
+                vec![
+                    message(MessageRole::User, &user),
+                    message(MessageRole::System, &system),
+                ],
+                vec![message(MessageRole::System, &system)],
+            ] {
+                let mut error = ptr::null_mut();
+                let response =
+                    goose_agent_send_messages(agent, invalid.as_ptr(), invalid.len(), &mut error);
+                assert!(response.is_null());
+                assert!(!error.is_null());
+                goose_free_string(error);
+            }
+
+            goose_agent_free(agent);
+        }
+    }
+
+    /// Replies with the content of the last message of each request
+    struct EchoLastMessage;
+
+    impl Respond for EchoLastMessage {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = request.body_json().unwrap();
+            let last = body["messages"].as_array().unwrap().last().unwrap();
+            completion(json!({
+                "role": "assistant",
+                "content": format!("Last message: {}", last["content"])
+            }))
+        }
+    }
+
+    /// Calls the weather tool when the last message is from the user, and otherwise
+    /// replies with the tool's result
+    struct CallWeatherTool;
+
+    impl Respond for CallWeatherTool {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = request.body_json().unwrap();
+            let last = body["messages"].as_array().unwrap().last().unwrap();
+            if last["role"] == "tool" {
+                return completion(json!({
+                    "role": "assistant",
+                    "content": format!("Tool result: {}", last["content"])
+                }));
+            }
+            completion(json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "weather", "arguments": "{\"city\": \"Paris\"}"}
+                }]
+            }))
+        }
+    }
+
+    #[test]
+    fn test_inject_tool_result() {
+        let server = get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path(format!("/serving-endpoints/{}/invocations", MODEL)))
+                .respond_with(CallWeatherTool)
+                .mount(&server)
+                .await;
+            server
+        });
+        let agent = agent_for(&server);
+
+        unsafe {
+            let name = CString::new("weather").unwrap();
+            let description = CString::new("Get the weather in a city").unwrap();
+            let schema =
+                CString::new(r#"{"type": "object", "properties": {"city": {"type": "string"}}}"#)
+                    .unwrap();
+            let mut error = ptr::null_mut();
+            assert!(goose_agent_add_frontend_tool(
+                agent,
+                name.as_ptr(),
+                description.as_ptr(),
+                schema.as_ptr(),
+                &mut error
+            ));
+            assert!(error.is_null());
+
+            // Nothing to continue yet
+            assert!(goose_agent_continue(agent).is_null());
+            assert_eq!(
+                take_last_error().unwrap().0,
+                GooseErrorCode::InvalidArgument
+            );
+
+            // The reply stops at the call of the frontend tool
+            let message = CString::new("What's the weather in Paris?").unwrap();
+            let response = goose_agent_send_message(agent, message.as_ptr());
+            assert!(!response.is_null());
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            assert!(text.contains("frontendToolRequest"), "{}", text);
+            assert!(!text.contains("Tool result"), "{}", text);
+
+            let pending = goose_agent_get_pending_tool_calls(agent);
+            assert!(!pending.is_null());
+            let json = CStr::from_ptr(pending).to_string_lossy().to_string();
+            goose_free_string(pending);
+            assert_eq!(
+                serde_json::from_str::<Value>(&json).unwrap(),
+                json!([{"id": "call_1", "name": "weather", "arguments": {"city": "Paris"}}])
+            );
+
+            // The result is required to continue
+            assert!(goose_agent_continue(agent).is_null());
+            assert_eq!(
+                take_last_error().unwrap().0,
+                GooseErrorCode::InvalidArgument
+            );
+
+            let id = CString::new("call_1").unwrap();
+            let result = CString::new("Sunny, 24 degrees").unwrap();
+            let other = CString::new("call_2").unwrap();
+            assert!(!goose_agent_inject_tool_result(
+                agent,
+                name.as_ptr(),
+                other.as_ptr(),
+                result.as_ptr()
+            ));
+            assert_eq!(
+                take_last_error().unwrap().0,
+                GooseErrorCode::InvalidArgument
+            );
+            assert!(goose_agent_inject_tool_result(
+                agent,
+                name.as_ptr(),
+                id.as_ptr(),
+                result.as_ptr()
+            ));
+            // The call is no longer pending
+            assert!(!goose_agent_inject_tool_result(
+                agent,
+                name.as_ptr(),
+                id.as_ptr(),
+                result.as_ptr()
+            ));
+            assert_eq!(
+                take_last_error().unwrap().0,
+                GooseErrorCode::InvalidArgument
+            );
+            let pending = goose_agent_get_pending_tool_calls(agent);
+            assert_eq!(CStr::from_ptr(pending).to_str().unwrap(), "[]");
+            goose_free_string(pending);
+
+            let response = goose_agent_continue(agent);
+            assert!(!response.is_null());
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            assert!(text.contains("Tool result: "), "{}", text);
+            assert!(text.contains("Sunny, 24 degrees"), "{}", text);
+
+            // The reply is complete
+            assert!(goose_agent_continue(agent).is_null());
+
+            // An agent can be freed while its reply waits for tool results
+            let response = goose_agent_send_message(agent, message.as_ptr());
+            goose_free_string(response);
+            goose_agent_free(agent);
+        }
+    }
+
+    #[test]
+    fn test_record_frontend_tool_requests() {
+        let tool_call = Ok(ToolCall::new("weather", json!({"city": "Paris"})));
+        let mut conversation = vec![Message::user().with_text("Weather?")];
+
+        record(
+            &mut conversation,
+            Message::assistant().with_text("Checking"),
+        );
+        record(
+            &mut conversation,
+            Message::assistant().with_frontend_tool_request("call_1", tool_call.clone()),
+        );
+        assert_eq!(conversation.len(), 2);
+        assert_eq!(conversation[1].as_concat_text(), "Checking");
+        assert_eq!(
+            conversation[1].content[1].as_tool_request().unwrap().id,
+            "call_1"
+        );
+
+        // Without text, the request makes its own assistant message
+        conversation.truncate(1);
+        record(&mut conversation, Message::assistant());
+        record(
+            &mut conversation,
+            Message::assistant().with_frontend_tool_request("call_1", tool_call),
+        );
+        assert_eq!(conversation.len(), 2);
+        assert_eq!(conversation[1].role, Role::Assistant);
+        assert!(conversation[1].content[0].as_tool_request().is_some());
+    }
+
+    /// Runs the test with builtin extensions served by the goose binary, which is built
+    /// next to the test binary by `cargo build -p goose-cli`
+    fn with_goose_builtins(test: impl FnOnce()) {
+        let goose: PathBuf = std::env::current_exe()
+            .unwrap()
+            .parent()
+            .and_then(Path::parent)
+            .unwrap()
+            .join(format!("goose{}", std::env::consts::EXE_SUFFIX));
+        assert!(
+            goose.exists(),
+            "{} has not been built, run cargo build -p goose-cli first",
+            goose.display()
+        );
+        temp_env::with_vars(
+            [
+                ("GOOSE_BUILTIN_EXECUTABLE", Some(goose.as_os_str())),
+                ("GOOSE_MODE", Some("auto".as_ref())),
+            ],
+            test,
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[ignore = "requires the goose binary"]
+    fn test_builtin_extension_tools_are_called() {
+        with_goose_builtins(|| unsafe {
+            let server = mock_databricks();
+            let agent = agent_for(&server);
+
+            let mut error = ptr::null_mut();
+            let cmd = CString::new("/nonexistent/extension").unwrap();
+            assert!(!goose_agent_add_stdio_extension(
+                agent,
+                cmd.as_ptr(),
+                ptr::null(),
+                0,
+                ptr::null(),
+                ptr::null(),
+                0,
+                &mut error,
+            ));
+            assert!(!error.is_null());
+            assert!(CStr::from_ptr(error)
+                .to_string_lossy()
+                .starts_with("Failed to add extension"));
+            goose_free_string(error);
+
+            let name = CString::new("developer").unwrap();
+            let mut error = ptr::null_mut();
+            assert!(goose_agent_add_builtin_extension(
+                agent,
+                name.as_ptr(),
+                &mut error
+            ));
+            assert!(error.is_null());
+
+            let message = CString::new("Say hello from a shell").unwrap();
+            let response = goose_agent_send_message(agent, message.as_ptr());
+            assert!(!response.is_null());
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            goose_agent_free(agent);
+
+            assert!(text.contains("developer__shell"), "{}", text);
+            // The output of the shell command, not just the request for it
+            assert!(text.contains("answer-42"), "{}", text);
+            assert!(text.contains("All done"), "{}", text);
+        });
+    }
+
+    #[test]
+    #[serial]
+    #[ignore = "requires the goose binary"]
+    fn test_add_builtin_extension() {
+        with_goose_builtins(|| unsafe {
+            let server = mock_databricks();
+            let agent = agent_for(&server);
+            let name = CString::new("developer").unwrap();
+
+            let result = goose_agent_add_extension(
+                agent,
+                name.as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                0,
+                ptr::null(),
+            );
+            assert!((*result).succeeded);
+            assert!((*result).error_message.is_null());
+            goose_free_async_result(result);
+
+            let message = CString::new("Say hello from a shell").unwrap();
+            let response = goose_agent_send_message(agent, message.as_ptr());
+            assert!(!response.is_null());
+            let text = CStr::from_ptr(response).to_string_lossy().to_string();
+            goose_free_string(response);
+            assert!(text.contains("answer-42"), "{}", text);
+
+            goose_agent_free(agent);
+        });
+    }
+
+    #[test]
+    fn test_add_extension() {
+        let server = mock_databricks();
+
+        unsafe {
+            let agent = agent_for(&server);
+            let name = CString::new("developer").unwrap();
+
+            let cmd = CString::new("/nonexistent/extension").unwrap();
+            let envs = CString::new("[\"not\", \"an object\"]").unwrap();
+            let result = goose_agent_add_extension(
+                agent,
+                name.as_ptr(),
+                cmd.as_ptr(),
+                ptr::null(),
+                0,
+                envs.as_ptr(),
+            );
+            assert!(!(*result).succeeded);
+            assert!(CStr::from_ptr((*result).error_message)
+                .to_string_lossy()
+                .starts_with("Invalid envs_json"));
+            goose_free_async_result(result);
+
+            let envs = CString::new(r#"{"API_KEY": "secret"}"#).unwrap();
+            let result = goose_agent_add_extension(
+                agent,
+                name.as_ptr(),
+                cmd.as_ptr(),
+                ptr::null(),
+                0,
+                envs.as_ptr(),
+            );
+            assert!(!(*result).succeeded);
+            assert!(CStr::from_ptr((*result).error_message)
+                .to_string_lossy()
+                .starts_with("Failed to add extension"));
+            goose_free_async_result(result);
+
+            goose_agent_free(agent);
+        }
+    }
+
+    unsafe fn take_last_error() -> Option<(GooseErrorCode, String)> {
+        let error = goose_get_last_error();
+        if error.is_null() {
+            return None;
+        }
+        let taken = (
+            (*error).code,
+            CStr::from_ptr((*error).message)
+                .to_string_lossy()
+                .to_string(),
+        );
+        goose_free_error(error);
+        Some(taken)
+    }
+
+    #[test]
+    #[serial]
+    fn test_last_error() {
+        let model_name = CString::new(MODEL).unwrap();
+        let host = CString::new("http://localhost:1").unwrap();
+        let mut config = ProviderConfigFFI {
+            provider_type: ProviderType::Databricks,
+            api_key: ptr::null(),
+            model_name: ptr::null(),
+            host: host.as_ptr(),
+        };
+
+        temp_env::with_var_unset("DATABRICKS_API_KEY", || unsafe {
+            assert!(take_last_error().is_none());
+
+            assert!(goose_agent_new(ptr::null()).is_null());
+            let (code, _) = take_last_error().unwrap();
+            assert_eq!(code, GooseErrorCode::NullPointer);
+
+            assert!(goose_agent_new(&config).is_null());
+            let (code, _) = take_last_error().unwrap();
+            assert_eq!(code, GooseErrorCode::MissingModelName);
+
+            config.model_name = model_name.as_ptr();
+            assert!(goose_agent_new(&config).is_null());
+            let (code, message) = take_last_error().unwrap();
+            assert_eq!(code, GooseErrorCode::MissingApiKey);
+            assert!(message.contains("DATABRICKS_API_KEY"), "{}", message);
+
+            // Errors are only reported once
+            assert!(take_last_error().is_none());
+
+            let message = CString::new("Hello").unwrap();
+            assert!(goose_agent_send_message(ptr::null_mut(), message.as_ptr()).is_null());
+            let (code, _) = take_last_error().unwrap();
+            assert_eq!(code, GooseErrorCode::NullPointer);
+        });
+    }
+
+    #[test]
+    fn test_session_save_and_load() {
+        let server = get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path(format!("/serving-endpoints/{}/invocations", MODEL)))
+                .respond_with(EchoLastMessage)
+                .mount(&server)
+                .await;
+            server
+        });
+        let agent = agent_for(&server);
+        let path =
+            std::env::temp_dir().join(format!("goose-ffi-session-{}.jsonl", std::process::id()));
+        let c_path = CString::new(path.to_string_lossy().as_ref()).unwrap();
+
+        unsafe {
+            let session = goose_session_new();
+            assert!(goose_agent_reply_session(agent, session).is_null());
+            assert_eq!(
+                take_last_error().unwrap().0,
+                GooseErrorCode::InvalidArgument
+            );
+
+            let system = CString::new("Be brief").unwrap();
+            assert!(!goose_session_add_message(
+                session,
+                MessageRole::System,
+                system.as_ptr()
+            ));
+            assert_eq!(
+                take_last_error().unwrap().0,
+                GooseErrorCode::InvalidArgument
+            );
+
+            for text in ["Hello", "And again"] {
+                let text = CString::new(text).unwrap();
+                assert!(goose_session_add_message(
+                    session,
+                    MessageRole::User,
+                    text.as_ptr()
+                ));
+                let response = goose_agent_reply_session(agent, session);
+                assert!(!response.is_null());
+                goose_free_string(response);
+            }
+            assert_eq!((*session).messages.len(), 4);
+
+            let result = goose_session_save(session, c_path.as_ptr());
+            assert!(
+                (*result).succeeded,
+                "{:?}",
+                CStr::from_ptr((*result).error_message)
+            );
+            goose_free_async_result(result);
+            goose_session_free(session);
+
+            let loaded = goose_session_load(c_path.as_ptr());
+            assert!(!loaded.is_null());
+            let texts: Vec<_> = (*loaded)
+                .messages
+                .iter()
+                .map(|message| (message.role.clone(), message.as_concat_text()))
+                .collect();
+            assert_eq!(
+                texts,
+                vec![
+                    (Role::User, "Hello".to_string()),
+                    (Role::Assistant, "Last message: \"Hello\"".to_string()),
+                    (Role::User, "And again".to_string()),
+                    (Role::Assistant, "Last message: \"And again\"".to_string()),
+                ]
+            );
+            goose_session_free(loaded);
+            std::fs::remove_file(&path).unwrap();
+
+            assert!(goose_session_load(c_path.as_ptr()).is_null());
+            assert_eq!(take_last_error().unwrap().0, GooseErrorCode::IoError);
+
+            goose_agent_free(agent);
+        }
+    }
+
+    extern "C" fn collect_chunk(message: *const c_char, user_data: *mut c_void) {
+        let chunks = unsafe { &mut *(user_data as *mut Vec<Option<String>>) };
+        chunks.push((!message.is_null()).then(|| {
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .to_string()
+        }));
+    }
+
+    #[test]
+    fn test_send_message_stream() {
+        let server = get_runtime().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path(format!("/serving-endpoints/{}/invocations", MODEL)))
+                .respond_with(EchoLastMessage)
+                .mount(&server)
+                .await;
+            server
+        });
+        let agent = agent_for(&server);
+        let message = CString::new("Hello").unwrap();
+        let mut chunks: Vec<Option<String>> = Vec::new();
+
+        unsafe {
+            assert!(goose_agent_send_message_stream(
+                agent,
+                message.as_ptr(),
+                collect_chunk,
+                &mut chunks as *mut _ as *mut c_void,
+                None,
+            ));
+
+            // One message, then the end of the stream
+            assert_eq!(chunks.len(), 2, "{:?}", chunks);
+            let reply: Value = serde_json::from_str(chunks[0].as_deref().unwrap()).unwrap();
+            assert_eq!(reply["role"], "assistant");
+            assert_eq!(reply["content"][0]["text"], "Last message: \"Hello\"");
+            assert!(chunks[1].is_none());
+
+            // The streamed reply is kept like any other
+            let pending = goose_agent_get_pending_tool_calls(agent);
+            assert_eq!(CStr::from_ptr(pending).to_str().unwrap(), "[]");
+            goose_free_string(pending);
+
+            assert!(!goose_agent_send_message_stream(
+                ptr::null_mut(),
+                message.as_ptr(),
+                collect_chunk,
+                &mut chunks as *mut _ as *mut c_void,
+                None,
+            ));
+            assert_eq!(chunks.len(), 2);
+            assert_eq!(
+                take_last_error().map(|(code, _)| code),
+                Some(GooseErrorCode::NullPointer)
+            );
+
+            goose_agent_free(agent);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use azure_storage::{CloudLocation, StorageCredentials};
+use azure_storage_blobs::prelude::{BlobServiceClient, ClientBuilder};
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use url::Url;
+
+/// The well known account of the Azurite emulator
+const AZURITE_ACCOUNT: &str = "devstoreaccount1";
+const AZURITE_KEY: &str =
+    "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+const AZURITE_ENDPOINT: &str = "http://127.0.0.1:10000/devstoreaccount1";
+
+/// A storage account, its key and the endpoint of its blob service
+#[derive(Clone, Debug)]
+pub struct StorageAccount {
+    pub name: String,
+    key: String,
+    pub endpoint: Url,
+}
+
+impl StorageAccount {
+    pub fn new(name: &str, key: &str, endpoint: Option<&str>) -> Result<Self, String> {
+        let key = key.trim();
+        BASE64_STANDARD
+            .decode(key)
+            .map_err(|_| "The storage account key is not valid base64".to_string())?;
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint.to_string(),
+            None => format!("https://{}.blob.core.windows.net", name),
+        };
+        let endpoint = Url::parse(endpoint.trim_end_matches('/'))
+            .map_err(|e| format!("Invalid blob endpoint '{}': {}", endpoint, e))?;
+        Ok(Self {
+            name: name.to_string(),
+            key: key.to_string(),
+            endpoint,
+        })
+    }
+
+    /// Read the account from AZURE_STORAGE_CONNECTION_STRING, or from AZURE_STORAGE_ACCOUNT
+    /// and AZURE_STORAGE_KEY with the endpoint in AZURE_BLOB_ENDPOINT
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        if let Some(connection_string) = var("AZURE_STORAGE_CONNECTION_STRING") {
+            return Self::from_connection_string(&connection_string);
+        }
+        match (var("AZURE_STORAGE_ACCOUNT"), var("AZURE_STORAGE_KEY")) {
+            (Some(account), Some(key)) => {
+                Self::new(&account, &key, var("AZURE_BLOB_ENDPOINT").as_deref())
+            }
+            _ => Err("Set AZURE_STORAGE_CONNECTION_STRING, or AZURE_STORAGE_ACCOUNT and AZURE_STORAGE_KEY".to_string()),
+        }
+    }
+
+    pub fn from_connection_string(connection_string: &str) -> Result<Self, String> {
+        let setting = |name: &str| {
+            connection_string
+                .split(';')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim().to_string())
+        };
+        if setting("UseDevelopmentStorage").is_some_and(|value| value == "true") {
+            return Self::new(AZURITE_ACCOUNT, AZURITE_KEY, Some(AZURITE_ENDPOINT));
+        }
+
+        let name =
+            setting("AccountName").ok_or("The connection string has no AccountName".to_string())?;
+        let key =
+            setting("AccountKey").ok_or("The connection string has no AccountKey".to_string())?;
+        let endpoint = setting("BlobEndpoint").or_else(|| {
+            let protocol = setting("DefaultEndpointsProtocol").unwrap_or("https".to_string());
+            let suffix = setting("EndpointSuffix").unwrap_or("core.windows.net".to_string());
+            Some(format!("{}://{}.blob.{}", protocol, name, suffix))
+        });
+        Self::new(&name, &key, endpoint.as_deref())
+    }
+
+    /// A client of the blob service authorized with the account key
+    pub fn client(&self) -> BlobServiceClient {
+        let location = CloudLocation::Custom {
+            account: self.name.clone(),
+            uri: self.endpoint.to_string(),
+        };
+        let credentials = StorageCredentials::access_key(self.name.clone(), self.key.clone());
+        ClientBuilder::with_location(location, credentials).blob_service_client()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_strings() {
+        let account = StorageAccount::from_connection_string(
+            "DefaultEndpointsProtocol=https;AccountName=acme;AccountKey=a2V5;EndpointSuffix=core.windows.net",
+        )
+        .unwrap();
+        assert_eq!(account.name, "acme");
+        let blob = account
+            .client()
+            .container_client("logs")
+            .blob_client("2025/01/app.log");
+        assert_eq!(
+            blob.url().unwrap().as_str(),
+            "https://acme.blob.core.windows.net/logs/2025/01/app.log"
+        );
+
+        let azurite = StorageAccount::from_connection_string("UseDevelopmentStorage=true").unwrap();
+        assert_eq!(
+            azurite
+                .client()
+                .container_client("logs")
+                .url()
+                .unwrap()
+                .as_str(),
+            "http://127.0.0.1:10000/devstoreaccount1/logs"
+        );
+
+        assert!(StorageAccount::from_connection_string("AccountName=acme").is_err());
+        assert!(StorageAccount::new("acme", "not base64!", None).is_err());
+    }
+}
@@ -0,0 +1,1063 @@
+mod auth;
+
+use azure_core::date;
+use azure_storage::shared_access_signature::{service_sas::BlobSasPermissions, SasProtocol};
+use azure_storage_blobs::prelude::{
+    BlobClient, BlobServiceClient, DeleteSnapshotsMethod, PublicAccess,
+};
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use futures::StreamExt;
+use indoc::indoc;
+use serde_json::{json, Value};
+use std::num::NonZeroU32;
+use std::{future::Future, pin::Pin};
+use time::OffsetDateTime;
+
+use crate::util::{json_content, required_str};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+pub use auth::StorageAccount;
+
+/// Blobs larger than this are not downloaded, a SAS url should be used instead
+const MAX_BLOB_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_PAGE_SIZE: u64 = 100;
+const DEFAULT_SAS_EXPIRY_HOURS: i64 = 1;
+/// The blob permissions a SAS url can grant, in the order the service requires
+const SAS_PERMISSIONS: &str = "racwd";
+
+/// Router for Azure Blob Storage, authorized with the account key
+#[derive(Clone)]
+pub struct AzureBlobRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    service: Result<BlobServiceClient, String>,
+}
+
+impl Default for AzureBlobRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AzureBlobRouter {
+    pub fn new() -> Self {
+        Self::with_account(StorageAccount::from_env())
+    }
+
+    /// Create a router for the given storage account, or one whose tools report the error
+    pub fn with_account(account: Result<StorageAccount, String>) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let writing = |title: &str, destructive: bool| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: false,
+                destructive_hint: destructive,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+
+        let list_containers = Tool::new(
+            "list_containers",
+            "List the containers of the storage account.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "prefix": {"type": "string", "description": "Only list containers whose name starts with this"}
+                }
+            }),
+            read_only("List Azure containers"),
+        );
+
+        let list_blobs = Tool::new(
+            "list_blobs",
+            indoc! {r#"
+                List the blobs of a container, one page at a time. When the result has a
+                next_marker, pass it as marker to get the next page.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["container"],
+                "properties": {
+                    "container": {"type": "string"},
+                    "prefix": {"type": "string", "description": "Only list blobs whose name starts with this, e.g. a virtual directory like 'logs/'"},
+                    "marker": {"type": "string", "description": "The next_marker of the previous page"},
+                    "limit": {"type": "integer", "default": DEFAULT_PAGE_SIZE, "description": "The maximum number of blobs per page, at most 5000"}
+                }
+            }),
+            read_only("List Azure blobs"),
+        );
+
+        let get_blob = Tool::new(
+            "get_blob",
+            indoc! {r#"
+                Download a blob of at most 10 MB. Text content is returned as is, other content
+                is returned base64 encoded. Use generate_sas_url for larger blobs.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["container", "blob"],
+                "properties": {
+                    "container": {"type": "string"},
+                    "blob": {"type": "string", "description": "The blob name, including any virtual directories"}
+                }
+            }),
+            read_only("Get an Azure blob"),
+        );
+
+        let upload_blob = Tool::new(
+            "upload_blob",
+            "Upload a block blob from a local file or a string, creating the blob or overwriting it.",
+            json!({
+                "type": "object",
+                "required": ["container", "blob"],
+                "properties": {
+                    "container": {"type": "string"},
+                    "blob": {"type": "string"},
+                    "path": {"type": "string", "description": "The local file to upload"},
+                    "content": {"type": "string", "description": "The text to upload, when no path is given"},
+                    "content_type": {"type": "string", "description": "Defaults to a type guessed from the blob name"}
+                }
+            }),
+            writing("Upload an Azure blob", false),
+        );
+
+        let delete_blob = Tool::new(
+            "delete_blob",
+            "Delete a blob and its snapshots.",
+            json!({
+                "type": "object",
+                "required": ["container", "blob"],
+                "properties": {
+                    "container": {"type": "string"},
+                    "blob": {"type": "string"}
+                }
+            }),
+            writing("Delete an Azure blob", true),
+        );
+
+        let copy_blob = Tool::new(
+            "copy_blob",
+            indoc! {r#"
+                Copy a blob within a container or to another container of the account. Large
+                copies complete in the background, check get_blob_metadata for the copy status.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["source_container", "source_blob", "destination_container"],
+                "properties": {
+                    "source_container": {"type": "string"},
+                    "source_blob": {"type": "string"},
+                    "destination_container": {"type": "string"},
+                    "destination_blob": {"type": "string", "description": "Defaults to the source blob name"}
+                }
+            }),
+            writing("Copy an Azure blob", false),
+        );
+
+        let get_blob_metadata = Tool::new(
+            "get_blob_metadata",
+            "Get the properties and user metadata of a blob without downloading it.",
+            json!({
+                "type": "object",
+                "required": ["container", "blob"],
+                "properties": {
+                    "container": {"type": "string"},
+                    "blob": {"type": "string"}
+                }
+            }),
+            read_only("Get Azure blob metadata"),
+        );
+
+        let generate_sas_url = Tool::new(
+            "generate_sas_url",
+            indoc! {r#"
+                Generate a url granting temporary access to a blob, signed with the account key.
+                Anyone with the url has the given permissions until it expires.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["container", "blob"],
+                "properties": {
+                    "container": {"type": "string"},
+                    "blob": {"type": "string"},
+                    "permissions": {
+                        "type": "string",
+                        "default": "r",
+                        "description": "Any of r (read), a (add), c (create), w (write) and d (delete)"
+                    },
+                    "expiry_hours": {"type": "integer", "default": DEFAULT_SAS_EXPIRY_HOURS}
+                }
+            }),
+            read_only("Generate an Azure SAS url"),
+        );
+
+        let instructions = indoc! {r#"
+            The Azure Blob Storage extension manages the containers and blobs of a storage account.
+            Blob names can contain slashes, which act as virtual directories: list them with a
+            prefix such as 'logs/2025/'.
+
+            - get_blob downloads blobs up to 10 MB; share larger blobs with generate_sas_url.
+            - upload_blob overwrites existing blobs without asking, and delete_blob cannot be
+              undone unless soft delete is enabled on the account. Confirm with the user first.
+            - SAS urls give access to anyone who has them, so keep their expiry short.
+        "#};
+
+        Self {
+            tools: vec![
+                list_containers,
+                list_blobs,
+                get_blob,
+                upload_blob,
+                delete_blob,
+                copy_blob,
+                get_blob_metadata,
+                generate_sas_url,
+            ],
+            instructions: instructions.to_string(),
+            service: account.map(|account| account.client()),
+        }
+    }
+
+    fn service(&self) -> Result<&BlobServiceClient, ToolError> {
+        self.service.as_ref().map_err(|e| {
+            ToolError::ExecutionError(format!("Azure Storage is not configured: {}", e))
+        })
+    }
+
+    fn blob(&self, params: &Value) -> Result<BlobClient, ToolError> {
+        let container = required_str(params, "container")?;
+        let blob = required_str(params, "blob")?;
+        Ok(self
+            .service()?
+            .container_client(container)
+            .blob_client(blob))
+    }
+
+    async fn list_containers(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let mut list = self.service()?.list_containers();
+        if let Some(prefix) = params.get("prefix").and_then(Value::as_str) {
+            list = list.prefix(prefix.to_string());
+        }
+
+        let mut pages = list.into_stream();
+        let mut containers = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(storage_error)?;
+            containers.extend(page.containers.iter().map(|container| {
+                let public_access = match container.public_access {
+                    PublicAccess::None => None,
+                    access => Some(<&str>::from(access)),
+                };
+                json!({
+                    "name": container.name,
+                    "last_modified": date::to_rfc1123(&container.last_modified),
+                    "public_access": public_access,
+                })
+            }));
+        }
+        Ok(vec![json_content(&Value::Array(containers))])
+    }
+
+    async fn list_blobs(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let container = required_str(&params, "container")?;
+        let limit = params
+            .get("limit")
+            .and_then(Value::as_u64)
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, 5000) as u32;
+
+        let mut list = self
+            .service()?
+            .container_client(container)
+            .list_blobs()
+            .max_results(NonZeroU32::new(limit).expect("limit is at least 1"));
+        if let Some(prefix) = params.get("prefix").and_then(Value::as_str) {
+            list = list.prefix(prefix.to_string());
+        }
+        if let Some(marker) = params.get("marker").and_then(Value::as_str) {
+            list = list.marker(marker.to_string());
+        }
+        let page = list
+            .into_stream()
+            .next()
+            .await
+            .ok_or_else(|| ToolError::ExecutionError("Azure returned no blob list".into()))?
+            .map_err(storage_error)?;
+
+        let blobs: Vec<Value> = page
+            .blobs
+            .blobs()
+            .map(|blob| {
+                json!({
+                    "name": blob.name,
+                    "size": blob.properties.content_length,
+                    "content_type": blob.properties.content_type,
+                    "last_modified": date::to_rfc1123(&blob.properties.last_modified),
+                })
+            })
+            .collect();
+        Ok(vec![json_content(&json!({
+            "blobs": blobs,
+            "next_marker": page.next_marker.as_ref().map(|marker| marker.as_str()),
+        }))])
+    }
+
+    async fn get_blob(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        // Ask for one byte more than the limit, to tell a blob of exactly 10 MB from a larger one
+        let mut response = self
+            .blob(&params)?
+            .get()
+            .range(0..MAX_BLOB_SIZE + 1)
+            .into_stream()
+            .next()
+            .await
+            .ok_or_else(|| ToolError::ExecutionError("Azure returned no blob content".into()))?
+            .map_err(storage_error)?;
+        let content_type = response.blob.properties.content_type.clone();
+
+        // The size is checked while reading, as servers may ignore the range
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.data.next().await {
+            let chunk = chunk.map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to download the blob: {}", e))
+            })?;
+            if bytes.len() + chunk.len() > MAX_BLOB_SIZE as usize {
+                return Err(ToolError::ExecutionError(
+                    "The blob is larger than the 10 MB limit. Use generate_sas_url to download it"
+                        .into(),
+                ));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(vec![Content::text(text)]),
+            Err(e) => {
+                let bytes = e.into_bytes();
+                Ok(vec![json_content(&json!({
+                    "content_type": content_type,
+                    "size": bytes.len(),
+                    "encoding": "base64",
+                    "content": BASE64_STANDARD.encode(&bytes),
+                }))])
+            }
+        }
+    }
+
+    async fn upload_blob(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let blob = self.blob(&params)?;
+        let body = match (
+            params.get("path").and_then(Value::as_str),
+            params.get("content").and_then(Value::as_str),
+        ) {
+            (Some(path), None) => {
+                std::fs::read(shellexpand::tilde(path).as_ref()).map_err(|e| {
+                    ToolError::InvalidParameters(format!("Failed to read '{}': {}", path, e))
+                })?
+            }
+            (None, Some(content)) => content.as_bytes().to_vec(),
+            _ => {
+                return Err(ToolError::InvalidParameters(
+                    "Provide either 'path' or 'content'".into(),
+                ))
+            }
+        };
+        let content_type = match params.get("content_type").and_then(Value::as_str) {
+            Some(content_type) => content_type.to_string(),
+            None => mime_guess::from_path(blob.blob_name())
+                .first_or_octet_stream()
+                .to_string(),
+        };
+
+        let size = body.len();
+        let response = blob
+            .put_block_blob(body)
+            .content_type(content_type.clone())
+            .await
+            .map_err(storage_error)?;
+
+        Ok(vec![json_content(&json!({
+            "container": blob.container_client().container_name(),
+            "blob": blob.blob_name(),
+            "size": size,
+            "content_type": content_type,
+            "etag": response.etag,
+        }))])
+    }
+
+    async fn delete_blob(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let blob = self.blob(&params)?;
+        blob.delete()
+            .delete_snapshots_method(DeleteSnapshotsMethod::Include)
+            .await
+            .map_err(storage_error)?;
+        Ok(vec![Content::text(format!(
+            "Deleted blob '{}' from container '{}'",
+            blob.blob_name(),
+            blob.container_client().container_name()
+        ))])
+    }
+
+    async fn copy_blob(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let source_container = required_str(&params, "source_container")?;
+        let source_blob = required_str(&params, "source_blob")?;
+        let destination_container = required_str(&params, "destination_container")?;
+        let destination_blob = params
+            .get("destination_blob")
+            .and_then(Value::as_str)
+            .unwrap_or(source_blob);
+
+        let service = self.service()?;
+        let source = service
+            .container_client(source_container)
+            .blob_client(source_blob)
+            .url()
+            .map_err(storage_error)?;
+        let response = service
+            .container_client(destination_container)
+            .blob_client(destination_blob)
+            .copy(source)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(vec![json_content(&json!({
+            "container": destination_container,
+            "blob": destination_blob,
+            "copy_id": response.copy_id.to_string(),
+            "copy_status": <&str>::from(response.copy_status),
+        }))])
+    }
+
+    async fn get_blob_metadata(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let blob = self.blob(&params)?;
+        let properties = blob.get_properties().await.map_err(storage_error)?.blob;
+
+        Ok(vec![json_content(&json!({
+            "container": blob.container_client().container_name(),
+            "blob": blob.blob_name(),
+            "size": properties.properties.content_length,
+            "content_type": properties.properties.content_type,
+            "last_modified": date::to_rfc1123(&properties.properties.last_modified),
+            "etag": properties.properties.etag.to_string(),
+            "blob_type": <&str>::from(properties.properties.blob_type),
+            "access_tier": properties.properties.access_tier.map(<&str>::from),
+            "copy_status": properties.properties.copy_status.map(<&str>::from),
+            "metadata": properties.metadata.unwrap_or_default(),
+        }))])
+    }
+
+    async fn generate_sas_url(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let blob = self.blob(&params)?;
+        let requested = params
+            .get("permissions")
+            .and_then(Value::as_str)
+            .unwrap_or("r");
+        if requested.is_empty() || !requested.chars().all(|c| SAS_PERMISSIONS.contains(c)) {
+            return Err(ToolError::InvalidParameters(format!(
+                "Invalid permissions '{}', use any of r, a, c, w and d",
+                requested
+            )));
+        }
+        let permissions = BlobSasPermissions {
+            read: requested.contains('r'),
+            add: requested.contains('a'),
+            create: requested.contains('c'),
+            write: requested.contains('w'),
+            delete: requested.contains('d'),
+            ..Default::default()
+        };
+        // In the order the service requires, like "rw" for "wr"
+        let granted = permissions.to_string();
+        let hours = params
+            .get("expiry_hours")
+            .and_then(Value::as_i64)
+            .unwrap_or(DEFAULT_SAS_EXPIRY_HOURS);
+        let expiry = Some(hours)
+            .filter(|hours| *hours >= 1)
+            .and_then(|hours| hours.checked_mul(3600))
+            .and_then(|seconds| {
+                OffsetDateTime::now_utc().checked_add(time::Duration::seconds(seconds))
+            })
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("'expiry_hours' must be at least 1".into())
+            })?;
+        let protocol = match blob.url().map_err(storage_error)?.scheme() {
+            "https" => SasProtocol::Https,
+            _ => SasProtocol::HttpHttps,
+        };
+        let sas = blob
+            .shared_access_signature(permissions, expiry)
+            .await
+            .map_err(storage_error)?
+            .protocol(protocol);
+        let url = blob.generate_signed_blob_url(&sas).map_err(storage_error)?;
+        let expires = url
+            .query_pairs()
+            .find(|(name, _)| name == "se")
+            .map(|(_, value)| value.into_owned());
+        Ok(vec![json_content(&json!({
+            "url": url.as_str(),
+            "permissions": granted,
+            "expires": expires,
+        }))])
+    }
+}
+
+/// Map a failed request to a tool error
+fn storage_error(error: azure_core::Error) -> ToolError {
+    let Some(http) = error.as_http_error() else {
+        return ToolError::ExecutionError(format!("Azure request failed: {}", error));
+    };
+    let status = http.status() as u16;
+    let code = http.error_code().unwrap_or_default();
+    let detail = http
+        .error_message()
+        .and_then(|message| message.lines().next())
+        .unwrap_or(code);
+    match (status, code) {
+        (404, "ContainerNotFound") => ToolError::InvalidParameters("Container not found".into()),
+        (404, "BlobNotFound") => ToolError::InvalidParameters("Blob not found".into()),
+        (404, _) => ToolError::InvalidParameters(format!("Not found: {}", detail)),
+        (403, _) => ToolError::ExecutionError(format!(
+            "Azure authentication failed ({}), check the storage account key: {}",
+            code, detail
+        )),
+        (400, _) => ToolError::InvalidParameters(format!("Invalid request ({}): {}", code, detail)),
+        _ => ToolError::ExecutionError(format!(
+            "Azure Storage returned {} ({}): {}",
+            status, code, detail
+        )),
+    }
+}
+
+impl Router for AzureBlobRouter {
+    fn name(&self) -> String {
+        "azure_blob".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "list_containers" => this.list_containers(arguments).await,
+                "list_blobs" => this.list_blobs(arguments).await,
+                "get_blob" => this.get_blob(arguments).await,
+                "upload_blob" => this.upload_blob(arguments).await,
+                "delete_blob" => this.delete_blob(arguments).await,
+                "copy_blob" => this.copy_blob(arguments).await,
+                "get_blob_metadata" => this.get_blob_metadata(arguments).await,
+                "generate_sas_url" => this.generate_sas_url(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::parse;
+    use url::Url;
+    use wiremock::matchers::{header, header_exists, header_regex, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A router for an account served by `server` with path style urls, like Azurite
+    fn router_for(server: &MockServer) -> AzureBlobRouter {
+        let endpoint = format!("{}/devstoreaccount1", server.uri());
+        AzureBlobRouter::with_account(StorageAccount::new(
+            "devstoreaccount1",
+            "a2V5",
+            Some(&endpoint),
+        ))
+    }
+
+    fn signed() -> impl wiremock::Match {
+        header_regex(
+            "authorization",
+            "^SharedKey devstoreaccount1:[A-Za-z0-9+/]+=*$",
+        )
+    }
+
+    /// A response with the headers the service sends with every response
+    fn storage_response(status: u16) -> ResponseTemplate {
+        ResponseTemplate::new(status)
+            .insert_header("x-ms-request-id", "4b6ad1a4-301e-0000-3e3a-6f5dbd000000")
+            .insert_header("x-ms-version", "2022-11-02")
+            .insert_header("date", "Mon, 06 Jan 2025 10:00:00 GMT")
+            .insert_header("server", "Windows-Azure-Blob/1.0")
+    }
+
+    /// A response with the properties of a blob in its headers
+    fn blob_response(status: u16) -> ResponseTemplate {
+        storage_response(status)
+            .insert_header("x-ms-creation-time", "Mon, 06 Jan 2025 09:00:00 GMT")
+            .insert_header("last-modified", "Mon, 06 Jan 2025 10:00:00 GMT")
+            .insert_header("etag", "\"0x1\"")
+            .insert_header("x-ms-blob-type", "BlockBlob")
+            .insert_header("x-ms-server-encrypted", "true")
+    }
+
+    fn error_response(status: u16, code: &str, message: &str) -> ResponseTemplate {
+        storage_response(status)
+            .insert_header("x-ms-error-code", code)
+            .set_body_raw(
+                format!(
+                    "<?xml version=\"1.0\" encoding=\"utf-8\"?><Error><Code>{}</Code><Message>{}</Message></Error>",
+                    code, message
+                ),
+                "application/xml",
+            )
+    }
+
+    #[tokio::test]
+    async fn test_list_containers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1"))
+            .and(query_param("comp", "list"))
+            .and(signed())
+            .and(header_exists("x-ms-version"))
+            .and(header_exists("x-ms-date"))
+            .respond_with(storage_response(200).set_body_raw(
+                indoc! {r#"
+                    <?xml version="1.0" encoding="utf-8"?>
+                    <EnumerationResults ServiceEndpoint="http://127.0.0.1:10000/devstoreaccount1">
+                      <Containers>
+                        <Container>
+                          <Name>logs</Name>
+                          <Properties>
+                            <Last-Modified>Mon, 06 Jan 2025 10:00:00 GMT</Last-Modified>
+                            <Etag>"0x1"</Etag>
+                            <LeaseStatus>unlocked</LeaseStatus>
+                            <LeaseState>available</LeaseState>
+                            <HasImmutabilityPolicy>false</HasImmutabilityPolicy>
+                            <HasLegalHold>false</HasLegalHold>
+                          </Properties>
+                        </Container>
+                      </Containers>
+                      <NextMarker />
+                    </EnumerationResults>
+                "#},
+                "application/xml",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("list_containers", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(
+            parse(result),
+            json!([{"name": "logs", "last_modified": "Mon, 06 Jan 2025 10:00:00 GMT", "public_access": null}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_blobs_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1/logs"))
+            .and(query_param("restype", "container"))
+            .and(query_param("comp", "list"))
+            .and(query_param("prefix", "2025/"))
+            .and(query_param("marker", "page2"))
+            .and(query_param("maxresults", "2"))
+            .and(signed())
+            .respond_with(storage_response(200).set_body_raw(
+                indoc! {r#"
+                    <?xml version="1.0" encoding="utf-8"?>
+                    <EnumerationResults ContainerName="logs">
+                      <Prefix>2025/</Prefix>
+                      <Blobs>
+                        <Blob>
+                          <Name>2025/a&amp;b.log</Name>
+                          <Properties>
+                            <Creation-Time>Mon, 06 Jan 2025 09:00:00 GMT</Creation-Time>
+                            <Last-Modified>Mon, 06 Jan 2025 10:00:00 GMT</Last-Modified>
+                            <Etag>0x1</Etag>
+                            <Content-Length>12</Content-Length>
+                            <Content-Type>text/plain</Content-Type>
+                            <BlobType>BlockBlob</BlobType>
+                          </Properties>
+                        </Blob>
+                      </Blobs>
+                      <NextMarker>page3</NextMarker>
+                    </EnumerationResults>
+                "#},
+                "application/xml",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "list_blobs",
+                json!({"container": "logs", "prefix": "2025/", "marker": "page2", "limit": 2}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            parse(result),
+            json!({
+                "blobs": [{
+                    "name": "2025/a&b.log",
+                    "size": 12,
+                    "content_type": "text/plain",
+                    "last_modified": "Mon, 06 Jan 2025 10:00:00 GMT"
+                }],
+                "next_marker": "page3"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_blob() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1/docs/notes/readme.md"))
+            .and(header("x-ms-range", "bytes=0-10485760"))
+            .and(signed())
+            .respond_with(blob_response(200).set_body_raw("# Notes", "text/markdown"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1/docs/logo.png"))
+            .respond_with(blob_response(200).set_body_raw(vec![0x89, 0x50, 0xff], "image/png"))
+            .mount(&server)
+            .await;
+        // A server ignoring the range, whose body is still cut at the limit
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1/docs/huge.bin"))
+            .respond_with(blob_response(200).set_body_bytes(vec![0; MAX_BLOB_SIZE as usize + 1024]))
+            .mount(&server)
+            .await;
+        let router = router_for(&server);
+
+        let result = router
+            .call_tool(
+                "get_blob",
+                json!({"container": "docs", "blob": "notes/readme.md"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result[0].as_text().unwrap(), "# Notes");
+
+        let result = router
+            .call_tool("get_blob", json!({"container": "docs", "blob": "logo.png"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            parse(result),
+            json!({"content_type": "image/png", "size": 3, "encoding": "base64", "content": "iVD/"})
+        );
+
+        let result = router
+            .call_tool("get_blob", json!({"container": "docs", "blob": "huge.bin"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("generate_sas_url"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_blob() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/devstoreaccount1/docs/report.json"))
+            .and(header("x-ms-blob-type", "BlockBlob"))
+            .and(header("x-ms-blob-content-type", "application/json"))
+            .and(wiremock::matchers::body_string("{\"ok\": true}"))
+            .and(signed())
+            .respond_with(
+                storage_response(201)
+                    .insert_header("etag", "\"0x1\"")
+                    .insert_header("last-modified", "Mon, 06 Jan 2025 10:00:00 GMT")
+                    .insert_header("x-ms-request-server-encrypted", "true"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let router = router_for(&server);
+
+        let result = router
+            .call_tool(
+                "upload_blob",
+                json!({"container": "docs", "blob": "report.json", "content": "{\"ok\": true}"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(parse(result)["etag"], "\"0x1\"");
+
+        let result = router
+            .call_tool(
+                "upload_blob",
+                json!({"container": "docs", "blob": "report.json"}),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_copy_blob() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/devstoreaccount1/docs/old.txt"))
+            .and(header("x-ms-delete-snapshots", "include"))
+            .and(signed())
+            .respond_with(
+                storage_response(202).insert_header("x-ms-delete-type-permanent", "false"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/devstoreaccount1/archive/new.txt"))
+            .and(header(
+                "x-ms-copy-source",
+                format!("{}/devstoreaccount1/docs/old.txt", server.uri()).as_str(),
+            ))
+            .and(signed())
+            .respond_with(
+                storage_response(202)
+                    .insert_header("etag", "\"0x2\"")
+                    .insert_header("last-modified", "Mon, 06 Jan 2025 10:00:00 GMT")
+                    .insert_header("x-ms-copy-id", "8f1a7d4e-2c3b-4a5d-9e6f-0a1b2c3d4e5f")
+                    .insert_header("x-ms-copy-status", "success"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let router = router_for(&server);
+
+        let result = router
+            .call_tool(
+                "copy_blob",
+                json!({
+                    "source_container": "docs",
+                    "source_blob": "old.txt",
+                    "destination_container": "archive",
+                    "destination_blob": "new.txt"
+                }),
+            )
+            .await
+            .unwrap();
+        let copy = parse(result);
+        assert_eq!(copy["copy_id"], "8f1a7d4e-2c3b-4a5d-9e6f-0a1b2c3d4e5f");
+        assert_eq!(copy["copy_status"], "success");
+
+        let result = router
+            .call_tool(
+                "delete_blob",
+                json!({"container": "docs", "blob": "old.txt"}),
+            )
+            .await
+            .unwrap();
+        assert!(result[0]
+            .as_text()
+            .unwrap()
+            .contains("Deleted blob 'old.txt'"));
+
+        let delete = router
+            .list_tools()
+            .into_iter()
+            .find(|tool| tool.name == "delete_blob");
+        assert!(delete.unwrap().annotations.unwrap().destructive_hint);
+    }
+
+    #[tokio::test]
+    async fn test_get_blob_metadata() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/devstoreaccount1/docs/report.pdf"))
+            .and(signed())
+            .respond_with(
+                blob_response(200)
+                    .insert_header("content-length", "2048")
+                    .insert_header("content-type", "application/pdf")
+                    .insert_header("x-ms-meta-author", "ada"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/devstoreaccount1/docs/missing.pdf"))
+            .respond_with(storage_response(404).insert_header("x-ms-error-code", "BlobNotFound"))
+            .mount(&server)
+            .await;
+        let router = router_for(&server);
+
+        let metadata = parse(
+            router
+                .call_tool(
+                    "get_blob_metadata",
+                    json!({"container": "docs", "blob": "report.pdf"}),
+                )
+                .await
+                .unwrap(),
+        );
+        assert_eq!(metadata["size"], 2048);
+        assert_eq!(metadata["content_type"], "application/pdf");
+        assert_eq!(metadata["blob_type"], "BlockBlob");
+        assert_eq!(metadata["metadata"], json!({"author": "ada"}));
+
+        let result = router
+            .call_tool(
+                "get_blob_metadata",
+                json!({"container": "docs", "blob": "missing.pdf"}),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message == "Blob not found")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_sas_url() {
+        let router = AzureBlobRouter::with_account(StorageAccount::new("acme", "a2V5", None));
+        let result = router
+            .call_tool(
+                "generate_sas_url",
+                json!({"container": "docs", "blob": "report.pdf", "permissions": "wr", "expiry_hours": 2}),
+            )
+            .await
+            .unwrap();
+        let sas = parse(result);
+        assert_eq!(sas["permissions"], "rw");
+        let url = Url::parse(sas["url"].as_str().unwrap()).unwrap();
+        assert_eq!(url.host_str(), Some("acme.blob.core.windows.net"));
+        assert_eq!(url.path(), "/docs/report.pdf");
+        let query: serde_json::Map<String, Value> = url
+            .query_pairs()
+            .map(|(name, value)| (name.into_owned(), json!(value)))
+            .collect();
+        assert_eq!(query["sp"], "rw");
+        assert_eq!(query["sr"], "b");
+        assert_eq!(query["spr"], "https");
+        assert_eq!(query["se"], sas["expires"]);
+        assert!(query.contains_key("sig"));
+
+        for params in [
+            json!({"container": "docs", "blob": "report.pdf", "permissions": "x"}),
+            json!({"container": "docs", "blob": "report.pdf", "expiry_hours": 0}),
+            json!({"container": "docs", "blob": "report.pdf", "expiry_hours": i64::MAX}),
+        ] {
+            let result = router.call_tool("generate_sas_url", params).await;
+            assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1/missing"))
+            .respond_with(error_response(
+                404,
+                "ContainerNotFound",
+                "The specified container does not exist.",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/devstoreaccount1/private"))
+            .respond_with(error_response(
+                403,
+                "AuthenticationFailed",
+                "Signature mismatch\nRequestId:1",
+            ))
+            .mount(&server)
+            .await;
+        let router = router_for(&server);
+
+        let result = router
+            .call_tool("list_blobs", json!({"container": "missing"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message == "Container not found")
+        );
+        let result = router
+            .call_tool("list_blobs", json!({"container": "private"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("Signature mismatch") && !message.contains("RequestId"))
+        );
+
+        let router = AzureBlobRouter::with_account(Err("no credentials".into()));
+        let result = router.call_tool("list_containers", json!({})).await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("no credentials"))
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::Serialize;
+
+/// Elements that never belong to the body of an article
+const BOILERPLATE_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form", "iframe", "svg",
+    "button",
+];
+
+/// The main content of a web page, with the metadata needed to cite it
+#[derive(Debug, Clone, Serialize)]
+pub struct Article {
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    pub published_date: Option<String>,
+    pub body_markdown: String,
+    pub canonical_url: String,
+}
+
+/// Extract the article from an HTML page fetched from `url`
+///
+/// The body is taken from the first `<article>` or `[role="main"]` element, otherwise
+/// from the `<div>` holding the most paragraph text. When none of those are found the
+/// whole page is converted to Markdown.
+pub fn extract_article(html: &str, url: &str) -> Article {
+    let document = Html::parse_document(html);
+
+    let body = find_article_root(&document)
+        .or_else(|| first_match(&document, "body"))
+        .map(|root| to_markdown(root))
+        .unwrap_or_default();
+
+    Article {
+        title: meta_content(&document, r#"meta[property="og:title"]"#)
+            .or_else(|| first_text(&document, "title"))
+            .or_else(|| first_text(&document, "h1")),
+        byline: meta_content(&document, r#"meta[name="author"]"#)
+            .or_else(|| first_text(&document, r#"[rel="author"], [itemprop="author"], .byline"#)),
+        published_date: meta_content(&document, r#"meta[property="article:published_time"]"#)
+            .or_else(|| {
+                first_match(&document, "time[datetime]")
+                    .and_then(|time| time.value().attr("datetime"))
+                    .map(str::to_string)
+            })
+            .or_else(|| meta_content(&document, r#"meta[name="date"]"#)),
+        body_markdown: body,
+        canonical_url: first_match(&document, r#"link[rel="canonical"]"#)
+            .and_then(|link| link.value().attr("href"))
+            .map(str::to_string)
+            .or_else(|| meta_content(&document, r#"meta[property="og:url"]"#))
+            .unwrap_or_else(|| url.to_string()),
+    }
+}
+
+/// Convert the `<body>` of a whole page to Markdown, without navigation, footers, scripts
+/// and other boilerplate
+pub fn page_to_markdown(html: &str) -> String {
+    let document = Html::parse_document(html);
+    first_match(&document, "body")
+        .map(to_markdown)
+        .unwrap_or_default()
+}
+
+/// Metadata describing a web page, taken from its `<head>`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub og_image: Option<String>,
+    pub canonical: Option<String>,
+    pub favicon_url: Option<String>,
+}
+
+/// Extract the metadata of a page fetched from `url`
+///
+/// `html` may be just the start of the page, as long as it includes the `<head>`. Relative
+/// URLs are resolved against `url`, and the favicon defaults to `/favicon.ico` on its host.
+pub fn extract_page_metadata(html: &str, url: &str) -> PageMetadata {
+    let document = Html::parse_document(html);
+    let base = Url::parse(url).ok();
+    let resolve = |href: String| match &base {
+        Some(base) => base.join(&href).map(|url| url.to_string()).unwrap_or(href),
+        None => href,
+    };
+
+    PageMetadata {
+        title: first_text(&document, "title")
+            .or_else(|| meta_content(&document, r#"meta[property="og:title"]"#)),
+        description: meta_content(&document, r#"meta[name="description"]"#)
+            .or_else(|| meta_content(&document, r#"meta[property="og:description"]"#)),
+        og_image: meta_content(&document, r#"meta[property="og:image"]"#).map(resolve),
+        canonical: link_href(&document, r#"link[rel="canonical"]"#).map(resolve),
+        favicon_url: link_href(&document, r#"link[rel="icon"], link[rel="shortcut icon"]"#)
+            .map(resolve)
+            .or_else(|| {
+                base.as_ref()
+                    .filter(|base| base.has_host())
+                    .and_then(|base| base.join("/favicon.ico").ok())
+                    .map(|url| url.to_string())
+            }),
+    }
+}
+
+fn link_href(document: &Html, selector: &str) -> Option<String> {
+    first_match(document, selector)
+        .and_then(|link| link.value().attr("href"))
+        .map(|href| href.trim().to_string())
+        .filter(|href| !href.is_empty())
+}
+
+fn find_article_root(document: &Html) -> Option<ElementRef<'_>> {
+    first_match(document, "article")
+        .or_else(|| first_match(document, r#"[role="main"]"#))
+        .or_else(|| largest_div(document))
+}
+
+/// The div whose direct paragraphs hold the most text
+fn largest_div(document: &Html) -> Option<ElementRef<'_>> {
+    let divs = Selector::parse("div").unwrap();
+    document
+        .select(&divs)
+        .map(|div| {
+            let text_len: usize = div
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|child| child.value().name() == "p")
+                .map(|p| p.text().map(str::len).sum::<usize>())
+                .sum();
+            (div, text_len)
+        })
+        .filter(|(_, text_len)| *text_len > 0)
+        .max_by_key(|(_, text_len)| *text_len)
+        .map(|(div, _)| div)
+}
+
+fn first_match<'a>(document: &'a Html, selector: &str) -> Option<ElementRef<'a>> {
+    let selector = Selector::parse(selector).ok()?;
+    document.select(&selector).next()
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    first_match(document, selector)
+        .map(|element| collapse_whitespace(&element.text().collect::<String>()))
+        .filter(|text| !text.is_empty())
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    first_match(document, selector)
+        .and_then(|meta| meta.value().attr("content"))
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Convert an element to Markdown, dropping boilerplate such as navigation and scripts
+fn to_markdown(root: ElementRef) -> String {
+    let mut markdown = String::new();
+    write_children(root, &mut markdown);
+
+    // Collapse the blank lines left between blocks
+    let mut result = String::new();
+    let mut blank_lines = 0;
+    for line in markdown.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !result.is_empty() {
+            result.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        result.push_str(line);
+        blank_lines = 0;
+    }
+    result
+}
+
+fn write_children(element: ElementRef, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => {
+                // Whitespace between elements only matters if it separates words
+                let text = text.replace(['\n', '\t'], " ");
+                if !text.trim().is_empty() || !(out.is_empty() || out.ends_with([' ', '\n'])) {
+                    out.push_str(&text);
+                }
+            }
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    write_element(child, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn inline_text(element: ElementRef) -> String {
+    let mut text = String::new();
+    write_children(element, &mut text);
+    collapse_whitespace(&text)
+}
+
+fn write_element(element: ElementRef, out: &mut String) {
+    let name = element.value().name();
+    if BOILERPLATE_TAGS.contains(&name) {
+        return;
+    }
+
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse().unwrap_or(1);
+            out.push_str(&format!(
+                "\n\n{} {}\n\n",
+                "#".repeat(level),
+                inline_text(element)
+            ));
+        }
+        "p" => out.push_str(&format!("\n\n{}\n\n", inline_text(element))),
+        "br" => out.push('\n'),
+        "li" => out.push_str(&format!("\n- {}", inline_text(element))),
+        "ul" | "ol" => {
+            write_children(element, out);
+            out.push_str("\n\n");
+        }
+        "blockquote" => out.push_str(&format!("\n\n> {}\n\n", inline_text(element))),
+        "pre" => out.push_str(&format!(
+            "\n\n```\n{}\n```\n\n",
+            element.text().collect::<String>().trim_end()
+        )),
+        "code" => out.push_str(&format!("`{}`", element.text().collect::<String>())),
+        "strong" | "b" => out.push_str(&format!("**{}**", inline_text(element))),
+        "em" | "i" => out.push_str(&format!("*{}*", inline_text(element))),
+        "a" => {
+            let text = inline_text(element);
+            match element.value().attr("href") {
+                Some(href) if !text.is_empty() => out.push_str(&format!("[{}]({})", text, href)),
+                _ => out.push_str(&text),
+            }
+        }
+        "img" => {
+            if let Some(src) = element.value().attr("src") {
+                let alt = element.value().attr("alt").unwrap_or_default();
+                out.push_str(&format!("![{}]({})", alt, src));
+            }
+        }
+        _ => write_children(element, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEWS_PAGE: &str = r#"
+        <html>
+          <head>
+            <title>Rust 2.0 released | Example News</title>
+            <meta name="author" content="Ferris Crab">
+            <meta property="article:published_time" content="2025-01-15T09:00:00Z">
+            <link rel="canonical" href="https://news.example.com/rust-2">
+          </head>
+          <body>
+            <nav><a href="/">Home</a> <a href="/sports">Sports</a> <a href="/weather">Weather</a></nav>
+            <div class="ad">Buy one get one free!</div>
+            <article>
+              <h1>Rust 2.0 released</h1>
+              <p>The Rust team announced a <strong>major</strong> release today.</p>
+              <ul><li>Faster builds</li><li>Better errors</li></ul>
+              <p>Read the <a href="https://blog.example.com">release notes</a>.</p>
+              <script>trackPageView();</script>
+            </article>
+            <footer>Copyright Example News</footer>
+          </body>
+        </html>
+    "#;
+
+    #[test]
+    fn test_extract_article_element() {
+        let article = extract_article(NEWS_PAGE, "https://news.example.com/rust-2?ref=home");
+
+        assert_eq!(
+            article.title.as_deref(),
+            Some("Rust 2.0 released | Example News")
+        );
+        assert_eq!(article.byline.as_deref(), Some("Ferris Crab"));
+        assert_eq!(
+            article.published_date.as_deref(),
+            Some("2025-01-15T09:00:00Z")
+        );
+        assert_eq!(article.canonical_url, "https://news.example.com/rust-2");
+        assert_eq!(
+            article.body_markdown,
+            "# Rust 2.0 released\n\n\
+             The Rust team announced a **major** release today.\n\n\
+             - Faster builds\n\
+             - Better errors\n\n\
+             Read the [release notes](https://blog.example.com)."
+        );
+
+        for boilerplate in ["Sports", "Weather", "Buy one", "Copyright", "trackPageView"] {
+            assert!(
+                !article.body_markdown.contains(boilerplate),
+                "{boilerplate}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_without_article_falls_back_to_page() {
+        let html = r#"
+            <html><body>
+              <nav>Menu</nav>
+              <span>Just some text</span>
+            </body></html>
+        "#;
+        let article = extract_article(html, "https://example.com/page");
+
+        assert_eq!(article.title, None);
+        assert_eq!(article.canonical_url, "https://example.com/page");
+        assert_eq!(article.body_markdown, "Just some text");
+    }
+
+    #[test]
+    fn test_page_to_markdown() {
+        let html = r#"
+            <html><head><style>body { color: red; }</style></head><body>
+              <nav><a href="/">Home</a></nav>
+              <h1>Rust</h1>
+              <p>Rust is a <b>systems</b> programming language.</p>
+              <script>trackPageView();</script>
+              <footer>Copyright</footer>
+            </body></html>
+        "#;
+
+        assert_eq!(
+            page_to_markdown(html),
+            "# Rust\n\nRust is a **systems** programming language."
+        );
+    }
+
+    #[test]
+    fn test_extract_page_metadata() {
+        let html = r#"
+            <html><head>
+              <title>Example Domain</title>
+              <meta name="description" content="An example page for documentation.">
+              <meta property="og:title" content="Example">
+              <meta property="og:image" content="/images/preview.png">
+              <link rel="canonical" href="https://example.com/">
+              <link rel="icon" href="https://cdn.example.com/icon.svg">
+            </head>
+        "#;
+        let metadata = extract_page_metadata(html, "https://example.com/index.html?ref=1");
+
+        assert_eq!(
+            metadata,
+            PageMetadata {
+                title: Some("Example Domain".to_string()),
+                description: Some("An example page for documentation.".to_string()),
+                og_image: Some("https://example.com/images/preview.png".to_string()),
+                canonical: Some("https://example.com/".to_string()),
+                favicon_url: Some("https://cdn.example.com/icon.svg".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_extract_page_metadata_missing_fields() {
+        let html = "<html><head><meta charset=\"utf-8\"></head>";
+        let metadata = extract_page_metadata(html, "https://example.com/docs/page");
+
+        assert_eq!(
+            serde_json::to_value(&metadata).unwrap(),
+            serde_json::json!({
+                "title": null,
+                "description": null,
+                "og_image": null,
+                "canonical": null,
+                "favicon_url": "https://example.com/favicon.ico"
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_largest_div() {
+        let html = r#"
+            <html><body>
+              <div class="sidebar"><p>Related links</p></div>
+              <div class="content">
+                <p>First paragraph of the story.</p>
+                <p>Second paragraph of the story.</p>
+              </div>
+            </body></html>
+        "#;
+        let article = extract_article(html, "https://example.com/story");
+
+        assert_eq!(
+            article.body_markdown,
+            "First paragraph of the story.\n\nSecond paragraph of the story."
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use base64::engine::GeneralPurpose;
+use base64::prelude::{Engine as _, BASE64_STANDARD, BASE64_URL_SAFE, BASE64_URL_SAFE_NO_PAD};
+use mcp_core::{Content, ToolError};
+use serde_json::Value;
+use std::{fs, path::Path};
+
+use super::resolve_cache_path;
+
+/// Run a base64_tool operation, except decode_file, which saves through the router's cache
+pub fn base64_tool(
+    operation: &str,
+    params: &Value,
+    cache_dir: &Path,
+) -> Result<Vec<Content>, ToolError> {
+    let engine = engine(params);
+    let param = |name: &str| {
+        params
+            .get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters(format!("Missing '{}' parameter", name)))
+    };
+
+    match operation {
+        "encode" => Ok(vec![Content::text(engine.encode(param("data")?))]),
+        "decode" => {
+            let bytes = decode(engine, param("data")?)?;
+            Ok(vec![Content::text(describe_bytes(bytes))])
+        }
+        "encode_file" => {
+            let path = resolve_cache_path(param("path")?, cache_dir)?;
+            let bytes = fs::read(&path).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            Ok(vec![Content::text(engine.encode(bytes))])
+        }
+        "jwt_decode" => jwt_decode(param("data")?).map(|text| vec![Content::text(text)]),
+        _ => Err(ToolError::InvalidParameters(format!(
+            "Invalid operation: {}. Valid operations are: 'encode', 'decode', 'encode_file', 'decode_file', 'jwt_decode'",
+            operation
+        ))),
+    }
+}
+
+/// The bytes of the base64 `data` parameter, for decode_file
+pub fn decode_data(params: &Value) -> Result<Vec<u8>, ToolError> {
+    let data = params
+        .get("data")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'data' parameter".into()))?;
+    decode(engine(params), data)
+}
+
+fn engine(params: &Value) -> &'static GeneralPurpose {
+    if params
+        .get("url_safe")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        &BASE64_URL_SAFE
+    } else {
+        &BASE64_STANDARD
+    }
+}
+
+fn decode(engine: &GeneralPurpose, data: &str) -> Result<Vec<u8>, ToolError> {
+    engine
+        .decode(data.trim())
+        .map_err(|e| ToolError::InvalidParameters(format!("Invalid base64 data: {}", e)))
+}
+
+/// Show decoded bytes as text, pretty printing JSON, or as hex when they are not UTF-8
+fn describe_bytes(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => serde_json::from_str::<Value>(&text)
+            .ok()
+            .filter(|value| value.is_object() || value.is_array())
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+            .unwrap_or(text),
+        Err(e) => e
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+    }
+}
+
+/// Decode the header and payload of a JWT, without verifying its signature
+fn jwt_decode(token: &str) -> Result<String, ToolError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    if parts.len() != 3 {
+        return Err(ToolError::InvalidParameters(
+            "A JWT must have three dot separated parts".to_string(),
+        ));
+    }
+
+    let decode_part = |name: &str, part: &str| -> Result<Value, ToolError> {
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(part.trim_end_matches('='))
+            .map_err(|e| ToolError::InvalidParameters(format!("Invalid JWT {}: {}", name, e)))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| ToolError::InvalidParameters(format!("Invalid JWT {}: {}", name, e)))
+    };
+    let header = decode_part("header", parts[0])?;
+    let payload = decode_part("payload", parts[1])?;
+
+    Ok(format!(
+        "Header:\n{}\n\nPayload:\n{}\n\nThe signature was not verified.",
+        serde_json::to_string_pretty(&header).unwrap_or_default(),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn run(operation: &str, params: Value) -> String {
+        let cache_dir = TempDir::new().unwrap();
+        let result = base64_tool(operation, &params, cache_dir.path()).unwrap();
+        result[0].as_text().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_base64_round_trips() {
+        let data = "user:p@ss?>>";
+        let standard = run("encode", json!({"data": data}));
+        assert_eq!(standard, "dXNlcjpwQHNzPz4+");
+        assert_eq!(run("decode", json!({"data": standard})), data);
+
+        let url_safe = run("encode", json!({"data": data, "url_safe": true}));
+        assert_eq!(url_safe, "dXNlcjpwQHNzPz4-");
+        assert_eq!(
+            run("decode", json!({"data": url_safe, "url_safe": true})),
+            data
+        );
+    }
+
+    #[test]
+    fn test_decode_pretty_prints_json_and_falls_back_to_hex() {
+        let json_data = BASE64_STANDARD.encode(r#"{"event":"push","id":7}"#);
+        assert_eq!(
+            run("decode", json!({"data": json_data})),
+            "{\n  \"event\": \"push\",\n  \"id\": 7\n}"
+        );
+
+        let binary = BASE64_STANDARD.encode([0xff, 0x00, 0x9f]);
+        assert_eq!(run("decode", json!({"data": binary})), "ff009f");
+    }
+
+    #[test]
+    fn test_file_operations() {
+        let cache_dir = TempDir::new().unwrap();
+        fs::write(cache_dir.path().join("input.txt"), "hello").unwrap();
+
+        let encoded = base64_tool(
+            "encode_file",
+            &json!({"path": "input.txt"}),
+            cache_dir.path(),
+        )
+        .unwrap();
+        assert_eq!(encoded[0].as_text().unwrap(), "aGVsbG8=");
+
+        assert_eq!(decode_data(&json!({"data": "aGVsbG8="})).unwrap(), b"hello");
+        assert_eq!(
+            decode_data(&json!({"data": "_-8=", "url_safe": true})).unwrap(),
+            [0xff, 0xef]
+        );
+        assert!(matches!(
+            decode_data(&json!({})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_jwt_decode() {
+        let token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+                     eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+                     SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+        assert_eq!(
+            run("jwt_decode", json!({"data": token})),
+            "Header:\n\
+             {\n  \"alg\": \"HS256\",\n  \"typ\": \"JWT\"\n}\n\n\
+             Payload:\n\
+             {\n  \"iat\": 1516239022,\n  \"name\": \"John Doe\",\n  \"sub\": \"1234567890\"\n}\n\n\
+             The signature was not verified."
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use std::path::{Path, PathBuf};
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use mcp_core::{Content, ToolError};
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use super::platform::CommandRunner;
+
+const DEFAULT_VIEWPORT_WIDTH: u32 = 1280;
+const DEFAULT_VIEWPORT_HEIGHT: u32 = 800;
+
+/// Time given to the page to render when waiting for a selector, doubled on each attempt
+const INITIAL_TIME_BUDGET_MS: u64 = 1000;
+const MAX_TIME_BUDGET_MS: u64 = 16000;
+
+/// Find the Chrome or Chromium binary, from GOOSE_CHROME_PATH or the standard install
+/// locations of the current `os`
+pub fn find_browser(configured: Option<PathBuf>, os: &str) -> Option<PathBuf> {
+    if configured.is_some() {
+        return configured;
+    }
+    let candidates: &[&str] = match os {
+        "macos" => &[
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+            "/Applications/Chromium.app/Contents/MacOS/Chromium",
+        ],
+        "windows" => &[
+            r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+            r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+        ],
+        _ => &[
+            "/usr/bin/chromium",
+            "/usr/bin/chromium-browser",
+            "/usr/bin/google-chrome",
+            "/usr/bin/google-chrome-stable",
+            "/snap/bin/chromium",
+        ],
+    };
+    candidates
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+/// Take a screenshot of a web page with headless Chrome
+///
+/// When `wait_for_selector` is given, the page is rendered with growing time budgets until
+/// an element matches it, and the screenshot is taken with the same budget.
+pub fn browser_screenshot(
+    runner: &dyn CommandRunner,
+    browser: &Path,
+    params: &Value,
+) -> Result<Vec<Content>, ToolError> {
+    let url = params
+        .get("url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'url' parameter".into()))?;
+    let parsed = url::Url::parse(url)
+        .map_err(|e| ToolError::InvalidParameters(format!("Invalid url '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https" | "file") {
+        return Err(ToolError::InvalidParameters(format!(
+            "Unsupported url scheme '{}', use http, https or file",
+            parsed.scheme()
+        )));
+    }
+
+    let dimension = |name: &str, default: u32| match params.get(name) {
+        None | Some(Value::Null) => Ok(default),
+        Some(value) => value
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .filter(|value| *value > 0)
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!("'{}' must be a positive integer", name))
+            }),
+    };
+    let width = dimension("viewport_width", DEFAULT_VIEWPORT_WIDTH)?;
+    let height = dimension("viewport_height", DEFAULT_VIEWPORT_HEIGHT)?;
+
+    let time_budget = match params.get("wait_for_selector").and_then(|v| v.as_str()) {
+        Some(selector) => Some(wait_for_selector(runner, browser, url, selector)?),
+        None => None,
+    };
+
+    let output_dir = tempfile::tempdir().map_err(|e| {
+        ToolError::ExecutionError(format!("Failed to create temporary directory: {}", e))
+    })?;
+    let screenshot_path = output_dir.path().join("screenshot.png");
+
+    let mut args = headless_args();
+    args.push(format!("--screenshot={}", screenshot_path.display()));
+    args.push(format!("--window-size={},{}", width, height));
+    if let Some(time_budget) = time_budget {
+        args.push(format!("--virtual-time-budget={}", time_budget));
+    }
+    args.push(url.to_string());
+    run_browser(runner, browser, &args)?;
+
+    let png = std::fs::read(&screenshot_path).map_err(|e| {
+        ToolError::ExecutionError(format!("The browser did not save a screenshot: {}", e))
+    })?;
+    Ok(vec![Content::image(
+        BASE64_STANDARD.encode(png),
+        "image/png",
+    )])
+}
+
+/// Render the page until an element matches `selector`, returning the time budget needed
+fn wait_for_selector(
+    runner: &dyn CommandRunner,
+    browser: &Path,
+    url: &str,
+    selector: &str,
+) -> Result<u64, ToolError> {
+    let parsed = Selector::parse(selector).map_err(|e| {
+        ToolError::InvalidParameters(format!("Invalid selector '{}': {}", selector, e))
+    })?;
+
+    let mut time_budget = INITIAL_TIME_BUDGET_MS;
+    loop {
+        let mut args = headless_args();
+        args.push("--dump-dom".to_string());
+        args.push(format!("--virtual-time-budget={}", time_budget));
+        args.push(url.to_string());
+        let dom = run_browser(runner, browser, &args)?;
+
+        let document = Html::parse_document(&String::from_utf8_lossy(&dom));
+        if document.select(&parsed).next().is_some() {
+            return Ok(time_budget);
+        }
+        if time_budget >= MAX_TIME_BUDGET_MS {
+            return Err(ToolError::ExecutionError(format!(
+                "No element matched '{}' after waiting {}ms",
+                selector, time_budget
+            )));
+        }
+        time_budget *= 2;
+    }
+}
+
+fn headless_args() -> Vec<String> {
+    ["--headless", "--disable-gpu", "--hide-scrollbars"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn run_browser(
+    runner: &dyn CommandRunner,
+    browser: &Path,
+    args: &[String],
+) -> Result<Vec<u8>, ToolError> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    runner
+        .run(&browser.to_string_lossy(), &args, None)
+        .map_err(|e| {
+            ToolError::ExecutionError(format!(
+                "Failed to run the browser at '{}': {}",
+                browser.display(),
+                e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Records the arguments it is run with, saves screenshots and prints a fixed DOM
+    struct MockRunner {
+        calls: Mutex<Vec<Vec<String>>>,
+        dom: &'static str,
+    }
+
+    impl MockRunner {
+        fn new(dom: &'static str) -> Self {
+            Self {
+                calls: Mutex::new(Vec::new()),
+                dom,
+            }
+        }
+
+        fn calls(&self) -> Vec<Vec<String>> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl CommandRunner for MockRunner {
+        fn run(
+            &self,
+            program: &str,
+            args: &[&str],
+            _input: Option<&[u8]>,
+        ) -> std::io::Result<Vec<u8>> {
+            assert_eq!(program, "/opt/chrome");
+            self.calls
+                .lock()
+                .unwrap()
+                .push(args.iter().map(|arg| arg.to_string()).collect());
+            if let Some(path) = args
+                .iter()
+                .find_map(|arg| arg.strip_prefix("--screenshot="))
+            {
+                std::fs::write(path, b"\x89PNG")?;
+            }
+            Ok(self.dom.as_bytes().to_vec())
+        }
+    }
+
+    #[test]
+    fn test_screenshot_flags() {
+        let runner = MockRunner::new("");
+        let params = json!({"url": "https://example.com", "viewport_width": 640});
+
+        let result = browser_screenshot(&runner, Path::new("/opt/chrome"), &params).unwrap();
+        let (data, mime_type) = result[0].as_image().unwrap();
+        assert_eq!(mime_type, "image/png");
+        assert_eq!(BASE64_STANDARD.decode(data).unwrap(), b"\x89PNG");
+
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 1);
+        let args = &calls[0];
+        assert_eq!(
+            args[..3],
+            ["--headless", "--disable-gpu", "--hide-scrollbars"]
+        );
+        assert!(args[3].starts_with("--screenshot=") && args[3].ends_with("screenshot.png"));
+        assert_eq!(args[4..], ["--window-size=640,800", "https://example.com"]);
+    }
+
+    #[test]
+    fn test_wait_for_selector() {
+        let runner = MockRunner::new("<html><body><div id=\"chart\"></div></body></html>");
+        let params = json!({"url": "https://example.com", "wait_for_selector": "#chart"});
+
+        browser_screenshot(&runner, Path::new("/opt/chrome"), &params).unwrap();
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            calls[0][3..],
+            [
+                "--dump-dom",
+                "--virtual-time-budget=1000",
+                "https://example.com"
+            ]
+        );
+        assert!(calls[1].contains(&"--virtual-time-budget=1000".to_string()));
+
+        // Gives up when the selector never matches
+        let runner = MockRunner::new("<html><body></body></html>");
+        let result = browser_screenshot(&runner, Path::new("/opt/chrome"), &params);
+        assert!(matches!(result, Err(ToolError::ExecutionError(_))));
+        let budgets: Vec<String> = runner.calls().iter().map(|args| args[4].clone()).collect();
+        assert_eq!(
+            budgets,
+            [
+                "--virtual-time-budget=1000",
+                "--virtual-time-budget=2000",
+                "--virtual-time-budget=4000",
+                "--virtual-time-budget=8000",
+                "--virtual-time-budget=16000"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let runner = MockRunner::new("");
+        for params in [
+            json!({}),
+            json!({"url": "not a url"}),
+            json!({"url": "ftp://example.com"}),
+            json!({"url": "https://example.com", "viewport_height": 0}),
+            json!({"url": "https://example.com", "wait_for_selector": "##"}),
+        ] {
+            assert!(matches!(
+                browser_screenshot(&runner, Path::new("/opt/chrome"), &params),
+                Err(ToolError::InvalidParameters(_))
+            ));
+        }
+        assert!(runner.calls().is_empty());
+
+        let configured = PathBuf::from("/custom/chrome");
+        assert_eq!(
+            find_browser(Some(configured.clone()), "linux"),
+            Some(configured)
+        );
+    }
+}
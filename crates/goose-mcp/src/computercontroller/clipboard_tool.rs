@@ -0,0 +1,410 @@
+use std::io::ErrorKind;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use mcp_core::{Content, ToolError};
+use serde_json::Value;
+
+use super::platform::CommandRunner;
+
+const WINDOWS_READ_IMAGE: &str = r#"Add-Type -AssemblyName System.Windows.Forms
+$image = [System.Windows.Forms.Clipboard]::GetImage()
+if ($image -eq $null) { throw "The clipboard does not contain an image" }
+$stream = New-Object System.IO.MemoryStream
+$image.Save($stream, [System.Drawing.Imaging.ImageFormat]::Png)
+[Convert]::ToBase64String($stream.ToArray())"#;
+
+/// Read or write the clipboard
+///
+/// The utilities for the current `os` are run through `commands`: pbpaste/pbcopy on macOS,
+/// PowerShell on Windows, and xclip or xsel on Linux, where `display` is the X display the
+/// clipboard belongs to.
+pub fn clipboard(
+    commands: &dyn CommandRunner,
+    os: &str,
+    display: Option<&str>,
+    operation: &str,
+    params: &Value,
+) -> Result<Vec<Content>, ToolError> {
+    if os == "linux" && display.is_none_or(str::is_empty) {
+        return Err(ToolError::ExecutionError(
+            "No X display is available (DISPLAY is not set), so the clipboard cannot be accessed"
+                .into(),
+        ));
+    }
+
+    match operation {
+        "read" => {
+            let read_image = params
+                .get("read_image")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if read_image {
+                read_image_png(commands, os).map(|data| vec![Content::image(data, "image/png")])
+            } else {
+                read_text(commands, os).map(|text| vec![Content::text(text)])
+            }
+        }
+        "write" => {
+            let content = params
+                .get("content")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| {
+                    ToolError::InvalidParameters("Missing 'content' parameter".into())
+                })?;
+            write_text(commands, os, content)?;
+            Ok(vec![Content::text(format!(
+                "Wrote {} characters to the clipboard",
+                content.chars().count()
+            ))])
+        }
+        _ => Err(ToolError::InvalidParameters(format!(
+            "Invalid operation: {}. Valid operations are: 'read', 'write'",
+            operation
+        ))),
+    }
+}
+
+fn read_text(commands: &dyn CommandRunner, os: &str) -> Result<String, ToolError> {
+    let output = match os {
+        "macos" => run(commands, "pbpaste", &[], None)?,
+        "windows" => run(
+            commands,
+            "powershell",
+            &[
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "Get-Clipboard -Raw",
+            ],
+            None,
+        )?,
+        "linux" => run_xclip_or_xsel(
+            commands,
+            &["-selection", "clipboard", "-o"],
+            &["--clipboard", "--output"],
+            None,
+        )?,
+        _ => return Err(unsupported(os)),
+    };
+    let text = String::from_utf8_lossy(&output).into_owned();
+    // PowerShell ends its output with a line break that is not part of the clipboard
+    Ok(match os {
+        "windows" => text.strip_suffix("\r\n").unwrap_or(&text).to_string(),
+        _ => text,
+    })
+}
+
+fn write_text(commands: &dyn CommandRunner, os: &str, content: &str) -> Result<(), ToolError> {
+    let input = Some(content.as_bytes());
+    match os {
+        "macos" => run(commands, "pbcopy", &[], input)?,
+        "windows" => run(
+            commands,
+            "powershell",
+            &[
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "[Console]::In.ReadToEnd() | Set-Clipboard",
+            ],
+            input,
+        )?,
+        "linux" => run_xclip_or_xsel(
+            commands,
+            &["-selection", "clipboard", "-i"],
+            &["--clipboard", "--input"],
+            input,
+        )?,
+        _ => return Err(unsupported(os)),
+    };
+    Ok(())
+}
+
+/// Read the image on the clipboard as base64 encoded PNG data
+fn read_image_png(commands: &dyn CommandRunner, os: &str) -> Result<String, ToolError> {
+    let png = match os {
+        "macos" => {
+            // AppleScript prints the data as «data PNGf89504E47...»
+            let output = run(
+                commands,
+                "osascript",
+                &["-e", "the clipboard as «class PNGf»"],
+                None,
+            )?;
+            let output = String::from_utf8_lossy(&output);
+            let hex = output
+                .trim()
+                .strip_prefix("«data PNGf")
+                .and_then(|data| data.strip_suffix('»'))
+                .ok_or_else(no_image)?;
+            decode_hex(hex).ok_or_else(no_image)?
+        }
+        "windows" => {
+            let output = run(
+                commands,
+                "powershell",
+                &[
+                    "-NoProfile",
+                    "-NonInteractive",
+                    "-Command",
+                    WINDOWS_READ_IMAGE,
+                ],
+                None,
+            )?;
+            return Ok(String::from_utf8_lossy(&output).trim().to_string());
+        }
+        "linux" => run(
+            commands,
+            "xclip",
+            &["-selection", "clipboard", "-t", "image/png", "-o"],
+            None,
+        )?,
+        _ => return Err(unsupported(os)),
+    };
+    if png.is_empty() {
+        return Err(no_image());
+    }
+    Ok(BASE64_STANDARD.encode(png))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => u8::from_str_radix(std::str::from_utf8(&[*high, *low]).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run xclip, falling back to xsel when xclip is not installed
+fn run_xclip_or_xsel(
+    commands: &dyn CommandRunner,
+    xclip_args: &[&str],
+    xsel_args: &[&str],
+    input: Option<&[u8]>,
+) -> Result<Vec<u8>, ToolError> {
+    match commands.run("xclip", xclip_args, input) {
+        Err(e) if e.kind() == ErrorKind::NotFound => run(commands, "xsel", xsel_args, input),
+        result => result.map_err(|e| command_error("xclip", e)),
+    }
+}
+
+fn run(
+    commands: &dyn CommandRunner,
+    program: &str,
+    args: &[&str],
+    input: Option<&[u8]>,
+) -> Result<Vec<u8>, ToolError> {
+    commands
+        .run(program, args, input)
+        .map_err(|e| command_error(program, e))
+}
+
+fn command_error(program: &str, error: std::io::Error) -> ToolError {
+    if error.kind() != ErrorKind::NotFound {
+        return ToolError::ExecutionError(format!("{} failed: {}", program, error));
+    }
+    let install = match program {
+        "xclip" | "xsel" => {
+            "Install xclip or xsel with your package manager, e.g. `sudo apt install xclip`"
+        }
+        "powershell" => "Install PowerShell from https://aka.ms/powershell",
+        _ => "It is part of macOS and should be available on the PATH",
+    };
+    ToolError::ExecutionError(format!(
+        "The clipboard utility '{}' was not found. {}.",
+        program, install
+    ))
+}
+
+fn no_image() -> ToolError {
+    ToolError::ExecutionError("The clipboard does not contain an image".into())
+}
+
+fn unsupported(os: &str) -> ToolError {
+    ToolError::ExecutionError(format!("The clipboard is not supported on {}", os))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    type Call = (String, Vec<String>, Option<String>);
+
+    /// Records the commands it is asked to run, and prints a fixed output. Programs not
+    /// in `installed` are not found.
+    struct MockRunner {
+        calls: Mutex<Vec<Call>>,
+        installed: &'static [&'static str],
+        output: &'static [u8],
+    }
+
+    impl MockRunner {
+        fn new(installed: &'static [&'static str], output: &'static [u8]) -> Self {
+            Self {
+                calls: Mutex::new(Vec::new()),
+                installed,
+                output,
+            }
+        }
+
+        fn calls(&self) -> Vec<Call> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl CommandRunner for MockRunner {
+        fn run(
+            &self,
+            program: &str,
+            args: &[&str],
+            input: Option<&[u8]>,
+        ) -> std::io::Result<Vec<u8>> {
+            self.calls.lock().unwrap().push((
+                program.to_string(),
+                args.iter().map(|arg| arg.to_string()).collect(),
+                input.map(|input| String::from_utf8_lossy(input).into_owned()),
+            ));
+            if !self.installed.contains(&program) {
+                return Err(std::io::Error::from(ErrorKind::NotFound));
+            }
+            Ok(self.output.to_vec())
+        }
+    }
+
+    #[test]
+    fn test_read_and_write_on_macos() {
+        let commands = MockRunner::new(&["pbpaste", "pbcopy"], b"copied text");
+
+        let result = clipboard(&commands, "macos", None, "read", &json!({})).unwrap();
+        assert_eq!(result[0].as_text(), Some("copied text"));
+
+        let result = clipboard(
+            &commands,
+            "macos",
+            None,
+            "write",
+            &json!({"content": "héllo"}),
+        )
+        .unwrap();
+        assert_eq!(
+            result[0].as_text(),
+            Some("Wrote 5 characters to the clipboard")
+        );
+
+        assert_eq!(
+            commands.calls(),
+            vec![
+                ("pbpaste".to_string(), vec![], None),
+                ("pbcopy".to_string(), vec![], Some("héllo".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_and_write_on_windows() {
+        let commands = MockRunner::new(&["powershell"], b"line one\r\nline two\r\n");
+
+        let result = clipboard(&commands, "windows", None, "read", &json!({})).unwrap();
+        assert_eq!(result[0].as_text(), Some("line one\r\nline two"));
+        clipboard(
+            &commands,
+            "windows",
+            None,
+            "write",
+            &json!({"content": "x"}),
+        )
+        .unwrap();
+
+        let calls = commands.calls();
+        assert_eq!(calls[0].0, "powershell");
+        assert_eq!(calls[0].1.last().unwrap(), "Get-Clipboard -Raw");
+        assert_eq!(
+            calls[1].1.last().unwrap(),
+            "[Console]::In.ReadToEnd() | Set-Clipboard"
+        );
+        assert_eq!(calls[1].2.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn test_linux_falls_back_to_xsel() {
+        let commands = MockRunner::new(&["xsel"], b"from xsel");
+
+        let result = clipboard(&commands, "linux", Some(":0"), "read", &json!({})).unwrap();
+        assert_eq!(result[0].as_text(), Some("from xsel"));
+
+        let calls = commands.calls();
+        assert_eq!(calls[0].0, "xclip");
+        assert_eq!(calls[0].1, vec!["-selection", "clipboard", "-o"]);
+        assert_eq!(calls[1].0, "xsel");
+        assert_eq!(calls[1].1, vec!["--clipboard", "--output"]);
+    }
+
+    #[test]
+    fn test_linux_requires_display_and_utilities() {
+        let commands = MockRunner::new(&[], b"");
+
+        let result = clipboard(&commands, "linux", None, "read", &json!({}));
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("DISPLAY"))
+        );
+        assert!(commands.calls().is_empty());
+
+        let result = clipboard(&commands, "linux", Some(":0"), "read", &json!({}));
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("sudo apt install xclip"))
+        );
+    }
+
+    #[test]
+    fn test_read_image() {
+        let commands = MockRunner::new(&["osascript"], "«data PNGf89504E47»\n".as_bytes());
+        let result = clipboard(
+            &commands,
+            "macos",
+            None,
+            "read",
+            &json!({"read_image": true}),
+        )
+        .unwrap();
+        let image = result[0].as_image().unwrap();
+        assert_eq!(image.1, "image/png");
+        assert_eq!(
+            BASE64_STANDARD.decode(image.0).unwrap(),
+            [0x89, 0x50, 0x4E, 0x47]
+        );
+
+        let commands = MockRunner::new(&["xclip"], b"\x89PNG");
+        let result = clipboard(
+            &commands,
+            "linux",
+            Some(":0"),
+            "read",
+            &json!({"read_image": true}),
+        )
+        .unwrap();
+        assert_eq!(
+            result[0].as_image().unwrap().0,
+            BASE64_STANDARD.encode(b"\x89PNG")
+        );
+        assert_eq!(
+            commands.calls()[0].1,
+            vec!["-selection", "clipboard", "-t", "image/png", "-o"]
+        );
+
+        let commands = MockRunner::new(&["osascript"], b"");
+        assert!(matches!(
+            clipboard(
+                &commands,
+                "macos",
+                None,
+                "read",
+                &json!({"read_image": true})
+            ),
+            Err(ToolError::ExecutionError(_))
+        ));
+    }
+}
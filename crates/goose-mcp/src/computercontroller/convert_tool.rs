@@ -0,0 +1,334 @@
+use mcp_core::{Content, ToolError};
+use serde_json::{Map, Number, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::resolve_cache_path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn from_param(params: &Value, name: &str) -> Result<Self, ToolError> {
+        match params.get(name).and_then(|v| v.as_str()) {
+            Some("json") => Ok(Format::Json),
+            Some("yaml") => Ok(Format::Yaml),
+            Some("toml") => Ok(Format::Toml),
+            Some(other) => Err(ToolError::InvalidParameters(format!(
+                "Invalid '{}' format: {}. Valid formats are: 'json', 'yaml', 'toml'",
+                name, other
+            ))),
+            None => Err(ToolError::InvalidParameters(format!(
+                "Missing '{}' parameter",
+                name
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+        }
+    }
+}
+
+/// Convert a document between JSON, YAML and TOML
+///
+/// The input is either the `input` string or the cache file `from_file`. The result is
+/// returned, or written to the cache file `to_file` when given. TOML datetimes become
+/// strings in the other formats, and strings holding a valid TOML datetime become
+/// datetimes again when converting to TOML.
+pub fn data_convert(params: &Value, cache_dir: &Path) -> Result<Vec<Content>, ToolError> {
+    let from = Format::from_param(params, "from")?;
+    let to = Format::from_param(params, "to")?;
+    let pretty = params
+        .get("pretty")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
+    let input = match (
+        params.get("input").and_then(|v| v.as_str()),
+        params.get("from_file").and_then(|v| v.as_str()),
+    ) {
+        (Some(input), None) => input.to_string(),
+        (None, Some(path)) => {
+            let path = resolve_cache_path(path, cache_dir)?;
+            fs::read_to_string(&path).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to read {}: {}", path.display(), e))
+            })?
+        }
+        _ => {
+            return Err(ToolError::InvalidParameters(
+                "Exactly one of 'input' or 'from_file' must be provided".into(),
+            ))
+        }
+    };
+
+    let output = serialize(&parse(&input, from)?, to, pretty)?;
+
+    match params.get("to_file").and_then(|v| v.as_str()) {
+        Some(path) => {
+            let path = output_path(path, cache_dir)?;
+            fs::write(&path, &output).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to write to cache: {}", e))
+            })?;
+            Ok(vec![Content::text(format!(
+                "Converted {} to {} and saved to: {}",
+                from.name(),
+                to.name(),
+                path.display()
+            ))])
+        }
+        None => Ok(vec![Content::text(output)]),
+    }
+}
+
+/// Resolve a file to be created in the cache directory
+fn output_path(path: &str, cache_dir: &Path) -> Result<PathBuf, ToolError> {
+    let file_name = Path::new(path)
+        .file_name()
+        .ok_or_else(|| ToolError::InvalidParameters(format!("Invalid file name: {}", path)))?;
+    let parent = Path::new(path)
+        .parent()
+        .and_then(|parent| parent.to_str())
+        .filter(|parent| !parent.is_empty())
+        .unwrap_or(".");
+    Ok(resolve_cache_path(parent, cache_dir)?.join(file_name))
+}
+
+fn parse(input: &str, format: Format) -> Result<Value, ToolError> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        ToolError::InvalidParameters(format!("Invalid {} input: {}", format.name(), e))
+    };
+    match format {
+        Format::Json => serde_json::from_str(input).map_err(|e| invalid(&e)),
+        Format::Yaml => serde_yaml::from_str(input).map_err(|e| invalid(&e)),
+        Format::Toml => input
+            .parse::<toml::Table>()
+            .map(|table| from_toml(toml::Value::Table(table)))
+            .map_err(|e| invalid(&e)),
+    }
+}
+
+fn serialize(value: &Value, format: Format, pretty: bool) -> Result<String, ToolError> {
+    let failed = |e: &dyn std::fmt::Display| {
+        ToolError::ExecutionError(format!("Failed to convert to {}: {}", format.name(), e))
+    };
+    match format {
+        Format::Json if pretty => serde_json::to_string_pretty(value).map_err(|e| failed(&e)),
+        Format::Json => serde_json::to_string(value).map_err(|e| failed(&e)),
+        Format::Yaml => serde_yaml::to_string(value).map_err(|e| failed(&e)),
+        Format::Toml => {
+            let toml::Value::Table(table) = to_toml(value, "$")? else {
+                return Err(ToolError::InvalidParameters(
+                    "TOML documents must be a table at the top level".into(),
+                ));
+            };
+            if pretty {
+                toml::to_string_pretty(&table).map_err(|e| failed(&e))
+            } else {
+                toml::to_string(&table).map_err(|e| failed(&e))
+            }
+        }
+    }
+}
+
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Number::from_f64(f)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(f.to_string())),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, from_toml(value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+/// Convert to a TOML value, reporting values TOML cannot represent by their JSON path
+fn to_toml(value: &Value, path: &str) -> Result<toml::Value, ToolError> {
+    let unsupported = |what: &str| {
+        ToolError::InvalidParameters(format!("TOML does not support {} (at {})", what, path))
+    };
+    Ok(match value {
+        Value::Null => return Err(unsupported("null values")),
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => toml::Value::Integer(i),
+            None if n.is_u64() => return Err(unsupported("integers larger than i64")),
+            None => toml::Value::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => match s.parse::<toml::value::Datetime>() {
+            Ok(datetime) => toml::Value::Datetime(datetime),
+            Err(_) => toml::Value::String(s.clone()),
+        },
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| to_toml(item, &format!("{}[{}]", path, index)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => toml::Value::Table(
+            map.iter()
+                .map(|(key, value)| {
+                    Ok((key.clone(), to_toml(value, &format!("{}.{}", path, key))?))
+                })
+                .collect::<Result<_, ToolError>>()?,
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const TOML_DOCUMENT: &str = r#"title = "Release config"
+released = 1979-05-27T07:32:00Z
+version = 2
+ratio = 0.5
+whole_float = 3.0
+enabled = true
+tags = ["alpha", "beta"]
+
+[owner]
+name = "Ops"
+contacts = [1, 2.5, "three"]
+
+[[servers]]
+host = "a.example.com"
+port = 8080
+
+[[servers]]
+host = "b.example.com"
+port = 8081
+weights = [[1, 2], [3, 4]]
+"#;
+
+    fn format(name: &str) -> Format {
+        Format::from_param(&json!({"format": name}), "format").unwrap()
+    }
+
+    fn convert(input: &str, from: &str, to: &str) -> String {
+        let cache_dir = TempDir::new().unwrap();
+        let result = data_convert(
+            &json!({"input": input, "from": from, "to": to}),
+            cache_dir.path(),
+        )
+        .unwrap();
+        result[0].as_text().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_round_trips_between_all_formats() {
+        let expected = parse(TOML_DOCUMENT, Format::Toml).unwrap();
+        assert_eq!(expected["released"], json!("1979-05-27T07:32:00Z"));
+        assert_eq!(expected["whole_float"], json!(3.0));
+        assert!(expected["whole_float"].is_f64());
+        assert!(expected["version"].is_i64());
+
+        for (from, to) in [
+            ("toml", "json"),
+            ("toml", "yaml"),
+            ("json", "yaml"),
+            ("json", "toml"),
+            ("yaml", "json"),
+            ("yaml", "toml"),
+        ] {
+            let source = match from {
+                "toml" => TOML_DOCUMENT.to_string(),
+                _ => convert(TOML_DOCUMENT, "toml", from),
+            };
+            let converted = convert(&source, from, to);
+            let back = convert(&converted, to, from);
+            assert_eq!(
+                parse(&back, format(from)).unwrap(),
+                expected,
+                "{} -> {} -> {}",
+                from,
+                to,
+                from
+            );
+        }
+
+        // Datetimes come back as TOML datetimes, not strings
+        let toml = convert(&convert(TOML_DOCUMENT, "toml", "yaml"), "yaml", "toml");
+        assert!(toml.contains("released = 1979-05-27T07:32:00Z\n"));
+        assert!(toml.contains("whole_float = 3.0\n"));
+    }
+
+    #[test]
+    fn test_malformed_input_reports_location() {
+        let cache_dir = TempDir::new().unwrap();
+        let result = data_convert(
+            &json!({"input": "{\n  \"a\": 1,\n  \"b\": \n}", "from": "json", "to": "yaml"}),
+            cache_dir.path(),
+        );
+        let Err(ToolError::InvalidParameters(message)) = result else {
+            panic!("expected invalid parameters, got {:?}", result);
+        };
+        assert!(message.starts_with("Invalid JSON input:"));
+        assert!(message.contains("line 4 column 1"), "{}", message);
+
+        let result = data_convert(
+            &json!({"input": "{\"a\": null}", "from": "json", "to": "toml"}),
+            cache_dir.path(),
+        );
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message.contains("$.a"))
+        );
+    }
+
+    #[test]
+    fn test_cache_files() {
+        let cache_dir = TempDir::new().unwrap();
+        fs::write(
+            cache_dir.path().join("config.yaml"),
+            "name: goose\nport: 8080\n",
+        )
+        .unwrap();
+
+        let result = data_convert(
+            &json!({
+                "from_file": "config.yaml",
+                "from": "yaml",
+                "to": "json",
+                "to_file": "config.json",
+                "pretty": false,
+            }),
+            cache_dir.path(),
+        )
+        .unwrap();
+        assert!(result[0]
+            .as_text()
+            .unwrap()
+            .starts_with("Converted YAML to JSON and saved to: "));
+        assert_eq!(
+            fs::read_to_string(cache_dir.path().join("config.json")).unwrap(),
+            r#"{"name":"goose","port":8080}"#
+        );
+
+        let result = data_convert(
+            &json!({"input": "{}", "from": "json", "to": "yaml", "to_file": "../escape.yaml"}),
+            cache_dir.path(),
+        );
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+}
@@ -0,0 +1,228 @@
+use mcp_core::{Content, ToolError};
+use serde_json::{Map, Value};
+use similar::TextDiff;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::resolve_cache_path;
+
+/// Maximum number of lines of diff output returned
+const MAX_DIFF_LINES: usize = 500;
+
+/// Structural differences between two JSON documents, as JSON paths
+#[derive(Debug, Default)]
+struct JsonChanges {
+    added: Vec<String>,
+    removed: Vec<String>,
+    changed: Vec<String>,
+}
+
+/// Diff two files in the cache directory
+///
+/// Returns a unified patch. When both files are valid JSON they are compared semantically
+/// (sorted keys, ignoring formatting) and a summary of the added, removed and changed
+/// values is returned as well.
+pub fn diff_cached_files(
+    path_a: &str,
+    path_b: &str,
+    cache_dir: &Path,
+) -> Result<Vec<Content>, ToolError> {
+    let file_a = resolve_cache_path(path_a, cache_dir)?;
+    let file_b = resolve_cache_path(path_b, cache_dir)?;
+    let read = |path: &PathBuf| {
+        fs::read_to_string(path).map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to read {}: {}", path.display(), e))
+        })
+    };
+    let (text_a, text_b) = (read(&file_a)?, read(&file_b)?);
+
+    let json = serde_json::from_str::<Value>(&text_a)
+        .ok()
+        .zip(serde_json::from_str::<Value>(&text_b).ok());
+
+    let (old, new) = match &json {
+        Some((a, b)) => (pretty_sorted(a), pretty_sorted(b)),
+        None => (text_a, text_b),
+    };
+    let patch = unified_diff(&old, &new, path_a, path_b);
+
+    let mut contents = vec![Content::text(patch)];
+    if let Some((a, b)) = json {
+        let mut changes = JsonChanges::default();
+        compare_json("$", &a, &b, &mut changes);
+        contents.push(Content::text(format_json_changes(&changes)));
+    }
+    Ok(contents)
+}
+
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), sort_keys(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sort_keys).collect()),
+        _ => value.clone(),
+    }
+}
+
+fn pretty_sorted(value: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(&sort_keys(value)).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let patch = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(old_name, new_name)
+        .to_string();
+    if patch.is_empty() {
+        return "Files are identical".to_string();
+    }
+
+    let total = patch.lines().count();
+    if total <= MAX_DIFF_LINES {
+        return patch;
+    }
+    let mut truncated: String = patch
+        .lines()
+        .take(MAX_DIFF_LINES)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    truncated.push_str(&format!(
+        "... diff truncated, {} more lines\n",
+        total - MAX_DIFF_LINES
+    ));
+    truncated
+}
+
+fn compare_json(path: &str, old: &Value, new: &Value, changes: &mut JsonChanges) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let child = format!("{}.{}", path, key);
+                match new.get(key) {
+                    Some(new_value) => compare_json(&child, old_value, new_value, changes),
+                    None => changes.removed.push(child),
+                }
+            }
+            for key in new.keys().filter(|key| !old.contains_key(*key)) {
+                changes.added.push(format!("{}.{}", path, key));
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (index, old_value) in old.iter().enumerate() {
+                let child = format!("{}[{}]", path, index);
+                match new.get(index) {
+                    Some(new_value) => compare_json(&child, old_value, new_value, changes),
+                    None => changes.removed.push(child),
+                }
+            }
+            for index in old.len()..new.len() {
+                changes.added.push(format!("{}[{}]", path, index));
+            }
+        }
+        _ if old != new => changes.changed.push(path.to_string()),
+        _ => {}
+    }
+}
+
+fn format_json_changes(changes: &JsonChanges) -> String {
+    let mut text = format!(
+        "{{added: {}, removed: {}, changed: {}}}",
+        changes.added.len(),
+        changes.removed.len(),
+        changes.changed.len()
+    );
+    for (marker, paths) in [
+        ('+', &changes.added),
+        ('-', &changes.removed),
+        ('~', &changes.changed),
+    ] {
+        for path in paths {
+            text.push_str(&format!("\n{} {}", marker, path));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_cached_json_files() {
+        let cache_dir = TempDir::new().unwrap();
+        fs::write(
+            cache_dir.path().join("web_1.json"),
+            r#"{"status": "ok", "count": 2}"#,
+        )
+        .unwrap();
+        fs::write(
+            cache_dir.path().join("web_2.json"),
+            r#"{
+                "count": 2,
+                "status": "error",
+                "message": "rate limited"
+            }"#,
+        )
+        .unwrap();
+
+        let result = diff_cached_files("web_1.json", "web_2.json", cache_dir.path()).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "--- web_1.json\n\
+             +++ web_2.json\n\
+             @@ -1,4 +1,5 @@\n \
+             {\n   \
+             \"count\": 2,\n\
+             -  \"status\": \"ok\"\n\
+             +  \"message\": \"rate limited\",\n\
+             +  \"status\": \"error\"\n \
+             }\n"
+        );
+        assert_eq!(
+            result[1].as_text().unwrap(),
+            "{added: 1, removed: 0, changed: 1}\n+ $.message\n~ $.status"
+        );
+    }
+
+    #[test]
+    fn test_diff_cached_text_files() {
+        let cache_dir = TempDir::new().unwrap();
+        fs::write(cache_dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        fs::write(cache_dir.path().join("b.txt"), "one\nthree\n").unwrap();
+
+        let result = diff_cached_files("a.txt", "b.txt", cache_dir.path()).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "--- a.txt\n+++ b.txt\n@@ -1,2 +1,2 @@\n one\n-two\n+three\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_rejects_paths_outside_cache() {
+        let cache_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let outside_file = outside.path().join("secret.txt");
+        fs::write(cache_dir.path().join("a.txt"), "one\n").unwrap();
+        fs::write(&outside_file, "two\n").unwrap();
+
+        let result = diff_cached_files("a.txt", outside_file.to_str().unwrap(), cache_dir.path());
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+        let result = diff_cached_files("../a.txt", "a.txt", cache_dir.path());
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+}
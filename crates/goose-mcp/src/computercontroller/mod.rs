@@ -4,7 +4,16 @@ use indoc::{formatdoc, indoc};
 use reqwest::{Client, Url};
 use serde_json::{json, Value};
 use std::{
-    collections::HashMap, fs, future::Future, path::PathBuf, pin::Pin, sync::Arc, sync::Mutex,
+    collections::hash_map::DefaultHasher,
+    collections::HashMap,
+    fs,
+    future::Future,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    sync::Mutex,
+    time::Duration,
 };
 use tokio::process::Command;
 
@@ -19,13 +28,47 @@ use mcp_core::{
 use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
+mod article_tool;
+mod base64_tool;
+mod browser_tool;
+mod clipboard_tool;
+mod convert_tool;
+mod diff_tool;
 mod docx_tool;
 mod pdf_tool;
 mod presentation_tool;
+mod regex_tool;
+mod table_tool;
+mod url_tool;
+mod web_cache;
+mod web_headers;
+mod webcam_tool;
+mod window_tool;
 mod xlsx_tool;
 
 mod platform;
-use platform::{create_system_automation, SystemAutomation};
+use platform::{create_system_automation, SystemAutomation, SystemCommandRunner};
+
+/// How much of a page url_metadata reads while looking for the end of its head
+const METADATA_READ_LIMIT: usize = 64 * 1024;
+
+/// How many characters of a page web_scrape shows when saving it as Markdown
+const MARKDOWN_PREVIEW_CHARS: usize = 500;
+
+/// Resolve a path relative to the cache directory, rejecting anything outside of it
+pub(crate) fn resolve_cache_path(path: &str, cache_dir: &Path) -> Result<PathBuf, ToolError> {
+    let outside =
+        || ToolError::InvalidParameters(format!("{} is not within the cache directory", path));
+    let cache_dir = cache_dir.canonicalize().map_err(|_| outside())?;
+    let resolved = cache_dir
+        .join(path)
+        .canonicalize()
+        .map_err(|e| ToolError::InvalidParameters(format!("Cannot access {}: {}", path, e)))?;
+    if !resolved.starts_with(&cache_dir) {
+        return Err(outside());
+    }
+    Ok(resolved)
+}
 
 /// An extension designed for non-developers to help them with common tasks like
 /// web scraping, data processing, and automation.
@@ -54,9 +97,12 @@ impl ComputerControllerRouter {
                 - text (for HTML pages)
                 - json (for API responses)
                 - binary (for images and other files)
+                - tables (the HTML tables of the page, returned as CSV)
+                - markdown (HTML pages without navigation, scripts and styles)
 
                 The content is cached locally and can be accessed later using the cache_path
-                returned in the response.
+                returned in the response. With cache_ttl_seconds, a copy of the same URL fetched
+                within that many seconds is returned instead of fetching the URL again.
             "#},
             json!({
                 "type": "object",
@@ -68,9 +114,18 @@ impl ComputerControllerRouter {
                     },
                     "save_as": {
                         "type": "string",
-                        "enum": ["text", "json", "binary"],
+                        "enum": ["text", "json", "binary", "tables", "markdown"],
                         "default": "text",
                         "description": "How to interpret and save the content"
+                    },
+                    "headers": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "HTTP headers to send with the request, such as Authorization or Accept"
+                    },
+                    "cache_ttl_seconds": {
+                        "type": "integer",
+                        "description": "Reuse a copy of the URL saved as the same type within this many seconds instead of fetching it again"
                     }
                 }
             }),
@@ -83,6 +138,63 @@ impl ComputerControllerRouter {
             }),
         );
 
+        let web_extract_article_tool = Tool::new(
+            "web_extract_article",
+            indoc! {r#"
+                Fetch a web page and extract just the main article, without navigation, ads and
+                other boilerplate. Best for news articles, blog posts and documentation pages.
+
+                Returns JSON with the title, byline, published_date, canonical_url and the
+                article body as markdown. If no article can be detected the whole page is
+                converted to markdown. The result is cached locally like web_scrape.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["url"],
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The URL of the page to extract the article from"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Extract web article".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: true,
+            }),
+        );
+
+        let url_metadata_tool = Tool::new(
+            "url_metadata",
+            indoc! {r#"
+                Get the title, description, preview image, canonical URL and favicon of a web page
+                without downloading the full page. Only the start of the page is read.
+
+                Returns JSON with title, description, og_image, canonical and favicon_url, using
+                null for anything the page does not declare. Results are cached per URL.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["url"],
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The URL of the page"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("URL metadata".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            }),
+        );
+
         let computer_control_desc = match std::env::consts::OS {
             "windows" => indoc! {r#"
                 Control the computer using Windows system automation.
@@ -227,13 +339,442 @@ impl ComputerControllerRouter {
             None,
         );
 
+        let diff_cached_files_tool = Tool::new(
+            "diff_cached_files",
+            indoc! {r#"
+                Compare two files in the cache directory, such as two saved API responses.
+
+                JSON files are compared semantically (sorted keys, formatting ignored) and a
+                summary of the added, removed and changed values is returned with the diff.
+                Other files are compared line by line. The diff is returned as a unified patch,
+                limited to 500 lines.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["path_a", "path_b"],
+                "properties": {
+                    "path_a": {
+                        "type": "string",
+                        "description": "Path of the original file, within the cache directory"
+                    },
+                    "path_b": {
+                        "type": "string",
+                        "description": "Path of the changed file, within the cache directory"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Diff cached files".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let base64_tool = Tool::new(
+            "base64_tool",
+            indoc! {r#"
+                Encode and decode base64, e.g. for API credentials, webhook payloads or JWTs.
+                Supports operations:
+                - encode: Encode the data string
+                - decode: Decode the data string. Text is returned as is (JSON is pretty printed),
+                  other binary data is returned as hex
+                - encode_file: Encode the contents of a file in the cache directory
+                - decode_file: Decode the data string and save the bytes to a new cache file
+                - jwt_decode: Decode the header and payload of a JWT given as data. The
+                  signature is NOT verified.
+
+                Set url_safe to use the URL-safe alphabet (- and _ instead of + and /).
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["encode", "decode", "encode_file", "decode_file", "jwt_decode"],
+                        "description": "The operation to perform"
+                    },
+                    "data": {
+                        "type": "string",
+                        "description": "The data to encode or decode, or the JWT for jwt_decode"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file to encode, within the cache directory (encode_file only)"
+                    },
+                    "url_safe": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Use the URL-safe base64 alphabet"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Base64 encode/decode".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let url_tool = Tool::new(
+            "url_tool",
+            indoc! {r#"
+                Encode, decode, parse and build URLs, rather than assembling them by hand.
+                Supports operations:
+                - encode: Percent-encode the text
+                - decode: Percent-decode the text
+                - parse: Break the url into scheme, host, port, path, query_params (decoded)
+                  and fragment
+                - build: Assemble a URL from scheme, host, port, path, query_params and
+                  fragment. Query parameter values are percent-encoded automatically.
+                - extract_query_param: Get the decoded value of the key query parameter in
+                  the url
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["encode", "decode", "parse", "build", "extract_query_param"],
+                        "description": "The operation to perform"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "The text to encode or decode"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "The URL to parse or extract a query parameter from"
+                    },
+                    "key": {
+                        "type": "string",
+                        "description": "The query parameter to extract"
+                    },
+                    "scheme": {
+                        "type": "string",
+                        "default": "https",
+                        "description": "The scheme of the URL to build"
+                    },
+                    "host": {
+                        "type": "string",
+                        "description": "The host of the URL to build"
+                    },
+                    "port": {
+                        "type": "integer",
+                        "description": "The port of the URL to build"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "The path of the URL to build"
+                    },
+                    "query_params": {
+                        "type": "object",
+                        "description": "The query parameters of the URL to build. Use an array value to repeat a parameter"
+                    },
+                    "fragment": {
+                        "type": "string",
+                        "description": "The fragment of the URL to build, without the leading #"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("URL utilities".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let data_convert_tool = Tool::new(
+            "data_convert",
+            indoc! {r#"
+                Convert a document between JSON, YAML and TOML, e.g. to rewrite a configuration file
+                in another format. Integers and floats are kept distinct, and TOML datetimes are
+                converted to strings (and back again when converting to TOML).
+
+                Provide the document either as input or as from_file, a file in the cache directory.
+                The converted document is returned, or saved to the cache file to_file if given.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["from", "to"],
+                "properties": {
+                    "input": {
+                        "type": "string",
+                        "description": "The document to convert"
+                    },
+                    "from_file": {
+                        "type": "string",
+                        "description": "Path of a file in the cache directory to convert, instead of input"
+                    },
+                    "from": {
+                        "type": "string",
+                        "enum": ["json", "yaml", "toml"],
+                        "description": "The format of the input"
+                    },
+                    "to": {
+                        "type": "string",
+                        "enum": ["json", "yaml", "toml"],
+                        "description": "The format to convert to"
+                    },
+                    "pretty": {
+                        "type": "boolean",
+                        "default": true,
+                        "description": "Pretty print JSON and TOML output"
+                    },
+                    "to_file": {
+                        "type": "string",
+                        "description": "Path of a file in the cache directory to save the result to"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Convert JSON/YAML/TOML".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let regex_tool = Tool::new(
+            "regex_tool",
+            indoc! {r#"
+                Test, validate and apply regular expressions before using them in scripts or commands.
+                Uses Rust regex syntax (similar to PCRE, without lookaround or backreferences in patterns).
+                Supports operations:
+                - test: Match the pattern against the text. Returns matched, the capture groups of the
+                  first match, and the start, end and text of up to 100 matches
+                - validate: Check whether the pattern is valid, returning the error if not
+                - replace: Replace all matches in the text with the replacement, which can refer to
+                  capture groups as $1, $2 or ${name}
+
+                flags can combine i (case insensitive), m (multi-line), s (dot matches newline),
+                x (ignore whitespace) and U (swap greediness).
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation", "pattern"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["test", "validate", "replace"],
+                        "description": "The operation to perform"
+                    },
+                    "pattern": {
+                        "type": "string",
+                        "description": "The regular expression"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "The text to match against (test and replace)"
+                    },
+                    "replacement": {
+                        "type": "string",
+                        "description": "The replacement for each match (replace only)"
+                    },
+                    "flags": {
+                        "type": "string",
+                        "description": "Flags from imsxU, e.g. \"im\""
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Regex tester".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let window_control_tool = Tool::new(
+            "window_control",
+            indoc! {r#"
+                Resize, move, maximize, minimize or restore an application window, found by its
+                exact title. Returns the new geometry of the window as {x, y, width, height}.
+                Supported on macOS and Windows. For windows that cannot be controlled this way,
+                use computer_control instead.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation", "window_title"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["resize", "move_to", "maximize", "minimize", "restore"],
+                        "description": "The operation to perform"
+                    },
+                    "window_title": {
+                        "type": "string",
+                        "description": "The exact title of the window"
+                    },
+                    "width": {
+                        "type": "integer",
+                        "description": "The new width in pixels (resize only)"
+                    },
+                    "height": {
+                        "type": "integer",
+                        "description": "The new height in pixels (resize only)"
+                    },
+                    "x": {
+                        "type": "integer",
+                        "description": "The new left edge in screen coordinates (move_to only)"
+                    },
+                    "y": {
+                        "type": "integer",
+                        "description": "The new top edge in screen coordinates (move_to only)"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Control a window".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let clipboard_tool = Tool::new(
+            "clipboard",
+            indoc! {r#"
+                Read or write the system clipboard.
+                - read: Returns the text on the clipboard, or with read_image the image on the
+                  clipboard as a PNG
+                - write: Puts the given text on the clipboard, and returns how many characters
+                  were written
+                Uses pbpaste/pbcopy on macOS, PowerShell on Windows, and xclip or xsel on Linux.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["read", "write"],
+                        "description": "The operation to perform"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "The text to put on the clipboard (write only)"
+                    },
+                    "read_image": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Read the image on the clipboard instead of text (read only)"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Use the clipboard".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let browser_screenshot_tool = Tool::new(
+            "browser_screenshot",
+            indoc! {r#"
+                Take a screenshot of a web page in headless Chrome, without opening a browser
+                window. Returns the screenshot as a PNG image.
+                Use wait_for_selector for pages that render their content with JavaScript.
+                Chrome or Chromium must be installed, set GOOSE_CHROME_PATH if it is not found.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["url"],
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The http, https or file url of the page"
+                    },
+                    "viewport_width": {
+                        "type": "integer",
+                        "default": 1280,
+                        "description": "The width of the browser window in pixels"
+                    },
+                    "viewport_height": {
+                        "type": "integer",
+                        "default": 800,
+                        "description": "The height of the browser window in pixels"
+                    },
+                    "wait_for_selector": {
+                        "type": "string",
+                        "description": "A CSS selector to wait for before taking the screenshot"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Screenshot a web page".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            }),
+        );
+
+        let webcam_capture_tool = Tool::new(
+            "webcam_capture",
+            indoc! {r#"
+                Capture still images from a webcam connected to the machine.
+                - list_devices: Returns the index and name of each connected camera
+                - capture: Takes a picture with the camera at device_index, and returns it as a
+                  PNG image, which is also saved to the cache
+                Uses ffmpeg, which must be installed. Webcam access is disabled unless the user
+                sets GOOSE_WEBCAM_ALLOWED=true.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["list_devices", "capture"],
+                        "description": "The operation to perform"
+                    },
+                    "device_index": {
+                        "type": "integer",
+                        "default": 0,
+                        "description": "The index of the camera, from list_devices (capture only)"
+                    },
+                    "width": {
+                        "type": "integer",
+                        "description": "The width of the picture in pixels, with height (capture only)"
+                    },
+                    "height": {
+                        "type": "integer",
+                        "description": "The height of the picture in pixels, with width (capture only)"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Capture from a webcam".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
         let pdf_tool = Tool::new(
             "pdf_tool",
             indoc! {r#"
                 Process PDF files to extract text and images.
                 Supports operations:
-                - extract_text: Extract all text content from the PDF
+                - extract_text: Extract the text content from the PDF, with a `## Page N` header
+                  before each page when there are several
                 - extract_images: Extract and save embedded images to PNG files
+                - extract_page_count: Get the number of pages, without extracting anything
+                Use page_range to only process some of the pages of long documents.
 
                 Use this when there is a .pdf file or files that need to be processed.
             "#},
@@ -247,8 +788,17 @@ impl ComputerControllerRouter {
                     },
                     "operation": {
                         "type": "string",
-                        "enum": ["extract_text", "extract_images"],
+                        "enum": ["extract_text", "extract_images", "extract_page_count"],
                         "description": "Operation to perform on the PDF"
+                    },
+                    "page_range": {
+                        "type": "object",
+                        "required": ["start", "end"],
+                        "properties": {
+                            "start": {"type": "integer", "minimum": 1},
+                            "end": {"type": "integer", "minimum": 1}
+                        },
+                        "description": "The pages to process, 1-indexed and inclusive (extract_text and extract_images only)"
                     }
                 }
             }),
@@ -567,12 +1117,38 @@ impl ComputerControllerRouter {
             web_scrape
               - Fetch content from html websites and APIs
               - Save as text, JSON, or binary files
+              - Use save_as "tables" to get the HTML tables of a page as CSV
+              - Use save_as "markdown" to get a page as Markdown, without navigation, scripts and styles
+              - Pass headers such as Authorization for APIs and pages that need them
               - Content is cached locally for later use
               - This is not optimised for complex websites, so don't use this as the first tool.
+            web_extract_article
+              - Extract the main article of a web page as markdown, with its title, byline and date
+              - Prefer this over web_scrape for reading news, blog posts and documentation
+            url_metadata
+              - Get the title, description and favicon of a URL without fetching the whole page
             cache
               - Manage your cached files
               - List, view, delete files
               - Clear all cached data
+            diff_cached_files
+              - Compare two cached files, e.g. API responses before and after a change
+            base64_tool
+              - Encode and decode base64 text and files, and inspect JWTs
+            url_tool
+              - Encode, decode, parse and build URLs and query strings
+            data_convert
+              - Convert documents between JSON, YAML and TOML
+            regex_tool
+              - Test, validate and apply regular expressions
+            window_control
+              - Resize, move, maximize, minimize or restore a window by its title
+            clipboard
+              - Read text or images from the clipboard, and write text to it
+            browser_screenshot
+              - Take a screenshot of a web page in headless Chrome
+            webcam_capture
+              - List the connected cameras and take pictures with them, when GOOSE_WEBCAM_ALLOWED is set
             The extension automatically manages:
             - Cache directory: {cache_dir}
             - File organization and cleanup
@@ -584,9 +1160,20 @@ impl ComputerControllerRouter {
         Self {
             tools: vec![
                 web_scrape_tool,
+                web_extract_article_tool,
+                url_metadata_tool,
                 quick_script_tool,
                 computer_control_tool,
                 cache_tool,
+                diff_cached_files_tool,
+                base64_tool,
+                url_tool,
+                data_convert_tool,
+                regex_tool,
+                window_control_tool,
+                clipboard_tool,
+                browser_screenshot_tool,
+                webcam_capture_tool,
                 pdf_tool,
                 docx_tool,
                 xlsx_tool,
@@ -648,10 +1235,48 @@ impl ComputerControllerRouter {
             .and_then(|v| v.as_str())
             .unwrap_or("text");
 
+        let headers = params
+            .get("headers")
+            .map(web_headers::parse_headers)
+            .transpose()?
+            .unwrap_or_default();
+
+        let extension = match save_as {
+            "text" => "txt",
+            "json" => "json",
+            "binary" => "bin",
+            "tables" => "csv",
+            "markdown" => "md",
+            _ => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Invalid 'save_as' parameter: {}. Valid options are: 'text', 'json', 'binary', 'tables', 'markdown'",
+                    save_as
+                )));
+            }
+        };
+
+        // With a TTL the content is saved under a name derived from the URL and headers, so a
+        // recent copy can be found and returned without fetching the URL again
+        let cache_ttl = params
+            .get("cache_ttl_seconds")
+            .and_then(|v| v.as_u64())
+            .map(Duration::from_secs);
+        if let Some(ttl) = cache_ttl {
+            let cache_path = web_cache::cache_path(&self.cache_dir, url, &headers, extension);
+            if web_cache::is_fresh(&cache_path, url, ttl) {
+                let content = fs::read(&cache_path).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to read from cache: {}", e))
+                })?;
+                self.register_as_resource(&cache_path, save_as)?;
+                return Ok(self.web_scrape_result(save_as, &cache_path, &content, true));
+            }
+        }
+
         // Fetch the content
         let response = self
             .http_client
             .get(url)
+            .headers(headers.clone())
             .send()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch URL: {}", e)))?;
@@ -665,50 +1290,216 @@ impl ComputerControllerRouter {
         }
 
         // Process based on save_as parameter
-        let (content, extension) =
-            match save_as {
-                "text" => {
-                    let text = response.text().await.map_err(|e| {
-                        ToolError::ExecutionError(format!("Failed to get text: {}", e))
-                    })?;
-                    (text.into_bytes(), "txt")
-                }
-                "json" => {
-                    let text = response.text().await.map_err(|e| {
-                        ToolError::ExecutionError(format!("Failed to get text: {}", e))
-                    })?;
-                    // Verify it's valid JSON
-                    serde_json::from_str::<Value>(&text).map_err(|e| {
-                        ToolError::ExecutionError(format!("Invalid JSON response: {}", e))
-                    })?;
-                    (text.into_bytes(), "json")
-                }
-                "binary" => {
-                    let bytes = response.bytes().await.map_err(|e| {
-                        ToolError::ExecutionError(format!("Failed to get bytes: {}", e))
-                    })?;
-                    (bytes.to_vec(), "bin")
-                }
-                _ => {
-                    return Err(ToolError::InvalidParameters(format!(
-                    "Invalid 'save_as' parameter: {}. Valid options are: 'text', 'json', 'binary'",
-                    save_as
-                )));
+        let mut tables = Vec::new();
+        let content = match save_as {
+            "binary" => {
+                let bytes = response.bytes().await.map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to get bytes: {}", e))
+                })?;
+                bytes.to_vec()
+            }
+            _ => {
+                let text = response
+                    .text()
+                    .await
+                    .map_err(|e| ToolError::ExecutionError(format!("Failed to get text: {}", e)))?;
+                match save_as {
+                    "json" => {
+                        // Verify it's valid JSON
+                        serde_json::from_str::<Value>(&text).map_err(|e| {
+                            ToolError::ExecutionError(format!("Invalid JSON response: {}", e))
+                        })?;
+                        text.into_bytes()
+                    }
+                    "tables" => {
+                        tables = table_tool::extract_tables(&text);
+                        if tables.is_empty() {
+                            return Ok(vec![Content::text("No data tables found on the page")]);
+                        }
+                        tables
+                            .iter()
+                            .filter_map(|table| table.as_text())
+                            .collect::<Vec<_>>()
+                            .join("\n\n")
+                            .into_bytes()
+                    }
+                    "markdown" => article_tool::page_to_markdown(&text).into_bytes(),
+                    _ => text.into_bytes(),
                 }
-            };
+            }
+        };
+
+        // Save to cache
+        let cache_path = match cache_ttl {
+            Some(_) => {
+                let cache_path = web_cache::cache_path(&self.cache_dir, url, &headers, extension);
+                fs::write(&cache_path, &content).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to write to cache: {}", e))
+                })?;
+                web_cache::write_meta(&cache_path, url)?;
+                cache_path
+            }
+            None => self.save_to_cache(&content, "web", extension).await?,
+        };
+
+        // Register as a resource
+        let mime_type = match save_as {
+            "tables" | "markdown" => "text",
+            _ => save_as,
+        };
+        self.register_as_resource(&cache_path, mime_type)?;
+
+        if !tables.is_empty() {
+            return Ok(tables
+                .into_iter()
+                .chain(std::iter::once(Content::text(format!(
+                    "Content saved to: {}",
+                    cache_path.display()
+                ))))
+                .collect());
+        }
+        Ok(self.web_scrape_result(save_as, &cache_path, &content, false))
+    }
+
+    /// What web_scrape returns for content saved to `cache_path`
+    fn web_scrape_result(
+        &self,
+        save_as: &str,
+        cache_path: &Path,
+        content: &[u8],
+        cached: bool,
+    ) -> Vec<Content> {
+        let saved = if cached {
+            format!("Cached content from: {}", cache_path.display())
+        } else {
+            format!("Content saved to: {}", cache_path.display())
+        };
+        match save_as {
+            "markdown" => {
+                let markdown = String::from_utf8_lossy(content);
+                let preview: String = markdown.chars().take(MARKDOWN_PREVIEW_CHARS).collect();
+                let truncated = if preview.len() < markdown.len() {
+                    "\n..."
+                } else {
+                    ""
+                };
+                vec![Content::text(format!(
+                    "{}\n\n{}{}",
+                    saved, preview, truncated
+                ))]
+            }
+            "tables" => vec![
+                Content::text(String::from_utf8_lossy(content)),
+                Content::text(saved),
+            ],
+            _ => vec![Content::text(saved)],
+        }
+    }
+
+    async fn web_extract_article(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let url = params
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'url' parameter".into()))?;
+
+        let response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch URL: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "HTTP request failed with status: {}",
+                status
+            )));
+        }
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get text: {}", e)))?;
+
+        let article = article_tool::extract_article(&html, url);
+        let article_json = serde_json::to_string_pretty(&article).map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to serialize article: {}", e))
+        })?;
 
         // Save to cache
-        let cache_path = self.save_to_cache(&content, "web", extension).await?;
+        let cache_path = self
+            .save_to_cache(article_json.as_bytes(), "article", "json")
+            .await?;
 
         // Register as a resource
-        self.register_as_resource(&cache_path, save_as)?;
+        self.register_as_resource(&cache_path, "json")?;
 
         Ok(vec![Content::text(format!(
-            "Content saved to: {}",
+            "{}\n\nContent saved to: {}",
+            article_json,
             cache_path.display()
         ))])
     }
 
+    async fn url_metadata(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let url = params
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'url' parameter".into()))?;
+
+        // Metadata is cached by URL rather than by time, so repeated lookups skip the fetch
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        let cache_path = self
+            .cache_dir
+            .join(format!("metadata_{:016x}.json", hasher.finish()));
+        if let Ok(cached) = fs::read_to_string(&cache_path) {
+            return Ok(vec![Content::text(cached)]);
+        }
+
+        let mut response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch URL: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "HTTP request failed with status: {}",
+                status
+            )));
+        }
+
+        // Read until the end of the head, up to the limit, then drop the connection
+        let mut head = Vec::new();
+        while head.len() < METADATA_READ_LIMIT {
+            let chunk = response
+                .chunk()
+                .await
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to read page: {}", e)))?;
+            let Some(chunk) = chunk else { break };
+            head.extend_from_slice(&chunk);
+            if String::from_utf8_lossy(&head).contains("</head>") {
+                break;
+            }
+        }
+        head.truncate(METADATA_READ_LIMIT);
+
+        let metadata = article_tool::extract_page_metadata(&String::from_utf8_lossy(&head), url);
+        let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to serialize metadata: {}", e))
+        })?;
+
+        fs::write(&cache_path, &metadata_json)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write to cache: {}", e)))?;
+        self.register_as_resource(&cache_path, "json")?;
+
+        Ok(vec![Content::text(metadata_json)])
+    }
+
     // Implement quick_script tool functionality
     async fn quick_script(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let language = params
@@ -999,6 +1790,163 @@ impl ComputerControllerRouter {
         }
     }
 
+    async fn diff_cached_files(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path_a = params
+            .get("path_a")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path_a' parameter".into()))?;
+
+        let path_b = params
+            .get("path_b")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path_b' parameter".into()))?;
+
+        crate::computercontroller::diff_tool::diff_cached_files(path_a, path_b, &self.cache_dir)
+    }
+
+    async fn base64_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+
+        if operation == "decode_file" {
+            let bytes = crate::computercontroller::base64_tool::decode_data(&params)?;
+            let cache_path = self.save_to_cache(&bytes, "decoded", "bin").await?;
+            self.register_as_resource(&cache_path, "binary")?;
+            return Ok(vec![Content::text(format!(
+                "Decoded {} bytes to: {}",
+                bytes.len(),
+                cache_path.display()
+            ))]);
+        }
+        crate::computercontroller::base64_tool::base64_tool(operation, &params, &self.cache_dir)
+    }
+
+    async fn url_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+
+        crate::computercontroller::url_tool::url_tool(operation, &params)
+    }
+
+    async fn data_convert(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        crate::computercontroller::convert_tool::data_convert(&params, &self.cache_dir)
+    }
+
+    async fn regex_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+
+        crate::computercontroller::regex_tool::regex_tool(operation, &params)
+    }
+
+    async fn window_control(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+
+        crate::computercontroller::window_tool::window_control(
+            self.system_automation.as_ref().as_ref(),
+            std::env::consts::OS,
+            operation,
+            &params,
+        )
+    }
+
+    async fn clipboard(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+
+        crate::computercontroller::clipboard_tool::clipboard(
+            &SystemCommandRunner,
+            std::env::consts::OS,
+            std::env::var("DISPLAY").ok().as_deref(),
+            operation,
+            &params,
+        )
+    }
+
+    async fn browser_screenshot(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let browser = crate::computercontroller::browser_tool::find_browser(
+            std::env::var_os("GOOSE_CHROME_PATH").map(PathBuf::from),
+            std::env::consts::OS,
+        )
+        .ok_or_else(|| {
+            ToolError::ExecutionError(
+                "Chrome or Chromium was not found. Install it, or set GOOSE_CHROME_PATH to its binary."
+                    .into(),
+            )
+        })?;
+
+        // Chrome takes a while to render, so keep it off the async runtime
+        tokio::task::spawn_blocking(move || {
+            crate::computercontroller::browser_tool::browser_screenshot(
+                &SystemCommandRunner,
+                &browser,
+                &params,
+            )
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+    }
+
+    async fn webcam_capture(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+        let allowed = std::env::var("GOOSE_WEBCAM_ALLOWED")
+            .is_ok_and(|allowed| allowed.eq_ignore_ascii_case("true"));
+        if !allowed {
+            return Err(ToolError::ExecutionError(
+                "Webcam access is disabled. The user can allow it by setting GOOSE_WEBCAM_ALLOWED=true."
+                    .into(),
+            ));
+        }
+
+        let camera = || crate::computercontroller::webcam_tool::FfmpegCamera {
+            commands: &SystemCommandRunner,
+            os: std::env::consts::OS,
+        };
+        // ffmpeg takes a moment to open the camera, so keep it off the async runtime
+        match operation {
+            "list_devices" => {
+                let devices = tokio::task::spawn_blocking(move || {
+                    crate::computercontroller::webcam_tool::list_devices(&camera())
+                })
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))??;
+                Ok(vec![Content::text(devices)])
+            }
+            "capture" => {
+                let png = tokio::task::spawn_blocking(move || {
+                    crate::computercontroller::webcam_tool::capture(&camera(), &params)
+                })
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))??;
+
+                let cache_path = self.save_to_cache(&png, "webcam", "png").await?;
+                self.register_as_resource(&cache_path, "image/png")?;
+                Ok(vec![
+                    Content::text(format!("Saved the picture to {}", cache_path.display())),
+                    Content::image(base64::prelude::BASE64_STANDARD.encode(png), "image/png"),
+                ])
+            }
+            _ => Err(ToolError::InvalidParameters(format!(
+                "Invalid operation: {}. Valid operations are: 'list_devices', 'capture'",
+                operation
+            ))),
+        }
+    }
+
     // Implement cache tool functionality
     async fn docx_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let path = params
@@ -1031,7 +1979,11 @@ impl ComputerControllerRouter {
             .and_then(|v| v.as_str())
             .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
 
-        crate::computercontroller::pdf_tool::pdf_tool(path, operation, &self.cache_dir).await
+        let page_range =
+            crate::computercontroller::pdf_tool::parse_page_range(params.get("page_range"))?;
+
+        crate::computercontroller::pdf_tool::pdf_tool(path, operation, page_range, &self.cache_dir)
+            .await
     }
 
     async fn cache(&self, params: Value) -> Result<Vec<Content>, ToolError> {
@@ -1141,9 +2093,20 @@ impl Router for ComputerControllerRouter {
         Box::pin(async move {
             match tool_name.as_str() {
                 "web_scrape" => this.web_scrape(arguments).await,
+                "web_extract_article" => this.web_extract_article(arguments).await,
+                "url_metadata" => this.url_metadata(arguments).await,
                 "automation_script" => this.quick_script(arguments).await,
                 "computer_control" => this.computer_control(arguments).await,
                 "cache" => this.cache(arguments).await,
+                "diff_cached_files" => this.diff_cached_files(arguments).await,
+                "base64_tool" => this.base64_tool(arguments).await,
+                "url_tool" => this.url_tool(arguments).await,
+                "data_convert" => this.data_convert(arguments).await,
+                "regex_tool" => this.regex_tool(arguments).await,
+                "window_control" => this.window_control(arguments).await,
+                "clipboard" => this.clipboard(arguments).await,
+                "browser_screenshot" => this.browser_screenshot(arguments).await,
+                "webcam_capture" => this.webcam_capture(arguments).await,
                 "pdf_tool" => this.pdf_tool(arguments).await,
                 "docx_tool" => this.docx_tool(arguments).await,
                 "xlsx_tool" => this.xlsx_tool(arguments).await,
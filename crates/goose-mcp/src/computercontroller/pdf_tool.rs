@@ -1,23 +1,88 @@
 use lopdf::{content::Content as PdfContent, Document, Object};
 use mcp_core::{Content, ToolError};
-use std::{fs, path::Path};
+use mcp_server::report_progress;
+use serde_json::Value;
+use std::{fs, ops::RangeInclusive, path::Path};
+
+/// Report that `done` of `total` pages have been processed
+fn report_page_progress(done: usize, total: usize) {
+    report_progress(
+        Some(done as f32 / total as f32 * 100.0),
+        format!("Processed page {} of {}", done, total),
+    );
+}
+
+/// Parse a `{start, end}` page range, 1-indexed and inclusive
+pub fn parse_page_range(value: Option<&Value>) -> Result<Option<RangeInclusive<u32>>, ToolError> {
+    let Some(value) = value.filter(|value| !value.is_null()) else {
+        return Ok(None);
+    };
+    let page = |name: &str| {
+        value
+            .get(name)
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v > 0)
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!(
+                    "'page_range.{}' must be a page number, starting from 1",
+                    name
+                ))
+            })
+    };
+    let (start, end) = (page("start")?, page("end")?);
+    if start > end {
+        return Err(ToolError::InvalidParameters(format!(
+            "Invalid page range {}-{}, the start is after the end",
+            start, end
+        )));
+    }
+    Ok(Some(start..=end))
+}
 
 pub async fn pdf_tool(
     path: &str,
     operation: &str,
+    page_range: Option<RangeInclusive<u32>>,
     cache_dir: &Path,
 ) -> Result<Vec<Content>, ToolError> {
     // Open and parse the PDF file
     let doc = Document::load(path)
         .map_err(|e| ToolError::ExecutionError(format!("Failed to open PDF file: {}", e)))?;
 
+    let pages = doc.get_pages();
+    if operation == "extract_page_count" {
+        return Ok(vec![Content::text(pages.len().to_string())]);
+    }
+    if let Some(range) = &page_range {
+        if *range.end() as usize > pages.len() {
+            return Err(ToolError::InvalidParameters(format!(
+                "Invalid page range {}-{}, the PDF has {} pages",
+                range.start(),
+                range.end(),
+                pages.len()
+            )));
+        }
+    }
+    let pages: Vec<_> = pages
+        .into_iter()
+        .filter(|(page_num, _)| {
+            page_range
+                .as_ref()
+                .is_none_or(|range| range.contains(page_num))
+        })
+        .collect();
+    let page_count = pages.len();
+
     let result = match operation {
         "extract_text" => {
             let mut text = String::new();
 
-            // Iterate over each page in the document
-            for (page_num, page_id) in doc.get_pages() {
-                text.push_str(&format!("Page {}:\n", page_num));
+            // Iterate over each selected page in the document
+            for (index, (page_num, page_id)) in pages.into_iter().enumerate() {
+                if page_count > 1 {
+                    text.push_str(&format!("## Page {}\n", page_num));
+                }
 
                 // Try to get text from page contents
                 if let Ok(page_obj) = doc.get_object(page_id) {
@@ -102,6 +167,7 @@ pub async fn pdf_tool(
                     }
                 }
                 text.push('\n');
+                report_page_progress(index + 1, page_count);
             }
 
             if text.trim().is_empty() {
@@ -163,8 +229,8 @@ pub async fn pdf_tool(
                 }
             }
 
-            // Process each page
-            for (page_num, page_id) in doc.get_pages() {
+            // Process each selected page
+            for (index, (page_num, page_id)) in pages.into_iter().enumerate() {
                 let page = doc.get_object(page_id).map_err(|e| {
                     ToolError::ExecutionError(format!("Failed to get page {}: {}", page_num, e))
                 })?;
@@ -302,6 +368,7 @@ pub async fn pdf_tool(
                         }
                     }
                 }
+                report_page_progress(index + 1, page_count);
             }
 
             if images.is_empty() {
@@ -313,7 +380,7 @@ pub async fn pdf_tool(
 
         _ => {
             return Err(ToolError::InvalidParameters(format!(
-                "Invalid operation: {}. Valid operations are: 'extract_text', 'extract_images'",
+                "Invalid operation: {}. Valid operations are: 'extract_text', 'extract_images', 'extract_page_count'",
                 operation
             )))
         }
@@ -325,8 +392,111 @@ pub async fn pdf_tool(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lopdf::{content::Operation, dictionary, Stream};
+    use serde_json::json;
     use std::path::PathBuf;
 
+    /// Write a PDF with a page showing each of `texts`
+    fn write_pdf(path: &Path, texts: &[&str]) {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let kids: Vec<Object> = texts
+            .iter()
+            .map(|text| {
+                let content = PdfContent {
+                    operations: vec![
+                        Operation::new("BT", vec![]),
+                        Operation::new("Tf", vec!["F1".into(), 24.into()]),
+                        Operation::new("Td", vec![100.into(), 600.into()]),
+                        Operation::new("Tj", vec![Object::string_literal(*text)]),
+                        Operation::new("ET", vec![]),
+                    ],
+                };
+                let content_id =
+                    doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => kids.len() as i64,
+                "Kids" => kids,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.save(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pdf_page_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("two_pages.pdf");
+        write_pdf(&path, &["First page text", "Second page text"]);
+        let path = path.to_str().unwrap();
+
+        let result = pdf_tool(path, "extract_page_count", None, dir.path())
+            .await
+            .unwrap();
+        assert_eq!(result[0].as_text().unwrap(), "2");
+
+        let result = pdf_tool(path, "extract_text", Some(1..=1), dir.path())
+            .await
+            .unwrap();
+        let text = result[0].as_text().unwrap();
+        assert!(text.contains("First page text"));
+        assert!(!text.contains("Second page text"));
+        assert!(!text.contains("## Page"));
+
+        let result = pdf_tool(path, "extract_text", None, dir.path())
+            .await
+            .unwrap();
+        let text = result[0].as_text().unwrap();
+        let first = text.find("## Page 1\nFirst page text").unwrap();
+        let second = text.find("## Page 2\nSecond page text").unwrap();
+        assert!(first < second);
+
+        let result = pdf_tool(path, "extract_text", Some(2..=3), dir.path()).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[test]
+    fn test_parse_page_range() {
+        assert_eq!(parse_page_range(None).unwrap(), None);
+        assert_eq!(
+            parse_page_range(Some(&json!({"start": 2, "end": 4}))).unwrap(),
+            Some(2..=4)
+        );
+        for invalid in [
+            json!({"start": 0, "end": 1}),
+            json!({"start": 3, "end": 2}),
+            json!({"start": 1}),
+            json!("1-2"),
+        ] {
+            assert!(parse_page_range(Some(&invalid)).is_err());
+        }
+    }
+
     #[tokio::test]
     async fn test_pdf_text_extraction() {
         let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -335,14 +505,23 @@ mod tests {
 
         println!("Testing text extraction from: {}", test_pdf_path.display());
 
-        let result = pdf_tool(test_pdf_path.to_str().unwrap(), "extract_text", &cache_dir).await;
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "extract_text",
+            None,
+            &cache_dir,
+        )
+        .await;
 
         assert!(result.is_ok(), "PDF text extraction should succeed");
         let content = result.unwrap();
         assert!(!content.is_empty(), "Extracted text should not be empty");
         let text = content[0].as_text().unwrap();
         println!("Extracted text:\n{}", text);
-        assert!(text.contains("Page 1"), "Should contain page marker");
+        assert!(
+            !text.contains("## Page"),
+            "A single page should not have a page marker"
+        );
         assert!(
             text.contains("This is a test PDF"),
             "Should contain expected test content"
@@ -361,6 +540,7 @@ mod tests {
         let result = pdf_tool(
             test_pdf_path.to_str().unwrap(),
             "extract_images",
+            None,
             &cache_dir,
         )
         .await;
@@ -399,7 +579,7 @@ mod tests {
     #[tokio::test]
     async fn test_pdf_invalid_path() {
         let cache_dir = tempfile::tempdir().unwrap().into_path();
-        let result = pdf_tool("nonexistent.pdf", "extract_text", &cache_dir).await;
+        let result = pdf_tool("nonexistent.pdf", "extract_text", None, &cache_dir).await;
 
         assert!(result.is_err(), "Should fail with invalid path");
     }
@@ -413,6 +593,7 @@ mod tests {
         let result = pdf_tool(
             test_pdf_path.to_str().unwrap(),
             "invalid_operation",
+            None,
             &cache_dir,
         )
         .await;
@@ -1,3 +1,6 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
 mod linux;
 mod macos;
 mod windows;
@@ -17,6 +20,41 @@ pub trait SystemAutomation: Send + Sync {
     fn get_temp_path(&self) -> std::path::PathBuf;
 }
 
+/// Runs command line utilities, so tools using them can be tested without them
+pub trait CommandRunner: Send + Sync {
+    /// Run `program` with `args`, writing `input` to its stdin, and return its stdout
+    fn run(&self, program: &str, args: &[&str], input: Option<&[u8]>) -> std::io::Result<Vec<u8>>;
+}
+
+/// Runs command line utilities as subprocesses
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str], input: Option<&[u8]>) -> std::io::Result<Vec<u8>> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input)?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(std::io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(output.stdout)
+    }
+}
+
 pub fn create_system_automation() -> Box<dyn SystemAutomation + Send + Sync> {
     #[cfg(target_os = "windows")]
     {
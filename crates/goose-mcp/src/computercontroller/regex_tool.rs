@@ -0,0 +1,186 @@
+use mcp_core::{Content, ToolError};
+use regex::{Regex, RegexBuilder};
+use serde_json::{json, Value};
+
+/// Maximum number of matches listed by the test operation
+const MAX_MATCHES: usize = 100;
+
+/// Compiled size limit, so overly large patterns fail with a clear error
+const SIZE_LIMIT: usize = 1024 * 1024;
+
+pub fn regex_tool(operation: &str, params: &Value) -> Result<Vec<Content>, ToolError> {
+    let param = |name: &str| {
+        params
+            .get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters(format!("Missing '{}' parameter", name)))
+    };
+    let pattern = param("pattern")?;
+    let flags = params.get("flags").and_then(|v| v.as_str()).unwrap_or("");
+
+    let result = match operation {
+        "validate" => match compile(pattern, flags) {
+            Ok(_) => json!({"valid": true, "error": null}),
+            Err(error) => json!({"valid": false, "error": error}),
+        },
+        "test" => {
+            let regex = compile(pattern, flags).map_err(ToolError::InvalidParameters)?;
+            test(&regex, param("text")?)
+        }
+        "replace" => {
+            let regex = compile(pattern, flags).map_err(ToolError::InvalidParameters)?;
+            let replaced = regex.replace_all(param("text")?, param("replacement")?);
+            return Ok(vec![Content::text(replaced)]);
+        }
+        _ => {
+            return Err(ToolError::InvalidParameters(format!(
+                "Invalid operation: {}. Valid operations are: 'test', 'validate', 'replace'",
+                operation
+            )))
+        }
+    };
+
+    Ok(vec![Content::text(
+        serde_json::to_string_pretty(&result).unwrap_or_default(),
+    )])
+}
+
+/// Compile a pattern with flags from `imsxU`, describing any error for the user
+fn compile(pattern: &str, flags: &str) -> Result<Regex, String> {
+    let mut builder = RegexBuilder::new(pattern);
+    builder.size_limit(SIZE_LIMIT);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            'x' => builder.ignore_whitespace(true),
+            'U' => builder.swap_greed(true),
+            // Matching is global anyway
+            'g' => &mut builder,
+            other => {
+                return Err(format!(
+                    "Unsupported flag '{}'. Supported flags are: i, m, s, x, U",
+                    other
+                ))
+            }
+        };
+    }
+
+    builder.build().map_err(|e| match e {
+        regex::Error::CompiledTooBig(limit) => format!(
+            "The pattern is too large once compiled (limit {} bytes), try simplifying it, \
+             e.g. by reducing large repetition counts",
+            limit
+        ),
+        e => e.to_string(),
+    })
+}
+
+/// The capture groups of the first match, and the positions of all matches
+fn test(regex: &Regex, text: &str) -> Value {
+    let groups: Vec<Value> = regex
+        .captures(text)
+        .map(|captures| {
+            captures
+                .iter()
+                .skip(1)
+                .map(|group| group.map_or(Value::Null, |group| json!(group.as_str())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let all_matches: Vec<Value> = regex
+        .find_iter(text)
+        .take(MAX_MATCHES)
+        .map(|m| json!({"start": m.start(), "end": m.end(), "text": m.as_str()}))
+        .collect();
+
+    json!({
+        "matched": !all_matches.is_empty(),
+        "groups": groups,
+        "all_matches": all_matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(operation: &str, params: Value) -> String {
+        let result = regex_tool(operation, &params).unwrap();
+        result[0].as_text().unwrap().to_string()
+    }
+
+    fn run_json(operation: &str, params: Value) -> Value {
+        serde_json::from_str(&run(operation, params)).unwrap()
+    }
+
+    #[test]
+    fn test_captures_groups() {
+        let result = run_json(
+            "test",
+            json!({
+                "pattern": r"(\w+)@(\w+)\.COM(/\d+)?",
+                "text": "alice@example.com, bob@test.com",
+                "flags": "i",
+            }),
+        );
+        assert_eq!(
+            result,
+            json!({
+                "matched": true,
+                "groups": ["alice", "example", null],
+                "all_matches": [
+                    {"start": 0, "end": 17, "text": "alice@example.com"},
+                    {"start": 19, "end": 31, "text": "bob@test.com"},
+                ],
+            })
+        );
+
+        let result = run_json("test", json!({"pattern": "x", "text": "abc"}));
+        assert_eq!(
+            result,
+            json!({"matched": false, "groups": [], "all_matches": []})
+        );
+    }
+
+    #[test]
+    fn test_limits_matches() {
+        let result = run_json("test", json!({"pattern": "a", "text": "a".repeat(150)}));
+        assert_eq!(result["all_matches"].as_array().unwrap().len(), MAX_MATCHES);
+    }
+
+    #[test]
+    fn test_replace_with_backreferences() {
+        let result = run(
+            "replace",
+            json!({
+                "pattern": r"(\d{4})-(\d{2})-(\d{2})",
+                "text": "from 2024-01-31 to 2024-02-29",
+                "replacement": "$3/$2/$1",
+            }),
+        );
+        assert_eq!(result, "from 31/01/2024 to 29/02/2024");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            run_json("validate", json!({"pattern": r"^\d+$"})),
+            json!({"valid": true, "error": null})
+        );
+
+        let result = run_json("validate", json!({"pattern": "(unclosed"}));
+        assert_eq!(result["valid"], json!(false));
+        assert!(result["error"].as_str().unwrap().contains("unclosed group"));
+
+        let result = run_json("validate", json!({"pattern": r"\w{1000}{1000}"}));
+        assert_eq!(result["valid"], json!(false));
+        assert!(result["error"].as_str().unwrap().contains("too large"));
+
+        assert!(matches!(
+            regex_tool("test", &json!({"pattern": "(", "text": ""})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+}
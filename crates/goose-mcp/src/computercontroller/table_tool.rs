@@ -0,0 +1,213 @@
+use mcp_core::Content;
+use scraper::{ElementRef, Html, Selector};
+
+/// Maximum number of tables returned for a single page
+const MAX_TABLES: usize = 10;
+
+/// Upper bound for rowspan and colspan, to guard against malformed pages
+const MAX_SPAN: usize = 1000;
+
+struct Table {
+    caption: Option<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Extract the data tables of an HTML page as CSV, one content block per table
+///
+/// Each block starts with a `// Table 1 of N: caption` label. The caption comes from the
+/// table's `<caption>`, or the closest heading before the table not already used by an
+/// earlier table. Tables with fewer than two rows or columns are skipped, as they are
+/// usually used for layout.
+pub fn extract_tables(html: &str) -> Vec<Content> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("h1, h2, h3, h4, h5, h6, table").unwrap();
+
+    let mut tables = Vec::new();
+    let mut last_heading = None;
+    for element in document.select(&selector) {
+        if element.value().name() != "table" {
+            last_heading = Some(collapse_whitespace(&element.text().collect::<String>()));
+            continue;
+        }
+
+        let rows = table_rows(element);
+        let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+        if rows.len() < 2 || columns < 2 {
+            continue;
+        }
+
+        let caption = child_elements(element)
+            .find(|child| child.value().name() == "caption")
+            .map(|caption| collapse_whitespace(&caption.text().collect::<String>()))
+            .or_else(|| last_heading.take())
+            .filter(|caption| !caption.is_empty());
+        tables.push(Table { caption, rows });
+        if tables.len() == MAX_TABLES {
+            break;
+        }
+    }
+
+    let total = tables.len();
+    tables
+        .into_iter()
+        .enumerate()
+        .map(|(index, table)| {
+            let label = match table.caption {
+                Some(caption) => format!("// Table {} of {}: {}", index + 1, total, caption),
+                None => format!("// Table {} of {}", index + 1, total),
+            };
+            Content::text(format!("{}\n{}", label, to_csv(&table.rows)))
+        })
+        .collect()
+}
+
+fn child_elements(element: ElementRef) -> impl Iterator<Item = ElementRef> {
+    element.children().filter_map(ElementRef::wrap)
+}
+
+/// The rows of a table (ignoring nested tables), with spanning cells repeated in every
+/// row and column they cover
+fn table_rows(table: ElementRef) -> Vec<Vec<String>> {
+    let rows = child_elements(table).flat_map(|child| match child.value().name() {
+        "tr" => vec![child],
+        "thead" | "tbody" | "tfoot" => child_elements(child)
+            .filter(|row| row.value().name() == "tr")
+            .collect(),
+        _ => vec![],
+    });
+
+    // Cells spanning into later rows, by column: (rows remaining, value)
+    let mut carried: Vec<Option<(usize, String)>> = Vec::new();
+    let mut grid = Vec::new();
+    for row in rows {
+        let mut cells =
+            child_elements(row).filter(|cell| matches!(cell.value().name(), "td" | "th"));
+        let mut values = Vec::new();
+        loop {
+            let column = values.len();
+            if let Some(Some((remaining, value))) = carried.get_mut(column) {
+                values.push(value.clone());
+                *remaining -= 1;
+                if *remaining == 0 {
+                    carried[column] = None;
+                }
+                continue;
+            }
+
+            let Some(cell) = cells.next() else {
+                // Keep the row aligned with cells still spanning into later columns
+                if carried
+                    .get(column..)
+                    .is_some_and(|rest| rest.iter().any(Option::is_some))
+                {
+                    values.push(String::new());
+                    continue;
+                }
+                break;
+            };
+
+            let text = collapse_whitespace(&cell.text().collect::<String>());
+            let span = |name| {
+                cell.value()
+                    .attr(name)
+                    .and_then(|span: &str| span.trim().parse::<usize>().ok())
+                    .unwrap_or(1)
+                    .clamp(1, MAX_SPAN)
+            };
+            let (colspan, rowspan) = (span("colspan"), span("rowspan"));
+            for _ in 0..colspan {
+                if rowspan > 1 {
+                    let column = values.len();
+                    if carried.len() <= column {
+                        carried.resize(column + 1, None);
+                    }
+                    carried[column] = Some((rowspan - 1, text.clone()));
+                }
+                values.push(text.clone());
+            }
+        }
+        grid.push(values);
+    }
+    grid
+}
+
+fn to_csv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| csv_field(value))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tables() {
+        let html = r#"
+            <html><body>
+              <h2>Quarterly revenue</h2>
+              <table>
+                <thead><tr><th>Region</th><th colspan="2">2024</th></tr></thead>
+                <tbody>
+                  <tr><td rowspan="2">North</td><td>Q1</td><td>1,200</td></tr>
+                  <tr><td>Q2</td><td>1,350</td></tr>
+                </tbody>
+              </table>
+
+              <table><tr><td>Layout only</td></tr></table>
+
+              <table>
+                <caption>League standings</caption>
+                <tr><th>Team</th><th>Points</th></tr>
+                <tr><td>Rovers</td><td>42</td></tr>
+                <tr><td>United</td><td>39</td></tr>
+              </table>
+            </body></html>
+        "#;
+
+        let tables = extract_tables(html);
+        assert_eq!(tables.len(), 2);
+        assert_eq!(
+            tables[0].as_text().unwrap(),
+            "// Table 1 of 2: Quarterly revenue\n\
+             Region,2024,2024\n\
+             North,Q1,\"1,200\"\n\
+             North,Q2,\"1,350\""
+        );
+        assert_eq!(
+            tables[1].as_text().unwrap(),
+            "// Table 2 of 2: League standings\n\
+             Team,Points\n\
+             Rovers,42\n\
+             United,39"
+        );
+    }
+
+    #[test]
+    fn test_extract_tables_caps_table_count() {
+        let table = "<table><tr><td>a</td><td>b</td></tr><tr><td>c</td><td>d</td></tr></table>";
+        let html = format!("<html><body>{}</body></html>", table.repeat(12));
+
+        let tables = extract_tables(&html);
+        assert_eq!(tables.len(), MAX_TABLES);
+        assert_eq!(tables[9].as_text().unwrap(), "// Table 10 of 10\na,b\nc,d");
+    }
+}
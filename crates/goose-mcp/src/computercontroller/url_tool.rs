@@ -0,0 +1,188 @@
+use mcp_core::{Content, ToolError};
+use serde_json::{json, Map, Value};
+use url::{form_urlencoded, Url};
+
+pub fn url_tool(operation: &str, params: &Value) -> Result<Vec<Content>, ToolError> {
+    let param = |name: &str| {
+        params
+            .get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters(format!("Missing '{}' parameter", name)))
+    };
+
+    match operation {
+        "encode" => Ok(vec![Content::text(urlencoding::encode(param("text")?))]),
+        "decode" => {
+            let decoded = urlencoding::decode(param("text")?).map_err(|e| {
+                ToolError::InvalidParameters(format!("Decoded text is not valid UTF-8: {}", e))
+            })?;
+            Ok(vec![Content::text(decoded)])
+        }
+        "parse" => {
+            let parsed = parse_url(&parse(param("url")?)?);
+            Ok(vec![Content::text(
+                serde_json::to_string_pretty(&parsed).unwrap_or_default(),
+            )])
+        }
+        "build" => build_url(params).map(|url| vec![Content::text(url.to_string())]),
+        "extract_query_param" => {
+            let url = parse(param("url")?)?;
+            let key = param("key")?;
+            let values: Vec<String> = url
+                .query_pairs()
+                .filter(|(name, _)| name == key)
+                .map(|(_, value)| value.into_owned())
+                .collect();
+            if values.is_empty() {
+                return Err(ToolError::ExecutionError(format!(
+                    "Query parameter '{}' not found",
+                    key
+                )));
+            }
+            Ok(vec![Content::text(values.join("\n"))])
+        }
+        _ => Err(ToolError::InvalidParameters(format!(
+            "Invalid operation: {}. Valid operations are: 'encode', 'decode', 'parse', 'build', 'extract_query_param'",
+            operation
+        ))),
+    }
+}
+
+fn parse(url: &str) -> Result<Url, ToolError> {
+    Url::parse(url.trim())
+        .map_err(|e| ToolError::InvalidParameters(format!("Invalid URL '{}': {}", url, e)))
+}
+
+/// Break a URL into its components, with the query parameters decoded
+///
+/// Parameters that appear more than once are collected into an array.
+fn parse_url(url: &Url) -> Value {
+    let mut query_params = Map::new();
+    for (key, value) in form_urlencoded::parse(url.query().unwrap_or_default().as_bytes()) {
+        let value = Value::String(value.into_owned());
+        match query_params.get_mut(key.as_ref()) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                query_params.insert(key.into_owned(), value);
+            }
+        }
+    }
+
+    json!({
+        "scheme": url.scheme(),
+        "host": url.host_str(),
+        "port": url.port(),
+        "path": url.path(),
+        "query_params": query_params,
+        "fragment": url.fragment(),
+    })
+}
+
+/// Assemble a URL from its components, percent-encoding the query parameter values
+fn build_url(params: &Value) -> Result<Url, ToolError> {
+    let field = |name: &str| params.get(name).and_then(|v| v.as_str());
+    let scheme = field("scheme").unwrap_or("https");
+    let host = field("host")
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'host' parameter".into()))?;
+
+    let mut url = parse(&format!("{}://{}", scheme, host))?;
+    if let Some(port) = params.get("port").and_then(|v| v.as_u64()) {
+        let port = u16::try_from(port)
+            .map_err(|_| ToolError::InvalidParameters(format!("Invalid port: {}", port)))?;
+        url.set_port(Some(port))
+            .map_err(|_| ToolError::InvalidParameters(format!("Cannot set a port on {}", url)))?;
+    }
+    if let Some(path) = field("path") {
+        url.set_path(path);
+    }
+
+    if let Some(query_params) = params.get("query_params") {
+        let query_params = query_params.as_object().ok_or_else(|| {
+            ToolError::InvalidParameters("'query_params' must be an object".into())
+        })?;
+        let mut query = url.query_pairs_mut();
+        for (key, value) in query_params {
+            let values = match value {
+                Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            };
+            for value in values {
+                match value {
+                    Value::String(value) => query.append_pair(key, value),
+                    Value::Null => query.append_key_only(key),
+                    value => query.append_pair(key, &value.to_string()),
+                };
+            }
+        }
+    }
+    url.set_fragment(field("fragment"));
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(operation: &str, params: Value) -> String {
+        let result = url_tool(operation, &params).unwrap();
+        result[0].as_text().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let text = "café & crème/10%";
+        let encoded = run("encode", json!({"text": text}));
+        assert_eq!(encoded, "caf%C3%A9%20%26%20cr%C3%A8me%2F10%25");
+        assert_eq!(run("decode", json!({"text": encoded})), text);
+    }
+
+    #[test]
+    fn test_parse_and_build_round_trip() {
+        let url = "https://example.com:8443/caf%C3%A9/menu?q=caf%C3%A9+%26+cr%C3%A8me&tag=a%2Fb%3Dc#section-2";
+
+        let parsed: Value = serde_json::from_str(&run("parse", json!({"url": url}))).unwrap();
+        assert_eq!(
+            parsed,
+            json!({
+                "scheme": "https",
+                "host": "example.com",
+                "port": 8443,
+                "path": "/caf%C3%A9/menu",
+                "query_params": {"q": "café & crème", "tag": "a/b=c"},
+                "fragment": "section-2",
+            })
+        );
+
+        assert_eq!(run("build", parsed), url);
+        assert_eq!(
+            run("extract_query_param", json!({"url": url, "key": "q"})),
+            "café & crème"
+        );
+    }
+
+    #[test]
+    fn test_build_encodes_query_values() {
+        let url = run(
+            "build",
+            json!({
+                "host": "api.example.com",
+                "path": "/search",
+                "query_params": {"q": "rust & wasm", "page": 2, "tags": ["a b", "c"]},
+            }),
+        );
+        assert_eq!(
+            url,
+            "https://api.example.com/search?page=2&q=rust+%26+wasm&tags=a+b&tags=c"
+        );
+
+        let parsed: Value = serde_json::from_str(&run("parse", json!({"url": url}))).unwrap();
+        assert_eq!(parsed["query_params"]["tags"], json!(["a b", "c"]));
+        assert!(url_tool(
+            "extract_query_param",
+            &json!({"url": url, "key": "missing"})
+        )
+        .is_err());
+    }
+}
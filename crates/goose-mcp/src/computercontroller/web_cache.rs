@@ -0,0 +1,129 @@
+use mcp_core::handler::ToolError;
+use reqwest::header::HeaderMap;
+use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Path of the cached copy of `url`, named after a hash of the URL so later scrapes find it
+///
+/// The request headers are part of the hash, since they can change the response, such as
+/// with a different Authorization or Accept header.
+pub fn cache_path(cache_dir: &Path, url: &str, headers: &HeaderMap, extension: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let mut headers: Vec<_> = headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_bytes()))
+        .collect();
+    headers.sort();
+    headers.hash(&mut hasher);
+    cache_dir.join(format!("web_{:016x}.{}", hasher.finish(), extension))
+}
+
+/// Path of the sidecar holding the source URL and fetch time of a cached file
+fn meta_path(path: &Path) -> PathBuf {
+    let mut meta = path.as_os_str().to_owned();
+    meta.push(".meta.json");
+    PathBuf::from(meta)
+}
+
+/// Record that `path` was just fetched from `url`
+pub fn write_meta(path: &Path, url: &str) -> Result<(), ToolError> {
+    let fetched_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let meta = json!({ "url": url, "fetched_at": fetched_at });
+    fs::write(meta_path(path), meta.to_string())
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to write to cache: {}", e)))
+}
+
+/// Whether `path` holds a copy of `url` fetched less than `ttl` ago
+///
+/// The fetch time comes from the sidecar, or from the creation time of the file when there is
+/// no sidecar.
+pub fn is_fresh(path: &Path, url: &str, ttl: Duration) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+    let meta = fs::read_to_string(meta_path(path))
+        .ok()
+        .and_then(|meta| serde_json::from_str::<Value>(&meta).ok());
+    let fetched_at = match meta {
+        Some(meta) => {
+            if meta.get("url").and_then(Value::as_str) != Some(url) {
+                return false;
+            }
+            meta.get("fetched_at")
+                .and_then(Value::as_u64)
+                .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        }
+        None => fs::metadata(path).and_then(|m| m.created()).ok(),
+    };
+    fetched_at
+        .and_then(|fetched_at| SystemTime::now().duration_since(fetched_at).ok())
+        .is_some_and(|age| age < ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_path_depends_on_url_and_headers() {
+        let dir = Path::new("/cache");
+        let none = HeaderMap::new();
+        let path = cache_path(dir, "https://example.com/a", &none, "md");
+        assert_eq!(path, cache_path(dir, "https://example.com/a", &none, "md"));
+        assert_ne!(path, cache_path(dir, "https://example.com/b", &none, "md"));
+        assert_eq!(path.extension().unwrap(), "md");
+
+        let mut alice = HeaderMap::new();
+        alice.insert("authorization", "token alice".parse().unwrap());
+        alice.insert("accept", "text/html".parse().unwrap());
+        let mut bob = alice.clone();
+        bob.insert("authorization", "token bob".parse().unwrap());
+        let alice_path = cache_path(dir, "https://example.com/a", &alice, "md");
+        assert_ne!(alice_path, path);
+        assert_ne!(
+            alice_path,
+            cache_path(dir, "https://example.com/a", &bob, "md")
+        );
+
+        // The order the headers are given in does not matter
+        let mut reordered = HeaderMap::new();
+        reordered.insert("accept", "text/html".parse().unwrap());
+        reordered.insert("authorization", "token alice".parse().unwrap());
+        assert_eq!(
+            alice_path,
+            cache_path(dir, "https://example.com/a", &reordered, "md")
+        );
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = "https://example.com/page";
+        let path = cache_path(dir.path(), url, &HeaderMap::new(), "txt");
+        let ttl = Duration::from_secs(300);
+
+        assert!(!is_fresh(&path, url, ttl));
+
+        fs::write(&path, "content").unwrap();
+        write_meta(&path, url).unwrap();
+        assert!(is_fresh(&path, url, ttl));
+        assert!(!is_fresh(&path, url, Duration::ZERO));
+        assert!(!is_fresh(&path, "https://example.com/other", ttl));
+
+        // An old fetch time in the sidecar is stale
+        fs::write(
+            meta_path(&path),
+            json!({ "url": url, "fetched_at": 0 }).to_string(),
+        )
+        .unwrap();
+        assert!(!is_fresh(&path, url, ttl));
+    }
+}
@@ -0,0 +1,78 @@
+use mcp_core::ToolError;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+
+/// Headers set by the HTTP client for the connection itself, which requests cannot override
+const FORBIDDEN_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "keep-alive",
+    "upgrade",
+    "te",
+    "trailer",
+    "proxy-connection",
+];
+
+/// Parse a JSON object of header names to values into headers for a request
+pub fn parse_headers(headers: &Value) -> Result<HeaderMap, ToolError> {
+    let headers = headers.as_object().ok_or_else(|| {
+        ToolError::InvalidParameters("The 'headers' parameter must be an object".into())
+    })?;
+
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers {
+        if !name.is_ascii() {
+            return Err(ToolError::InvalidParameters(format!(
+                "Header name '{}' must be ASCII",
+                name
+            )));
+        }
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| ToolError::InvalidParameters(format!("Invalid header name '{}'", name)))?;
+        if FORBIDDEN_HEADERS.contains(&header_name.as_str()) {
+            return Err(ToolError::InvalidParameters(format!(
+                "The '{}' header cannot be set",
+                name
+            )));
+        }
+        let value = value.as_str().ok_or_else(|| {
+            ToolError::InvalidParameters(format!("The value of header '{}' must be a string", name))
+        })?;
+        let header_value = HeaderValue::from_str(value).map_err(|_| {
+            ToolError::InvalidParameters(format!("Invalid value for header '{}'", name))
+        })?;
+        header_map.insert(header_name, header_value);
+    }
+    Ok(header_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_headers() {
+        let headers = parse_headers(&json!({
+            "Authorization": "token ghp_example",
+            "Accept": "application/vnd.github+json",
+        }))
+        .unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["authorization"], "token ghp_example");
+        assert_eq!(headers["accept"], "application/vnd.github+json");
+    }
+
+    #[test]
+    fn test_parse_headers_rejects_invalid_headers() {
+        assert!(parse_headers(&json!("Authorization: token")).is_err());
+        assert!(parse_headers(&json!({"Host": "example.com"})).is_err());
+        assert!(parse_headers(&json!({"Content-Length": "10"})).is_err());
+        assert!(parse_headers(&json!({"Clé": "value"})).is_err());
+        assert!(parse_headers(&json!({"Bad Name": "value"})).is_err());
+        assert!(parse_headers(&json!({"X-Api-Key": 42})).is_err());
+        assert!(parse_headers(&json!({"X-Api-Key": "a\nb"})).is_err());
+    }
+}
@@ -0,0 +1,430 @@
+use std::path::Path;
+
+use image::ImageFormat;
+use mcp_core::ToolError;
+use serde_json::Value;
+
+use super::platform::CommandRunner;
+
+/// A camera connected to the machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CameraDevice {
+    pub index: u32,
+    pub name: String,
+}
+
+/// A source of webcam frames, so the tool can be tested without camera hardware
+pub trait Camera: Send + Sync {
+    /// The cameras that are connected
+    fn devices(&self) -> Result<Vec<CameraDevice>, ToolError>;
+
+    /// Capture a single frame from `device` as PNG data, at `resolution` when given
+    fn capture(
+        &self,
+        device: &CameraDevice,
+        resolution: Option<(u32, u32)>,
+    ) -> Result<Vec<u8>, ToolError>;
+}
+
+/// Captures frames with ffmpeg, from video4linux on Linux, AVFoundation on macOS and
+/// DirectShow on Windows
+pub struct FfmpegCamera<'a> {
+    pub commands: &'a dyn CommandRunner,
+    pub os: &'a str,
+}
+
+impl Camera for FfmpegCamera<'_> {
+    fn devices(&self) -> Result<Vec<CameraDevice>, ToolError> {
+        match self.os {
+            "linux" => Ok(video4linux_devices(Path::new("/sys/class/video4linux"))),
+            // ffmpeg prints the devices as its log, and then fails as no input was opened
+            "macos" => Ok(parse_avfoundation_devices(&ffmpeg_log(
+                self.commands,
+                &[
+                    "-hide_banner",
+                    "-f",
+                    "avfoundation",
+                    "-list_devices",
+                    "true",
+                    "-i",
+                    "",
+                ],
+            )?)),
+            "windows" => Ok(parse_dshow_devices(&ffmpeg_log(
+                self.commands,
+                &[
+                    "-hide_banner",
+                    "-f",
+                    "dshow",
+                    "-list_devices",
+                    "true",
+                    "-i",
+                    "dummy",
+                ],
+            )?)),
+            _ => Err(unsupported(self.os)),
+        }
+    }
+
+    fn capture(
+        &self,
+        device: &CameraDevice,
+        resolution: Option<(u32, u32)>,
+    ) -> Result<Vec<u8>, ToolError> {
+        let (format, input) = match self.os {
+            "linux" => ("v4l2", format!("/dev/video{}", device.index)),
+            "macos" => ("avfoundation", device.index.to_string()),
+            "windows" => ("dshow", format!("video={}", device.name)),
+            _ => return Err(unsupported(self.os)),
+        };
+
+        let mut args = vec!["-hide_banner", "-loglevel", "error", "-f", format];
+        // AVFoundation only opens cameras at a frame rate they support
+        if self.os == "macos" {
+            args.extend(["-framerate", "30"]);
+        }
+        let video_size = resolution.map(|(width, height)| format!("{}x{}", width, height));
+        if let Some(video_size) = &video_size {
+            args.extend(["-video_size", video_size.as_str()]);
+        }
+        args.extend([
+            "-i",
+            input.as_str(),
+            "-frames:v",
+            "1",
+            "-f",
+            "image2pipe",
+            "-vcodec",
+            "png",
+            "-",
+        ]);
+
+        self.commands.run("ffmpeg", &args, None).map_err(|e| {
+            ToolError::ExecutionError(format!(
+                "Failed to capture from camera [{}] {}: {}",
+                device.index, device.name, e
+            ))
+        })
+    }
+}
+
+/// List the cameras connected to the machine
+pub fn list_devices(camera: &dyn Camera) -> Result<String, ToolError> {
+    let devices = camera.devices()?;
+    if devices.is_empty() {
+        return Ok("No cameras were detected".to_string());
+    }
+    Ok(describe_devices(&devices))
+}
+
+/// Capture a frame from the camera at `device_index`, checking it is a valid PNG
+pub fn capture(camera: &dyn Camera, params: &Value) -> Result<Vec<u8>, ToolError> {
+    let index = match params.get("device_index") {
+        None | Some(Value::Null) => 0,
+        Some(value) => value
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("'device_index' must be a non-negative integer".into())
+            })?,
+    };
+    let dimension = |name: &str| match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .filter(|value| *value > 0)
+            .map(Some)
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!("'{}' must be a positive integer", name))
+            }),
+    };
+    let resolution = match (dimension("width")?, dimension("height")?) {
+        (Some(width), Some(height)) => Some((width, height)),
+        (None, None) => None,
+        _ => {
+            return Err(ToolError::InvalidParameters(
+                "Provide both 'width' and 'height', or neither".into(),
+            ))
+        }
+    };
+
+    let devices = camera.devices()?;
+    let device = devices
+        .iter()
+        .find(|device| device.index == index)
+        .ok_or_else(|| {
+            ToolError::ExecutionError(if devices.is_empty() {
+                format!("No camera at index {}, no cameras were detected", index)
+            } else {
+                format!(
+                    "No camera at index {}, the detected cameras are:\n{}",
+                    index,
+                    describe_devices(&devices)
+                )
+            })
+        })?;
+
+    let png = camera.capture(device, resolution)?;
+    image::load_from_memory_with_format(&png, ImageFormat::Png).map_err(|e| {
+        ToolError::ExecutionError(format!(
+            "The camera did not return a valid PNG image: {}",
+            e
+        ))
+    })?;
+    Ok(png)
+}
+
+fn describe_devices(devices: &[CameraDevice]) -> String {
+    devices
+        .iter()
+        .map(|device| format!("[{}] {}", device.index, device.name))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The cameras under the video4linux sysfs directory, where `videoN` is `/dev/videoN`
+fn video4linux_devices(sysfs: &Path) -> Vec<CameraDevice> {
+    let Ok(entries) = std::fs::read_dir(sysfs) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<CameraDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let index = file_name.to_str()?.strip_prefix("video")?.parse().ok()?;
+            let name = std::fs::read_to_string(entry.path().join("name"))
+                .map(|name| name.trim().to_string())
+                .unwrap_or_else(|_| format!("video{}", index));
+            Some(CameraDevice { index, name })
+        })
+        .collect();
+    devices.sort_by_key(|device| device.index);
+    devices
+}
+
+/// The video devices in an AVFoundation listing, lines like
+/// `[AVFoundation indev @ 0x7f8] [0] FaceTime HD Camera` before the audio devices
+fn parse_avfoundation_devices(log: &str) -> Vec<CameraDevice> {
+    log.lines()
+        .skip_while(|line| !line.contains("AVFoundation video devices"))
+        .take_while(|line| !line.contains("AVFoundation audio devices"))
+        .filter_map(|line| {
+            let (_, device) = line.split_once("] [")?;
+            let (index, name) = device.split_once("] ")?;
+            Some(CameraDevice {
+                index: index.parse().ok()?,
+                name: name.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The video devices in a DirectShow listing, lines like
+/// `[dshow @ 000001] "Integrated Camera" (video)`, numbered in the order they are listed
+fn parse_dshow_devices(log: &str) -> Vec<CameraDevice> {
+    log.lines()
+        .filter(|line| line.trim_end().ends_with("(video)"))
+        .filter_map(|line| line.split('"').nth(1))
+        .zip(0..)
+        .map(|(name, index)| CameraDevice {
+            index,
+            name: name.to_string(),
+        })
+        .collect()
+}
+
+/// The log ffmpeg prints when asked to list devices, which it reports as a failure
+fn ffmpeg_log(commands: &dyn CommandRunner, args: &[&str]) -> Result<String, ToolError> {
+    match commands.run("ffmpeg", args, None) {
+        Ok(output) => Ok(String::from_utf8_lossy(&output).into_owned()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(ToolError::ExecutionError(
+            "ffmpeg was not found, install it to use the webcam".into(),
+        )),
+        Err(e) => Ok(e.to_string()),
+    }
+}
+
+fn unsupported(os: &str) -> ToolError {
+    ToolError::ExecutionError(format!("The webcam is not supported on {}", os))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use serde_json::json;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    /// The camera index and requested resolution of a capture
+    type Capture = (u32, Option<(u32, u32)>);
+
+    /// A camera producing a test pattern at the requested resolution
+    struct TestbenchCamera {
+        devices: Vec<CameraDevice>,
+        captures: Mutex<Vec<Capture>>,
+    }
+
+    impl TestbenchCamera {
+        fn new(names: &[&str]) -> Self {
+            Self {
+                devices: names
+                    .iter()
+                    .zip(0..)
+                    .map(|(name, index)| CameraDevice {
+                        index,
+                        name: name.to_string(),
+                    })
+                    .collect(),
+                captures: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Camera for TestbenchCamera {
+        fn devices(&self) -> Result<Vec<CameraDevice>, ToolError> {
+            Ok(self.devices.clone())
+        }
+
+        fn capture(
+            &self,
+            device: &CameraDevice,
+            resolution: Option<(u32, u32)>,
+        ) -> Result<Vec<u8>, ToolError> {
+            self.captures
+                .lock()
+                .unwrap()
+                .push((device.index, resolution));
+            let (width, height) = resolution.unwrap_or((64, 48));
+            let frame = RgbImage::from_fn(width, height, |x, y| {
+                Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+            });
+            let mut png = Vec::new();
+            frame
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .unwrap();
+            Ok(png)
+        }
+    }
+
+    #[test]
+    fn test_capture_returns_png() {
+        let camera = TestbenchCamera::new(&["Front", "Rear"]);
+
+        let png = capture(&camera, &json!({})).unwrap();
+        let frame = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert_eq!((frame.width(), frame.height()), (64, 48));
+
+        let png = capture(
+            &camera,
+            &json!({"device_index": 1, "width": 32, "height": 16}),
+        )
+        .unwrap();
+        let frame = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert_eq!((frame.width(), frame.height()), (32, 16));
+
+        assert_eq!(
+            *camera.captures.lock().unwrap(),
+            vec![(0, None), (1, Some((32, 16)))]
+        );
+        assert_eq!(list_devices(&camera).unwrap(), "[0] Front\n[1] Rear");
+    }
+
+    #[test]
+    fn test_missing_camera() {
+        let error = capture(
+            &TestbenchCamera::new(&["Front"]),
+            &json!({"device_index": 2}),
+        );
+        assert!(matches!(
+            error,
+            Err(ToolError::ExecutionError(message))
+                if message == "No camera at index 2, the detected cameras are:\n[0] Front"
+        ));
+
+        let camera = TestbenchCamera::new(&[]);
+        assert!(matches!(
+            capture(&camera, &json!({})),
+            Err(ToolError::ExecutionError(message)) if message.contains("no cameras were detected")
+        ));
+        assert_eq!(list_devices(&camera).unwrap(), "No cameras were detected");
+
+        assert!(matches!(
+            capture(&camera, &json!({"width": 640})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert!(camera.captures.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_device_listings() {
+        let avfoundation = "\
+[AVFoundation indev @ 0x7f8] AVFoundation video devices:
+[AVFoundation indev @ 0x7f8] [0] FaceTime HD Camera
+[AVFoundation indev @ 0x7f8] [1] Capture screen 0
+[AVFoundation indev @ 0x7f8] AVFoundation audio devices:
+[AVFoundation indev @ 0x7f8] [0] MacBook Pro Microphone
+: Input/output error";
+        assert_eq!(
+            parse_avfoundation_devices(avfoundation),
+            vec![
+                CameraDevice {
+                    index: 0,
+                    name: "FaceTime HD Camera".into()
+                },
+                CameraDevice {
+                    index: 1,
+                    name: "Capture screen 0".into()
+                },
+            ]
+        );
+
+        let dshow = r#"[dshow @ 000001] "Integrated Camera" (video)
+[dshow @ 000001]   Alternative name "@device_pnp_\\?\usb"
+[dshow @ 000001] "Microphone Array" (audio)
+[dshow @ 000001] "USB Webcam" (video)
+dummy: Immediate exit requested"#;
+        assert_eq!(
+            parse_dshow_devices(dshow),
+            vec![
+                CameraDevice {
+                    index: 0,
+                    name: "Integrated Camera".into()
+                },
+                CameraDevice {
+                    index: 1,
+                    name: "USB Webcam".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_video4linux_devices() {
+        let sysfs = tempfile::tempdir().unwrap();
+        for (entry, name) in [
+            ("video2", "USB Camera\n"),
+            ("video0", "Integrated Camera\n"),
+        ] {
+            std::fs::create_dir(sysfs.path().join(entry)).unwrap();
+            std::fs::write(sysfs.path().join(entry).join("name"), name).unwrap();
+        }
+        std::fs::create_dir(sysfs.path().join("v4l-subdev0")).unwrap();
+
+        assert_eq!(
+            video4linux_devices(sysfs.path()),
+            vec![
+                CameraDevice {
+                    index: 0,
+                    name: "Integrated Camera".into()
+                },
+                CameraDevice {
+                    index: 2,
+                    name: "USB Camera".into()
+                },
+            ]
+        );
+        assert!(video4linux_devices(&sysfs.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+use mcp_core::{Content, ToolError};
+use serde_json::{json, Value};
+
+use super::platform::SystemAutomation;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WindowOperation {
+    Resize { width: u32, height: u32 },
+    MoveTo { x: i32, y: i32 },
+    Maximize,
+    Minimize,
+    Restore,
+}
+
+impl WindowOperation {
+    fn from_params(operation: &str, params: &Value) -> Result<Self, ToolError> {
+        let number = |name: &str| {
+            params.get(name).and_then(|v| v.as_i64()).ok_or_else(|| {
+                ToolError::InvalidParameters(format!("Missing '{}' parameter", name))
+            })
+        };
+        let size = |name: &str| {
+            u32::try_from(number(name)?)
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| ToolError::InvalidParameters(format!("'{}' must be positive", name)))
+        };
+        let position = |name: &str| {
+            i32::try_from(number(name)?)
+                .map_err(|_| ToolError::InvalidParameters(format!("'{}' is out of range", name)))
+        };
+
+        match operation {
+            "resize" => Ok(Self::Resize {
+                width: size("width")?,
+                height: size("height")?,
+            }),
+            "move_to" => Ok(Self::MoveTo {
+                x: position("x")?,
+                y: position("y")?,
+            }),
+            "maximize" => Ok(Self::Maximize),
+            "minimize" => Ok(Self::Minimize),
+            "restore" => Ok(Self::Restore),
+            _ => Err(ToolError::InvalidParameters(format!(
+                "Invalid operation: {}. Valid operations are: 'resize', 'move_to', 'maximize', 'minimize', 'restore'",
+                operation
+            ))),
+        }
+    }
+}
+
+/// Resize, move, maximize, minimize or restore a window by its title
+///
+/// The script for the current `os` is run through `automation`, and prints the new
+/// geometry of the window as `x,y,width,height`.
+pub fn window_control(
+    automation: &dyn SystemAutomation,
+    os: &str,
+    operation: &str,
+    params: &Value,
+) -> Result<Vec<Content>, ToolError> {
+    let title = params
+        .get("window_title")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'window_title' parameter".into()))?;
+    let op = WindowOperation::from_params(operation, params)?;
+
+    let script = match os {
+        "macos" => applescript(title, op),
+        "windows" => powershell(title, op),
+        _ => {
+            return Err(ToolError::ExecutionError(format!(
+                "window_control is not supported on {}. Use computer_control instead.",
+                os
+            )))
+        }
+    };
+
+    let output = automation
+        .execute_system_script(&script)
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to execute script: {}", e)))?;
+    let geometry = parse_geometry(&output).ok_or_else(|| {
+        ToolError::ExecutionError(format!(
+            "Failed to {} window '{}'. The application may not be scriptable, \
+             try computer_control instead. Output:\n{}",
+            operation,
+            title,
+            output.trim()
+        ))
+    })?;
+
+    Ok(vec![Content::text(
+        serde_json::to_string_pretty(&geometry).unwrap_or_default(),
+    )])
+}
+
+/// Parse the `x,y,width,height` printed as the last line of a script
+fn parse_geometry(output: &str) -> Option<Value> {
+    let line = output.lines().rev().find(|line| !line.trim().is_empty())?;
+    let values: Vec<i64> = line
+        .split(',')
+        .map(|value| value.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let [x, y, width, height] = values[..] else {
+        return None;
+    };
+    Some(json!({"x": x, "y": y, "width": width, "height": height}))
+}
+
+fn applescript(title: &str, op: WindowOperation) -> String {
+    let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+    // Scriptable applications are changed with `set bounds`, others through accessibility
+    let set_bounds = r#"
+        try
+            tell application procName to set bounds of window targetTitle to {x, y, x + w, y + h}
+        on error
+            set position of targetWindow to {x, y}
+            set size of targetWindow to {w, h}
+        end try"#;
+    let operation = match op {
+        WindowOperation::Resize { width, height } => {
+            format!("set {{w, h}} to {{{}, {}}}{}", width, height, set_bounds)
+        }
+        WindowOperation::MoveTo { x, y } => {
+            format!("set {{x, y}} to {{{}, {}}}{}", x, y, set_bounds)
+        }
+        WindowOperation::Maximize => format!(
+            r#"tell application "Finder" to set screenBounds to bounds of window of desktop
+        set {{x, y, w, h}} to {{0, 0, item 3 of screenBounds, item 4 of screenBounds}}{}"#,
+            set_bounds
+        ),
+        WindowOperation::Minimize => {
+            r#"set value of attribute "AXMinimized" of targetWindow to true"#.to_string()
+        }
+        WindowOperation::Restore => {
+            r#"set value of attribute "AXMinimized" of targetWindow to false"#.to_string()
+        }
+    };
+
+    format!(
+        r#"set targetTitle to "{title}"
+set targetWindow to missing value
+tell application "System Events"
+    repeat with proc in (every process whose background only is false)
+        if exists (window targetTitle of proc) then
+            set targetWindow to window targetTitle of proc
+            set procName to name of proc
+            exit repeat
+        end if
+    end repeat
+    if targetWindow is missing value then error "No window found with title " & targetTitle
+    set {{x, y}} to position of targetWindow
+    set {{w, h}} to size of targetWindow
+    {operation}
+    set {{x, y}} to position of targetWindow
+    set {{w, h}} to size of targetWindow
+end tell
+return (x as text) & "," & (y as text) & "," & (w as text) & "," & (h as text)"#
+    )
+}
+
+fn powershell(title: &str, op: WindowOperation) -> String {
+    let title = title.replace('\'', "''");
+    let operation = match op {
+        WindowOperation::Resize { width, height } => format!(
+            "[void][Window]::MoveWindow($hwnd, $rect.Left, $rect.Top, {}, {}, $true)",
+            width, height
+        ),
+        WindowOperation::MoveTo { x, y } => format!(
+            "[void][Window]::MoveWindow($hwnd, {}, {}, $rect.Right - $rect.Left, $rect.Bottom - $rect.Top, $true)",
+            x, y
+        ),
+        WindowOperation::Maximize => "[void][Window]::ShowWindow($hwnd, 3)".to_string(),
+        WindowOperation::Minimize => "[void][Window]::ShowWindow($hwnd, 6)".to_string(),
+        WindowOperation::Restore => "[void][Window]::ShowWindow($hwnd, 9)".to_string(),
+    };
+
+    format!(
+        r#"Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+public struct RECT {{ public int Left; public int Top; public int Right; public int Bottom; }}
+public class Window {{
+    [DllImport("user32.dll")] public static extern IntPtr FindWindow(string lpClassName, string lpWindowName);
+    [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr hWnd, out RECT lpRect);
+    [DllImport("user32.dll")] public static extern bool MoveWindow(IntPtr hWnd, int X, int Y, int nWidth, int nHeight, bool bRepaint);
+    [DllImport("user32.dll")] public static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+}}
+"@
+$title = '{title}'
+$hwnd = [Window]::FindWindow($null, $title)
+if ($hwnd -eq [IntPtr]::Zero) {{ throw "No window found with title $title" }}
+$rect = New-Object RECT
+[void][Window]::GetWindowRect($hwnd, [ref]$rect)
+{operation}
+[void][Window]::GetWindowRect($hwnd, [ref]$rect)
+Write-Output "$($rect.Left),$($rect.Top),$($rect.Right - $rect.Left),$($rect.Bottom - $rect.Top)""#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    /// Records the scripts it is asked to run, and prints a fixed output
+    struct MockAutomation {
+        scripts: Mutex<Vec<String>>,
+        output: &'static str,
+    }
+
+    impl MockAutomation {
+        fn new(output: &'static str) -> Self {
+            Self {
+                scripts: Mutex::new(Vec::new()),
+                output,
+            }
+        }
+
+        fn last_script(&self) -> String {
+            self.scripts.lock().unwrap().last().cloned().unwrap()
+        }
+    }
+
+    impl SystemAutomation for MockAutomation {
+        fn execute_system_script(&self, script: &str) -> std::io::Result<String> {
+            self.scripts.lock().unwrap().push(script.to_string());
+            Ok(self.output.to_string())
+        }
+
+        fn get_shell_command(&self) -> (&'static str, &'static str) {
+            ("bash", "-c")
+        }
+
+        fn get_temp_path(&self) -> PathBuf {
+            std::env::temp_dir()
+        }
+    }
+
+    #[test]
+    fn test_resize_on_macos() {
+        let automation = MockAutomation::new("10,20,800,600\n");
+        let params = json!({"window_title": "Notes \"draft\"", "width": 800, "height": 600});
+
+        let result = window_control(&automation, "macos", "resize", &params).unwrap();
+        let geometry: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(
+            geometry,
+            json!({"x": 10, "y": 20, "width": 800, "height": 600})
+        );
+
+        let script = automation.last_script();
+        assert!(script.starts_with("set targetTitle to \"Notes \\\"draft\\\"\"\n"));
+        assert!(script.contains("set {w, h} to {800, 600}"));
+        assert!(script.contains("set bounds of window targetTitle to {x, y, x + w, y + h}"));
+    }
+
+    #[test]
+    fn test_move_and_minimize_on_windows() {
+        let automation = MockAutomation::new("-5,40,1024,768\r\n");
+        let params = json!({"window_title": "Bob's Notepad", "x": -5, "y": 40});
+
+        let result = window_control(&automation, "windows", "move_to", &params).unwrap();
+        let geometry: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(
+            geometry,
+            json!({"x": -5, "y": 40, "width": 1024, "height": 768})
+        );
+
+        let script = automation.last_script();
+        assert!(script.contains("$title = 'Bob''s Notepad'"));
+        assert!(script.contains(
+            "[Window]::MoveWindow($hwnd, -5, 40, $rect.Right - $rect.Left, $rect.Bottom - $rect.Top, $true)"
+        ));
+
+        window_control(&automation, "windows", "minimize", &params).unwrap();
+        assert!(automation
+            .last_script()
+            .contains("[void][Window]::ShowWindow($hwnd, 6)"));
+    }
+
+    #[test]
+    fn test_failures_suggest_computer_control() {
+        let automation = MockAutomation::new("");
+        let params = json!({"window_title": "Notes"});
+
+        let result = window_control(&automation, "macos", "maximize", &params);
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("computer_control"))
+        );
+        assert!(matches!(
+            window_control(&automation, "macos", "resize", &params),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            window_control(&automation, "linux", "maximize", &params),
+            Err(ToolError::ExecutionError(_))
+        ));
+    }
+}
@@ -0,0 +1,812 @@
+mod storage;
+
+use indoc::indoc;
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use std::{future::Future, pin::Pin};
+
+use crate::util::{json_content, required_str, send_json};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+use storage::{confluence_storage_to_markdown, markdown_to_confluence_storage};
+
+const DEFAULT_LIMIT: u64 = 25;
+
+/// The Confluence site and the credentials to access it with
+#[derive(Clone, Debug)]
+pub struct ConfluenceConfig {
+    pub url: String,
+    pub user: String,
+    pub api_token: String,
+}
+
+impl ConfluenceConfig {
+    /// Read the configuration from CONFLUENCE_URL, CONFLUENCE_USER and CONFLUENCE_API_TOKEN
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            url: std::env::var("CONFLUENCE_URL").ok()?,
+            user: std::env::var("CONFLUENCE_USER").ok()?,
+            api_token: std::env::var("CONFLUENCE_API_TOKEN").ok()?,
+        })
+    }
+
+    /// The url of the wiki, which Confluence Cloud serves under /wiki
+    fn wiki_url(&self) -> String {
+        format!(
+            "{}/wiki",
+            self.url.trim_end_matches('/').trim_end_matches("/wiki")
+        )
+    }
+}
+
+/// Router for the Confluence Cloud REST API
+#[derive(Clone)]
+pub struct ConfluenceRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    client: Client,
+    config: Option<ConfluenceConfig>,
+}
+
+impl Default for ConfluenceRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfluenceRouter {
+    pub fn new() -> Self {
+        Self::with_config(ConfluenceConfig::from_env())
+    }
+
+    pub fn with_config(config: Option<ConfluenceConfig>) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let writing = |title: &str, destructive: bool| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: false,
+                destructive_hint: destructive,
+                idempotent_hint: false,
+                open_world_hint: true,
+            })
+        };
+
+        let search_pages = Tool::new(
+            "search_pages",
+            indoc! {r#"
+                Search for Confluence pages with a CQL query, e.g.
+                `space = ENG AND title ~ "onboarding"` or `text ~ "release process"`.
+                The query is limited to pages. Returns the id, title, space and url of each page.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["cql"],
+                "properties": {
+                    "cql": {"type": "string", "description": "The CQL query"},
+                    "limit": {"type": "integer", "default": DEFAULT_LIMIT}
+                }
+            }),
+            read_only("Search Confluence pages"),
+        );
+
+        let get_page = Tool::new(
+            "get_page",
+            "Get a Confluence page with its body converted to Markdown.",
+            json!({
+                "type": "object",
+                "required": ["page_id"],
+                "properties": {
+                    "page_id": {"type": "string"}
+                }
+            }),
+            read_only("Get a Confluence page"),
+        );
+
+        let create_page = Tool::new(
+            "create_page",
+            "Create a Confluence page from Markdown. Returns the id and url of the new page.",
+            json!({
+                "type": "object",
+                "required": ["space_key", "title", "body"],
+                "properties": {
+                    "space_key": {"type": "string", "description": "The key of the space, e.g. ENG"},
+                    "title": {"type": "string"},
+                    "body": {"type": "string", "description": "The content of the page, in Markdown"},
+                    "parent_id": {"type": "string", "description": "The id of the parent page"}
+                }
+            }),
+            writing("Create a Confluence page", false),
+        );
+
+        let update_page = Tool::new(
+            "update_page",
+            indoc! {r#"
+                Replace the body of a Confluence page with Markdown, and optionally its title.
+                The whole body is replaced, so include the parts of the page to keep.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["page_id", "body"],
+                "properties": {
+                    "page_id": {"type": "string"},
+                    "body": {"type": "string", "description": "The new content of the page, in Markdown"},
+                    "title": {"type": "string"}
+                }
+            }),
+            writing("Update a Confluence page", true),
+        );
+
+        let list_spaces = Tool::new(
+            "list_spaces",
+            "List the Confluence spaces the user can see, with their key and name.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "limit": {"type": "integer", "default": DEFAULT_LIMIT}
+                }
+            }),
+            read_only("List Confluence spaces"),
+        );
+
+        let get_space = Tool::new(
+            "get_space",
+            "Get a Confluence space by key, with its description and home page.",
+            json!({
+                "type": "object",
+                "required": ["space_key"],
+                "properties": {
+                    "space_key": {"type": "string"}
+                }
+            }),
+            read_only("Get a Confluence space"),
+        );
+
+        let add_comment = Tool::new(
+            "add_comment",
+            "Add a comment, written in Markdown, to a Confluence page.",
+            json!({
+                "type": "object",
+                "required": ["page_id", "body"],
+                "properties": {
+                    "page_id": {"type": "string"},
+                    "body": {"type": "string"}
+                }
+            }),
+            writing("Comment on a Confluence page", false),
+        );
+
+        let get_page_children = Tool::new(
+            "get_page_children",
+            "List the child pages of a Confluence page, with their id and title.",
+            json!({
+                "type": "object",
+                "required": ["page_id"],
+                "properties": {
+                    "page_id": {"type": "string"},
+                    "limit": {"type": "integer", "default": DEFAULT_LIMIT}
+                }
+            }),
+            read_only("List Confluence child pages"),
+        );
+
+        let instructions = indoc! {r#"
+            The Confluence extension searches, reads and edits the pages of the Confluence site
+            in CONFLUENCE_URL, as the user in CONFLUENCE_USER.
+
+            Find pages with CQL in search_pages. Page bodies are read and written as Markdown;
+            code blocks, tables and info, note, tip, warning and panel macros are converted.
+        "#}
+        .to_string();
+
+        Self {
+            tools: vec![
+                search_pages,
+                get_page,
+                create_page,
+                update_page,
+                list_spaces,
+                get_space,
+                add_comment,
+                get_page_children,
+            ],
+            instructions,
+            client: Client::new(),
+            config,
+        }
+    }
+
+    fn config(&self) -> Result<&ConfluenceConfig, ToolError> {
+        self.config.as_ref().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "Confluence is not configured. Set CONFLUENCE_URL, CONFLUENCE_USER and CONFLUENCE_API_TOKEN, using an API token from https://id.atlassian.com/manage-profile/security/api-tokens".into(),
+            )
+        })
+    }
+
+    // Helper method to send a request to the Confluence API and parse the response
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<Value>,
+    ) -> Result<Value, ToolError> {
+        let config = self.config()?;
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", config.wiki_url(), path))
+            .basic_auth(&config.user, Some(&config.api_token))
+            .header("Accept", "application/json")
+            .query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let (status, body) = send_json(request, "Confluence").await?;
+        if status.is_success() {
+            return Ok(body);
+        }
+        let message = error_message(&body).unwrap_or_else(|| status.to_string());
+        Err(match status {
+            // Bad requests are how Confluence reports CQL syntax errors and invalid bodies
+            StatusCode::BAD_REQUEST => ToolError::InvalidParameters(message),
+            _ => {
+                ToolError::ExecutionError(format!("Confluence API error ({}): {}", status, message))
+            }
+        })
+    }
+
+    /// The space with the given key, as the v2 API identifies spaces by id
+    async fn space(&self, key: &str) -> Result<Value, ToolError> {
+        let response = self
+            .request(
+                Method::GET,
+                "/api/v2/spaces",
+                &[
+                    ("keys", key.to_string()),
+                    ("description-format", "plain".to_string()),
+                ],
+                None,
+            )
+            .await?;
+        response["results"]
+            .as_array()
+            .and_then(|spaces| spaces.first())
+            .cloned()
+            .ok_or_else(|| ToolError::InvalidParameters(format!("No space with key '{}'", key)))
+    }
+
+    async fn search_pages(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let cql = required_str(&params, "cql")?;
+        let limit = params["limit"].as_u64().unwrap_or(DEFAULT_LIMIT);
+
+        // CQL search is only available in the v1 API
+        let response = self
+            .request(
+                Method::GET,
+                "/rest/api/search",
+                &[
+                    ("cql", format!("type = page AND ({})", cql)),
+                    ("limit", limit.to_string()),
+                ],
+                None,
+            )
+            .await?;
+        let wiki_url = self.config()?.wiki_url();
+        let pages: Vec<Value> = response["results"]
+            .as_array()
+            .map(|results| {
+                results
+                    .iter()
+                    .map(|result| {
+                        json!({
+                            "id": result["content"]["id"],
+                            "title": result["content"]["title"],
+                            "space": result["resultGlobalContainer"]["title"],
+                            "url": result["url"].as_str().map(|url| format!("{}{}", wiki_url, url))
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(vec![json_content(&json!({
+            "total": response["totalSize"],
+            "pages": pages
+        }))])
+    }
+
+    async fn get_page(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let page = self
+            .request(
+                Method::GET,
+                &format!("/api/v2/pages/{}", page_id),
+                &[("body-format", "storage".to_string())],
+                None,
+            )
+            .await?;
+        let body = confluence_storage_to_markdown(
+            page["body"]["storage"]["value"]
+                .as_str()
+                .unwrap_or_default(),
+        );
+        Ok(vec![Content::text(format!(
+            "# {}\n\n{}",
+            page["title"].as_str().unwrap_or_default(),
+            body
+        ))])
+    }
+
+    async fn create_page(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let space_key = required_str(&params, "space_key")?;
+        let title = required_str(&params, "title")?;
+        let body = required_str(&params, "body")?;
+        let space = self.space(space_key).await?;
+
+        let mut payload = json!({
+            "spaceId": space["id"],
+            "status": "current",
+            "title": title,
+            "body": {
+                "representation": "storage",
+                "value": markdown_to_confluence_storage(body)
+            }
+        });
+        if let Some(parent_id) = params["parent_id"].as_str() {
+            payload["parentId"] = json!(parent_id);
+        }
+        let page = self
+            .request(Method::POST, "/api/v2/pages", &[], Some(payload))
+            .await?;
+        Ok(vec![json_content(&json!({
+            "id": page["id"],
+            "url": self.page_url(&page)?
+        }))])
+    }
+
+    async fn update_page(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let body = required_str(&params, "body")?;
+        let path = format!("/api/v2/pages/{}", page_id);
+
+        // Confluence only accepts an update that names the next version of the page
+        let current = self.request(Method::GET, &path, &[], None).await?;
+        let version = current["version"]["number"].as_u64().ok_or_else(|| {
+            ToolError::ExecutionError(format!("Page {} has no version number", page_id))
+        })?;
+        let page = self
+            .request(
+                Method::PUT,
+                &path,
+                &[],
+                Some(json!({
+                    "id": page_id,
+                    "status": "current",
+                    "title": params["title"].as_str().unwrap_or(current["title"].as_str().unwrap_or_default()),
+                    "body": {
+                        "representation": "storage",
+                        "value": markdown_to_confluence_storage(body)
+                    },
+                    "version": {"number": version + 1}
+                })),
+            )
+            .await?;
+        Ok(vec![Content::text(format!(
+            "Updated page {} to version {}",
+            page_id,
+            page["version"]["number"].as_u64().unwrap_or(version + 1)
+        ))])
+    }
+
+    async fn list_spaces(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let limit = params["limit"].as_u64().unwrap_or(DEFAULT_LIMIT);
+        let response = self
+            .request(
+                Method::GET,
+                "/api/v2/spaces",
+                &[("limit", limit.to_string())],
+                None,
+            )
+            .await?;
+        let spaces: Vec<Value> = response["results"]
+            .as_array()
+            .map(|spaces| {
+                spaces
+                    .iter()
+                    .map(|space| {
+                        json!({
+                            "id": space["id"],
+                            "key": space["key"],
+                            "name": space["name"],
+                            "type": space["type"]
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(vec![json_content(&Value::Array(spaces))])
+    }
+
+    async fn get_space(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let space = self.space(required_str(&params, "space_key")?).await?;
+        Ok(vec![json_content(&json!({
+            "id": space["id"],
+            "key": space["key"],
+            "name": space["name"],
+            "type": space["type"],
+            "status": space["status"],
+            "description": space["description"]["plain"]["value"],
+            "homepage_id": space["homepageId"]
+        }))])
+    }
+
+    async fn add_comment(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let body = required_str(&params, "body")?;
+        let comment = self
+            .request(
+                Method::POST,
+                "/api/v2/footer-comments",
+                &[],
+                Some(json!({
+                    "pageId": page_id,
+                    "body": {
+                        "representation": "storage",
+                        "value": markdown_to_confluence_storage(body)
+                    }
+                })),
+            )
+            .await?;
+        Ok(vec![Content::text(format!(
+            "Added comment {} to page {}",
+            comment["id"].as_str().unwrap_or_default(),
+            page_id
+        ))])
+    }
+
+    async fn get_page_children(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let limit = params["limit"].as_u64().unwrap_or(DEFAULT_LIMIT);
+        let response = self
+            .request(
+                Method::GET,
+                &format!("/api/v2/pages/{}/children", page_id),
+                &[("limit", limit.to_string())],
+                None,
+            )
+            .await?;
+        let children: Vec<Value> = response["results"]
+            .as_array()
+            .map(|children| {
+                children
+                    .iter()
+                    .map(|child| json!({"id": child["id"], "title": child["title"]}))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(vec![json_content(&Value::Array(children))])
+    }
+
+    fn page_url(&self, page: &Value) -> Result<Value, ToolError> {
+        let wiki_url = self.config()?.wiki_url();
+        Ok(json!(page["_links"]["webui"]
+            .as_str()
+            .map(|path| format!("{}{}", wiki_url, path))))
+    }
+}
+
+/// The messages of a Confluence error response, from either API version
+fn error_message(body: &Value) -> Option<String> {
+    // The v2 API returns a list of errors, the v1 API a single message
+    let messages: Vec<String> = match body["errors"].as_array() {
+        Some(errors) => errors
+            .iter()
+            .filter_map(|error| {
+                error["title"]
+                    .as_str()
+                    .or(error["detail"].as_str())
+                    .map(String::from)
+            })
+            .collect(),
+        None => body["message"]
+            .as_str()
+            .map(String::from)
+            .into_iter()
+            .collect(),
+    };
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+impl Router for ConfluenceRouter {
+    fn name(&self) -> String {
+        "confluence".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "search_pages" => this.search_pages(arguments).await,
+                "get_page" => this.get_page(arguments).await,
+                "create_page" => this.create_page(arguments).await,
+                "update_page" => this.update_page(arguments).await,
+                "list_spaces" => this.list_spaces(arguments).await,
+                "get_space" => this.get_space(arguments).await,
+                "add_comment" => this.add_comment(arguments).await,
+                "get_page_children" => this.get_page_children(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::text;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn router_for(server: &MockServer) -> ConfluenceRouter {
+        ConfluenceRouter::with_config(Some(ConfluenceConfig {
+            url: server.uri(),
+            user: "me@example.com".to_string(),
+            api_token: "token".to_string(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_search_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiki/rest/api/search"))
+            .and(query_param("cql", "type = page AND (space = ENG)"))
+            .and(header(
+                "authorization",
+                "Basic bWVAZXhhbXBsZS5jb206dG9rZW4=",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "totalSize": 1,
+                "results": [{
+                    "content": {"id": "123", "type": "page", "title": "Onboarding"},
+                    "resultGlobalContainer": {"title": "Engineering", "displayUrl": "/spaces/ENG"},
+                    "url": "/spaces/ENG/pages/123/Onboarding"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("search_pages", json!({"cql": "space = ENG"}))
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&text(result)).unwrap();
+        assert_eq!(
+            result["pages"],
+            json!([{
+                "id": "123",
+                "title": "Onboarding",
+                "space": "Engineering",
+                "url": format!("{}/wiki/spaces/ENG/pages/123/Onboarding", server.uri())
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiki/api/v2/pages/123"))
+            .and(query_param("body-format", "storage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "123",
+                "title": "Onboarding",
+                "version": {"number": 4},
+                "body": {"storage": {
+                    "representation": "storage",
+                    "value": "<p>Install <strong>goose</strong>:</p><ac:structured-macro ac:name=\"code\"><ac:parameter ac:name=\"language\">sh</ac:parameter><ac:plain-text-body><![CDATA[brew install goose]]></ac:plain-text-body></ac:structured-macro>"
+                }}
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("get_page", json!({"page_id": "123"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            text(result),
+            "# Onboarding\n\nInstall **goose**:\n\n```sh\nbrew install goose\n```"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_page_increments_version() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiki/api/v2/pages/123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "123",
+                "title": "Onboarding",
+                "version": {"number": 4}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/wiki/api/v2/pages/123"))
+            .and(body_json(json!({
+                "id": "123",
+                "status": "current",
+                "title": "Onboarding",
+                "body": {"representation": "storage", "value": "<p>New <em>content</em></p>\n"},
+                "version": {"number": 5}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "123",
+                "version": {"number": 5}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "update_page",
+                json!({"page_id": "123", "body": "New *content*"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(text(result), "Updated page 123 to version 5");
+    }
+
+    #[tokio::test]
+    async fn test_create_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiki/api/v2/spaces"))
+            .and(query_param("keys", "ENG"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [{"id": "98304", "key": "ENG", "name": "Engineering"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/wiki/api/v2/pages"))
+            .and(body_json(json!({
+                "spaceId": "98304",
+                "status": "current",
+                "title": "Runbook",
+                "parentId": "123",
+                "body": {"representation": "storage", "value": "<h1>Steps</h1>\n"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "456",
+                "_links": {"webui": "/spaces/ENG/pages/456/Runbook"}
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "create_page",
+                json!({"space_key": "ENG", "title": "Runbook", "body": "# Steps", "parent_id": "123"}),
+            )
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&text(result)).unwrap();
+        assert_eq!(result["id"], "456");
+        assert_eq!(
+            result["url"],
+            format!("{}/wiki/spaces/ENG/pages/456/Runbook", server.uri())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wiki/rest/api/search"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "statusCode": 400,
+                "message": "Could not parse cql : space = = ENG"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/wiki/api/v2/pages/999"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "errors": [{"status": 404, "code": "NOT_FOUND", "title": "Page not found"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let router = router_for(&server);
+        let result = router
+            .call_tool("search_pages", json!({"cql": "space = = ENG"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message == "Could not parse cql : space = = ENG")
+        );
+        let result = router
+            .call_tool("get_page", json!({"page_id": "999"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.ends_with("Page not found"))
+        );
+
+        let result = ConfluenceRouter::with_config(None)
+            .call_tool("list_spaces", json!({}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("CONFLUENCE_API_TOKEN"))
+        );
+    }
+}
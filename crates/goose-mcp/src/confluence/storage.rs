@@ -0,0 +1,660 @@
+//! Conversion between Markdown and the Confluence storage format, the XHTML that
+//! Confluence stores page bodies in.
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader;
+
+/// A node of a storage format document
+#[derive(Debug)]
+enum Node {
+    Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+impl Node {
+    fn name(&self) -> &str {
+        match self {
+            Node::Element { name, .. } => name,
+            Node::Text(_) => "",
+        }
+    }
+
+    fn attribute(&self, key: &str) -> Option<&str> {
+        match self {
+            Node::Element { attributes, .. } => attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            Node::Text(_) => None,
+        }
+    }
+
+    fn children(&self) -> &[Node] {
+        match self {
+            Node::Element { children, .. } => children,
+            Node::Text(_) => &[],
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<&Node> {
+        self.children().iter().find(|child| child.name() == name)
+    }
+
+    /// The text of the node and its descendants, as written
+    fn text(&self) -> String {
+        match self {
+            Node::Element { children, .. } => children.iter().map(Node::text).collect(),
+            Node::Text(text) => text.clone(),
+        }
+    }
+
+    /// The value of a macro parameter, like the language of a code macro
+    fn parameter(&self, name: &str) -> Option<String> {
+        self.children()
+            .iter()
+            .find(|child| {
+                child.name() == "ac:parameter" && child.attribute("ac:name") == Some(name)
+            })
+            .map(Node::text)
+    }
+}
+
+/// Parse a storage format fragment into its top level nodes
+///
+/// Parsing is lenient: mismatched tags are accepted, and the document ends at the first
+/// syntax error.
+fn parse(storage: &str) -> Vec<Node> {
+    let mut reader = Reader::from_str(storage);
+    let config = reader.config_mut();
+    config.expand_empty_elements = true;
+    config.check_end_names = false;
+
+    let mut stack: Vec<Node> = vec![Node::Element {
+        name: String::new(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+    }];
+    let push = |stack: &mut Vec<Node>, node: Node| {
+        if let Some(Node::Element { children, .. }) = stack.last_mut() {
+            children.push(node);
+        }
+    };
+    loop {
+        match reader.read_event() {
+            Ok(XmlEvent::Start(start)) => {
+                let attributes = start
+                    .attributes()
+                    .flatten()
+                    .map(|attribute| {
+                        let value = attribute
+                            .unescape_value()
+                            .map(|value| value.into_owned())
+                            .unwrap_or_else(|_| {
+                                String::from_utf8_lossy(&attribute.value).into_owned()
+                            });
+                        (
+                            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                            value,
+                        )
+                    })
+                    .collect();
+                stack.push(Node::Element {
+                    name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+                    attributes,
+                    children: Vec::new(),
+                });
+            }
+            Ok(XmlEvent::End(_)) => {
+                if stack.len() > 1 {
+                    let node = stack.pop().unwrap();
+                    push(&mut stack, node);
+                }
+            }
+            Ok(XmlEvent::Text(text)) => {
+                let text = text
+                    .unescape()
+                    .map(|text| text.into_owned())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&text).into_owned());
+                push(&mut stack, Node::Text(text));
+            }
+            Ok(XmlEvent::CData(data)) => {
+                push(
+                    &mut stack,
+                    Node::Text(String::from_utf8_lossy(&data).into_owned()),
+                );
+            }
+            Ok(XmlEvent::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+
+    // Close any elements left open by a truncated document
+    while stack.len() > 1 {
+        let node = stack.pop().unwrap();
+        push(&mut stack, node);
+    }
+    match stack.pop() {
+        Some(Node::Element { children, .. }) => children,
+        _ => Vec::new(),
+    }
+}
+
+/// Convert a page body in the Confluence storage format to Markdown
+///
+/// Besides the usual HTML elements this handles the `code`, `info`, `note`, `tip`,
+/// `warning` and `panel` macros, links to other pages, images and task lists. The body
+/// of any other macro is kept.
+pub fn confluence_storage_to_markdown(storage: &str) -> String {
+    blocks(&parse(storage), "\n\n")
+}
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "pre",
+    "blockquote",
+    "hr",
+    "table",
+    "div",
+    "ac:structured-macro",
+    "ac:task-list",
+    "ac:layout",
+    "ac:layout-section",
+    "ac:layout-cell",
+    "ac:rich-text-body",
+];
+
+/// Render a sequence of nodes as Markdown blocks joined by `separator`, wrapping runs of
+/// inline nodes in paragraphs
+fn blocks(nodes: &[Node], separator: &str) -> String {
+    let mut rendered = Vec::new();
+    let mut run: Vec<&Node> = Vec::new();
+    let flush = |run: &mut Vec<&Node>, rendered: &mut Vec<String>| {
+        let text = run.iter().map(|node| inline(node)).collect::<String>();
+        let text = text.trim();
+        if !text.is_empty() {
+            rendered.push(text.to_string());
+        }
+        run.clear();
+    };
+
+    for node in nodes {
+        let is_block = BLOCK_ELEMENTS.contains(&node.name())
+            // Inline macros like status lozenges stay in their paragraph
+            && !(node.name() == "ac:structured-macro" && is_inline_macro(node));
+        if !is_block {
+            run.push(node);
+            continue;
+        }
+        flush(&mut run, &mut rendered);
+        let block = block(node);
+        if !block.trim().is_empty() {
+            rendered.push(block);
+        }
+    }
+    flush(&mut run, &mut rendered);
+    rendered.join(separator)
+}
+
+fn is_inline_macro(node: &Node) -> bool {
+    matches!(
+        node.attribute("ac:name"),
+        Some("status" | "anchor" | "jira")
+    )
+}
+
+fn block(node: &Node) -> String {
+    match node.name() {
+        "p" => inline_children(node).trim().to_string(),
+        name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+            let level = name[1..].parse().unwrap_or(1);
+            format!("{} {}", "#".repeat(level), inline_children(node).trim())
+        }
+        "ul" => list(node, None),
+        "ol" => list(
+            node,
+            node.attribute("start")
+                .and_then(|s| s.parse().ok())
+                .or(Some(1)),
+        ),
+        "pre" => fence(&node.text(), ""),
+        "blockquote" => quote(&blocks(node.children(), "\n\n")),
+        "hr" => "---".to_string(),
+        "table" => table(node),
+        "ac:task-list" => node
+            .children()
+            .iter()
+            .filter(|task| task.name() == "ac:task")
+            .map(|task| {
+                let done = task
+                    .child("ac:task-status")
+                    .is_some_and(|status| status.text().trim() == "complete");
+                let body = task
+                    .child("ac:task-body")
+                    .map(inline_children)
+                    .unwrap_or_default();
+                format!("- [{}] {}", if done { "x" } else { " " }, body.trim())
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "ac:structured-macro" => structured_macro(node),
+        _ => blocks(node.children(), "\n\n"),
+    }
+}
+
+fn structured_macro(node: &Node) -> String {
+    let rich_body = || {
+        node.child("ac:rich-text-body")
+            .map(|body| blocks(body.children(), "\n\n"))
+            .unwrap_or_default()
+    };
+    let name = node.attribute("ac:name").unwrap_or_default();
+    match name {
+        "code" | "noformat" => fence(
+            &node
+                .child("ac:plain-text-body")
+                .map(Node::text)
+                .unwrap_or_default(),
+            &node.parameter("language").unwrap_or_default(),
+        ),
+        "info" | "note" | "tip" | "warning" | "panel" => {
+            let label = match name {
+                "panel" => String::new(),
+                _ => format!("{}{}", name[..1].to_uppercase(), &name[1..]),
+            };
+            let heading = match (label.is_empty(), node.parameter("title")) {
+                (false, Some(title)) => format!("**{}: {}**", label, title.trim()),
+                (false, None) => format!("**{}**", label),
+                (true, Some(title)) => format!("**{}**", title.trim()),
+                (true, None) => String::new(),
+            };
+            let body = rich_body();
+            let content = match (heading.is_empty(), body.is_empty()) {
+                (true, _) => body,
+                (false, true) => heading,
+                (false, false) => format!("{}\n\n{}", heading, body),
+            };
+            quote(&content)
+        }
+        _ => match node.child("ac:rich-text-body") {
+            Some(_) => rich_body(),
+            None => node
+                .child("ac:plain-text-body")
+                .map(|body| fence(&body.text(), ""))
+                .unwrap_or_default(),
+        },
+    }
+}
+
+fn fence(code: &str, language: &str) -> String {
+    let code = code.trim_matches('\n');
+    // The fence must be longer than any run of backticks in the code
+    let mut longest = 0;
+    let mut current = 0;
+    for c in code.chars() {
+        current = if c == '`' { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    let fence = "`".repeat((longest + 1).max(3));
+    format!("{}{}\n{}\n{}", fence, language.trim(), code, fence)
+}
+
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render a list, numbered from `start` for ordered lists
+fn list(node: &Node, start: Option<usize>) -> String {
+    node.children()
+        .iter()
+        .filter(|item| item.name() == "li")
+        .enumerate()
+        .map(|(index, item)| {
+            let marker = match start {
+                Some(start) => format!("{}. ", start + index),
+                None => "- ".to_string(),
+            };
+            let indent = " ".repeat(marker.len());
+            let content = blocks(item.children(), "\n");
+            let mut lines = content.lines();
+            let mut rendered = format!("{}{}", marker, lines.next().unwrap_or_default());
+            for line in lines {
+                rendered.push('\n');
+                if !line.is_empty() {
+                    rendered.push_str(&indent);
+                    rendered.push_str(line);
+                }
+            }
+            rendered
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn table(node: &Node) -> String {
+    fn collect_rows<'a>(node: &'a Node, rows: &mut Vec<&'a Node>) {
+        for child in node.children() {
+            match child.name() {
+                "tr" => rows.push(child),
+                "thead" | "tbody" | "tfoot" => collect_rows(child, rows),
+                _ => {}
+            }
+        }
+    }
+    let mut table_rows = Vec::new();
+    collect_rows(node, &mut table_rows);
+
+    let cells: Vec<Vec<String>> = table_rows
+        .iter()
+        .map(|row| {
+            row.children()
+                .iter()
+                .filter(|cell| matches!(cell.name(), "th" | "td"))
+                .map(|cell| {
+                    blocks(cell.children(), "<br>")
+                        .replace('\n', "<br>")
+                        .replace('|', "\\|")
+                })
+                .collect()
+        })
+        .filter(|row: &Vec<String>| !row.is_empty())
+        .collect();
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let line = |row: &[String]| {
+        let mut row = row.to_vec();
+        row.resize(columns, String::new());
+        format!("| {} |", row.join(" | "))
+    };
+    // Markdown tables need a header, so the first row is used even when it holds data
+    let mut lines = vec![line(&cells[0]), line(&vec!["---".to_string(); columns])];
+    lines.extend(cells[1..].iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+fn inline_children(node: &Node) -> String {
+    node.children().iter().map(inline).collect()
+}
+
+fn inline(node: &Node) -> String {
+    let wrap = |marker: &str| {
+        let content = inline_children(node);
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            return content;
+        }
+        // Keep the surrounding spaces outside of the markers
+        let leading = &content[..content.len() - content.trim_start().len()];
+        let trailing = &content[content.trim_end().len()..];
+        format!("{}{}{}{}{}", leading, marker, trimmed, marker, trailing)
+    };
+
+    match node {
+        Node::Text(text) => collapse_whitespace(text),
+        Node::Element { name, .. } => match name.as_str() {
+            "strong" | "b" => wrap("**"),
+            "em" | "i" => wrap("*"),
+            "s" | "del" | "strike" => wrap("~~"),
+            "code" => format!("`{}`", node.text()),
+            "br" => "\n".to_string(),
+            "a" => {
+                let text = inline_children(node);
+                match node.attribute("href") {
+                    Some(href) => format!("[{}]({})", text.trim(), href),
+                    None => text,
+                }
+            }
+            "ac:link" => {
+                let body = node
+                    .child("ac:link-body")
+                    .map(inline_children)
+                    .or_else(|| node.child("ac:plain-text-link-body").map(Node::text))
+                    .filter(|body| !body.trim().is_empty());
+                let target = node
+                    .child("ri:page")
+                    .and_then(|page| page.attribute("ri:content-title"))
+                    .or_else(|| {
+                        node.child("ri:attachment")
+                            .and_then(|attachment| attachment.attribute("ri:filename"))
+                    })
+                    .or_else(|| {
+                        node.child("ri:user")
+                            .and_then(|user| user.attribute("ri:account-id"))
+                    })
+                    .map(String::from);
+                body.or(target).unwrap_or_default()
+            }
+            "ac:image" => {
+                let source = node
+                    .child("ri:url")
+                    .and_then(|url| url.attribute("ri:value"))
+                    .or_else(|| {
+                        node.child("ri:attachment")
+                            .and_then(|attachment| attachment.attribute("ri:filename"))
+                    })
+                    .unwrap_or_default();
+                format!(
+                    "![{}]({})",
+                    node.attribute("ac:alt").unwrap_or_default(),
+                    source
+                )
+            }
+            "ac:emoticon" => node
+                .attribute("ac:emoji-fallback")
+                .or(node.attribute("ac:name"))
+                .unwrap_or_default()
+                .to_string(),
+            "ac:structured-macro" if node.attribute("ac:name") == Some("status") => {
+                format!("[{}]", node.parameter("title").unwrap_or_default().trim())
+            }
+            "ac:structured-macro" if is_inline_macro(node) => String::new(),
+            "ac:parameter" | "ac:placeholder" => String::new(),
+            name if BLOCK_ELEMENTS.contains(&name) => block(node),
+            _ => inline_children(node),
+        },
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        // Non-breaking spaces are kept, they are often used for indentation
+        if c.is_whitespace() && c != '\u{a0}' {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+/// Convert Markdown to the Confluence storage format
+///
+/// Fenced code blocks become code macros, so they render with syntax highlighting.
+pub fn markdown_to_confluence_storage(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut events = Vec::new();
+    let mut code: Option<(String, String)> = None;
+    for event in Parser::new_ext(markdown, options) {
+        match (event, code.as_mut()) {
+            (Event::Start(Tag::CodeBlock(kind)), _) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((language, String::new()));
+            }
+            (Event::Text(text), Some((_, body))) => body.push_str(&text),
+            (Event::End(TagEnd::CodeBlock), Some(_)) => {
+                let (language, body) = code.take().unwrap_or_default();
+                events.push(Event::Html(code_macro(&language, &body).into()));
+            }
+            (event, _) => events.push(event),
+        }
+    }
+
+    let mut storage = String::new();
+    pulldown_cmark::html::push_html(&mut storage, events.into_iter());
+    storage
+}
+
+fn code_macro(language: &str, code: &str) -> String {
+    let language = if language.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<ac:parameter ac:name="language">{}</ac:parameter>"#,
+            quick_xml::escape::escape(language)
+        )
+    };
+    format!(
+        r#"<ac:structured-macro ac:name="code">{}<ac:plain-text-body><![CDATA[{}]]></ac:plain-text-body></ac:structured-macro>"#,
+        language,
+        // A CDATA section cannot contain its own terminator, so it is split around it
+        code.trim_end_matches('\n')
+            .replace("]]>", "]]]]><![CDATA[>")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_code_blocks() {
+        let storage = indoc! {r#"
+            <p>Run it with:</p>
+            <ac:structured-macro ac:name="code" ac:schema-version="1">
+              <ac:parameter ac:name="language">rust</ac:parameter>
+              <ac:parameter ac:name="title">main.rs</ac:parameter>
+              <ac:plain-text-body><![CDATA[fn main() {
+                println!("<&>");
+            }]]></ac:plain-text-body>
+            </ac:structured-macro>
+            <pre>let x = 1;</pre>
+        "#};
+        assert_eq!(
+            confluence_storage_to_markdown(storage),
+            indoc! {r#"
+                Run it with:
+
+                ```rust
+                fn main() {
+                    println!("<&>");
+                }
+                ```
+
+                ```
+                let x = 1;
+                ```"#}
+        );
+
+        // Code macros survive a round trip through Markdown
+        let markdown = "```python\nprint(']]>')\n```";
+        let storage = markdown_to_confluence_storage(markdown);
+        assert!(storage.contains(r#"<ac:parameter ac:name="language">python</ac:parameter>"#));
+        assert_eq!(confluence_storage_to_markdown(&storage), markdown);
+    }
+
+    #[test]
+    fn test_tables() {
+        let storage = indoc! {r#"
+            <table data-layout="default"><colgroup><col /><col /></colgroup><tbody>
+              <tr><th><p><strong>Name</strong></p></th><th><p>Notes</p></th></tr>
+              <tr><td><p>alpha</p></td><td><p>first</p><p>a | b</p></td></tr>
+              <tr><td><p><code>beta</code></p></td></tr>
+            </tbody></table>
+        "#};
+        assert_eq!(
+            confluence_storage_to_markdown(storage),
+            indoc! {r#"
+                | **Name** | Notes |
+                | --- | --- |
+                | alpha | first<br>a \| b |
+                | `beta` |  |"#}
+        );
+
+        let storage = markdown_to_confluence_storage("| a | b |\n| - | - |\n| 1 | 2 |");
+        assert_eq!(
+            confluence_storage_to_markdown(&storage),
+            "| a | b |\n| --- | --- |\n| 1 | 2 |"
+        );
+    }
+
+    #[test]
+    fn test_macros_and_formatting() {
+        let storage = indoc! {r#"
+            <h2>Setup&nbsp;guide</h2>
+            <ac:structured-macro ac:name="warning">
+              <ac:parameter ac:name="title">Careful</ac:parameter>
+              <ac:rich-text-body><p>This deletes <em>everything</em>.</p></ac:rich-text-body>
+            </ac:structured-macro>
+            <ac:structured-macro ac:name="info"><ac:rich-text-body><p>Read the docs</p></ac:rich-text-body></ac:structured-macro>
+            <ac:structured-macro ac:name="panel">
+              <ac:parameter ac:name="title">Summary</ac:parameter>
+              <ac:rich-text-body><ul><li>one</li><li>two<ol><li>nested</li></ol></li></ul></ac:rich-text-body>
+            </ac:structured-macro>
+            <p>See <ac:link><ri:page ri:content-title="Home" /></ac:link> and <a href="https://example.com">the site</a>,
+            status <ac:structured-macro ac:name="status"><ac:parameter ac:name="title">Done</ac:parameter></ac:structured-macro>.</p>
+            <ac:task-list><ac:task><ac:task-status>complete</ac:task-status><ac:task-body>Ship it</ac:task-body></ac:task></ac:task-list>
+        "#};
+        assert_eq!(
+            confluence_storage_to_markdown(storage),
+            indoc! {r#"
+                ## Setup guide
+
+                > **Warning: Careful**
+                >
+                > This deletes *everything*.
+
+                > **Info**
+                >
+                > Read the docs
+
+                > **Summary**
+                >
+                > - one
+                > - two
+                >   1. nested
+
+                See Home and [the site](https://example.com), status [Done].
+
+                - [x] Ship it"#}
+            .replace("Setup guide", "Setup\u{a0}guide")
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use mcp_core::handler::ToolError;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+/// Default number of commits returned by the log operation
+pub const DEFAULT_MAX_LOG_ENTRIES: usize = 10;
+
+// Separators of the fields and records of the log format, which commit messages don't contain
+const FIELD_SEPARATOR: char = '\u{1f}';
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// Run git with the given arguments in the current directory, returning its output
+pub async fn run_git(args: &[&str]) -> Result<String, ToolError> {
+    let output = Command::new("git")
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(ToolError::ExecutionError(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The tracked and untracked files, but not those ignored by git, that the pathspecs name, or
+/// every file without pathspecs, relative to the current directory
+pub async fn ls_files(pathspecs: &[String]) -> Result<Vec<String>, ToolError> {
+    let mut args = vec![
+        "ls-files",
+        "-z",
+        "--cached",
+        "--others",
+        "--exclude-standard",
+        "--",
+    ];
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = run_git(&args).await?;
+    Ok(output
+        .split('\0')
+        .filter(|file| !file.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Arguments of `git log` for the latest commits, in the format read by [`parse_log`]
+pub fn log_args(max_entries: usize) -> Vec<String> {
+    vec![
+        "log".to_string(),
+        format!("--max-count={}", max_entries),
+        format!(
+            "--format=%H{sep}%an <%ae>{sep}%aI{sep}%B{end}",
+            sep = FIELD_SEPARATOR,
+            end = RECORD_SEPARATOR
+        ),
+    ]
+}
+
+/// Parse the output of `git log` with [`log_args`] into a hash, author, date and message
+/// per commit
+pub fn parse_log(output: &str) -> Vec<Value> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(4, FIELD_SEPARATOR);
+            let (hash, author, date, message) = (
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+            );
+            Some(json!({
+                "hash": hash,
+                "author": author,
+                "date": date,
+                "message": message.trim_end(),
+            }))
+        })
+        .collect()
+}
+
+/// Parse the output of `git status --porcelain=v1 --branch` into the branch and the
+/// status of each changed file
+pub fn parse_status(output: &str) -> Value {
+    let mut branch = Value::Null;
+    let mut files = Vec::new();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("## ") {
+            branch = json!(header);
+        } else if line.len() > 3 {
+            files.push(json!({
+                "index": line[..1].trim(),
+                "worktree": line[1..2].trim(),
+                "path": &line[3..],
+            }));
+        }
+    }
+    json!({ "branch": branch, "files": files })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = format!(
+            "abc123{s}Jane <jane@example.com>{s}2024-05-01T10:00:00+02:00{s}Fix the parser\n\nIt failed on empty input\n{e}\ndef456{s}Joe <joe@example.com>{s}2024-04-30T09:00:00+02:00{s}Initial commit\n{e}\n",
+            s = FIELD_SEPARATOR,
+            e = RECORD_SEPARATOR
+        );
+        assert_eq!(
+            parse_log(&output),
+            vec![
+                json!({
+                    "hash": "abc123",
+                    "author": "Jane <jane@example.com>",
+                    "date": "2024-05-01T10:00:00+02:00",
+                    "message": "Fix the parser\n\nIt failed on empty input",
+                }),
+                json!({
+                    "hash": "def456",
+                    "author": "Joe <joe@example.com>",
+                    "date": "2024-04-30T09:00:00+02:00",
+                    "message": "Initial commit",
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let status = parse_status(
+            "## main...origin/main [ahead 1]\nM  src/lib.rs\n M README.md\n?? notes.txt\n",
+        );
+        assert_eq!(
+            status,
+            json!({
+                "branch": "main...origin/main [ahead 1]",
+                "files": [
+                    {"index": "M", "worktree": "", "path": "src/lib.rs"},
+                    {"index": "", "worktree": "M", "path": "README.md"},
+                    {"index": "?", "worktree": "?", "path": "notes.txt"},
+                ]
+            })
+        );
+    }
+}
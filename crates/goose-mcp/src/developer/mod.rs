@@ -1,4 +1,7 @@
+mod git;
 mod lang;
+mod patch;
+mod screen;
 mod shell;
 
 use anyhow::Result;
@@ -13,7 +16,6 @@ use std::{
     path::{Path, PathBuf},
     pin::Pin,
 };
-use tokio::process::Command;
 use url::Url;
 
 use include_dir::{include_dir, Dir};
@@ -29,17 +31,18 @@ use mcp_core::{
     tool::ToolAnnotations,
 };
 use mcp_server::router::CapabilitiesBuilder;
-use mcp_server::Router;
+use mcp_server::{report_progress, Router};
 
 use mcp_core::role::Role;
 
+use self::screen::{crop_image, perform_ocr, CropRegion};
 use self::shell::{
-    expand_path, format_command_for_platform, get_shell_config, is_absolute_path,
-    normalize_line_endings,
+    expand_path, format_command_for_platform, is_absolute_path, is_write_command,
+    normalize_line_endings, run_shell_command, sanitize_command,
 };
 use indoc::indoc;
-use std::process::Stdio;
-use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex, RwLock};
 use xcap::{Monitor, Window};
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
@@ -47,55 +50,129 @@ use ignore::gitignore::{Gitignore, GitignoreBuilder};
 // Embeds the prompts directory to the build
 static PROMPTS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/developer/prompts");
 
+type PromptMap = Arc<HashMap<String, Prompt>>;
+
+/// Prompts shared by all routers, loaded on first use and replaced by `reload_prompts`
+static PROMPTS: Lazy<RwLock<Option<PromptMap>>> = Lazy::new(|| RwLock::new(None));
+
+/// Parses a prompt file and adds it to `prompts`, skipping invalid files and duplicate names.
+fn add_prompt_file(prompts: &mut HashMap<String, Prompt>, path: &Path, contents: &[u8]) {
+    let prompt_str = String::from_utf8_lossy(contents).into_owned();
+
+    let template: PromptTemplate = match serde_json::from_str(&prompt_str) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!(
+                "Failed to parse prompt template in {}: {}",
+                path.display(),
+                e
+            );
+            return; // Skip invalid prompt file
+        }
+    };
+
+    let arguments = template
+        .arguments
+        .into_iter()
+        .map(|arg| PromptArgument {
+            name: arg.name,
+            description: arg.description,
+            required: arg.required,
+        })
+        .collect::<Vec<PromptArgument>>();
+
+    let prompt = Prompt::new(&template.id, Some(&template.template), Some(arguments));
+
+    if prompts.contains_key(&prompt.name) {
+        eprintln!("Duplicate prompt name '{}' found. Skipping.", prompt.name);
+        return; // Skip duplicate prompt name
+    }
+
+    prompts.insert(prompt.name.clone(), prompt);
+}
+
 /// Loads prompt files from the embedded PROMPTS_DIR and returns a HashMap of prompts.
 /// Ensures that each prompt name is unique.
 pub fn load_prompt_files() -> HashMap<String, Prompt> {
     let mut prompts = HashMap::new();
 
     for entry in PROMPTS_DIR.files() {
-        let prompt_str = String::from_utf8_lossy(entry.contents()).into_owned();
-
-        let template: PromptTemplate = match serde_json::from_str(&prompt_str) {
-            Ok(t) => t,
-            Err(e) => {
-                eprintln!(
-                    "Failed to parse prompt template in {}: {}",
-                    entry.path().display(),
-                    e
-                );
-                continue; // Skip invalid prompt file
-            }
-        };
+        add_prompt_file(&mut prompts, entry.path(), entry.contents());
+    }
 
-        let arguments = template
-            .arguments
-            .into_iter()
-            .map(|arg| PromptArgument {
-                name: arg.name,
-                description: arg.description,
-                required: arg.required,
-            })
-            .collect::<Vec<PromptArgument>>();
+    prompts
+}
 
-        let prompt = Prompt::new(&template.id, Some(&template.template), Some(arguments));
+/// Loads prompt files from a directory on disk, the same way as the embedded prompts.
+pub fn load_prompt_files_from_dir(dir: &Path) -> HashMap<String, Prompt> {
+    let mut prompts = HashMap::new();
 
-        if prompts.contains_key(&prompt.name) {
-            eprintln!("Duplicate prompt name '{}' found. Skipping.", prompt.name);
-            continue; // Skip duplicate prompt name
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect(),
+        Err(e) => {
+            eprintln!("Failed to read prompt directory {}: {}", dir.display(), e);
+            return prompts;
+        }
+    };
+    // Sort so that the first of any duplicate names wins consistently
+    paths.sort();
+
+    for path in paths {
+        match std::fs::read(&path) {
+            Ok(contents) => add_prompt_file(&mut prompts, &path, &contents),
+            Err(e) => eprintln!("Failed to read prompt file {}: {}", path.display(), e),
         }
-
-        prompts.insert(prompt.name.clone(), prompt);
     }
 
     prompts
 }
 
+/// Loads the prompts from GOOSE_PROMPT_DIR when set, otherwise from the embedded prompts.
+fn load_prompts() -> HashMap<String, Prompt> {
+    match std::env::var_os("GOOSE_PROMPT_DIR") {
+        Some(dir) => load_prompt_files_from_dir(Path::new(&dir)),
+        None => load_prompt_files(),
+    }
+}
+
+/// Default limit on the characters of shell output and viewed files, overridden by
+/// GOOSE_MAX_OUTPUT_CHARS
+const DEFAULT_MAX_OUTPUT_CHARS: usize = 400_000; // 409600 chars = 400KB
+/// Default limit on the size of viewed files in KB, overridden by GOOSE_MAX_FILE_SIZE_KB
+const DEFAULT_MAX_FILE_SIZE_KB: u64 = 400;
+
+/// Default number of edits that can be undone per file, overridden by GOOSE_UNDO_DEPTH
+const DEFAULT_UNDO_DEPTH: usize = 10;
+
+/// Longest timeout the shell tool accepts, in seconds
+const MAX_SHELL_TIMEOUT_SECS: u64 = 300;
+
+/// Most commands the shell tool runs concurrently in one call
+const MAX_PARALLEL_COMMANDS: usize = 8;
+
+/// Reads a numeric limit from the environment, falling back to the default when unset or invalid.
+fn limit_from_env<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
+
 pub struct DeveloperRouter {
     tools: Vec<Tool>,
-    prompts: Arc<HashMap<String, Prompt>>,
     instructions: String,
     file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
     ignore_patterns: Arc<Gitignore>,
+    max_output_chars: usize,
+    max_file_size_bytes: u64,
+    /// Most previous versions kept per file for undo_edit
+    undo_depth: usize,
+    /// Timeout of shell commands that don't set one, from GOOSE_SHELL_TIMEOUT
+    shell_timeout_secs: Option<u64>,
+    allow_writes: bool,
 }
 
 impl Default for DeveloperRouter {
@@ -159,9 +236,20 @@ impl DeveloperRouter {
             shell_tool_desc.to_string(),
             json!({
                 "type": "object",
-                "required": ["command"],
+                "required": [],
                 "properties": {
-                    "command": {"type": "string"}
+                    "command": {"type": "string"},
+                    "commands": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "maxItems": MAX_PARALLEL_COMMANDS,
+                        "description": "Optional: independent commands to run concurrently instead of `command`. Returns a JSON array with the command, exit_code, stdout and stderr of each"
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "maximum": MAX_SHELL_TIMEOUT_SECS,
+                        "description": "Optional: kill the command if it runs for longer than this many seconds"
+                    }
                 }
             }),
             None,
@@ -173,10 +261,12 @@ impl DeveloperRouter {
                 Perform text editing operations on files.
 
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file, or only lines `start_line` to `end_line` of it.
                 - `write`: Create or overwrite a file with the given content
                 - `str_replace`: Replace a string in a file with a new string.
+                - `patch`: Apply a unified diff to a file.
                 - `undo_edit`: Undo the last edit made to a file.
+                - `undo_depth`: Get how many edits to a file can be undone.
 
                 To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
                 existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
@@ -184,6 +274,10 @@ impl DeveloperRouter {
                 To use the str_replace command, you must specify both `old_str` and `new_str` - the `old_str` needs to exactly match one
                 unique section of the original file, including any whitespace. Make sure to include enough context that the match is not
                 ambiguous. The entire original string will be replaced with `new_str`.
+
+                To use the patch command, you must specify `patch_text`, a unified diff of the file with `@@ -a,b +c,d @@` hunks.
+                Hunks whose context does not match the file are rejected and the others applied. Set `fuzzy` to true to match
+                context lines that only differ in whitespace.
             "#}.to_string(),
             json!({
                 "type": "object",
@@ -195,12 +289,71 @@ impl DeveloperRouter {
                     },
                     "command": {
                         "type": "string",
-                        "enum": ["view", "write", "str_replace", "undo_edit"],
-                        "description": "Allowed options are: `view`, `write`, `str_replace`, undo_edit`."
+                        "enum": ["view", "write", "str_replace", "patch", "undo_edit", "undo_depth"],
+                        "description": "Allowed options are: `view`, `write`, `str_replace`, `patch`, `undo_edit`, `undo_depth`."
                     },
                     "old_str": {"type": "string"},
                     "new_str": {"type": "string"},
-                    "file_text": {"type": "string"}
+                    "file_text": {"type": "string"},
+                    "patch_text": {"type": "string"},
+                    "fuzzy": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "For `patch`, match context lines that only differ in whitespace."
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "For `view`, the first line to show, counting from 1. Defaults to the start of the file."
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "For `view`, the last line to show, inclusive. Defaults to the end of the file."
+                    }
+                }
+            }),
+            None,
+        );
+
+        let git_tool = Tool::new(
+            "git",
+            indoc! {r#"
+                Run git operations in the current repository, with structured output.
+
+                The `operation` parameter specifies the operation to perform. Allowed options are:
+                - `status`: The current branch and the status of each changed file, as JSON.
+                - `diff`: The unified diff of unstaged changes, or staged ones with `staged`, limited to `paths` if given.
+                - `log`: The latest commits as JSON with hash, author, date and message, at most `max_log_entries`.
+                - `add`: Stage the given `paths`.
+                - `commit`: Commit the staged changes with the given `message`.
+                - `restore`: Discard the changes to the given `paths`, or unstage them with `staged`.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["status", "diff", "log", "add", "commit", "restore"]
+                    },
+                    "paths": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files for `diff`, `add` and `restore`, relative to the current directory or absolute"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "Commit message for `commit`"
+                    },
+                    "staged": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "For `diff`, show staged changes. For `restore`, unstage instead of discarding changes."
+                    },
+                    "max_log_entries": {
+                        "type": "integer",
+                        "default": git::DEFAULT_MAX_LOG_ENTRIES,
+                        "description": "Most commits returned by `log`"
+                    }
                 }
             }),
             None,
@@ -236,6 +389,9 @@ impl DeveloperRouter {
                 2. A specific window by its title using the window_title parameter
 
                 Only one of display or window_title should be specified.
+
+                Use crop_region to capture only part of the display or window, and perform_ocr
+                to also extract the text of the (cropped) image. OCR requires tesseract to be installed.
             "#},
             json!({
                 "type": "object",
@@ -250,6 +406,22 @@ impl DeveloperRouter {
                         "type": "string",
                         "default": null,
                         "description": "Optional: the exact title of the window to capture. use the list_windows tool to find the available windows."
+                    },
+                    "crop_region": {
+                        "type": "object",
+                        "description": "Optional: the region to keep, in logical pixels relative to the top-left of the display or window",
+                        "required": ["x", "y", "width", "height"],
+                        "properties": {
+                            "x": {"type": "integer"},
+                            "y": {"type": "integer"},
+                            "width": {"type": "integer"},
+                            "height": {"type": "integer"}
+                        }
+                    },
+                    "perform_ocr": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Also extract the text of the captured image with OCR"
                     }
                 }
             }),
@@ -421,22 +593,129 @@ impl DeveloperRouter {
             tools: vec![
                 bash_tool,
                 text_editor_tool,
+                git_tool,
                 list_windows_tool,
                 screen_capture_tool,
                 image_processor_tool,
             ],
-            prompts: Arc::new(load_prompt_files()),
             instructions,
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            max_output_chars: limit_from_env("GOOSE_MAX_OUTPUT_CHARS", DEFAULT_MAX_OUTPUT_CHARS),
+            max_file_size_bytes: limit_from_env("GOOSE_MAX_FILE_SIZE_KB", DEFAULT_MAX_FILE_SIZE_KB)
+                .saturating_mul(1024),
+            undo_depth: limit_from_env("GOOSE_UNDO_DEPTH", DEFAULT_UNDO_DEPTH),
+            shell_timeout_secs: Some(limit_from_env("GOOSE_SHELL_TIMEOUT", 0))
+                .filter(|&secs| secs > 0),
+            allow_writes: !std::env::var("GOOSE_DEVELOPER_READ_ONLY")
+                .map(|value| value.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Create a router that can view files and run shell commands, but refuses to edit
+    /// files or run shell commands that write files. Also enabled by
+    /// GOOSE_DEVELOPER_READ_ONLY=true.
+    pub fn new_read_only() -> Self {
+        Self {
+            allow_writes: false,
+            ..Self::new()
+        }
+    }
+
+    // Helper method to refuse write operations in read-only mode
+    fn check_writes_allowed(&self) -> Result<(), ToolError> {
+        if self.allow_writes {
+            Ok(())
+        } else {
+            Err(ToolError::ExecutionError(
+                "write operations disabled in read-only mode".to_string(),
+            ))
         }
     }
 
+    /// Create a router with the given limits on the characters of shell output and viewed
+    /// files, and on the size of viewed files, instead of those from the environment.
+    pub fn with_limits(max_output_chars: usize, max_file_size_bytes: u64) -> Self {
+        Self {
+            max_output_chars,
+            max_file_size_bytes,
+            ..Self::new()
+        }
+    }
+
+    // Helper method to get the prompts, loading them on first use
+    fn prompts(&self) -> PromptMap {
+        if let Some(prompts) = PROMPTS.read().unwrap().as_ref() {
+            return Arc::clone(prompts);
+        }
+        let mut cached = PROMPTS.write().unwrap();
+        Arc::clone(cached.get_or_insert_with(|| Arc::new(load_prompts())))
+    }
+
+    /// Discard the loaded prompts and read them again, e.g. after editing files in GOOSE_PROMPT_DIR
+    pub fn reload_prompts(&self) {
+        *PROMPTS.write().unwrap() = Some(Arc::new(load_prompts()));
+    }
+
     // Helper method to check if a path should be ignored
     fn is_ignored(&self, path: &Path) -> bool {
         self.ignore_patterns.matched(path, false).is_ignore()
     }
 
+    // Helper method to check if a file relative to `dir`, or a directory it is in, is ignored
+    fn is_ignored_file(&self, dir: &Path, file: &Path) -> bool {
+        self.ignore_patterns
+            .matched(dir.join(file), false)
+            .is_ignore()
+            || file
+                .ancestors()
+                .skip(1)
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .any(|parent| {
+                    self.ignore_patterns
+                        .matched(dir.join(parent), true)
+                        .is_ignore()
+                })
+    }
+
+    // Helper method to check that a path stays within GOOSE_TEXT_EDITOR_ROOT, when it is set
+    fn check_within_root(&self, path: &Path) -> Result<(), ToolError> {
+        let Some(root) = std::env::var_os("GOOSE_TEXT_EDITOR_ROOT") else {
+            return Ok(());
+        };
+        let root = std::fs::canonicalize(&root).map_err(|e| {
+            ToolError::ExecutionError(format!("Invalid GOOSE_TEXT_EDITOR_ROOT: {}", e))
+        })?;
+
+        // The file may not exist yet, so canonicalise its nearest existing ancestor
+        // (resolving symlinks and `..`) and re-attach the remaining components
+        let mut existing = path;
+        let mut remaining = Vec::new();
+        while !existing.exists() {
+            match (existing.parent(), existing.file_name()) {
+                (Some(parent), Some(name)) => {
+                    remaining.push(name);
+                    existing = parent;
+                }
+                _ => break,
+            }
+        }
+        let mut resolved = std::fs::canonicalize(existing)
+            .map_err(|_| ToolError::InvalidParameters("path escapes allowed root".into()))?;
+        for component in remaining.into_iter().rev() {
+            resolved.push(component);
+        }
+
+        if resolved.starts_with(&root) {
+            Ok(())
+        } else {
+            Err(ToolError::InvalidParameters(
+                "path escapes allowed root".into(),
+            ))
+        }
+    }
+
     // Helper method to resolve a path relative to cwd with platform-specific handling
     fn resolve_path(&self, path_str: &str) -> Result<PathBuf, ToolError> {
         let cwd = std::env::current_dir().expect("should have a current working dir");
@@ -455,19 +734,22 @@ impl DeveloperRouter {
         }
     }
 
-    // Shell command execution with platform-specific handling
-    async fn bash(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let command =
-            params
-                .get("command")
-                .and_then(|v| v.as_str())
-                .ok_or(ToolError::InvalidParameters(
-                    "The command string is required".to_string(),
-                ))?;
+    // Helper method to refuse shell commands that are unsafe, write in read-only mode, or
+    // access files restricted by .gooseignore
+    fn check_shell_command(&self, command: &str) -> Result<(), ToolError> {
+        let skip_sanitize = std::env::var("GOOSE_SHELL_SKIP_SANITIZE")
+            .map(|value| value.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !skip_sanitize {
+            sanitize_command(command)?;
+        }
+        if is_write_command(command) {
+            self.check_writes_allowed()?;
+        }
 
         // Check if command might access ignored files and return early if it does
         let cmd_parts: Vec<&str> = command.split_whitespace().collect();
-        for arg in &cmd_parts[1..] {
+        for arg in cmd_parts.iter().skip(1) {
             // Skip command flags
             if arg.starts_with('-') {
                 continue;
@@ -485,40 +767,140 @@ impl DeveloperRouter {
                 )));
             }
         }
+        Ok(())
+    }
 
-        // Get platform-specific shell configuration
-        let shell_config = get_shell_config();
-        let cmd_with_redirect = format_command_for_platform(command);
-
-        // Execute the command using platform-specific shell
-        let child = Command::new(&shell_config.executable)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .stdin(Stdio::null())
-            .kill_on_drop(true)
-            .arg(&shell_config.arg)
-            .arg(cmd_with_redirect)
-            .spawn()
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-
-        // Wait for the command to complete and get output
-        let output = child
-            .wait_with_output()
-            .await
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+    // Run independent shell commands concurrently, with the output of each as JSON
+    async fn bash_parallel(
+        &self,
+        commands: Vec<String>,
+        timeout_secs: Option<u64>,
+    ) -> Result<Vec<Content>, ToolError> {
+        if commands.is_empty() || commands.len() > MAX_PARALLEL_COMMANDS {
+            return Err(ToolError::InvalidParameters(format!(
+                "'commands' must hold between 1 and {} commands",
+                MAX_PARALLEL_COMMANDS
+            )));
+        }
+        for command in &commands {
+            self.check_shell_command(command)?;
+        }
+
+        let tasks: Vec<_> = commands
+            .iter()
+            .map(|command| {
+                let command = format_command_for_platform(command);
+                tokio::spawn(async move { run_shell_command(&command, timeout_secs, |_| {}).await })
+            })
+            .collect();
+        let mut results = Vec::with_capacity(tasks.len());
+        for (command, task) in commands.into_iter().zip(tasks) {
+            let result = task
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            let (exit_code, stdout, stderr) = match result {
+                Ok(output) if output.timed_out => (
+                    None,
+                    output.stdout,
+                    format!(
+                        "{}The command was killed after {} seconds",
+                        output.stderr,
+                        timeout_secs.unwrap_or_default()
+                    ),
+                ),
+                Ok(output) => (output.exit_code, output.stdout, output.stderr),
+                Err(e) => (None, String::new(), e),
+            };
+            let char_count = stdout.chars().count() + stderr.chars().count();
+            let (stdout, stderr) = if char_count > self.max_output_chars {
+                (
+                    String::new(),
+                    format!(
+                        "Shell output has too many characters ({}). Maximum character count is {}.",
+                        char_count, self.max_output_chars
+                    ),
+                )
+            } else {
+                (stdout, stderr)
+            };
+            results.push(json!({
+                "command": command,
+                "exit_code": exit_code,
+                "stdout": stdout,
+                "stderr": stderr,
+            }));
+        }
+
+        let output = serde_json::to_string_pretty(&results).unwrap();
+        Ok(vec![
+            Content::text(output.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    // Shell command execution with platform-specific handling
+    async fn bash(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let timeout_secs = match params.get("timeout_seconds") {
+            None | Some(Value::Null) => self.shell_timeout_secs,
+            Some(value) => Some(
+                value
+                    .as_u64()
+                    .filter(|secs| (1..=MAX_SHELL_TIMEOUT_SECS).contains(secs))
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters(format!(
+                            "'timeout_seconds' must be between 1 and {}",
+                            MAX_SHELL_TIMEOUT_SECS
+                        ))
+                    })?,
+            ),
+        };
+
+        if let Some(commands) = params.get("commands").filter(|v| !v.is_null()) {
+            let commands: Vec<String> = serde_json::from_value(commands.clone()).map_err(|_| {
+                ToolError::InvalidParameters("'commands' must be an array of strings".to_string())
+            })?;
+            return self.bash_parallel(commands, timeout_secs).await;
+        }
+
+        let command =
+            params
+                .get("command")
+                .and_then(|v| v.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The command string is required".to_string(),
+                ))?;
+        self.check_shell_command(command)?;
+
+        // stderr is redirected to stdout, so progress reports both as the command runs
+        let output = run_shell_command(
+            &format_command_for_platform(command),
+            timeout_secs,
+            |line| report_progress(None, line),
+        )
+        .await
+        .map_err(ToolError::ExecutionError)?;
+        if output.timed_out {
+            return Err(ToolError::ExecutionError(format!(
+                "The command '{}' was killed after {} seconds. Output so far:\n{}{}",
+                command,
+                timeout_secs.unwrap_or_default(),
+                output.stdout,
+                output.stderr
+            )));
+        }
 
-        let stdout_str = String::from_utf8_lossy(&output.stdout);
-        let output_str = stdout_str;
+        let output_str = output.stdout;
 
         // Check the character count of the output
-        const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
         let char_count = output_str.chars().count();
-        if char_count > MAX_CHAR_COUNT {
+        if char_count > self.max_output_chars {
             return Err(ToolError::ExecutionError(format!(
                     "Shell output from command '{}' has too many characters ({}). Maximum character count is {}.",
                     command,
                     char_count,
-                    MAX_CHAR_COUNT
+                    self.max_output_chars
                 )));
         }
 
@@ -553,8 +935,31 @@ impl DeveloperRouter {
             )));
         }
 
+        self.check_within_root(&path)?;
+
         match command {
-            "view" => self.text_editor_view(&path).await,
+            "view" => {
+                let line = |name: &str| -> Result<Option<usize>, ToolError> {
+                    match params.get(name) {
+                        None | Some(Value::Null) => Ok(None),
+                        Some(value) => value
+                            .as_u64()
+                            .filter(|&line| line > 0)
+                            .map(|line| Some(line as usize))
+                            .ok_or_else(|| {
+                                ToolError::InvalidParameters(format!(
+                                    "'{}' must be a line number of at least 1",
+                                    name
+                                ))
+                            }),
+                    }
+                };
+                let line_range = match (line("start_line")?, line("end_line")?) {
+                    (None, None) => None,
+                    (start, end) => Some((start.unwrap_or(1), end)),
+                };
+                self.text_editor_view(&path, line_range).await
+            }
             "write" => {
                 let file_text = params
                     .get("file_text")
@@ -581,7 +986,22 @@ impl DeveloperRouter {
 
                 self.text_editor_replace(&path, old_str, new_str).await
             }
+            "patch" => {
+                let patch_text = params
+                    .get("patch_text")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters("Missing 'patch_text' parameter".into())
+                    })?;
+                let fuzzy = params
+                    .get("fuzzy")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                self.text_editor_patch(&path, patch_text, fuzzy).await
+            }
             "undo_edit" => self.text_editor_undo(&path).await,
+            "undo_depth" => self.text_editor_undo_depth(&path).await,
             _ => Err(ToolError::InvalidParameters(format!(
                 "Unknown command '{}'",
                 command
@@ -589,23 +1009,27 @@ impl DeveloperRouter {
         }
     }
 
-    async fn text_editor_view(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+    /// View a file, or only the lines from the first of `line_range` to the second, or the end
+    /// of the file, numbered from 1
+    async fn text_editor_view(
+        &self,
+        path: &PathBuf,
+        line_range: Option<(usize, Option<usize>)>,
+    ) -> Result<Vec<Content>, ToolError> {
         if path.is_file() {
-            // Check file size first (400KB limit)
-            const MAX_FILE_SIZE: u64 = 400 * 1024; // 400KB in bytes
-            const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
-
+            // Check file size first
             let file_size = std::fs::metadata(path)
                 .map_err(|e| {
                     ToolError::ExecutionError(format!("Failed to get file metadata: {}", e))
                 })?
                 .len();
 
-            if file_size > MAX_FILE_SIZE {
+            if file_size > self.max_file_size_bytes {
                 return Err(ToolError::ExecutionError(format!(
-                    "File '{}' is too large ({:.2}KB). Maximum size is 400KB to prevent memory issues.",
+                    "File '{}' is too large ({:.2}KB). Maximum size is {}KB to prevent memory issues.",
                     path.display(),
-                    file_size as f64 / 1024.0
+                    file_size as f64 / 1024.0,
+                    self.max_file_size_bytes / 1024
                 )));
             }
 
@@ -616,13 +1040,38 @@ impl DeveloperRouter {
             let content = std::fs::read_to_string(path)
                 .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
 
+            let mut heading = path.display().to_string();
+            let content = match line_range {
+                None => content,
+                Some((start, end)) => {
+                    let lines: Vec<&str> = content.lines().collect();
+                    let end = end.unwrap_or(lines.len()).min(lines.len());
+                    if start > end {
+                        return Err(ToolError::InvalidParameters(format!(
+                            "Cannot show lines {}-{} of '{}', which has {} lines.",
+                            start,
+                            end,
+                            path.display(),
+                            lines.len()
+                        )));
+                    }
+                    heading = format!("{} (lines {}-{} of {})", heading, start, end, lines.len());
+
+                    let width = end.to_string().len();
+                    (start..=end)
+                        .map(|number| format!("{:>width$}: {}", number, lines[number - 1]))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            };
+
             let char_count = content.chars().count();
-            if char_count > MAX_CHAR_COUNT {
+            if char_count > self.max_output_chars {
                 return Err(ToolError::ExecutionError(format!(
                     "File '{}' has too many characters ({}). Maximum character count is {}.",
                     path.display(),
                     char_count,
-                    MAX_CHAR_COUNT
+                    self.max_output_chars
                 )));
             }
 
@@ -633,13 +1082,17 @@ impl DeveloperRouter {
                 {content}
                 ```
                 ",
-                path=path.display(),
+                path=heading,
                 language=language,
                 content=content,
             };
 
             // The LLM gets just a quick update as we expect the file to view in the status
             // but we send a low priority message for the human
+            let content = match line_range {
+                Some(_) => format!("### {}\n{}", heading, content),
+                None => content,
+            };
             Ok(vec![
                 Content::embedded_text(uri, content).with_audience(vec![Role::Assistant]),
                 Content::text(formatted)
@@ -659,6 +1112,8 @@ impl DeveloperRouter {
         path: &PathBuf,
         file_text: &str,
     ) -> Result<Vec<Content>, ToolError> {
+        self.check_writes_allowed()?;
+
         // Normalize line endings based on platform
         let normalized_text = normalize_line_endings(file_text);
 
@@ -695,6 +1150,8 @@ impl DeveloperRouter {
         old_str: &str,
         new_str: &str,
     ) -> Result<Vec<Content>, ToolError> {
+        self.check_writes_allowed()?;
+
         // Check if file exists and is active
         if !path.exists() {
             return Err(ToolError::InvalidParameters(format!(
@@ -783,7 +1240,80 @@ impl DeveloperRouter {
         ])
     }
 
+    async fn text_editor_patch(
+        &self,
+        path: &PathBuf,
+        patch_text: &str,
+        fuzzy: bool,
+    ) -> Result<Vec<Content>, ToolError> {
+        self.check_writes_allowed()?;
+
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "File '{}' does not exist, you can write a new file with the `write` command",
+                path.display()
+            )));
+        }
+
+        let hunks = patch::parse(patch_text)?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
+        let outcome = patch::apply(&content, &hunks, fuzzy);
+
+        let mut summary = format!(
+            "Applied {} of {} hunks to {}.",
+            outcome.applied.len(),
+            hunks.len(),
+            path.display()
+        );
+        for (index, line) in &outcome.applied {
+            summary.push_str(&format!(
+                "\n- Hunk {} `{}` applied at line {}",
+                index + 1,
+                hunks[*index].header,
+                line
+            ));
+        }
+        for (index, reason) in &outcome.rejected {
+            summary.push_str(&format!(
+                "\n- Hunk {} `{}` rejected: {}",
+                index + 1,
+                hunks[*index].header,
+                reason
+            ));
+        }
+
+        if outcome.applied.is_empty() {
+            return Err(ToolError::ExecutionError(format!(
+                "{}\nThe file was not changed. View it and make a patch against its current content{}.",
+                summary,
+                if fuzzy { "" } else { ", or retry with `fuzzy`" }
+            )));
+        }
+
+        // Save history for undo
+        self.save_file_history(path)?;
+
+        let normalized_content = normalize_line_endings(&outcome.content);
+        std::fs::write(path, &normalized_content)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+
+        if !outcome.rejected.is_empty() {
+            summary.push_str(
+                "\nView the file and make a new patch for the rejected hunks, or undo the edit.",
+            );
+        }
+        Ok(vec![
+            Content::text(summary.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
     async fn text_editor_undo(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        self.check_writes_allowed()?;
+
         let mut history = self.file_history.lock().unwrap();
         if let Some(contents) = history.get_mut(path) {
             if let Some(previous_content) = contents.pop() {
@@ -804,6 +1334,21 @@ impl DeveloperRouter {
         }
     }
 
+    async fn text_editor_undo_depth(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
+        let depth = self
+            .file_history
+            .lock()
+            .unwrap()
+            .get(path)
+            .map_or(0, |versions| versions.len());
+        Ok(vec![Content::text(format!(
+            "{} of at most {} edits to {} can be undone",
+            depth,
+            self.undo_depth,
+            path.display()
+        ))])
+    }
+
     fn save_file_history(&self, path: &PathBuf) -> Result<(), ToolError> {
         let mut history = self.file_history.lock().unwrap();
         let content = if path.exists() {
@@ -812,10 +1357,140 @@ impl DeveloperRouter {
         } else {
             String::new()
         };
-        history.entry(path.clone()).or_default().push(content);
+        let versions = history.entry(path.clone()).or_default();
+        versions.push(content);
+        // Drop the oldest versions beyond the undo depth
+        let excess = versions.len().saturating_sub(self.undo_depth);
+        versions.drain(..excess);
         Ok(())
     }
 
+    async fn git(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'operation' parameter".to_string())
+            })?;
+        let paths: Vec<String> = match params.get("paths") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(paths) => serde_json::from_value(paths.clone()).map_err(|_| {
+                ToolError::InvalidParameters("'paths' must be an array of strings".into())
+            })?,
+        };
+        let staged = params
+            .get("staged")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Files that are staged, restored or shown must not be hidden by .gooseignore, including
+        // those within directories or matched by globs, so the pathspecs are expanded to files
+        let mut hidden = Vec::new();
+        if matches!(operation, "add" | "restore" | "diff") {
+            if paths.is_empty() && operation != "diff" {
+                return Err(ToolError::InvalidParameters(format!(
+                    "'paths' is required for '{}'",
+                    operation
+                )));
+            }
+            let cwd = std::env::current_dir().expect("should have a current working dir");
+            for path in &paths {
+                self.check_within_root(&cwd.join(expand_path(path)))?;
+            }
+            for file in git::ls_files(&paths).await? {
+                if self.is_ignored_file(&cwd, Path::new(&file)) {
+                    hidden.push(file);
+                }
+            }
+            if let Some(file) = hidden.first().filter(|_| operation != "diff") {
+                return Err(ToolError::ExecutionError(format!(
+                    "Access to '{}' is restricted by .gooseignore",
+                    cwd.join(file).display()
+                )));
+            }
+        }
+
+        let output = match operation {
+            "status" => {
+                let output = git::run_git(&["status", "--porcelain=v1", "--branch"]).await?;
+                serde_json::to_string_pretty(&git::parse_status(&output)).unwrap()
+            }
+            "diff" => {
+                let mut args = vec!["diff".to_string()];
+                if staged {
+                    args.push("--staged".to_string());
+                }
+                args.push("--".to_string());
+                if paths.is_empty() {
+                    args.push(".".to_string());
+                }
+                args.extend(paths.iter().cloned());
+                // Hidden files are left out of the diff rather than failing it
+                args.extend(
+                    hidden
+                        .iter()
+                        .map(|file| format!(":(exclude,literal){}", file)),
+                );
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                let diff = git::run_git(&args).await?;
+                if diff.is_empty() {
+                    "No changes".to_string()
+                } else {
+                    diff
+                }
+            }
+            "log" => {
+                let max_entries = params
+                    .get("max_log_entries")
+                    .and_then(|v| v.as_u64())
+                    .map_or(git::DEFAULT_MAX_LOG_ENTRIES, |max| max as usize);
+                let args = git::log_args(max_entries);
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                let output = git::run_git(&args).await?;
+                serde_json::to_string_pretty(&git::parse_log(&output)).unwrap()
+            }
+            "add" | "restore" | "commit" => {
+                self.check_writes_allowed()?;
+                let mut args = vec![operation];
+                if operation == "commit" {
+                    let message =
+                        params
+                            .get("message")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| {
+                                ToolError::InvalidParameters("Missing 'message' parameter".into())
+                            })?;
+                    args.extend(["-m", message]);
+                } else {
+                    if operation == "restore" && staged {
+                        args.push("--staged");
+                    }
+                    args.push("--");
+                    args.extend(paths.iter().map(String::as_str));
+                }
+                let output = git::run_git(&args).await?;
+                if output.trim().is_empty() {
+                    format!("git {} succeeded", operation)
+                } else {
+                    output
+                }
+            }
+            _ => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Unknown operation '{}'",
+                    operation
+                )))
+            }
+        };
+
+        Ok(vec![
+            Content::text(output.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
     async fn list_windows(&self, _params: Value) -> Result<Vec<Content>, ToolError> {
         let windows = Window::all()
             .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
@@ -952,7 +1627,13 @@ impl DeveloperRouter {
     }
 
     async fn screen_capture(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let mut image = if let Some(window_title) =
+        let crop_region = CropRegion::from_params(&params)?;
+        let ocr = params
+            .get("perform_ocr")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let (mut image, scale_factor) = if let Some(window_title) =
             params.get("window_title").and_then(|v| v.as_str())
         {
             // Try to find and capture the specified window
@@ -969,12 +1650,13 @@ impl DeveloperRouter {
                     ))
                 })?;
 
-            window.capture_image().map_err(|e| {
+            let image = window.capture_image().map_err(|e| {
                 ToolError::ExecutionError(format!(
                     "Failed to capture window '{}': {}",
                     window_title, e
                 ))
-            })?
+            })?;
+            (image, window.current_monitor().scale_factor())
         } else {
             // Default to display capture if no window title is specified
             let display = params.get("display").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
@@ -989,9 +1671,19 @@ impl DeveloperRouter {
                 ))
             })?;
 
-            monitor.capture_image().map_err(|e| {
+            let image = monitor.capture_image().map_err(|e| {
                 ToolError::ExecutionError(format!("Failed to capture display {}: {}", display, e))
-            })?
+            })?;
+            (image, monitor.scale_factor())
+        };
+
+        if let Some(region) = crop_region {
+            image = crop_image(&image, region, scale_factor)?;
+        }
+        // Run OCR before resizing, on the full resolution image
+        let text = match ocr {
+            true => Some(perform_ocr(&image).await?),
+            false => None,
         };
 
         // Resize the image to a reasonable width while maintaining aspect ratio
@@ -1017,10 +1709,18 @@ impl DeveloperRouter {
         // Convert to base64
         let data = base64::prelude::BASE64_STANDARD.encode(bytes);
 
-        Ok(vec![
+        let mut contents = vec![
             Content::text("Screenshot captured").with_audience(vec![Role::Assistant]),
             Content::image(data, "image/png").with_priority(0.0),
-        ])
+        ];
+        if let Some(text) = text {
+            contents.push(Content::text(if text.is_empty() {
+                "No text found in the screenshot".to_string()
+            } else {
+                text
+            }));
+        }
+        Ok(contents)
     }
 }
 
@@ -1055,6 +1755,7 @@ impl Router for DeveloperRouter {
             match tool_name.as_str() {
                 "shell" => this.bash(arguments).await,
                 "text_editor" => this.text_editor(arguments).await,
+                "git" => this.git(arguments).await,
                 "list_windows" => this.list_windows(arguments).await,
                 "screen_capture" => this.screen_capture(arguments).await,
                 "image_processor" => this.image_processor(arguments).await,
@@ -1076,7 +1777,7 @@ impl Router for DeveloperRouter {
     }
 
     fn list_prompts(&self) -> Vec<Prompt> {
-        self.prompts.values().cloned().collect()
+        self.prompts().values().cloned().collect()
     }
 
     fn get_prompt(
@@ -1094,7 +1795,7 @@ impl Router for DeveloperRouter {
             });
         }
 
-        let prompts = Arc::clone(&self.prompts);
+        let prompts = self.prompts();
 
         Box::pin(async move {
             match prompts.get(&prompt_name) {
@@ -1111,10 +1812,14 @@ impl Clone for DeveloperRouter {
     fn clone(&self) -> Self {
         Self {
             tools: self.tools.clone(),
-            prompts: Arc::clone(&self.prompts),
             instructions: self.instructions.clone(),
             file_history: Arc::clone(&self.file_history),
             ignore_patterns: Arc::clone(&self.ignore_patterns),
+            max_output_chars: self.max_output_chars,
+            max_file_size_bytes: self.max_file_size_bytes,
+            undo_depth: self.undo_depth,
+            shell_timeout_secs: self.shell_timeout_secs,
+            allow_writes: self.allow_writes,
         }
     }
 }
@@ -1211,6 +1916,70 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    #[cfg(not(windows))]
+    async fn test_shell_streams_progress_before_result() {
+        use mcp_core::protocol::{JsonRpcMessage, ToolProgressEvent};
+        use mcp_server::router::RouterService;
+        use mcp_server::{ByteTransport, Server};
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server);
+        let server = Server::new(RouterService(DeveloperRouter::new()));
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "shell",
+                "arguments": {"command": "for i in 1 2 3 4 5; do echo line $i; done"}
+            }
+        });
+
+        // Dropping the client's end once the response arrives stops the server
+        let client = async move {
+            client_write
+                .write_all(format!("{}\n", request).as_bytes())
+                .await
+                .unwrap();
+
+            let mut lines = BufReader::new(client_read).lines();
+            let mut progress = Vec::new();
+            loop {
+                let line = lines.next_line().await.unwrap().unwrap();
+                match serde_json::from_str::<JsonRpcMessage>(&line).unwrap() {
+                    JsonRpcMessage::Notification(notification) => {
+                        progress.push(ToolProgressEvent::from_notification(&notification).unwrap());
+                    }
+                    JsonRpcMessage::Response(response) => return (progress, response),
+                    other => panic!("Unexpected message: {:?}", other),
+                }
+            }
+        };
+        let (_, (progress, result)) = tokio::join!(
+            server.run(ByteTransport::new(server_read, server_write)),
+            client
+        );
+
+        assert_eq!(result.id, Some(1));
+        assert_eq!(progress.len(), 5);
+        for (i, event) in progress.iter().enumerate() {
+            assert_eq!(event.tool_name, "shell");
+            assert_eq!(event.message, format!("line {}", i + 1));
+        }
+        let output = result.result.unwrap().to_string();
+        assert!(output.contains("line 5"));
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     #[cfg(windows)]
@@ -1237,6 +2006,101 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    #[serial]
+    #[cfg(unix)]
+    async fn test_shell_timeout() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let router = get_router().await;
+
+        let started = std::time::Instant::now();
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": "echo started && sleep 300",
+                    "timeout_seconds": 2
+                }),
+            )
+            .await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(3));
+        let err = result.unwrap_err();
+        assert!(matches!(err, ToolError::ExecutionError(_)));
+        assert!(err.to_string().contains("killed after 2 seconds"));
+        assert!(err.to_string().contains("started"));
+
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": "echo done",
+                    "timeout_seconds": 301
+                }),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
+        let result = router
+            .call_tool(
+                "shell",
+                json!({
+                    "command": "echo done",
+                    "timeout_seconds": 5
+                }),
+            )
+            .await
+            .unwrap();
+        assert!(result[0].as_text().unwrap().contains("done"));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    #[cfg(unix)]
+    async fn test_shell_parallel_commands() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let router = get_router().await;
+
+        let commands: Vec<String> = (1..=4)
+            .map(|i| format!("sleep 1 && echo lint {}", i))
+            .chain(["echo failed >&2 && exit 3".to_string()])
+            .collect();
+        let started = std::time::Instant::now();
+        let result = router
+            .call_tool("shell", json!({ "commands": commands }))
+            .await
+            .unwrap();
+        // Run one after the other, the commands would take at least 4 seconds
+        assert!(started.elapsed() < std::time::Duration::from_secs(3));
+
+        let results: Vec<Value> = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(results.len(), 5);
+        for (i, result) in results[..4].iter().enumerate() {
+            assert_eq!(result["command"], commands[i]);
+            assert_eq!(result["exit_code"], 0);
+            assert_eq!(result["stdout"], format!("lint {}\n", i + 1));
+        }
+        assert_eq!(results[4]["exit_code"], 3);
+        assert_eq!(results[4]["stdout"], "");
+        assert_eq!(results[4]["stderr"], "failed\n");
+
+        let result = router
+            .call_tool("shell", json!({ "commands": "echo not an array" }))
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
+        let too_many = vec!["true"; MAX_PARALLEL_COMMANDS + 1];
+        let result = router
+            .call_tool("shell", json!({ "commands": too_many }))
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_size_limits() {
@@ -1300,6 +2164,118 @@ mod tests {
         // Let temp_dir drop naturally at end of scope
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_configured_limits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = DeveloperRouter::with_limits(10, 400 * 1024);
+
+        let file_path = temp_dir.path().join("twenty.txt");
+        std::fs::write(&file_path, "x".repeat(20)).unwrap();
+
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path.to_str().unwrap()
+                }),
+            )
+            .await;
+
+        let err = result.err().unwrap();
+        assert!(matches!(err, ToolError::ExecutionError(_)));
+        assert!(
+            err.to_string()
+                .contains("has too many characters (20). Maximum character count is 10."),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_is_write_command() {
+        for command in [
+            "rm -rf build",
+            "echo hi > out.txt",
+            "cat a.txt >> b.txt",
+            "ls && mkdir new",
+            "FOO=1 /bin/cp a b",
+            "sed -i 's/a/b/' file.txt",
+            "git commit -m message",
+            "echo $(touch x)",
+        ] {
+            assert!(is_write_command(command), "{}", command);
+        }
+        for command in [
+            "ls -la",
+            "cat file.txt | grep needle",
+            "rg 'a > b' src",
+            "ls missing 2>&1",
+            "find . -name '*.rs' 2> /dev/null",
+            "git status",
+            "sed 's/a/b/' file.txt",
+        ] {
+            assert!(!is_write_command(command), "{}", command);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_read_only_mode() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = DeveloperRouter::new_read_only();
+
+        let file_path = temp_dir.path().join("existing.txt");
+        std::fs::write(&file_path, "hello").unwrap();
+        let file_path_str = file_path.to_str().unwrap();
+
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": temp_dir.path().join("new.txt").to_str().unwrap(),
+                    "file_text": "Hello, world!"
+                }),
+            )
+            .await;
+        let err = result.err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("write operations disabled in read-only mode"));
+        assert!(!temp_dir.path().join("new.txt").exists());
+
+        let result = router
+            .call_tool("shell", json!({"command": "touch created.txt"}))
+            .await;
+        let err = result.err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("write operations disabled in read-only mode"));
+        assert!(!temp_dir.path().join("created.txt").exists());
+
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "view", "path": file_path_str}),
+            )
+            .await;
+        assert!(result.is_ok());
+
+        let result = router
+            .call_tool("shell", json!({"command": "cat existing.txt"}))
+            .await
+            .unwrap();
+        assert!(result
+            .iter()
+            .any(|content| content.as_text().is_some_and(|text| text.contains("hello"))));
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_write_and_view_file() {
@@ -1350,6 +2326,170 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_line_range() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("long.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let content: Vec<String> = (1..=10_000).map(|i| format!("line {}", i)).collect();
+        std::fs::write(&file_path, content.join("\n")).unwrap();
+
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str,
+                    "start_line": 50,
+                    "end_line": 100
+                }),
+            )
+            .await
+            .unwrap();
+
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        assert!(text.contains("(lines 50-100 of 10000)"));
+        assert!(text.contains(" 50: line 50\n"));
+        assert!(text.contains("100: line 100\n"));
+        assert!(!text.contains("line 49\n"));
+        assert!(!text.contains("line 101"));
+
+        // The end defaults to the end of the file
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "view",
+                    "path": file_path_str,
+                    "start_line": 9999
+                }),
+            )
+            .await
+            .unwrap();
+        let text = view_result
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::User))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        assert!(text.contains("(lines 9999-10000 of 10000)"));
+
+        for params in [
+            json!({"start_line": 0}),
+            json!({"start_line": 200, "end_line": 100}),
+            json!({"start_line": 20_000}),
+        ] {
+            let mut arguments = json!({"command": "view", "path": file_path_str});
+            arguments
+                .as_object_mut()
+                .unwrap()
+                .extend(params.as_object().unwrap().clone());
+            let result = router.call_tool("text_editor", arguments).await;
+            assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+        }
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_patch_and_undo() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let original = indoc! {"
+            pub fn add(a: i32, b: i32) -> i32 {
+                a + b
+            }
+
+            pub fn sub(a: i32, b: i32) -> i32 {
+                a - b
+            }
+        "};
+        std::fs::write(&file_path, original).unwrap();
+
+        let patch = indoc! {"
+            --- a/lib.rs
+            +++ b/lib.rs
+            @@ -1,3 +1,4 @@
+            +/// Adds two numbers
+             pub fn add(a: i32, b: i32) -> i32 {
+                 a + b
+             }
+            @@ -5,3 +6,3 @@
+             pub fn sub(a: i32, b: i32) -> i32 {
+            -    a - b
+            +    a.saturating_sub(b)
+             }
+        "};
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "patch",
+                    "path": file_path_str,
+                    "patch_text": patch
+                }),
+            )
+            .await
+            .unwrap();
+        let text = result[0].as_text().unwrap();
+        assert!(text.contains("Applied 2 of 2 hunks"), "{}", text);
+        let patched = std::fs::read_to_string(&file_path).unwrap();
+        assert!(patched.starts_with("/// Adds two numbers\npub fn add"));
+        assert!(patched.contains("    a.saturating_sub(b)\n"));
+
+        // A patch that matches nothing leaves the file alone
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "patch",
+                    "path": file_path_str,
+                    "patch_text": "@@ -2,1 +2,1 @@\n-    a * b\n+    a / b\n"
+                }),
+            )
+            .await;
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Applied 0 of 1 hunks"), "{}", err);
+        assert!(err.contains("rejected"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), patched);
+
+        router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "undo_edit",
+                    "path": file_path_str
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), original);
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_str_replace() {
@@ -1425,6 +2565,71 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_undo_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = DeveloperRouter {
+            undo_depth: 3,
+            ..DeveloperRouter::new()
+        };
+
+        let undo_depth = || async {
+            let result = router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "undo_depth",
+                        "path": file_path_str
+                    }),
+                )
+                .await
+                .unwrap();
+            result[0].as_text().unwrap().to_string()
+        };
+        assert!(undo_depth().await.starts_with("0 of at most 3 edits"));
+
+        std::fs::write(&file_path, "version 0").unwrap();
+        for version in 1..=5 {
+            router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "str_replace",
+                        "path": file_path_str,
+                        "old_str": format!("version {}", version - 1),
+                        "new_str": format!("version {}", version)
+                    }),
+                )
+                .await
+                .unwrap();
+        }
+        assert!(undo_depth().await.starts_with("3 of at most 3 edits"));
+        assert_eq!(router.file_history.lock().unwrap()[&file_path].len(), 3);
+
+        // Only the latest edits can be undone
+        for _ in 0..3 {
+            router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "undo_edit",
+                        "path": file_path_str
+                    }),
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "version 2");
+        assert!(undo_depth().await.starts_with("0 of at most 3 edits"));
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_undo_edit() {
@@ -1518,10 +2723,14 @@ mod tests {
 
         let router = DeveloperRouter {
             tools: vec![],
-            prompts: Arc::new(HashMap::new()),
             instructions: String::new(),
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            max_output_chars: DEFAULT_MAX_OUTPUT_CHARS,
+            max_file_size_bytes: DEFAULT_MAX_FILE_SIZE_KB * 1024,
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            shell_timeout_secs: None,
+            allow_writes: true,
         };
 
         // Test basic file matching
@@ -1555,6 +2764,103 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_git_add_commit_and_log() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        for args in [
+            &["init", "--quiet"][..],
+            &["config", "user.name", "Goose"],
+            &["config", "user.email", "goose@example.com"],
+        ] {
+            git::run_git(args).await.unwrap();
+        }
+
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "secret.txt").unwrap();
+        let router = DeveloperRouter {
+            ignore_patterns: Arc::new(builder.build().unwrap()),
+            ..DeveloperRouter::new()
+        };
+        let git = |arguments: Value| {
+            let router = router.clone();
+            async move {
+                router
+                    .call_tool("git", arguments)
+                    .await
+                    .map(|content| content[0].as_text().unwrap().to_string())
+            }
+        };
+
+        std::fs::write(temp_dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("secret.txt"), "hunter2").unwrap();
+
+        let status: Value =
+            serde_json::from_str(&git(json!({"operation": "status"})).await.unwrap()).unwrap();
+        let files = status["files"].as_array().unwrap();
+        assert!(files
+            .iter()
+            .any(|file| file["path"] == "main.rs" && file["worktree"] == "?"));
+
+        let result = git(json!({"operation": "add", "paths": ["secret.txt"]})).await;
+        assert!(result.unwrap_err().to_string().contains(".gooseignore"));
+
+        git(json!({"operation": "add", "paths": ["main.rs"]}))
+            .await
+            .unwrap();
+        let diff = git(json!({"operation": "diff", "staged": true}))
+            .await
+            .unwrap();
+        assert!(diff.contains("+fn main() {}"), "{}", diff);
+
+        git(json!({"operation": "commit", "message": "Add main"}))
+            .await
+            .unwrap();
+        let log: Value =
+            serde_json::from_str(&git(json!({"operation": "log"})).await.unwrap()).unwrap();
+        let log = log.as_array().unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0]["message"], "Add main");
+        assert_eq!(log[0]["author"], "Goose <goose@example.com>");
+        assert_eq!(log[0]["hash"].as_str().unwrap().len(), 40);
+
+        let result = git(json!({"operation": "commit"})).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
+        // Directories and globs are expanded to the files they name
+        for paths in [json!(["."]), json!(["*.txt"])] {
+            let result = git(json!({"operation": "add", "paths": paths})).await;
+            assert!(result.unwrap_err().to_string().contains(".gooseignore"));
+        }
+
+        // Hidden files committed outside of goose are left out of diffs and cannot be restored
+        git::run_git(&["add", "--force", "secret.txt"])
+            .await
+            .unwrap();
+        git::run_git(&["commit", "--quiet", "-m", "Add secret"])
+            .await
+            .unwrap();
+        std::fs::write(temp_dir.path().join("main.rs"), "fn main() { run() }\n").unwrap();
+        std::fs::write(temp_dir.path().join("secret.txt"), "hunter3").unwrap();
+        for diff in [
+            json!({"operation": "diff"}),
+            json!({"operation": "diff", "paths": ["."]}),
+        ] {
+            let diff = git(diff).await.unwrap();
+            assert!(diff.contains("+fn main() { run() }"), "{}", diff);
+            assert!(!diff.contains("hunter"), "{}", diff);
+        }
+        let diff = git(json!({"operation": "diff", "paths": ["secret.txt"]}))
+            .await
+            .unwrap();
+        assert_eq!(diff, "No changes");
+        let result = git(json!({"operation": "restore", "paths": ["."]})).await;
+        assert!(result.unwrap_err().to_string().contains(".gooseignore"));
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_respects_ignore_patterns() {
@@ -1568,10 +2874,14 @@ mod tests {
 
         let router = DeveloperRouter {
             tools: DeveloperRouter::new().tools, // Reuse default tools
-            prompts: Arc::new(HashMap::new()),
             instructions: String::new(),
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            max_output_chars: DEFAULT_MAX_OUTPUT_CHARS,
+            max_file_size_bytes: DEFAULT_MAX_FILE_SIZE_KB * 1024,
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            shell_timeout_secs: None,
+            allow_writes: true,
         };
 
         // Try to write to an ignored file
@@ -1625,10 +2935,14 @@ mod tests {
 
         let router = DeveloperRouter {
             tools: DeveloperRouter::new().tools, // Reuse default tools
-            prompts: Arc::new(HashMap::new()),
             instructions: String::new(),
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            max_output_chars: DEFAULT_MAX_OUTPUT_CHARS,
+            max_file_size_bytes: DEFAULT_MAX_FILE_SIZE_KB * 1024,
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            shell_timeout_secs: None,
+            allow_writes: true,
         };
 
         // Create an ignored file
@@ -1665,4 +2979,146 @@ mod tests {
 
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn test_sanitize_command() {
+        assert!(matches!(
+            sanitize_command("cat $(echo secret.txt)"),
+            Err(ToolError::ExecutionError(_))
+        ));
+        assert!(sanitize_command("head -n 5 `ls ./private/*`").is_err());
+        assert!(sanitize_command("ls && cat $(find . -name secret.txt)").is_err());
+
+        assert!(sanitize_command("echo hello | tr a-z A-Z").is_ok());
+        // Substitutions without paths, in single quotes, or in command position are allowed
+        assert!(sanitize_command("git commit -m \"release $(date)\"").is_ok());
+        assert!(sanitize_command("echo '$(cat secret.txt)'").is_ok());
+        assert!(sanitize_command("$(which python3) --version").is_ok());
+    }
+
+    #[tokio::test]
+    #[serial]
+    #[cfg(not(windows))]
+    async fn test_bash_sanitize_opt_out() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "notes").unwrap();
+
+        let router = get_router().await;
+        let command = json!({ "command": "cat $(echo notes.txt)" });
+
+        let result = router.call_tool("shell", command.clone()).await;
+        assert!(
+            result.is_err(),
+            "Substitution should be rejected by default"
+        );
+
+        std::env::set_var("GOOSE_SHELL_SKIP_SANITIZE", "true");
+        let result = router.call_tool("shell", command).await;
+        std::env::remove_var("GOOSE_SHELL_SKIP_SANITIZE");
+        assert!(
+            result.is_ok(),
+            "Sanitizing should be skipped when opted out"
+        );
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_respects_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let root = temp_dir.path().join("root");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(temp_dir.path().join("outside.txt"), "outside").unwrap();
+
+        let router = get_router().await;
+        std::env::set_var("GOOSE_TEXT_EDITOR_ROOT", &root);
+
+        let traversal = root.join("sub/../../outside.txt");
+        let write_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": traversal.to_str().unwrap(),
+                    "file_text": "overwritten"
+                }),
+            )
+            .await;
+        let view_result = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "view", "path": traversal.to_str().unwrap()}),
+            )
+            .await;
+
+        let inside = root.join("sub/file.txt");
+        let inside_result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "write",
+                    "path": inside.to_str().unwrap(),
+                    "file_text": "inside"
+                }),
+            )
+            .await;
+
+        std::env::remove_var("GOOSE_TEXT_EDITOR_ROOT");
+
+        assert!(
+            matches!(write_result, Err(ToolError::InvalidParameters(ref msg)) if msg == "path escapes allowed root")
+        );
+        assert!(matches!(view_result, Err(ToolError::InvalidParameters(_))));
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("outside.txt")).unwrap(),
+            "outside"
+        );
+
+        assert!(
+            inside_result.is_ok(),
+            "Writes within the root should succeed"
+        );
+        assert_eq!(std::fs::read_to_string(&inside).unwrap(), "inside");
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_prompts_loaded_from_prompt_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join("custom.json"),
+            json!({
+                "id": "custom_prompt",
+                "template": "Do the custom thing",
+                "arguments": []
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let router = get_router().await;
+        std::env::set_var("GOOSE_PROMPT_DIR", temp_dir.path());
+        router.reload_prompts();
+        let names: Vec<String> = router
+            .list_prompts()
+            .into_iter()
+            .map(|prompt| prompt.name)
+            .collect();
+
+        std::env::remove_var("GOOSE_PROMPT_DIR");
+        router.reload_prompts();
+
+        assert_eq!(names, vec!["custom_prompt".to_string()]);
+        assert!(router
+            .list_prompts()
+            .iter()
+            .any(|prompt| prompt.name == "unit_test"));
+
+        temp_dir.close().unwrap();
+    }
 }
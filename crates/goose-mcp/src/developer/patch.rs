@@ -0,0 +1,266 @@
+use mcp_core::handler::ToolError;
+
+/// A line of a hunk, without its leading marker
+#[derive(Debug, Clone, PartialEq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// A hunk of a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// The `@@ -a,b +c,d @@` line the hunk starts with
+    pub header: String,
+    /// First line of the hunk in the original file, counting from 1
+    old_start: usize,
+    lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_len(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| !matches!(line, HunkLine::Remove(_)))
+            .count()
+    }
+}
+
+/// What happened to each hunk of a patch
+#[derive(Debug, Default, PartialEq)]
+pub struct PatchOutcome {
+    /// The patched content
+    pub content: String,
+    /// Index and line in the patched content of each applied hunk
+    pub applied: Vec<(usize, usize)>,
+    /// Index of each hunk that could not be applied, with the reason
+    pub rejected: Vec<(usize, String)>,
+}
+
+/// Parse the hunks of a unified diff of a single file, ignoring any file headers
+pub fn parse(patch: &str) -> Result<Vec<Hunk>, ToolError> {
+    let patch = diffy::Patch::from_str(patch)
+        .map_err(|e| ToolError::InvalidParameters(format!("Invalid unified diff: {}", e)))?;
+    if patch.hunks().is_empty() {
+        return Err(ToolError::InvalidParameters(
+            "The patch has no hunks, expected a unified diff with `@@ -a,b +c,d @@` lines".into(),
+        ));
+    }
+
+    // diffy keeps the newline of each line, which the file lines are compared without
+    let text = |line: &str| line.trim_end_matches(['\r', '\n']).to_string();
+    Ok(patch
+        .hunks()
+        .iter()
+        .map(|hunk| Hunk {
+            header: format!("@@ -{} +{} @@", hunk.old_range(), hunk.new_range()),
+            old_start: hunk.old_range().start(),
+            lines: hunk
+                .lines()
+                .iter()
+                .map(|line| match line {
+                    diffy::Line::Context(line) => HunkLine::Context(text(line)),
+                    diffy::Line::Delete(line) => HunkLine::Remove(text(line)),
+                    diffy::Line::Insert(line) => HunkLine::Add(text(line)),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Apply hunks to content in order, each at the line it names or the closest one after the
+/// previous hunk where its context and removed lines match.
+///
+/// With `fuzzy`, lines match when they only differ in whitespace, and the file's version of
+/// context lines is kept.
+pub fn apply(content: &str, hunks: &[Hunk], fuzzy: bool) -> PatchOutcome {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let same = |a: &str, b: &str| {
+        if fuzzy {
+            a.split_whitespace().eq(b.split_whitespace())
+        } else {
+            a == b
+        }
+    };
+
+    let mut outcome = PatchOutcome::default();
+    // Lines added minus lines removed so far, to find later hunks in the patched content
+    let mut offset: isize = 0;
+    // Hunks cannot apply before the end of the previous one
+    let mut min_position = 0;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old_lines = hunk.old_lines();
+        // A hunk without original lines inserts after its start line
+        let start = if old_lines.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = start.saturating_add_signed(offset).max(min_position);
+
+        let matches_at = |position: usize| {
+            old_lines
+                .iter()
+                .zip(&lines[position..])
+                .all(|(old, line)| same(old, line))
+        };
+        let position = lines.len().checked_sub(old_lines.len()).and_then(|last| {
+            let expected = expected.min(last);
+            (0..=last)
+                .flat_map(|distance| {
+                    [
+                        expected.checked_add(distance),
+                        expected.checked_sub(distance),
+                    ]
+                })
+                .flatten()
+                .filter(|&position| position >= min_position && position <= last)
+                .find(|&position| matches_at(position))
+        });
+        let Some(position) = position else {
+            outcome.rejected.push((
+                index,
+                "its context and removed lines do not match the file".to_string(),
+            ));
+            continue;
+        };
+
+        let mut replacement = Vec::with_capacity(hunk.new_len());
+        let mut file_lines = lines[position..position + old_lines.len()].iter();
+        for line in &hunk.lines {
+            match line {
+                HunkLine::Context(_) => replacement.push(file_lines.next().unwrap().clone()),
+                HunkLine::Remove(_) => {
+                    file_lines.next();
+                }
+                HunkLine::Add(text) => replacement.push(text.clone()),
+            }
+        }
+        let new_len = replacement.len();
+        lines.splice(position..position + old_lines.len(), replacement);
+
+        offset = position as isize - start as isize + new_len as isize - old_lines.len() as isize;
+        min_position = position + new_len;
+        outcome.applied.push((index, position + 1));
+    }
+
+    outcome.content = lines.join("\n");
+    if content.ends_with('\n') || (content.is_empty() && !outcome.content.is_empty()) {
+        outcome.content.push('\n');
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n\nfn helper() -> u32 {\n    42\n}\n";
+
+    const PATCH: &str = "\
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,4 +1,5 @@
+ fn main() {
+-    let x = 1;
++    let x = helper();
++    let y = x + 1;
+     println!(\"{}\", x);
+ }
+@@ -6,3 +7,3 @@
+ fn helper() -> u32 {
+-    42
++    41
+ }
+";
+
+    #[test]
+    fn test_parse() {
+        let hunks = parse(PATCH).unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header, "@@ -1,4 +1,5 @@");
+        assert_eq!(hunks[0].old_start, 1);
+        assert_eq!(
+            hunks[1].lines,
+            vec![
+                HunkLine::Context("fn helper() -> u32 {".to_string()),
+                HunkLine::Remove("    42".to_string()),
+                HunkLine::Add("    41".to_string()),
+                HunkLine::Context("}".to_string()),
+            ]
+        );
+
+        assert!(parse("not a diff").is_err());
+        assert!(parse("@@ -1,2 +1,2 @@\n-a\n+b\n").is_err());
+    }
+
+    #[test]
+    fn test_apply_multiple_hunks() {
+        let outcome = apply(ORIGINAL, &parse(PATCH).unwrap(), false);
+        assert_eq!(outcome.applied, vec![(0, 1), (1, 7)]);
+        assert!(outcome.rejected.is_empty());
+        assert_eq!(
+            outcome.content,
+            "fn main() {\n    let x = helper();\n    let y = x + 1;\n    println!(\"{}\", x);\n}\n\nfn helper() -> u32 {\n    41\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_at_offset() {
+        // Lines added above the hunks since the diff was made
+        let content = format!("use std::fmt;\n\n{}", ORIGINAL);
+        let outcome = apply(&content, &parse(PATCH).unwrap(), false);
+        assert_eq!(outcome.applied, vec![(0, 3), (1, 9)]);
+        assert!(outcome.content.contains("    41\n"));
+
+        // Lines removed between the hunks since the diff was made
+        let content = ORIGINAL.replace("}\n\nfn helper", "}\nfn helper");
+        let outcome = apply(&content, &parse(PATCH).unwrap(), false);
+        assert_eq!(outcome.applied, vec![(0, 1), (1, 6)]);
+        assert!(outcome.rejected.is_empty());
+        assert!(outcome
+            .content
+            .ends_with("}\nfn helper() -> u32 {\n    41\n}\n"));
+    }
+
+    #[test]
+    fn test_parse_function_context_and_blank_lines() {
+        // Hunk headers may name the enclosing function, and copied diffs often lose the
+        // leading space of blank context lines
+        let patch = "@@ -4,4 +4,4 @@ fn main() {\n }\n\n fn helper() -> u32 {\n-    42\n+    41\n";
+        let hunks = parse(patch).unwrap();
+        assert_eq!(hunks[0].header, "@@ -4,4 +4,4 @@");
+        assert_eq!(hunks[0].lines[1], HunkLine::Context(String::new()));
+
+        let outcome = apply(ORIGINAL, &hunks, false);
+        assert_eq!(outcome.applied, vec![(0, 4)]);
+        assert!(outcome.content.contains("    41\n"));
+    }
+
+    #[test]
+    fn test_apply_rejects_mismatched_context() {
+        let content = ORIGINAL.replace("    42", "\t42");
+        let hunks = parse(PATCH).unwrap();
+
+        let outcome = apply(&content, &hunks, false);
+        assert_eq!(outcome.applied, vec![(0, 1)]);
+        assert_eq!(outcome.rejected.len(), 1);
+        assert_eq!(outcome.rejected[0].0, 1);
+        assert!(outcome.content.contains("\t42"));
+
+        let outcome = apply(&content, &hunks, true);
+        assert!(outcome.rejected.is_empty());
+        assert!(outcome.content.contains("    41"));
+    }
+}
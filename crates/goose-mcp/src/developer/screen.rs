@@ -0,0 +1,200 @@
+use mcp_core::handler::ToolError;
+use serde_json::Value;
+use std::io::ErrorKind;
+use tokio::process::Command;
+use xcap::image::{imageops, ImageFormat, RgbaImage};
+
+/// A rectangle of a captured image, in logical pixels from its top-left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CropRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRegion {
+    /// Read the optional `crop_region` parameter
+    pub fn from_params(params: &Value) -> Result<Option<Self>, ToolError> {
+        let Some(region) = params.get("crop_region").filter(|v| !v.is_null()) else {
+            return Ok(None);
+        };
+        let field = |name: &str| {
+            region
+                .get(name)
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| {
+                    ToolError::InvalidParameters(format!(
+                        "crop_region.{} must be a non-negative integer",
+                        name
+                    ))
+                })
+        };
+        Ok(Some(Self {
+            x: field("x")?,
+            y: field("y")?,
+            width: field("width")?,
+            height: field("height")?,
+        }))
+    }
+}
+
+/// Crop a captured image to a region given in logical pixels
+///
+/// `scale_factor` converts logical to physical pixels of the capture. The region is
+/// clamped to the image, and is an error only when nothing of it is left.
+pub fn crop_image(
+    image: &RgbaImage,
+    region: CropRegion,
+    scale_factor: f32,
+) -> Result<RgbaImage, ToolError> {
+    let scale = |value: u32| (value as f32 * scale_factor).round() as u32;
+    let x = scale(region.x);
+    let y = scale(region.y);
+    let width = scale(region.width).min(image.width().saturating_sub(x));
+    let height = scale(region.height).min(image.height().saturating_sub(y));
+    if width == 0 || height == 0 {
+        return Err(ToolError::InvalidParameters(format!(
+            "crop_region ({}, {}, {}x{}) is outside the captured image ({}x{})",
+            region.x,
+            region.y,
+            region.width,
+            region.height,
+            (image.width() as f32 / scale_factor) as u32,
+            (image.height() as f32 / scale_factor) as u32
+        )));
+    }
+    Ok(imageops::crop_imm(image, x, y, width, height).to_image())
+}
+
+/// Extract the text of an image with the `tesseract` command line tool
+pub async fn perform_ocr(image: &RgbaImage) -> Result<String, ToolError> {
+    let file = tempfile::Builder::new()
+        .suffix(".png")
+        .tempfile()
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to create temp file: {}", e)))?;
+    image
+        .save_with_format(file.path(), ImageFormat::Png)
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to write image for OCR: {}", e)))?;
+
+    let output = Command::new("tesseract")
+        .arg(file.path())
+        .arg("stdout")
+        .output()
+        .await
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => ToolError::ExecutionError(
+                "OCR requires tesseract, which was not found on the PATH. \
+                 Install it (e.g. `brew install tesseract` or `apt install tesseract-ocr`) and try again."
+                    .into(),
+            ),
+            _ => ToolError::ExecutionError(format!("Failed to run tesseract: {}", e)),
+        })?;
+    if !output.status.success() {
+        return Err(ToolError::ExecutionError(format!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use xcap::image::Rgba;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    #[test]
+    fn test_crop_image() {
+        // A white image with a black rectangle at logical (10, 5) to (40, 25)
+        let image = RgbaImage::from_fn(200, 100, |x, y| {
+            if (20..80).contains(&x) && (10..50).contains(&y) {
+                BLACK
+            } else {
+                WHITE
+            }
+        });
+
+        let region = CropRegion::from_params(&json!({
+            "crop_region": {"x": 10, "y": 5, "width": 30, "height": 20}
+        }))
+        .unwrap()
+        .unwrap();
+        let cropped = crop_image(&image, region, 2.0).unwrap();
+        assert_eq!(cropped.dimensions(), (60, 40));
+        assert!(cropped.pixels().all(|pixel| *pixel == BLACK));
+
+        // Regions reaching past the edge are clamped
+        let region = CropRegion {
+            x: 150,
+            y: 80,
+            width: 100,
+            height: 100,
+        };
+        let cropped = crop_image(&image, region, 1.0).unwrap();
+        assert_eq!(cropped.dimensions(), (50, 20));
+
+        let region = CropRegion { x: 200, ..region };
+        assert!(matches!(
+            crop_image(&image, region, 1.0),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert_eq!(CropRegion::from_params(&json!({})).unwrap(), None);
+        assert!(CropRegion::from_params(&json!({"crop_region": {"x": -1}})).is_err());
+    }
+
+    // Glyphs of a blocky 5x7 font
+    type Glyph = [&'static str; 7];
+    const H: Glyph = [
+        "#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#",
+    ];
+    const E: Glyph = [
+        "#####", "#....", "#....", "####.", "#....", "#....", "#####",
+    ];
+    const L: Glyph = [
+        "#....", "#....", "#....", "#....", "#....", "#....", "#####",
+    ];
+    const O: Glyph = [
+        ".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###.",
+    ];
+
+    /// Draw a line of glyphs, large enough for OCR
+    fn render_text(glyphs: &[Glyph]) -> RgbaImage {
+        const GLYPH_SCALE: u32 = 10;
+        const MARGIN: u32 = 40;
+
+        let columns = glyphs.len() as u32 * 6 - 1;
+        let mut image = RgbaImage::from_pixel(
+            columns * GLYPH_SCALE + 2 * MARGIN,
+            7 * GLYPH_SCALE + 2 * MARGIN,
+            WHITE,
+        );
+        for (index, glyph) in glyphs.iter().enumerate() {
+            for (row, line) in glyph.iter().enumerate() {
+                for (column, _) in line.chars().enumerate().filter(|(_, c)| *c == '#') {
+                    let left = MARGIN + (index as u32 * 6 + column as u32) * GLYPH_SCALE;
+                    let top = MARGIN + row as u32 * GLYPH_SCALE;
+                    for x in left..left + GLYPH_SCALE {
+                        for y in top..top + GLYPH_SCALE {
+                            image.put_pixel(x, y, BLACK);
+                        }
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    #[tokio::test]
+    #[ignore = "requires tesseract"]
+    async fn test_perform_ocr() {
+        let image = render_text(&[H, E, L, L, O]);
+        assert_eq!(perform_ocr(&image).await.unwrap(), "HELLO");
+    }
+}
@@ -1,4 +1,9 @@
+use mcp_core::ToolError;
 use std::env;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
 
 #[derive(Debug, Clone)]
 pub struct ShellConfig {
@@ -41,6 +46,97 @@ pub fn format_command_for_platform(command: &str) -> String {
     }
 }
 
+/// Kill a command and its children, when it was spawned in its own process group
+pub fn kill_process_group(pid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        // SAFETY: only sends a signal to the process group of the command
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// What a shell command printed, and how it ended
+#[derive(Debug)]
+pub struct ShellOutput {
+    /// None when the command was killed, by a signal or after timing out
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Whether the command was killed for running longer than its timeout, in which case the
+    /// output is what it printed until then
+    pub timed_out: bool,
+}
+
+/// Run a command in the shell, passing each line of its stdout to `on_stdout_line` as it is
+/// printed. stderr is drained alongside so the command cannot block on a full pipe.
+///
+/// With a timeout, the command runs in its own process group, so it is killed along with its
+/// children when it runs for too long.
+pub async fn run_shell_command(
+    command: &str,
+    timeout_secs: Option<u64>,
+    mut on_stdout_line: impl FnMut(&str) + Send,
+) -> Result<ShellOutput, String> {
+    let config = get_shell_config();
+    let mut builder = Command::new(&config.executable);
+    builder
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .arg(&config.arg)
+        .arg(command);
+    #[cfg(unix)]
+    if timeout_secs.is_some() {
+        builder.process_group(0);
+    }
+    let mut child = builder.spawn().map_err(|e| e.to_string())?;
+
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let mut output = Vec::new();
+    let mut error_output = Vec::new();
+    let run = async {
+        let read_stdout = async {
+            let mut reader = BufReader::new(stdout);
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line).await? > 0 {
+                on_stdout_line(String::from_utf8_lossy(&line).trim_end());
+                output.append(&mut line);
+            }
+            Ok::<_, std::io::Error>(())
+        };
+        let read_stderr = stderr.read_to_end(&mut error_output);
+        tokio::try_join!(read_stdout, read_stderr)?;
+
+        // Wait for the command to complete
+        child.wait().await
+    };
+    let result = match timeout_secs {
+        Some(secs) => tokio::time::timeout(Duration::from_secs(secs), run).await,
+        None => Ok(run.await),
+    };
+    let (exit_code, timed_out) = match result {
+        Ok(status) => (status.map_err(|e| e.to_string())?.code(), false),
+        Err(_) => {
+            kill_process_group(child.id());
+            let _ = child.kill().await;
+            (None, true)
+        }
+    };
+
+    Ok(ShellOutput {
+        exit_code,
+        stdout: String::from_utf8_lossy(&output).into_owned(),
+        stderr: String::from_utf8_lossy(&error_output).into_owned(),
+        timed_out,
+    })
+}
+
 pub fn expand_path(path_str: &str) -> String {
     if cfg!(windows) {
         // Expand Windows environment variables (%VAR%)
@@ -75,3 +171,172 @@ pub fn normalize_line_endings(text: &str) -> String {
         text.replace("\r\n", "\n")
     }
 }
+
+/// Find the command substitutions (`$(...)` and `` `...` ``) in a command
+///
+/// Returns the byte offset where each substitution starts along with its inner command.
+/// Substitutions inside single quotes are not expanded by the shell and are skipped.
+fn find_command_substitutions(command: &str) -> Vec<(usize, &str)> {
+    let bytes = command.as_bytes();
+    let mut substitutions = Vec::new();
+    let mut in_single_quotes = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if !in_single_quotes => i += 1,
+            b'\'' => in_single_quotes = !in_single_quotes,
+            b'$' if !in_single_quotes && bytes.get(i + 1) == Some(&b'(') => {
+                let mut depth = 0;
+                let mut end = None;
+                for (j, byte) in bytes.iter().enumerate().skip(i + 1) {
+                    match byte {
+                        b'(' => depth += 1,
+                        b')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                end = Some(j);
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                let end = end.unwrap_or(bytes.len());
+                substitutions.push((i, &command[i + 2..end]));
+                i = end;
+            }
+            b'`' if !in_single_quotes => {
+                let end = command[i + 1..]
+                    .find('`')
+                    .map(|offset| i + 1 + offset)
+                    .unwrap_or(bytes.len());
+                substitutions.push((i, &command[i + 1..end]));
+                i = end;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    substitutions
+}
+
+/// Check a shell command for patterns that would bypass the `.gooseignore` check
+///
+/// The `.gooseignore` check only sees the literal arguments of a command, so a command
+/// substitution used as an argument (e.g. `cat $(echo secret.txt)`) can produce a path
+/// that is never checked. This flags substitutions in argument positions whose inner
+/// command refers to something path-like. It is not a general shell injection guard:
+/// the shell tool is intended to run arbitrary commands.
+pub fn sanitize_command(command: &str) -> Result<(), ToolError> {
+    for (start, inner) in find_command_substitutions(command) {
+        // The words of the current pipeline segment before the substitution; when there are
+        // none, the substitution is the command itself rather than one of its arguments
+        let segment = command[..start]
+            .rsplit(['|', ';', '&', '\n', '('])
+            .next()
+            .unwrap_or_default();
+        if segment.trim().is_empty() {
+            continue;
+        }
+
+        let refers_to_path = inner
+            .split_whitespace()
+            .any(|word| word.contains(['/', '.', '~', '*']));
+        if refers_to_path {
+            tracing::warn!(
+                "Rejected shell command with command substitution in argument position: {}",
+                command
+            );
+            return Err(ToolError::ExecutionError(format!(
+                "The command uses a command substitution (`{}`) as an argument, which prevents \
+                checking it against .gooseignore. Use the path directly instead.",
+                inner.trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Commands that create, modify or delete files
+const WRITE_COMMANDS: &[&str] = &[
+    "rm", "rmdir", "mv", "cp", "mkdir", "touch", "ln", "chmod", "chown", "chgrp", "tee", "dd",
+    "truncate", "install", "patch", "shred", "unlink", "rsync",
+];
+
+/// Subcommands of `git` that change the repository or working tree
+const GIT_WRITE_SUBCOMMANDS: &[&str] = &[
+    "add",
+    "am",
+    "apply",
+    "checkout",
+    "cherry-pick",
+    "clean",
+    "clone",
+    "commit",
+    "init",
+    "merge",
+    "mv",
+    "pull",
+    "push",
+    "rebase",
+    "reset",
+    "restore",
+    "revert",
+    "rm",
+    "stash",
+    "switch",
+    "tag",
+];
+
+/// Check whether a shell command may write files
+///
+/// This is a best effort check of the commands in each pipeline segment and of output
+/// redirections, used to keep read-only mode from running commands that obviously write.
+/// It does not detect writes by arbitrary programs or scripts.
+pub fn is_write_command(command: &str) -> bool {
+    let bytes = command.as_bytes();
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut i = 0;
+
+    // Redirecting output to anything but /dev/null or another descriptor writes a file
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if !in_single_quotes => i += 1,
+            b'\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            b'"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            b'>' if !in_single_quotes && !in_double_quotes => {
+                let rest = command[i + 1..].trim_start_matches('>');
+                if !rest.starts_with('&') {
+                    let target = rest.split_whitespace().next().unwrap_or_default();
+                    if target != "/dev/null" {
+                        return true;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    command
+        .split(['|', ';', '&', '\n', '(', ')', '`'])
+        .any(|segment| {
+            let mut words = segment
+                .split_whitespace()
+                .skip_while(|word| word.contains('=') || *word == "sudo");
+            let Some(program) = words.next() else {
+                return false;
+            };
+            let program = program.rsplit('/').next().unwrap_or(program);
+            match program {
+                "git" => words
+                    .find(|word| !word.starts_with('-'))
+                    .is_some_and(|subcommand| GIT_WRITE_SUBCOMMANDS.contains(&subcommand)),
+                "sed" | "perl" => words.any(|word| word.starts_with("-i")),
+                _ => WRITE_COMMANDS.contains(&program),
+            }
+        })
+}
@@ -0,0 +1,947 @@
+use indoc::indoc;
+use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use mail_parser::{MessageParser, MimeHeaders};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::Arc;
+use std::{future::Future, pin::Pin};
+use tokio_rustls::rustls::{
+    pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore, StreamOwned,
+};
+
+use crate::util::{json_content, limit};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+const DEFAULT_IMAP_PORT: u16 = 993;
+const DEFAULT_FOLDER: &str = "INBOX";
+const DEFAULT_LIMIT: u64 = 20;
+
+type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+/// The mail servers and account, read from the GOOSE_EMAIL_* variables
+#[derive(Clone, Debug, Default)]
+pub struct EmailConfig {
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub imap_host: Option<String>,
+    pub imap_port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl EmailConfig {
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let port = |name: &str, default: u16| {
+            var(name)
+                .and_then(|port| port.parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            smtp_host: var("GOOSE_EMAIL_SMTP_HOST"),
+            smtp_port: port("GOOSE_EMAIL_SMTP_PORT", DEFAULT_SMTP_PORT),
+            imap_host: var("GOOSE_EMAIL_IMAP_HOST"),
+            imap_port: port("GOOSE_EMAIL_IMAP_PORT", DEFAULT_IMAP_PORT),
+            username: var("GOOSE_EMAIL_USERNAME"),
+            password: var("GOOSE_EMAIL_PASSWORD"),
+        }
+    }
+
+    fn account(&self) -> Result<(&str, &str), ToolError> {
+        match (&self.username, &self.password) {
+            (Some(username), Some(password)) => Ok((username, password)),
+            _ => Err(ToolError::ExecutionError(
+                "Set GOOSE_EMAIL_USERNAME and GOOSE_EMAIL_PASSWORD to use the email extension"
+                    .into(),
+            )),
+        }
+    }
+}
+
+/// Router to send email over SMTP and read it over IMAP
+#[derive(Clone)]
+pub struct EmailRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    config: EmailConfig,
+}
+
+impl Default for EmailRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmailRouter {
+    pub fn new() -> Self {
+        Self::with_config(EmailConfig::from_env())
+    }
+
+    pub fn with_config(config: EmailConfig) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let folder = json!({
+            "type": "string",
+            "default": DEFAULT_FOLDER,
+            "description": "The mailbox folder"
+        });
+        let limit = json!({
+            "type": "integer",
+            "default": DEFAULT_LIMIT,
+            "description": "The maximum number of emails, newest first"
+        });
+
+        let send_email = Tool::new(
+            "send_email",
+            indoc! {r#"
+                Send an email from the configured account. The body is written in Markdown and
+                sent as both plain text and HTML. Attachments are paths of local files.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["to", "subject", "body_markdown"],
+                "properties": {
+                    "to": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "The recipients, e.g. [\"Ada <ada@example.com>\"]"
+                    },
+                    "subject": {"type": "string"},
+                    "body_markdown": {"type": "string"},
+                    "attachments": {"type": "array", "items": {"type": "string"}}
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Send an email".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: true,
+            }),
+        );
+
+        let list_mailbox_folders = Tool::new(
+            "list_mailbox_folders",
+            "List the folders of the mailbox.",
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+            read_only("List mailbox folders"),
+        );
+
+        let list_emails = Tool::new(
+            "list_emails",
+            "List the latest emails of a folder with their id, sender, subject and date.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "folder": folder,
+                    "limit": limit
+                }
+            }),
+            read_only("List emails"),
+        );
+
+        let read_email = Tool::new(
+            "read_email",
+            "Read an email by its id, returning its body and the list of its attachments.",
+            json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": {"type": "integer", "description": "The id from list_emails or search_emails"},
+                    "folder": folder
+                }
+            }),
+            read_only("Read an email"),
+        );
+
+        let search_emails = Tool::new(
+            "search_emails",
+            "Search the emails of a folder whose subject, sender or body contain the given keywords.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "subject": {"type": "string"},
+                    "from": {"type": "string"},
+                    "body": {"type": "string"},
+                    "folder": folder,
+                    "limit": limit
+                }
+            }),
+            read_only("Search emails"),
+        );
+
+        let instructions = indoc! {r#"
+            The email extension sends email from the user's account and reads their mailbox.
+
+            Email ids are only valid within their folder, so pass the same folder to read_email
+            that was used to find the email. Confirm recipients and content with the user before
+            sending an email.
+        "#}
+        .to_string();
+
+        Self {
+            tools: vec![
+                send_email,
+                list_mailbox_folders,
+                list_emails,
+                read_email,
+                search_emails,
+            ],
+            instructions,
+            config,
+        }
+    }
+
+    async fn send_email(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let host = self.config.smtp_host.as_deref().ok_or_else(|| {
+            ToolError::ExecutionError("Set GOOSE_EMAIL_SMTP_HOST to send email".into())
+        })?;
+        let (username, password) = self.config.account()?;
+        let message = build_message(username, &params)?;
+
+        // Port 465 uses implicit TLS, other ports upgrade the connection with STARTTLS
+        let builder = if self.config.smtp_port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+        }
+        .map_err(|e| ToolError::ExecutionError(format!("Invalid SMTP server: {}", e)))?;
+        let transport = builder
+            .port(self.config.smtp_port)
+            .credentials(Credentials::new(username.to_string(), password.to_string()))
+            .build();
+
+        send(&transport, message).await
+    }
+
+    // Helper method to run `operation` in an IMAP session logged in to the account
+    //
+    // The IMAP client is blocking, so the session runs on a blocking thread.
+    async fn with_imap<T, F>(&self, operation: F) -> Result<T, ToolError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut imap::Session<TlsStream>) -> Result<T, ToolError> + Send + 'static,
+    {
+        let host = self.config.imap_host.clone().ok_or_else(|| {
+            ToolError::ExecutionError("Set GOOSE_EMAIL_IMAP_HOST to read email".into())
+        })?;
+        let port = self.config.imap_port;
+        let (username, password) = self.config.account()?;
+        let (username, password) = (username.to_string(), password.to_string());
+
+        tokio::task::spawn_blocking(move || {
+            let mut session = login(connect_tls(&host, port)?, &username, &password)?;
+            let result = operation(&mut session);
+            session.logout().ok();
+            result
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("IMAP session failed: {}", e)))?
+    }
+
+    async fn list_mailbox_folders(&self) -> Result<Vec<Content>, ToolError> {
+        let folders = self.with_imap(list_folders).await?;
+        Ok(vec![json_content(&json!(folders))])
+    }
+
+    async fn list_emails(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let emails = self
+            .with_imap(move |session| list_emails(session, &params))
+            .await?;
+        Ok(vec![json_content(&emails)])
+    }
+
+    async fn read_email(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let email = self
+            .with_imap(move |session| read_email(session, &params))
+            .await?;
+        Ok(vec![json_content(&email)])
+    }
+
+    async fn search_emails(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let emails = self
+            .with_imap(move |session| search_emails(session, &params))
+            .await?;
+        Ok(vec![json_content(&emails)])
+    }
+}
+
+fn imap_error(error: imap::Error) -> ToolError {
+    ToolError::ExecutionError(format!("IMAP request failed: {}", error))
+}
+
+/// Connect to an IMAP server with implicit TLS
+fn connect_tls(host: &str, port: u16) -> Result<imap::Client<TlsStream>, ToolError> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|e| ToolError::ExecutionError(format!("Invalid IMAP server: {}", e)))?;
+    let connection = ClientConnection::new(Arc::new(config), server_name)
+        .map_err(|e| ToolError::ExecutionError(format!("IMAP connection failed: {}", e)))?;
+    let tcp = TcpStream::connect((host, port))
+        .map_err(|e| ToolError::ExecutionError(format!("IMAP connection failed: {}", e)))?;
+    Ok(imap::Client::new(StreamOwned::new(connection, tcp)))
+}
+
+/// Read the greeting of the server and log in
+fn login<S: Read + Write>(
+    mut client: imap::Client<S>,
+    username: &str,
+    password: &str,
+) -> Result<imap::Session<S>, ToolError> {
+    client.read_greeting().map_err(imap_error)?;
+    client
+        .login(username, password)
+        .map_err(|(error, _)| imap_error(error))
+}
+
+/// The names of the folders that can be opened
+fn list_folders<S: Read + Write>(session: &mut imap::Session<S>) -> Result<Vec<String>, ToolError> {
+    let names = session.list(Some(""), Some("*")).map_err(imap_error)?;
+    Ok(names
+        .iter()
+        // Folders that cannot be selected are only containers of other folders
+        .filter(|name| {
+            !name
+                .attributes()
+                .contains(&imap::types::NameAttribute::NoSelect)
+        })
+        .map(|name| name.name().to_string())
+        .collect())
+}
+
+fn folder(params: &Value) -> &str {
+    params["folder"].as_str().unwrap_or(DEFAULT_FOLDER)
+}
+
+/// Build the message for `send_email`, with the Markdown body as plain text and HTML
+fn build_message(from: &str, params: &Value) -> Result<Message, ToolError> {
+    let from: Mailbox = from.parse().map_err(|e| {
+        ToolError::ExecutionError(format!(
+            "GOOSE_EMAIL_USERNAME is not an email address: {}",
+            e
+        ))
+    })?;
+    let recipients: Vec<&str> = match &params["to"] {
+        Value::String(to) => vec![to.as_str()],
+        Value::Array(to) => to.iter().filter_map(|to| to.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if recipients.is_empty() {
+        return Err(ToolError::InvalidParameters(
+            "Missing 'to' parameter".into(),
+        ));
+    }
+    let subject = params["subject"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'subject' parameter".into()))?;
+    let markdown = params["body_markdown"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'body_markdown' parameter".into()))?;
+
+    let mut builder = Message::builder().from(from).subject(subject);
+    for recipient in recipients {
+        let mailbox: Mailbox = recipient.parse().map_err(|e| {
+            ToolError::InvalidParameters(format!("Invalid recipient '{}': {}", recipient, e))
+        })?;
+        builder = builder.to(mailbox);
+    }
+
+    let mut body = MultiPart::mixed().multipart(MultiPart::alternative_plain_html(
+        markdown.to_string(),
+        markdown_to_html(markdown),
+    ));
+    for path in params["attachments"].as_array().into_iter().flatten() {
+        let path = path.as_str().ok_or_else(|| {
+            ToolError::InvalidParameters("'attachments' must be a list of paths".into())
+        })?;
+        body = body.singlepart(attachment(Path::new(path))?);
+    }
+
+    builder
+        .multipart(body)
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to build the email: {}", e)))
+}
+
+fn attachment(path: &Path) -> Result<lettre::message::SinglePart, ToolError> {
+    let content = std::fs::read(path).map_err(|e| {
+        ToolError::InvalidParameters(format!(
+            "Failed to read attachment '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "attachment".to_string());
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let content_type = ContentType::parse(mime.as_ref())
+        .unwrap_or_else(|_| ContentType::parse("application/octet-stream").unwrap());
+    Ok(Attachment::new(filename).body(content, content_type))
+}
+
+fn markdown_to_html(markdown: &str) -> String {
+    let options = pulldown_cmark::Options::ENABLE_TABLES
+        | pulldown_cmark::Options::ENABLE_STRIKETHROUGH
+        | pulldown_cmark::Options::ENABLE_TASKLISTS;
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(
+        &mut html,
+        pulldown_cmark::Parser::new_ext(markdown, options),
+    );
+    html
+}
+
+async fn send<T>(transport: &T, message: Message) -> Result<Vec<Content>, ToolError>
+where
+    T: AsyncTransport + Sync,
+    T::Error: std::fmt::Display,
+{
+    let recipients = message.envelope().to().len();
+    transport
+        .send(message)
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to send the email: {}", e)))?;
+    Ok(vec![Content::text(format!(
+        "Sent the email to {} recipient{}",
+        recipients,
+        if recipients == 1 { "" } else { "s" }
+    ))])
+}
+
+/// Summaries of the emails with the given UIDs, newest first
+fn summaries<S: Read + Write>(
+    session: &mut imap::Session<S>,
+    uids: &[u32],
+) -> Result<Value, ToolError> {
+    // `UID FETCH` needs at least one UID, so an empty mailbox or search has nothing to fetch
+    if uids.is_empty() {
+        return Ok(Value::Array(Vec::new()));
+    }
+    let fetches = session
+        .uid_fetch(uid_set(uids), "(UID BODY.PEEK[HEADER])")
+        .map_err(imap_error)?;
+    let mut headers: Vec<(u32, &[u8])> = fetches
+        .iter()
+        .filter_map(|fetch| Some((fetch.uid?, fetch.header().unwrap_or_default())))
+        .collect();
+    headers.sort_by_key(|(uid, _)| std::cmp::Reverse(*uid));
+
+    let parser = MessageParser::default();
+    Ok(Value::Array(
+        headers
+            .iter()
+            .map(|(uid, header)| {
+                let message = parser.parse_headers(*header);
+                let message = message.as_ref();
+                // Without the body, a mixed multipart is the best sign of attachments
+                let has_attachments = message
+                    .and_then(|message| message.content_type())
+                    .is_some_and(|content_type| {
+                        content_type.ctype().eq_ignore_ascii_case("multipart")
+                            && content_type
+                                .subtype()
+                                .is_some_and(|subtype| subtype.eq_ignore_ascii_case("mixed"))
+                    });
+                json!({
+                    "id": uid,
+                    "from": message.and_then(|message| message.from()).and_then(|from| from.first()).map(address),
+                    "subject": message.and_then(|message| message.subject()),
+                    "date": message.and_then(|message| message.date()).map(|date| date.to_rfc3339()),
+                    "has_attachments": has_attachments
+                })
+            })
+            .collect(),
+    ))
+}
+
+fn address(addr: &mail_parser::Addr) -> String {
+    match (addr.name(), addr.address()) {
+        (Some(name), Some(address)) => format!("{} <{}>", name, address),
+        (None, Some(address)) => address.to_string(),
+        (name, None) => name.unwrap_or_default().to_string(),
+    }
+}
+
+/// The UIDs of the last `limit` messages of `uids`
+fn latest(uids: impl IntoIterator<Item = u32>, limit: usize) -> Vec<u32> {
+    let mut uids: Vec<u32> = uids.into_iter().collect();
+    uids.sort_unstable();
+    let start = uids.len().saturating_sub(limit);
+    uids.split_off(start)
+}
+
+fn uid_set(uids: &[u32]) -> String {
+    uids.iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn list_emails<S: Read + Write>(
+    session: &mut imap::Session<S>,
+    params: &Value,
+) -> Result<Value, ToolError> {
+    session.examine(folder(params)).map_err(imap_error)?;
+    let uids = session.uid_search("ALL").map_err(imap_error)?;
+    summaries(session, &latest(uids, limit(params, DEFAULT_LIMIT)))
+}
+
+fn search_emails<S: Read + Write>(
+    session: &mut imap::Session<S>,
+    params: &Value,
+) -> Result<Value, ToolError> {
+    let criteria = search_criteria(params)?;
+    session.examine(folder(params)).map_err(imap_error)?;
+    let uids = session.uid_search(&criteria).map_err(imap_error)?;
+    summaries(session, &latest(uids, limit(params, DEFAULT_LIMIT)))
+}
+
+/// Quote a search value, which cannot contain line breaks or NUL in a quoted string
+fn quote(name: &str, value: &str) -> Result<String, ToolError> {
+    if value.contains(['\r', '\n', '\0']) {
+        return Err(ToolError::InvalidParameters(format!(
+            "'{}' cannot contain line breaks",
+            name
+        )));
+    }
+    Ok(format!(
+        "\"{}\"",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+fn search_criteria(params: &Value) -> Result<String, ToolError> {
+    let keys = [("subject", "SUBJECT"), ("from", "FROM"), ("body", "BODY")]
+        .iter()
+        .filter_map(|(name, key)| {
+            params[*name]
+                .as_str()
+                .filter(|value| !value.is_empty())
+                .map(|value| Ok(format!("{} {}", key, quote(name, value)?)))
+        })
+        .collect::<Result<Vec<String>, ToolError>>()?;
+    if keys.is_empty() {
+        return Err(ToolError::InvalidParameters(
+            "Provide at least one of 'subject', 'from' and 'body'".into(),
+        ));
+    }
+    let criteria = keys.join(" ");
+    Ok(if criteria.is_ascii() {
+        criteria
+    } else {
+        format!("CHARSET UTF-8 {}", criteria)
+    })
+}
+
+fn read_email<S: Read + Write>(
+    session: &mut imap::Session<S>,
+    params: &Value,
+) -> Result<Value, ToolError> {
+    let uid = params["id"]
+        .as_u64()
+        .and_then(|id| u32::try_from(id).ok())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'id' parameter".into()))?;
+    session.examine(folder(params)).map_err(imap_error)?;
+    let fetched = session
+        .uid_fetch(uid.to_string(), "(UID BODY.PEEK[])")
+        .map_err(imap_error)?;
+    let raw = fetched
+        .iter()
+        .find(|fetch| fetch.uid == Some(uid))
+        .and_then(|fetch| fetch.body())
+        .ok_or_else(|| {
+            ToolError::ExecutionError(format!("No email with id {} in {}", uid, folder(params)))
+        })?;
+
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or_else(|| ToolError::ExecutionError("Failed to parse the email".into()))?;
+    let list = |addresses: Option<&mail_parser::Address>| -> Vec<String> {
+        addresses
+            .map(|addresses| addresses.iter().map(address).collect())
+            .unwrap_or_default()
+    };
+    let attachments: Vec<Value> = message
+        .attachments()
+        .map(|attachment| {
+            let content_type =
+                attachment
+                    .content_type()
+                    .map(|content_type| match content_type.subtype() {
+                        Some(subtype) => format!("{}/{}", content_type.ctype(), subtype),
+                        None => content_type.ctype().to_string(),
+                    });
+            json!({
+                "filename": attachment.attachment_name(),
+                "content_type": content_type,
+                "size": attachment.len()
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "id": uid,
+        "from": list(message.from()),
+        "to": list(message.to()),
+        "cc": list(message.cc()),
+        "subject": message.subject(),
+        "date": message.date().map(|date| date.to_rfc3339()),
+        "body": message.body_text(0),
+        "attachments": attachments
+    }))
+}
+
+impl Router for EmailRouter {
+    fn name(&self) -> String {
+        "email".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "send_email" => this.send_email(arguments).await,
+                "list_mailbox_folders" => this.list_mailbox_folders().await,
+                "list_emails" => this.list_emails(arguments).await,
+                "read_email" => this.read_email(arguments).await,
+                "search_emails" => this.search_emails(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::prelude::{Engine as _, BASE64_STANDARD};
+    use lettre::transport::stub::AsyncStubTransport;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    /// Log in to a scripted IMAP server, which answers each expected command with its response
+    fn mock_session(script: Vec<(&'static str, String)>) -> imap::Session<TcpStream> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            writer.write_all(b"* OK IMAP4rev1 ready\r\n").unwrap();
+            let login = (
+                "LOGIN \"me@example.com\" \"secret\"",
+                "TAG OK done\r\n".into(),
+            );
+            for (expected, response) in std::iter::once(login).chain(script) {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    return;
+                }
+                let (tag, command) = line.trim_end().split_once(' ').unwrap();
+                assert_eq!(command, expected);
+                writer
+                    .write_all(response.replace("TAG", tag).as_bytes())
+                    .unwrap();
+            }
+        });
+        let client = imap::Client::new(TcpStream::connect(addr).unwrap());
+        login(client, "me@example.com", "secret").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_email_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("report.pdf");
+        std::fs::write(&report, b"%PDF-1.4 binary \x00\xff").unwrap();
+
+        let message = build_message(
+            "me@example.com",
+            &json!({
+                "to": ["Ada <ada@example.com>", "bob@example.com"],
+                "subject": "Weekly report",
+                "body_markdown": "Hi,\n\nThe report is **attached**.",
+                "attachments": [report.to_str().unwrap()]
+            }),
+        )
+        .unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+
+        assert!(formatted.contains("From: me@example.com\r\n"));
+        assert!(formatted.contains("To: Ada <ada@example.com>, bob@example.com\r\n"));
+        assert!(formatted.contains("Subject: Weekly report\r\n"));
+        assert!(formatted.contains("Content-Type: multipart/mixed;"));
+        assert!(formatted.contains("Content-Type: multipart/alternative;"));
+        assert!(formatted.contains("Content-Type: text/plain; charset=utf-8"));
+        assert!(formatted.contains("The report is **attached**."));
+        assert!(formatted.contains("Content-Type: text/html; charset=utf-8"));
+        assert!(formatted.contains("<p>The report is <strong>attached</strong>.</p>"));
+        assert!(formatted.contains("Content-Disposition: attachment; filename=\"report.pdf\""));
+        assert!(formatted.contains("Content-Type: application/pdf"));
+        assert!(formatted.contains("Content-Transfer-Encoding: base64"));
+        assert!(formatted.contains(&BASE64_STANDARD.encode(b"%PDF-1.4 binary \x00\xff")));
+
+        let transport = AsyncStubTransport::new_ok();
+        let result = send(&transport, message).await.unwrap();
+        assert_eq!(result[0].as_text(), Some("Sent the email to 2 recipients"));
+        let sent = transport.messages().await;
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0.from().unwrap().to_string(), "me@example.com");
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        let valid = json!({"to": "ada@example.com", "subject": "Hi", "body_markdown": "Hi"});
+        assert!(build_message("me@example.com", &valid).is_ok());
+        assert!(matches!(
+            build_message("not an address", &valid),
+            Err(ToolError::ExecutionError(_))
+        ));
+        for params in [
+            json!({"subject": "Hi", "body_markdown": "Hi"}),
+            json!({"to": "not an address", "subject": "Hi", "body_markdown": "Hi"}),
+            json!({"to": "ada@example.com", "subject": "Hi", "body_markdown": "Hi", "attachments": ["/missing/file"]}),
+        ] {
+            assert!(matches!(
+                build_message("me@example.com", &params),
+                Err(ToolError::InvalidParameters(_))
+            ));
+        }
+    }
+
+    fn fetch_response(uid: u32, item: &str, data: &str) -> String {
+        format!(
+            "* {} FETCH (UID {} {} {{{}}}\r\n{})\r\n",
+            uid,
+            uid,
+            item,
+            data.len(),
+            data
+        )
+    }
+
+    #[test]
+    fn test_list_folders() {
+        let mut session = mock_session(vec![(
+            "LIST \"\" *",
+            concat!(
+                "* LIST (\\HasNoChildren) \"/\" \"INBOX\"\r\n",
+                "* LIST (\\Noselect \\HasChildren) \"/\" \"[Gmail]\"\r\n",
+                "* LIST (\\HasNoChildren) \"/\" Archive\r\n",
+                "TAG OK LIST completed\r\n"
+            )
+            .into(),
+        )]);
+        assert_eq!(
+            list_folders(&mut session).unwrap(),
+            vec!["INBOX", "Archive"]
+        );
+    }
+
+    #[test]
+    fn test_list_emails() {
+        let plain = "From: Ada <ada@example.com>\r\nSubject: Lunch\r\nDate: Tue, 1 Jul 2025 12:00:00 +0000\r\nContent-Type: text/plain\r\n\r\n";
+        let mixed = "From: bob@example.com\r\nSubject: Report\r\nContent-Type: multipart/mixed; boundary=x\r\n\r\n";
+        let mut session = mock_session(vec![
+            (
+                "EXAMINE \"INBOX\"",
+                "* 8 EXISTS\r\nTAG OK [READ-ONLY] done\r\n".into(),
+            ),
+            ("UID SEARCH ALL", "* SEARCH 3 5 8\r\nTAG OK done\r\n".into()),
+            (
+                "UID FETCH 5,8 (UID BODY.PEEK[HEADER])",
+                format!(
+                    "{}{}TAG OK done\r\n",
+                    fetch_response(5, "BODY[HEADER]", plain),
+                    fetch_response(8, "BODY[HEADER]", mixed)
+                ),
+            ),
+        ]);
+
+        let emails = list_emails(&mut session, &json!({"limit": 2})).unwrap();
+        assert_eq!(
+            emails,
+            json!([
+                {"id": 8, "from": "bob@example.com", "subject": "Report", "date": null, "has_attachments": true},
+                {"id": 5, "from": "Ada <ada@example.com>", "subject": "Lunch", "date": "2025-07-01T12:00:00Z", "has_attachments": false}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_list_empty_mailbox() {
+        let mut session = mock_session(vec![
+            (
+                "EXAMINE \"INBOX\"",
+                "* 0 EXISTS\r\nTAG OK [READ-ONLY] done\r\n".into(),
+            ),
+            ("UID SEARCH ALL", "* SEARCH\r\nTAG OK done\r\n".into()),
+        ]);
+        assert_eq!(list_emails(&mut session, &json!({})).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_search_and_read_email() {
+        let raw = concat!(
+            "From: bob@example.com\r\n",
+            "To: me@example.com\r\n",
+            "Subject: Invoice\r\n",
+            "Content-Type: multipart/mixed; boundary=x\r\n",
+            "\r\n",
+            "--x\r\n",
+            "Content-Type: text/plain\r\n",
+            "\r\n",
+            "Please find the invoice attached.\r\n",
+            "--x\r\n",
+            "Content-Type: application/pdf; name=\"invoice.pdf\"\r\n",
+            "Content-Disposition: attachment; filename=\"invoice.pdf\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "JVBERi0xLjQ=\r\n",
+            "--x--\r\n"
+        );
+        let mut session = mock_session(vec![
+            (
+                "EXAMINE \"Archive\"",
+                "* 12 EXISTS\r\nTAG OK [READ-ONLY] done\r\n".into(),
+            ),
+            (
+                "UID SEARCH SUBJECT \"invoice\" FROM \"bob\"",
+                "* SEARCH 12\r\nTAG OK done\r\n".into(),
+            ),
+            (
+                "UID FETCH 12 (UID BODY.PEEK[HEADER])",
+                format!(
+                    "{}TAG OK done\r\n",
+                    fetch_response(12, "BODY[HEADER]", "Subject: Invoice\r\n\r\n")
+                ),
+            ),
+            (
+                "EXAMINE \"Archive\"",
+                "* 12 EXISTS\r\nTAG OK [READ-ONLY] done\r\n".into(),
+            ),
+            (
+                "UID FETCH 12 (UID BODY.PEEK[])",
+                format!("{}TAG OK done\r\n", fetch_response(12, "BODY[]", raw)),
+            ),
+        ]);
+
+        let found = search_emails(
+            &mut session,
+            &json!({"folder": "Archive", "subject": "invoice", "from": "bob"}),
+        )
+        .unwrap();
+        assert_eq!(found[0]["id"], 12);
+
+        let email = read_email(&mut session, &json!({"folder": "Archive", "id": 12})).unwrap();
+        assert_eq!(email["from"], json!(["bob@example.com"]));
+        assert_eq!(email["subject"], "Invoice");
+        assert_eq!(
+            email["body"].as_str().unwrap().trim_end(),
+            "Please find the invoice attached."
+        );
+        assert_eq!(
+            email["attachments"],
+            json!([{"filename": "invoice.pdf", "content_type": "application/pdf", "size": 8}])
+        );
+
+        assert!(matches!(
+            search_criteria(&json!({"folder": "INBOX"})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert_eq!(
+            search_criteria(&json!({"body": "café"})).unwrap(),
+            "CHARSET UTF-8 BODY \"café\""
+        );
+        assert_eq!(
+            search_criteria(&json!({"from": "a\"b\\"})).unwrap(),
+            "FROM \"a\\\"b\\\\\""
+        );
+        for injected in ["x\" ALL\r\nA2 DELETE INBOX", "x\nALL", "x\0"] {
+            assert!(matches!(
+                search_criteria(&json!({"subject": injected})),
+                Err(ToolError::InvalidParameters(_))
+            ));
+        }
+    }
+}
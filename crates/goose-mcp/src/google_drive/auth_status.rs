@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+
+use super::storage::TokenRefreshEvent;
+
+/// Environment variable overriding where the Google Drive auth status file is written
+pub const AUTH_STATUS_PATH_ENV: &str = "GOOGLE_DRIVE_AUTH_STATUS_PATH";
+
+/// The state of the Google Drive credentials after the last token refresh,
+/// persisted so that operators can tell when manual re-authentication is needed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthStatus {
+    pub authenticated: bool,
+    /// RFC 3339 expiry of the current access token, when known
+    pub expires_at: Option<String>,
+    pub needs_reauth: bool,
+}
+
+impl From<&TokenRefreshEvent> for AuthStatus {
+    fn from(event: &TokenRefreshEvent) -> Self {
+        Self {
+            authenticated: event.succeeded,
+            expires_at: event.next_expiry.map(|expiry| expiry.to_rfc3339()),
+            needs_reauth: !event.succeeded,
+        }
+    }
+}
+
+impl AuthStatus {
+    /// Read the status file, returning None if no refresh has been recorded yet
+    pub fn read(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+    }
+}
+
+/// Location of the auth status file
+///
+/// Uses GOOGLE_DRIVE_AUTH_STATUS_PATH when set, otherwise the goose data directory:
+/// - macOS/Linux: ~/.local/share/goose/google_drive/auth_status.json
+/// - Windows:     ~\AppData\Roaming\Block\goose\data\google_drive\auth_status.json
+pub fn auth_status_path() -> PathBuf {
+    if let Ok(path) = std::env::var(AUTH_STATUS_PATH_ENV) {
+        return PathBuf::from(shellexpand::tilde(&path).into_owned());
+    }
+    choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.in_data_dir("google_drive/auth_status.json"))
+        .unwrap_or_else(|_| PathBuf::from("./gdrive-auth-status.json"))
+}
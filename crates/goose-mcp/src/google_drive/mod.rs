@@ -1,7 +1,9 @@
+pub mod auth_status;
 mod oauth_pkce;
 pub mod storage;
 
 use anyhow::{Context, Error};
+use auth_status::{auth_status_path, AuthStatus};
 use base64::Engine;
 use indoc::indoc;
 use lazy_static::lazy_static;
@@ -9,8 +11,16 @@ use mcp_core::tool::ToolAnnotations;
 use oauth_pkce::PkceOAuth2Client;
 use regex::Regex;
 use serde_json::{json, Value};
-use std::io::Cursor;
-use std::{env, fs, future::Future, path::Path, pin::Pin, sync::Arc};
+use std::collections::HashMap;
+use std::io::{Cursor, SeekFrom};
+use std::time::{Duration, Instant};
+use std::{
+    env, fs,
+    future::Future,
+    path::Path,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
 use storage::CredentialsManager;
 
 use mcp_core::content::Content;
@@ -25,15 +35,27 @@ use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
 use google_docs1::{self, Docs};
-use google_drive3::common::ReadSeek;
+use google_drive3::common::{ContentRange, Delegate, ReadSeek};
 use google_drive3::{
     self,
-    api::{Comment, File, FileShortcutDetails, Permission, Reply, Scope},
+    api::{
+        AboutStorageQuota, Comment, CommentQuotedFileContent, Drive, File, FileShortcutDetails,
+        Permission, Reply, Revision, Scope,
+    },
+    hyper,
     hyper_rustls::{self, HttpsConnector},
     hyper_util::{self, client::legacy::connect::HttpConnector},
     DriveHub,
 };
-use google_sheets4::{self, Sheets};
+use google_sheets4::{
+    self,
+    api::{
+        AddConditionalFormatRuleRequest, BooleanCondition, BooleanRule, CellFormat, Color,
+        ConditionValue, ConditionalFormatRule, DeleteConditionalFormatRuleRequest,
+        DeveloperMetadata, DeveloperMetadataLocation, DimensionRange, GridRange, TextFormat,
+    },
+    Sheets,
+};
 use http_body_util::BodyExt;
 
 // Constants for credential storage
@@ -43,6 +65,45 @@ pub const KEYCHAIN_DISK_FALLBACK_ENV: &str = "GOOGLE_DRIVE_DISK_FALLBACK";
 
 const GOOGLE_DRIVE_SCOPES: Scope = Scope::Full;
 
+/// How long the shared drive id to name mapping is reused before listing drives again
+const DRIVE_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Content larger than this is uploaded in chunks with a resumable upload,
+/// as a single request upload fails for large files
+const RESUMABLE_UPLOAD_THRESHOLD: u64 = 5 * 1024 * 1024;
+
+/// Size of each chunk of a resumable upload (a power of two of at least 256 KB)
+const RESUMABLE_UPLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Logs the progress of a resumable upload every 10% of completion
+struct UploadProgress {
+    description: String,
+    logged_tenths: u64,
+}
+
+impl Delegate for UploadProgress {
+    fn chunk_size(&mut self) -> u64 {
+        RESUMABLE_UPLOAD_CHUNK_SIZE
+    }
+
+    // Called before each chunk is sent, so the start of the chunk is the amount uploaded so far
+    fn cancel_chunk_upload(&mut self, chunk: &ContentRange) -> bool {
+        if let Some(range) = &chunk.range {
+            let tenths = range.first * 10 / chunk.total_length.max(1);
+            if tenths > self.logged_tenths {
+                self.logged_tenths = tenths;
+                tracing::info!(
+                    "Uploading {}: {}% of {} bytes",
+                    self.description,
+                    tenths * 10,
+                    chunk.total_length
+                );
+            }
+        }
+        false
+    }
+}
+
 #[derive(Debug)]
 enum FileOperation {
     Create { name: String },
@@ -70,12 +131,664 @@ lazy_static! {
             .unwrap();
 }
 
+/// Escape a value for a single quoted string of a Drive search query
+fn escape_query_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
 fn extract_google_drive_id(url: &str) -> Option<&str> {
     GOOGLE_DRIVE_ID_REGEX
         .captures(url)
         .and_then(|caps| caps.get(1).map(|m| m.as_str()))
 }
 
+/// Find the shared drive whose name contains `name`, ignoring case
+///
+/// `drives` maps the id of each drive to its name, as several drives can share a name. An exact
+/// name match wins over partial matches; otherwise the name must match exactly one drive.
+fn find_drive_id(
+    drives: &HashMap<String, String>,
+    name: &str,
+) -> Result<(String, String), ToolError> {
+    let needle = name.to_lowercase();
+    let mut matches: Vec<(&String, &String)> = drives
+        .iter()
+        .map(|(id, drive_name)| (drive_name, id))
+        .filter(|(drive_name, _)| drive_name.to_lowercase() == needle)
+        .collect();
+    if matches.is_empty() {
+        matches = drives
+            .iter()
+            .map(|(id, drive_name)| (drive_name, id))
+            .filter(|(drive_name, _)| drive_name.to_lowercase().contains(&needle))
+            .collect();
+    }
+    matches.sort();
+    match matches.as_slice() {
+        [] => Err(ToolError::InvalidParameters(format!(
+            "No shared drive name contains '{}'",
+            name
+        ))),
+        [(drive_name, id)] => Ok((drive_name.to_string(), id.to_string())),
+        _ => Err(ToolError::InvalidParameters(format!(
+            "Multiple shared drives match '{}': {}",
+            name,
+            matches
+                .iter()
+                .map(|(drive_name, id)| format!("{} (uri: {})", drive_name, id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Build the location of developer metadata from the sheets_tool parameters
+///
+/// The location is the whole spreadsheet by default, or a sheet, rows, or columns of the sheet
+/// given by sheetId. Rows and columns span startIndex (inclusive) to endIndex (exclusive).
+fn developer_metadata_location(params: &Value) -> Result<DeveloperMetadataLocation, ToolError> {
+    let location = params
+        .get("location")
+        .and_then(|q| q.as_str())
+        .unwrap_or("spreadsheet");
+    let index = |name: &str| {
+        params
+            .get(name)
+            .and_then(|q| q.as_i64())
+            .and_then(|n| i32::try_from(n).ok())
+    };
+    let sheet_id = || {
+        index("sheetId").ok_or(ToolError::InvalidParameters(format!(
+            "The sheetId parameter is required for the {} location",
+            location
+        )))
+    };
+
+    match location {
+        "spreadsheet" => Ok(DeveloperMetadataLocation {
+            spreadsheet: Some(true),
+            ..Default::default()
+        }),
+        "sheet" => Ok(DeveloperMetadataLocation {
+            sheet_id: Some(sheet_id()?),
+            ..Default::default()
+        }),
+        "row" | "column" => {
+            let start_index = index("startIndex").ok_or(ToolError::InvalidParameters(format!(
+                "The startIndex parameter is required for the {} location",
+                location
+            )))?;
+            Ok(DeveloperMetadataLocation {
+                dimension_range: Some(DimensionRange {
+                    dimension: Some(if location == "row" { "ROWS" } else { "COLUMNS" }.to_string()),
+                    sheet_id: Some(sheet_id()?),
+                    start_index: Some(start_index),
+                    end_index: Some(index("endIndex").unwrap_or(start_index + 1)),
+                }),
+                ..Default::default()
+            })
+        }
+        _ => Err(ToolError::InvalidParameters(format!(
+            "location must be either 'spreadsheet', 'sheet', 'row', or 'column', got {}",
+            location
+        ))),
+    }
+}
+
+/// Describe developer metadata as `{metadata_id, key, value, location}`
+fn developer_metadata_json(metadata: &DeveloperMetadata) -> Value {
+    let location = match &metadata.location {
+        Some(DeveloperMetadataLocation {
+            dimension_range: Some(range),
+            ..
+        }) => json!({
+            "type": if range.dimension.as_deref() == Some("COLUMNS") { "column" } else { "row" },
+            "sheetId": range.sheet_id,
+            "startIndex": range.start_index,
+            "endIndex": range.end_index,
+        }),
+        Some(DeveloperMetadataLocation {
+            sheet_id: Some(sheet_id),
+            ..
+        }) => json!({"type": "sheet", "sheetId": sheet_id}),
+        _ => json!({"type": "spreadsheet"}),
+    };
+    json!({
+        "metadata_id": metadata.metadata_id,
+        "key": metadata.metadata_key,
+        "value": metadata.metadata_value,
+        "location": location,
+    })
+}
+
+/// Convert sheet values to CSV, one line per row (or column, for the COLUMNS major dimension)
+fn values_to_csv(values: Vec<Vec<Value>>) -> String {
+    let mut csv_string = String::new();
+    for row in values {
+        let row_values: Vec<String> = row
+            .into_iter()
+            .map(|cell| cell.as_str().unwrap_or_default().to_string())
+            .collect();
+        csv_string.push_str(&row_values.join(","));
+        csv_string.push('\n');
+    }
+    csv_string
+}
+
+/// Describe the ranges of a batch read as `[{range, values_csv}]`, in the order they were requested
+fn batch_values_json(value_ranges: Vec<google_sheets4::api::ValueRange>) -> Value {
+    value_ranges
+        .into_iter()
+        .map(|value_range| {
+            json!({
+                "range": value_range.range.unwrap_or_default(),
+                "values_csv": values_to_csv(value_range.values.unwrap_or_default()),
+            })
+        })
+        .collect()
+}
+
+/// The A1 column letters for a zero-based column index, e.g. `0` is `A` and `26` is `AA`
+fn column_letters(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+/// The zero-based index of A1 column letters, e.g. `A` is `0` and `AA` is `26`
+fn column_index(letters: &str) -> Option<usize> {
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    letters
+        .to_ascii_uppercase()
+        .bytes()
+        .try_fold(0usize, |index, b| {
+            index.checked_mul(26)?.checked_add((b - b'A') as usize + 1)
+        })
+        .map(|index| index - 1)
+}
+
+/// The grid range of an A1 range on the sheet `sheet_id`, e.g. `A1:D10`, `B:B`, or `2:5`.
+/// A sheet name before `!` is ignored.
+fn grid_range(sheet_id: i32, a1: &str) -> Result<GridRange, ToolError> {
+    let invalid = || ToolError::InvalidParameters(format!("Invalid A1 range '{}'", a1));
+    let cells = a1.rsplit('!').next().unwrap_or_default();
+    let (start, end) = cells.split_once(':').unwrap_or((cells, cells));
+
+    // Split a cell reference into its zero-based column and row, either of which may be missing
+    let cell = |reference: &str| -> Result<(Option<i32>, Option<i32>), ToolError> {
+        let reference = reference.trim().replace('$', "");
+        let digits = reference.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let letters = &reference[..reference.len() - digits.len()];
+        let column = match letters {
+            "" => None,
+            letters => Some(column_index(letters).ok_or_else(invalid)?),
+        };
+        let row = match digits {
+            "" => None,
+            digits => Some(
+                digits
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&row| row > 0)
+                    .ok_or_else(invalid)?
+                    - 1,
+            ),
+        };
+        if column.is_none() && row.is_none() {
+            return Err(invalid());
+        }
+        let to_i32 = |n: usize| i32::try_from(n).map_err(|_| invalid());
+        Ok((
+            column.map(to_i32).transpose()?,
+            row.map(to_i32).transpose()?,
+        ))
+    };
+    let (start_column, start_row) = cell(start)?;
+    let (end_column, end_row) = cell(end)?;
+
+    Ok(GridRange {
+        sheet_id: Some(sheet_id),
+        start_column_index: start_column,
+        end_column_index: end_column.map(|column| column + 1),
+        start_row_index: start_row,
+        end_row_index: end_row.map(|row| row + 1),
+    })
+}
+
+/// The A1 notation of a grid range, without the sheet name
+fn grid_range_a1(range: &GridRange) -> String {
+    let cell = |column: Option<i32>, row: Option<i32>| {
+        format!(
+            "{}{}",
+            column
+                .map(|column| column_letters(column as usize))
+                .unwrap_or_default(),
+            row.map(|row| (row + 1).to_string()).unwrap_or_default()
+        )
+    };
+    format!(
+        "{}:{}",
+        cell(range.start_column_index, range.start_row_index),
+        cell(
+            range.end_column_index.map(|column| column - 1),
+            range.end_row_index.map(|row| row - 1)
+        )
+    )
+}
+
+/// A color from `#RRGGBB`
+fn parse_color(hex: &str) -> Result<Color, ToolError> {
+    let invalid =
+        || ToolError::InvalidParameters(format!("Invalid color '{}', expected #RRGGBB", hex));
+    let digits = hex.strip_prefix('#').ok_or_else(invalid)?;
+    if digits.len() != 6 {
+        return Err(invalid());
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(digits.get(i..i + 2).ok_or_else(invalid)?, 16)
+            .map(|value| f32::from(value) / 255.0)
+            .map_err(|_| invalid())
+    };
+    Ok(Color {
+        red: Some(channel(0)?),
+        green: Some(channel(2)?),
+        blue: Some(channel(4)?),
+        alpha: None,
+    })
+}
+
+/// The `#RRGGBB` notation of a color
+fn color_hex(color: &Color) -> String {
+    let channel = |value: Option<f32>| (value.unwrap_or(0.0) * 255.0).round() as u8;
+    format!(
+        "#{:02X}{:02X}{:02X}",
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue)
+    )
+}
+
+/// The condition of a conditional formatting rule of the given rule_type.
+///
+/// For cell_value rules the condition_value may start with a comparison operator
+/// (`>`, `>=`, `<`, `<=`, `=`, `!=`), and compares numbers unless the value is text.
+fn boolean_condition(
+    rule_type: &str,
+    condition_value: Option<&str>,
+) -> Result<BooleanCondition, ToolError> {
+    let value = || {
+        condition_value.ok_or(ToolError::InvalidParameters(format!(
+            "The condition_value is required for {} rules",
+            rule_type
+        )))
+    };
+    let (condition_type, value) = match rule_type {
+        "cell_value" => {
+            let value = value()?.trim();
+            let (operator, operand) = [">=", "<=", "!=", ">", "<", "="]
+                .into_iter()
+                .find_map(|operator| {
+                    value
+                        .strip_prefix(operator)
+                        .map(|operand| (operator, operand.trim()))
+                })
+                .unwrap_or(("=", value));
+            let condition_type = match (operator, operand.parse::<f64>().is_ok()) {
+                (">=", _) => "NUMBER_GREATER_THAN_EQ",
+                ("<=", _) => "NUMBER_LESS_THAN_EQ",
+                (">", _) => "NUMBER_GREATER",
+                ("<", _) => "NUMBER_LESS",
+                ("!=", true) => "NUMBER_NOT_EQ",
+                ("=", true) => "NUMBER_EQ",
+                _ => "TEXT_EQ",
+            };
+            if operator == "!=" && condition_type == "TEXT_EQ" {
+                return Err(ToolError::InvalidParameters(
+                    "cell_value rules only support != for numbers".to_string(),
+                ));
+            }
+            (condition_type, Some(operand))
+        }
+        "text_contains" => ("TEXT_CONTAINS", Some(value()?)),
+        "blank" => ("BLANK", None),
+        "custom_formula" => ("CUSTOM_FORMULA", Some(value()?)),
+        _ => {
+            return Err(ToolError::InvalidParameters(format!(
+                "rule_type must be one of 'cell_value', 'text_contains', 'blank', or 'custom_formula', got {}",
+                rule_type
+            )))
+        }
+    };
+    Ok(BooleanCondition {
+        type_: Some(condition_type.to_string()),
+        values: value.map(|value| {
+            vec![ConditionValue {
+                user_entered_value: Some(value.to_string()),
+                ..Default::default()
+            }]
+        }),
+    })
+}
+
+/// The cell format of a conditional formatting rule from `{bold, italic, background_color, text_color}`
+fn conditional_cell_format(format: &Value) -> Result<CellFormat, ToolError> {
+    let color = |name: &str| {
+        format
+            .get(name)
+            .and_then(|q| q.as_str())
+            .map(parse_color)
+            .transpose()
+    };
+    let text_format = TextFormat {
+        bold: format.get("bold").and_then(|q| q.as_bool()),
+        italic: format.get("italic").and_then(|q| q.as_bool()),
+        foreground_color: color("text_color")?,
+        ..Default::default()
+    };
+    let has_text_format = text_format.bold.is_some()
+        || text_format.italic.is_some()
+        || text_format.foreground_color.is_some();
+    let cell_format = CellFormat {
+        background_color: color("background_color")?,
+        text_format: has_text_format.then_some(text_format),
+        ..Default::default()
+    };
+    if cell_format.background_color.is_none() && cell_format.text_format.is_none() {
+        return Err(ToolError::InvalidParameters(
+            "The format must set at least one of bold, italic, background_color, or text_color"
+                .to_string(),
+        ));
+    }
+    Ok(cell_format)
+}
+
+/// Describe a conditional formatting rule as `{rule_index, ranges, condition, format}`
+fn conditional_format_rule_json(rule_index: usize, rule: &ConditionalFormatRule) -> Value {
+    let ranges: Vec<String> = rule.ranges.iter().flatten().map(grid_range_a1).collect();
+    let Some(boolean_rule) = &rule.boolean_rule else {
+        return json!({"rule_index": rule_index, "ranges": ranges, "type": "gradient"});
+    };
+    let condition = boolean_rule.condition.as_ref();
+    let format = boolean_rule.format.as_ref();
+    let text_format = format.and_then(|format| format.text_format.as_ref());
+    json!({
+        "rule_index": rule_index,
+        "ranges": ranges,
+        "condition": {
+            "type": condition.and_then(|condition| condition.type_.as_deref()),
+            "values": condition
+                .and_then(|condition| condition.values.as_ref())
+                .into_iter()
+                .flatten()
+                .filter_map(|value| value.user_entered_value.as_deref())
+                .collect::<Vec<_>>(),
+        },
+        "format": {
+            "bold": text_format.and_then(|text_format| text_format.bold),
+            "italic": text_format.and_then(|text_format| text_format.italic),
+            "background_color": format
+                .and_then(|format| format.background_color.as_ref())
+                .map(color_hex),
+            "text_color": text_format
+                .and_then(|text_format| text_format.foreground_color.as_ref())
+                .map(color_hex),
+        },
+    })
+}
+
+/// The zero-based indices of the rows below the header whose cell in `filter_column`, a header
+/// name or column letters, satisfies `matches`. Missing cells are empty.
+fn matching_rows(
+    sheet: &[Vec<Value>],
+    filter_column: &str,
+    matches: impl Fn(&str) -> bool,
+) -> Result<Vec<usize>, ToolError> {
+    let header = sheet
+        .first()
+        .ok_or_else(|| ToolError::InvalidParameters("The sheet has no header row".to_string()))?;
+    let column = header
+        .iter()
+        .position(|cell| cell.as_str().map(str::trim) == Some(filter_column))
+        .or_else(|| column_index(filter_column).filter(|&index| index < header.len()))
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(format!(
+                "The sheet has no column named '{}'",
+                filter_column
+            ))
+        })?;
+    Ok(sheet
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, row)| matches(row.get(column).and_then(Value::as_str).unwrap_or_default()))
+        .map(|(index, _)| index)
+        .collect())
+}
+
+/// Group sorted row indices into `(start, end)` blocks of contiguous rows, end exclusive,
+/// from the bottom of the sheet up so deleting a block leaves the others' indices unchanged
+fn contiguous_blocks(rows: &[usize]) -> Vec<(usize, usize)> {
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    for &row in rows {
+        match blocks.last_mut() {
+            Some((_, end)) if *end == row => *end += 1,
+            _ => blocks.push((row, row + 1)),
+        }
+    }
+    blocks.reverse();
+    blocks
+}
+
+/// The changes a `smart_update_values` makes to a sheet
+#[derive(Debug, PartialEq)]
+struct SmartUpdatePlan {
+    /// Incoming cells that change an existing row, by one-based row number and zero-based
+    /// column. Only these cells are written, the rest of the row keeps its formulas and formats
+    updates: Vec<(usize, Vec<(usize, Value)>)>,
+    /// Incoming rows whose key is not in the sheet
+    appends: Vec<Vec<Value>>,
+    /// Incoming rows without a key or that would not change their row
+    skipped: usize,
+}
+
+/// Match the rows of a CSV, whose first line is a header naming sheet columns, against
+/// the rows of a sheet by the value in `key_column`
+fn plan_smart_update(
+    sheet: &[Vec<Value>],
+    values_csv: &str,
+    key_column: &str,
+) -> Result<SmartUpdatePlan, ToolError> {
+    let cell_text = |cell: &Value| cell.as_str().unwrap_or_default().trim().to_string();
+    let header: Vec<String> = sheet
+        .first()
+        .ok_or_else(|| ToolError::InvalidParameters("The sheet has no header row".to_string()))?
+        .iter()
+        .map(cell_text)
+        .collect();
+    let sheet_key = header
+        .iter()
+        .position(|name| name == key_column)
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(format!("The sheet has no column named '{}'", key_column))
+        })?;
+
+    let mut lines = values_csv.lines().filter(|line| !line.trim().is_empty());
+    let incoming_header: Vec<&str> = lines
+        .next()
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(
+                "The values must start with a header row naming the columns".to_string(),
+            )
+        })?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    // Where each incoming column goes in the sheet
+    let columns = incoming_header
+        .iter()
+        .map(|name| {
+            header.iter().position(|h| h == name).ok_or_else(|| {
+                ToolError::InvalidParameters(format!("The sheet has no column named '{}'", name))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let incoming_key = incoming_header
+        .iter()
+        .position(|name| *name == key_column)
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(format!(
+                "The values header must include the key column '{}'",
+                key_column
+            ))
+        })?;
+
+    // The first row holding each key, skipping the header
+    let mut rows_by_key = HashMap::new();
+    for (index, row) in sheet.iter().enumerate().skip(1) {
+        if let Some(key) = row.get(sheet_key).map(cell_text) {
+            if !key.is_empty() {
+                rows_by_key.entry(key).or_insert(index);
+            }
+        }
+    }
+
+    let mut plan = SmartUpdatePlan {
+        updates: Vec::new(),
+        appends: Vec::new(),
+        skipped: 0,
+    };
+    for line in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let key = cells.get(incoming_key).copied().unwrap_or_default();
+        if key.is_empty() {
+            plan.skipped += 1;
+            continue;
+        }
+        match rows_by_key.get(key).copied() {
+            Some(index) => {
+                let changed: Vec<(usize, Value)> = cells
+                    .iter()
+                    .zip(&columns)
+                    .filter(|(cell, &column)| {
+                        sheet[index].get(column).map(cell_text).unwrap_or_default() != **cell
+                    })
+                    .map(|(cell, &column)| (column, Value::String(cell.to_string())))
+                    .collect();
+                if changed.is_empty() {
+                    plan.skipped += 1;
+                } else {
+                    plan.updates.push((index + 1, changed));
+                }
+            }
+            None => {
+                let mut row = vec![String::new(); header.len()];
+                for (cell, &column) in cells.iter().zip(&columns) {
+                    row[column] = cell.to_string();
+                }
+                plan.appends
+                    .push(row.into_iter().map(Value::String).collect());
+            }
+        }
+    }
+    Ok(plan)
+}
+
+/// Format files as `name (mimeType) (uri: id)`, one per line
+fn format_file_list(files: Option<Vec<File>>) -> String {
+    files
+        .into_iter()
+        .flatten()
+        .map(|f| {
+            format!(
+                "{} ({}) (uri: {})",
+                f.name.unwrap_or_default(),
+                f.mime_type.unwrap_or_default(),
+                f.id.unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format a byte count in binary units, e.g. `5.2 GB`
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Describe a storage quota as a human readable summary followed by the raw byte counts
+fn format_storage_quota(quota: &AboutStorageQuota) -> String {
+    let usage = quota.usage.unwrap_or_default();
+    let summary = match quota.limit {
+        Some(limit) => format!("{} / {}", format_bytes(usage), format_bytes(limit)),
+        None => format!("{} used (unlimited)", format_bytes(usage)),
+    };
+    let details = json!({
+        "usage_bytes": usage,
+        "limit_bytes": quota.limit,
+        "usage_in_drive_bytes": quota.usage_in_drive.unwrap_or_default(),
+        "usage_in_trash_bytes": quota.usage_in_drive_trash.unwrap_or_default(),
+        "is_unlimited": quota.limit.is_none(),
+    });
+    format!(
+        "Storage: {}\n{}",
+        summary,
+        serde_json::to_string_pretty(&details).unwrap_or_default()
+    )
+}
+
+/// Concatenate the text runs of a document's body paragraphs
+fn document_body_text(document: &google_docs1::api::Document) -> String {
+    document
+        .body
+        .iter()
+        .flat_map(|body| body.content.iter().flatten())
+        .filter_map(|item| item.paragraph.as_ref())
+        .flat_map(|paragraph| paragraph.elements.iter().flatten())
+        .filter_map(|element| element.text_run.as_ref())
+        .filter_map(|text_run| text_run.content.as_deref())
+        .collect()
+}
+
+/// Build the Drive API anchor for the first occurrence of `anchor_text` in `text`
+///
+/// Offsets and lengths are counted in UTF-16 code units, matching Google Docs indexes.
+fn comment_anchor(revision_id: &str, text: &str, anchor_text: &str) -> Option<String> {
+    if anchor_text.is_empty() {
+        return None;
+    }
+    let start = text.find(anchor_text)?;
+    let anchor = json!({
+        "r": revision_id,
+        "a": [{
+            "txt": {
+                "o": text[..start].encode_utf16().count(),
+                "l": anchor_text.encode_utf16().count(),
+                "ml": text.encode_utf16().count(),
+            }
+        }]
+    });
+    Some(anchor.to_string())
+}
+
 pub struct GoogleDriveRouter {
     tools: Vec<Tool>,
     instructions: String,
@@ -83,6 +796,8 @@ pub struct GoogleDriveRouter {
     sheets: Sheets<HttpsConnector<HttpConnector>>,
     docs: Docs<HttpsConnector<HttpConnector>>,
     credentials_manager: Arc<CredentialsManager>,
+    drive_names_by_id: Arc<RwLock<HashMap<String, String>>>,
+    drive_ids_fetched_at: Arc<RwLock<Option<Instant>>>,
 }
 
 impl GoogleDriveRouter {
@@ -159,6 +874,27 @@ impl GoogleDriveRouter {
             KEYCHAIN_USERNAME.to_string(),
         ));
 
+        // Record the outcome of token refreshes so operators can tell when re-auth is needed
+        credentials_manager.set_token_refresh_callback(Box::new(|event| {
+            if event.succeeded {
+                tracing::debug!(expires_at = ?event.next_expiry, "Refreshed Google Drive token");
+            } else {
+                tracing::error!(
+                    error = event.error.as_deref().unwrap_or_default(),
+                    "Failed to refresh Google Drive token, re-authentication is required"
+                );
+            }
+
+            let status_path = auth_status_path();
+            if let Err(e) = AuthStatus::from(&event).write(&status_path) {
+                tracing::error!(
+                    "Failed to write auth status to {}: {}",
+                    status_path.display(),
+                    e
+                );
+            }
+        }));
+
         // Read the OAuth credentials from the keyfile
         match fs::read_to_string(keyfile_path) {
             Ok(_) => {
@@ -226,6 +962,10 @@ impl GoogleDriveRouter {
                     "type": "string",
                     "description": "ID of a shared drive to constrain the search to when using the corpus 'drive'.",
                 },
+                "driveName": {
+                    "type": "string",
+                    "description": "Name (or part of the name) of a shared drive to search, instead of a driveId.",
+                },
                 "corpora": {
                     "type": "string",
                     "description": "Which corpus to search, either 'user' (default), 'drive' (requires a driveID) or 'allDrives'",
@@ -273,6 +1013,10 @@ impl GoogleDriveRouter {
                   "includeImages": {
                       "type": "boolean",
                       "description": "Whether or not to include images as base64 encoded strings, defaults to false",
+                  },
+                  "allSheets": {
+                      "type": "boolean",
+                      "description": "For a Google Sheet, whether to read every sheet instead of only the first one, defaults to false",
                   }
               },
             }),
@@ -318,6 +1062,10 @@ impl GoogleDriveRouter {
                       "type": "string",
                       "description": "ID of the file to target when creating a shortcut",
                   },
+                  "driveName": {
+                      "type": "string",
+                      "description": "Name (or part of the name) of a shared drive to create the file in, when no parentId is given",
+                  },
                   "allowSharedDrives": {
                       "type": "boolean",
                       "description": "Whether to allow access to shared drives or just your personal drive (default: false)",
@@ -367,6 +1115,73 @@ impl GoogleDriveRouter {
             }),
         );
 
+        let copy_file_tool = Tool::new(
+            "copy_file".to_string(),
+            indoc! {r#"
+                Copy a Google Drive file, optionally with a new name or into a different folder.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                  "fileId": {
+                      "type": "string",
+                      "description": "The ID of the file to copy.",
+                  },
+                  "name": {
+                      "type": "string",
+                      "description": "Name of the copy (default: 'Copy of' followed by the name of the file)",
+                  },
+                  "parentId": {
+                      "type": "string",
+                      "description": "ID of the folder to put the copy in (default: the folder of the file)",
+                  },
+                  "allowSharedDrives": {
+                      "type": "boolean",
+                      "description": "Whether to allow access to shared drives or just your personal drive (default: false)",
+                  }
+              },
+              "required": ["fileId"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("Copy file".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let delete_file_tool = Tool::new(
+            "delete_file".to_string(),
+            indoc! {r#"
+                Delete a Google Drive file or folder by moving it to the trash, from which it can be restored.
+                Set permanent to delete it for good instead.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                  "fileId": {
+                      "type": "string",
+                      "description": "The ID of the file to delete.",
+                  },
+                  "permanent": {
+                      "type": "boolean",
+                      "description": "Delete the file permanently, skipping the trash (default: false). WARNING: this cannot be undone, and deleting a folder also deletes everything in it. Only set it when the user explicitly asks for a permanent deletion.",
+                  },
+              },
+              "required": ["fileId"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("Delete file".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
         let update_file_tool = Tool::new(
             "update_file".to_string(),
             indoc! {r#"
@@ -416,10 +1231,20 @@ impl GoogleDriveRouter {
                 - list_sheets: List all sheets in a spreadsheet
                 - get_columns: Get column headers from a specific sheet
                 - get_values: Get values from a range
+                - batch_get_values: Get values from several ranges (up to 20) in a single request
                 - update_values: Update values in a range
+                - smart_update_values: Update the rows matching a key column and append the rest
+                - delete_matching_rows: Delete every row whose value in a column matches a filter
                 - update_cell: Update a single cell value
                 - add_sheet: Add a new sheet (tab) to a spreadsheet
                 - clear_values: Clear values from a range
+                - developer_metadata_list: List the key-value metadata attached to the spreadsheet, its sheets, rows, or columns
+                - developer_metadata_get: Get a metadata entry by its metadataId
+                - developer_metadata_set: Set the value of a metadata key at a location (non-destructive)
+                - named_functions_list, named_functions_set: Not available, as the Sheets API does not expose named functions
+                - conditional_formatting_list: List the conditional formatting rules of a sheet
+                - conditional_formatting_add: Add a conditional formatting rule to a range of a sheet
+                - conditional_formatting_delete: Delete a conditional formatting rule by its rule_index
             "#}
             .to_string(),
             json!({
@@ -431,7 +1256,7 @@ impl GoogleDriveRouter {
                   },
                   "operation": {
                       "type": "string",
-                      "enum": ["list_sheets", "get_columns", "get_values", "update_values", "update_cell", "add_sheet", "clear_values"],
+                      "enum": ["list_sheets", "get_columns", "get_values", "batch_get_values", "update_values", "smart_update_values", "delete_matching_rows", "update_cell", "add_sheet", "clear_values", "developer_metadata_list", "developer_metadata_get", "developer_metadata_set", "named_functions_list", "named_functions_set", "conditional_formatting_list", "conditional_formatting_add", "conditional_formatting_delete"],
                       "description": "The operation to perform on the spreadsheet",
                   },
                   "sheetName": {
@@ -442,9 +1267,39 @@ impl GoogleDriveRouter {
                       "type": "string",
                       "description": "The A1 notation of the range to retrieve or update values (e.g., 'Sheet1!A1:D10')",
                   },
+                  "ranges": {
+                      "type": "array",
+                      "items": {"type": "string"},
+                      "description": "The A1 notation of up to 20 ranges to retrieve (required for batch_get_values)",
+                  },
+                  "majorDimension": {
+                      "type": "string",
+                      "enum": ["ROWS", "COLUMNS"],
+                      "description": "Whether batch_get_values returns a CSV line per row or per column (default: ROWS)",
+                  },
                   "values": {
                       "type": "string",
-                      "description": "CSV formatted data for update operations (required for update_values)",
+                      "description": "CSV formatted data for update operations (required for update_values and smart_update_values)",
+                  },
+                  "filter_column": {
+                      "type": "string",
+                      "description": "The header or column letter to filter on (required for delete_matching_rows)",
+                  },
+                  "filter_value": {
+                      "type": "string",
+                      "description": "The exact cell value, or regex with use_regex, of rows to delete (required for delete_matching_rows)",
+                  },
+                  "use_regex": {
+                      "type": "boolean",
+                      "description": "Match filter_value as a regular expression (default: false)",
+                  },
+                  "allow_bulk_delete": {
+                      "type": "boolean",
+                      "description": "Allow delete_matching_rows to delete more than 90% of the rows (default: false)",
+                  },
+                  "key_column": {
+                      "type": "string",
+                      "description": "The header of the column identifying rows (required for smart_update_values)",
                   },
                   "cell": {
                       "type": "string",
@@ -462,6 +1317,58 @@ impl GoogleDriveRouter {
                       "type": "string",
                       "enum": ["RAW", "USER_ENTERED"],
                       "description": "How input data should be interpreted (default: USER_ENTERED)",
+                  },
+                  "metadataId": {
+                      "type": "number",
+                      "description": "The ID of a metadata entry (required for developer_metadata_get)",
+                  },
+                  "metadataKey": {
+                      "type": "string",
+                      "description": "The metadata key (required for developer_metadata_set, filters developer_metadata_list)",
+                  },
+                  "metadataValue": {
+                      "type": "string",
+                      "description": "The metadata value (required for developer_metadata_set)",
+                  },
+                  "location": {
+                      "type": "string",
+                      "enum": ["spreadsheet", "sheet", "row", "column"],
+                      "description": "What the metadata is attached to for developer_metadata_set (default: spreadsheet)",
+                  },
+                  "sheetId": {
+                      "type": "number",
+                      "description": "The ID of the sheet for the sheet, row, and column metadata locations and the conditional formatting operations (see list_sheets)",
+                  },
+                  "rule_type": {
+                      "type": "string",
+                      "enum": ["cell_value", "text_contains", "blank", "custom_formula"],
+                      "description": "What cells the rule applies to (required for conditional_formatting_add)",
+                  },
+                  "condition_value": {
+                      "type": "string",
+                      "description": "The value compared for cell_value rules, optionally after >, >=, <, <=, = or != (e.g. '>100'), the text for text_contains rules, or the formula for custom_formula rules",
+                  },
+                  "format": {
+                      "type": "object",
+                      "properties": {
+                          "bold": {"type": "boolean"},
+                          "italic": {"type": "boolean"},
+                          "background_color": {"type": "string", "description": "#RRGGBB"},
+                          "text_color": {"type": "string", "description": "#RRGGBB"}
+                      },
+                      "description": "The format of the cells matching the rule (required for conditional_formatting_add)",
+                  },
+                  "rule_index": {
+                      "type": "number",
+                      "description": "The index of the rule to delete, from conditional_formatting_list (required for conditional_formatting_delete)",
+                  },
+                  "startIndex": {
+                      "type": "number",
+                      "description": "Zero-based index of the first row or column for the row and column metadata locations",
+                  },
+                  "endIndex": {
+                      "type": "number",
+                      "description": "Zero-based index after the last row or column (default: startIndex + 1)",
                   }
               },
               "required": ["spreadsheetId", "operation"],
@@ -548,7 +1455,7 @@ impl GoogleDriveRouter {
         let create_comment_tool = Tool::new(
             "create_comment".to_string(),
             indoc! {r#"
-                Create a comment for the latest revision of a Google Drive file. The Google Drive API only supports unanchored comments (they don't refer to a specific location in the file).
+                Create a comment for the latest revision of a Google Drive file. Comments are unanchored (they don't refer to a specific location in the file) unless anchor_text is given for a Google Doc, in which case the comment is anchored to the first occurrence of that text.
             "#}
             .to_string(),
             json!({
@@ -561,6 +1468,10 @@ impl GoogleDriveRouter {
                 "comment": {
                     "type": "string",
                     "description": "Content of the comment.",
+                },
+                "anchor_text": {
+                    "type": "string",
+                    "description": "Text in a Google Doc to anchor the comment to. The first occurrence is used.",
                 }
               },
               "required": ["fileId", "comment"],
@@ -717,35 +1628,254 @@ impl GoogleDriveRouter {
             }),
         );
 
-        let instructions = indoc::formatdoc! {r#"
-            Google Drive MCP Server Instructions
-
-            ## Overview
-            The Google Drive MCP server provides tools for interacting with Google Drive files, Google Sheets, and Google Docs:
-            1. search - Find files in your Google Drive
-            2. read - Read file contents directly using a uri in the `gdrive:///uri` format
-            3. move_file - Move a file to a new location in Google Drive
-            4. list_drives - List the shared drives to which you have access
-            5. get_permissions - List the permissions of a file or folder
-            6. sharing - Share a file or folder with others
-            7. get_comments - List a file or folder's comments
-            8. create_comment - Create a comment on a file or folder
-            9. reply - Reply to a comment on a file or folder
-            10. create_file - Create a new file
-            11. update_file - Update a existing file
-            12. sheets_tool - Work with Google Sheets data using various operations
-            13. docs_tool - Work with Google Docs data using various operations
-
-            ## Available Tools
-
-            ### 1. Search Tool
-            Search for files in Google Drive, by name and ordered by most recently viewedByMeTime.
-            A corpora parameter controls which corpus is searched.
-            Returns: List of files with their names, MIME types, and IDs
-
+        let create_permission_tool = Tool::new(
+            "create_permission".to_string(),
+            indoc! {r#"
+                Share a Google Drive file or folder by giving a user, group, domain, or anyone a role on it.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "fileId": {
+                    "type": "string",
+                    "description": "Id of the file or folder.",
+                },
+                "role": {
+                    "type": "string",
+                    "description": "Role to grant.",
+                    "enum": ["reader", "commenter", "writer", "owner"]
+                },
+                "type": {
+                    "type": "string",
+                    "description": "Type of the grantee.",
+                    "enum": ["user", "group", "domain", "anyone"],
+                },
+                "emailAddress": {
+                    "type": "string",
+                    "description": "Email address of the user or group, required for the user and group types.",
+                },
+                "domain": {
+                    "type": "string",
+                    "description": "Domain name, required for the domain type.",
+                },
+              },
+              "required": ["fileId", "role", "type"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("Create sharing permission".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let delete_permission_tool = Tool::new(
+            "delete_permission".to_string(),
+            indoc! {r#"
+                Remove a sharing permission from a Google Drive file or folder.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "fileId": {
+                    "type": "string",
+                    "description": "Id of the file or folder.",
+                },
+                "permissionId": {
+                    "type": "string",
+                    "description": "Id of the permission, as listed by get_permissions.",
+                },
+              },
+              "required": ["fileId", "permissionId"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("Delete sharing permission".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let list_revisions_tool = Tool::new(
+            "list_revisions".to_string(),
+            indoc! {r#"
+                List the revisions of a Google Drive file, oldest first.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "fileId": {
+                    "type": "string",
+                    "description": "Id of the file.",
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Only list this many of the most recent revisions (default: all of them)",
+                },
+              },
+              "required": ["fileId"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("List file revisions".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let restore_revision_tool = Tool::new(
+            "restore_revision".to_string(),
+            indoc! {r#"
+                Restore a Google Drive file to an earlier revision, by making the content of that revision the newest version of the file.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "fileId": {
+                    "type": "string",
+                    "description": "Id of the file.",
+                },
+                "revisionId": {
+                    "type": "string",
+                    "description": "Id of the revision to restore, as listed by list_revisions.",
+                },
+              },
+              "required": ["fileId", "revisionId"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("Restore file revision".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let resolve_drive_tool = Tool::new(
+            "resolve_drive".to_string(),
+            indoc! {r#"
+                Find the id of a shared drive from its name, or part of its name.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name, or part of the name, of the shared drive.",
+                }
+              },
+              "required": ["name"],
+            }),
+            Some(ToolAnnotations {
+                title: Some("Resolve shared drive name".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let recent_files_tool = Tool::new(
+            "recent_files".to_string(),
+            indoc! {r#"
+                List the most recently viewed, modified, or created files in google drive, without a search query.
+                Use this to find a file the user was recently working on.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "limit": {
+                    "type": "number",
+                    "description": "How many files to return, between 1 and 100. Defaults to 10.",
+                },
+                "sort_by": {
+                    "type": "string",
+                    "description": "Which time to order the files by, most recent first. Defaults to viewedByMeTime.",
+                    "enum": ["viewedByMeTime", "modifiedTime", "createdTime"],
+                },
+                "mimeType_filter": {
+                    "type": "string",
+                    "description": "Optional mime type to only list files of that type.",
+                }
+              },
+            }),
+            Some(ToolAnnotations {
+                title: Some("List recent files".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
+        let get_quota_tool = Tool::new(
+            "get_quota".to_string(),
+            indoc! {r#"
+                Get the Google Drive storage quota: how much storage is used and how much remains.
+                Shared drives use the storage pool of their organization; pass a driveId to report
+                the quota that applies to a shared drive.
+            "#}
+            .to_string(),
+            json!({
+              "type": "object",
+              "properties": {
+                "driveId": {
+                    "type": "string",
+                    "description": "Optional id of a shared drive to report the quota for.",
+                }
+              },
+            }),
+            Some(ToolAnnotations {
+                title: Some("Get storage quota".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let instructions = indoc::formatdoc! {r#"
+            Google Drive MCP Server Instructions
+
+            ## Overview
+            The Google Drive MCP server provides tools for interacting with Google Drive files, Google Sheets, and Google Docs:
+            1. search - Find files in your Google Drive
+            2. read - Read file contents directly using a uri in the `gdrive:///uri` format
+            3. move_file - Move a file to a new location in Google Drive
+            4. list_drives - List the shared drives to which you have access
+            5. get_permissions - List the permissions of a file or folder
+            6. sharing - Share a file or folder with others
+            7. get_comments - List a file or folder's comments
+            8. create_comment - Create a comment on a file or folder
+            9. reply - Reply to a comment on a file or folder
+            10. create_file - Create a new file
+            11. update_file - Update a existing file
+            12. sheets_tool - Work with Google Sheets data using various operations
+            13. docs_tool - Work with Google Docs data using various operations
+            14. get_quota - Show used and remaining storage
+            15. recent_files - List the most recently viewed, modified, or created files
+            16. resolve_drive - Find the id of a shared drive by name
+
+            ## Available Tools
+
+            ### 1. Search Tool
+            Search for files in Google Drive, by name and ordered by most recently viewedByMeTime.
+            A corpora parameter controls which corpus is searched.
+            Returns: List of files with their names, MIME types, and IDs
+
             ### 2. Read File Tool
             Read a file's contents using its ID, and optionally include images as base64 encoded data.
             The default is to exclude images, to include images set includeImages to true in the query.
+            Only the first sheet of a Google Sheet is read, unless allSheets is set to true.
 
             Example mappings for Google Drive resources to `gdrive:///$URI` format:
             - Google Document File:
@@ -825,15 +1955,31 @@ impl GoogleDriveRouter {
             - list_sheets: List all sheets in a spreadsheet
             - get_columns: Get column headers from a specific sheet
             - get_values: Get values from a range
+            - batch_get_values: Get values from up to 20 ranges in one request, returned as JSON `[{{range, values_csv}}]`
             - update_values: Update values in a range (requires CSV formatted data)
+            - smart_update_values: Update only the rows whose key matches, appending the others
+            - delete_matching_rows: Delete the rows whose value in a column matches a filter
             - update_cell: Update a single cell value
             - add_sheet: Add a new sheet (tab) to a spreadsheet
             - clear_values: Clear values from a range
+            - developer_metadata_list, developer_metadata_get, developer_metadata_set: Manage key-value metadata
+            - conditional_formatting_list, conditional_formatting_add, conditional_formatting_delete: Manage conditional formatting rules
 
             For update_values operation, provide CSV formatted data in the values parameter.
             Each line represents a row, with values separated by commas.
             Example: "John,Doe,30\nJane,Smith,25"
 
+            For smart_update_values operation, provide the sheetName, a key_column header, and CSV data
+            whose first line names the columns to set, including the key column. Rows whose key is
+            already in the sheet have only those cells updated in place, the others are appended, and the result is
+            JSON `{{updated, appended, skipped}}`. Rows without a key or without changes are skipped.
+            Example: key_column "Email" with "Email,Status\njane@example.com,Active"
+
+            For delete_matching_rows operation, provide the sheetName, a filter_column header or letter,
+            and the filter_value to match exactly, or as a regex with use_regex. An empty filter_value
+            matches empty cells. The result is JSON `{{deleted_count, remaining_row_count}}`, not counting
+            the header row. Deleting more than 90% of the rows requires allow_bulk_delete.
+
             For update_cell operation, provide the cell reference (e.g., 'Sheet1!A1') and the value to set.
 
             Parameters:
@@ -841,12 +1987,30 @@ impl GoogleDriveRouter {
             - operation: The operation to perform (one of the operations listed above)
             - sheetName: The name of the sheet to work with (optional for some operations)
             - range: The A1 notation of the range to retrieve or update values (e.g., 'Sheet1!A1:D10')
+            - ranges: The A1 notation of each range to retrieve for batch_get_values
+            - majorDimension: ROWS (default) or COLUMNS, for batch_get_values
             - values: CSV formatted data for update operations
+            - key_column: The header of the column identifying rows for smart_update_values
+            - filter_column, filter_value, use_regex, allow_bulk_delete: The rows to delete for delete_matching_rows
             - cell: The A1 notation of the cell to update (e.g., 'Sheet1!A1') for update_cell operation
             - value: The value to set in the cell for update_cell operation
             - title: Title for the new sheet (required for add_sheet operation)
             - valueInputOption: How input data should be interpreted (RAW or USER_ENTERED)
 
+            Developer metadata operations read and write key-value pairs attached to the spreadsheet,
+            a sheet, or a range of rows or columns, returned as JSON `{{metadata_id, key, value, location}}`:
+            - developer_metadata_list: optionally filtered by metadataKey
+            - developer_metadata_get: requires metadataId
+            - developer_metadata_set: requires metadataKey and metadataValue; location defaults to the spreadsheet,
+              sheet/row/column locations require sheetId and row/column locations a startIndex
+
+            Conditional formatting operations work on the sheet given by sheetId:
+            - conditional_formatting_list: returns JSON `[{{rule_index, ranges, condition, format}}]`
+            - conditional_formatting_add: requires range (A1), rule_type (cell_value, text_contains, blank or
+              custom_formula), condition_value unless the rule_type is blank, and a format
+              `{{bold, italic, background_color, text_color}}` with colors as #RRGGBB. The new rule becomes rule 0
+            - conditional_formatting_delete: requires the rule_index of the rule to delete
+
             ### 13. Docs Tool
             Work with Google Docs data using various operations:
             - get_document: Get the full document content
@@ -865,6 +2029,46 @@ impl GoogleDriveRouter {
             - startPosition: The start position for delete_content operation
             - endPosition: The end position for delete_content operation
 
+            ### 14. Get Quota Tool
+            Reports storage usage and limit, both in bytes and human readable (e.g. 5.2 GB / 15.0 GB),
+            along with the usage by Drive files and by trashed files. Use it before large uploads.
+            Shared drives count against their organization's storage pool.
+
+            ### 15. Recent Files Tool
+            Lists the most recent files without needing a search query, ordered by
+            viewedByMeTime (default), modifiedTime, or createdTime. Returns files in the
+            same format as search. Use it when the user refers to a file they recently worked on.
+
+            ### 16. Delete File Tool
+            Moves a file or folder to the trash by ID. Only with permanent set to true is it
+            deleted for good, which cannot be undone, so only do that when the user asks for it.
+
+            ### 17. Copy File Tool
+            Copies a file by ID, optionally giving the copy a new name and putting it in another
+            folder. Returns the copy in the same format as create_file.
+
+            ### 18. Resolve Drive Tool
+            Finds the id of a shared drive from part of its name. The search and create_file
+            tools also accept a driveName parameter instead of an id. If several drives match
+            the name, the matches are listed so a more specific name can be given.
+
+            ### 19. Create Permission Tool
+            Grants a role ('reader', 'commenter', 'writer', 'owner') on a file or folder to a
+            user or group given by emailAddress, a domain, or anyone. Only share files when the
+            user asks to, and check existing access with get_permissions first.
+
+            ### 20. Delete Permission Tool
+            Removes a permission from a file or folder by its id, as listed by get_permissions.
+
+            ### 21. List Revisions Tool
+            Lists the revisions of a file, oldest first, with their id, modification time, the
+            user who made them and their size. Set limit to only list the most recent ones.
+
+            ### 22. Restore Revision Tool
+            Reverts a file to one of its revisions by uploading the content of that revision as a
+            new version. The revision is kept forever, and the versions after it stay in the
+            history, so a restore can itself be undone.
+
             ## Common Usage Pattern
 
             1. First, search for the file you want to read, searching by name.
@@ -894,6 +2098,8 @@ impl GoogleDriveRouter {
                 read_tool,
                 create_file_tool,
                 move_file_tool,
+                copy_file_tool,
+                delete_file_tool,
                 update_file_tool,
                 sheets_tool,
                 docs_tool,
@@ -903,12 +2109,21 @@ impl GoogleDriveRouter {
                 list_drives_tool,
                 get_permissions_tool,
                 sharing_tool,
+                create_permission_tool,
+                delete_permission_tool,
+                list_revisions_tool,
+                restore_revision_tool,
+                get_quota_tool,
+                recent_files_tool,
+                resolve_drive_tool,
             ],
             instructions,
             drive,
             sheets,
             docs,
             credentials_manager,
+            drive_names_by_id: Arc::new(RwLock::new(HashMap::new())),
+            drive_ids_fetched_at: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -916,10 +2131,23 @@ impl GoogleDriveRouter {
     async fn search(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let name = params.get("name").and_then(|q| q.as_str());
         let mime_type = params.get("mimeType").and_then(|q| q.as_str());
-        let drive_id = params.get("driveId").and_then(|q| q.as_str());
+        let drive_name = params.get("driveName").and_then(|q| q.as_str());
+        let drive_id = match drive_name {
+            Some(n) => Some(self.resolve_drive_name(n).await?.1),
+            None => params
+                .get("driveId")
+                .and_then(|q| q.as_str())
+                .map(String::from),
+        };
         let parent = params.get("parent").and_then(|q| q.as_str());
 
         // extract corpora query parameter, validate options, or default to "user"
+        // ("drive" when searching a drive given by name)
+        let default_corpus = if drive_name.is_some() {
+            "drive"
+        } else {
+            "user"
+        };
         let corpus = params
             .get("corpora")
             .and_then(|c| c.as_str())
@@ -933,7 +2161,7 @@ impl GoogleDriveRouter {
                     )))
                 }
             })
-            .unwrap_or(Ok("user"))?;
+            .unwrap_or(Ok(default_corpus))?;
 
         // extract pageSize, and convert it to an i32, default to 10
         let page_size: i32 = params
@@ -957,16 +2185,10 @@ impl GoogleDriveRouter {
 
         let mut query = Vec::new();
         if let Some(n) = name {
-            query.push(
-                format!(
-                    "name contains '{}'",
-                    n.replace('\\', "\\\\").replace('\'', "\\'")
-                )
-                .to_string(),
-            );
+            query.push(format!("name contains '{}'", escape_query_value(n)));
         }
         if let Some(m) = mime_type {
-            query.push(format!("mimeType = '{}'", m).to_string());
+            query.push(format!("mimeType = '{}'", escape_query_value(m)));
         }
         if let Some(p) = parent {
             query.push(format!("'{}' in parents", p).to_string());
@@ -992,7 +2214,7 @@ impl GoogleDriveRouter {
             .clear_scopes() // Scope::MeetReadonly is the default, remove it
             .add_scope(GOOGLE_DRIVE_SCOPES);
         // You can only use the drive_id param when the corpus is "drive".
-        if let (Some(d), "drive") = (drive_id, corpus) {
+        if let (Some(d), "drive") = (drive_id.as_deref(), corpus) {
             builder = builder.drive_id(d);
         }
         let result = builder.doit().await;
@@ -1004,24 +2226,74 @@ impl GoogleDriveRouter {
                 e
             ))),
             Ok(r) => {
-                let content =
-                    r.1.files
-                        .map(|files| {
-                            files.into_iter().map(|f| {
-                                format!(
-                                    "{} ({}) (uri: {})",
-                                    f.name.unwrap_or_default(),
-                                    f.mime_type.unwrap_or_default(),
-                                    f.id.unwrap_or_default()
-                                )
-                            })
-                        })
-                        .into_iter()
-                        .flatten()
-                        .collect::<Vec<_>>()
-                        .join("\n");
+                let content = format_file_list(r.1.files);
+                Ok(vec![Content::text(content).with_priority(0.3)])
+            }
+        }
+    }
+
+    async fn recent_files(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let mime_type = params.get("mimeType_filter").and_then(|q| q.as_str());
+
+        let limit: i32 = params
+            .get("limit")
+            .map(|s| {
+                s.as_i64()
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or_else(|| ToolError::InvalidParameters(format!("Invalid limit: {}", s)))
+                    .and_then(|n| {
+                        if (1..=100).contains(&n) {
+                            Ok(n)
+                        } else {
+                            Err(ToolError::InvalidParameters(format!(
+                                "limit must be between 1 and 100, got {}",
+                                n
+                            )))
+                        }
+                    })
+            })
+            .unwrap_or(Ok(10))?;
+
+        let sort_by = params
+            .get("sort_by")
+            .and_then(|s| s.as_str())
+            .map(|s| {
+                if ["viewedByMeTime", "modifiedTime", "createdTime"].contains(&s) {
+                    Ok(s)
+                } else {
+                    Err(ToolError::InvalidParameters(format!(
+                        "sort_by must be either 'viewedByMeTime', 'modifiedTime', or 'createdTime', got {}",
+                        s
+                    )))
+                }
+            })
+            .unwrap_or(Ok("viewedByMeTime"))?;
+
+        let order_by = format!("{} desc", sort_by);
+        let mut builder = self
+            .drive
+            .files()
+            .list()
+            .order_by(order_by.as_str())
+            .param("fields", "files(id, name, mimeType, modifiedTime, size)")
+            .page_size(limit)
+            .supports_all_drives(true)
+            .include_items_from_all_drives(true)
+            .clear_scopes() // Scope::MeetReadonly is the default, remove it
+            .add_scope(GOOGLE_DRIVE_SCOPES);
+        if let Some(m) = mime_type {
+            builder = builder.q(format!("mimeType = '{}'", escape_query_value(m)).as_str());
+        }
+        let result = builder.doit().await;
 
-                Ok(vec![Content::text(content.to_string()).with_priority(0.3)])
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to list recent google drive files, {}.",
+                e
+            ))),
+            Ok(r) => {
+                let content = format_file_list(r.1.files);
+                Ok(vec![Content::text(content).with_priority(0.3)])
             }
         }
     }
@@ -1176,12 +2448,64 @@ impl GoogleDriveRouter {
         }
     }
 
-    // handle for files we can use files.get on
-    async fn get_google_file(
-        &self,
-        uri: &str,
-        include_images: bool,
-    ) -> Result<Vec<Content>, ToolError> {
+    // Export every sheet of a spreadsheet as CSV, since files.export only exports the first one
+    async fn export_all_sheets(&self, spreadsheet_id: &str) -> Result<Vec<Content>, ToolError> {
+        let spreadsheet = self
+            .sheets
+            .spreadsheets()
+            .get(spreadsheet_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to execute Google Sheets get query, {}.",
+                    e
+                ))
+            })?
+            .1;
+        let sheet_names = spreadsheet
+            .sheets
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|sheet| sheet.properties?.title);
+
+        let mut sections = Vec::new();
+        for sheet_name in sheet_names {
+            let range = format!("'{}'!A:ZZ", sheet_name.replace('\'', "''"));
+            let values = self
+                .sheets
+                .spreadsheets()
+                .values_get(spreadsheet_id, &range)
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets values_get query for sheet {}, {}.",
+                        sheet_name, e
+                    ))
+                })?
+                .1
+                .values
+                .unwrap_or_default();
+            sections.push(format!(
+                "### Sheet: {}\n{}",
+                sheet_name,
+                values_to_csv(values)
+            ));
+        }
+        Ok(vec![Content::text(sections.join("\n")).with_priority(0.1)])
+    }
+
+    // handle for files we can use files.get on
+    async fn get_google_file(
+        &self,
+        uri: &str,
+        include_images: bool,
+    ) -> Result<Vec<Content>, ToolError> {
         let result = self
             .drive
             .files()
@@ -1290,6 +2614,10 @@ impl GoogleDriveRouter {
             .get("includeImages")
             .and_then(|i| i.as_bool())
             .unwrap_or(false);
+        let all_sheets = params
+            .get("allSheets")
+            .and_then(|i| i.as_bool())
+            .unwrap_or(false);
 
         let metadata = self.fetch_file_metadata(&drive_uri).await?;
         let mime_type = metadata.mime_type.ok_or_else(|| {
@@ -1300,7 +2628,9 @@ impl GoogleDriveRouter {
         })?;
 
         // Handle Google Docs export
-        if mime_type.starts_with("application/vnd.google-apps") {
+        if all_sheets && mime_type == "application/vnd.google-apps.spreadsheet" {
+            self.export_all_sheets(&drive_uri).await
+        } else if mime_type.starts_with("application/vnd.google-apps") {
             self.export_google_file(&drive_uri, &mime_type, include_images)
                 .await
         } else {
@@ -1428,18 +2758,7 @@ impl GoogleDriveRouter {
                         let value_range = r.1;
                         // Convert the values to a CSV string
                         let csv_content = match value_range.values {
-                            Some(values) => {
-                                let mut csv_string = String::new();
-                                for row in values {
-                                    let row_values: Vec<String> = row
-                                        .into_iter()
-                                        .map(|cell| cell.as_str().unwrap_or_default().to_string())
-                                        .collect();
-                                    csv_string.push_str(&row_values.join(","));
-                                    csv_string.push('\n');
-                                }
-                                csv_string
-                            }
+                            Some(values) => values_to_csv(values),
                             None => "No data found".to_string(),
                         };
 
@@ -1447,6 +2766,58 @@ impl GoogleDriveRouter {
                     }
                 }
             },
+            "batch_get_values" => {
+                const MAX_BATCH_RANGES: usize = 20;
+
+                let ranges: Vec<&str> = params
+                    .get("ranges")
+                    .and_then(|q| q.as_array())
+                    .map(|ranges| ranges.iter().filter_map(|r| r.as_str()).collect())
+                    .unwrap_or_default();
+                if ranges.is_empty() || ranges.len() > MAX_BATCH_RANGES {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "Between 1 and {} ranges are required for batch_get_values operation",
+                        MAX_BATCH_RANGES
+                    )));
+                }
+
+                let major_dimension = params
+                    .get("majorDimension")
+                    .and_then(|q| q.as_str())
+                    .unwrap_or("ROWS");
+                if !["ROWS", "COLUMNS"].contains(&major_dimension) {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "majorDimension must be either 'ROWS' or 'COLUMNS', got {}",
+                        major_dimension
+                    )));
+                }
+
+                let mut builder = self
+                    .sheets
+                    .spreadsheets()
+                    .values_batch_get(spreadsheet_id)
+                    .major_dimension(major_dimension)
+                    .clear_scopes()
+                    .add_scope(GOOGLE_DRIVE_SCOPES);
+                for range in ranges {
+                    builder = builder.add_ranges(range);
+                }
+                let result = builder.doit().await;
+
+                match result {
+                    Err(e) => Err(ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets values_batch_get query, {}.",
+                        e
+                    ))),
+                    Ok(r) => {
+                        let values = batch_values_json(r.1.value_ranges.unwrap_or_default());
+                        Ok(vec![Content::text(
+                            serde_json::to_string_pretty(&values).unwrap_or_default(),
+                        )
+                        .with_priority(0.1)])
+                    }
+                }
+            },
             "update_values" => {
                 let range = params
                     .get("range")
@@ -1689,138 +3060,789 @@ impl GoogleDriveRouter {
                     }
                 }
             },
+            "smart_update_values" => self.sheets_smart_update(spreadsheet_id, &params).await,
+            "delete_matching_rows" => {
+                self.sheets_delete_matching_rows(spreadsheet_id, &params).await
+            },
+            "developer_metadata_list" | "developer_metadata_get" | "developer_metadata_set" => {
+                self.sheets_developer_metadata(spreadsheet_id, operation, &params).await
+            },
+            // Named functions are only managed from the Sheets UI, the API neither reads nor
+            // writes them
+            "named_functions_list" | "named_functions_set" => Err(ToolError::ExecutionError(
+                format!(
+                    "The {} operation is not supported, as the Google Sheets API does not expose named functions. \
+                     Manage them from Data > Named functions in Google Sheets.",
+                    operation
+                ),
+            )),
+            "conditional_formatting_list"
+            | "conditional_formatting_add"
+            | "conditional_formatting_delete" => {
+                self.sheets_conditional_formatting(spreadsheet_id, operation, &params).await
+            },
             _ => Err(ToolError::InvalidParameters(format!(
-                "Invalid operation: {}. Supported operations are: list_sheets, get_columns, get_values, update_values, update_cell, add_sheet, clear_values",
+                "Invalid operation: {}. Supported operations are: list_sheets, get_columns, get_values, batch_get_values, update_values, smart_update_values, delete_matching_rows, update_cell, add_sheet, clear_values, developer_metadata_list, developer_metadata_get, developer_metadata_set, conditional_formatting_list, conditional_formatting_add, conditional_formatting_delete",
                 operation
             ))),
         }
     }
 
-    async fn read_google_resource(&self, uri: String) -> Result<String, ResourceError> {
-        self.read(json!({"uri": uri}))
-            .await
-            .map_err(|e| ResourceError::ExecutionError(e.to_string()))
-            .map(|contents| {
-                contents
-                    .into_iter()
-                    .map(|content| content.as_text().unwrap_or_default().to_string())
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            })
-    }
-
-    async fn list_google_resources(&self, params: Value) -> Vec<Resource> {
-        let next_page_token = params.get("cursor").and_then(|q| q.as_str());
-
-        let mut query = self
-            .drive
-            .files()
-            .list()
-            .order_by("viewedByMeTime desc")
-            .page_size(10)
-            .param("fields", "nextPageToken, files(id, name, mimeType)")
-            .supports_all_drives(true)
-            .include_items_from_all_drives(true)
-            .clear_scopes() // Scope::MeetReadonly is the default, remove it
-            .add_scope(GOOGLE_DRIVE_SCOPES);
+    async fn search_developer_metadata(
+        &self,
+        spreadsheet_id: &str,
+        lookup: google_sheets4::api::DeveloperMetadataLookup,
+    ) -> Result<Vec<DeveloperMetadata>, ToolError> {
+        let request = google_sheets4::api::SearchDeveloperMetadataRequest {
+            data_filters: Some(vec![google_sheets4::api::DataFilter {
+                developer_metadata_lookup: Some(lookup),
+                ..Default::default()
+            }]),
+        };
+        let result = self
+            .sheets
+            .spreadsheets()
+            .developer_metadata_search(request, spreadsheet_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await;
 
-        // add a next token if we have one
-        if let Some(token) = next_page_token {
-            query = query.page_token(token)
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to execute Google Sheets developer_metadata_search query, {}.",
+                e
+            ))),
+            Ok(r) => Ok(r
+                .1
+                .matched_developer_metadata
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|matched| matched.developer_metadata)
+                .collect()),
         }
+    }
 
-        let result = query.doit().await;
+    async fn sheets_smart_update(
+        &self,
+        spreadsheet_id: &str,
+        params: &Value,
+    ) -> Result<Vec<Content>, ToolError> {
+        let sheet_name = params.get("sheetName").and_then(|q| q.as_str()).ok_or(
+            ToolError::InvalidParameters(
+                "The sheetName is required for smart_update_values operation".to_string(),
+            ),
+        )?;
+        let values_csv =
+            params
+                .get("values")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The values parameter is required for smart_update_values operation"
+                        .to_string(),
+                ))?;
+        let key_column = params.get("key_column").and_then(|q| q.as_str()).ok_or(
+            ToolError::InvalidParameters(
+                "The key_column is required for smart_update_values operation".to_string(),
+            ),
+        )?;
+        let value_input_option = params
+            .get("valueInputOption")
+            .and_then(|q| q.as_str())
+            .unwrap_or("USER_ENTERED");
 
-        match result {
-            Err(_) => {
-                //Err(ResourceError::ExecutionError(format!(
-                //    "Failed to execute google drive list query, {}.",
-                //    e,
-                //)));
-                vec![]
-            }
-            Ok(r) => {
-                r.1.files
-                    .map(|files| {
-                        files.into_iter().map(|f| Resource {
-                            uri: f.id.unwrap_or_default(),
-                            mime_type: f.mime_type.unwrap_or_default(),
-                            name: f.name.unwrap_or_default(),
-                            description: None,
-                            annotations: None,
+        let sheet = self
+            .sheets
+            .spreadsheets()
+            .values_get(spreadsheet_id, sheet_name)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to execute Google Sheets values_get query, {}.",
+                    e
+                ))
+            })?
+            .1
+            .values
+            .unwrap_or_default();
+        let plan = plan_smart_update(&sheet, values_csv, key_column)?;
+
+        if !plan.updates.is_empty() {
+            let sheet_ref = &format!("'{}'", sheet_name.replace('\'', "''"));
+            // One range per cell, so the cells between them are left as they are
+            let data = plan
+                .updates
+                .iter()
+                .flat_map(|(row_number, cells)| {
+                    cells
+                        .iter()
+                        .map(move |(column, value)| google_sheets4::api::ValueRange {
+                            range: Some(format!(
+                                "{}!{}{}",
+                                sheet_ref,
+                                column_letters(*column),
+                                row_number
+                            )),
+                            values: Some(vec![vec![value.clone()]]),
+                            major_dimension: None,
                         })
-                    })
-                    .into_iter()
-                    .flatten()
-                    .collect::<Vec<_>>()
-            }
+                })
+                .collect();
+            let request = google_sheets4::api::BatchUpdateValuesRequest {
+                data: Some(data),
+                value_input_option: Some(value_input_option.to_string()),
+                ..Default::default()
+            };
+            self.sheets
+                .spreadsheets()
+                .values_batch_update(request, spreadsheet_id)
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets values_batch_update query, {}.",
+                        e
+                    ))
+                })?;
+        }
+
+        if !plan.appends.is_empty() {
+            let value_range = google_sheets4::api::ValueRange {
+                range: Some(sheet_name.to_string()),
+                values: Some(plan.appends.clone()),
+                major_dimension: None,
+            };
+            self.sheets
+                .spreadsheets()
+                .values_append(value_range, spreadsheet_id, sheet_name)
+                .value_input_option(value_input_option)
+                .insert_data_option("INSERT_ROWS")
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets values_append query, {}.",
+                        e
+                    ))
+                })?;
         }
+
+        let summary = json!({
+            "updated": plan.updates.len(),
+            "appended": plan.appends.len(),
+            "skipped": plan.skipped,
+        });
+        Ok(vec![Content::text(summary.to_string()).with_priority(0.1)])
     }
 
-    #[allow(clippy::too_many_arguments)]
-    async fn upload_to_drive(
+    async fn sheets_delete_matching_rows(
         &self,
-        operation: FileOperation,
-        content: Box<dyn ReadSeek>,
-        source_mime_type: &str,
-        target_mime_type: &str,
-        parent: Option<&str>,
-        support_all_drives: bool,
-        target_id: Option<&str>,
+        spreadsheet_id: &str,
+        params: &Value,
     ) -> Result<Vec<Content>, ToolError> {
-        let mut req = File {
-            mime_type: Some(target_mime_type.to_string()),
-            ..Default::default()
-        };
+        /// Share of the rows that may be deleted without allow_bulk_delete
+        const BULK_DELETE_PERCENT: usize = 90;
 
-        let builder = self.drive.files();
+        let sheet_name = params.get("sheetName").and_then(|q| q.as_str()).ok_or(
+            ToolError::InvalidParameters(
+                "The sheetName is required for delete_matching_rows operation".to_string(),
+            ),
+        )?;
+        let filter_column = params.get("filter_column").and_then(|q| q.as_str()).ok_or(
+            ToolError::InvalidParameters(
+                "The filter_column is required for delete_matching_rows operation".to_string(),
+            ),
+        )?;
+        let filter_value = params.get("filter_value").and_then(|q| q.as_str()).ok_or(
+            ToolError::InvalidParameters(
+                "The filter_value is required for delete_matching_rows operation".to_string(),
+            ),
+        )?;
+        let use_regex = params
+            .get("use_regex")
+            .and_then(|q| q.as_bool())
+            .unwrap_or(false);
+        let allow_bulk_delete = params
+            .get("allow_bulk_delete")
+            .and_then(|q| q.as_bool())
+            .unwrap_or(false);
+        let regex = use_regex
+            .then(|| Regex::new(filter_value))
+            .transpose()
+            .map_err(|e| {
+                ToolError::InvalidParameters(format!("Invalid filter_value regex, {}", e))
+            })?;
 
-        let result = match operation {
-            FileOperation::Create { ref name } => {
-                req.name = Some(name.to_string());
+        let sheet = self
+            .sheets
+            .spreadsheets()
+            .values_get(spreadsheet_id, sheet_name)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to execute Google Sheets values_get query, {}.",
+                    e
+                ))
+            })?
+            .1
+            .values
+            .unwrap_or_default();
+        let rows = matching_rows(&sheet, filter_column, |cell| match &regex {
+            Some(regex) => regex.is_match(cell),
+            None => cell == filter_value,
+        })?;
 
-                // we only accept parent_id from create tool calls
-                if let Some(p) = parent {
-                    req.parents = Some(vec![p.to_string()]);
-                }
+        let total = sheet.len().saturating_sub(1);
+        if rows.len() * 100 > total * BULK_DELETE_PERCENT && !allow_bulk_delete {
+            return Err(ToolError::InvalidParameters(format!(
+                "The filter matches {} of {} rows, set allow_bulk_delete to delete more than {}% of the rows",
+                rows.len(),
+                total,
+                BULK_DELETE_PERCENT
+            )));
+        }
 
-                if let Some(t) = target_id {
-                    req.shortcut_details = Some(FileShortcutDetails {
-                        target_id: Some(t.to_string()),
-                        ..Default::default()
-                    });
-                }
+        if !rows.is_empty() {
+            let spreadsheet = self
+                .sheets
+                .spreadsheets()
+                .get(spreadsheet_id)
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets get query, {}.",
+                        e
+                    ))
+                })?
+                .1;
+            let sheet_id = spreadsheet
+                .sheets
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|sheet| sheet.properties)
+                .find(|properties| properties.title.as_deref() == Some(sheet_name))
+                .and_then(|properties| properties.sheet_id)
+                .ok_or_else(|| {
+                    ToolError::InvalidParameters(format!("No sheet named '{}'", sheet_name))
+                })?;
+
+            let requests = contiguous_blocks(&rows)
+                .into_iter()
+                .map(|(start, end)| google_sheets4::api::Request {
+                    delete_dimension: Some(google_sheets4::api::DeleteDimensionRequest {
+                        range: Some(google_sheets4::api::DimensionRange {
+                            sheet_id: Some(sheet_id),
+                            dimension: Some("ROWS".to_string()),
+                            start_index: Some(start as i32),
+                            end_index: Some(end as i32),
+                        }),
+                    }),
+                    ..google_sheets4::api::Request::default()
+                })
+                .collect();
+            let request = google_sheets4::api::BatchUpdateSpreadsheetRequest {
+                requests: Some(requests),
+                ..Default::default()
+            };
+            self.sheets
+                .spreadsheets()
+                .batch_update(request, spreadsheet_id)
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets delete_matching_rows operation, {}.",
+                        e
+                    ))
+                })?;
+        }
 
-                builder
-                    .create(req)
-                    .use_content_as_indexable_text(true)
-                    .supports_all_drives(support_all_drives)
+        let summary = json!({
+            "deleted_count": rows.len(),
+            "remaining_row_count": total - rows.len(),
+        });
+        Ok(vec![Content::text(summary.to_string()).with_priority(0.1)])
+    }
+
+    async fn sheets_conditional_formatting(
+        &self,
+        spreadsheet_id: &str,
+        operation: &str,
+        params: &Value,
+    ) -> Result<Vec<Content>, ToolError> {
+        let sheet_id = params
+            .get("sheetId")
+            .and_then(|q| q.as_i64())
+            .and_then(|n| i32::try_from(n).ok())
+            .ok_or(ToolError::InvalidParameters(format!(
+                "The sheetId is required for {} operation",
+                operation
+            )))?;
+
+        let request = match operation {
+            "conditional_formatting_list" => {
+                let spreadsheet = self
+                    .sheets
+                    .spreadsheets()
+                    .get(spreadsheet_id)
                     .clear_scopes()
                     .add_scope(GOOGLE_DRIVE_SCOPES)
-                    .upload(content, source_mime_type.parse().unwrap())
+                    .doit()
                     .await
+                    .map_err(|e| {
+                        ToolError::ExecutionError(format!(
+                            "Failed to execute Google Sheets get query, {}.",
+                            e
+                        ))
+                    })?
+                    .1;
+                let sheet = spreadsheet
+                    .sheets
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|sheet| {
+                        sheet
+                            .properties
+                            .as_ref()
+                            .and_then(|properties| properties.sheet_id)
+                            == Some(sheet_id)
+                    })
+                    .ok_or_else(|| {
+                        ToolError::InvalidParameters(format!("No sheet with ID {}", sheet_id))
+                    })?;
+                let rules: Vec<Value> = sheet
+                    .conditional_formats
+                    .unwrap_or_default()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, rule)| conditional_format_rule_json(index, rule))
+                    .collect();
+                return Ok(vec![Content::text(Value::Array(rules).to_string())]);
             }
-            FileOperation::Update { ref file_id } => {
-                builder
-                    .update(req, file_id)
-                    .use_content_as_indexable_text(true)
-                    .clear_scopes()
-                    .add_scope(GOOGLE_DRIVE_SCOPES)
-                    .supports_all_drives(support_all_drives)
-                    .upload(content, source_mime_type.parse().unwrap())
-                    .await
+            "conditional_formatting_add" => {
+                let range = params.get("range").and_then(|q| q.as_str()).ok_or(
+                    ToolError::InvalidParameters(
+                        "The range is required for conditional_formatting_add operation"
+                            .to_string(),
+                    ),
+                )?;
+                let rule_type = params.get("rule_type").and_then(|q| q.as_str()).ok_or(
+                    ToolError::InvalidParameters(
+                        "The rule_type is required for conditional_formatting_add operation"
+                            .to_string(),
+                    ),
+                )?;
+                let format = params.get("format").ok_or(ToolError::InvalidParameters(
+                    "The format is required for conditional_formatting_add operation".to_string(),
+                ))?;
+                let condition = boolean_condition(
+                    rule_type,
+                    params.get("condition_value").and_then(|q| q.as_str()),
+                )?;
+
+                google_sheets4::api::Request {
+                    add_conditional_format_rule: Some(AddConditionalFormatRuleRequest {
+                        index: Some(0),
+                        rule: Some(ConditionalFormatRule {
+                            ranges: Some(vec![grid_range(sheet_id, range)?]),
+                            boolean_rule: Some(BooleanRule {
+                                condition: Some(condition),
+                                format: Some(conditional_cell_format(format)?),
+                            }),
+                            gradient_rule: None,
+                        }),
+                    }),
+                    ..google_sheets4::api::Request::default()
+                }
+            }
+            "conditional_formatting_delete" => {
+                let rule_index = params
+                    .get("rule_index")
+                    .and_then(|q| q.as_i64())
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or(ToolError::InvalidParameters(
+                        "The rule_index is required for conditional_formatting_delete operation"
+                            .to_string(),
+                    ))?;
+
+                google_sheets4::api::Request {
+                    delete_conditional_format_rule: Some(DeleteConditionalFormatRuleRequest {
+                        index: Some(rule_index),
+                        sheet_id: Some(sheet_id),
+                    }),
+                    ..google_sheets4::api::Request::default()
+                }
             }
+            _ => unreachable!("not a conditional formatting operation: {}", operation),
         };
 
-        match result {
-            Err(e) => Err(ToolError::ExecutionError(format!(
-                "Failed to upload google drive file {:?}, {}.",
-                operation, e
-            ))),
-            Ok(r) => Ok(vec![Content::text(format!(
-                "{} ({}) (uri: {})",
-                r.1.name.unwrap_or_default(),
-                r.1.mime_type.unwrap_or_default(),
+        let request = google_sheets4::api::BatchUpdateSpreadsheetRequest {
+            requests: Some(vec![request]),
+            ..Default::default()
+        };
+        self.sheets
+            .spreadsheets()
+            .batch_update(request, spreadsheet_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to execute Google Sheets {} operation, {}.",
+                    operation, e
+                ))
+            })?;
+
+        let message = if operation == "conditional_formatting_add" {
+            format!(
+                "Successfully added conditional formatting rule 0 to sheet {}.",
+                sheet_id
+            )
+        } else {
+            format!(
+                "Successfully deleted conditional formatting rule {} from sheet {}.",
+                params["rule_index"], sheet_id
+            )
+        };
+        Ok(vec![Content::text(message).with_priority(0.1)])
+    }
+
+    async fn sheets_developer_metadata(
+        &self,
+        spreadsheet_id: &str,
+        operation: &str,
+        params: &Value,
+    ) -> Result<Vec<Content>, ToolError> {
+        let metadata_key = params.get("metadataKey").and_then(|q| q.as_str());
+
+        match operation {
+            "developer_metadata_list" => {
+                // Without a key, match everything visible to the whole document
+                let lookup = google_sheets4::api::DeveloperMetadataLookup {
+                    metadata_key: metadata_key.map(String::from),
+                    visibility: metadata_key.is_none().then(|| "DOCUMENT".to_string()),
+                    ..Default::default()
+                };
+                let metadata = self
+                    .search_developer_metadata(spreadsheet_id, lookup)
+                    .await?
+                    .iter()
+                    .map(developer_metadata_json)
+                    .collect::<Vec<_>>();
+                Ok(vec![Content::text(
+                    serde_json::to_string_pretty(&metadata).unwrap_or_default(),
+                )
+                .with_priority(0.1)])
+            }
+            "developer_metadata_get" => {
+                let metadata_id = params
+                    .get("metadataId")
+                    .and_then(|q| q.as_i64())
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or(ToolError::InvalidParameters(
+                        "The metadataId parameter is required for developer_metadata_get operation"
+                            .to_string(),
+                    ))?;
+
+                let result = self
+                    .sheets
+                    .spreadsheets()
+                    .developer_metadata_get(spreadsheet_id, metadata_id)
+                    .clear_scopes()
+                    .add_scope(GOOGLE_DRIVE_SCOPES)
+                    .doit()
+                    .await;
+
+                match result {
+                    Err(e) => Err(ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets developer_metadata_get query, {}.",
+                        e
+                    ))),
+                    Ok(r) => Ok(vec![Content::text(
+                        serde_json::to_string_pretty(&developer_metadata_json(&r.1))
+                            .unwrap_or_default(),
+                    )
+                    .with_priority(0.1)]),
+                }
+            }
+            _ => {
+                let metadata_key = metadata_key.ok_or(ToolError::InvalidParameters(
+                    "The metadataKey parameter is required for developer_metadata_set operation"
+                        .to_string(),
+                ))?;
+                let metadata_value = params
+                    .get("metadataValue")
+                    .and_then(|q| q.as_str())
+                    .ok_or(ToolError::InvalidParameters(
+                    "The metadataValue parameter is required for developer_metadata_set operation"
+                        .to_string(),
+                ))?;
+                let location = developer_metadata_location(params)?;
+
+                // Update the value when the key is already set at this location, otherwise create it
+                let existing = self
+                    .search_developer_metadata(
+                        spreadsheet_id,
+                        google_sheets4::api::DeveloperMetadataLookup {
+                            metadata_key: Some(metadata_key.to_string()),
+                            metadata_location: Some(location.clone()),
+                            location_matching_strategy: Some("EXACT_LOCATION".to_string()),
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+
+                let request = match existing.first().and_then(|m| m.metadata_id) {
+                    Some(metadata_id) => google_sheets4::api::Request {
+                        update_developer_metadata: Some(
+                            google_sheets4::api::UpdateDeveloperMetadataRequest {
+                                data_filters: Some(vec![google_sheets4::api::DataFilter {
+                                    developer_metadata_lookup: Some(
+                                        google_sheets4::api::DeveloperMetadataLookup {
+                                            metadata_id: Some(metadata_id),
+                                            ..Default::default()
+                                        },
+                                    ),
+                                    ..Default::default()
+                                }]),
+                                developer_metadata: Some(DeveloperMetadata {
+                                    metadata_value: Some(metadata_value.to_string()),
+                                    ..Default::default()
+                                }),
+                                fields: Some(google_sheets4::FieldMask::new(&["metadataValue"])),
+                            },
+                        ),
+                        ..Default::default()
+                    },
+                    None => google_sheets4::api::Request {
+                        create_developer_metadata: Some(
+                            google_sheets4::api::CreateDeveloperMetadataRequest {
+                                developer_metadata: Some(DeveloperMetadata {
+                                    metadata_key: Some(metadata_key.to_string()),
+                                    metadata_value: Some(metadata_value.to_string()),
+                                    location: Some(location),
+                                    visibility: Some("DOCUMENT".to_string()),
+                                    ..Default::default()
+                                }),
+                            },
+                        ),
+                        ..Default::default()
+                    },
+                };
+
+                let batch_update_request = google_sheets4::api::BatchUpdateSpreadsheetRequest {
+                    requests: Some(vec![request]),
+                    ..Default::default()
+                };
+                let result = self
+                    .sheets
+                    .spreadsheets()
+                    .batch_update(batch_update_request, spreadsheet_id)
+                    .clear_scopes()
+                    .add_scope(GOOGLE_DRIVE_SCOPES)
+                    .doit()
+                    .await;
+
+                match result {
+                    Err(e) => Err(ToolError::ExecutionError(format!(
+                        "Failed to execute Google Sheets developer_metadata_set operation, {}.",
+                        e
+                    ))),
+                    Ok(r) => {
+                        let reply = r.1.replies.unwrap_or_default().into_iter().next();
+                        let metadata = reply.and_then(|reply| {
+                            reply
+                                .create_developer_metadata
+                                .and_then(|created| created.developer_metadata)
+                                .or_else(|| {
+                                    reply
+                                        .update_developer_metadata
+                                        .and_then(|updated| updated.developer_metadata)
+                                        .and_then(|updated| updated.into_iter().next())
+                                })
+                        });
+                        let content = match metadata {
+                            Some(metadata) => {
+                                serde_json::to_string_pretty(&developer_metadata_json(&metadata))
+                                    .unwrap_or_default()
+                            }
+                            None => format!("Successfully set metadata '{}'.", metadata_key),
+                        };
+                        Ok(vec![Content::text(content).with_priority(0.1)])
+                    }
+                }
+            }
+        }
+    }
+
+    async fn read_google_resource(&self, uri: String) -> Result<String, ResourceError> {
+        self.read(json!({"uri": uri}))
+            .await
+            .map_err(|e| ResourceError::ExecutionError(e.to_string()))
+            .map(|contents| {
+                contents
+                    .into_iter()
+                    .map(|content| content.as_text().unwrap_or_default().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+    }
+
+    async fn list_google_resources(&self, params: Value) -> Vec<Resource> {
+        let next_page_token = params.get("cursor").and_then(|q| q.as_str());
+
+        let mut query = self
+            .drive
+            .files()
+            .list()
+            .order_by("viewedByMeTime desc")
+            .page_size(10)
+            .param("fields", "nextPageToken, files(id, name, mimeType)")
+            .supports_all_drives(true)
+            .include_items_from_all_drives(true)
+            .clear_scopes() // Scope::MeetReadonly is the default, remove it
+            .add_scope(GOOGLE_DRIVE_SCOPES);
+
+        // add a next token if we have one
+        if let Some(token) = next_page_token {
+            query = query.page_token(token)
+        }
+
+        let result = query.doit().await;
+
+        match result {
+            Err(_) => {
+                //Err(ResourceError::ExecutionError(format!(
+                //    "Failed to execute google drive list query, {}.",
+                //    e,
+                //)));
+                vec![]
+            }
+            Ok(r) => {
+                r.1.files
+                    .map(|files| {
+                        files.into_iter().map(|f| Resource {
+                            uri: f.id.unwrap_or_default(),
+                            mime_type: f.mime_type.unwrap_or_default(),
+                            name: f.name.unwrap_or_default(),
+                            description: None,
+                            annotations: None,
+                        })
+                    })
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn upload_to_drive(
+        &self,
+        operation: FileOperation,
+        mut content: Box<dyn ReadSeek>,
+        source_mime_type: &str,
+        target_mime_type: &str,
+        parent: Option<&str>,
+        support_all_drives: bool,
+        target_id: Option<&str>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let mut req = File {
+            mime_type: Some(target_mime_type.to_string()),
+            ..Default::default()
+        };
+
+        // Large files, or content whose size cannot be determined, are uploaded in chunks
+        let content_size = content
+            .seek(SeekFrom::End(0))
+            .and_then(|size| content.seek(SeekFrom::Start(0)).map(|_| size))
+            .ok();
+        let resumable = content_size.is_none_or(|size| size > RESUMABLE_UPLOAD_THRESHOLD);
+        let mut progress = UploadProgress {
+            description: format!("{:?}", operation),
+            logged_tenths: 0,
+        };
+
+        let builder = self.drive.files();
+
+        let result = match operation {
+            FileOperation::Create { ref name } => {
+                req.name = Some(name.to_string());
+
+                // we only accept parent_id from create tool calls
+                if let Some(p) = parent {
+                    req.parents = Some(vec![p.to_string()]);
+                }
+
+                if let Some(t) = target_id {
+                    req.shortcut_details = Some(FileShortcutDetails {
+                        target_id: Some(t.to_string()),
+                        ..Default::default()
+                    });
+                }
+
+                let call = builder
+                    .create(req)
+                    .use_content_as_indexable_text(true)
+                    .supports_all_drives(support_all_drives)
+                    .clear_scopes()
+                    .add_scope(GOOGLE_DRIVE_SCOPES);
+                if resumable {
+                    call.delegate(&mut progress)
+                        .upload_resumable(content, source_mime_type.parse().unwrap())
+                        .await
+                } else {
+                    call.upload(content, source_mime_type.parse().unwrap())
+                        .await
+                }
+            }
+            FileOperation::Update { ref file_id } => {
+                let call = builder
+                    .update(req, file_id)
+                    .use_content_as_indexable_text(true)
+                    .clear_scopes()
+                    .add_scope(GOOGLE_DRIVE_SCOPES)
+                    .supports_all_drives(support_all_drives);
+                if resumable {
+                    call.delegate(&mut progress)
+                        .upload_resumable(content, source_mime_type.parse().unwrap())
+                        .await
+                } else {
+                    call.upload(content, source_mime_type.parse().unwrap())
+                        .await
+                }
+            }
+        };
+
+        if resumable && result.is_ok() {
+            tracing::info!("Uploading {}: 100% complete", progress.description);
+        }
+
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to upload google drive file {:?}, {}.",
+                operation, e
+            ))),
+            Ok(r) => Ok(vec![Content::text(format!(
+                "{} ({}) (uri: {})",
+                r.1.name.unwrap_or_default(),
+                r.1.mime_type.unwrap_or_default(),
                 r.1.id.unwrap_or_default()
             ))]),
         }
@@ -1844,16 +3866,28 @@ impl GoogleDriveRouter {
                     "The mimeType param is required".to_string(),
                 ))?;
 
-        let parent_id = params.get("parentId").and_then(|q| q.as_str());
         let target_id = params.get("targetId").and_then(|q| q.as_str());
         let body = params.get("body").and_then(|q| q.as_str());
         let path = params.get("path").and_then(|q| q.as_str());
 
-        let allow_shared_drives = params
+        let mut allow_shared_drives = params
             .get("allowSharedDrives")
             .and_then(|q| q.as_bool())
             .unwrap_or_default();
 
+        // The root folder of a shared drive has the id of the drive
+        let drive_id = match params.get("driveName").and_then(|q| q.as_str()) {
+            Some(n) => {
+                allow_shared_drives = true;
+                Some(self.resolve_drive_name(n).await?.1)
+            }
+            None => None,
+        };
+        let parent_id = params
+            .get("parentId")
+            .and_then(|q| q.as_str())
+            .or(drive_id.as_deref());
+
         // Determine source and target MIME types based on file_type
         let (source_mime_type, target_mime_type, reader): (String, String, Box<dyn ReadSeek>) =
             match mime_type {
@@ -2005,7 +4039,7 @@ impl GoogleDriveRouter {
         }
     }
 
-    async fn update_file(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+    async fn copy_file(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let file_id =
             params
                 .get("fileId")
@@ -2013,17 +4047,126 @@ impl GoogleDriveRouter {
                 .ok_or(ToolError::InvalidParameters(
                     "The fileId param is required".to_string(),
                 ))?;
+        let allow_shared_drives = params
+            .get("allowSharedDrives")
+            .and_then(|q| q.as_bool())
+            .unwrap_or_default();
 
-        let mime_type =
+        let req = File {
+            name: params
+                .get("name")
+                .and_then(|q| q.as_str())
+                .map(str::to_string),
+            parents: params
+                .get("parentId")
+                .and_then(|q| q.as_str())
+                .map(|p| vec![p.to_string()]),
+            ..Default::default()
+        };
+        let result = self
+            .drive
+            .files()
+            .copy(req, file_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .supports_all_drives(allow_shared_drives)
+            .doit()
+            .await;
+
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to copy google drive file {}, {}.",
+                file_id, e
+            ))),
+            Ok(r) => Ok(vec![Content::text(format!(
+                "{} ({}) (uri: {})",
+                r.1.name.unwrap_or_default(),
+                r.1.mime_type.unwrap_or_default(),
+                r.1.id.unwrap_or_default()
+            ))]),
+        }
+    }
+
+    async fn delete_file(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let file_id =
             params
-                .get("mimeType")
+                .get("fileId")
                 .and_then(|q| q.as_str())
                 .ok_or(ToolError::InvalidParameters(
-                    "The mimeType param is required".to_string(),
+                    "The fileId param is required".to_string(),
                 ))?;
+        let permanent = params
+            .get("permanent")
+            .and_then(|q| q.as_bool())
+            .unwrap_or(false);
 
-        let body = params.get("body").and_then(|q| q.as_str());
-        let path = params.get("path").and_then(|q| q.as_str());
+        if permanent {
+            self.drive
+                .files()
+                .delete(file_id)
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .supports_all_drives(true)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to delete google drive file {}, {}.",
+                        file_id, e
+                    ))
+                })?;
+            return Ok(vec![Content::text(format!(
+                "Permanently deleted file {}",
+                file_id
+            ))]);
+        }
+
+        let req = File {
+            trashed: Some(true),
+            ..Default::default()
+        };
+        let result = self
+            .drive
+            .files()
+            .update(req, file_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .supports_all_drives(true)
+            .doit_without_upload()
+            .await;
+
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to trash google drive file {}, {}.",
+                file_id, e
+            ))),
+            Ok(r) => Ok(vec![Content::text(format!(
+                "Moved {} ({}) to the trash",
+                r.1.name.unwrap_or_default(),
+                r.1.id.unwrap_or_default()
+            ))]),
+        }
+    }
+
+    async fn update_file(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let file_id =
+            params
+                .get("fileId")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The fileId param is required".to_string(),
+                ))?;
+
+        let mime_type =
+            params
+                .get("mimeType")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The mimeType param is required".to_string(),
+                ))?;
+
+        let body = params.get("body").and_then(|q| q.as_str());
+        let path = params.get("path").and_then(|q| q.as_str());
 
         // Determine source and target MIME types based on file_type
         let (source_mime_type, target_mime_type, reader): (String, String, Box<dyn ReadSeek>) =
@@ -2193,10 +4336,18 @@ impl GoogleDriveRouter {
                     "The comment param is required".to_string(),
                 ))?;
 
-        let req = Comment {
+        let mut req = Comment {
             content: Some(comment.to_string()),
             ..Default::default()
         };
+        if let Some(anchor_text) = params.get("anchor_text").and_then(|q| q.as_str()) {
+            req.anchor = Some(self.anchor_for_text(file_id, anchor_text).await?);
+            req.quoted_file_content = Some(CommentQuotedFileContent {
+                mime_type: Some("text/plain".to_string()),
+                value: Some(anchor_text.to_string()),
+            });
+        }
+
         let result = self
             .drive
             .comments()
@@ -2212,17 +4363,68 @@ impl GoogleDriveRouter {
                 "Failed to add comment for google drive file {}, {}.",
                 file_id, e
             ))),
-            Ok(r) => Ok(vec![Content::text(format!(
-                "Author: {:?} Content: {} Created: {} uri: {} quoted_content: {:?}",
-                r.1.author.unwrap_or_default(),
-                r.1.content.unwrap_or_default(),
-                r.1.created_time.unwrap_or_default(),
-                r.1.id.unwrap_or_default(),
-                r.1.quoted_file_content.unwrap_or_default()
-            ))]),
+            Ok(r) => {
+                let mut text = format!(
+                    "Author: {:?} Content: {} Created: {} uri: {} quoted_content: {:?}",
+                    r.1.author.unwrap_or_default(),
+                    r.1.content.unwrap_or_default(),
+                    r.1.created_time.unwrap_or_default(),
+                    r.1.id.unwrap_or_default(),
+                    r.1.quoted_file_content.unwrap_or_default()
+                );
+                if let Some(anchor_text) = params.get("anchor_text").and_then(|q| q.as_str()) {
+                    text.push_str(&format!(" anchored_to: {:?}", anchor_text));
+                }
+                Ok(vec![Content::text(text)])
+            }
         }
     }
 
+    /// Anchor for the first occurrence of `anchor_text` in the head revision of a Google Doc
+    async fn anchor_for_text(&self, file_id: &str, anchor_text: &str) -> Result<String, ToolError> {
+        let document = self
+            .docs
+            .documents()
+            .get(file_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to read google doc {} to anchor comment, {}.",
+                    file_id, e
+                ))
+            })?
+            .1;
+        let revision_id = self
+            .drive
+            .files()
+            .get(file_id)
+            .param("fields", "headRevisionId")
+            .supports_all_drives(true)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to get head revision of google drive file {}, {}.",
+                    file_id, e
+                ))
+            })?
+            .1
+            .head_revision_id
+            .unwrap_or_default();
+
+        comment_anchor(&revision_id, &document_body_text(&document), anchor_text).ok_or_else(|| {
+            ToolError::InvalidParameters(format!(
+                "anchor_text {:?} was not found in document {}",
+                anchor_text, file_id
+            ))
+        })
+    }
+
     async fn reply(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let file_id =
             params
@@ -2307,29 +4509,13 @@ impl GoogleDriveRouter {
                     ))),
                     Ok(r) => {
                         let document = r.1;
-                        let title = document.title.unwrap_or_default();
+                        let title = document.title.as_deref().unwrap_or_default();
 
                         // Extract the document content as text
                         let mut content = String::new();
                         content.push_str(&format!("# {}\n\n", title));
 
-                        if let Some(body) = document.body {
-                            if let Some(content_items) = body.content {
-                                for item in content_items {
-                                    if let Some(paragraph) = item.paragraph {
-                                        if let Some(elements) = paragraph.elements {
-                                            for element in elements {
-                                                if let Some(text_run) = element.text_run {
-                                                    if let Some(text) = text_run.content {
-                                                        content.push_str(&text);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                        content.push_str(&document_body_text(&document));
 
                         Ok(vec![Content::text(content).with_priority(0.1)])
                     }
@@ -2655,10 +4841,8 @@ impl GoogleDriveRouter {
         }
     }
 
-    async fn list_drives(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let query = params.get("name_contains").and_then(|q| q.as_str());
-
-        let mut results: Vec<String> = Vec::new();
+    async fn fetch_drives(&self, query: Option<&str>) -> Result<Vec<Drive>, ToolError> {
+        let mut results: Vec<Drive> = Vec::new();
         let mut state = PaginationState::Start;
         while state != PaginationState::End {
             let mut builder = self
@@ -2684,22 +4868,7 @@ impl GoogleDriveRouter {
                     )))
                 }
                 Ok(r) => {
-                    let mut content =
-                        r.1.drives
-                            .map(|drives| {
-                                drives.into_iter().map(|f| {
-                                    format!(
-                                        "{} (capabilities: {:?}) (uri: {})",
-                                        f.name.unwrap_or_default(),
-                                        f.capabilities.unwrap_or_default(),
-                                        f.id.unwrap_or_default()
-                                    )
-                                })
-                            })
-                            .into_iter()
-                            .flatten()
-                            .collect::<Vec<_>>();
-                    results.append(&mut content);
+                    results.extend(r.1.drives.unwrap_or_default());
                     state = match r.1.next_page_token {
                         Some(npt) => PaginationState::Next(npt),
                         None => PaginationState::End,
@@ -2707,9 +4876,125 @@ impl GoogleDriveRouter {
                 }
             }
         }
+        Ok(results)
+    }
+
+    async fn list_drives(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let query = params.get("name_contains").and_then(|q| q.as_str());
+
+        let results: Vec<String> = self
+            .fetch_drives(query)
+            .await?
+            .into_iter()
+            .map(|f| {
+                format!(
+                    "{} (capabilities: {:?}) (uri: {})",
+                    f.name.unwrap_or_default(),
+                    f.capabilities.unwrap_or_default(),
+                    f.id.unwrap_or_default()
+                )
+            })
+            .collect();
         Ok(vec![Content::text(results.join("\n"))])
     }
 
+    /// Forget the cached shared drive names, so the next lookup lists the drives again
+    pub fn cache_invalidate_drives(&self) {
+        self.drive_names_by_id.write().unwrap().clear();
+        *self.drive_ids_fetched_at.write().unwrap() = None;
+    }
+
+    // Resolve a shared drive name to its (name, id), listing drives when the cache is stale
+    async fn resolve_drive_name(&self, name: &str) -> Result<(String, String), ToolError> {
+        let fresh = self
+            .drive_ids_fetched_at
+            .read()
+            .unwrap()
+            .is_some_and(|fetched_at| fetched_at.elapsed() < DRIVE_CACHE_TTL);
+
+        if !fresh {
+            let drives: HashMap<String, String> = self
+                .fetch_drives(None)
+                .await?
+                .into_iter()
+                .filter_map(|d| Some((d.id?, d.name?)))
+                .collect();
+            *self.drive_names_by_id.write().unwrap() = drives;
+            *self.drive_ids_fetched_at.write().unwrap() = Some(Instant::now());
+        }
+
+        find_drive_id(&self.drive_names_by_id.read().unwrap(), name)
+    }
+
+    async fn resolve_drive(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let name =
+            params
+                .get("name")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The name param is required".to_string(),
+                ))?;
+
+        let (drive_name, id) = self.resolve_drive_name(name).await?;
+        Ok(vec![Content::text(format!("{} (uri: {})", drive_name, id))])
+    }
+
+    async fn get_quota(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let drive_id = params.get("driveId").and_then(|q| q.as_str());
+
+        // Shared drives have no quota of their own, they use the organization's storage pool
+        let drive_name = match drive_id {
+            Some(id) => {
+                let result = self
+                    .drive
+                    .drives()
+                    .get(id)
+                    .clear_scopes() // Scope::MeetReadonly is the default, remove it
+                    .add_scope(GOOGLE_DRIVE_SCOPES)
+                    .doit()
+                    .await;
+                match result {
+                    Err(e) => {
+                        return Err(ToolError::ExecutionError(format!(
+                            "Failed to get shared drive {}, {}.",
+                            id, e
+                        )))
+                    }
+                    Ok(r) => Some(r.1.name.unwrap_or_else(|| id.to_string())),
+                }
+            }
+            None => None,
+        };
+
+        let result = self
+            .drive
+            .about()
+            .get()
+            .param("fields", "storageQuota")
+            .clear_scopes() // Scope::MeetReadonly is the default, remove it
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await;
+
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to get google drive storage quota, {}.",
+                e
+            ))),
+            Ok(r) => {
+                let quota = r.1.storage_quota.unwrap_or_default();
+                let mut content = format_storage_quota(&quota);
+                if let Some(name) = drive_name {
+                    content = format!(
+                        "Shared drive '{}' uses the organization's storage pool.\n{}",
+                        name, content
+                    );
+                }
+                Ok(vec![Content::text(content)])
+            }
+        }
+    }
+
     fn output_permission(&self, p: Permission) -> String {
         format!(
             "(display_name: {}) (domain: {}) (email_address: {}) (expiration_time: {}) (permission_details: {:?}) (role: {}) (type: {}) (uri: {})",
@@ -2915,7 +5200,7 @@ impl GoogleDriveRouter {
                     ))),
                     Ok(_) => Ok(vec![Content::text(format!(
                         "Deleted permission: {} from file: {}",
-                        file_id, permission_id
+                        permission_id, file_id
                     ))]),
                 }
             }
@@ -2928,6 +5213,261 @@ impl GoogleDriveRouter {
             )),
         }
     }
+
+    async fn create_permission(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let target = params.get("emailAddress").or_else(|| params.get("domain"));
+        self.sharing(json!({
+            "fileId": params.get("fileId"),
+            "operation": "create",
+            "role": params.get("role"),
+            "type": params.get("type"),
+            "target": target,
+        }))
+        .await
+    }
+
+    async fn delete_permission(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        self.sharing(json!({
+            "fileId": params.get("fileId"),
+            "operation": "delete",
+            "permissionId": params.get("permissionId"),
+        }))
+        .await
+    }
+
+    fn output_revision(&self, r: Revision) -> String {
+        let user = r.last_modifying_user.unwrap_or_default();
+        format!(
+            "(id: {}) (modified_time: {}) (last_modifying_user: {} <{}>) (size: {})",
+            r.id.unwrap_or_default(),
+            r.modified_time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            user.display_name.unwrap_or_default(),
+            user.email_address.unwrap_or_default(),
+            r.size.map(|s| s.to_string()).unwrap_or_default()
+        )
+    }
+
+    async fn list_revisions(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let file_id =
+            params
+                .get("fileId")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The fileId param is required".to_string(),
+                ))?;
+        let limit = params
+            .get("limit")
+            .and_then(|q| q.as_u64())
+            .map(|l| l as usize);
+
+        let mut revisions: Vec<Revision> = Vec::new();
+        let mut state = PaginationState::Start;
+        while state != PaginationState::End {
+            let mut builder = self
+                .drive
+                .revisions()
+                .list(file_id)
+                .param(
+                    "fields",
+                    "nextPageToken, revisions(id, modifiedTime, lastModifyingUser, size)",
+                )
+                .page_size(1000)
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES);
+            if let PaginationState::Next(pt) = state {
+                builder = builder.page_token(&pt);
+            }
+            let r = builder.doit().await.map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to list revisions of google drive file {}, {}.",
+                    file_id, e
+                ))
+            })?;
+            revisions.extend(r.1.revisions.unwrap_or_default());
+            state = match r.1.next_page_token {
+                Some(npt) => PaginationState::Next(npt),
+                None => PaginationState::End,
+            };
+        }
+
+        let skip = limit.map_or(0, |l| revisions.len().saturating_sub(l));
+        let results: Vec<String> = revisions
+            .into_iter()
+            .skip(skip)
+            .map(|r| self.output_revision(r))
+            .collect();
+        Ok(vec![Content::text(results.join("\n"))])
+    }
+
+    /// Download a revision export link, which the API has no method for
+    async fn download_export_link(&self, url: &str) -> Result<Vec<u8>, ToolError> {
+        let token = self
+            .drive
+            .auth
+            .get_token(&[GOOGLE_DRIVE_SCOPES.as_ref()])
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get a token, {}.", e)))?;
+        let mut request = hyper::Request::get(url);
+        if let Some(token) = token {
+            request = request.header(hyper::header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request
+            .body(google_drive3::common::to_body::<String>(None))
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Invalid export link {}, {}.", url, e))
+            })?;
+
+        let response = self.drive.client.request(request).await.map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to download {}, {}.", url, e))
+        })?;
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to download {}, status {}.",
+                url,
+                response.status()
+            )));
+        }
+        response
+            .into_body()
+            .collect()
+            .await
+            .map(|body| body.to_bytes().to_vec())
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to download {}, {}.", url, e)))
+    }
+
+    async fn restore_revision(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let file_id =
+            params
+                .get("fileId")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The fileId param is required".to_string(),
+                ))?;
+        let revision_id = params.get("revisionId").and_then(|q| q.as_str()).ok_or(
+            ToolError::InvalidParameters("The revisionId param is required".to_string()),
+        )?;
+
+        // Keep the revision, so it isn't purged after the restore adds a newer one
+        let req = Revision {
+            keep_forever: Some(true),
+            ..Default::default()
+        };
+        self.drive
+            .revisions()
+            .update(req, file_id, revision_id)
+            .clear_scopes()
+            .add_scope(GOOGLE_DRIVE_SCOPES)
+            .doit()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to keep revision {} of google drive file {}, {}.",
+                    revision_id, file_id, e
+                ))
+            })?;
+
+        let mime_type = self
+            .fetch_file_metadata(file_id)
+            .await?
+            .mime_type
+            .unwrap_or_default();
+        let (content, source_mime_type) = if mime_type.starts_with("application/vnd.google-apps") {
+            // Revisions of Google Workspace files can only be exported, so the Office format
+            // is used as it keeps the formatting when converted back
+            let export_mime_type = match mime_type.as_str() {
+                "application/vnd.google-apps.document" => {
+                    "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+                }
+                "application/vnd.google-apps.spreadsheet" => {
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+                }
+                "application/vnd.google-apps.presentation" => {
+                    "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+                }
+                _ => {
+                    return Err(ToolError::ExecutionError(format!(
+                        "Restoring revisions of {} files is not supported.",
+                        mime_type
+                    )))
+                }
+            };
+            let revision = self
+                .drive
+                .revisions()
+                .get(file_id, revision_id)
+                .param("fields", "exportLinks")
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to get revision {} of google drive file {}, {}.",
+                        revision_id, file_id, e
+                    ))
+                })?
+                .1;
+            let export_link = revision
+                .export_links
+                .and_then(|links| links.get(export_mime_type).cloned())
+                .ok_or_else(|| {
+                    ToolError::ExecutionError(format!(
+                        "Revision {} of {} cannot be exported.",
+                        revision_id, file_id
+                    ))
+                })?;
+            (
+                self.download_export_link(&export_link).await?,
+                export_mime_type.to_string(),
+            )
+        } else {
+            let response = self
+                .drive
+                .revisions()
+                .get(file_id, revision_id)
+                .param("alt", "media")
+                .clear_scopes()
+                .add_scope(GOOGLE_DRIVE_SCOPES)
+                .doit()
+                .await
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to download revision {} of google drive file {}, {}.",
+                        revision_id, file_id, e
+                    ))
+                })?
+                .0;
+            let content = response.into_body().collect().await.map_err(|e| {
+                ToolError::ExecutionError(format!(
+                    "Failed to download revision {} of google drive file {}, {}.",
+                    revision_id, file_id, e
+                ))
+            })?;
+            (content.to_bytes().to_vec(), mime_type.clone())
+        };
+
+        let uploaded = self
+            .upload_to_drive(
+                FileOperation::Update {
+                    file_id: file_id.to_string(),
+                },
+                Box::new(Cursor::new(content)),
+                &source_mime_type,
+                &mime_type,
+                None,
+                true,
+                None,
+            )
+            .await?;
+        Ok(vec![Content::text(format!(
+            "Restored revision {} as the newest version of {}",
+            revision_id,
+            uploaded
+                .first()
+                .and_then(|c| c.as_text())
+                .unwrap_or(file_id)
+        ))])
+    }
 }
 
 impl Router for GoogleDriveRouter {
@@ -2963,6 +5503,8 @@ impl Router for GoogleDriveRouter {
                 "read" => this.read(arguments).await,
                 "create_file" => this.create_file(arguments).await,
                 "move_file" => this.move_file(arguments).await,
+                "copy_file" => this.copy_file(arguments).await,
+                "delete_file" => this.delete_file(arguments).await,
                 "update_file" => this.update_file(arguments).await,
                 "sheets_tool" => this.sheets_tool(arguments).await,
                 "docs_tool" => this.docs_tool(arguments).await,
@@ -2972,6 +5514,13 @@ impl Router for GoogleDriveRouter {
                 "list_drives" => this.list_drives(arguments).await,
                 "get_permissions" => this.get_permissions(arguments).await,
                 "sharing" => this.sharing(arguments).await,
+                "create_permission" => this.create_permission(arguments).await,
+                "delete_permission" => this.delete_permission(arguments).await,
+                "list_revisions" => this.list_revisions(arguments).await,
+                "restore_revision" => this.restore_revision(arguments).await,
+                "get_quota" => this.get_quota(arguments).await,
+                "recent_files" => this.recent_files(arguments).await,
+                "resolve_drive" => this.resolve_drive(arguments).await,
                 _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
             }
         })
@@ -3020,6 +5569,8 @@ impl Clone for GoogleDriveRouter {
             sheets: self.sheets.clone(),
             docs: self.docs.clone(),
             credentials_manager: self.credentials_manager.clone(),
+            drive_names_by_id: Arc::clone(&self.drive_names_by_id),
+            drive_ids_fetched_at: Arc::clone(&self.drive_ids_fetched_at),
         }
     }
 }
@@ -3027,19 +5578,877 @@ impl Clone for GoogleDriveRouter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
 
-    #[test]
-    fn test_document_url() {
-        let url = "https://docs.google.com/document/d/1QG8d8wtWe7ZfmG93sW-1h2WXDJDUkOi-9hDnvJLmWrc/edit?tab=t.0#heading=h.5v419d3h97tr";
-        assert_eq!(
-            extract_google_drive_id(url),
-            Some("1QG8d8wtWe7ZfmG93sW-1h2WXDJDUkOi-9hDnvJLmWrc")
-        );
-    }
-
-    #[test]
-    fn test_spreadsheets_url() {
-        let url = "https://docs.google.com/spreadsheets/d/1J5KHqWsGFzweuiQboX7dlm8Ejv90Po16ocEBahzCt4W/edit?gid=1249300797#gid=1249300797";
+    /// Build a router whose Google API hubs talk to the given mock server
+    fn mock_router(server: &MockServer) -> GoogleDriveRouter {
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build(
+                    hyper_rustls::HttpsConnectorBuilder::new()
+                        .with_native_roots()
+                        .unwrap()
+                        .https_or_http()
+                        .enable_http1()
+                        .build(),
+                );
+        let url = format!("{}/", server.uri());
+
+        let mut drive = DriveHub::new(client.clone(), "token".to_string());
+        drive.root_url(url.clone());
+        drive.base_url(format!("{}drive/v3/", url));
+        let mut sheets = Sheets::new(client.clone(), "token".to_string());
+        sheets.root_url(url.clone());
+        sheets.base_url(url.clone());
+        let mut docs = Docs::new(client, "token".to_string());
+        docs.root_url(url.clone());
+        docs.base_url(url);
+
+        GoogleDriveRouter {
+            tools: Vec::new(),
+            instructions: String::new(),
+            drive,
+            sheets,
+            docs,
+            credentials_manager: Arc::new(CredentialsManager::new(
+                String::new(),
+                false,
+                "test-service".to_string(),
+                "test-user".to_string(),
+            )),
+            drive_names_by_id: Arc::new(RwLock::new(HashMap::new())),
+            drive_ids_fetched_at: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_anchored_comment() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/documents/doc-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "documentId": "doc-id",
+                "body": {
+                    "content": [
+                        {"paragraph": {"elements": [{"textRun": {"content": "Quarterly report\n"}}]}},
+                        {"paragraph": {"elements": [
+                            {"textRun": {"content": "Revenue grew "}},
+                            {"textRun": {"content": "twelve percent.\n"}}
+                        ]}}
+                    ]
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files/doc-id"))
+            .and(query_param("fields", "headRevisionId"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"headRevisionId": "rev-42"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/drive/v3/files/doc-id/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "comment-id",
+                "content": "Source?",
+                "quotedFileContent": {"mimeType": "text/plain", "value": "grew twelve"}
+            })))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .create_comment(json!({
+                "fileId": "doc-id",
+                "comment": "Source?",
+                "anchor_text": "grew twelve"
+            }))
+            .await
+            .unwrap();
+        assert!(result[0]
+            .as_text()
+            .unwrap()
+            .ends_with("anchored_to: \"grew twelve\""));
+
+        let requests = server.received_requests().await.unwrap();
+        let comment_request = requests
+            .iter()
+            .find(|request| request.url.path() == "/drive/v3/files/doc-id/comments")
+            .unwrap();
+        let body: Value = serde_json::from_slice(&comment_request.body).unwrap();
+        let anchor: Value = serde_json::from_str(body["anchor"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            anchor,
+            json!({"r": "rev-42", "a": [{"txt": {"o": 25, "l": 11, "ml": 46}}]})
+        );
+        assert_eq!(body["quotedFileContent"]["value"], "grew twelve");
+
+        let missing = router
+            .create_comment(json!({
+                "fileId": "doc-id",
+                "comment": "Source?",
+                "anchor_text": "not in the document"
+            }))
+            .await;
+        assert!(matches!(missing, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/drive/v3/files/file-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "file-id",
+                "name": "Old notes",
+                "trashed": true
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/drive/v3/files/file-id"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .delete_file(json!({"fileId": "file-id"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Moved Old notes (file-id) to the trash"
+        );
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body, json!({"trashed": true}));
+
+        let result = router
+            .delete_file(json!({"fileId": "file-id", "permanent": true}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Permanently deleted file file-id"
+        );
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests[1].method.as_str(), "DELETE");
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_permission() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/drive/v3/files/file-id/permissions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "perm-id",
+                "emailAddress": "jane@example.com",
+                "role": "reader",
+                "type": "user"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/drive/v3/files/file-id/permissions/perm-id"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .create_permission(json!({
+                "fileId": "file-id",
+                "role": "reader",
+                "type": "user",
+                "emailAddress": "jane@example.com"
+            }))
+            .await
+            .unwrap();
+        assert!(result[0]
+            .as_text()
+            .unwrap()
+            .contains("(email_address: jane@example.com)"));
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(
+            body,
+            json!({"emailAddress": "jane@example.com", "role": "reader", "type": "user"})
+        );
+
+        // User permissions need an email address
+        assert!(router
+            .create_permission(json!({"fileId": "file-id", "role": "reader", "type": "user"}))
+            .await
+            .is_err());
+
+        let result = router
+            .delete_permission(json!({"fileId": "file-id", "permissionId": "perm-id"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Deleted permission: perm-id from file: file-id"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_all_sheets() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files/sheet-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "mimeType": "application/vnd.google-apps.spreadsheet"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "sheets": [
+                    {"properties": {"title": "People"}},
+                    {"properties": {"title": "Q1 Sales"}}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id/values/'People'!A:ZZ"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [["name", "age"], ["Ada", "36"]]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id/values/'Q1%20Sales'!A:ZZ"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [["region", "total"], ["EMEA", "120"]]
+            })))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .read(json!({"uri": "gdrive:///sheet-id", "allSheets": true}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "### Sheet: People\nname,age\nAda,36\n\n### Sheet: Q1 Sales\nregion,total\nEMEA,120\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_and_restore_revisions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files/doc-id/revisions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "revisions": [
+                    {"id": "1", "modifiedTime": "2025-01-01T10:00:00Z", "size": "100",
+                     "lastModifyingUser": {"displayName": "Ada", "emailAddress": "ada@example.com"}},
+                    {"id": "2", "modifiedTime": "2025-01-02T10:00:00Z", "size": "120",
+                     "lastModifyingUser": {"displayName": "Ada", "emailAddress": "ada@example.com"}},
+                    {"id": "3", "modifiedTime": "2025-01-03T10:00:00Z", "size": "90",
+                     "lastModifyingUser": {"displayName": "Joe", "emailAddress": "joe@example.com"}}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/drive/v3/files/doc-id/revisions/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "2"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files/doc-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "mimeType": "application/vnd.google-apps.document"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files/doc-id/revisions/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "exportLinks": {
+                    "application/vnd.openxmlformats-officedocument.wordprocessingml.document":
+                        format!("{}/export/doc-id/2.docx", server.uri())
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/export/doc-id/2.docx"))
+            .and(wiremock::matchers::header("authorization", "Bearer token"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"docx content".to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/upload/drive/v3/files/doc-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "doc-id",
+                "name": "Plan",
+                "mimeType": "application/vnd.google-apps.document"
+            })))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .list_revisions(json!({"fileId": "doc-id", "limit": 2}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "(id: 2) (modified_time: 2025-01-02T10:00:00+00:00) (last_modifying_user: Ada <ada@example.com>) (size: 120)\n\
+             (id: 3) (modified_time: 2025-01-03T10:00:00+00:00) (last_modifying_user: Joe <joe@example.com>) (size: 90)"
+        );
+
+        let result = router
+            .restore_revision(json!({"fileId": "doc-id", "revisionId": "2"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Restored revision 2 as the newest version of Plan (application/vnd.google-apps.document) (uri: doc-id)"
+        );
+
+        let requests = server.received_requests().await.unwrap();
+        let keep: Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(keep, json!({"keepForever": true}));
+        let upload = requests.last().unwrap();
+        assert!(String::from_utf8_lossy(&upload.body).contains("docx content"));
+    }
+
+    #[tokio::test]
+    async fn test_copy_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/drive/v3/files/doc-id/copy"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "copy-id",
+                "name": "Report 2025",
+                "mimeType": "application/vnd.google-apps.document"
+            })))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .copy_file(json!({
+                "fileId": "doc-id",
+                "name": "Report 2025",
+                "parentId": "folder-id"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Report 2025 (application/vnd.google-apps.document) (uri: copy-id)"
+        );
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(
+            body,
+            json!({"name": "Report 2025", "parents": ["folder-id"]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_large_upload_is_resumable() {
+        let server = MockServer::start().await;
+        let session_url = format!("{}/upload-session", server.uri());
+
+        Mock::given(method("POST"))
+            .and(path("/resumable/upload/drive/v3/files"))
+            .and(query_param("uploadType", "resumable"))
+            .respond_with(ResponseTemplate::new(200).insert_header("Location", session_url))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/upload-session"))
+            .respond_with(|request: &Request| {
+                let range = request.headers["Content-Range"].to_str().unwrap();
+                if range.ends_with("-10485759/10485760") {
+                    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                        "id": "file-id",
+                        "name": "large.bin",
+                        "mimeType": "application/octet-stream"
+                    }))
+                } else {
+                    ResponseTemplate::new(308)
+                }
+            })
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let content = Cursor::new(vec![0u8; 10 * 1024 * 1024]);
+        let result = router
+            .upload_to_drive(
+                FileOperation::Create {
+                    name: "large.bin".to_string(),
+                },
+                Box::new(content),
+                "application/octet-stream",
+                "application/octet-stream",
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result[0].as_text(),
+            Some("large.bin (application/octet-stream) (uri: file-id)")
+        );
+
+        let ranges: Vec<String> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path() == "/upload-session")
+            .map(|request| {
+                request.headers["Content-Range"]
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                "bytes 0-4194303/10485760",
+                "bytes 4194304-8388607/10485760",
+                "bytes 8388608-10485759/10485760",
+            ]
+        );
+    }
+
+    fn people_sheet() -> Value {
+        json!({
+            "range": "People!A1:C4",
+            "majorDimension": "ROWS",
+            "values": [
+                ["Email", "Name", "Status"],
+                ["ann@example.com", "Ann", "Active"],
+                ["bob@example.com", "Bob", "Active"],
+                ["cat@example.com", "Cat", "Active"]
+            ]
+        })
+    }
+
+    #[test]
+    fn test_plan_smart_update() {
+        let sheet: Vec<Vec<Value>> =
+            serde_json::from_value(people_sheet()["values"].clone()).unwrap();
+        let plan = plan_smart_update(
+            &sheet,
+            "Status,Email\nInactive,bob@example.com\nActive,ann@example.com\nNew,dan@example.com\nNew,",
+            "Email",
+        )
+        .unwrap();
+        assert_eq!(
+            plan,
+            SmartUpdatePlan {
+                updates: vec![(3, vec![(2, json!("Inactive"))])],
+                appends: vec![vec![json!("dan@example.com"), json!(""), json!("New")]],
+                skipped: 2,
+            }
+        );
+
+        assert!(matches!(
+            plan_smart_update(&sheet, "Email,Phone\nann@example.com,555", "Email"),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            plan_smart_update(&sheet, "Name\nAnn", "Email"),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert_eq!(column_letters(0), "A");
+        assert_eq!(column_letters(25), "Z");
+        assert_eq!(column_letters(27), "AB");
+    }
+
+    #[tokio::test]
+    async fn test_smart_update_values() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id/values/People"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(people_sheet()))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v4/spreadsheets/sheet-id/values:batchUpdate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v4/spreadsheets/sheet-id/values/People:append"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "smart_update_values",
+                "sheetName": "People",
+                "key_column": "Email",
+                "values": "Email,Status\nann@example.com,Inactive\ncat@example.com,Away\ndan@example.com,New"
+            }))
+            .await
+            .unwrap();
+        let summary: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(summary, json!({"updated": 2, "appended": 1, "skipped": 0}));
+
+        let requests = server.received_requests().await.unwrap();
+        let batch_update = requests
+            .iter()
+            .find(|request| request.url.path().ends_with("values:batchUpdate"))
+            .unwrap();
+        let body: Value = serde_json::from_slice(&batch_update.body).unwrap();
+        // Only the changed Status cells of Ann and Cat are written, the other cells keep
+        // their formulas
+        assert_eq!(
+            body["data"],
+            json!([
+                {"range": "'People'!C2", "values": [["Inactive"]]},
+                {"range": "'People'!C4", "values": [["Away"]]}
+            ])
+        );
+        assert_eq!(body["valueInputOption"], "USER_ENTERED");
+        let append = requests
+            .iter()
+            .find(|request| request.url.path().ends_with(":append"))
+            .unwrap();
+        let body: Value = serde_json::from_slice(&append.body).unwrap();
+        assert_eq!(body["values"], json!([["dan@example.com", "", "New"]]));
+    }
+
+    #[test]
+    fn test_matching_rows() {
+        let sheet: Vec<Vec<Value>> = serde_json::from_value(json!([
+            ["Name", "Email"],
+            ["Ann", "ann@example.com"],
+            ["Bob"],
+            ["Cat", ""],
+            ["Dan", "dan@example.com"]
+        ]))
+        .unwrap();
+        assert_eq!(
+            matching_rows(&sheet, "Email", str::is_empty).unwrap(),
+            vec![2, 3]
+        );
+        assert_eq!(
+            matching_rows(&sheet, "b", str::is_empty).unwrap(),
+            vec![2, 3]
+        );
+        assert_eq!(
+            matching_rows(&sheet, "A", |cell| cell.starts_with('D')).unwrap(),
+            vec![4]
+        );
+        assert!(matches!(
+            matching_rows(&sheet, "Phone", str::is_empty),
+            Err(ToolError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            matching_rows(&sheet, "C", str::is_empty),
+            Err(ToolError::InvalidParameters(_))
+        ));
+
+        assert_eq!(
+            contiguous_blocks(&[1, 2, 3, 5, 8, 9]),
+            vec![(8, 10), (5, 6), (1, 4)]
+        );
+        assert_eq!(column_index("A"), Some(0));
+        assert_eq!(column_index("ab"), Some(27));
+        assert_eq!(column_index("A1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_delete_matching_rows() {
+        let server = MockServer::start().await;
+        let rows = Arc::new(std::sync::Mutex::new(vec![
+            json!(["Name", "Email"]),
+            json!(["Ann", "ann@example.com"]),
+            json!(["Bob", ""]),
+            json!(["Cat", "cat@example.com"]),
+            json!(["Dan"]),
+            json!(["Eve", "eve@example.com"]),
+        ]));
+
+        let values = rows.clone();
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id/values/People"))
+            .respond_with(move |_: &Request| {
+                ResponseTemplate::new(200).set_body_json(json!({"values": *values.lock().unwrap()}))
+            })
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "sheets": [
+                    {"properties": {"sheetId": 0, "title": "Other"}},
+                    {"properties": {"sheetId": 7, "title": "People"}}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        // Apply the deletes in order, as the Sheets API does
+        let deleted = rows.clone();
+        Mock::given(method("POST"))
+            .and(path("/v4/spreadsheets/sheet-id:batchUpdate"))
+            .respond_with(move |request: &Request| {
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                let mut rows = deleted.lock().unwrap();
+                for request in body["requests"].as_array().unwrap() {
+                    let range = &request["deleteDimension"]["range"];
+                    assert_eq!(range["sheetId"], 7);
+                    assert_eq!(range["dimension"], "ROWS");
+                    let start = range["startIndex"].as_u64().unwrap() as usize;
+                    let end = range["endIndex"].as_u64().unwrap() as usize;
+                    rows.drain(start..end);
+                }
+                ResponseTemplate::new(200).set_body_json(json!({"spreadsheetId": "sheet-id"}))
+            })
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "delete_matching_rows",
+                "sheetName": "People",
+                "filter_column": "Email",
+                "filter_value": "^$",
+                "use_regex": true
+            }))
+            .await
+            .unwrap();
+        let summary: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(
+            summary,
+            json!({"deleted_count": 2, "remaining_row_count": 3})
+        );
+
+        let result = router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "get_values",
+                "range": "People"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Name,Email\nAnn,ann@example.com\nCat,cat@example.com\nEve,eve@example.com\n"
+        );
+
+        // Deleting every remaining row needs confirmation
+        let bulk = router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "delete_matching_rows",
+                "sheetName": "People",
+                "filter_column": "Email",
+                "filter_value": "@example\\.com$",
+                "use_regex": true
+            }))
+            .await;
+        assert!(matches!(bulk, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[test]
+    fn test_grid_range() {
+        let range = grid_range(7, "Log!B2:D10").unwrap();
+        assert_eq!(
+            (
+                range.start_column_index,
+                range.end_column_index,
+                range.start_row_index,
+                range.end_row_index
+            ),
+            (Some(1), Some(4), Some(1), Some(10))
+        );
+        assert_eq!(grid_range_a1(&range), "B2:D10");
+        assert_eq!(grid_range_a1(&grid_range(7, "C:C").unwrap()), "C:C");
+        assert_eq!(grid_range_a1(&grid_range(7, "A5").unwrap()), "A5:A5");
+        assert!(grid_range(7, "A0").is_err());
+        assert!(grid_range(7, "!!").is_err());
+
+        assert_eq!(color_hex(&parse_color("#ff0000").unwrap()), "#FF0000");
+        assert!(parse_color("red").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_conditional_formatting() {
+        let server = MockServer::start().await;
+        let rules = Arc::new(std::sync::Mutex::new(Vec::<Value>::new()));
+        let requests = Arc::new(std::sync::Mutex::new(Vec::<Value>::new()));
+
+        let listed = rules.clone();
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id"))
+            .respond_with(move |_: &Request| {
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "sheets": [
+                        {"properties": {"sheetId": 0, "title": "Other"}},
+                        {
+                            "properties": {"sheetId": 7, "title": "Log"},
+                            "conditionalFormats": *listed.lock().unwrap()
+                        }
+                    ]
+                }))
+            })
+            .mount(&server)
+            .await;
+        // Apply the rule changes, as the Sheets API does
+        let (updated, received) = (rules.clone(), requests.clone());
+        Mock::given(method("POST"))
+            .and(path("/v4/spreadsheets/sheet-id:batchUpdate"))
+            .respond_with(move |request: &Request| {
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                let request = body["requests"][0].clone();
+                let mut rules = updated.lock().unwrap();
+                if let Some(add) = request.get("addConditionalFormatRule") {
+                    rules.insert(add["index"].as_u64().unwrap() as usize, add["rule"].clone());
+                }
+                if let Some(delete) = request.get("deleteConditionalFormatRule") {
+                    rules.remove(delete["index"].as_u64().unwrap() as usize);
+                }
+                received.lock().unwrap().push(request);
+                ResponseTemplate::new(200).set_body_json(json!({"spreadsheetId": "sheet-id"}))
+            })
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "conditional_formatting_add",
+                "sheetId": 7,
+                "range": "A2:C100",
+                "rule_type": "text_contains",
+                "condition_value": "ERROR",
+                "format": {"bold": true, "background_color": "#FF0000"}
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            json!({
+                "addConditionalFormatRule": {
+                    "index": 0,
+                    "rule": {
+                        "ranges": [{
+                            "sheetId": 7,
+                            "startColumnIndex": 0,
+                            "endColumnIndex": 3,
+                            "startRowIndex": 1,
+                            "endRowIndex": 100
+                        }],
+                        "booleanRule": {
+                            "condition": {
+                                "type": "TEXT_CONTAINS",
+                                "values": [{"userEnteredValue": "ERROR"}]
+                            },
+                            "format": {
+                                "backgroundColor": {"red": 1.0, "green": 0.0, "blue": 0.0},
+                                "textFormat": {"bold": true}
+                            }
+                        }
+                    }
+                }
+            })
+        );
+
+        let result = router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "conditional_formatting_list",
+                "sheetId": 7
+            }))
+            .await
+            .unwrap();
+        let listed: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(
+            listed,
+            json!([{
+                "rule_index": 0,
+                "ranges": ["A2:C100"],
+                "condition": {"type": "TEXT_CONTAINS", "values": ["ERROR"]},
+                "format": {
+                    "bold": true,
+                    "italic": null,
+                    "background_color": "#FF0000",
+                    "text_color": null
+                }
+            }])
+        );
+
+        router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "conditional_formatting_delete",
+                "sheetId": 7,
+                "rule_index": 0
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            requests.lock().unwrap()[1],
+            json!({"deleteConditionalFormatRule": {"index": 0, "sheetId": 7}})
+        );
+        assert!(rules.lock().unwrap().is_empty());
+
+        // Rules need a condition to compare cells with
+        let missing_value = router
+            .sheets_tool(json!({
+                "spreadsheetId": "sheet-id",
+                "operation": "conditional_formatting_add",
+                "sheetId": 7,
+                "range": "A2:C100",
+                "rule_type": "cell_value",
+                "format": {"italic": true}
+            }))
+            .await;
+        assert!(matches!(
+            missing_value,
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_document_url() {
+        let url = "https://docs.google.com/document/d/1QG8d8wtWe7ZfmG93sW-1h2WXDJDUkOi-9hDnvJLmWrc/edit?tab=t.0#heading=h.5v419d3h97tr";
+        assert_eq!(
+            extract_google_drive_id(url),
+            Some("1QG8d8wtWe7ZfmG93sW-1h2WXDJDUkOi-9hDnvJLmWrc")
+        );
+    }
+
+    #[test]
+    fn test_spreadsheets_url() {
+        let url = "https://docs.google.com/spreadsheets/d/1J5KHqWsGFzweuiQboX7dlm8Ejv90Po16ocEBahzCt4W/edit?gid=1249300797#gid=1249300797";
         assert_eq!(
             extract_google_drive_id(url),
             Some("1J5KHqWsGFzweuiQboX7dlm8Ejv90Po16ocEBahzCt4W")
@@ -3076,6 +6485,360 @@ mod tests {
         assert_eq!(extract_google_drive_id(url), None);
     }
 
+    #[test]
+    fn test_format_storage_quota() {
+        let about: google_drive3::api::About = serde_json::from_value(json!({
+            "storageQuota": {
+                "limit": "16106127360",
+                "usage": "5583457484",
+                "usageInDrive": "4294967296",
+                "usageInDriveTrash": "10485760"
+            }
+        }))
+        .unwrap();
+        let content = format_storage_quota(&about.storage_quota.unwrap());
+
+        assert!(content.starts_with("Storage: 5.2 GB / 15.0 GB\n"));
+        let details: Value = serde_json::from_str(content.split_once('\n').unwrap().1).unwrap();
+        assert_eq!(
+            details,
+            json!({
+                "usage_bytes": 5583457484_i64,
+                "limit_bytes": 16106127360_i64,
+                "usage_in_drive_bytes": 4294967296_i64,
+                "usage_in_trash_bytes": 10485760,
+                "is_unlimited": false
+            })
+        );
+
+        let unlimited = AboutStorageQuota {
+            usage: Some(3 * 1024_i64.pow(4) + 1024_i64.pow(4) / 2),
+            ..Default::default()
+        };
+        assert!(format_storage_quota(&unlimited).starts_with("Storage: 3.5 TB used (unlimited)"));
+    }
+
+    #[test]
+    fn test_format_recent_file_list() {
+        let list: google_drive3::api::FileList = serde_json::from_value(json!({
+            "files": [
+                {
+                    "id": "doc1",
+                    "name": "Planning",
+                    "mimeType": "application/vnd.google-apps.document",
+                    "modifiedTime": "2025-03-02T10:00:00Z"
+                },
+                {
+                    "id": "sheet1",
+                    "name": "Budget",
+                    "mimeType": "application/vnd.google-apps.spreadsheet",
+                    "modifiedTime": "2025-03-01T10:00:00Z"
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            format_file_list(list.files),
+            "Planning (application/vnd.google-apps.document) (uri: doc1)\n\
+             Budget (application/vnd.google-apps.spreadsheet) (uri: sheet1)"
+        );
+        assert_eq!(format_file_list(None), "");
+    }
+
+    #[test]
+    fn test_find_drive_id() {
+        let drives = HashMap::from([
+            ("0AEngId".to_string(), "Engineering".to_string()),
+            ("0AMktId".to_string(), "Marketing".to_string()),
+        ]);
+
+        assert_eq!(
+            find_drive_id(&drives, "engin").unwrap(),
+            ("Engineering".to_string(), "0AEngId".to_string())
+        );
+        assert_eq!(find_drive_id(&drives, "MARKETING").unwrap().1, "0AMktId");
+        assert!(matches!(
+            find_drive_id(&drives, "finance"),
+            Err(ToolError::InvalidParameters(_))
+        ));
+
+        // "ing" is in both names
+        match find_drive_id(&drives, "ing") {
+            Err(ToolError::InvalidParameters(msg)) => {
+                assert!(msg.contains("Engineering (uri: 0AEngId)"));
+                assert!(msg.contains("Marketing (uri: 0AMktId)"));
+            }
+            other => panic!("Expected ambiguous match error, got {:?}", other),
+        }
+
+        // Drives with the same name are both kept, and reported instead of picking one
+        let drives = HashMap::from([
+            ("0AEngId".to_string(), "Engineering".to_string()),
+            ("0AEng2Id".to_string(), "engineering".to_string()),
+        ]);
+        match find_drive_id(&drives, "Engineering") {
+            Err(ToolError::InvalidParameters(msg)) => {
+                assert!(msg.contains("Engineering (uri: 0AEngId)"));
+                assert!(msg.contains("engineering (uri: 0AEng2Id)"));
+            }
+            other => panic!("Expected ambiguous match error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recent_files_sorted_by_modified_time() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files"))
+            .and(query_param("orderBy", "modifiedTime desc"))
+            .and(query_param("pageSize", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [
+                    {
+                        "id": "doc1",
+                        "name": "Planning",
+                        "mimeType": "application/vnd.google-apps.document",
+                        "modifiedTime": "2025-03-02T10:00:00Z"
+                    },
+                    {
+                        "id": "sheet1",
+                        "name": "Budget",
+                        "mimeType": "application/vnd.google-apps.spreadsheet",
+                        "modifiedTime": "2025-03-01T10:00:00Z"
+                    }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .recent_files(json!({"sort_by": "modifiedTime", "limit": 2}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Planning (application/vnd.google-apps.document) (uri: doc1)\n\
+             Budget (application/vnd.google-apps.spreadsheet) (uri: sheet1)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recent_files_escapes_mime_type() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files"))
+            .and(query_param("orderBy", "viewedByMeTime desc"))
+            .and(query_param(
+                "q",
+                r"mimeType = 'text/plain\' or name contains \'x'",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"files": []})))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        router
+            .recent_files(json!({"mimeType_filter": "text/plain' or name contains 'x"}))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_by_drive_name() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/drives"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "drives": [
+                    {"id": "0AEngId", "name": "Engineering"},
+                    {"id": "0AMktId", "name": "Marketing"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/drive/v3/files"))
+            .and(query_param("corpora", "drive"))
+            .and(query_param("driveId", "0AMktId"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [
+                    {"id": "doc1", "name": "Launch plan", "mimeType": "application/vnd.google-apps.document"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let result = router
+            .search(json!({"name": "plan", "driveName": "market"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Launch plan (application/vnd.google-apps.document) (uri: doc1)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_developer_metadata_round_trip() {
+        let server = MockServer::start().await;
+        let stored = Arc::new(std::sync::Mutex::new(Vec::<Value>::new()));
+
+        // Match the stored metadata by key, as the Sheets API does
+        let searched = stored.clone();
+        Mock::given(method("POST"))
+            .and(path("/v4/spreadsheets/sheet-id/developerMetadata:search"))
+            .respond_with(move |request: &Request| {
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                let key = &body["dataFilters"][0]["developerMetadataLookup"]["metadataKey"];
+                let matched: Vec<Value> = searched
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|metadata| key.is_null() || metadata["metadataKey"] == *key)
+                    .map(|metadata| json!({"developerMetadata": metadata}))
+                    .collect();
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"matchedDeveloperMetadata": matched}))
+            })
+            .mount(&server)
+            .await;
+        let updated = stored.clone();
+        Mock::given(method("POST"))
+            .and(path("/v4/spreadsheets/sheet-id:batchUpdate"))
+            .respond_with(move |request: &Request| {
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                let request = &body["requests"][0];
+                let mut stored = updated.lock().unwrap();
+                let reply = if let Some(create) = request.get("createDeveloperMetadata") {
+                    let mut metadata = create["developerMetadata"].clone();
+                    metadata["metadataId"] = json!(stored.len() as i64 + 7);
+                    stored.push(metadata.clone());
+                    json!({"createDeveloperMetadata": {"developerMetadata": metadata}})
+                } else {
+                    let update = &request["updateDeveloperMetadata"];
+                    let id = &update["dataFilters"][0]["developerMetadataLookup"]["metadataId"];
+                    let metadata = stored
+                        .iter_mut()
+                        .find(|metadata| metadata["metadataId"] == *id)
+                        .unwrap();
+                    metadata["metadataValue"] =
+                        update["developerMetadata"]["metadataValue"].clone();
+                    json!({"updateDeveloperMetadata": {"developerMetadata": [metadata]}})
+                };
+                ResponseTemplate::new(200).set_body_json(json!({"replies": [reply]}))
+            })
+            .mount(&server)
+            .await;
+        let fetched = stored.clone();
+        Mock::given(method("GET"))
+            .and(path("/v4/spreadsheets/sheet-id/developerMetadata/7"))
+            .respond_with(move |_: &Request| {
+                ResponseTemplate::new(200).set_body_json(fetched.lock().unwrap()[0].clone())
+            })
+            .mount(&server)
+            .await;
+
+        let router = mock_router(&server);
+        let call = |params: Value| {
+            let router = &router;
+            async move {
+                let mut params = params;
+                params["spreadsheetId"] = json!("sheet-id");
+                let result = router.sheets_tool(params).await.unwrap();
+                serde_json::from_str::<Value>(result[0].as_text().unwrap()).unwrap()
+            }
+        };
+        let owner = json!({
+            "metadata_id": 7,
+            "key": "owner",
+            "value": "finance-team",
+            "location": {"type": "column", "sheetId": 42, "startIndex": 3, "endIndex": 4}
+        });
+        let set = json!({
+            "operation": "developer_metadata_set",
+            "metadataKey": "owner",
+            "metadataValue": "finance-team",
+            "location": "column",
+            "sheetId": 42,
+            "startIndex": 3
+        });
+        assert_eq!(call(set.clone()).await, owner);
+        assert_eq!(
+            call(json!({"operation": "developer_metadata_list", "metadataKey": "owner"})).await,
+            json!([owner])
+        );
+        assert_eq!(
+            call(json!({"operation": "developer_metadata_get", "metadataId": 7})).await,
+            owner
+        );
+
+        // Setting the key again updates its value instead of adding another entry
+        let mut reset = set;
+        reset["metadataValue"] = json!("data-team");
+        assert_eq!(call(reset).await["value"], "data-team");
+        let listed = call(json!({"operation": "developer_metadata_list"})).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        assert_eq!(listed[0]["value"], "data-team");
+
+        assert!(matches!(
+            developer_metadata_location(&json!({"location": "sheet"})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_named_functions_are_not_supported() {
+        let server = MockServer::start().await;
+        let router = mock_router(&server);
+        for operation in ["named_functions_list", "named_functions_set"] {
+            let result = router
+                .sheets_tool(json!({"spreadsheetId": "sheet-id", "operation": operation}))
+                .await;
+            match result {
+                Err(ToolError::ExecutionError(msg)) => {
+                    assert!(msg.contains("does not expose named functions"))
+                }
+                other => panic!("Expected an unsupported operation error, got {:?}", other),
+            }
+        }
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_batch_values_json() {
+        let response: google_sheets4::api::BatchGetValuesResponse =
+            serde_json::from_value(json!({
+                "spreadsheetId": "sheet",
+                "valueRanges": [
+                    {"range": "Sheet1!A1:B2", "majorDimension": "ROWS", "values": [["a", "b"], ["c", "d"]]},
+                    {"range": "Sheet2!A1:A2", "majorDimension": "ROWS", "values": [["x"], ["y"]]},
+                    {"range": "Sheet3!C1:C1", "majorDimension": "ROWS"}
+                ]
+            }))
+            .unwrap();
+
+        assert_eq!(
+            batch_values_json(response.value_ranges.unwrap()),
+            json!([
+                {"range": "Sheet1!A1:B2", "values_csv": "a,b\nc,d\n"},
+                {"range": "Sheet2!A1:A2", "values_csv": "x\ny\n"},
+                {"range": "Sheet3!C1:C1", "values_csv": ""}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(10485760), "10.0 MB");
+        assert_eq!(format_bytes(1610612736), "1.5 GB");
+        assert_eq!(format_bytes(2 * 1024_i64.pow(4)), "2.0 TB");
+    }
+
     #[test]
     fn test_no_d_segment() {
         let url =
@@ -8,6 +8,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use chrono::DateTime;
 use google_drive3::common::GetToken;
 use oauth2::basic::BasicClient;
 use oauth2::reqwest;
@@ -19,7 +20,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info};
 use url::Url;
 
-use super::storage::CredentialsManager;
+use super::storage::{CredentialsManager, TokenRefreshEvent};
 
 /// Structure representing the OAuth2 configuration file format
 #[derive(Debug, Deserialize, Serialize)]
@@ -199,10 +200,36 @@ impl PkceOAuth2Client {
         Ok(access_token)
     }
 
+    /// Refresh the access token, reporting the outcome to the credentials manager's callback
     async fn refresh_token(
         &self,
         refresh_token: &str,
     ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let result = self.exchange_refresh_token(refresh_token).await;
+
+        let event = match &result {
+            Ok(token_data) => TokenRefreshEvent {
+                succeeded: true,
+                error: None,
+                next_expiry: token_data
+                    .expires_at
+                    .and_then(|expires_at| DateTime::from_timestamp(expires_at as i64, 0)),
+            },
+            Err(e) => TokenRefreshEvent {
+                succeeded: false,
+                error: Some(e.to_string()),
+                next_expiry: None,
+            },
+        };
+        self.credentials_manager.notify_token_refresh(event);
+
+        result.map(|token_data| token_data.access_token)
+    }
+
+    async fn exchange_refresh_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<TokenData, Box<dyn Error + Send + Sync>> {
         debug!("Attempting to refresh access token");
 
         // Create a RefreshToken from the string
@@ -235,8 +262,8 @@ impl PkceOAuth2Client {
 
         // Always update the token data with the new access token and expiration
         let token_data = TokenData {
-            access_token: access_token.clone(),
-            refresh_token: new_refresh_token.clone(),
+            access_token,
+            refresh_token: new_refresh_token,
             expires_at,
             project_id: self.project_id.clone(),
         };
@@ -247,7 +274,7 @@ impl PkceOAuth2Client {
             .map(|_| debug!("Successfully stored token data"))
             .unwrap_or_else(|e| error!("Failed to store token data: {}", e));
 
-        Ok(access_token)
+        Ok(token_data)
     }
 
     fn start_redirect_server(
@@ -349,3 +376,55 @@ impl GetToken for PkceOAuth2Client {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_failed_refresh_notifies_callback() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("oauth.json");
+        let config = json_config("http://127.0.0.1:9/token");
+        fs::write(&config_path, config).unwrap();
+
+        let credentials_manager = Arc::new(CredentialsManager::new(
+            dir.path().join("credentials.json").display().to_string(),
+            true,
+            "test_service".to_string(),
+            "test_user".to_string(),
+        ));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        credentials_manager.set_token_refresh_callback(Box::new(move |event| {
+            recorded.lock().unwrap().push(event);
+        }));
+
+        let client = PkceOAuth2Client::new(&config_path, credentials_manager).unwrap();
+        let result = client.refresh_token("expired_refresh_token").await;
+        assert!(result.is_err());
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(!events[0].succeeded);
+        assert!(events[0].error.as_ref().is_some_and(|e| !e.is_empty()));
+        assert_eq!(events[0].next_expiry, None);
+    }
+
+    fn json_config(token_uri: &str) -> String {
+        serde_json::json!({
+            "installed": {
+                "client_id": "client",
+                "project_id": "project",
+                "auth_uri": "http://127.0.0.1:9/auth",
+                "token_uri": token_uri,
+                "auth_provider_x509_cert_url": "http://127.0.0.1:9/certs",
+                "client_secret": "secret",
+                "redirect_uris": ["http://localhost"]
+            }
+        })
+        .to_string()
+    }
+}
@@ -1,8 +1,10 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use keyring::Entry;
 use serde::{de::DeserializeOwned, Serialize};
 use std::fs;
 use std::path::Path;
+use std::sync::{Arc, RwLock};
 use thiserror::Error;
 use tracing::{debug, error, warn};
 
@@ -21,6 +23,18 @@ pub enum StorageError {
     SerializationError(#[from] serde_json::Error),
 }
 
+/// The outcome of an attempt to refresh the OAuth access token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenRefreshEvent {
+    pub succeeded: bool,
+    /// Why the refresh failed, in which case the user needs to re-authenticate
+    pub error: Option<String>,
+    /// When the refreshed access token expires
+    pub next_expiry: Option<DateTime<Utc>>,
+}
+
+pub type TokenRefreshCallback = Box<dyn Fn(TokenRefreshEvent) + Send + Sync>;
+
 /// CredentialsManager handles secure storage of OAuth credentials.
 /// It attempts to store credentials in the system keychain first,
 /// with fallback to file system storage if keychain access fails and fallback is enabled.
@@ -29,6 +43,7 @@ pub struct CredentialsManager {
     fallback_to_disk: bool,
     keychain_service: String,
     keychain_username: String,
+    token_refresh_callback: Arc<RwLock<Option<TokenRefreshCallback>>>,
 }
 
 impl CredentialsManager {
@@ -43,6 +58,21 @@ impl CredentialsManager {
             fallback_to_disk,
             keychain_service,
             keychain_username,
+            token_refresh_callback: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Sets a callback that is invoked after each attempt to refresh the access token,
+    /// so that failures needing manual re-authentication can be surfaced.
+    /// The callback is shared with all clones of this manager.
+    pub fn set_token_refresh_callback(&self, callback: TokenRefreshCallback) {
+        *self.token_refresh_callback.write().unwrap() = Some(callback);
+    }
+
+    /// Reports the outcome of a token refresh to the registered callback, if any.
+    pub fn notify_token_refresh(&self, event: TokenRefreshEvent) {
+        if let Some(callback) = self.token_refresh_callback.read().unwrap().as_ref() {
+            callback(event);
         }
     }
 
@@ -229,6 +259,7 @@ impl Clone for CredentialsManager {
             fallback_to_disk: self.fallback_to_disk,
             keychain_service: self.keychain_service.clone(),
             keychain_username: self.keychain_username.clone(),
+            token_refresh_callback: Arc::clone(&self.token_refresh_callback),
         }
     }
 }
@@ -0,0 +1,707 @@
+mod schema;
+
+use graphql_parser::query::{Definition, OperationDefinition};
+use indoc::indoc;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{future::Future, pin::Pin};
+use tokio::time::Instant;
+
+use crate::util::{json_content, required_str};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+use schema::{Schema, INTROSPECTION_QUERY};
+
+/// How long an introspected schema is reused before it is fetched again
+const SCHEMA_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The introspected schema of each endpoint, with the time it was fetched
+type SchemaCache = Arc<Mutex<HashMap<String, (Instant, Arc<Schema>)>>>;
+
+/// Router for any GraphQL endpoint, authenticating requests to the endpoint in
+/// GOOSE_GRAPHQL_ENDPOINT with GOOSE_GRAPHQL_TOKEN when both are set
+#[derive(Clone)]
+pub struct GraphQLRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    client: Client,
+    /// The token, with the origin of the endpoint it is sent to
+    token: Option<(url::Origin, String)>,
+    schemas: SchemaCache,
+}
+
+impl Default for GraphQLRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphQLRouter {
+    pub fn new() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let token = var("GOOSE_GRAPHQL_TOKEN");
+        let endpoint = var("GOOSE_GRAPHQL_ENDPOINT");
+        if token.is_some() && endpoint.is_none() {
+            tracing::warn!("GOOSE_GRAPHQL_TOKEN is not sent without GOOSE_GRAPHQL_ENDPOINT");
+        }
+        Self::with_token(token, endpoint.as_deref())
+    }
+
+    /// Create a router sending the given token in the Authorization header of requests to
+    /// the origin of `endpoint`, and no token to other endpoints
+    pub fn with_token(token: Option<String>, endpoint: Option<&str>) -> Self {
+        let token =
+            token
+                .zip(endpoint)
+                .and_then(|(token, endpoint)| match url::Url::parse(endpoint) {
+                    Ok(url) => Some((url.origin(), token)),
+                    Err(e) => {
+                        tracing::warn!("Invalid GOOSE_GRAPHQL_ENDPOINT '{}': {}", endpoint, e);
+                        None
+                    }
+                });
+
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+
+        let operation_schema = json!({
+            "type": "object",
+            "required": ["endpoint", "query"],
+            "properties": {
+                "endpoint": {"type": "string", "description": "The url of the GraphQL endpoint"},
+                "query": {"type": "string", "description": "The GraphQL document"},
+                "variables": {
+                    "type": "object",
+                    "description": "The variables of the operation, as a JSON object or a string containing one"
+                },
+                "operation_name": {
+                    "type": "string",
+                    "description": "The operation to run, when the document contains several"
+                }
+            }
+        });
+
+        let execute_query = Tool::new(
+            "execute_query",
+            indoc! {r#"
+                Run a GraphQL query and return its JSON result. Mutations are rejected, use
+                execute_mutation for them. When the result contains both data and errors, both
+                are returned.
+            "#},
+            operation_schema.clone(),
+            read_only("Execute a GraphQL query"),
+        );
+
+        let execute_mutation = Tool::new(
+            "execute_mutation",
+            "Run a GraphQL mutation and return its JSON result. Mutations change data on the server.",
+            operation_schema,
+            Some(ToolAnnotations {
+                title: Some("Execute a GraphQL mutation".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: true,
+            }),
+        );
+
+        let introspect_schema = Tool::new(
+            "introspect_schema",
+            indoc! {r#"
+                Fetch the schema of an endpoint with an introspection query and return it as SDL.
+                The schema is cached for 10 minutes, set refresh to fetch it again.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["endpoint"],
+                "properties": {
+                    "endpoint": {"type": "string"},
+                    "refresh": {"type": "boolean", "default": false}
+                }
+            }),
+            read_only("Introspect a GraphQL schema"),
+        );
+
+        let validate_query = Tool::new(
+            "validate_query",
+            indoc! {r#"
+                Check the syntax of a GraphQL document and its fields, arguments, types and
+                fragments against the schema of the endpoint, without running it.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["endpoint", "query"],
+                "properties": {
+                    "endpoint": {"type": "string"},
+                    "query": {"type": "string"}
+                }
+            }),
+            read_only("Validate a GraphQL query"),
+        );
+
+        let instructions = indoc! {r#"
+            The GraphQL extension talks to any GraphQL endpoint, given as a url on each call.
+
+            - Start with introspect_schema to learn the available types, fields and arguments.
+            - Check new queries with validate_query before running them.
+            - Pass values through variables rather than writing them into the query.
+            - execute_mutation changes data on the server, so confirm mutations with the user.
+        "#};
+
+        Self {
+            tools: vec![
+                execute_query,
+                execute_mutation,
+                introspect_schema,
+                validate_query,
+            ],
+            instructions: instructions.to_string(),
+            client: Client::new(),
+            token,
+            schemas: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // Helper method to post a GraphQL request, returning the body of the response
+    async fn post(&self, endpoint: &str, body: Value) -> Result<Value, ToolError> {
+        let url = url::Url::parse(endpoint)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!("Invalid endpoint url '{}'", endpoint))
+            })?;
+
+        // The token only goes to its endpoint, not to any url a query names
+        let token = self
+            .token
+            .as_ref()
+            .filter(|(origin, _)| *origin == url.origin())
+            .map(|(_, token)| token);
+        let mut request = self.client.post(url).json(&body);
+        if let Some(token) = token {
+            // A token that already names its scheme, like "Basic ...", is sent as is
+            request = if token.contains(' ') {
+                request.header("authorization", token)
+            } else {
+                request.bearer_auth(token)
+            };
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("GraphQL request failed: {}", e)))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+
+        // Servers may report GraphQL errors with any status, so the body is checked first
+        match serde_json::from_str::<Value>(&text) {
+            Ok(body) if body.get("data").is_some() || body.get("errors").is_some() => Ok(body),
+            _ if status == reqwest::StatusCode::UNAUTHORIZED
+                || status == reqwest::StatusCode::FORBIDDEN =>
+            {
+                Err(ToolError::ExecutionError(format!(
+                    "The endpoint refused the request ({}), check GOOSE_GRAPHQL_TOKEN and \
+                     GOOSE_GRAPHQL_ENDPOINT",
+                    status
+                )))
+            }
+            _ => Err(ToolError::ExecutionError(format!(
+                "The endpoint returned {} instead of a GraphQL response: {}",
+                status,
+                text.chars().take(500).collect::<String>()
+            ))),
+        }
+    }
+
+    async fn execute(&self, params: Value, mutation: bool) -> Result<Vec<Content>, ToolError> {
+        let endpoint = required_str(&params, "endpoint")?;
+        let query = required_str(&params, "query")?;
+
+        let has_mutation = graphql_parser::parse_query::<&str>(query)
+            .map_err(|e| ToolError::InvalidParameters(e.to_string()))?
+            .definitions
+            .iter()
+            .any(|definition| {
+                matches!(
+                    definition,
+                    Definition::Operation(OperationDefinition::Mutation(_))
+                )
+            });
+        if has_mutation && !mutation {
+            return Err(ToolError::InvalidParameters(
+                "The document contains a mutation, use execute_mutation to run it".into(),
+            ));
+        }
+        if !has_mutation && mutation {
+            return Err(ToolError::InvalidParameters(
+                "The document contains no mutation, use execute_query to run it".into(),
+            ));
+        }
+
+        let mut body = json!({"query": query});
+        match params.get("variables") {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(variables)) => body["variables"] = json!(variables),
+            Some(Value::String(variables)) => {
+                let variables: Value = serde_json::from_str(variables).map_err(|e| {
+                    ToolError::InvalidParameters(format!("Invalid variables JSON: {}", e))
+                })?;
+                if !variables.is_object() {
+                    return Err(ToolError::InvalidParameters(
+                        "'variables' must be a JSON object".into(),
+                    ));
+                }
+                body["variables"] = variables;
+            }
+            Some(_) => {
+                return Err(ToolError::InvalidParameters(
+                    "'variables' must be a JSON object".into(),
+                ))
+            }
+        }
+        if let Some(operation_name) = params.get("operation_name").and_then(Value::as_str) {
+            body["operationName"] = json!(operation_name);
+        }
+
+        let response = self.post(endpoint, body).await?;
+        let errors = response["errors"]
+            .as_array()
+            .filter(|errors| !errors.is_empty());
+        match (errors, &response["data"]) {
+            (Some(errors), Value::Null) => Err(ToolError::ExecutionError(format!(
+                "The GraphQL endpoint returned errors:\n{}",
+                format_errors(errors)
+            ))),
+            (Some(_), _) => Ok(vec![json_content(&json!({
+                "data": response["data"],
+                "errors": response["errors"],
+            }))]),
+            (None, data) => Ok(vec![json_content(data)]),
+        }
+    }
+
+    /// The schema of an endpoint, introspected at most once per SCHEMA_CACHE_TTL
+    async fn schema(&self, endpoint: &str, refresh: bool) -> Result<Arc<Schema>, ToolError> {
+        if !refresh {
+            let schemas = self.schemas.lock().unwrap();
+            if let Some((fetched, schema)) = schemas.get(endpoint) {
+                if fetched.elapsed() < SCHEMA_CACHE_TTL {
+                    return Ok(schema.clone());
+                }
+            }
+        }
+
+        let response = self
+            .post(endpoint, json!({"query": INTROSPECTION_QUERY}))
+            .await?;
+        if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+            return Err(ToolError::ExecutionError(format!(
+                "Introspection failed, it may be disabled on this endpoint:\n{}",
+                format_errors(errors)
+            )));
+        }
+        let schema = Arc::new(
+            Schema::from_introspection(&response["data"]).map_err(ToolError::ExecutionError)?,
+        );
+        self.schemas
+            .lock()
+            .unwrap()
+            .insert(endpoint.to_string(), (Instant::now(), schema.clone()));
+        Ok(schema)
+    }
+
+    async fn introspect_schema(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let endpoint = required_str(&params, "endpoint")?;
+        let refresh = params
+            .get("refresh")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let schema = self.schema(endpoint, refresh).await?;
+        Ok(vec![Content::text(schema.to_sdl())])
+    }
+
+    async fn validate_query(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let endpoint = required_str(&params, "endpoint")?;
+        let query = required_str(&params, "query")?;
+
+        if let Err(e) = graphql_parser::parse_query::<&str>(query) {
+            return Ok(vec![Content::text(format!("Syntax error: {}", e))]);
+        }
+        let schema = self.schema(endpoint, false).await?;
+        // The document borrows the query and cannot be held across the await above
+        let errors = match graphql_parser::parse_query::<&str>(query) {
+            Ok(document) => schema.validate(&document),
+            Err(e) => vec![e.to_string()],
+        };
+        if errors.is_empty() {
+            return Ok(vec![Content::text("The query is valid")]);
+        }
+        Ok(vec![Content::text(format!(
+            "Found {} error{}:\n{}",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" },
+            errors
+                .iter()
+                .map(|error| format!("- {}", error))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ))])
+    }
+}
+
+/// One line per entry of a GraphQL `errors` array, with its path and location
+fn format_errors(errors: &[Value]) -> String {
+    errors
+        .iter()
+        .map(|error| {
+            let mut line = format!("- {}", error["message"].as_str().unwrap_or("Unknown error"));
+            if let Some(path) = error["path"].as_array() {
+                let path: Vec<String> = path
+                    .iter()
+                    .map(|segment| match segment {
+                        Value::String(name) => name.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                line.push_str(&format!(" (at {})", path.join(".")));
+            }
+            if let Some(location) = error["locations"].get(0) {
+                line.push_str(&format!(
+                    " [line {}, column {}]",
+                    location["line"], location["column"]
+                ));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Router for GraphQLRouter {
+    fn name(&self) -> String {
+        "graphql".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "execute_query" => this.execute(arguments, false).await,
+                "execute_mutation" => this.execute(arguments, true).await,
+                "introspect_schema" => this.introspect_schema(arguments).await,
+                "validate_query" => this.validate_query(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schema::tests::library_introspection;
+    use wiremock::matchers::{
+        body_json, body_string_contains, header, header_exists, method, path,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn endpoint(server: &MockServer) -> String {
+        format!("{}/graphql", server.uri())
+    }
+
+    async fn mock_introspection(server: &MockServer, calls: u64) {
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("__schema"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"data": library_introspection()})),
+            )
+            .expect(calls)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_query_variables() {
+        let server = MockServer::start().await;
+        let query = "query Book($id: ID!) { book(id: $id) { title } }";
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_json(json!({
+                "query": query,
+                "variables": {"id": "42", "nested": {"tags": ["a", "b"]}},
+                "operationName": "Book"
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"data": {"book": {"title": "Dune"}}})),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+        let router = GraphQLRouter::with_token(Some("secret".into()), Some(&endpoint(&server)));
+
+        // Variables are sent as a JSON object, whether given as one or as a string
+        for variables in [
+            json!({"id": "42", "nested": {"tags": ["a", "b"]}}),
+            json!(r#"{"id": "42", "nested": {"tags": ["a", "b"]}}"#),
+        ] {
+            let result = router
+                .call_tool(
+                    "execute_query",
+                    json!({
+                        "endpoint": endpoint(&server),
+                        "query": query,
+                        "variables": variables,
+                        "operation_name": "Book"
+                    }),
+                )
+                .await
+                .unwrap();
+            let data: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+            assert_eq!(data, json!({"book": {"title": "Dune"}}));
+        }
+
+        let result = router
+            .call_tool(
+                "execute_query",
+                json!({"endpoint": endpoint(&server), "query": query, "variables": "[1]"}),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_token_is_only_sent_to_its_endpoint() {
+        let server = MockServer::start().await;
+        let other = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header_exists("authorization"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(0)
+            .mount(&other)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"data": {"books": []}})))
+            .expect(1)
+            .mount(&other)
+            .await;
+        let router = GraphQLRouter::with_token(Some("secret".into()), Some(&endpoint(&server)));
+
+        router
+            .call_tool(
+                "execute_query",
+                json!({"endpoint": endpoint(&other), "query": "{ books { id } }"}),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_forwarded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("missing"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": null,
+                "errors": [{
+                    "message": "Book not found",
+                    "path": ["book", 0],
+                    "locations": [{"line": 1, "column": 3}]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("partial"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "data": {"books": []},
+                "errors": [{"message": "Author is private"}]
+            })))
+            .mount(&server)
+            .await;
+        let router = GraphQLRouter::with_token(None, None);
+
+        let result = router
+            .call_tool(
+                "execute_query",
+                json!({"endpoint": endpoint(&server), "query": "{ missing: book(id: 1) { title } }"}),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(ToolError::ExecutionError(message))
+                if message.ends_with("- Book not found (at book.0) [line 1, column 3]")
+        ));
+
+        let result = router
+            .call_tool(
+                "execute_query",
+                json!({"endpoint": endpoint(&server), "query": "{ partial: books { title } }"}),
+            )
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(body["data"], json!({"books": []}));
+        assert_eq!(body["errors"][0]["message"], "Author is private");
+    }
+
+    #[tokio::test]
+    async fn test_operation_kinds() {
+        let router = GraphQLRouter::with_token(None, None);
+        let mutation = "mutation { addBook(input: {title: \"Dune\"}) { id } }";
+
+        let result = router
+            .call_tool(
+                "execute_query",
+                json!({"endpoint": "http://localhost/graphql", "query": mutation}),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message.contains("execute_mutation"))
+        );
+        let result = router
+            .call_tool(
+                "execute_mutation",
+                json!({"endpoint": "http://localhost/graphql", "query": "{ books { id } }"}),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+        let result = router
+            .call_tool(
+                "execute_query",
+                json!({"endpoint": "http://localhost/graphql", "query": "{ books { id }"}),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
+        let tool = router
+            .list_tools()
+            .into_iter()
+            .find(|tool| tool.name == "execute_mutation");
+        assert!(tool.unwrap().annotations.unwrap().destructive_hint);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_schema_cache() {
+        let server = MockServer::start().await;
+        mock_introspection(&server, 2).await;
+        let router = GraphQLRouter::with_token(None, None);
+
+        let result = router
+            .call_tool("introspect_schema", json!({"endpoint": endpoint(&server)}))
+            .await
+            .unwrap();
+        assert!(result[0]
+            .as_text()
+            .unwrap()
+            .contains("books(first: Int = 10, genre: Genre): [Book!]!"));
+
+        // Validation reuses the cached schema until it expires
+        let result = router
+            .call_tool(
+                "validate_query",
+                json!({"endpoint": endpoint(&server), "query": "{ books { id title } }"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result[0].as_text().unwrap(), "The query is valid");
+
+        tokio::time::advance(SCHEMA_CACHE_TTL + Duration::from_secs(1)).await;
+        let result = router
+            .call_tool(
+                "validate_query",
+                json!({"endpoint": endpoint(&server), "query": "{ books { titel } }"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Found 1 error:\n- 1:11: Cannot query field 'titel' on type 'Book'"
+        );
+
+        let result = router
+            .call_tool(
+                "validate_query",
+                json!({"endpoint": endpoint(&server), "query": "{ books { id }"}),
+            )
+            .await
+            .unwrap();
+        assert!(result[0].as_text().unwrap().starts_with("Syntax error:"));
+    }
+}
@@ -0,0 +1,758 @@
+use graphql_parser::query::{
+    Definition, Document, FragmentDefinition, OperationDefinition, Selection, SelectionSet, Type,
+    TypeCondition,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// The standard introspection query, without the directive definitions
+pub const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { ...InputValue }
+        type { ...TypeRef }
+        isDeprecated
+        deprecationReason
+      }
+      inputFields { ...InputValue }
+      interfaces { ...TypeRef }
+      enumValues(includeDeprecated: true) {
+        name
+        description
+        isDeprecated
+        deprecationReason
+      }
+      possibleTypes { ...TypeRef }
+    }
+  }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } } } }
+}
+"#;
+
+const BUILTIN_SCALARS: [&str; 5] = ["String", "Int", "Float", "Boolean", "ID"];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSchema {
+    query_type: Option<NamedRef>,
+    mutation_type: Option<NamedRef>,
+    subscription_type: Option<NamedRef>,
+    types: Vec<TypeDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedRef {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeDef {
+    kind: String,
+    name: String,
+    description: Option<String>,
+    fields: Option<Vec<FieldDef>>,
+    input_fields: Option<Vec<InputValue>>,
+    interfaces: Option<Vec<TypeRef>>,
+    enum_values: Option<Vec<EnumValue>>,
+    possible_types: Option<Vec<TypeRef>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldDef {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<InputValue>,
+    #[serde(rename = "type")]
+    type_ref: TypeRef,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InputValue {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    type_ref: TypeRef,
+    default_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnumValue {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: String,
+    name: Option<String>,
+    of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    /// The named type inside any list and non null wrappers
+    fn named(&self) -> &str {
+        match (&self.name, &self.of_type) {
+            (Some(name), _) => name,
+            (None, Some(inner)) => inner.named(),
+            (None, None) => "",
+        }
+    }
+
+    fn sdl(&self) -> String {
+        match (self.kind.as_str(), &self.of_type) {
+            ("NON_NULL", Some(inner)) => format!("{}!", inner.sdl()),
+            ("LIST", Some(inner)) => format!("[{}]", inner.sdl()),
+            _ => self.named().to_string(),
+        }
+    }
+}
+
+/// A schema read from the result of the introspection query
+#[derive(Debug)]
+pub struct Schema {
+    query_type: Option<String>,
+    mutation_type: Option<String>,
+    subscription_type: Option<String>,
+    types: BTreeMap<String, TypeDef>,
+}
+
+impl Schema {
+    /// Read the schema from the `data` of an introspection response
+    pub fn from_introspection(data: &Value) -> Result<Self, String> {
+        let raw: RawSchema = serde_json::from_value(data["__schema"].clone())
+            .map_err(|e| format!("Invalid introspection result: {}", e))?;
+        Ok(Self {
+            query_type: raw.query_type.map(|named| named.name),
+            mutation_type: raw.mutation_type.map(|named| named.name),
+            subscription_type: raw.subscription_type.map(|named| named.name),
+            types: raw
+                .types
+                .into_iter()
+                .map(|type_def| (type_def.name.clone(), type_def))
+                .collect(),
+        })
+    }
+
+    /// Print the schema in the schema definition language, leaving out introspection types
+    /// and built in scalars
+    pub fn to_sdl(&self) -> String {
+        let mut blocks = Vec::new();
+
+        let roots = [
+            ("query", &self.query_type, "Query"),
+            ("mutation", &self.mutation_type, "Mutation"),
+            ("subscription", &self.subscription_type, "Subscription"),
+        ];
+        if roots
+            .iter()
+            .any(|(_, name, default)| name.as_deref().is_some_and(|name| name != *default))
+        {
+            let operations: Vec<String> = roots
+                .iter()
+                .filter_map(|(operation, name, _)| {
+                    Some(format!("  {}: {}", operation, name.as_ref()?))
+                })
+                .collect();
+            blocks.push(format!("schema {{\n{}\n}}", operations.join("\n")));
+        }
+
+        for type_def in self.types.values() {
+            if type_def.name.starts_with("__") || BUILTIN_SCALARS.contains(&type_def.name.as_str())
+            {
+                continue;
+            }
+            let mut block = description(&type_def.description, "");
+            let name = &type_def.name;
+            match type_def.kind.as_str() {
+                "SCALAR" => block.push_str(&format!("scalar {}", name)),
+                "UNION" => {
+                    let members: Vec<&str> = type_def
+                        .possible_types
+                        .iter()
+                        .flatten()
+                        .map(TypeRef::named)
+                        .collect();
+                    block.push_str(&format!("union {} = {}", name, members.join(" | ")));
+                }
+                "ENUM" => {
+                    let values: Vec<String> = type_def
+                        .enum_values
+                        .iter()
+                        .flatten()
+                        .map(|value| {
+                            format!(
+                                "{}  {}{}",
+                                description(&value.description, "  "),
+                                value.name,
+                                deprecated(value.is_deprecated, &value.deprecation_reason)
+                            )
+                        })
+                        .collect();
+                    block.push_str(&format!("enum {} {{\n{}\n}}", name, values.join("\n")));
+                }
+                "INPUT_OBJECT" => {
+                    let fields: Vec<String> = type_def
+                        .input_fields
+                        .iter()
+                        .flatten()
+                        .map(|field| {
+                            format!(
+                                "{}  {}",
+                                description(&field.description, "  "),
+                                input_value(field)
+                            )
+                        })
+                        .collect();
+                    block.push_str(&format!("input {} {{\n{}\n}}", name, fields.join("\n")));
+                }
+                kind @ ("OBJECT" | "INTERFACE") => {
+                    let keyword = if kind == "OBJECT" {
+                        "type"
+                    } else {
+                        "interface"
+                    };
+                    let interfaces: Vec<&str> = type_def
+                        .interfaces
+                        .iter()
+                        .flatten()
+                        .map(TypeRef::named)
+                        .collect();
+                    let implements = if interfaces.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" implements {}", interfaces.join(" & "))
+                    };
+                    let fields: Vec<String> = type_def
+                        .fields
+                        .iter()
+                        .flatten()
+                        .map(|field| {
+                            let args = if field.args.is_empty() {
+                                String::new()
+                            } else {
+                                let args: Vec<String> =
+                                    field.args.iter().map(input_value).collect();
+                                format!("({})", args.join(", "))
+                            };
+                            format!(
+                                "{}  {}{}: {}{}",
+                                description(&field.description, "  "),
+                                field.name,
+                                args,
+                                field.type_ref.sdl(),
+                                deprecated(field.is_deprecated, &field.deprecation_reason)
+                            )
+                        })
+                        .collect();
+                    block.push_str(&format!(
+                        "{} {}{} {{\n{}\n}}",
+                        keyword,
+                        name,
+                        implements,
+                        fields.join("\n")
+                    ));
+                }
+                _ => continue,
+            }
+            blocks.push(block);
+        }
+        format!("{}\n", blocks.join("\n\n"))
+    }
+
+    /// Check the operations and fragments of a document against the schema, returning an
+    /// error for each unknown field, argument, type or fragment and each missing argument
+    /// or selection
+    pub fn validate<'a>(&self, document: &Document<'a, &'a str>) -> Vec<String> {
+        let fragments: HashMap<&str, &FragmentDefinition<'a, &'a str>> = document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some((fragment.name, fragment)),
+                _ => None,
+            })
+            .collect();
+
+        let mut errors = Vec::new();
+        for definition in &document.definitions {
+            match definition {
+                Definition::Operation(operation) => {
+                    let (kind, root, variables, selection_set) = match operation {
+                        OperationDefinition::SelectionSet(selection_set) => {
+                            ("query", &self.query_type, &[][..], selection_set)
+                        }
+                        OperationDefinition::Query(query) => (
+                            "query",
+                            &self.query_type,
+                            &query.variable_definitions[..],
+                            &query.selection_set,
+                        ),
+                        OperationDefinition::Mutation(mutation) => (
+                            "mutation",
+                            &self.mutation_type,
+                            &mutation.variable_definitions[..],
+                            &mutation.selection_set,
+                        ),
+                        OperationDefinition::Subscription(subscription) => (
+                            "subscription",
+                            &self.subscription_type,
+                            &subscription.variable_definitions[..],
+                            &subscription.selection_set,
+                        ),
+                    };
+                    for variable in variables {
+                        let type_name = base_type(&variable.var_type);
+                        match self.types.get(type_name).map(|t| t.kind.as_str()) {
+                            Some("SCALAR" | "ENUM" | "INPUT_OBJECT") => {}
+                            Some(_) => errors.push(format!(
+                                "{}: Variable '${}' cannot be of output type '{}'",
+                                variable.position, variable.name, type_name
+                            )),
+                            None => errors.push(format!(
+                                "{}: Unknown type '{}' of variable '${}'",
+                                variable.position, type_name, variable.name
+                            )),
+                        }
+                    }
+                    match root {
+                        Some(root) => {
+                            self.check_selection(root, selection_set, &fragments, &mut errors)
+                        }
+                        None => errors.push(format!(
+                            "{}: The schema does not support {} operations",
+                            selection_set.span.0, kind
+                        )),
+                    }
+                }
+                Definition::Fragment(fragment) => {
+                    let TypeCondition::On(type_name) = fragment.type_condition;
+                    if self.types.contains_key(type_name) {
+                        self.check_selection(
+                            type_name,
+                            &fragment.selection_set,
+                            &fragments,
+                            &mut errors,
+                        );
+                    } else {
+                        errors.push(format!(
+                            "{}: Unknown type '{}' of fragment '{}'",
+                            fragment.position, type_name, fragment.name
+                        ));
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    fn check_selection<'a>(
+        &self,
+        type_name: &str,
+        selection_set: &SelectionSet<'a, &'a str>,
+        fragments: &HashMap<&str, &FragmentDefinition<'a, &'a str>>,
+        errors: &mut Vec<String>,
+    ) {
+        let Some(parent) = self.types.get(type_name) else {
+            return;
+        };
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    let is_root_query = self.query_type.as_deref() == Some(type_name);
+                    if field.name == "__typename"
+                        || (is_root_query && matches!(field.name, "__schema" | "__type"))
+                    {
+                        continue;
+                    }
+                    let Some(definition) = parent
+                        .fields
+                        .iter()
+                        .flatten()
+                        .find(|definition| definition.name == field.name)
+                    else {
+                        errors.push(format!(
+                            "{}: Cannot query field '{}' on type '{}'",
+                            field.position, field.name, type_name
+                        ));
+                        continue;
+                    };
+
+                    for (argument, _) in &field.arguments {
+                        if !definition.args.iter().any(|arg| arg.name == *argument) {
+                            errors.push(format!(
+                                "{}: Unknown argument '{}' on field '{}.{}'",
+                                field.position, argument, type_name, field.name
+                            ));
+                        }
+                    }
+                    for arg in &definition.args {
+                        let required =
+                            arg.type_ref.kind == "NON_NULL" && arg.default_value.is_none();
+                        if required && !field.arguments.iter().any(|(name, _)| *name == arg.name) {
+                            errors.push(format!(
+                                "{}: Field '{}.{}' requires argument '{}' of type '{}'",
+                                field.position,
+                                type_name,
+                                field.name,
+                                arg.name,
+                                arg.type_ref.sdl()
+                            ));
+                        }
+                    }
+
+                    let field_type = definition.type_ref.named();
+                    let has_selection = !field.selection_set.items.is_empty();
+                    match self.types.get(field_type).map(|t| t.kind.as_str()) {
+                        Some("OBJECT" | "INTERFACE" | "UNION") if has_selection => self
+                            .check_selection(field_type, &field.selection_set, fragments, errors),
+                        Some("OBJECT" | "INTERFACE" | "UNION") => errors.push(format!(
+                            "{}: Field '{}' of type '{}' must have a selection of subfields",
+                            field.position,
+                            field.name,
+                            definition.type_ref.sdl()
+                        )),
+                        _ if has_selection => errors.push(format!(
+                            "{}: Field '{}' of type '{}' cannot have a selection of subfields",
+                            field.position,
+                            field.name,
+                            definition.type_ref.sdl()
+                        )),
+                        _ => {}
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    // Fragments are checked once, where they are defined
+                    if !fragments.contains_key(spread.fragment_name) {
+                        errors.push(format!(
+                            "{}: Unknown fragment '{}'",
+                            spread.position, spread.fragment_name
+                        ));
+                    }
+                }
+                Selection::InlineFragment(inline) => {
+                    let target = match &inline.type_condition {
+                        Some(TypeCondition::On(name)) => *name,
+                        None => type_name,
+                    };
+                    if self.types.contains_key(target) {
+                        self.check_selection(target, &inline.selection_set, fragments, errors);
+                    } else {
+                        errors.push(format!(
+                            "{}: Unknown type '{}' of inline fragment",
+                            inline.position, target
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn base_type<'a>(type_: &Type<'a, &'a str>) -> &'a str {
+    match type_ {
+        Type::NamedType(name) => name,
+        Type::ListType(inner) | Type::NonNullType(inner) => base_type(inner),
+    }
+}
+
+fn input_value(value: &InputValue) -> String {
+    match &value.default_value {
+        Some(default) => format!("{}: {} = {}", value.name, value.type_ref.sdl(), default),
+        None => format!("{}: {}", value.name, value.type_ref.sdl()),
+    }
+}
+
+/// A block string description line, indented like the definition it describes
+fn description(text: &Option<String>, indent: &str) -> String {
+    match text
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+    {
+        Some(text) => {
+            let text = text
+                .replace("\"\"\"", "\\\"\"\"")
+                .replace('\n', &format!("\n{}", indent));
+            // A quote right before the closing delimiter would end the string early
+            let padding = if text.ends_with('"') { " " } else { "" };
+            format!("{}\"\"\"{}{}\"\"\"\n", indent, text, padding)
+        }
+        None => String::new(),
+    }
+}
+
+fn deprecated(is_deprecated: bool, reason: &Option<String>) -> String {
+    match (is_deprecated, reason) {
+        (false, _) => String::new(),
+        (true, Some(reason)) => format!(
+            " @deprecated(reason: {})",
+            serde_json::to_string(reason).unwrap_or_default()
+        ),
+        (true, None) => " @deprecated".to_string(),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn named(kind: &str, name: &str) -> Value {
+        json!({"kind": kind, "name": name, "ofType": null})
+    }
+
+    fn non_null(inner: Value) -> Value {
+        json!({"kind": "NON_NULL", "name": null, "ofType": inner})
+    }
+
+    fn list(inner: Value) -> Value {
+        json!({"kind": "LIST", "name": null, "ofType": inner})
+    }
+
+    fn field(name: &str, type_ref: Value, args: Vec<Value>) -> Value {
+        json!({
+            "name": name,
+            "description": null,
+            "args": args,
+            "type": type_ref,
+            "isDeprecated": false,
+            "deprecationReason": null
+        })
+    }
+
+    fn arg(name: &str, type_ref: Value, default: Option<&str>) -> Value {
+        json!({"name": name, "description": null, "type": type_ref, "defaultValue": default})
+    }
+
+    /// The introspection `data` of a small schema of books and authors
+    pub fn library_introspection() -> Value {
+        let id = || non_null(named("SCALAR", "ID"));
+        let string = || named("SCALAR", "String");
+        let mut book_fields = vec![
+            field("id", id(), vec![]),
+            field("title", non_null(string()), vec![]),
+            field("author", named("OBJECT", "Author"), vec![]),
+        ];
+        book_fields[1]["description"] = json!("The title as printed on the cover");
+        let mut isbn = field("isbn", string(), vec![]);
+        isbn["isDeprecated"] = json!(true);
+        isbn["deprecationReason"] = json!("Use identifiers");
+        book_fields.push(isbn);
+
+        json!({
+            "__schema": {
+                "queryType": {"name": "Query"},
+                "mutationType": {"name": "Mutation"},
+                "subscriptionType": null,
+                "types": [
+                    {
+                        "kind": "OBJECT", "name": "Query", "description": null,
+                        "fields": [
+                            field("book", named("OBJECT", "Book"), vec![arg("id", id(), None)]),
+                            field("books", non_null(list(non_null(named("OBJECT", "Book")))), vec![
+                                arg("first", named("SCALAR", "Int"), Some("10")),
+                                arg("genre", named("ENUM", "Genre"), None)
+                            ]),
+                            field("search", list(named("UNION", "SearchResult")), vec![arg("text", non_null(string()), None)])
+                        ],
+                        "inputFields": null, "interfaces": [], "enumValues": null, "possibleTypes": null
+                    },
+                    {
+                        "kind": "OBJECT", "name": "Mutation", "description": null,
+                        "fields": [field("addBook", named("OBJECT", "Book"), vec![arg("input", non_null(named("INPUT_OBJECT", "BookInput")), None)])],
+                        "inputFields": null, "interfaces": [], "enumValues": null, "possibleTypes": null
+                    },
+                    {
+                        "kind": "OBJECT", "name": "Book", "description": "A published book",
+                        "fields": book_fields,
+                        "inputFields": null, "interfaces": [named("INTERFACE", "Node")], "enumValues": null, "possibleTypes": null
+                    },
+                    {
+                        "kind": "OBJECT", "name": "Author", "description": null,
+                        "fields": [field("id", id(), vec![]), field("name", string(), vec![])],
+                        "inputFields": null, "interfaces": [named("INTERFACE", "Node")], "enumValues": null, "possibleTypes": null
+                    },
+                    {
+                        "kind": "INTERFACE", "name": "Node", "description": null,
+                        "fields": [field("id", id(), vec![])],
+                        "inputFields": null, "interfaces": [], "enumValues": null,
+                        "possibleTypes": [named("OBJECT", "Author"), named("OBJECT", "Book")]
+                    },
+                    {
+                        "kind": "UNION", "name": "SearchResult", "description": null,
+                        "fields": null, "inputFields": null, "interfaces": null, "enumValues": null,
+                        "possibleTypes": [named("OBJECT", "Book"), named("OBJECT", "Author")]
+                    },
+                    {
+                        "kind": "ENUM", "name": "Genre", "description": null,
+                        "fields": null, "inputFields": null, "interfaces": null, "possibleTypes": null,
+                        "enumValues": [
+                            {"name": "FICTION", "description": null, "isDeprecated": false, "deprecationReason": null},
+                            {"name": "POETRY", "description": null, "isDeprecated": false, "deprecationReason": null}
+                        ]
+                    },
+                    {
+                        "kind": "INPUT_OBJECT", "name": "BookInput", "description": null,
+                        "fields": null, "interfaces": null, "enumValues": null, "possibleTypes": null,
+                        "inputFields": [arg("title", non_null(string()), None), arg("genre", named("ENUM", "Genre"), Some("FICTION"))]
+                    },
+                    {
+                        "kind": "SCALAR", "name": "String", "description": "Built in",
+                        "fields": null, "inputFields": null, "interfaces": null, "enumValues": null, "possibleTypes": null
+                    },
+                    {
+                        "kind": "SCALAR", "name": "DateTime", "description": null,
+                        "fields": null, "inputFields": null, "interfaces": null, "enumValues": null, "possibleTypes": null
+                    },
+                    {
+                        "kind": "OBJECT", "name": "__Type", "description": null,
+                        "fields": [field("name", string(), vec![])],
+                        "inputFields": null, "interfaces": [], "enumValues": null, "possibleTypes": null
+                    }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_to_sdl() {
+        let schema = Schema::from_introspection(&library_introspection()).unwrap();
+        let sdl = schema.to_sdl();
+        assert_eq!(
+            sdl,
+            indoc::indoc! {r#"
+                type Author implements Node {
+                  id: ID!
+                  name: String
+                }
+
+                """A published book"""
+                type Book implements Node {
+                  id: ID!
+                  """The title as printed on the cover"""
+                  title: String!
+                  author: Author
+                  isbn: String @deprecated(reason: "Use identifiers")
+                }
+
+                input BookInput {
+                  title: String!
+                  genre: Genre = FICTION
+                }
+
+                scalar DateTime
+
+                enum Genre {
+                  FICTION
+                  POETRY
+                }
+
+                type Mutation {
+                  addBook(input: BookInput!): Book
+                }
+
+                interface Node {
+                  id: ID!
+                }
+
+                type Query {
+                  book(id: ID!): Book
+                  books(first: Int = 10, genre: Genre): [Book!]!
+                  search(text: String!): [SearchResult]
+                }
+
+                union SearchResult = Book | Author
+            "#}
+        );
+        // The result is valid SDL
+        graphql_parser::parse_schema::<String>(&sdl).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = Schema::from_introspection(&library_introspection()).unwrap();
+        let errors = |query: &str| {
+            let document = graphql_parser::parse_query::<&str>(query).unwrap();
+            schema.validate(&document)
+        };
+
+        assert!(errors(indoc::indoc! {r#"
+            query Books($genre: Genre) {
+              books(genre: $genre) { ...BookParts author { name } }
+              search(text: "dune") { __typename ... on Book { title } ... on Author { name } }
+              __schema { types { name } }
+            }
+            fragment BookParts on Book { id title }
+            mutation { addBook(input: {title: "Dune"}) { id } }
+        "#})
+        .is_empty());
+
+        assert_eq!(
+            errors(indoc::indoc! {r#"
+                query($id: Book) {
+                  book { titel author }
+                  books(limit: 5) { id { value } ...Missing }
+                  search(text: "x") { title }
+                }
+                fragment Parts on Magazine { id }
+            "#}),
+            vec![
+                "1:7: Variable '$id' cannot be of output type 'Book'",
+                "2:3: Field 'Query.book' requires argument 'id' of type 'ID!'",
+                "2:10: Cannot query field 'titel' on type 'Book'",
+                "2:16: Field 'author' of type 'Author' must have a selection of subfields",
+                "3:3: Unknown argument 'limit' on field 'Query.books'",
+                "3:21: Field 'id' of type 'ID!' cannot have a selection of subfields",
+                "3:37: Unknown fragment 'Missing'",
+                "4:23: Cannot query field 'title' on type 'SearchResult'",
+                "6:1: Unknown type 'Magazine' of fragment 'Parts'",
+            ]
+        );
+
+        let schema = Schema::from_introspection(&json!({
+            "__schema": {"queryType": {"name": "Query"}, "types": [
+                {"kind": "OBJECT", "name": "Query", "description": null, "fields": [field("ok", named("SCALAR", "Boolean"), vec![])]}
+            ]}
+        }))
+        .unwrap();
+        let document = graphql_parser::parse_query::<&str>("mutation { ok }").unwrap();
+        assert_eq!(
+            schema.validate(&document),
+            vec!["1:10: The schema does not support mutation operations"]
+        );
+    }
+}
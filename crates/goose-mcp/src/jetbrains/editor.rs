@@ -0,0 +1,392 @@
+use std::path::Path;
+
+use indoc::indoc;
+use mcp_core::{
+    content::Content,
+    handler::ToolError,
+    tool::{Tool, ToolAnnotations},
+};
+use serde_json::{json, Value};
+
+use super::proxy::JetBrainsProxy;
+
+/// Tools of the IDE plugin the editor tools are built on
+const OPEN_FILE_PATH: &str = "get_open_in_editor_file_path";
+const CARET_POSITION: &str = "get_caret_position";
+const SELECTED_TEXT: &str = "get_selected_in_editor_text";
+const OPEN_FILE_PATHS: &str = "get_all_open_file_paths";
+const JUMP_TO_LINE: &str = "jump_to_line";
+const REPLACE_SELECTED_TEXT: &str = "replace_selected_text";
+
+/// The tools for the state of the editor, provided alongside those of the IDE plugin
+pub fn editor_tools() -> Vec<Tool> {
+    vec![
+        Tool::new(
+            "get_editor_context",
+            indoc! {r#"
+                Get what the developer is looking at in the IDE: the file open in the active
+                editor, the cursor position as 1-based line and column, the selected text, the
+                language of the file, and the paths of all open tabs.
+            "#},
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+            Some(ToolAnnotations {
+                title: Some("Get the editor context".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        ),
+        Tool::new(
+            "jump_to_line",
+            indoc! {r#"
+                Open a file in the IDE and move the cursor to the start of a line.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["file_path", "line"],
+                "properties": {
+                    "file_path": {
+                        "type": "string",
+                        "description": "The path of the file to open"
+                    },
+                    "line": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The 1-based line to move the cursor to"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Jump to a line".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        ),
+        Tool::new(
+            "replace_selection",
+            indoc! {r#"
+                Replace the text selected in the active editor of the IDE. Use get_editor_context
+                first to check what is selected.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["new_text"],
+                "properties": {
+                    "new_text": {
+                        "type": "string",
+                        "description": "The text to replace the selection with"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Replace the selection".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        ),
+    ]
+}
+
+/// Run the editor tool `tool_name`, or return `None` when it is a tool of the IDE plugin
+pub async fn call_editor_tool(
+    proxy: &JetBrainsProxy,
+    tool_name: &str,
+    arguments: &Value,
+) -> Option<Result<Vec<Content>, ToolError>> {
+    let result = match tool_name {
+        "get_editor_context" => get_editor_context(proxy).await,
+        "jump_to_line" => jump_to_line(proxy, arguments).await,
+        "replace_selection" => replace_selection(proxy, arguments).await,
+        _ => return None,
+    };
+    Some(result)
+}
+
+async fn get_editor_context(proxy: &JetBrainsProxy) -> Result<Vec<Content>, ToolError> {
+    let file_path = call(proxy, OPEN_FILE_PATH, json!({})).await?;
+    let open_files: Vec<String> = call(proxy, OPEN_FILE_PATHS, json!({}))
+        .await?
+        .lines()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(String::from)
+        .collect();
+
+    // Without an active editor there is no cursor or selection
+    let context = if file_path.trim().is_empty() {
+        json!({
+            "file_path": null,
+            "line": null,
+            "column": null,
+            "selected_text": null,
+            "language": null,
+            "open_files": open_files,
+        })
+    } else {
+        let caret: Value = serde_json::from_str(&call(proxy, CARET_POSITION, json!({})).await?)
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Invalid cursor position from the IDE: {}", e))
+            })?;
+        let selected_text = call(proxy, SELECTED_TEXT, json!({})).await?;
+        json!({
+            "file_path": file_path.trim(),
+            "line": caret.get("line"),
+            "column": caret.get("column"),
+            "selected_text": (!selected_text.is_empty()).then_some(selected_text),
+            "language": language(Path::new(file_path.trim())),
+            "open_files": open_files,
+        })
+    };
+    Ok(vec![Content::text(context.to_string())])
+}
+
+async fn jump_to_line(
+    proxy: &JetBrainsProxy,
+    arguments: &Value,
+) -> Result<Vec<Content>, ToolError> {
+    let file_path = arguments
+        .get("file_path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'file_path' parameter".into()))?;
+    let line = arguments
+        .get("line")
+        .and_then(|v| v.as_u64())
+        .and_then(|line| u32::try_from(line).ok())
+        .filter(|line| *line > 0)
+        .ok_or_else(|| {
+            ToolError::InvalidParameters("'line' must be a line number, starting from 1".into())
+        })?;
+
+    call(
+        proxy,
+        JUMP_TO_LINE,
+        json!({"filePath": file_path, "line": line}),
+    )
+    .await?;
+    Ok(vec![Content::text(format!(
+        "Moved the cursor to line {} of {}",
+        line, file_path
+    ))])
+}
+
+async fn replace_selection(
+    proxy: &JetBrainsProxy,
+    arguments: &Value,
+) -> Result<Vec<Content>, ToolError> {
+    let new_text = arguments
+        .get("new_text")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'new_text' parameter".into()))?;
+
+    call(proxy, REPLACE_SELECTED_TEXT, json!({"text": new_text})).await?;
+    Ok(vec![Content::text("Replaced the selection")])
+}
+
+async fn call(proxy: &JetBrainsProxy, name: &str, args: Value) -> Result<String, ToolError> {
+    proxy
+        .call_status(name, args)
+        .await
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))
+}
+
+/// The language of a file, from its extension
+fn language(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "rs" => "Rust",
+        "kt" | "kts" => "Kotlin",
+        "java" => "Java",
+        "py" => "Python",
+        "js" | "jsx" | "mjs" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "go" => "Go",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "cs" => "C#",
+        "swift" => "Swift",
+        "scala" => "Scala",
+        "sql" => "SQL",
+        "sh" | "bash" | "zsh" => "Shell",
+        "html" | "htm" => "HTML",
+        "css" | "scss" => "CSS",
+        "xml" => "XML",
+        "json" => "JSON",
+        "yaml" | "yml" => "YAML",
+        "toml" => "TOML",
+        "md" => "Markdown",
+        _ => "Plain text",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Answer the plugin tool `name`, called with `args`, with `response`
+    async fn mock_tool(server: &MockServer, name: &str, args: Value, response: Value) {
+        Mock::given(method("POST"))
+            .and(path(format!("/api/mcp/{}", name)))
+            .and(body_json(args))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    fn proxy(server: &MockServer) -> JetBrainsProxy {
+        JetBrainsProxy::with_endpoint(format!("{}/api", server.uri()))
+    }
+
+    #[tokio::test]
+    async fn test_get_editor_context() {
+        let server = MockServer::start().await;
+        mock_tool(
+            &server,
+            OPEN_FILE_PATH,
+            json!({}),
+            json!({"status": "/project/src/main.rs"}),
+        )
+        .await;
+        mock_tool(
+            &server,
+            CARET_POSITION,
+            json!({}),
+            json!({"status": "{\"line\": 12, \"column\": 5}"}),
+        )
+        .await;
+        mock_tool(
+            &server,
+            SELECTED_TEXT,
+            json!({}),
+            json!({"status": "fn main()"}),
+        )
+        .await;
+        mock_tool(
+            &server,
+            OPEN_FILE_PATHS,
+            json!({}),
+            json!({"status": "/project/src/main.rs\n/project/Cargo.toml\n"}),
+        )
+        .await;
+
+        let result = call_editor_tool(&proxy(&server), "get_editor_context", &json!({}))
+            .await
+            .unwrap()
+            .unwrap();
+        let context: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(
+            context,
+            json!({
+                "file_path": "/project/src/main.rs",
+                "line": 12,
+                "column": 5,
+                "selected_text": "fn main()",
+                "language": "Rust",
+                "open_files": ["/project/src/main.rs", "/project/Cargo.toml"],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_editor_context_without_open_file() {
+        let server = MockServer::start().await;
+        mock_tool(&server, OPEN_FILE_PATH, json!({}), json!({"status": ""})).await;
+        mock_tool(&server, OPEN_FILE_PATHS, json!({}), json!({"status": ""})).await;
+
+        let result = call_editor_tool(&proxy(&server), "get_editor_context", &json!({}))
+            .await
+            .unwrap()
+            .unwrap();
+        let context: Value = serde_json::from_str(result[0].as_text().unwrap()).unwrap();
+        assert_eq!(context["file_path"], Value::Null);
+        assert_eq!(context["open_files"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_jump_to_line() {
+        let server = MockServer::start().await;
+        mock_tool(
+            &server,
+            JUMP_TO_LINE,
+            json!({"filePath": "/project/src/lib.rs", "line": 42}),
+            json!({"status": "ok"}),
+        )
+        .await;
+
+        let proxy = proxy(&server);
+        let result = call_editor_tool(
+            &proxy,
+            "jump_to_line",
+            &json!({"file_path": "/project/src/lib.rs", "line": 42}),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap(),
+            "Moved the cursor to line 42 of /project/src/lib.rs"
+        );
+
+        let result = call_editor_tool(
+            &proxy,
+            "jump_to_line",
+            &json!({"file_path": "/project/src/lib.rs", "line": 0}),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_replace_selection() {
+        let server = MockServer::start().await;
+        mock_tool(
+            &server,
+            REPLACE_SELECTED_TEXT,
+            json!({"text": "let x = 1;"}),
+            json!({"error": "no text selected"}),
+        )
+        .await;
+
+        let result = call_editor_tool(
+            &proxy(&server),
+            "replace_selection",
+            &json!({"new_text": "let x = 1;"}),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            result,
+            Err(ToolError::ExecutionError(message))
+                if message == "The IDE failed to run replace_selected_text: no text selected"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_tools_are_not_handled() {
+        let server = MockServer::start().await;
+        assert!(
+            call_editor_tool(&proxy(&server), "get_project_vcs_status", &json!({}))
+                .await
+                .is_none()
+        );
+        assert_eq!(language(Path::new("Main.KT")), "Kotlin");
+        assert_eq!(language(Path::new("README")), "Plain text");
+    }
+}
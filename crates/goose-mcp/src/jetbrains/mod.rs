@@ -1,6 +1,8 @@
+mod editor;
 mod proxy;
 
 use anyhow::Result;
+use indoc::indoc;
 use mcp_core::{
     content::Content,
     handler::{PromptError, ResourceError, ToolError},
@@ -38,7 +40,13 @@ impl JetBrainsRouter {
     pub fn new() -> Self {
         let tools = Arc::new(Mutex::new(Vec::new()));
         let proxy = Arc::new(JetBrainsProxy::new());
-        let instructions = "JetBrains IDE integration".to_string();
+        let instructions = indoc! {r#"
+            JetBrains IDE integration.
+            Use get_editor_context to see the file, cursor position and selection the developer
+            is looking at, jump_to_line to show them a line, and replace_selection to edit the
+            text they selected.
+        "#}
+        .to_string();
 
         // Initialize the proxy
         let proxy_clone = Arc::clone(&proxy);
@@ -132,7 +140,7 @@ impl Router for JetBrainsRouter {
 
     fn list_tools(&self) -> Vec<Tool> {
         // Use block_in_place to avoid blocking the runtime
-        tokio::task::block_in_place(|| {
+        let plugin_tools = tokio::task::block_in_place(|| {
             let rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()
@@ -151,7 +159,19 @@ impl Router for JetBrainsRouter {
                     tools.clone()
                 }
             })
-        })
+        });
+
+        let mut tools = editor::editor_tools();
+        let plugin_tools: Vec<Tool> = plugin_tools
+            .into_iter()
+            .filter(|tool| {
+                !tools
+                    .iter()
+                    .any(|editor_tool| editor_tool.name == tool.name)
+            })
+            .collect();
+        tools.extend(plugin_tools);
+        tools
     }
 
     fn call_tool(
@@ -162,6 +182,11 @@ impl Router for JetBrainsRouter {
         let this = self.clone();
         let tool_name = tool_name.to_string();
         Box::pin(async move {
+            if let Some(result) =
+                editor::call_editor_tool(&this.proxy, &tool_name, &arguments).await
+            {
+                return result;
+            }
             this.ensure_tools().await?;
             this.call_proxy_tool(tool_name, arguments).await
         })
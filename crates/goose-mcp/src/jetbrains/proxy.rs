@@ -47,6 +47,16 @@ impl JetBrainsProxy {
         }
     }
 
+    /// A proxy for the IDE at `endpoint`, without searching for it
+    #[cfg(test)]
+    pub fn with_endpoint(endpoint: String) -> Self {
+        Self {
+            cached_endpoint: Arc::new(RwLock::new(Some(endpoint))),
+            previous_response: Arc::new(RwLock::new(None)),
+            client: Client::new(),
+        }
+    }
+
     async fn test_list_tools(&self, endpoint: &str) -> Result<bool> {
         debug!("Sending test request to {}/mcp/list_tools", endpoint);
 
@@ -302,6 +312,22 @@ impl JetBrainsProxy {
         })
     }
 
+    /// Call a tool of the IDE plugin, returning its status text, or an error when the
+    /// plugin reports one
+    pub async fn call_status(&self, name: &str, args: Value) -> Result<String> {
+        let result = self.call_tool(name, args).await?;
+        let text = result
+            .content
+            .first()
+            .and_then(|content| content.as_text())
+            .unwrap_or_default()
+            .to_string();
+        if result.is_error {
+            return Err(anyhow!("The IDE failed to run {}: {}", name, text));
+        }
+        Ok(text)
+    }
+
     async fn send_tools_changed(&self) {
         debug!("Sending tools changed notification");
         // TODO: Implement notification mechanism when needed
@@ -0,0 +1,969 @@
+use indoc::indoc;
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use std::{future::Future, pin::Pin};
+
+use crate::util::{json_content, required_str, send_json};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+const ISSUE_FIELDS: &str = "summary,status,assignee,priority";
+const DEFAULT_MAX_RESULTS: u64 = 50;
+
+/// The Jira site and the credentials to access it with
+#[derive(Clone, Debug)]
+pub struct JiraConfig {
+    pub url: String,
+    pub user: String,
+    pub api_token: String,
+}
+
+impl JiraConfig {
+    /// Read the configuration from JIRA_URL, JIRA_USER and JIRA_API_TOKEN
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            url: std::env::var("JIRA_URL").ok()?,
+            user: std::env::var("JIRA_USER").ok()?,
+            api_token: std::env::var("JIRA_API_TOKEN").ok()?,
+        })
+    }
+}
+
+/// Router for the Jira Cloud REST API
+#[derive(Clone)]
+pub struct JiraRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    client: Client,
+    config: Option<JiraConfig>,
+}
+
+impl Default for JiraRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JiraRouter {
+    pub fn new() -> Self {
+        Self::with_config(JiraConfig::from_env())
+    }
+
+    pub fn with_config(config: Option<JiraConfig>) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let writing = |title: &str, destructive: bool| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: false,
+                destructive_hint: destructive,
+                idempotent_hint: false,
+                open_world_hint: true,
+            })
+        };
+
+        let search_issues = Tool::new(
+            "search_issues",
+            indoc! {r#"
+                Search for Jira issues with a JQL query, e.g.
+                `project = APP AND status = "In Progress" ORDER BY priority DESC`.
+                Returns the key, summary, status, assignee and priority of each issue.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["jql"],
+                "properties": {
+                    "jql": {"type": "string", "description": "The JQL query"},
+                    "max_results": {
+                        "type": "integer",
+                        "default": DEFAULT_MAX_RESULTS,
+                        "description": "The maximum number of issues"
+                    }
+                }
+            }),
+            read_only("Search Jira issues"),
+        );
+
+        let get_issue = Tool::new(
+            "get_issue",
+            "Get a Jira issue with its description, comments and the list of its attachments.",
+            json!({
+                "type": "object",
+                "required": ["issue_key"],
+                "properties": {
+                    "issue_key": {"type": "string", "description": "The issue key, e.g. APP-123"}
+                }
+            }),
+            read_only("Get a Jira issue"),
+        );
+
+        let create_issue = Tool::new(
+            "create_issue",
+            "Create a Jira issue. Returns the key and url of the new issue.",
+            json!({
+                "type": "object",
+                "required": ["project_key", "summary"],
+                "properties": {
+                    "project_key": {"type": "string"},
+                    "summary": {"type": "string"},
+                    "description": {"type": "string"},
+                    "issue_type": {"type": "string", "default": "Task"},
+                    "assignee": {"type": "string", "description": "The account id of the assignee"}
+                }
+            }),
+            writing("Create a Jira issue", false),
+        );
+
+        let update_issue = Tool::new(
+            "update_issue",
+            indoc! {r#"
+                Update the summary or description of a Jira issue, or move it to another status.
+                The status must be reachable with one of the issue's workflow transitions.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["issue_key"],
+                "properties": {
+                    "issue_key": {"type": "string"},
+                    "summary": {"type": "string"},
+                    "description": {"type": "string"},
+                    "status": {"type": "string", "description": "The status to move the issue to"}
+                }
+            }),
+            writing("Update a Jira issue", true),
+        );
+
+        let add_comment = Tool::new(
+            "add_comment",
+            "Add a comment to a Jira issue.",
+            json!({
+                "type": "object",
+                "required": ["issue_key", "body"],
+                "properties": {
+                    "issue_key": {"type": "string"},
+                    "body": {"type": "string"}
+                }
+            }),
+            writing("Comment on a Jira issue", false),
+        );
+
+        let list_projects = Tool::new(
+            "list_projects",
+            "List the Jira projects the user can browse, with their key and name.",
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+            read_only("List Jira projects"),
+        );
+
+        let get_sprint = Tool::new(
+            "get_sprint",
+            "Get the active sprint of a Jira board and its issues.",
+            json!({
+                "type": "object",
+                "required": ["board_id"],
+                "properties": {
+                    "board_id": {"type": "integer", "description": "The id of the scrum board"}
+                }
+            }),
+            read_only("Get the active Jira sprint"),
+        );
+
+        let instructions = indoc! {r#"
+            The Jira extension searches, reads and edits the issues of the Jira site in JIRA_URL,
+            as the user in JIRA_USER.
+
+            Find issues with JQL in search_issues. Issue descriptions and comments are plain text.
+        "#}
+        .to_string();
+
+        Self {
+            tools: vec![
+                search_issues,
+                get_issue,
+                create_issue,
+                update_issue,
+                add_comment,
+                list_projects,
+                get_sprint,
+            ],
+            instructions,
+            client: Client::new(),
+            config,
+        }
+    }
+
+    fn config(&self) -> Result<&JiraConfig, ToolError> {
+        self.config.as_ref().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "Jira is not configured. Set JIRA_URL, JIRA_USER and JIRA_API_TOKEN, using an API token from https://id.atlassian.com/manage-profile/security/api-tokens".into(),
+            )
+        })
+    }
+
+    // Helper method to send a request to the Jira API and parse the response
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<Value>,
+    ) -> Result<Value, ToolError> {
+        let config = self.config()?;
+        let mut request = self
+            .client
+            .request(
+                method,
+                format!("{}{}", config.url.trim_end_matches('/'), path),
+            )
+            .basic_auth(&config.user, Some(&config.api_token))
+            .header("Accept", "application/json")
+            .query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let (status, body) = send_json(request, "Jira").await?;
+        if status.is_success() {
+            return Ok(body);
+        }
+        let message = error_message(&body).unwrap_or_else(|| status.to_string());
+        Err(match status {
+            // Bad requests are how Jira reports JQL syntax errors and invalid fields
+            StatusCode::BAD_REQUEST => ToolError::InvalidParameters(message),
+            _ => ToolError::ExecutionError(format!("Jira API error ({}): {}", status, message)),
+        })
+    }
+
+    async fn search_issues(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let jql = required_str(&params, "jql")?;
+        let max_results = params["max_results"]
+            .as_u64()
+            .unwrap_or(DEFAULT_MAX_RESULTS);
+
+        let response = self
+            .request(
+                Method::GET,
+                "/rest/api/3/search/jql",
+                &search_query(jql, max_results),
+                None,
+            )
+            .await?;
+        let issues = issue_summaries(&response["issues"]);
+        // The enhanced search API pages with tokens and does not count the matching issues
+        Ok(vec![json_content(&json!({
+            "issues": issues,
+            "has_more": !response["isLast"].as_bool().unwrap_or(true)
+        }))])
+    }
+
+    async fn get_issue(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let key = issue_key(&params)?;
+        let issue = self
+            .request(
+                Method::GET,
+                &format!("/rest/api/3/issue/{}", key),
+                &[(
+                    "fields",
+                    "summary,status,assignee,reporter,priority,issuetype,created,updated,description,comment,attachment"
+                        .to_string(),
+                )],
+                None,
+            )
+            .await?;
+
+        let fields = &issue["fields"];
+        let comments: Vec<Value> = fields["comment"]["comments"]
+            .as_array()
+            .map(|comments| {
+                comments
+                    .iter()
+                    .map(|comment| {
+                        json!({
+                            "author": comment["author"]["displayName"],
+                            "created": comment["created"],
+                            "body": adf_to_text(&comment["body"])
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let attachments: Vec<Value> = fields["attachment"]
+            .as_array()
+            .map(|attachments| {
+                attachments
+                    .iter()
+                    .map(|attachment| {
+                        json!({
+                            "filename": attachment["filename"],
+                            "mime_type": attachment["mimeType"],
+                            "size": attachment["size"],
+                            "url": attachment["content"]
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(vec![json_content(&json!({
+            "key": issue["key"],
+            "summary": fields["summary"],
+            "type": fields["issuetype"]["name"],
+            "status": fields["status"]["name"],
+            "priority": fields["priority"]["name"],
+            "assignee": fields["assignee"]["displayName"],
+            "reporter": fields["reporter"]["displayName"],
+            "created": fields["created"],
+            "updated": fields["updated"],
+            "description": adf_to_text(&fields["description"]),
+            "comments": comments,
+            "attachments": attachments
+        }))])
+    }
+
+    async fn create_issue(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let response = self
+            .request(
+                Method::POST,
+                "/rest/api/3/issue",
+                &[],
+                Some(create_issue_payload(&params)?),
+            )
+            .await?;
+        let key = response["key"].as_str().unwrap_or_default();
+        Ok(vec![json_content(&json!({
+            "key": key,
+            "url": format!("{}/browse/{}", self.config()?.url.trim_end_matches('/'), key)
+        }))])
+    }
+
+    async fn update_issue(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let key = issue_key(&params)?;
+        let mut changes = Vec::new();
+
+        let mut fields = serde_json::Map::new();
+        if let Some(summary) = params["summary"].as_str() {
+            fields.insert("summary".to_string(), json!(summary));
+        }
+        if let Some(description) = params["description"].as_str() {
+            fields.insert("description".to_string(), text_to_adf(description));
+        }
+        if !fields.is_empty() {
+            changes.extend(fields.keys().cloned());
+            self.request(
+                Method::PUT,
+                &format!("/rest/api/3/issue/{}", key),
+                &[],
+                Some(json!({"fields": fields})),
+            )
+            .await?;
+        }
+
+        if let Some(status) = params["status"].as_str() {
+            let path = format!("/rest/api/3/issue/{}/transitions", key);
+            let response = self.request(Method::GET, &path, &[], None).await?;
+            let transitions = response["transitions"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            let transition = transitions
+                .iter()
+                .find(|transition| {
+                    transition["to"]["name"]
+                        .as_str()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(status))
+                })
+                .ok_or_else(|| {
+                    let available: Vec<&str> = transitions
+                        .iter()
+                        .filter_map(|transition| transition["to"]["name"].as_str())
+                        .collect();
+                    ToolError::InvalidParameters(format!(
+                        "{} cannot be moved to '{}'. Available statuses: {}",
+                        key,
+                        status,
+                        available.join(", ")
+                    ))
+                })?;
+            self.request(
+                Method::POST,
+                &path,
+                &[],
+                Some(json!({"transition": {"id": transition["id"]}})),
+            )
+            .await?;
+            changes.push("status".to_string());
+        }
+
+        if changes.is_empty() {
+            return Err(ToolError::InvalidParameters(
+                "Provide at least one of 'summary', 'description' and 'status'".into(),
+            ));
+        }
+        Ok(vec![Content::text(format!(
+            "Updated {} of {}",
+            changes.join(", "),
+            key
+        ))])
+    }
+
+    async fn add_comment(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let key = issue_key(&params)?;
+        let body = required_str(&params, "body")?;
+        let comment = self
+            .request(
+                Method::POST,
+                &format!("/rest/api/3/issue/{}/comment", key),
+                &[],
+                Some(json!({"body": text_to_adf(body)})),
+            )
+            .await?;
+        Ok(vec![Content::text(format!(
+            "Added comment {} to {}",
+            comment["id"].as_str().unwrap_or_default(),
+            key
+        ))])
+    }
+
+    async fn list_projects(&self) -> Result<Vec<Content>, ToolError> {
+        let mut projects = Vec::new();
+        let mut start_at = 0;
+        loop {
+            let response = self
+                .request(
+                    Method::GET,
+                    "/rest/api/3/project/search",
+                    &[("startAt", start_at.to_string())],
+                    None,
+                )
+                .await?;
+            let values = response["values"].as_array().cloned().unwrap_or_default();
+            start_at += values.len();
+            projects.extend(values.iter().map(|project| {
+                json!({
+                    "key": project["key"],
+                    "name": project["name"],
+                    "type": project["projectTypeKey"]
+                })
+            }));
+            if values.is_empty() || response["isLast"].as_bool().unwrap_or(true) {
+                break;
+            }
+        }
+        Ok(vec![json_content(&Value::Array(projects))])
+    }
+
+    async fn get_sprint(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let board_id = params["board_id"]
+            .as_u64()
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'board_id' parameter".into()))?;
+        let response = self
+            .request(
+                Method::GET,
+                &format!("/rest/agile/1.0/board/{}/sprint", board_id),
+                &[("state", "active".to_string())],
+                None,
+            )
+            .await?;
+        let sprint = response["values"]
+            .as_array()
+            .and_then(|sprints| sprints.first())
+            .ok_or_else(|| {
+                ToolError::ExecutionError(format!("Board {} has no active sprint", board_id))
+            })?;
+
+        let issues = self
+            .request(
+                Method::GET,
+                &format!("/rest/agile/1.0/sprint/{}/issue", sprint["id"]),
+                &[
+                    ("fields", ISSUE_FIELDS.to_string()),
+                    ("maxResults", "200".to_string()),
+                ],
+                None,
+            )
+            .await?;
+        Ok(vec![json_content(&json!({
+            "id": sprint["id"],
+            "name": sprint["name"],
+            "goal": sprint["goal"],
+            "start_date": sprint["startDate"],
+            "end_date": sprint["endDate"],
+            "issues": issue_summaries(&issues["issues"])
+        }))])
+    }
+}
+
+/// The query parameters of an issue search
+/// The `issue_key` parameter, which must be a key like `APP-123` as it is part of API paths
+fn issue_key(params: &Value) -> Result<&str, ToolError> {
+    let key = required_str(params, "issue_key")?;
+    let valid = key.split_once('-').is_some_and(|(project, number)| {
+        project.starts_with(|c: char| c.is_ascii_alphabetic())
+            && project
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    });
+    if !valid {
+        return Err(ToolError::InvalidParameters(format!(
+            "Invalid issue key '{}', expected a key like APP-123",
+            key
+        )));
+    }
+    Ok(key)
+}
+
+fn search_query(jql: &str, max_results: u64) -> Vec<(&'static str, String)> {
+    vec![
+        ("jql", jql.to_string()),
+        ("maxResults", max_results.to_string()),
+        ("fields", ISSUE_FIELDS.to_string()),
+    ]
+}
+
+fn create_issue_payload(params: &Value) -> Result<Value, ToolError> {
+    let mut fields = json!({
+        "project": {"key": required_str(params, "project_key")?},
+        "summary": required_str(params, "summary")?,
+        "issuetype": {"name": params["issue_type"].as_str().unwrap_or("Task")}
+    });
+    if let Some(description) = params["description"].as_str() {
+        fields["description"] = text_to_adf(description);
+    }
+    if let Some(assignee) = params["assignee"].as_str() {
+        fields["assignee"] = json!({"accountId": assignee});
+    }
+    Ok(json!({"fields": fields}))
+}
+
+fn issue_summaries(issues: &Value) -> Vec<Value> {
+    issues
+        .as_array()
+        .map(|issues| {
+            issues
+                .iter()
+                .map(|issue| {
+                    let fields = &issue["fields"];
+                    json!({
+                        "key": issue["key"],
+                        "summary": fields["summary"],
+                        "status": fields["status"]["name"],
+                        "assignee": fields["assignee"]["displayName"],
+                        "priority": fields["priority"]["name"]
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The messages of a Jira error response
+fn error_message(body: &Value) -> Option<String> {
+    let mut messages: Vec<String> = body["errorMessages"]
+        .as_array()
+        .map(|messages| {
+            messages
+                .iter()
+                .filter_map(|message| message.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(errors) = body["errors"].as_object() {
+        messages.extend(
+            errors
+                .iter()
+                .map(|(field, message)| format!("{}: {}", field, message.as_str().unwrap_or(""))),
+        );
+    }
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+/// Convert plain text to an Atlassian document, with a paragraph per block of lines
+fn text_to_adf(text: &str) -> Value {
+    let paragraphs: Vec<Value> = text
+        .split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .map(|paragraph| {
+            let mut content = Vec::new();
+            for (index, line) in paragraph.lines().enumerate() {
+                if index > 0 {
+                    content.push(json!({"type": "hardBreak"}));
+                }
+                if !line.is_empty() {
+                    content.push(json!({"type": "text", "text": line}));
+                }
+            }
+            json!({"type": "paragraph", "content": content})
+        })
+        .collect();
+    json!({"type": "doc", "version": 1, "content": paragraphs})
+}
+
+/// The text of an Atlassian document, with blocks separated by blank lines
+fn adf_to_text(node: &Value) -> String {
+    let children = || -> Vec<String> {
+        node["content"]
+            .as_array()
+            .map(|content| content.iter().map(adf_to_text).collect())
+            .unwrap_or_default()
+    };
+    match node["type"].as_str() {
+        Some("text") => node["text"].as_str().unwrap_or_default().to_string(),
+        Some("hardBreak") => "\n".to_string(),
+        Some("mention") => node["attrs"]["text"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        Some("emoji") => node["attrs"]["text"]
+            .as_str()
+            .or(node["attrs"]["shortName"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        Some("inlineCard") => node["attrs"]["url"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        Some("paragraph" | "heading") => children().concat(),
+        Some("listItem") => format!("- {}", children().join("\n")),
+        Some("codeBlock") => format!("```\n{}\n```", children().concat()),
+        Some("bulletList" | "orderedList") => children().join("\n"),
+        Some("rule") => "---".to_string(),
+        _ => children().join("\n\n"),
+    }
+}
+
+impl Router for JiraRouter {
+    fn name(&self) -> String {
+        "jira".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "search_issues" => this.search_issues(arguments).await,
+                "get_issue" => this.get_issue(arguments).await,
+                "create_issue" => this.create_issue(arguments).await,
+                "update_issue" => this.update_issue(arguments).await,
+                "add_comment" => this.add_comment(arguments).await,
+                "list_projects" => this.list_projects().await,
+                "get_sprint" => this.get_sprint(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::text;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn router_for(server: &MockServer) -> JiraRouter {
+        JiraRouter::with_config(Some(JiraConfig {
+            url: server.uri(),
+            user: "me@example.com".to_string(),
+            api_token: "token".to_string(),
+        }))
+    }
+
+    #[test]
+    fn test_search_query() {
+        assert_eq!(
+            search_query("project = APP AND status = \"To Do\"", 10),
+            vec![
+                ("jql", "project = APP AND status = \"To Do\"".to_string()),
+                ("maxResults", "10".to_string()),
+                ("fields", "summary,status,assignee,priority".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_issue_key() {
+        for key in ["APP-1", "MY_PROJ2-123", "app-42"] {
+            assert_eq!(issue_key(&json!({"issue_key": key})).unwrap(), key);
+        }
+        for key in [
+            "APP",
+            "APP-",
+            "-1",
+            "1APP-1",
+            "APP-1/comment",
+            "../APP-1",
+            "APP-1?x=1",
+        ] {
+            assert!(
+                matches!(
+                    issue_key(&json!({"issue_key": key})),
+                    Err(ToolError::InvalidParameters(_))
+                ),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_create_issue_payload() {
+        let payload = create_issue_payload(&json!({
+            "project_key": "APP",
+            "summary": "Fix login",
+            "description": "Steps:\nopen the app\n\nExpected: it works",
+            "issue_type": "Bug",
+            "assignee": "5b10ac8d82e05b22cc7d4ef5"
+        }))
+        .unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "fields": {
+                    "project": {"key": "APP"},
+                    "summary": "Fix login",
+                    "issuetype": {"name": "Bug"},
+                    "assignee": {"accountId": "5b10ac8d82e05b22cc7d4ef5"},
+                    "description": {
+                        "type": "doc",
+                        "version": 1,
+                        "content": [
+                            {"type": "paragraph", "content": [
+                                {"type": "text", "text": "Steps:"},
+                                {"type": "hardBreak"},
+                                {"type": "text", "text": "open the app"}
+                            ]},
+                            {"type": "paragraph", "content": [
+                                {"type": "text", "text": "Expected: it works"}
+                            ]}
+                        ]
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            adf_to_text(&payload["fields"]["description"]),
+            "Steps:\nopen the app\n\nExpected: it works"
+        );
+
+        assert!(matches!(
+            create_issue_payload(&json!({"project_key": "APP"})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_issues() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/search/jql"))
+            .and(query_param("jql", "assignee = currentUser()"))
+            .and(query_param("maxResults", "50"))
+            .and(header(
+                "authorization",
+                "Basic bWVAZXhhbXBsZS5jb206dG9rZW4=",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "isLast": true,
+                "issues": [{
+                    "key": "APP-1",
+                    "fields": {
+                        "summary": "Fix login",
+                        "status": {"name": "In Progress"},
+                        "assignee": {"displayName": "Ada"},
+                        "priority": {"name": "High"}
+                    }
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("search_issues", json!({"jql": "assignee = currentUser()"}))
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&text(result)).unwrap();
+        assert_eq!(
+            result["issues"],
+            json!([{
+                "key": "APP-1",
+                "summary": "Fix login",
+                "status": "In Progress",
+                "assignee": "Ada",
+                "priority": "High"
+            }])
+        );
+        assert_eq!(result["has_more"], false);
+    }
+
+    #[tokio::test]
+    async fn test_jql_errors_are_invalid_parameters() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/search/jql"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "errorMessages": ["Error in the JQL Query: Expecting a field name but got 'AND'."],
+                "warningMessages": []
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("search_issues", json!({"jql": "project = APP AND AND"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message == "Error in the JQL Query: Expecting a field name but got 'AND'.")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_issue() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/issue"))
+            .and(body_json(json!({
+                "fields": {
+                    "project": {"key": "APP"},
+                    "summary": "Add dark mode",
+                    "issuetype": {"name": "Task"}
+                }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": "10001",
+                "key": "APP-2"
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "create_issue",
+                json!({"project_key": "APP", "summary": "Add dark mode"}),
+            )
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&text(result)).unwrap();
+        assert_eq!(result["key"], "APP-2");
+        assert_eq!(result["url"], format!("{}/browse/APP-2", server.uri()));
+    }
+
+    #[tokio::test]
+    async fn test_update_issue_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/APP-1/transitions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "transitions": [
+                    {"id": "21", "name": "Start", "to": {"name": "In Progress"}},
+                    {"id": "31", "name": "Finish", "to": {"name": "Done"}}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/issue/APP-1/transitions"))
+            .and(body_json(json!({"transition": {"id": "31"}})))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let router = router_for(&server);
+        let result = router
+            .call_tool(
+                "update_issue",
+                json!({"issue_key": "APP-1", "status": "done"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(text(result), "Updated status of APP-1");
+
+        let result = router
+            .call_tool(
+                "update_issue",
+                json!({"issue_key": "APP-1", "status": "Blocked"}),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidParameters(message)) if message.contains("In Progress, Done"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_not_configured() {
+        let router = JiraRouter::with_config(None);
+        let result = router.call_tool("list_projects", json!({})).await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("JIRA_API_TOKEN"))
+        );
+    }
+}
@@ -0,0 +1,996 @@
+use indoc::indoc;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Event, Namespace, Pod, Service};
+use kube::api::{
+    AttachParams, DynamicObject, GroupVersionKind, ListParams, LogParams, Patch, PatchParams,
+};
+use kube::discovery::{pinned_kind, Scope};
+use kube::{Api, Client, Config, ResourceExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{future::Future, pin::Pin, sync::Arc};
+use tokio::io::AsyncReadExt;
+use tokio::sync::OnceCell;
+
+use crate::util::{json_content, required_str};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+const DEFAULT_TAIL_LINES: i64 = 100;
+const FIELD_MANAGER: &str = "goose";
+
+/// Router for a Kubernetes cluster, using the kubeconfig from KUBECONFIG or ~/.kube/config
+#[derive(Clone)]
+pub struct KubernetesRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    client: Arc<OnceCell<Client>>,
+    /// The namespace used when a tool is not given one, from GOOSE_K8S_NAMESPACE
+    default_namespace: Option<String>,
+    allow_exec: bool,
+}
+
+impl Default for KubernetesRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KubernetesRouter {
+    pub fn new() -> Self {
+        Self::with_options(
+            None,
+            std::env::var("GOOSE_K8S_NAMESPACE")
+                .ok()
+                .filter(|namespace| !namespace.is_empty()),
+            std::env::var("GOOSE_K8S_ALLOW_EXEC").is_ok_and(|value| value == "true"),
+        )
+    }
+
+    /// Create a router using `client`, or a client for the inferred kubeconfig when `None`
+    pub fn with_options(
+        client: Option<Client>,
+        default_namespace: Option<String>,
+        allow_exec: bool,
+    ) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let namespace = json!({
+            "type": "string",
+            "description": "The namespace, by default the configured namespace"
+        });
+
+        let list_pods = Tool::new(
+            "list_pods",
+            "List the pods in a namespace with their phase, readiness, restarts and node.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "namespace": namespace,
+                    "label_selector": {"type": "string", "description": "e.g. app=web,tier!=cache"}
+                }
+            }),
+            read_only("List pods"),
+        );
+
+        let get_pod_logs = Tool::new(
+            "get_pod_logs",
+            "Get the last lines of the logs of a pod.",
+            json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "namespace": namespace,
+                    "container": {"type": "string", "description": "Required for pods with several containers"},
+                    "tail_lines": {"type": "integer", "default": DEFAULT_TAIL_LINES}
+                }
+            }),
+            read_only("Get pod logs"),
+        );
+
+        let describe_pod = Tool::new(
+            "describe_pod",
+            "Describe a pod: its status, containers, conditions and recent events.",
+            json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "namespace": namespace
+                }
+            }),
+            read_only("Describe a pod"),
+        );
+
+        let list_deployments = Tool::new(
+            "list_deployments",
+            "List the deployments in a namespace with their replica counts and images.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "namespace": namespace,
+                    "label_selector": {"type": "string"}
+                }
+            }),
+            read_only("List deployments"),
+        );
+
+        let scale_deployment = Tool::new(
+            "scale_deployment",
+            "Set the number of replicas of a deployment.",
+            json!({
+                "type": "object",
+                "required": ["name", "replicas"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "namespace": namespace,
+                    "replicas": {"type": "integer", "minimum": 0}
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Scale a deployment".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: true,
+                open_world_hint: true,
+            }),
+        );
+
+        let list_services = Tool::new(
+            "list_services",
+            "List the services in a namespace with their type, cluster IP and ports.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "namespace": namespace,
+                    "label_selector": {"type": "string"}
+                }
+            }),
+            read_only("List services"),
+        );
+
+        let get_service = Tool::new(
+            "get_service",
+            "Get a service with its selector, ports and load balancer addresses.",
+            json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "namespace": namespace
+                }
+            }),
+            read_only("Get a service"),
+        );
+
+        let list_namespaces = Tool::new(
+            "list_namespaces",
+            "List the namespaces of the cluster.",
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+            read_only("List namespaces"),
+        );
+
+        let apply_manifest = Tool::new(
+            "apply_manifest",
+            indoc! {r#"
+                Apply the resources of a local YAML manifest to the cluster with server-side
+                apply, creating or updating them. The file may contain several documents.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {"type": "string", "description": "The path of the YAML manifest"},
+                    "namespace": namespace
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Apply a manifest".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: true,
+                open_world_hint: true,
+            }),
+        );
+
+        let exec_in_pod = Tool::new(
+            "exec_in_pod",
+            "Run a command in a container of a pod, returning its output.",
+            json!({
+                "type": "object",
+                "required": ["name", "command"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "namespace": namespace,
+                    "container": {"type": "string"},
+                    "command": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "The program and its arguments, e.g. [\"ls\", \"-l\", \"/app\"]"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Run a command in a pod".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: true,
+            }),
+        );
+
+        let mut tools = vec![
+            list_pods,
+            get_pod_logs,
+            describe_pod,
+            list_deployments,
+            scale_deployment,
+            list_services,
+            get_service,
+            list_namespaces,
+            apply_manifest,
+        ];
+        // Running commands in pods is opt in, so the tool is only offered when enabled
+        if allow_exec {
+            tools.push(exec_in_pod);
+        }
+
+        let instructions = indoc! {r#"
+            The Kubernetes extension inspects and manages the cluster of the current kubeconfig
+            context. Tools use the configured default namespace unless given one.
+
+            Check pods with describe_pod and get_pod_logs before changing deployments.
+        "#}
+        .to_string();
+
+        let cell = match client {
+            Some(client) => OnceCell::new_with(Some(client)),
+            None => OnceCell::new(),
+        };
+        Self {
+            tools,
+            instructions,
+            client: Arc::new(cell),
+            default_namespace,
+            allow_exec,
+        }
+    }
+
+    // Helper method to get the client, connecting to the cluster on first use
+    async fn client(&self) -> Result<Client, ToolError> {
+        self.client
+            .get_or_try_init(|| async {
+                let config = Config::infer().await.map_err(|e| {
+                    ToolError::ExecutionError(format!(
+                        "Failed to load the kubeconfig from KUBECONFIG or ~/.kube/config: {}",
+                        e
+                    ))
+                })?;
+                Client::try_from(config).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to create the client: {}", e))
+                })
+            })
+            .await
+            .cloned()
+    }
+
+    // Helper method to get the client and the namespace a tool is scoped to
+    async fn scope(&self, params: &Value) -> Result<(Client, String), ToolError> {
+        let client = self.client().await?;
+        let namespace = params["namespace"]
+            .as_str()
+            .map(String::from)
+            .or_else(|| self.default_namespace.clone())
+            .unwrap_or_else(|| client.default_namespace().to_string());
+        Ok((client, namespace))
+    }
+
+    async fn list_pods(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let (client, namespace) = self.scope(&params).await?;
+        let pods = Api::<Pod>::namespaced(client, &namespace)
+            .list(&list_params(&params))
+            .await
+            .map_err(api_error)?;
+
+        let pods: Vec<Value> = pods
+            .items
+            .iter()
+            .map(|pod| {
+                let statuses = pod
+                    .status
+                    .as_ref()
+                    .and_then(|status| status.container_statuses.clone())
+                    .unwrap_or_default();
+                let ready = statuses.iter().filter(|status| status.ready).count();
+                let restarts: i32 = statuses.iter().map(|status| status.restart_count).sum();
+                json!({
+                    "name": pod.name_any(),
+                    "phase": pod.status.as_ref().and_then(|status| status.phase.clone()),
+                    "ready": format!("{}/{}", ready, statuses.len()),
+                    "restarts": restarts,
+                    "node": pod.spec.as_ref().and_then(|spec| spec.node_name.clone()),
+                    "started": pod.status.as_ref().and_then(|status| status.start_time.as_ref()).map(|time| time.0.to_rfc3339())
+                })
+            })
+            .collect();
+        Ok(vec![json_content(&Value::Array(pods))])
+    }
+
+    async fn get_pod_logs(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let name = required_str(&params, "name")?;
+        let (client, namespace) = self.scope(&params).await?;
+        let log_params = LogParams {
+            container: params["container"].as_str().map(String::from),
+            tail_lines: Some(params["tail_lines"].as_i64().unwrap_or(DEFAULT_TAIL_LINES)),
+            ..Default::default()
+        };
+
+        let logs = Api::<Pod>::namespaced(client, &namespace)
+            .logs(name, &log_params)
+            .await
+            .map_err(api_error)?;
+        Ok(vec![Content::text(logs)])
+    }
+
+    async fn describe_pod(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let name = required_str(&params, "name")?;
+        let (client, namespace) = self.scope(&params).await?;
+        let pod = Api::<Pod>::namespaced(client.clone(), &namespace)
+            .get(name)
+            .await
+            .map_err(api_error)?;
+        let events = Api::<Event>::namespaced(client, &namespace)
+            .list(&ListParams::default().fields(&format!(
+                "involvedObject.kind=Pod,involvedObject.name={}",
+                name
+            )))
+            .await
+            .map_err(api_error)?;
+
+        let spec = pod.spec.clone().unwrap_or_default();
+        let status = pod.status.clone().unwrap_or_default();
+        let container_statuses = status.container_statuses.unwrap_or_default();
+        let containers: Vec<Value> = spec
+            .containers
+            .iter()
+            .map(|container| {
+                let status = container_statuses
+                    .iter()
+                    .find(|status| status.name == container.name);
+                json!({
+                    "name": container.name,
+                    "image": container.image,
+                    "ready": status.map(|status| status.ready),
+                    "restart_count": status.map(|status| status.restart_count),
+                    "state": status.and_then(|status| status.state.as_ref()).map(container_state),
+                    "last_state": status.and_then(|status| status.last_state.as_ref()).map(container_state),
+                })
+            })
+            .collect();
+        let conditions: Vec<Value> = status
+            .conditions
+            .unwrap_or_default()
+            .iter()
+            .map(|condition| {
+                json!({
+                    "type": condition.type_,
+                    "status": condition.status,
+                    "reason": condition.reason,
+                    "message": condition.message
+                })
+            })
+            .collect();
+        let events: Vec<Value> = events
+            .items
+            .iter()
+            .map(|event| {
+                json!({
+                    "type": event.type_,
+                    "reason": event.reason,
+                    "message": event.message,
+                    "count": event.count,
+                    "last_seen": event.last_timestamp.as_ref().map(|time| time.0.to_rfc3339())
+                })
+            })
+            .collect();
+
+        Ok(vec![json_content(&json!({
+            "name": pod.name_any(),
+            "namespace": namespace,
+            "node": spec.node_name,
+            "phase": status.phase,
+            "pod_ip": status.pod_ip,
+            "started": status.start_time.map(|time| time.0.to_rfc3339()),
+            "labels": pod.labels(),
+            "containers": containers,
+            "conditions": conditions,
+            "events": events
+        }))])
+    }
+
+    async fn list_deployments(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let (client, namespace) = self.scope(&params).await?;
+        let deployments = Api::<Deployment>::namespaced(client, &namespace)
+            .list(&list_params(&params))
+            .await
+            .map_err(api_error)?;
+
+        let deployments: Vec<Value> = deployments
+            .items
+            .iter()
+            .map(|deployment| {
+                let spec = deployment.spec.clone().unwrap_or_default();
+                let status = deployment.status.clone().unwrap_or_default();
+                let images: Vec<Option<String>> = spec
+                    .template
+                    .spec
+                    .map(|spec| spec.containers.into_iter().map(|c| c.image).collect())
+                    .unwrap_or_default();
+                json!({
+                    "name": deployment.name_any(),
+                    "replicas": spec.replicas,
+                    "ready_replicas": status.ready_replicas.unwrap_or(0),
+                    "available_replicas": status.available_replicas.unwrap_or(0),
+                    "images": images
+                })
+            })
+            .collect();
+        Ok(vec![json_content(&Value::Array(deployments))])
+    }
+
+    async fn scale_deployment(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let name = required_str(&params, "name")?;
+        let replicas = params["replicas"]
+            .as_u64()
+            .and_then(|replicas| i32::try_from(replicas).ok())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(
+                    "'replicas' must be a non-negative integer".to_string(),
+                )
+            })?;
+        let (client, namespace) = self.scope(&params).await?;
+
+        let scale = Api::<Deployment>::namespaced(client, &namespace)
+            .patch_scale(
+                name,
+                &PatchParams::default(),
+                &Patch::Merge(json!({"spec": {"replicas": replicas}})),
+            )
+            .await
+            .map_err(api_error)?;
+        let previous = scale.status.map(|status| status.replicas).unwrap_or(0);
+        Ok(vec![Content::text(format!(
+            "Scaled deployment {}/{} from {} to {} replicas",
+            namespace, name, previous, replicas
+        ))])
+    }
+
+    async fn list_services(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let (client, namespace) = self.scope(&params).await?;
+        let services = Api::<Service>::namespaced(client, &namespace)
+            .list(&list_params(&params))
+            .await
+            .map_err(api_error)?;
+
+        let services: Vec<Value> = services.items.iter().map(service_summary).collect();
+        Ok(vec![json_content(&Value::Array(services))])
+    }
+
+    async fn get_service(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let name = required_str(&params, "name")?;
+        let (client, namespace) = self.scope(&params).await?;
+        let service = Api::<Service>::namespaced(client, &namespace)
+            .get(name)
+            .await
+            .map_err(api_error)?;
+
+        let mut summary = service_summary(&service);
+        let spec = service.spec.clone().unwrap_or_default();
+        summary["selector"] = json!(spec.selector);
+        summary["labels"] = json!(service.labels());
+        summary["load_balancer"] = json!(service
+            .status
+            .and_then(|status| status.load_balancer)
+            .and_then(|load_balancer| load_balancer.ingress)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|ingress| ingress.ip.clone().or_else(|| ingress.hostname.clone()))
+            .collect::<Vec<_>>());
+        Ok(vec![json_content(&summary)])
+    }
+
+    async fn list_namespaces(&self) -> Result<Vec<Content>, ToolError> {
+        let client = self.client().await?;
+        let namespaces = Api::<Namespace>::all(client)
+            .list(&ListParams::default())
+            .await
+            .map_err(api_error)?;
+
+        let namespaces: Vec<Value> = namespaces
+            .items
+            .iter()
+            .map(|namespace| {
+                json!({
+                    "name": namespace.name_any(),
+                    "phase": namespace.status.as_ref().and_then(|status| status.phase.clone())
+                })
+            })
+            .collect();
+        Ok(vec![json_content(&Value::Array(namespaces))])
+    }
+
+    async fn apply_manifest(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path = shellexpand::tilde(required_str(&params, "path")?).into_owned();
+        let manifest = std::fs::read_to_string(&path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read '{}': {}", path, e)))?;
+        let objects = parse_manifest(&manifest)?;
+        let (client, namespace) = self.scope(&params).await?;
+
+        let mut applied = Vec::new();
+        for object in objects {
+            let types = object.types.clone().unwrap_or_default();
+            let gvk = GroupVersionKind::try_from(&types).map_err(|e| {
+                ToolError::InvalidParameters(format!("Invalid resource in manifest: {}", e))
+            })?;
+            let (resource, capabilities) = pinned_kind(&client, &gvk).await.map_err(api_error)?;
+            let api: Api<DynamicObject> = match capabilities.scope {
+                Scope::Namespaced => {
+                    let namespace = object.namespace().unwrap_or_else(|| namespace.clone());
+                    Api::namespaced_with(client.clone(), &namespace, &resource)
+                }
+                Scope::Cluster => Api::all_with(client.clone(), &resource),
+            };
+
+            let name = object.name_any();
+            api.patch(
+                &name,
+                &PatchParams::apply(FIELD_MANAGER).force(),
+                &Patch::Apply(&object),
+            )
+            .await
+            .map_err(api_error)?;
+            applied.push(format!("{}/{} applied", gvk.kind.to_lowercase(), name));
+        }
+        Ok(vec![Content::text(applied.join("\n"))])
+    }
+
+    async fn exec_in_pod(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        if !self.allow_exec {
+            return Err(ToolError::ExecutionError(
+                "Running commands in pods is disabled, set GOOSE_K8S_ALLOW_EXEC=true to enable it"
+                    .into(),
+            ));
+        }
+        let name = required_str(&params, "name")?;
+        let command: Vec<String> = params["command"]
+            .as_array()
+            .map(|command| {
+                command
+                    .iter()
+                    .filter_map(|arg| arg.as_str().map(String::from))
+                    .collect()
+            })
+            .filter(|command: &Vec<String>| !command.is_empty())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("'command' must be a non-empty list".into())
+            })?;
+        let (client, namespace) = self.scope(&params).await?;
+
+        let mut attach = AttachParams::default()
+            .stdin(false)
+            .stdout(true)
+            .stderr(true);
+        if let Some(container) = params["container"].as_str() {
+            attach = attach.container(container);
+        }
+        let mut process = Api::<Pod>::namespaced(client, &namespace)
+            .exec(name, command, &attach)
+            .await
+            .map_err(api_error)?;
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        if let Some(mut reader) = process.stdout() {
+            reader.read_to_string(&mut stdout).await.ok();
+        }
+        if let Some(mut reader) = process.stderr() {
+            reader.read_to_string(&mut stderr).await.ok();
+        }
+        let status = match process.take_status() {
+            Some(status) => status.await,
+            None => None,
+        };
+        process.join().await.ok();
+
+        let mut output = stdout;
+        if !stderr.is_empty() {
+            output.push_str(&format!("\n[stderr]\n{}", stderr));
+        }
+        if let Some(status) = status.filter(|status| status.status.as_deref() == Some("Failure")) {
+            output.push_str(&format!(
+                "\n[failed] {}",
+                status.message.unwrap_or_default()
+            ));
+        }
+        Ok(vec![Content::text(output)])
+    }
+}
+
+fn list_params(params: &Value) -> ListParams {
+    match params["label_selector"].as_str() {
+        Some(selector) => ListParams::default().labels(selector),
+        None => ListParams::default(),
+    }
+}
+
+fn api_error(error: kube::Error) -> ToolError {
+    match error {
+        kube::Error::Api(response) => ToolError::ExecutionError(format!(
+            "Kubernetes API error ({}): {}",
+            response.code, response.message
+        )),
+        error => ToolError::ExecutionError(format!("Kubernetes request failed: {}", error)),
+    }
+}
+
+fn container_state(state: &k8s_openapi::api::core::v1::ContainerState) -> Value {
+    if let Some(running) = &state.running {
+        json!({"running": {"started": running.started_at.as_ref().map(|time| time.0.to_rfc3339())}})
+    } else if let Some(waiting) = &state.waiting {
+        json!({"waiting": {"reason": waiting.reason, "message": waiting.message}})
+    } else if let Some(terminated) = &state.terminated {
+        json!({"terminated": {
+            "exit_code": terminated.exit_code,
+            "reason": terminated.reason,
+            "message": terminated.message
+        }})
+    } else {
+        Value::Null
+    }
+}
+
+fn service_summary(service: &Service) -> Value {
+    let spec = service.spec.clone().unwrap_or_default();
+    let ports: Vec<String> = spec
+        .ports
+        .unwrap_or_default()
+        .iter()
+        .map(|port| {
+            let protocol = port.protocol.as_deref().unwrap_or("TCP");
+            match port.node_port {
+                Some(node_port) => format!("{}:{}/{}", port.port, node_port, protocol),
+                None => format!("{}/{}", port.port, protocol),
+            }
+        })
+        .collect();
+    json!({
+        "name": service.name_any(),
+        "type": spec.type_,
+        "cluster_ip": spec.cluster_ip,
+        "ports": ports
+    })
+}
+
+/// The resources of a YAML manifest, skipping empty documents
+fn parse_manifest(manifest: &str) -> Result<Vec<DynamicObject>, ToolError> {
+    let mut objects = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(manifest) {
+        let value = serde_yaml::Value::deserialize(document)
+            .map_err(|e| ToolError::InvalidParameters(format!("Invalid YAML manifest: {}", e)))?;
+        if value.is_null() {
+            continue;
+        }
+        let object: DynamicObject = serde_yaml::from_value(value).map_err(|e| {
+            ToolError::InvalidParameters(format!("Invalid resource in manifest: {}", e))
+        })?;
+        objects.push(object);
+    }
+    if objects.is_empty() {
+        return Err(ToolError::InvalidParameters(
+            "The manifest contains no resources".into(),
+        ));
+    }
+    Ok(objects)
+}
+
+impl Router for KubernetesRouter {
+    fn name(&self) -> String {
+        "kubernetes".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "list_pods" => this.list_pods(arguments).await,
+                "get_pod_logs" => this.get_pod_logs(arguments).await,
+                "describe_pod" => this.describe_pod(arguments).await,
+                "list_deployments" => this.list_deployments(arguments).await,
+                "scale_deployment" => this.scale_deployment(arguments).await,
+                "list_services" => this.list_services(arguments).await,
+                "get_service" => this.get_service(arguments).await,
+                "list_namespaces" => this.list_namespaces().await,
+                "apply_manifest" => this.apply_manifest(arguments).await,
+                "exec_in_pod" => this.exec_in_pod(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::parse;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A router for a mock API server, where the kubeconfig namespace is "default"
+    fn router_for(server: &MockServer, default_namespace: Option<&str>) -> KubernetesRouter {
+        let config = Config::new(server.uri().parse().unwrap());
+        KubernetesRouter::with_options(
+            Some(Client::try_from(config).unwrap()),
+            default_namespace.map(String::from),
+            false,
+        )
+    }
+
+    fn pod() -> Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": {"name": "web-1", "namespace": "shop", "labels": {"app": "web"}},
+            "spec": {
+                "nodeName": "node-a",
+                "containers": [{"name": "app", "image": "shop/web:1.2"}]
+            },
+            "status": {
+                "phase": "Running",
+                "podIP": "10.0.0.7",
+                "conditions": [{"type": "Ready", "status": "True"}],
+                "containerStatuses": [{
+                    "name": "app",
+                    "image": "shop/web:1.2",
+                    "imageID": "",
+                    "ready": true,
+                    "restartCount": 2,
+                    "state": {"running": {}}
+                }]
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_pods() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/namespaces/shop/pods"))
+            .and(query_param("labelSelector", "app=web"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "apiVersion": "v1",
+                "kind": "PodList",
+                "metadata": {},
+                "items": [pod()]
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server, Some("shop"))
+            .call_tool("list_pods", json!({"label_selector": "app=web"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            parse(result),
+            json!([{
+                "name": "web-1",
+                "phase": "Running",
+                "ready": "1/1",
+                "restarts": 2,
+                "node": "node-a",
+                "started": null
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_describe_pod() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/namespaces/shop/pods/web-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pod()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/namespaces/shop/events"))
+            .and(query_param(
+                "fieldSelector",
+                "involvedObject.kind=Pod,involvedObject.name=web-1",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "apiVersion": "v1",
+                "kind": "EventList",
+                "metadata": {},
+                "items": [{
+                    "metadata": {"name": "web-1.1"},
+                    "involvedObject": {"kind": "Pod", "name": "web-1"},
+                    "type": "Warning",
+                    "reason": "BackOff",
+                    "message": "Back-off restarting failed container",
+                    "count": 3
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server, None)
+            .call_tool(
+                "describe_pod",
+                json!({"name": "web-1", "namespace": "shop"}),
+            )
+            .await
+            .unwrap();
+        let pod = parse(result);
+        assert_eq!(pod["node"], "node-a");
+        assert_eq!(pod["pod_ip"], "10.0.0.7");
+        assert_eq!(pod["labels"], json!({"app": "web"}));
+        assert_eq!(
+            pod["containers"],
+            json!([{
+                "name": "app",
+                "image": "shop/web:1.2",
+                "ready": true,
+                "restart_count": 2,
+                "state": {"running": {"started": null}},
+                "last_state": null
+            }])
+        );
+        assert_eq!(pod["events"][0]["reason"], "BackOff");
+        assert_eq!(pod["events"][0]["count"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_api_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/namespaces/default/services/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "kind": "Status",
+                "apiVersion": "v1",
+                "status": "Failure",
+                "message": "services \"missing\" not found",
+                "reason": "NotFound",
+                "code": 404
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server, None)
+            .call_tool("get_service", json!({"name": "missing"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message == "Kubernetes API error (404): services \"missing\" not found")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_requires_opt_in() {
+        let server = MockServer::start().await;
+        let router = router_for(&server, None);
+        assert!(!router
+            .list_tools()
+            .iter()
+            .any(|tool| tool.name == "exec_in_pod"));
+
+        let result = router
+            .call_tool("exec_in_pod", json!({"name": "web-1", "command": ["ls"]}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("GOOSE_K8S_ALLOW_EXEC"))
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let objects = parse_manifest(indoc! {r#"
+            ---
+            apiVersion: v1
+            kind: ConfigMap
+            metadata:
+              name: settings
+            data:
+              mode: fast
+            ---
+            apiVersion: apps/v1
+            kind: Deployment
+            metadata:
+              name: web
+              namespace: shop
+            ---
+        "#})
+        .unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].name_any(), "settings");
+        assert_eq!(objects[0].data["data"]["mode"], "fast");
+        assert_eq!(objects[1].namespace().as_deref(), Some("shop"));
+        assert_eq!(
+            objects[1].types.as_ref().unwrap().api_version,
+            "apps/v1".to_string()
+        );
+
+        assert!(matches!(
+            parse_manifest("---\n"),
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+}
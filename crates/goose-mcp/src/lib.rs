@@ -7,16 +7,33 @@ pub static APP_STRATEGY: Lazy<AppStrategyArgs> = Lazy::new(|| AppStrategyArgs {
     app_name: "goose".to_string(),
 });
 
+mod azure_blob;
 pub mod computercontroller;
+mod confluence;
 mod developer;
+mod email;
 pub mod google_drive;
+mod graphql;
 mod jetbrains;
+mod jira;
+mod kubernetes;
 mod memory;
+mod notion;
+mod slack;
 mod tutorial;
+mod util;
 
+pub use azure_blob::AzureBlobRouter;
 pub use computercontroller::ComputerControllerRouter;
+pub use confluence::ConfluenceRouter;
 pub use developer::DeveloperRouter;
+pub use email::EmailRouter;
 pub use google_drive::GoogleDriveRouter;
+pub use graphql::GraphQLRouter;
 pub use jetbrains::JetBrainsRouter;
+pub use jira::JiraRouter;
+pub use kubernetes::KubernetesRouter;
 pub use memory::MemoryRouter;
+pub use notion::NotionRouter;
+pub use slack::SlackRouter;
 pub use tutorial::TutorialRouter;
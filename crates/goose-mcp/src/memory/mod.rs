@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
 use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
 use serde_json::{json, Value};
@@ -7,8 +8,12 @@ use std::{
     fs,
     future::Future,
     io::{self, Read, Write},
-    path::PathBuf,
+    path::{Component, Path, PathBuf},
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 use mcp_core::{
@@ -22,11 +27,22 @@ use mcp_core::{
 use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
+/// The default limit on the size of a wildcard retrieval, to keep it within the context window
+const DEFAULT_MAX_TOTAL_BYTES: usize = 512 * 1024;
+
+/// The longest TTL of a memory, about a hundred years, which longer TTLs are capped to
+const MAX_TTL_DAYS: u64 = 36_500;
+
 // MemoryRouter implementation
 #[derive(Clone)]
 pub struct MemoryRouter {
     tools: Vec<Tool>,
-    instructions: String,
+    /// The instructions without the saved memories
+    base_instructions: String,
+    /// The instructions including the saved memories, shared by every clone of the router
+    instructions: Arc<Mutex<String>>,
+    /// Set when memories change, so the instructions are rebuilt the next time they are read
+    instructions_dirty: Arc<AtomicBool>,
     global_memory_dir: PathBuf,
     local_memory_dir: PathBuf,
 }
@@ -48,7 +64,11 @@ impl MemoryRouter {
                     "category": {"type": "string"},
                     "data": {"type": "string"},
                     "tags": {"type": "array", "items": {"type": "string"}},
-                    "is_global": {"type": "boolean"}
+                    "is_global": {"type": "boolean"},
+                    "ttl_days": {
+                        "type": "integer",
+                        "description": "Forget the memory after this many days (default: never)"
+                    }
                 },
                 "required": ["category", "data", "is_global"]
             }),
@@ -63,12 +83,21 @@ impl MemoryRouter {
 
         let retrieve_memories = Tool::new(
             "retrieve_memories",
-            "Retrieves all memories from a specified category",
+            "Retrieves all memories from a specified category, or from every category matching a glob pattern such as \"*\" or \"project_*\"",
             json!({
                 "type": "object",
                 "properties": {
                     "category": {"type": "string"},
-                    "is_global": {"type": "boolean"}
+                    "is_global": {"type": "boolean"},
+                    "tags": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Only retrieve memories with at least one of these tags"
+                    },
+                    "max_total_bytes": {
+                        "type": "integer",
+                        "description": "Limit on the size of a pattern retrieval (default: 524288)"
+                    }
                 },
                 "required": ["category", "is_global"]
             }),
@@ -81,6 +110,46 @@ impl MemoryRouter {
             }),
         );
 
+        let list_memory_categories = Tool::new(
+            "list_memory_categories",
+            "Lists the memory categories as a tree, with nested categories indented under their parents",
+            json!({
+                "type": "object",
+                "properties": {
+                    "is_global": {"type": "boolean"}
+                },
+                "required": ["is_global"]
+            }),
+            Some(ToolAnnotations {
+                title: Some("List Memory Categories".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let list_categories = Tool::new(
+            "list_categories",
+            "Lists the memory categories as a JSON array, with the scope of each and how many memories it holds",
+            json!({
+                "type": "object",
+                "properties": {
+                    "is_global": {
+                        "type": "boolean",
+                        "description": "Only list global (true) or local (false) categories (default: both)"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("List Categories".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
         let remove_memory_category = Tool::new(
             "remove_memory_category",
             "Removes all memories within a specified category",
@@ -101,6 +170,30 @@ impl MemoryRouter {
             }),
         );
 
+        let rename_category = Tool::new(
+            "rename_category",
+            "Renames a memory category, keeping its memories",
+            json!({
+                "type": "object",
+                "properties": {
+                    "old_category": {"type": "string"},
+                    "new_category": {
+                        "type": "string",
+                        "description": "The new name, with '/' between the names of nested categories"
+                    },
+                    "is_global": {"type": "boolean"}
+                },
+                "required": ["old_category", "new_category", "is_global"]
+            }),
+            Some(ToolAnnotations {
+                title: Some("Rename Category".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
         let remove_specific_memory = Tool::new(
             "remove_specific_memory",
             "Removes a specific memory within a specified category",
@@ -122,6 +215,99 @@ impl MemoryRouter {
             }),
         );
 
+        let search_memories = Tool::new(
+            "search_memories",
+            "Searches the content of memories for a text, case-insensitively, returning the matching memories grouped by scope and category",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "is_global": {
+                        "type": "boolean",
+                        "description": "Only search global (true) or local (false) memories (default: both)"
+                    },
+                    "category": {
+                        "type": "string",
+                        "description": "Only search this category, or the categories matching a glob pattern (default: all)"
+                    }
+                },
+                "required": ["query"]
+            }),
+            Some(ToolAnnotations {
+                title: Some("Search Memories".to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let expire_memories = Tool::new(
+            "expire_memories",
+            "Removes every memory whose ttl_days have passed from all categories",
+            json!({
+                "type": "object",
+                "properties": {
+                    "is_global": {
+                        "type": "boolean",
+                        "description": "Only expire global (true) or local (false) memories (default: both)"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Expire Memories".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let export_memories = Tool::new(
+            "export_memories",
+            "Exports every memory of a scope to a JSON file under the home directory, as an object of categories to their memories",
+            json!({
+                "type": "object",
+                "properties": {
+                    "dest_path": {"type": "string"},
+                    "is_global": {"type": "boolean"}
+                },
+                "required": ["dest_path", "is_global"]
+            }),
+            Some(ToolAnnotations {
+                title: Some("Export Memories".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: true,
+                open_world_hint: false,
+            }),
+        );
+
+        let import_memories = Tool::new(
+            "import_memories",
+            "Imports memories from a JSON file under the home directory written by export_memories",
+            json!({
+                "type": "object",
+                "properties": {
+                    "src_path": {"type": "string"},
+                    "is_global": {"type": "boolean"},
+                    "merge_strategy": {
+                        "type": "string",
+                        "enum": ["merge", "replace"],
+                        "description": "Whether to add the memories missing from each category (merge, the default) or to overwrite the categories in the file (replace)"
+                    }
+                },
+                "required": ["src_path", "is_global"]
+            }),
+            Some(ToolAnnotations {
+                title: Some("Import Memories".to_string()),
+                read_only_hint: false,
+                destructive_hint: true,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
         let instructions = formatdoc! {r#"
              This extension allows storage and retrieval of categorized information with tagging support. It's designed to help
              manage important information across sessions in a systematic and organized manner.
@@ -154,6 +340,7 @@ impl MemoryRouter {
                   - Global storage (~/.config/goose/memory) for user-wide data.
                 - Use the remember_memory tool to store the information.
                   - `remember_memory(category, data, tags, is_global)`
+                - For information that is only useful for a while, such as a meeting agenda, set `ttl_days` so the memory is forgotten after that many days.
              Keywords that trigger memory tools:
              - "remember"
              - "forget"
@@ -195,15 +382,29 @@ impl MemoryRouter {
                - Use: `retrieve_memories(category="development", is_global=False)`
                - Note: If you want to retrieve all local memories, use `retrieve_memories(category="*", is_global=False)`
                - Note: If you want to retrieve all global memories, use `retrieve_memories(category="*", is_global=True)`
+               - Note: Glob patterns retrieve every matching category, e.g. `retrieve_memories(category="project_*", is_global=False)`
+               - Note: Categories can be nested with `/`, e.g. `project/backend/database`, and a path ending in `/` retrieves the whole subtree, e.g. `retrieve_memories(category="project/", is_global=False)`
+               - Note: Use `list_memory_categories(is_global)` to see the category tree
+               - Note: Use `list_categories()` to discover the categories of both scopes and how many memories each holds
+               - Note: Pattern retrievals stop at `max_total_bytes` (default 512 KB) and then start with a JSON header listing the categories_included and categories_omitted
+             - **Search by Content**:
+               - Finds memories containing a text in every category and both scopes, ignoring case.
+               - Use: `search_memories(query="python")`, optionally narrowed with `is_global` and `category`
              - **Filter by Tags**:
                - Enables targeted retrieval based on specific tags.
-               - Use: Provide tag filters to refine search.
+               - Use: `retrieve_memories(category="development", is_global=False, tags=["formatting"])` returns only the memories with at least one of the tags
+            To reorganize memories:
+              - Use: `rename_category(old_category="dev", new_category="development", is_global=False)`
+            To back up memories or move them to another machine:
+              - Use: `export_memories(dest_path="~/backup.json", is_global=True)` and `import_memories(src_path="~/backup.json", is_global=True)`
+              - Note: The file must be under the home directory. Importing merges by default, `merge_strategy="replace"` overwrites the imported categories
             To remove a memory, use the following protocol:
             - **Remove by Category**:
               - Removes all memories within the specified category.
               - Use: `remove_memory_category(category="development", is_global=False)`
               - Note: If you want to remove all local memories, use `remove_memory_category(category="*", is_global=False)`
               - Note: If you want to remove all global memories, use `remove_memory_category(category="*", is_global=True)`
+              - Note: A path ending in `/` removes every category under it, e.g. `remove_memory_category(category="project/", is_global=False)`
             The Protocol is:
              1. Confirm what kind of information the user seeks by category or keyword.
              2. Suggest categories or relevant tags based on the user's request.
@@ -247,18 +448,35 @@ impl MemoryRouter {
             tools: vec![
                 remember_memory,
                 retrieve_memories,
+                list_memory_categories,
+                list_categories,
                 remove_memory_category,
                 remove_specific_memory,
+                rename_category,
+                search_memories,
+                expire_memories,
+                export_memories,
+                import_memories,
             ],
-            instructions: instructions.clone(),
+            base_instructions: instructions.clone(),
+            instructions: Arc::new(Mutex::new(instructions)),
+            instructions_dirty: Arc::new(AtomicBool::new(false)),
             global_memory_dir,
             local_memory_dir,
         };
 
-        let retrieved_global_memories = memory_router.retrieve_all(true);
-        let retrieved_local_memories = memory_router.retrieve_all(false);
+        let updated_instructions = memory_router.rebuild_instructions();
+        memory_router.set_instructions(updated_instructions);
+
+        memory_router
+    }
 
-        let mut updated_instructions = instructions;
+    /// The instructions followed by every saved memory, re-read from the memory files
+    fn rebuild_instructions(&self) -> String {
+        let retrieved_global_memories = self.retrieve_all(true);
+        let retrieved_local_memories = self.retrieve_all(false);
+
+        let mut updated_instructions = self.base_instructions.clone();
 
         let memories_follow_up_instructions = formatdoc! {r#"
             **Here are the user's currently saved memories:**
@@ -295,18 +513,22 @@ impl MemoryRouter {
             }
         }
 
-        memory_router.set_instructions(updated_instructions);
-
-        memory_router
+        updated_instructions
     }
 
     // Add a setter method for instructions
     pub fn set_instructions(&mut self, new_instructions: String) {
-        self.instructions = new_instructions;
+        *self.instructions.lock().unwrap() = new_instructions;
+        self.instructions_dirty.store(false, Ordering::SeqCst);
     }
 
-    pub fn get_instructions(&self) -> &str {
-        &self.instructions
+    /// The instructions, rebuilt first if memories changed since they were last read
+    pub fn get_instructions(&self) -> String {
+        let mut instructions = self.instructions.lock().unwrap();
+        if self.instructions_dirty.swap(false, Ordering::SeqCst) {
+            *instructions = self.rebuild_instructions();
+        }
+        instructions.clone()
     }
 
     fn get_memory_file(&self, category: &str, is_global: bool) -> PathBuf {
@@ -319,29 +541,299 @@ impl MemoryRouter {
         base_dir.join(format!("{}.txt", category))
     }
 
-    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
+    /// Every category with its file, named by its path from the memory directory, e.g.
+    /// `project/backend/database`, in tree order
+    fn categories(&self, is_global: bool) -> io::Result<Vec<(String, PathBuf)>> {
         let base_dir = if is_global {
             &self.global_memory_dir
         } else {
             &self.local_memory_dir
         };
-        let mut memories = HashMap::new();
-        if base_dir.exists() {
-            for entry in fs::read_dir(base_dir)? {
-                let entry = entry?;
-                if entry.file_type()?.is_file() {
-                    let category = entry.file_name().to_string_lossy().replace(".txt", "");
-                    let category_memories = self.retrieve(&category, is_global)?;
-                    memories.insert(
-                        category,
-                        category_memories.into_iter().flat_map(|(_, v)| v).collect(),
-                    );
+        let mut categories = Vec::new();
+        let mut dirs = vec![(String::new(), base_dir.clone())];
+        while let Some((prefix, dir)) = dirs.pop() {
+            if !dir.exists() {
+                continue;
+            }
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    dirs.push((format!("{}{}/", prefix, name), path));
+                } else if path.extension().is_some_and(|ext| ext == "txt") {
+                    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                    categories.push((format!("{}{}", prefix, name), path));
+                }
+            }
+        }
+        categories.sort_by(|(a, _), (b, _)| a.split('/').cmp(b.split('/')));
+        Ok(categories)
+    }
+
+    /// The categories of each of the given scopes in turn, with whether their scope is global
+    fn scoped_categories(&self, scopes: &[bool]) -> io::Result<Vec<(bool, String, PathBuf)>> {
+        let mut categories = Vec::new();
+        for &is_global in scopes {
+            for (category, path) in self.categories(is_global)? {
+                categories.push((is_global, category, path));
+            }
+        }
+        Ok(categories)
+    }
+
+    /// The contents of a memory file without its expired memories, which are removed from the
+    /// file, along with how many were removed. Removing memories marks the instructions dirty.
+    fn read_unexpired(&self, path: &Path) -> io::Result<(String, usize)> {
+        let content = fs::read_to_string(path)?;
+        let now = Utc::now();
+        let entries: Vec<&str> = content.split("\n\n").collect();
+        let kept: Vec<&str> = entries
+            .iter()
+            .copied()
+            .filter(|entry| !is_expired(entry, now))
+            .collect();
+        let expired = entries.len() - kept.len();
+        if expired == 0 {
+            return Ok((content, 0));
+        }
+        let kept = kept.join("\n\n");
+        fs::write(path, &kept)?;
+        self.instructions_dirty.store(true, Ordering::SeqCst);
+        Ok((kept, expired))
+    }
+
+    /// Remove the expired memories of every category, returning how many were removed
+    pub fn expire(&self, is_global: bool) -> io::Result<usize> {
+        let mut expired = 0;
+        for (_, path) in self.categories(is_global)? {
+            expired += self.read_unexpired(&path)?.1;
+        }
+        Ok(expired)
+    }
+
+    /// The memories of a file, each with its tag line, without the blank lines between them
+    fn entries(content: &str) -> Vec<String> {
+        content
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Write every category of a scope to `path` as a JSON object of category names to their
+    /// memories, returning how many categories were exported
+    pub fn export(&self, path: &Path, is_global: bool) -> io::Result<usize> {
+        let mut export = serde_json::Map::new();
+        for (category, file) in self.categories(is_global)? {
+            let (content, _) = self.read_unexpired(&file)?;
+            export.insert(category, json!(Self::entries(&content)));
+        }
+        let count = export.len();
+        fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        Ok(count)
+    }
+
+    /// Read memories exported to `path` into a scope, replacing the categories in the file or
+    /// adding the memories missing from them, returning how many memories were written
+    pub fn import(&self, path: &Path, is_global: bool, replace: bool) -> io::Result<usize> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        let import: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let categories = import
+            .as_object()
+            .ok_or_else(|| invalid("Expected a JSON object of categories to their memories"))?;
+
+        let mut imported = 0;
+        for (category, memories) in categories {
+            validate_category(category)?;
+            let memories = memories
+                .as_array()
+                .and_then(|memories| {
+                    memories
+                        .iter()
+                        .map(Value::as_str)
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| {
+                    invalid(&format!("The memories of '{}' must be strings", category))
+                })?;
+
+            let file = self.get_memory_file(category, is_global);
+            let mut entries = if replace || !file.exists() {
+                Vec::new()
+            } else {
+                Self::entries(&self.read_unexpired(&file)?.0)
+            };
+            for memory in memories {
+                let memory = memory.trim();
+                if !memory.is_empty() && !entries.iter().any(|entry| entry == memory) {
+                    entries.push(memory.to_string());
+                    imported += 1;
                 }
             }
+
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let content: String = entries
+                .iter()
+                .map(|entry| format!("{}\n\n", entry))
+                .collect();
+            fs::write(file, content)?;
+        }
+        Ok(imported)
+    }
+
+    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
+        let mut memories = HashMap::new();
+        for (category, _) in self.categories(is_global)? {
+            let category_memories = self.retrieve(&category, is_global)?;
+            memories.insert(
+                category,
+                category_memories.into_values().flatten().collect(),
+            );
         }
         Ok(memories)
     }
 
+    /// The categories as a tree, each nesting level indented by two spaces and parents ending in `/`
+    pub fn category_tree(&self, is_global: bool) -> io::Result<String> {
+        let mut lines = Vec::new();
+        let mut parents: Vec<String> = Vec::new();
+        for (category, _) in self.categories(is_global)? {
+            let mut parts: Vec<&str> = category.split('/').collect();
+            let name = parts.pop().unwrap_or_default();
+            let shared = parents
+                .iter()
+                .zip(&parts)
+                .take_while(|(a, b)| a == b)
+                .count();
+            for (depth, part) in parts.iter().enumerate().skip(shared) {
+                lines.push(format!("{}{}/", "  ".repeat(depth), part));
+            }
+            lines.push(format!("{}{}", "  ".repeat(parts.len()), name));
+            parents = parts.into_iter().map(String::from).collect();
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Every category of the given scopes as `{category, scope, entries}`, where entries is the
+    /// number of memories in it
+    pub fn category_counts(&self, scopes: &[bool]) -> io::Result<Vec<Value>> {
+        self.scoped_categories(scopes)?
+            .into_iter()
+            .map(|(is_global, category, path)| {
+                let (content, _) = self.read_unexpired(&path)?;
+                Ok(json!({
+                    "category": category,
+                    "scope": if is_global { "global" } else { "local" },
+                    "entries": Self::entries(&content).len(),
+                }))
+            })
+            .collect()
+    }
+
+    /// The contents of every category matching a glob pattern, in name order, each under a
+    /// `## Category: {name}` heading. Categories that would take the total past `max_total_bytes`
+    /// are left out and listed in a JSON header. With `tags`, only memories with one of them
+    /// are included.
+    pub fn retrieve_matching(
+        &self,
+        pattern: &str,
+        is_global: bool,
+        max_total_bytes: usize,
+        tags: &[&str],
+    ) -> io::Result<String> {
+        let pattern = glob::Pattern::new(pattern)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        let categories = self
+            .categories(is_global)?
+            .into_iter()
+            .filter(|(category, _)| pattern.matches(category));
+
+        let mut sections = String::new();
+        let mut included = Vec::new();
+        let mut omitted = Vec::new();
+        for (category, path) in categories {
+            let (mut content, _) = self.read_unexpired(&path)?;
+            if !tags.is_empty() {
+                content = content
+                    .split("\n\n")
+                    .filter(|entry| {
+                        entry
+                            .lines()
+                            .next()
+                            .and_then(|line| line.strip_prefix('#'))
+                            .is_some_and(|entry_tags| has_any_tag(entry_tags, tags))
+                    })
+                    .map(|entry| format!("{}\n\n", entry))
+                    .collect();
+                if content.is_empty() {
+                    continue;
+                }
+            }
+            let section = format!("## Category: {}\n{}\n", category, content);
+            if omitted.is_empty() && sections.len() + section.len() <= max_total_bytes {
+                sections.push_str(&section);
+                included.push(category);
+            } else {
+                omitted.push(category);
+            }
+        }
+
+        if omitted.is_empty() {
+            return Ok(sections);
+        }
+        let header = json!({
+            "truncated": true,
+            "categories_included": included,
+            "categories_omitted": omitted,
+        });
+        Ok(format!("{}\n\n{}", header, sections))
+    }
+
+    /// The lines of memories containing `query`, ignoring case, under a `## {Scope}: {category}`
+    /// heading for each category with matches
+    pub fn search(
+        &self,
+        query: &str,
+        scopes: &[bool],
+        category_pattern: Option<&str>,
+    ) -> io::Result<String> {
+        let pattern = category_pattern
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        let query = query.to_lowercase();
+
+        let mut sections = Vec::new();
+        for (is_global, category, path) in self.scoped_categories(scopes)? {
+            if pattern.as_ref().is_some_and(|p| !p.matches(&category)) {
+                continue;
+            }
+            let matches: Vec<String> = self
+                .read_unexpired(&path)?
+                .0
+                .lines()
+                // Lines starting with `#` hold the tags of a memory
+                .filter(|line| !line.starts_with('#'))
+                .filter(|line| line.to_lowercase().contains(&query))
+                .map(|line| format!("- {}", line))
+                .collect();
+            if !matches.is_empty() {
+                let scope = if is_global { "Global" } else { "Local" };
+                sections.push(format!(
+                    "## {}: {}\n{}",
+                    scope,
+                    category,
+                    matches.join("\n")
+                ));
+            }
+        }
+        Ok(sections.join("\n\n"))
+    }
+
+    /// Store a memory, which expires after `ttl_days` when it is set
     pub fn remember(
         &self,
         _context: &str,
@@ -349,8 +841,12 @@ impl MemoryRouter {
         data: &str,
         tags: &[&str],
         is_global: bool,
+        ttl_days: Option<u64>,
     ) -> io::Result<()> {
         let memory_file_path = self.get_memory_file(category, is_global);
+        if let Some(parent) = memory_file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
 
         let mut file = fs::OpenOptions::new()
             .append(true)
@@ -359,7 +855,16 @@ impl MemoryRouter {
         if !tags.is_empty() {
             writeln!(file, "# {}", tags.join(" "))?;
         }
-        writeln!(file, "{}\n", data)?;
+        match ttl_days {
+            Some(days) => writeln!(
+                file,
+                "{} [ttl:{}] {}\n",
+                Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+                days,
+                data
+            )?,
+            None => writeln!(file, "{}\n", data)?,
+        }
 
         Ok(())
     }
@@ -374,9 +879,7 @@ impl MemoryRouter {
             return Ok(HashMap::new());
         }
 
-        let mut file = fs::File::open(memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
+        let (content, _) = self.read_unexpired(&memory_file_path)?;
 
         let mut memories = HashMap::new();
         for entry in content.split("\n\n") {
@@ -387,7 +890,10 @@ impl MemoryRouter {
                         .split_whitespace()
                         .map(String::from)
                         .collect::<Vec<_>>();
-                    memories.insert(tags.join(" "), lines.map(String::from).collect());
+                    memories
+                        .entry(tags.join(" "))
+                        .or_insert_with(Vec::new)
+                        .extend(lines.map(String::from));
                 } else {
                     let entry_data: Vec<String> = std::iter::once(first_line.to_string())
                         .chain(lines.map(String::from))
@@ -430,7 +936,50 @@ impl MemoryRouter {
         Ok(())
     }
 
+    /// Move the memories of a category to a new category, which must not exist yet
+    pub fn rename_category(
+        &self,
+        old_category: &str,
+        new_category: &str,
+        is_global: bool,
+    ) -> io::Result<()> {
+        validate_category(old_category)?;
+        validate_category(new_category)?;
+
+        let old_path = self.get_memory_file(old_category, is_global);
+        let new_path = self.get_memory_file(new_category, is_global);
+        if !old_path.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Category '{}' does not exist", old_category),
+            ));
+        }
+        if new_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("Category '{}' already exists", new_category),
+            ));
+        }
+        if let Some(parent) = new_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(old_path, new_path)
+    }
+
     pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
+        if let Some(subtree) = category.strip_suffix('/') {
+            let base_dir = if is_global {
+                &self.global_memory_dir
+            } else {
+                &self.local_memory_dir
+            };
+            let subtree_dir = base_dir.join(subtree);
+            if subtree_dir.is_dir() {
+                fs::remove_dir_all(subtree_dir)?;
+            }
+            return Ok(());
+        }
+
         let memory_file_path = self.get_memory_file(category, is_global);
         if memory_file_path.exists() {
             fs::remove_file(memory_file_path)?;
@@ -450,6 +999,23 @@ impl MemoryRouter {
     }
 
     async fn execute_tool_call(&self, tool_call: ToolCall) -> Result<String, io::Error> {
+        let changes_memories = matches!(
+            tool_call.name.as_str(),
+            "remember_memory"
+                | "remove_memory_category"
+                | "remove_specific_memory"
+                | "expire_memories"
+                | "import_memories"
+                | "rename_category"
+        );
+        let result = self.run_tool_call(tool_call).await;
+        if changes_memories && result.is_ok() {
+            self.instructions_dirty.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+
+    async fn run_tool_call(&self, tool_call: ToolCall) -> Result<String, io::Error> {
         match tool_call.name.as_str() {
             "remember_memory" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
@@ -459,17 +1025,96 @@ impl MemoryRouter {
                         "Data must exist when remembering a memory",
                     )
                 })?;
-                self.remember("context", args.category, data, &args.tags, args.is_global)?;
+                let ttl_days = match tool_call.arguments.get("ttl_days") {
+                    None | Some(Value::Null) => None,
+                    Some(ttl) => {
+                        let days = ttl.as_u64().filter(|&days| days > 0).ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                "ttl_days must be a positive integer",
+                            )
+                        })?;
+                        Some(days.min(MAX_TTL_DAYS))
+                    }
+                };
+                self.remember(
+                    "context",
+                    args.category,
+                    data,
+                    &args.tags,
+                    args.is_global,
+                    ttl_days,
+                )?;
                 Ok(format!("Stored memory in category: {}", args.category))
             }
             "retrieve_memories" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let memories = if args.category == "*" {
-                    self.retrieve_all(args.is_global)?
+                let subtree = args
+                    .category
+                    .strip_suffix('/')
+                    .map(|prefix| format!("{}/*", glob::Pattern::escape(prefix)));
+                let pattern = subtree.as_deref().unwrap_or(args.category);
+                if pattern.contains(['*', '?', '[']) {
+                    let max_total_bytes = tool_call
+                        .arguments
+                        .get("max_total_bytes")
+                        .and_then(|v| v.as_u64())
+                        .map_or(DEFAULT_MAX_TOTAL_BYTES, |v| v as usize);
+                    return self.retrieve_matching(
+                        pattern,
+                        args.is_global,
+                        max_total_bytes,
+                        &args.tags,
+                    );
+                }
+                let mut memories = self.retrieve(args.category, args.is_global)?;
+                if !args.tags.is_empty() {
+                    memories.retain(|entry_tags, _| {
+                        entry_tags != "untagged" && has_any_tag(entry_tags, &args.tags)
+                    });
+                }
+                Ok(format!("Retrieved memories: {:?}", memories))
+            }
+            "list_memory_categories" => {
+                let is_global = MemoryArgs::is_global(&tool_call.arguments)?;
+                let tree = self.category_tree(is_global)?;
+                if tree.is_empty() {
+                    Ok(format!(
+                        "No {} memory categories",
+                        if is_global { "global" } else { "local" }
+                    ))
                 } else {
-                    self.retrieve(args.category, args.is_global)?
+                    Ok(tree)
+                }
+            }
+            "rename_category" => {
+                let param = |name: &str| {
+                    tool_call
+                        .arguments
+                        .get(name)
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                format!("{} must be a string", name),
+                            )
+                        })
                 };
-                Ok(format!("Retrieved memories: {:?}", memories))
+                let (old_category, new_category) = (param("old_category")?, param("new_category")?);
+                let is_global = MemoryArgs::is_global(&tool_call.arguments)?;
+                self.rename_category(old_category, new_category, is_global)?;
+                Ok(format!(
+                    "Renamed category {} to {}",
+                    old_category, new_category
+                ))
+            }
+            "list_categories" => {
+                let scopes = match tool_call.arguments.get("is_global") {
+                    Some(_) => vec![MemoryArgs::is_global(&tool_call.arguments)?],
+                    None => vec![false, true],
+                };
+                let counts = self.category_counts(&scopes)?;
+                Ok(serde_json::to_string_pretty(&counts)?)
             }
             "remove_memory_category" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
@@ -493,6 +1138,89 @@ impl MemoryRouter {
                     args.category
                 ))
             }
+            "search_memories" => {
+                let query = tool_call
+                    .arguments
+                    .get("query")
+                    .and_then(|q| q.as_str())
+                    .filter(|q| !q.is_empty())
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "Query must be a string")
+                    })?;
+                let scopes = match tool_call.arguments.get("is_global") {
+                    Some(_) => vec![MemoryArgs::is_global(&tool_call.arguments)?],
+                    None => vec![false, true],
+                };
+                let category = tool_call.arguments.get("category").and_then(|c| c.as_str());
+                let subtree = category
+                    .and_then(|c| c.strip_suffix('/'))
+                    .map(|prefix| format!("{}/*", glob::Pattern::escape(prefix)));
+                let results = self.search(query, &scopes, subtree.as_deref().or(category))?;
+                if results.is_empty() {
+                    Ok(format!("No memories matching '{}'", query))
+                } else {
+                    Ok(results)
+                }
+            }
+            "expire_memories" => {
+                let scopes = match tool_call.arguments.get("is_global") {
+                    Some(_) => vec![MemoryArgs::is_global(&tool_call.arguments)?],
+                    None => vec![false, true],
+                };
+                let mut expired = 0;
+                for is_global in scopes {
+                    expired += self.expire(is_global)?;
+                }
+                Ok(format!("Removed {} expired memories", expired))
+            }
+            "export_memories" | "import_memories" => {
+                let path_param = if tool_call.name == "export_memories" {
+                    "dest_path"
+                } else {
+                    "src_path"
+                };
+                let path = tool_call
+                    .arguments
+                    .get(path_param)
+                    .and_then(|p| p.as_str())
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("{} must be a string", path_param),
+                        )
+                    })?;
+                let home = etcetera::home_dir().map_err(|e| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("No home directory: {}", e))
+                })?;
+                let path = resolve_user_path(path, &home)?;
+                let is_global = MemoryArgs::is_global(&tool_call.arguments)?;
+
+                if tool_call.name == "export_memories" {
+                    let count = self.export(&path, is_global)?;
+                    return Ok(format!(
+                        "Exported {} categories to {}",
+                        count,
+                        path.display()
+                    ));
+                }
+                let replace = match tool_call.arguments.get("merge_strategy") {
+                    None => false,
+                    Some(Value::String(s)) if s == "merge" => false,
+                    Some(Value::String(s)) if s == "replace" => true,
+                    Some(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "merge_strategy must be 'merge' or 'replace'",
+                        ))
+                    }
+                };
+                let count = self.import(&path, is_global, replace)?;
+                Ok(format!(
+                    "Imported {} memories from {}",
+                    count,
+                    path.display()
+                ))
+            }
             _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "Unknown tool")),
         }
     }
@@ -505,7 +1233,7 @@ impl Router for MemoryRouter {
     }
 
     fn instructions(&self) -> String {
-        self.instructions.clone()
+        self.get_instructions()
     }
 
     fn capabilities(&self) -> ServerCapabilities {
@@ -564,6 +1292,83 @@ impl Router for MemoryRouter {
     }
 }
 
+/// Check that a category stays within the memory directory
+fn validate_category(category: &str) -> io::Result<()> {
+    // Nested categories are paths within the memory directory, which they must not leave
+    let mut components = Path::new(category).components().peekable();
+    if components.peek().is_none()
+        || !components.all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid category '{}': nested categories are names separated by '/', without '.' or '..'",
+                category
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Resolve a path given to export_memories or import_memories, which must be within `home`
+fn resolve_user_path(path: &str, home: &Path) -> io::Result<PathBuf> {
+    let outside = || {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("The path '{}' must be within the home directory", path),
+        )
+    };
+    let expanded = PathBuf::from(shellexpand::tilde(path).as_ref());
+    if !expanded.is_absolute()
+        || expanded
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(outside());
+    }
+
+    // The file itself may not exist yet, but its directory must, so links can be resolved
+    let file_name = expanded.file_name().ok_or_else(outside)?;
+    let dir = expanded
+        .parent()
+        .and_then(|dir| dir.canonicalize().ok())
+        .ok_or_else(outside)?;
+    if !dir.starts_with(home.canonicalize()?) {
+        return Err(outside());
+    }
+    Ok(dir.join(file_name))
+}
+
+/// Whether a memory stored with a TTL, whose first line after its tags starts with
+/// `{timestamp} [ttl:{days}]`, is older than its TTL. A TTL reaching past the latest
+/// representable time never expires.
+fn is_expired(entry: &str, now: DateTime<Utc>) -> bool {
+    let Some(line) = entry.lines().find(|line| !line.starts_with('#')) else {
+        return false;
+    };
+    let Some((timestamp, rest)) = line.split_once(' ') else {
+        return false;
+    };
+    let ttl_days = rest
+        .strip_prefix("[ttl:")
+        .and_then(|rest| rest.split_once(']'))
+        .and_then(|(days, _)| days.parse::<i64>().ok());
+    match (DateTime::parse_from_rfc3339(timestamp), ttl_days) {
+        (Ok(stored_at), Some(days)) => TimeDelta::try_days(days)
+            .and_then(|ttl| stored_at.checked_add_signed(ttl))
+            .is_some_and(|expires_at| expires_at <= now),
+        _ => false,
+    }
+}
+
+/// Whether the space-separated tags of a memory include one of `tags`, with or without a `#`
+fn has_any_tag(entry_tags: &str, tags: &[&str]) -> bool {
+    entry_tags.split_whitespace().any(|entry_tag| {
+        tags.iter()
+            .any(|tag| tag.trim_start_matches('#') == entry_tag.trim_start_matches('#'))
+    })
+}
+
 #[derive(Debug)]
 struct MemoryArgs<'a> {
     category: &'a str,
@@ -585,6 +1390,7 @@ impl<'a> MemoryArgs<'a> {
                 "Category must be a string",
             ));
         }
+        validate_category(category)?;
 
         let data = args.get("data").and_then(|d| d.as_str());
 
@@ -594,18 +1400,7 @@ impl<'a> MemoryArgs<'a> {
             _ => Vec::new(),
         };
 
-        let is_global = match &args.get("is_global") {
-            // Default to false if no is_global flag is provided
-            Some(Value::Bool(b)) => *b,
-            Some(Value::String(s)) => s.to_lowercase() == "true",
-            None => false,
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "is_global must be a boolean or string 'true'/'false'",
-                ))
-            }
-        };
+        let is_global = Self::is_global(args)?;
 
         Ok(Self {
             category,
@@ -614,4 +1409,573 @@ impl<'a> MemoryArgs<'a> {
             is_global,
         })
     }
+
+    fn is_global(args: &Value) -> Result<bool, io::Error> {
+        match &args.get("is_global") {
+            // Default to false if no is_global flag is provided
+            Some(Value::Bool(b)) => Ok(*b),
+            Some(Value::String(s)) => Ok(s.to_lowercase() == "true"),
+            None => Ok(false),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "is_global must be a boolean or string 'true'/'false'",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn router_in(dir: &TempDir) -> MemoryRouter {
+        MemoryRouter {
+            tools: Vec::new(),
+            base_instructions: String::new(),
+            instructions: Arc::new(Mutex::new(String::new())),
+            instructions_dirty: Arc::new(AtomicBool::new(false)),
+            global_memory_dir: dir.path().join("global"),
+            local_memory_dir: dir.path().join("local"),
+        }
+    }
+
+    async fn call(router: &MemoryRouter, name: &str, arguments: Value) -> io::Result<String> {
+        router
+            .execute_tool_call(ToolCall {
+                name: name.to_string(),
+                arguments,
+            })
+            .await
+    }
+
+    async fn retrieve(router: &MemoryRouter, arguments: Value) -> String {
+        call(router, "retrieve_memories", arguments).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_memories_by_pattern() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        fs::create_dir_all(&router.local_memory_dir).unwrap();
+        for category in ["alpha", "beta", "project_one", "project_two", "zeta"] {
+            router
+                .remember(
+                    "context",
+                    category,
+                    &format!("{} memory", category),
+                    &[],
+                    false,
+                    None,
+                )
+                .unwrap();
+        }
+        fs::write(router.local_memory_dir.join("notes.md"), "not a category").unwrap();
+
+        let all = retrieve(&router, json!({"category": "*", "is_global": false})).await;
+        assert!(all.starts_with("## Category: alpha\nalpha memory\n"));
+        assert_eq!(all.matches("## Category: ").count(), 5);
+        assert!(!all.contains("not a category"));
+
+        let projects = retrieve(
+            &router,
+            json!({"category": "project_*", "is_global": false}),
+        )
+        .await;
+        assert_eq!(
+            projects,
+            "## Category: project_one\nproject_one memory\n\n\n## Category: project_two\nproject_two memory\n\n\n"
+        );
+
+        let truncated = retrieve(
+            &router,
+            json!({"category": "*", "is_global": false, "max_total_bytes": 100}),
+        )
+        .await;
+        let (header, sections) = truncated.split_once("\n\n").unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(header).unwrap(),
+            json!({
+                "truncated": true,
+                "categories_included": ["alpha", "beta"],
+                "categories_omitted": ["project_one", "project_two", "zeta"],
+            })
+        );
+        assert!(sections.len() <= 100);
+        assert!(sections.contains("beta memory") && !sections.contains("zeta"));
+    }
+
+    #[tokio::test]
+    async fn test_nested_categories() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        for (category, data) in [
+            ("a/b/c", "c memory"),
+            ("a/b/d", "d memory"),
+            ("e", "e memory"),
+        ] {
+            call(
+                &router,
+                "remember_memory",
+                json!({"category": category, "data": data, "is_global": true}),
+            )
+            .await
+            .unwrap();
+        }
+        assert!(router.global_memory_dir.join("a/b/c.txt").is_file());
+
+        let subtree = retrieve(&router, json!({"category": "a/", "is_global": true})).await;
+        assert!(subtree.contains("## Category: a/b/c\nc memory"));
+        assert!(subtree.contains("## Category: a/b/d\nd memory"));
+        assert!(!subtree.contains("e memory"));
+
+        let tree = call(
+            &router,
+            "list_memory_categories",
+            json!({"is_global": true}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(tree, "a/\n  b/\n    c\n    d\ne");
+
+        for category in ["a/../secret", "../secret", "/etc/passwd", "./a", ""] {
+            let result = call(
+                &router,
+                "remember_memory",
+                json!({"category": category, "data": "x", "is_global": true}),
+            )
+            .await;
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        }
+        assert!(!dir.path().join("secret.txt").exists());
+
+        call(
+            &router,
+            "remove_memory_category",
+            json!({"category": "a/b/", "is_global": true}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(router.category_tree(true).unwrap(), "e");
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_memories_by_tags() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        for (data, tags) in [
+            ("Use black for formatting", json!(["formatting", "python"])),
+            ("Use rustfmt", json!(["#formatting"])),
+            ("Deploy with make release", json!(["deploy"])),
+            ("No tags here", json!([])),
+        ] {
+            call(
+                &router,
+                "remember_memory",
+                json!({"category": "development", "data": data, "tags": tags, "is_global": false}),
+            )
+            .await
+            .unwrap();
+        }
+
+        let result = retrieve(
+            &router,
+            json!({"category": "development", "is_global": false, "tags": ["formatting"]}),
+        )
+        .await;
+        assert!(result.contains("Use black for formatting"));
+        assert!(result.contains("Use rustfmt"));
+        assert!(!result.contains("Deploy"));
+        assert!(!result.contains("No tags here"));
+
+        let matching = retrieve(
+            &router,
+            json!({"category": "*", "is_global": false, "tags": ["deploy"]}),
+        )
+        .await;
+        assert_eq!(
+            matching,
+            "## Category: development\n# deploy\nDeploy with make release\n\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memories_expire_after_ttl() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        call(
+            &router,
+            "remember_memory",
+            json!({"category": "meetings", "data": "Agenda: roadmap review", "tags": ["agenda"], "ttl_days": 1, "is_global": false}),
+        )
+        .await
+        .unwrap();
+        call(
+            &router,
+            "remember_memory",
+            json!({"category": "meetings", "data": "Standup is at 9:30", "is_global": false}),
+        )
+        .await
+        .unwrap();
+
+        let result = retrieve(&router, json!({"category": "meetings", "is_global": false})).await;
+        assert!(result.contains("[ttl:1] Agenda: roadmap review"));
+
+        // Move the agenda back in time by two days
+        let path = router.local_memory_dir.join("meetings.txt");
+        let content = fs::read_to_string(&path).unwrap();
+        let stored_at = content.lines().nth(1).unwrap().split(' ').next().unwrap();
+        let two_days_ago =
+            (Utc::now() - chrono::Duration::days(2)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        fs::write(&path, content.replace(stored_at, &two_days_ago)).unwrap();
+
+        let result = retrieve(&router, json!({"category": "meetings", "is_global": false})).await;
+        assert!(!result.contains("Agenda"));
+        assert!(result.contains("Standup is at 9:30"));
+        assert!(!fs::read_to_string(&path).unwrap().contains("Agenda"));
+
+        // Expired memories in other categories are removed by expire_memories
+        fs::write(
+            router.local_memory_dir.join("keys.txt"),
+            format!("{} [ttl:1] temporary key\n\n", two_days_ago),
+        )
+        .unwrap();
+        let result = call(&router, "expire_memories", json!({})).await.unwrap();
+        assert_eq!(result, "Removed 1 expired memories");
+        assert_eq!(
+            fs::read_to_string(router.local_memory_dir.join("keys.txt")).unwrap(),
+            ""
+        );
+
+        let invalid = call(
+            &router,
+            "remember_memory",
+            json!({"category": "meetings", "data": "x", "ttl_days": -1, "is_global": false}),
+        )
+        .await;
+        assert_eq!(invalid.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_long_ttls_never_expire() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        call(
+            &router,
+            "remember_memory",
+            json!({"category": "facts", "data": "The sky is blue", "ttl_days": u64::MAX, "is_global": false}),
+        )
+        .await
+        .unwrap();
+        let path = router.local_memory_dir.join("facts.txt");
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains(&format!("[ttl:{}] The sky is blue", MAX_TTL_DAYS)));
+
+        // TTLs past the latest representable time, e.g. from files written by hand
+        let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        fs::write(
+            &path,
+            format!(
+                "{now} [ttl:{}] Water is wet\n\n{now} [ttl:{}] Fire is hot\n\n",
+                i64::MAX,
+                i64::MAX / 86_400
+            ),
+        )
+        .unwrap();
+        let result = retrieve(&router, json!({"category": "facts", "is_global": false})).await;
+        assert!(result.contains("Water is wet"));
+        assert!(result.contains("Fire is hot"));
+    }
+
+    #[tokio::test]
+    async fn test_reads_that_expire_memories_update_instructions() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        fs::create_dir_all(&router.local_memory_dir).unwrap();
+        let two_days_ago =
+            (Utc::now() - chrono::Duration::days(2)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        fs::write(
+            router.local_memory_dir.join("keys.txt"),
+            format!("{} [ttl:1] temporary key\n\n", two_days_ago),
+        )
+        .unwrap();
+
+        call(&router, "search_memories", json!({"query": "key"}))
+            .await
+            .unwrap();
+        assert!(router.instructions_dirty.load(Ordering::SeqCst));
+        assert!(!router.get_instructions().contains("temporary key"));
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_memories() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        for (category, data) in [
+            ("development", "Use black for formatting"),
+            ("development", "Run tests with cargo test"),
+            ("tools/editors", "Prefer vim"),
+        ] {
+            call(
+                &router,
+                "remember_memory",
+                json!({"category": category, "data": data, "tags": ["setup"], "is_global": true}),
+            )
+            .await
+            .unwrap();
+        }
+
+        let backup = dir.path().join("backup.json");
+        assert_eq!(router.export(&backup, true).unwrap(), 2);
+        assert_eq!(
+            serde_json::from_str::<Value>(&fs::read_to_string(&backup).unwrap()).unwrap(),
+            json!({
+                "development": ["# setup\nUse black for formatting", "# setup\nRun tests with cargo test"],
+                "tools/editors": ["# setup\nPrefer vim"],
+            })
+        );
+
+        // Another machine with one of the memories already
+        let other_dir = TempDir::new().unwrap();
+        let other = router_in(&other_dir);
+        call(
+            &other,
+            "remember_memory",
+            json!({"category": "development", "data": "Use black for formatting", "tags": ["setup"], "is_global": true}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(other.import(&backup, true, false).unwrap(), 2);
+        assert_eq!(
+            other
+                .retrieve_matching("*", true, DEFAULT_MAX_TOTAL_BYTES, &[])
+                .unwrap(),
+            router
+                .retrieve_matching("*", true, DEFAULT_MAX_TOTAL_BYTES, &[])
+                .unwrap()
+        );
+        // Merging again adds nothing, replacing rewrites the categories
+        assert_eq!(other.import(&backup, true, false).unwrap(), 0);
+        assert_eq!(other.import(&backup, true, true).unwrap(), 3);
+
+        fs::write(&backup, r#"{"../escape": ["x"]}"#).unwrap();
+        assert_eq!(
+            other.import(&backup, true, false).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_resolve_user_path() {
+        let home = TempDir::new().unwrap();
+        let home_path = home.path().canonicalize().unwrap();
+        fs::create_dir(home_path.join("backups")).unwrap();
+
+        let inside = home_path.join("backups/memories.json");
+        assert_eq!(
+            resolve_user_path(inside.to_str().unwrap(), &home_path).unwrap(),
+            inside
+        );
+
+        for path in [
+            "/etc/memories.json".to_string(),
+            "relative.json".to_string(),
+            format!("{}/../memories.json", home_path.display()),
+            format!("{}/missing/memories.json", home_path.display()),
+        ] {
+            assert_eq!(
+                resolve_user_path(&path, &home_path).unwrap_err().kind(),
+                io::ErrorKind::PermissionDenied,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_categories() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        for (category, data, is_global) in [
+            ("development", "Use black for formatting", false),
+            ("development", "Run tests with cargo test", false),
+            ("project/backend", "Postgres 16", false),
+            ("personal", "Name is Sam", true),
+        ] {
+            call(
+                &router,
+                "remember_memory",
+                json!({"category": category, "data": data, "tags": ["setup"], "is_global": is_global}),
+            )
+            .await
+            .unwrap();
+        }
+
+        let all = call(&router, "list_categories", json!({})).await.unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&all).unwrap(),
+            json!([
+                {"category": "development", "scope": "local", "entries": 2},
+                {"category": "project/backend", "scope": "local", "entries": 1},
+                {"category": "personal", "scope": "global", "entries": 1},
+            ])
+        );
+
+        let global = call(&router, "list_categories", json!({"is_global": true}))
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&global).unwrap(),
+            json!([{"category": "personal", "scope": "global", "entries": 1}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_category() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        call(
+            &router,
+            "remember_memory",
+            json!({"category": "dev", "data": "Use black for formatting", "tags": ["python"], "is_global": false}),
+        )
+        .await
+        .unwrap();
+        call(
+            &router,
+            "remember_memory",
+            json!({"category": "personal", "data": "Name is Sam", "is_global": false}),
+        )
+        .await
+        .unwrap();
+        let before = retrieve(&router, json!({"category": "dev", "is_global": false})).await;
+
+        let result = call(
+            &router,
+            "rename_category",
+            json!({"old_category": "dev", "new_category": "development", "is_global": false}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result, "Renamed category dev to development");
+        assert!(!router.local_memory_dir.join("dev.txt").exists());
+        assert_eq!(
+            retrieve(
+                &router,
+                json!({"category": "development", "is_global": false})
+            )
+            .await,
+            before
+        );
+
+        for (old, new, kind) in [
+            (
+                "development",
+                "../../../etc/passwd",
+                io::ErrorKind::InvalidInput,
+            ),
+            ("development", "a/../b", io::ErrorKind::InvalidInput),
+            ("missing", "other", io::ErrorKind::NotFound),
+            ("development", "personal", io::ErrorKind::AlreadyExists),
+        ] {
+            let result = call(
+                &router,
+                "rename_category",
+                json!({"old_category": old, "new_category": new, "is_global": false}),
+            )
+            .await;
+            assert_eq!(result.unwrap_err().kind(), kind, "{} -> {}", old, new);
+        }
+
+        // Any category the other tools accept can be renamed to
+        call(
+            &router,
+            "rename_category",
+            json!({"old_category": "development", "new_category": "my notes", "is_global": false}),
+        )
+        .await
+        .unwrap();
+        assert!(router.local_memory_dir.join("my notes.txt").is_file());
+    }
+
+    #[tokio::test]
+    async fn test_search_memories() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        for (category, data, is_global) in [
+            ("development", "Use black to format Python code", false),
+            ("development", "Run tests with cargo test", false),
+            ("tools/editors", "Prefer python-mode in emacs", true),
+            ("personal", "Name is Sam", true),
+        ] {
+            call(
+                &router,
+                "remember_memory",
+                json!({"category": category, "data": data, "tags": ["python"], "is_global": is_global}),
+            )
+            .await
+            .unwrap();
+        }
+
+        let results = call(&router, "search_memories", json!({"query": "python"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            results,
+            "## Local: development\n- Use black to format Python code\n\n\
+             ## Global: tools/editors\n- Prefer python-mode in emacs"
+        );
+
+        let global = call(
+            &router,
+            "search_memories",
+            json!({"query": "PYTHON", "is_global": true}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            global,
+            "## Global: tools/editors\n- Prefer python-mode in emacs"
+        );
+
+        let in_category = call(
+            &router,
+            "search_memories",
+            json!({"query": "python", "category": "personal"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(in_category, "No memories matching 'python'");
+    }
+
+    #[tokio::test]
+    async fn test_instructions_include_new_memories() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        assert!(!router.instructions().contains("Use black for formatting"));
+
+        // Tool calls run on a clone of the router, which shares its instructions
+        router
+            .clone()
+            .call_tool(
+                "remember_memory",
+                json!({"category": "development", "data": "Use black for formatting", "is_global": false}),
+            )
+            .await
+            .unwrap();
+        let instructions = router.instructions();
+        assert!(instructions.contains("Local Memories:"));
+        assert!(instructions.contains("Category: development"));
+        assert!(instructions.contains("- Use black for formatting"));
+
+        call(
+            &router,
+            "remove_memory_category",
+            json!({"category": "development", "is_global": false}),
+        )
+        .await
+        .unwrap();
+        assert!(!router.instructions().contains("Use black for formatting"));
+    }
 }
@@ -0,0 +1,484 @@
+use std::iter::Peekable;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+
+/// Notion rejects rich text objects with more than this many characters
+const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Render Notion blocks as Markdown
+///
+/// Blocks may carry their nested blocks in a `children` field, which are indented below
+/// list items and rendered in place for other blocks. Block types without a Markdown
+/// equivalent are skipped.
+pub fn blocks_to_markdown(blocks: &[Value]) -> String {
+    let mut lines = Vec::new();
+    render_blocks(blocks, 0, &mut lines);
+    lines.join("\n").trim_end().to_string()
+}
+
+fn render_blocks(blocks: &[Value], depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    let mut number = 0;
+    for (index, block) in blocks.iter().enumerate() {
+        let block_type = block["type"].as_str().unwrap_or_default();
+        let data = &block[block_type];
+        let text = rich_text_to_markdown(&data["rich_text"]);
+
+        number = if block_type == "numbered_list_item" {
+            number + 1
+        } else {
+            0
+        };
+        let list_item = is_list_item(block);
+
+        let rendered = match block_type {
+            "paragraph" => Some(text),
+            "heading_1" => Some(format!("# {}", text)),
+            "heading_2" => Some(format!("## {}", text)),
+            "heading_3" => Some(format!("### {}", text)),
+            "bulleted_list_item" | "toggle" => Some(format!("- {}", text)),
+            "numbered_list_item" => Some(format!("{}. {}", number, text)),
+            "to_do" => {
+                let checked = data["checked"].as_bool().unwrap_or(false);
+                Some(format!("- [{}] {}", if checked { "x" } else { " " }, text))
+            }
+            "quote" | "callout" => Some(format!("> {}", text.replace('\n', "\n> "))),
+            "code" => {
+                let language = match data["language"].as_str().unwrap_or_default() {
+                    "plain text" => "",
+                    language => language,
+                };
+                Some(format!(
+                    "```{}\n{}\n```",
+                    language,
+                    plain_text(&data["rich_text"])
+                ))
+            }
+            "divider" => Some("---".to_string()),
+            "image" => {
+                let url = data["external"]["url"]
+                    .as_str()
+                    .or_else(|| data["file"]["url"].as_str())
+                    .unwrap_or_default();
+                Some(format!("![{}]({})", plain_text(&data["caption"]), url))
+            }
+            "bookmark" | "embed" | "link_preview" => {
+                data["url"].as_str().map(|url| format!("<{}>", url))
+            }
+            "child_page" | "child_database" => {
+                data["title"].as_str().map(|title| format!("**{}**", title))
+            }
+            _ => None,
+        };
+
+        if let Some(rendered) = rendered {
+            for line in rendered.lines() {
+                lines.push(format!("{}{}", indent, line));
+            }
+            // Items of the same list stay together, other blocks are separated
+            if !list_item {
+                lines.push(String::new());
+            }
+        }
+
+        if let Some(children) = block["children"].as_array() {
+            if list_item {
+                render_blocks(children, depth + 1, lines);
+            } else {
+                render_blocks(children, depth, lines);
+            }
+        }
+
+        // A blank line ends a list
+        let next_is_list_item = blocks.get(index + 1).is_some_and(is_list_item);
+        if list_item && !next_is_list_item && depth == 0 {
+            lines.push(String::new());
+        }
+    }
+}
+
+fn is_list_item(block: &Value) -> bool {
+    matches!(
+        block["type"].as_str(),
+        Some("bulleted_list_item" | "numbered_list_item" | "to_do" | "toggle")
+    )
+}
+
+/// The text of a rich text array, without formatting
+pub fn plain_text(rich_text: &Value) -> String {
+    rich_text
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| {
+                    part["plain_text"]
+                        .as_str()
+                        .or(part["text"]["content"].as_str())
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The text of a rich text array, with its annotations and links as Markdown
+fn rich_text_to_markdown(rich_text: &Value) -> String {
+    let Some(parts) = rich_text.as_array() else {
+        return String::new();
+    };
+    parts
+        .iter()
+        .map(|part| {
+            let mut text = part["plain_text"]
+                .as_str()
+                .or(part["text"]["content"].as_str())
+                .unwrap_or_default()
+                .to_string();
+            if text.trim().is_empty() {
+                return text;
+            }
+            let annotations = &part["annotations"];
+            let annotated = |name: &str| annotations[name].as_bool().unwrap_or(false);
+            if annotated("code") {
+                text = format!("`{}`", text);
+            }
+            if annotated("bold") {
+                text = format!("**{}**", text);
+            }
+            if annotated("italic") {
+                text = format!("*{}*", text);
+            }
+            if annotated("strikethrough") {
+                text = format!("~~{}~~", text);
+            }
+            if let Some(url) = part["href"]
+                .as_str()
+                .or(part["text"]["link"]["url"].as_str())
+            {
+                text = format!("[{}]({})", text, url);
+            }
+            text
+        })
+        .collect()
+}
+
+static NUMBERED_ITEM: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+[.)] ").unwrap());
+static INLINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"`([^`]+)`|\*\*([^*]+)\*\*|\*([^*]+)\*|~~([^~]+)~~|\[([^\]]+)\]\(([^)\s]+)\)")
+        .unwrap()
+});
+
+/// Convert Markdown to Notion blocks
+///
+/// Supports headings, bulleted, numbered and to-do lists (nested by indentation), quotes,
+/// fenced code blocks, dividers and paragraphs, with bold, italic, strikethrough, code and
+/// links inline.
+pub fn markdown_to_blocks(markdown: &str) -> Vec<Value> {
+    // Blocks with the indentation of list items, to nest deeper items below them
+    let mut blocks: Vec<(usize, Value)> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut lines = markdown.lines();
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<(usize, Value)>| {
+        if !paragraph.is_empty() {
+            blocks.push((0, text_block("paragraph", &paragraph.join("\n"))));
+            paragraph.clear();
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some(language) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .collect();
+            blocks.push((0, code_block(language.trim(), &code.join("\n"))));
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+            continue;
+        }
+
+        let block = if let Some(text) = trimmed.strip_prefix("### ") {
+            text_block("heading_3", text)
+        } else if let Some(text) = trimmed.strip_prefix("## ") {
+            text_block("heading_2", text)
+        } else if let Some(text) = trimmed.strip_prefix("# ") {
+            text_block("heading_1", text)
+        } else if let Some(text) = trimmed
+            .strip_prefix("- [ ] ")
+            .or_else(|| trimmed.strip_prefix("* [ ] "))
+        {
+            to_do_block(text, false)
+        } else if let Some(text) = trimmed
+            .strip_prefix("- [x] ")
+            .or_else(|| trimmed.strip_prefix("- [X] "))
+            .or_else(|| trimmed.strip_prefix("* [x] "))
+        {
+            to_do_block(text, true)
+        } else if let Some(text) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            text_block("bulleted_list_item", text)
+        } else if let Some(found) = NUMBERED_ITEM.find(trimmed) {
+            text_block("numbered_list_item", &trimmed[found.end()..])
+        } else if let Some(text) = trimmed.strip_prefix("> ") {
+            text_block("quote", text)
+        } else if matches!(trimmed.trim_end(), "---" | "***" | "___") {
+            json!({"object": "block", "type": "divider", "divider": {}})
+        } else {
+            paragraph.push(trimmed);
+            continue;
+        };
+        flush(&mut paragraph, &mut blocks);
+        let indent = if is_list_item(&block) { indent + 1 } else { 0 };
+        blocks.push((indent, block));
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    nest(&mut blocks.into_iter().peekable(), 0)
+}
+
+/// Move list items indented below another list item into its children
+///
+/// List items have their indentation plus one, other blocks zero, so only list items are
+/// nested and other blocks end a list.
+fn nest(blocks: &mut Peekable<impl Iterator<Item = (usize, Value)>>, parent: usize) -> Vec<Value> {
+    let mut nested = Vec::new();
+    while let Some((indent, _)) = blocks.peek() {
+        if parent > 0 && *indent <= parent {
+            break;
+        }
+        let Some((indent, mut block)) = blocks.next() else {
+            break;
+        };
+        if indent > 0 {
+            let children = nest(blocks, indent);
+            if !children.is_empty() {
+                let block_type = block["type"].as_str().unwrap_or_default().to_string();
+                block[block_type.as_str()]["children"] = Value::Array(children);
+            }
+        }
+        nested.push(block);
+    }
+    nested
+}
+
+fn text_block(block_type: &str, text: &str) -> Value {
+    json!({
+        "object": "block",
+        "type": block_type,
+        block_type: {"rich_text": markdown_to_rich_text(text)}
+    })
+}
+
+fn to_do_block(text: &str, checked: bool) -> Value {
+    json!({
+        "object": "block",
+        "type": "to_do",
+        "to_do": {"rich_text": markdown_to_rich_text(text), "checked": checked}
+    })
+}
+
+fn code_block(language: &str, code: &str) -> Value {
+    let language = match language.to_lowercase().as_str() {
+        "" | "text" | "txt" => "plain text".to_string(),
+        "js" => "javascript".to_string(),
+        "ts" => "typescript".to_string(),
+        "py" => "python".to_string(),
+        "rs" => "rust".to_string(),
+        "sh" | "bash" | "zsh" => "shell".to_string(),
+        "yml" => "yaml".to_string(),
+        language => language.to_string(),
+    };
+    json!({
+        "object": "block",
+        "type": "code",
+        "code": {"rich_text": plain_rich_text(code, json!({})), "language": language}
+    })
+}
+
+/// Convert a line of Markdown to a rich text array
+pub fn markdown_to_rich_text(text: &str) -> Vec<Value> {
+    let mut rich_text = Vec::new();
+    let mut last = 0;
+    for captures in INLINE.captures_iter(text) {
+        let found = captures.get(0).unwrap();
+        rich_text.extend(plain_rich_text(&text[last..found.start()], json!({})));
+        let (content, annotations, link) = if let Some(code) = captures.get(1) {
+            (code.as_str(), json!({"code": true}), None)
+        } else if let Some(bold) = captures.get(2) {
+            (bold.as_str(), json!({"bold": true}), None)
+        } else if let Some(italic) = captures.get(3) {
+            (italic.as_str(), json!({"italic": true}), None)
+        } else if let Some(strikethrough) = captures.get(4) {
+            (strikethrough.as_str(), json!({"strikethrough": true}), None)
+        } else {
+            (
+                captures.get(5).unwrap().as_str(),
+                json!({}),
+                captures.get(6).map(|url| url.as_str()),
+            )
+        };
+        let mut parts = plain_rich_text(content, annotations);
+        if let Some(url) = link {
+            for part in &mut parts {
+                part["text"]["link"] = json!({"url": url});
+            }
+        }
+        rich_text.extend(parts);
+        last = found.end();
+    }
+    rich_text.extend(plain_rich_text(&text[last..], json!({})));
+    rich_text
+}
+
+/// Rich text for plain text, split into parts Notion accepts
+fn plain_rich_text(text: &str, annotations: Value) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_TEXT_LENGTH)
+        .map(|chunk| {
+            let mut part = json!({
+                "type": "text",
+                "text": {"content": chunk.iter().collect::<String>()}
+            });
+            if annotations.as_object().is_some_and(|a| !a.is_empty()) {
+                part["annotations"] = annotations.clone();
+            }
+            part
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rich(text: &str) -> Value {
+        json!([{"type": "text", "plain_text": text, "text": {"content": text}}])
+    }
+
+    #[test]
+    fn test_blocks_to_markdown() {
+        let blocks = vec![
+            json!({"type": "heading_1", "heading_1": {"rich_text": rich("Title")}}),
+            json!({"type": "paragraph", "paragraph": {"rich_text": [
+                {"plain_text": "Some ", "annotations": {}},
+                {"plain_text": "bold", "annotations": {"bold": true}},
+                {"plain_text": " and ", "annotations": {}},
+                {"plain_text": "docs", "annotations": {}, "href": "https://example.com"}
+            ]}}),
+            json!({"type": "heading_2", "heading_2": {"rich_text": rich("List")}}),
+            json!({
+                "type": "bulleted_list_item",
+                "bulleted_list_item": {"rich_text": rich("First")},
+                "children": [
+                    {"type": "bulleted_list_item", "bulleted_list_item": {"rich_text": rich("Nested")}}
+                ]
+            }),
+            json!({"type": "bulleted_list_item", "bulleted_list_item": {"rich_text": rich("Second")}}),
+            json!({"type": "numbered_list_item", "numbered_list_item": {"rich_text": rich("One")}}),
+            json!({"type": "numbered_list_item", "numbered_list_item": {"rich_text": rich("Two")}}),
+            json!({"type": "to_do", "to_do": {"rich_text": rich("Done"), "checked": true}}),
+            json!({"type": "heading_3", "heading_3": {"rich_text": rich("Code")}}),
+            json!({"type": "code", "code": {"rich_text": rich("fn main() {\n    println!(\"hi\");\n}"), "language": "rust"}}),
+            json!({"type": "divider", "divider": {}}),
+            json!({"type": "unsupported", "unsupported": {}}),
+        ];
+
+        assert_eq!(
+            blocks_to_markdown(&blocks),
+            indoc::indoc! {r#"
+                # Title
+
+                Some **bold** and [docs](https://example.com)
+
+                ## List
+
+                - First
+                  - Nested
+                - Second
+                1. One
+                2. Two
+                - [x] Done
+
+                ### Code
+
+                ```rust
+                fn main() {
+                    println!("hi");
+                }
+                ```
+
+                ---"#}
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_blocks() {
+        let blocks = markdown_to_blocks(indoc::indoc! {r#"
+            # Title
+            Some **bold** text
+            continued
+
+            - First
+              - Nested
+            - [ ] Todo
+            2. Numbered
+
+            ```py
+            print("hi")
+            ```
+        "#});
+
+        let types: Vec<&str> = blocks
+            .iter()
+            .map(|block| block["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "heading_1",
+                "paragraph",
+                "bulleted_list_item",
+                "to_do",
+                "numbered_list_item",
+                "code"
+            ]
+        );
+        assert_eq!(
+            blocks[1]["paragraph"]["rich_text"],
+            json!([
+                {"type": "text", "text": {"content": "Some "}},
+                {"type": "text", "text": {"content": "bold"}, "annotations": {"bold": true}},
+                {"type": "text", "text": {"content": " text\ncontinued"}}
+            ])
+        );
+        assert_eq!(
+            plain_text(
+                &blocks[2]["bulleted_list_item"]["children"][0]["bulleted_list_item"]["rich_text"]
+            ),
+            "Nested"
+        );
+        assert_eq!(blocks[3]["to_do"]["checked"], json!(false));
+        assert_eq!(blocks[5]["code"]["language"], json!("python"));
+        assert_eq!(plain_text(&blocks[5]["code"]["rich_text"]), "print(\"hi\")");
+    }
+
+    #[test]
+    fn test_long_text_is_split() {
+        let blocks = markdown_to_blocks(&"x".repeat(4500));
+        let rich_text = blocks[0]["paragraph"]["rich_text"].as_array().unwrap();
+        assert_eq!(rich_text.len(), 3);
+        assert_eq!(plain_text(&blocks[0]["paragraph"]["rich_text"]).len(), 4500);
+    }
+}
@@ -0,0 +1,1059 @@
+mod markdown;
+
+use indoc::indoc;
+use reqwest::{Client, Method};
+use serde_json::{json, Map, Value};
+use std::{future::Future, pin::Pin};
+
+use crate::util::{json_content, limit, required_str, send_json};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+pub use self::markdown::{blocks_to_markdown, markdown_to_blocks};
+use self::markdown::{markdown_to_rich_text, plain_text};
+
+const NOTION_API_URL: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Notion accepts at most this many blocks per request
+const MAX_BLOCKS_PER_REQUEST: usize = 100;
+const DEFAULT_LIMIT: u64 = 20;
+
+/// Router for the Notion API, authenticated with NOTION_API_KEY
+#[derive(Clone)]
+pub struct NotionRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    client: Client,
+    api_key: Option<String>,
+    base_url: String,
+}
+
+impl Default for NotionRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NotionRouter {
+    pub fn new() -> Self {
+        Self::with_base_url(
+            std::env::var("NOTION_API_KEY").ok(),
+            NOTION_API_URL.to_string(),
+        )
+    }
+
+    /// Create a router using the given API key and Notion API url
+    pub fn with_base_url(api_key: Option<String>, base_url: String) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let writing = |title: &str, destructive: bool| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: false,
+                destructive_hint: destructive,
+                idempotent_hint: false,
+                open_world_hint: true,
+            })
+        };
+        let limit = json!({
+            "type": "integer",
+            "default": DEFAULT_LIMIT,
+            "description": "The maximum number of results"
+        });
+
+        let search_pages = Tool::new(
+            "search_pages",
+            "Search the titles and content of the Notion pages shared with the integration. Returns the id, title and url of each page.",
+            json!({
+                "type": "object",
+                "required": ["query"],
+                "properties": {
+                    "query": {"type": "string", "description": "The text to search for"},
+                    "limit": limit
+                }
+            }),
+            read_only("Search Notion pages"),
+        );
+
+        let get_page = Tool::new(
+            "get_page",
+            "Get the title and content of a Notion page as Markdown.",
+            json!({
+                "type": "object",
+                "required": ["page_id"],
+                "properties": {
+                    "page_id": {"type": "string", "description": "The id of the page"}
+                }
+            }),
+            read_only("Get a Notion page"),
+        );
+
+        let create_page = Tool::new(
+            "create_page",
+            indoc! {r#"
+                Create a Notion page with a title and Markdown content, either as a child of a
+                page (parent_page_id) or as an entry of a database (database_id).
+                Returns the id and url of the new page.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["title"],
+                "properties": {
+                    "title": {"type": "string"},
+                    "content": {"type": "string", "description": "The content as Markdown"},
+                    "parent_page_id": {"type": "string"},
+                    "database_id": {"type": "string"}
+                }
+            }),
+            writing("Create a Notion page", false),
+        );
+
+        let update_page = Tool::new(
+            "update_page",
+            "Append Markdown content to a Notion page, or overwrite its content.",
+            json!({
+                "type": "object",
+                "required": ["page_id", "content"],
+                "properties": {
+                    "page_id": {"type": "string"},
+                    "content": {"type": "string", "description": "The content as Markdown"},
+                    "mode": {
+                        "type": "string",
+                        "enum": ["append", "overwrite"],
+                        "default": "append"
+                    }
+                }
+            }),
+            writing("Update a Notion page", true),
+        );
+
+        let list_databases = Tool::new(
+            "list_databases",
+            "List the Notion databases shared with the integration. Returns the id, title and url of each database.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "limit": limit
+                }
+            }),
+            read_only("List Notion databases"),
+        );
+
+        let query_database = Tool::new(
+            "query_database",
+            indoc! {r#"
+                Query the entries of a Notion database, returned as CSV with a column for the
+                id and each property. The filter and sorts use the Notion API format, e.g.
+                {"property": "Status", "status": {"equals": "Done"}} and
+                [{"property": "Due", "direction": "ascending"}].
+            "#},
+            json!({
+                "type": "object",
+                "required": ["database_id"],
+                "properties": {
+                    "database_id": {"type": "string"},
+                    "filter": {"type": "object"},
+                    "sorts": {"type": "array", "items": {"type": "object"}},
+                    "limit": {
+                        "type": "integer",
+                        "default": 100,
+                        "description": "The maximum number of entries"
+                    }
+                }
+            }),
+            read_only("Query a Notion database"),
+        );
+
+        let properties = json!({
+            "type": "object",
+            "description": "Property values by property name: text for title, rich text, select, status, url, email and phone number properties, a number, a boolean for checkboxes, a list of names for multi-select, and an ISO 8601 date"
+        });
+        let create_database_entry = Tool::new(
+            "create_database_entry",
+            "Add an entry to a Notion database. Returns the id and url of the entry.",
+            json!({
+                "type": "object",
+                "required": ["database_id", "properties"],
+                "properties": {
+                    "database_id": {"type": "string"},
+                    "properties": properties
+                }
+            }),
+            writing("Create a Notion database entry", false),
+        );
+
+        let update_database_entry = Tool::new(
+            "update_database_entry",
+            "Change properties of a Notion database entry.",
+            json!({
+                "type": "object",
+                "required": ["page_id", "properties"],
+                "properties": {
+                    "page_id": {"type": "string", "description": "The id of the entry"},
+                    "properties": properties
+                }
+            }),
+            writing("Update a Notion database entry", true),
+        );
+
+        let instructions = indoc! {r#"
+            The Notion extension reads and edits the pages and databases shared with the Notion
+            integration whose key is in NOTION_API_KEY.
+
+            Page content is exchanged as Markdown. Use search_pages or list_databases to find ids,
+            and query_database to read the entries of a database as CSV.
+        "#}
+        .to_string();
+
+        Self {
+            tools: vec![
+                search_pages,
+                get_page,
+                create_page,
+                update_page,
+                list_databases,
+                query_database,
+                create_database_entry,
+                update_database_entry,
+            ],
+            instructions,
+            client: Client::new(),
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // Helper method to send a request to the Notion API and parse the response
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value, ToolError> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "NOTION_API_KEY is not set. Create an integration at https://www.notion.so/my-integrations and share pages with it.".into(),
+            )
+        })?;
+
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path))
+            .bearer_auth(api_key)
+            .header("Notion-Version", NOTION_VERSION);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let (status, body) = send_json(request, "Notion").await?;
+        if !status.is_success() {
+            let message = body["message"].as_str().unwrap_or("unknown error");
+            return Err(ToolError::ExecutionError(format!(
+                "Notion API error ({}): {}",
+                status, message
+            )));
+        }
+        Ok(body)
+    }
+
+    // Helper method to collect the results of a paginated list, up to limit results
+    async fn paginate(
+        &self,
+        method: Method,
+        path: &str,
+        body: Value,
+        limit: usize,
+    ) -> Result<Vec<Value>, ToolError> {
+        let mut results = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page_size = (limit - results.len()).min(100);
+            let response = if method == Method::GET {
+                let mut query = format!("{}?page_size={}", path, page_size);
+                if let Some(cursor) = &cursor {
+                    query.push_str(&format!("&start_cursor={}", cursor));
+                }
+                self.request(Method::GET, &query, None).await?
+            } else {
+                let mut body = body.clone();
+                body["page_size"] = json!(page_size);
+                if let Some(cursor) = &cursor {
+                    body["start_cursor"] = json!(cursor);
+                }
+                self.request(method.clone(), path, Some(body)).await?
+            };
+
+            results.extend(response["results"].as_array().cloned().unwrap_or_default());
+            cursor = response["next_cursor"].as_str().map(String::from);
+            if cursor.is_none() || !response["has_more"].as_bool().unwrap_or(false) {
+                break;
+            }
+            if results.len() >= limit {
+                break;
+            }
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    // Helper method to get the blocks of a page or block, with their nested blocks
+    fn get_blocks<'a>(
+        &'a self,
+        block_id: &'a str,
+        depth: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ToolError>> + Send + 'a>> {
+        Box::pin(async move {
+            let path = format!("/blocks/{}/children", block_id);
+            let mut blocks = self
+                .paginate(Method::GET, &path, Value::Null, usize::MAX)
+                .await?;
+            // Nested blocks take a request each, so only a few levels are followed
+            if depth < 3 {
+                for block in &mut blocks {
+                    if block["has_children"].as_bool().unwrap_or(false) {
+                        let id = block["id"].as_str().unwrap_or_default().to_string();
+                        block["children"] = Value::Array(self.get_blocks(&id, depth + 1).await?);
+                    }
+                }
+            }
+            Ok(blocks)
+        })
+    }
+
+    // Helper method to append blocks to a page, in as many requests as needed
+    async fn append_blocks(&self, page_id: &str, blocks: Vec<Value>) -> Result<(), ToolError> {
+        for chunk in blocks.chunks(MAX_BLOCKS_PER_REQUEST) {
+            self.request(
+                Method::PATCH,
+                &format!("/blocks/{}/children", page_id),
+                Some(json!({"children": chunk})),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn search(&self, object: &str, query: &str, limit: usize) -> Result<Value, ToolError> {
+        let body = json!({
+            "query": query,
+            "filter": {"property": "object", "value": object}
+        });
+        let results = self.paginate(Method::POST, "/search", body, limit).await?;
+        Ok(Value::Array(
+            results
+                .iter()
+                .map(|result| {
+                    let title = if object == "database" {
+                        plain_text(&result["title"])
+                    } else {
+                        page_title(result)
+                    };
+                    json!({"id": result["id"], "title": title, "url": result["url"]})
+                })
+                .collect(),
+        ))
+    }
+
+    async fn search_pages(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let query = required_str(&params, "query")?;
+        let results = self
+            .search("page", query, limit(&params, DEFAULT_LIMIT))
+            .await?;
+        Ok(vec![json_content(&results)])
+    }
+
+    async fn list_databases(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let results = self
+            .search("database", "", limit(&params, DEFAULT_LIMIT))
+            .await?;
+        Ok(vec![json_content(&results)])
+    }
+
+    async fn get_page(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let page = self
+            .request(Method::GET, &format!("/pages/{}", page_id), None)
+            .await?;
+        let blocks = self.get_blocks(page_id, 0).await?;
+
+        let content = blocks_to_markdown(&blocks);
+        Ok(vec![Content::text(format!(
+            "# {}\n\n{}",
+            page_title(&page),
+            content
+        ))])
+    }
+
+    async fn create_page(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let title = required_str(&params, "title")?;
+        let blocks = markdown_to_blocks(params["content"].as_str().unwrap_or_default());
+
+        let (parent, title_property) = match (
+            params["parent_page_id"].as_str(),
+            params["database_id"].as_str(),
+        ) {
+            (Some(page_id), None) => (json!({"page_id": page_id}), "title".to_string()),
+            (None, Some(database_id)) => {
+                let database = self
+                    .request(Method::GET, &format!("/databases/{}", database_id), None)
+                    .await?;
+                let title_property = database["properties"]
+                    .as_object()
+                    .and_then(|properties| {
+                        properties
+                            .iter()
+                            .find(|(_, property)| property["type"] == "title")
+                            .map(|(name, _)| name.clone())
+                    })
+                    .unwrap_or_else(|| "Name".to_string());
+                (json!({"database_id": database_id}), title_property)
+            }
+            _ => {
+                return Err(ToolError::InvalidParameters(
+                    "Provide exactly one of 'parent_page_id' and 'database_id'".into(),
+                ))
+            }
+        };
+
+        let mut remaining = blocks;
+        let first: Vec<Value> = remaining
+            .drain(..remaining.len().min(MAX_BLOCKS_PER_REQUEST))
+            .collect();
+        let page = self
+            .request(
+                Method::POST,
+                "/pages",
+                Some(json!({
+                    "parent": parent,
+                    "properties": {
+                        title_property: {"title": markdown_to_rich_text(title)}
+                    },
+                    "children": first
+                })),
+            )
+            .await?;
+        let page_id = page["id"].as_str().unwrap_or_default();
+        self.append_blocks(page_id, remaining).await?;
+
+        Ok(vec![json_content(
+            &json!({"id": page["id"], "url": page["url"]}),
+        )])
+    }
+
+    async fn update_page(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let content = required_str(&params, "content")?;
+        let overwrite = match params["mode"].as_str().unwrap_or("append") {
+            "append" => false,
+            "overwrite" => true,
+            mode => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Invalid mode: {}. Valid modes are: 'append', 'overwrite'",
+                    mode
+                )))
+            }
+        };
+
+        let existing = if overwrite {
+            let path = format!("/blocks/{}/children", page_id);
+            self.paginate(Method::GET, &path, Value::Null, usize::MAX)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let blocks = markdown_to_blocks(content);
+        let added = blocks.len();
+        self.append_blocks(page_id, blocks).await?;
+
+        // The old blocks are only removed once the new ones are added, so that a
+        // failure never leaves the page empty
+        let mut removed = 0;
+        for block in existing {
+            let id = block["id"].as_str().unwrap_or_default();
+            self.request(Method::DELETE, &format!("/blocks/{}", id), None)
+                .await?;
+            removed += 1;
+        }
+
+        Ok(vec![Content::text(if overwrite {
+            format!("Replaced {} blocks with {} blocks", removed, added)
+        } else {
+            format!("Appended {} blocks", added)
+        })])
+    }
+
+    async fn query_database(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let database_id = required_str(&params, "database_id")?;
+        let mut body = json!({});
+        if let Some(filter) = params.get("filter").filter(|filter| !filter.is_null()) {
+            body["filter"] = filter.clone();
+        }
+        if let Some(sorts) = params.get("sorts").filter(|sorts| !sorts.is_null()) {
+            body["sorts"] = sorts.clone();
+        }
+
+        let rows = self
+            .paginate(
+                Method::POST,
+                &format!("/databases/{}/query", database_id),
+                body,
+                limit(&params, 100),
+            )
+            .await?;
+        Ok(vec![Content::text(rows_to_csv(&rows))])
+    }
+
+    async fn create_database_entry(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let database_id = required_str(&params, "database_id")?;
+        let database = self
+            .request(Method::GET, &format!("/databases/{}", database_id), None)
+            .await?;
+        let properties = property_values(&database["properties"], &params["properties"])?;
+
+        let page = self
+            .request(
+                Method::POST,
+                "/pages",
+                Some(json!({
+                    "parent": {"database_id": database_id},
+                    "properties": properties
+                })),
+            )
+            .await?;
+        Ok(vec![json_content(
+            &json!({"id": page["id"], "url": page["url"]}),
+        )])
+    }
+
+    async fn update_database_entry(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let page_id = required_str(&params, "page_id")?;
+        let page = self
+            .request(Method::GET, &format!("/pages/{}", page_id), None)
+            .await?;
+        let properties = property_values(&page["properties"], &params["properties"])?;
+
+        self.request(
+            Method::PATCH,
+            &format!("/pages/{}", page_id),
+            Some(json!({"properties": properties})),
+        )
+        .await?;
+        Ok(vec![Content::text(format!(
+            "Updated {} properties",
+            properties.len()
+        ))])
+    }
+}
+
+/// The title of a page, from its title property
+fn page_title(page: &Value) -> String {
+    page["properties"]
+        .as_object()
+        .and_then(|properties| {
+            properties
+                .values()
+                .find(|property| property["type"] == "title")
+        })
+        .map(|property| plain_text(&property["title"]))
+        .unwrap_or_default()
+}
+
+/// Convert simple property values to Notion's format, using the types in `schema`
+///
+/// The schema is the properties of a database, or of a page whose values give the types.
+fn property_values(schema: &Value, values: &Value) -> Result<Map<String, Value>, ToolError> {
+    let values = values.as_object().ok_or_else(|| {
+        ToolError::InvalidParameters("'properties' must be an object of property values".into())
+    })?;
+
+    values
+        .iter()
+        .map(|(name, value)| {
+            let property_type = schema[name]["type"].as_str().ok_or_else(|| {
+                ToolError::InvalidParameters(format!("Unknown property '{}'", name))
+            })?;
+            let text = || {
+                value.as_str().map(String::from).ok_or_else(|| {
+                    ToolError::InvalidParameters(format!("Property '{}' must be text", name))
+                })
+            };
+            let converted = match property_type {
+                "title" | "rich_text" => json!({property_type: markdown_to_rich_text(&text()?)}),
+                "number" | "checkbox" => json!({property_type: value}),
+                "url" | "email" | "phone_number" => json!({property_type: text()?}),
+                "select" | "status" => json!({property_type: {"name": text()?}}),
+                "multi_select" => {
+                    let names: Vec<Value> = value
+                        .as_array()
+                        .ok_or_else(|| {
+                            ToolError::InvalidParameters(format!(
+                                "Property '{}' must be a list of names",
+                                name
+                            ))
+                        })?
+                        .iter()
+                        .map(|name| json!({"name": name}))
+                        .collect();
+                    json!({"multi_select": names})
+                }
+                "date" => json!({"date": {"start": text()?}}),
+                _ => {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "Property '{}' of type {} cannot be set",
+                        name, property_type
+                    )))
+                }
+            };
+            Ok((name.clone(), converted))
+        })
+        .collect()
+}
+
+/// The value of a page property as text
+fn property_text(property: &Value) -> String {
+    let property_type = property["type"].as_str().unwrap_or_default();
+    let value = &property[property_type];
+    match property_type {
+        "title" | "rich_text" => plain_text(value),
+        "select" | "status" => value["name"].as_str().unwrap_or_default().to_string(),
+        "multi_select" => value
+            .as_array()
+            .map(|options| {
+                options
+                    .iter()
+                    .filter_map(|option| option["name"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        "date" => match (value["start"].as_str(), value["end"].as_str()) {
+            (Some(start), Some(end)) => format!("{} - {}", start, end),
+            (Some(start), None) => start.to_string(),
+            _ => String::new(),
+        },
+        "people" => value
+            .as_array()
+            .map(|people| {
+                people
+                    .iter()
+                    .filter_map(|person| person["name"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        "relation" => value
+            .as_array()
+            .map(|pages| {
+                pages
+                    .iter()
+                    .filter_map(|page| page["id"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        "formula" => property_text(value),
+        "string" | "url" | "email" | "phone_number" | "created_time" | "last_edited_time" => {
+            value.as_str().unwrap_or_default().to_string()
+        }
+        _ => match value {
+            Value::Null => String::new(),
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        },
+    }
+}
+
+/// Database entries as CSV, with a column for the id and each property
+fn rows_to_csv(rows: &[Value]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for row in rows {
+        if let Some(properties) = row["properties"].as_object() {
+            for name in properties.keys() {
+                if !columns.contains(&name.as_str()) {
+                    columns.push(name);
+                }
+            }
+        }
+    }
+
+    let mut lines = vec![std::iter::once("id")
+        .chain(columns.iter().copied())
+        .map(csv_field)
+        .collect::<Vec<_>>()
+        .join(",")];
+    for row in rows {
+        let values = std::iter::once(row["id"].as_str().unwrap_or_default().to_string()).chain(
+            columns
+                .iter()
+                .map(|column| property_text(&row["properties"][*column])),
+        );
+        lines.push(
+            values
+                .map(|value| csv_field(&value))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    lines.join("\n")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl Router for NotionRouter {
+    fn name(&self) -> String {
+        "notion".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "search_pages" => this.search_pages(arguments).await,
+                "get_page" => this.get_page(arguments).await,
+                "create_page" => this.create_page(arguments).await,
+                "update_page" => this.update_page(arguments).await,
+                "list_databases" => this.list_databases(arguments).await,
+                "query_database" => this.query_database(arguments).await,
+                "create_database_entry" => this.create_database_entry(arguments).await,
+                "update_database_entry" => this.update_database_entry(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::text;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn rich(text: &str) -> Value {
+        json!([{"type": "text", "plain_text": text, "text": {"content": text}}])
+    }
+
+    fn router_for(server: &MockServer) -> NotionRouter {
+        NotionRouter::with_base_url(Some("secret".to_string()), server.uri())
+    }
+
+    #[tokio::test]
+    async fn test_search_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/search"))
+            .and(header("authorization", "Bearer secret"))
+            .and(header("notion-version", NOTION_VERSION))
+            .and(body_partial_json(json!({
+                "query": "roadmap",
+                "filter": {"property": "object", "value": "page"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [{
+                    "object": "page",
+                    "id": "page-1",
+                    "url": "https://www.notion.so/Roadmap-page1",
+                    "properties": {"Name": {"type": "title", "title": rich("Roadmap")}}
+                }],
+                "has_more": false,
+                "next_cursor": null
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("search_pages", json!({"query": "roadmap"}))
+            .await
+            .unwrap();
+        let pages: Value = serde_json::from_str(&text(result)).unwrap();
+        assert_eq!(
+            pages,
+            json!([{"id": "page-1", "title": "Roadmap", "url": "https://www.notion.so/Roadmap-page1"}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pages/page-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "page-1",
+                "properties": {"title": {"type": "title", "title": rich("Notes")}}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/blocks/page-1/children"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    {"id": "b1", "type": "heading_2", "heading_2": {"rich_text": rich("Plan")}},
+                    {"id": "b2", "type": "bulleted_list_item", "has_children": true,
+                     "bulleted_list_item": {"rich_text": rich("Ship")}}
+                ],
+                "has_more": false
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/blocks/b2/children"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    {"id": "b3", "type": "bulleted_list_item",
+                     "bulleted_list_item": {"rich_text": rich("Tests")}}
+                ],
+                "has_more": false
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("get_page", json!({"page_id": "page-1"}))
+            .await
+            .unwrap();
+        assert_eq!(text(result), "# Notes\n\n## Plan\n\n- Ship\n  - Tests");
+    }
+
+    #[tokio::test]
+    async fn test_create_database_entry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/databases/db-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "properties": {
+                    "Task": {"type": "title", "title": {}},
+                    "Done": {"type": "checkbox", "checkbox": {}},
+                    "Tags": {"type": "multi_select", "multi_select": {}}
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pages"))
+            .and(body_partial_json(json!({
+                "parent": {"database_id": "db-1"},
+                "properties": {
+                    "Task": {"title": [{"type": "text", "text": {"content": "Write docs"}}]},
+                    "Done": {"checkbox": false},
+                    "Tags": {"multi_select": [{"name": "docs"}]}
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "page-2",
+                "url": "https://www.notion.so/page2"
+            })))
+            .mount(&server)
+            .await;
+
+        let router = router_for(&server);
+        let result = router
+            .call_tool(
+                "create_database_entry",
+                json!({
+                    "database_id": "db-1",
+                    "properties": {"Task": "Write docs", "Done": false, "Tags": ["docs"]}
+                }),
+            )
+            .await
+            .unwrap();
+        assert!(text(result).contains("page-2"));
+
+        let result = router
+            .call_tool(
+                "create_database_entry",
+                json!({"database_id": "db-1", "properties": {"Owner": "me"}}),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_query_database_as_csv() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/databases/db-1/query"))
+            .and(body_partial_json(json!({
+                "sorts": [{"property": "Task", "direction": "ascending"}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [{
+                    "id": "row-1",
+                    "properties": {
+                        "Task": {"type": "title", "title": rich("Write, review")},
+                        "Points": {"type": "number", "number": 3}
+                    }
+                }],
+                "has_more": false
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "query_database",
+                json!({
+                    "database_id": "db-1",
+                    "sorts": [{"property": "Task", "direction": "ascending"}]
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(text(result), "id,Points,Task\nrow-1,3,\"Write, review\"");
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/blocks/page-1/children"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [{"id": "old-1", "type": "paragraph"}],
+                "has_more": false,
+                "next_cursor": null
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/blocks/page-1/children"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "object": "error",
+                "status": 400,
+                "message": "body failed validation"
+            })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/blocks/page-1/children"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"results": []})))
+            .mount(&server)
+            .await;
+        let delete = Mock::given(method("DELETE"))
+            .and(path("/blocks/old-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "old-1"})))
+            .expect(1)
+            .mount_as_scoped(&server)
+            .await;
+
+        let router = router_for(&server);
+        let params = json!({"page_id": "page-1", "content": "New text", "mode": "overwrite"});
+        // The old blocks are kept when the new ones cannot be added
+        assert!(router
+            .call_tool("update_page", params.clone())
+            .await
+            .is_err());
+        assert!(server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .all(|request| request.method != wiremock::http::Method::DELETE));
+
+        let result = router.call_tool("update_page", params).await.unwrap();
+        assert_eq!(text(result), "Replaced 1 blocks with 1 blocks");
+        drop(delete);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pages/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "object": "error",
+                "status": 404,
+                "message": "Could not find page with ID: missing."
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool("get_page", json!({"page_id": "missing"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("Could not find page"))
+        );
+
+        let router = NotionRouter::with_base_url(None, server.uri());
+        let result = router
+            .call_tool("search_pages", json!({"query": "x"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("NOTION_API_KEY"))
+        );
+    }
+}
@@ -0,0 +1,852 @@
+use indoc::indoc;
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{future::Future, pin::Pin};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::util::{json_content, required_str};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    tool::{Tool, ToolAnnotations},
+    Content,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+
+const SLACK_API_URL: &str = "https://slack.com/api";
+const DEFAULT_HISTORY_LIMIT: u64 = 20;
+
+/// Slack's Tier 1 limit allows about one request per second, with short bursts
+const RATE_LIMIT_BURST: u32 = 5;
+const RATE_LIMIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A token bucket allowing `burst` requests at once, refilled with one token per `interval`
+struct RateLimiter {
+    burst: u32,
+    interval: Duration,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    fn new(burst: u32, interval: Duration) -> Self {
+        Self {
+            burst,
+            interval,
+            state: Mutex::new((burst as f64, Instant::now())),
+        }
+    }
+
+    /// Wait until a request is allowed
+    async fn acquire(&self) {
+        let mut state = self.state.lock().await;
+        let (tokens, refilled) = &mut *state;
+        let now = Instant::now();
+        *tokens = (*tokens + (now - *refilled).as_secs_f64() / self.interval.as_secs_f64())
+            .min(self.burst as f64);
+        *refilled = now;
+
+        if *tokens < 1.0 {
+            // Holding the lock while waiting keeps requests in order
+            let wait = self.interval.mul_f64(1.0 - *tokens);
+            tokio::time::sleep(wait).await;
+            *tokens = 1.0;
+            *refilled = Instant::now();
+        }
+        *tokens -= 1.0;
+    }
+}
+
+/// Router for the Slack Web API, authenticated with SLACK_BOT_TOKEN
+#[derive(Clone)]
+pub struct SlackRouter {
+    tools: Vec<Tool>,
+    instructions: String,
+    client: Client,
+    token: Option<String>,
+    base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl Default for SlackRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SlackRouter {
+    pub fn new() -> Self {
+        Self::with_base_url(
+            std::env::var("SLACK_BOT_TOKEN").ok(),
+            SLACK_API_URL.to_string(),
+        )
+    }
+
+    /// Create a router using the given token and Slack API url
+    pub fn with_base_url(token: Option<String>, base_url: String) -> Self {
+        let read_only = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: true,
+            })
+        };
+        let writing = |title: &str| {
+            Some(ToolAnnotations {
+                title: Some(title.to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: true,
+            })
+        };
+
+        let list_channels = Tool::new(
+            "list_channels",
+            "List the public channels of the workspace and the private channels the bot is a member of.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "default": 1000,
+                        "description": "The maximum number of channels"
+                    }
+                }
+            }),
+            read_only("List Slack channels"),
+        );
+
+        let get_channel_history = Tool::new(
+            "get_channel_history",
+            "Get the latest messages of a channel, newest first.",
+            json!({
+                "type": "object",
+                "required": ["channel"],
+                "properties": {
+                    "channel": {"type": "string", "description": "The channel id"},
+                    "limit": {"type": "integer", "default": DEFAULT_HISTORY_LIMIT}
+                }
+            }),
+            read_only("Get Slack channel history"),
+        );
+
+        let post_message = Tool::new(
+            "post_message",
+            "Post a message to a channel, or reply in a thread when thread_ts is given.",
+            json!({
+                "type": "object",
+                "required": ["channel", "text"],
+                "properties": {
+                    "channel": {"type": "string", "description": "The channel id"},
+                    "text": {"type": "string", "description": "The message, in Slack mrkdwn"},
+                    "thread_ts": {"type": "string", "description": "The ts of the message to reply to"}
+                }
+            }),
+            writing("Post a Slack message"),
+        );
+
+        let upload_file = Tool::new(
+            "upload_file",
+            "Upload a local file, or text content as a file, to a channel.",
+            json!({
+                "type": "object",
+                "required": ["channel"],
+                "properties": {
+                    "channel": {"type": "string", "description": "The channel id"},
+                    "path": {"type": "string", "description": "The path of the file to upload"},
+                    "content": {"type": "string", "description": "Text to upload instead of a file"},
+                    "filename": {"type": "string", "description": "The name of the file, required with content"},
+                    "comment": {"type": "string", "description": "A message posted with the file"}
+                }
+            }),
+            writing("Upload a file to Slack"),
+        );
+
+        let get_user_info = Tool::new(
+            "get_user_info",
+            "Get the name, title and time zone of a user.",
+            json!({
+                "type": "object",
+                "required": ["user"],
+                "properties": {
+                    "user": {"type": "string", "description": "The user id, e.g. U012AB3CD"}
+                }
+            }),
+            read_only("Get Slack user info"),
+        );
+
+        let search_messages = Tool::new(
+            "search_messages",
+            indoc! {r#"
+                Search messages with Slack's search syntax, e.g. `deploy in:#ops from:@ada`.
+                Requires a token with the search:read scope.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["query"],
+                "properties": {
+                    "query": {"type": "string"},
+                    "count": {"type": "integer", "default": 20}
+                }
+            }),
+            read_only("Search Slack messages"),
+        );
+
+        let add_reaction = Tool::new(
+            "add_reaction",
+            "Add an emoji reaction to a message.",
+            json!({
+                "type": "object",
+                "required": ["channel", "ts", "emoji"],
+                "properties": {
+                    "channel": {"type": "string"},
+                    "ts": {"type": "string", "description": "The ts of the message"},
+                    "emoji": {"type": "string", "description": "The emoji name without colons, e.g. thumbsup"}
+                }
+            }),
+            writing("Add a Slack reaction"),
+        );
+
+        let instructions = indoc! {r#"
+            The Slack extension reads and posts messages in the Slack workspace of the bot token
+            in SLACK_BOT_TOKEN.
+
+            Tools take channel ids rather than names: use list_channels to find them. Messages
+            are identified by their channel and ts.
+        "#}
+        .to_string();
+
+        Self {
+            tools: vec![
+                list_channels,
+                get_channel_history,
+                post_message,
+                upload_file,
+                get_user_info,
+                search_messages,
+                add_reaction,
+            ],
+            instructions,
+            client: Client::new(),
+            token,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            rate_limiter: Arc::new(RateLimiter::new(RATE_LIMIT_BURST, RATE_LIMIT_INTERVAL)),
+        }
+    }
+
+    fn token(&self) -> Result<&str, ToolError> {
+        self.token.as_deref().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "SLACK_BOT_TOKEN is not set. Create a Slack app with a bot token at https://api.slack.com/apps".into(),
+            )
+        })
+    }
+
+    // Helper method to call a Slack API method, with query parameters for GET requests and
+    // a JSON body otherwise
+    async fn call(
+        &self,
+        method: Method,
+        api_method: &str,
+        params: Value,
+    ) -> Result<Value, ToolError> {
+        let token = self.token()?;
+        self.rate_limiter.acquire().await;
+
+        let mut request = self
+            .client
+            .request(method.clone(), format!("{}/{}", self.base_url, api_method))
+            .bearer_auth(token);
+        request = if method == Method::GET {
+            request.query(&query_pairs(&params))
+        } else {
+            request.json(&params)
+        };
+        self.send(request).await
+    }
+
+    // Helper method to call a Slack API method with a form encoded body
+    async fn call_form(
+        &self,
+        api_method: &str,
+        form: &[(&str, String)],
+    ) -> Result<Value, ToolError> {
+        let token = self.token()?;
+        self.rate_limiter.acquire().await;
+
+        let request = self
+            .client
+            .post(format!("{}/{}", self.base_url, api_method))
+            .bearer_auth(token)
+            .form(form);
+        self.send(request).await
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value, ToolError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Slack request failed: {}", e)))?;
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("a few");
+            return Err(ToolError::ExecutionError(format!(
+                "Slack rate limit exceeded, retry after {} seconds",
+                retry_after
+            )));
+        }
+
+        let body: Value = response.json().await.map_err(|e| {
+            ToolError::ExecutionError(format!("Invalid response from Slack: {}", e))
+        })?;
+        if body["ok"].as_bool() == Some(true) {
+            return Ok(body);
+        }
+        Err(slack_error(
+            body["error"].as_str().unwrap_or("unknown_error"),
+        ))
+    }
+
+    async fn list_channels(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let limit = params["limit"].as_u64().unwrap_or(1000) as usize;
+        let mut channels = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut params = json!({
+                "types": "public_channel,private_channel",
+                "exclude_archived": true,
+                "limit": 200
+            });
+            if !cursor.is_empty() {
+                params["cursor"] = json!(cursor);
+            }
+            let response = self.call(Method::GET, "conversations.list", params).await?;
+
+            for channel in response["channels"].as_array().into_iter().flatten() {
+                // Private channels are only listed when the bot is a member
+                channels.push(json!({
+                    "id": channel["id"],
+                    "name": channel["name"],
+                    "is_private": channel["is_private"],
+                    "is_member": channel["is_member"],
+                    "num_members": channel["num_members"],
+                    "topic": channel["topic"]["value"]
+                }));
+            }
+            cursor = response["response_metadata"]["next_cursor"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            if cursor.is_empty() || channels.len() >= limit {
+                break;
+            }
+        }
+        channels.truncate(limit);
+        Ok(vec![json_content(&Value::Array(channels))])
+    }
+
+    async fn get_channel_history(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let channel = required_str(&params, "channel")?;
+        let limit = params["limit"].as_u64().unwrap_or(DEFAULT_HISTORY_LIMIT);
+        let response = self
+            .call(
+                Method::GET,
+                "conversations.history",
+                json!({"channel": channel, "limit": limit}),
+            )
+            .await?;
+
+        let messages: Vec<Value> = response["messages"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|message| {
+                let mut summary = json!({
+                    "user": message["user"].as_str().or(message["username"].as_str()),
+                    "text": message["text"],
+                    "ts": message["ts"]
+                });
+                if let Some(replies) = message["reply_count"].as_u64() {
+                    summary["reply_count"] = json!(replies);
+                }
+                summary
+            })
+            .collect();
+        Ok(vec![json_content(&Value::Array(messages))])
+    }
+
+    async fn post_message(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let mut body = json!({
+            "channel": required_str(&params, "channel")?,
+            "text": required_str(&params, "text")?
+        });
+        if let Some(thread_ts) = params["thread_ts"].as_str() {
+            body["thread_ts"] = json!(thread_ts);
+        }
+        let response = self.call(Method::POST, "chat.postMessage", body).await?;
+        Ok(vec![json_content(&json!({
+            "channel": response["channel"],
+            "ts": response["ts"]
+        }))])
+    }
+
+    async fn upload_file(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let channel = required_str(&params, "channel")?;
+        let (filename, content) = match (params["path"].as_str(), params["content"].as_str()) {
+            (Some(path), None) => {
+                let path = std::path::Path::new(path);
+                let content = std::fs::read(path).map_err(|e| {
+                    ToolError::InvalidParameters(format!(
+                        "Failed to read '{}': {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                let filename = params["filename"]
+                    .as_str()
+                    .map(String::from)
+                    .or_else(|| {
+                        path.file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                    })
+                    .unwrap_or_else(|| "file".to_string());
+                (filename, content)
+            }
+            (None, Some(content)) => {
+                let filename = required_str(&params, "filename")?;
+                (filename.to_string(), content.as_bytes().to_vec())
+            }
+            _ => {
+                return Err(ToolError::InvalidParameters(
+                    "Provide exactly one of 'path' and 'content'".into(),
+                ))
+            }
+        };
+
+        // Files are uploaded to a url Slack provides, then shared in the channel
+        let upload = self
+            .call_form(
+                "files.getUploadURLExternal",
+                &[
+                    ("filename", filename.clone()),
+                    ("length", content.len().to_string()),
+                ],
+            )
+            .await?;
+        let upload_url = upload["upload_url"]
+            .as_str()
+            .ok_or_else(|| ToolError::ExecutionError("Slack returned no upload url".into()))?;
+        let file_id = upload["file_id"].clone();
+
+        let response = self
+            .client
+            .post(upload_url)
+            .header("Content-Type", "application/octet-stream")
+            .body(content)
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to upload the file: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to upload the file: {}",
+                response.status()
+            )));
+        }
+
+        let mut complete = json!({
+            "files": [{"id": file_id, "title": filename}],
+            "channel_id": channel
+        });
+        if let Some(comment) = params["comment"].as_str() {
+            complete["initial_comment"] = json!(comment);
+        }
+        self.call(Method::POST, "files.completeUploadExternal", complete)
+            .await?;
+        Ok(vec![Content::text(format!(
+            "Uploaded {} to {}",
+            filename, channel
+        ))])
+    }
+
+    async fn get_user_info(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let user = required_str(&params, "user")?;
+        let response = self
+            .call(Method::GET, "users.info", json!({"user": user}))
+            .await?;
+        let user = &response["user"];
+        Ok(vec![json_content(&json!({
+            "id": user["id"],
+            "name": user["name"],
+            "real_name": user["real_name"],
+            "display_name": user["profile"]["display_name"],
+            "title": user["profile"]["title"],
+            "time_zone": user["tz"],
+            "is_bot": user["is_bot"],
+            "deleted": user["deleted"]
+        }))])
+    }
+
+    async fn search_messages(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let query = required_str(&params, "query")?;
+        let count = params["count"].as_u64().unwrap_or(20);
+        let response = self
+            .call(
+                Method::GET,
+                "search.messages",
+                json!({"query": query, "count": count}),
+            )
+            .await?;
+
+        let matches: Vec<Value> = response["messages"]["matches"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|message| {
+                json!({
+                    "channel": message["channel"]["name"],
+                    "ts": message["ts"],
+                    "text": message["text"],
+                    "permalink": message["permalink"]
+                })
+            })
+            .collect();
+        Ok(vec![json_content(&Value::Array(matches))])
+    }
+
+    async fn add_reaction(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let emoji = required_str(&params, "emoji")?.trim_matches(':');
+        self.call(
+            Method::POST,
+            "reactions.add",
+            json!({
+                "channel": required_str(&params, "channel")?,
+                "timestamp": required_str(&params, "ts")?,
+                "name": emoji
+            }),
+        )
+        .await?;
+        Ok(vec![Content::text(format!("Added :{}:", emoji))])
+    }
+}
+
+fn query_pairs(params: &Value) -> Vec<(String, String)> {
+    params
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Map the `error` code of a Slack response to a tool error
+fn slack_error(code: &str) -> ToolError {
+    match code {
+        "channel_not_found" => ToolError::InvalidParameters(
+            "Channel not found. Use a channel id from list_channels, and invite the bot to private channels".into(),
+        ),
+        "user_not_found" => ToolError::InvalidParameters("User not found".into()),
+        "message_not_found" | "thread_not_found" => {
+            ToolError::InvalidParameters("Message not found".into())
+        }
+        "not_in_channel" => ToolError::ExecutionError(
+            "The bot is not a member of this channel, invite it with /invite".into(),
+        ),
+        "not_authed" | "invalid_auth" | "token_revoked" | "account_inactive" => {
+            ToolError::ExecutionError(format!(
+                "Slack authentication failed ({}), check SLACK_BOT_TOKEN",
+                code
+            ))
+        }
+        "missing_scope" | "not_allowed_token_type" => ToolError::ExecutionError(format!(
+            "The Slack token is not allowed to do this ({})",
+            code
+        )),
+        "already_reacted" => ToolError::ExecutionError("The reaction was already added".into()),
+        _ => ToolError::ExecutionError(format!("Slack API error: {}", code)),
+    }
+}
+
+impl Router for SlackRouter {
+    fn name(&self) -> String {
+        "slack".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "list_channels" => this.list_channels(arguments).await,
+                "get_channel_history" => this.get_channel_history(arguments).await,
+                "post_message" => this.post_message(arguments).await,
+                "upload_file" => this.upload_file(arguments).await,
+                "get_user_info" => this.get_user_info(arguments).await,
+                "search_messages" => this.search_messages(arguments).await,
+                "add_reaction" => this.add_reaction(arguments).await,
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move {
+            Err(ResourceError::NotFound(format!(
+                "Resource {} not found",
+                uri
+            )))
+        })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::parse;
+    use wiremock::matchers::{
+        body_json, body_string, body_string_contains, header, method, path, query_param,
+        query_param_is_missing,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn router_for(server: &MockServer) -> SlackRouter {
+        SlackRouter::with_base_url(Some("xoxb-token".to_string()), server.uri())
+    }
+
+    #[tokio::test]
+    async fn test_list_channels_pagination() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/conversations.list"))
+            .and(header("authorization", "Bearer xoxb-token"))
+            .and(query_param("types", "public_channel,private_channel"))
+            .and(query_param_is_missing("cursor"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "channels": [{"id": "C1", "name": "general", "is_private": false, "is_member": true}],
+                "response_metadata": {"next_cursor": "page2"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/conversations.list"))
+            .and(query_param("cursor", "page2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "channels": [{"id": "G2", "name": "team", "is_private": true, "is_member": true}],
+                "response_metadata": {"next_cursor": ""}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channels = parse(
+            router_for(&server)
+                .call_tool("list_channels", json!({}))
+                .await
+                .unwrap(),
+        );
+        let ids: Vec<&str> = channels
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|channel| channel["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["C1", "G2"]);
+    }
+
+    #[tokio::test]
+    async fn test_upload_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/files.getUploadURLExternal"))
+            .and(header("content-type", "application/x-www-form-urlencoded"))
+            .and(body_string("filename=notes+%26+todo.txt&length=13"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "upload_url": format!("{}/upload/F1", server.uri()),
+                "file_id": "F1"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/upload/F1"))
+            .and(body_string("line 1\nline 2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("OK - 13"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/files.completeUploadExternal"))
+            .and(body_json(json!({
+                "files": [{"id": "F1", "title": "notes & todo.txt"}],
+                "channel_id": "C1",
+                "initial_comment": "Here are the notes"
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"ok": true, "files": []})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "upload_file",
+                json!({
+                    "channel": "C1",
+                    "content": "line 1\nline 2",
+                    "filename": "notes & todo.txt",
+                    "comment": "Here are the notes"
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result[0].as_text(), Some("Uploaded notes & todo.txt to C1"));
+    }
+
+    #[tokio::test]
+    async fn test_post_message_in_thread() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat.postMessage"))
+            .and(body_string_contains("\"thread_ts\":\"1700000000.000100\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "channel": "C1",
+                "ts": "1700000001.000200"
+            })))
+            .mount(&server)
+            .await;
+
+        let result = router_for(&server)
+            .call_tool(
+                "post_message",
+                json!({"channel": "C1", "text": "On it", "thread_ts": "1700000000.000100"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(parse(result)["ts"], "1700000001.000200");
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/conversations.history"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": false,
+                "error": "channel_not_found"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/users.info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": false,
+                "error": "not_authed"
+            })))
+            .mount(&server)
+            .await;
+
+        let router = router_for(&server);
+        let result = router
+            .call_tool("get_channel_history", json!({"channel": "C404"}))
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+
+        let result = router
+            .call_tool("get_user_info", json!({"user": "U1"}))
+            .await;
+        assert!(
+            matches!(result, Err(ToolError::ExecutionError(message)) if message.contains("SLACK_BOT_TOKEN"))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit() {
+        let limiter = RateLimiter::new(5, Duration::from_secs(1));
+        let start = Instant::now();
+
+        // The burst is allowed at once
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Then one request per interval
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        assert!(start.elapsed() < Duration::from_millis(2100));
+
+        // Tokens refill while idle, up to the burst
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        let idle = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert_eq!(idle.elapsed(), Duration::ZERO);
+        limiter.acquire().await;
+        assert!(idle.elapsed() >= Duration::from_secs(1));
+    }
+}
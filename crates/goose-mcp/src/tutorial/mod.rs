@@ -1,8 +1,11 @@
+mod progress;
+
 use anyhow::Result;
+use etcetera::{choose_app_strategy, AppStrategy};
 use include_dir::{include_dir, Dir};
-use indoc::formatdoc;
+use indoc::{formatdoc, indoc};
 use serde_json::{json, Value};
-use std::{future::Future, pin::Pin};
+use std::{future::Future, path::PathBuf, pin::Pin};
 
 use mcp_core::{
     handler::{PromptError, ResourceError, ToolError},
@@ -17,11 +20,14 @@ use mcp_server::Router;
 
 use mcp_core::content::Content;
 
+use self::progress::{Step, TutorialProgress};
+
 static TUTORIALS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/tutorial/tutorials");
 
 pub struct TutorialRouter {
     tools: Vec<Tool>,
     instructions: String,
+    progress_path: PathBuf,
 }
 
 impl Default for TutorialRouter {
@@ -32,6 +38,16 @@ impl Default for TutorialRouter {
 
 impl TutorialRouter {
     pub fn new() -> Self {
+        // choose_app_strategy().config_dir()
+        // - macOS/Linux: ~/.config/goose/tutorial_progress.json
+        // - Windows:     ~\AppData\Roaming\Block\goose\config\tutorial_progress.json
+        let progress_path = choose_app_strategy(crate::APP_STRATEGY.clone())
+            .map(|strategy| strategy.in_config_dir("tutorial_progress.json"))
+            .unwrap_or_else(|_| PathBuf::from(".config/goose/tutorial_progress.json"));
+        Self::with_progress_path(progress_path)
+    }
+
+    fn with_progress_path(progress_path: PathBuf) -> Self {
         let load_tutorial = Tool::new(
             "load_tutorial".to_string(),
             "Load a specific tutorial by name. The tutorial will be returned as markdown content that provides step by step instructions.".to_string(),
@@ -54,6 +70,41 @@ impl TutorialRouter {
                 }),
         );
 
+        let tutorial_progress = Tool::new(
+            "tutorial_progress",
+            indoc! {r#"
+                Track the user's progress through tutorials, which is saved across sessions. The steps
+                of a tutorial are its level 2 sections.
+                - get: The current step of a tutorial and the percentage complete
+                - mark_complete: Mark the current step of a tutorial done and advance to the next
+                - reset: Clear the progress of a tutorial
+                - list: Every tutorial with its completion status
+                Mark a step complete once the user has finished it.
+            "#},
+            json!({
+                "type": "object",
+                "required": ["operation"],
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["get", "mark_complete", "reset", "list"],
+                        "description": "The operation to perform"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the tutorial (required for get, mark_complete and reset)"
+                    }
+                }
+            }),
+            Some(ToolAnnotations {
+                title: Some("Tutorial progress".to_string()),
+                read_only_hint: false,
+                destructive_hint: false,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        );
+
         // Get base instructions and available tutorials
         let available_tutorials = Self::get_available_tutorials();
 
@@ -71,16 +122,51 @@ impl TutorialRouter {
             **Important**: Make sure that you provide guidance or info *before* you run commands, as the command will
             run immediately for the user. For example while running a game tutorial, let the user know what to expect
             before you run a command to start the game itself.
+
+            Use tutorial_progress to mark each step complete as the user finishes it, so they can pick
+            up where they left off in a later session.
             "#,
             tutorials=available_tutorials,
         };
 
         Self {
-            tools: vec![load_tutorial],
+            tools: vec![load_tutorial, tutorial_progress],
             instructions,
+            progress_path,
         }
     }
 
+    /// The instructions, with the progress through any started tutorials
+    fn current_instructions(&self) -> String {
+        let progress = progress::load(&self.progress_path);
+        if progress.is_empty() {
+            return self.instructions.clone();
+        }
+
+        let lines: Vec<String> = progress
+            .iter()
+            .map(|progress| {
+                let steps = Self::tutorial_steps(&progress.tutorial_name).unwrap_or_default();
+                if progress.is_complete() {
+                    format!("- {}: 🎉 Tutorial complete!", progress.tutorial_name)
+                } else {
+                    format!(
+                        "- {}: on step {} ({}% complete, last active {})",
+                        progress.tutorial_name,
+                        Self::describe_step(progress, &steps),
+                        progress.percent_complete(&steps),
+                        progress.last_active.format("%Y-%m-%d %H:%M UTC")
+                    )
+                }
+            })
+            .collect();
+        format!(
+            "{}\nThe user's tutorial progress from earlier sessions, offer to continue where they left off:\n{}\n",
+            self.instructions,
+            lines.join("\n")
+        )
+    }
+
     fn get_available_tutorials() -> String {
         let mut tutorials = String::new();
         for file in TUTORIALS_DIR.files() {
@@ -105,8 +191,152 @@ impl TutorialRouter {
                 "Could not locate tutorial '{}'",
                 name
             )))?;
+
+        // Loading a tutorial starts it, unless the user is already on their way through
+        let mut all_progress = progress::load(&self.progress_path);
+        if !all_progress.iter().any(|p| p.tutorial_name == name) {
+            let steps = Self::tutorial_steps(name)?;
+            all_progress.push(TutorialProgress::start(name, &steps));
+            progress::save(&self.progress_path, &all_progress)?;
+        }
+
         Ok(String::from_utf8_lossy(file.contents()).into_owned())
     }
+
+    fn tutorial_steps(name: &str) -> Result<Vec<Step>, ToolError> {
+        TUTORIALS_DIR
+            .get_file(format!("{}.md", name))
+            .and_then(|file| file.contents_utf8())
+            .map(progress::parse_steps)
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!("Could not locate tutorial '{}'", name))
+            })
+    }
+
+    fn describe_step(progress: &TutorialProgress, steps: &[Step]) -> String {
+        let current = progress.current_step.as_deref().unwrap_or_default();
+        match steps.iter().position(|step| step.id == current) {
+            Some(index) => format!(
+                "{} of {}, \"{}\"",
+                index + 1,
+                steps.len(),
+                steps[index].title
+            ),
+            None => current.to_string(),
+        }
+    }
+
+    fn progress_summary(progress: &TutorialProgress, steps: &[Step]) -> Value {
+        let current_step = progress
+            .current_step
+            .as_ref()
+            .and_then(|current| steps.iter().find(|step| &step.id == current));
+        json!({
+            "tutorial_name": progress.tutorial_name,
+            "current_step": current_step.map(|step| &step.id),
+            "current_step_title": current_step.map(|step| &step.title),
+            "completed_steps": progress.completed_steps,
+            "total_steps": steps.len(),
+            "percent_complete": progress.percent_complete(steps),
+            "started_at": progress.started_at.to_rfc3339(),
+            "last_active": progress.last_active.to_rfc3339(),
+        })
+    }
+
+    async fn tutorial_progress(&self, arguments: Value) -> Result<String, ToolError> {
+        let operation = arguments
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'operation' parameter".to_string())
+            })?;
+        let name = || {
+            arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| {
+                    ToolError::InvalidParameters(format!(
+                        "The 'name' parameter is required for {}",
+                        operation
+                    ))
+                })
+        };
+
+        let mut all_progress = progress::load(&self.progress_path);
+        match operation {
+            "get" => {
+                let name = name()?;
+                let steps = Self::tutorial_steps(name)?;
+                let progress = all_progress
+                    .into_iter()
+                    .find(|p| p.tutorial_name == name)
+                    .unwrap_or_else(|| TutorialProgress::start(name, &steps));
+                Ok(Self::progress_summary(&progress, &steps).to_string())
+            }
+            "mark_complete" => {
+                let name = name()?;
+                let steps = Self::tutorial_steps(name)?;
+                let index = match all_progress.iter().position(|p| p.tutorial_name == name) {
+                    Some(index) => index,
+                    None => {
+                        all_progress.push(TutorialProgress::start(name, &steps));
+                        all_progress.len() - 1
+                    }
+                };
+                if !all_progress[index].complete_current_step(&steps) {
+                    return Err(ToolError::ExecutionError(format!(
+                        "Every step of '{}' is already complete",
+                        name
+                    )));
+                }
+                progress::save(&self.progress_path, &all_progress)?;
+
+                let summary = Self::progress_summary(&all_progress[index], &steps);
+                if all_progress[index].is_complete() {
+                    Ok(format!("🎉 Tutorial complete!\n{}", summary))
+                } else {
+                    Ok(summary.to_string())
+                }
+            }
+            "reset" => {
+                let name = name()?;
+                let count = all_progress.len();
+                all_progress.retain(|p| p.tutorial_name != name);
+                if all_progress.len() == count {
+                    return Ok(format!("No progress to reset for '{}'", name));
+                }
+                progress::save(&self.progress_path, &all_progress)?;
+                Ok(format!("Reset the progress of '{}'", name))
+            }
+            "list" => {
+                let mut lines = Vec::new();
+                for file in TUTORIALS_DIR.files() {
+                    let Some(name) = file.path().file_stem().map(|n| n.to_string_lossy()) else {
+                        continue;
+                    };
+                    let steps = file
+                        .contents_utf8()
+                        .map(progress::parse_steps)
+                        .unwrap_or_default();
+                    let status = match all_progress.iter().find(|p| p.tutorial_name == name) {
+                        None => "not started".to_string(),
+                        Some(progress) if progress.is_complete() => "complete".to_string(),
+                        Some(progress) => format!(
+                            "step {} ({}% complete)",
+                            Self::describe_step(progress, &steps),
+                            progress.percent_complete(&steps)
+                        ),
+                    };
+                    lines.push(format!("- {}: {}", name, status));
+                }
+                Ok(lines.join("\n"))
+            }
+            _ => Err(ToolError::InvalidParameters(format!(
+                "Invalid operation: {}. Valid operations are: 'get', 'mark_complete', 'reset', 'list'",
+                operation
+            ))),
+        }
+    }
 }
 
 impl Router for TutorialRouter {
@@ -115,7 +345,7 @@ impl Router for TutorialRouter {
     }
 
     fn instructions(&self) -> String {
-        self.instructions.clone()
+        self.current_instructions()
     }
 
     fn capabilities(&self) -> ServerCapabilities {
@@ -149,6 +379,10 @@ impl Router for TutorialRouter {
                         Content::text(content).with_audience(vec![Role::Assistant])
                     ])
                 }
+                "tutorial_progress" => {
+                    let content = this.tutorial_progress(arguments).await?;
+                    Ok(vec![Content::text(content)])
+                }
                 _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
             }
         })
@@ -188,6 +422,115 @@ impl Clone for TutorialRouter {
         Self {
             tools: self.tools.clone(),
             instructions: self.instructions.clone(),
+            progress_path: self.progress_path.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn router_in(dir: &TempDir) -> TutorialRouter {
+        TutorialRouter::with_progress_path(dir.path().join("tutorial_progress.json"))
+    }
+
+    async fn call(router: &TutorialRouter, arguments: Value) -> Value {
+        let content = router.tutorial_progress(arguments).await.unwrap();
+        serde_json::from_str(content.trim_start_matches("🎉 Tutorial complete!\n")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        let steps = progress::parse_steps(indoc! {r#"
+            # Title
+            ## First Step
+            ```sh
+            ## not a step
+            ```
+            ## Second: the rest
+            ## Notes for Agent
+        "#});
+        assert_eq!(
+            steps,
+            vec![
+                Step {
+                    id: "first-step".to_string(),
+                    title: "First Step".to_string()
+                },
+                Step {
+                    id: "second-the-rest".to_string(),
+                    title: "Second: the rest".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tutorial_progress() {
+        let dir = TempDir::new().unwrap();
+        let router = router_in(&dir);
+        let get = json!({"operation": "get", "name": "first-game"});
+
+        let progress = call(&router, get.clone()).await;
+        assert_eq!(progress["current_step"], "initial-discussion");
+        assert_eq!(progress["total_steps"], 7);
+        assert_eq!(progress["percent_complete"], 0);
+
+        router.load_tutorial("first-game").await.unwrap();
+        for _ in 0..2 {
+            call(
+                &router,
+                json!({"operation": "mark_complete", "name": "first-game"}),
+            )
+            .await;
+        }
+
+        // The progress is saved, so a new session picks it up
+        let router = router_in(&dir);
+        let progress = call(&router, get.clone()).await;
+        assert_eq!(progress["current_step"], "project-structure");
+        assert_eq!(progress["current_step_title"], "Project Structure");
+        assert_eq!(
+            progress["completed_steps"],
+            json!(["initial-discussion", "environment-setup"])
+        );
+        assert_eq!(progress["percent_complete"], 29);
+        assert!(router
+            .instructions()
+            .contains("- first-game: on step 3 of 7, \"Project Structure\" (29% complete"));
+
+        let list = router
+            .tutorial_progress(json!({"operation": "list"}))
+            .await
+            .unwrap();
+        assert!(list.contains("- first-game: step 3 of 7, \"Project Structure\" (29% complete)"));
+        assert!(list.contains("- build-mcp-extension: not started"));
+
+        for _ in 0..5 {
+            call(
+                &router,
+                json!({"operation": "mark_complete", "name": "first-game"}),
+            )
+            .await;
+        }
+        assert_eq!(call(&router, get.clone()).await["percent_complete"], 100);
+        assert!(router
+            .instructions()
+            .contains("- first-game: 🎉 Tutorial complete!"));
+        assert!(router
+            .tutorial_progress(json!({"operation": "mark_complete", "name": "first-game"}))
+            .await
+            .is_err());
+
+        router
+            .tutorial_progress(json!({"operation": "reset", "name": "first-game"}))
+            .await
+            .unwrap();
+        assert_eq!(call(&router, get).await["percent_complete"], 0);
+        assert!(!router
+            .instructions()
+            .contains("tutorial progress from earlier sessions"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use mcp_core::handler::ToolError;
+use serde::{Deserialize, Serialize};
+
+/// A step of a tutorial, one of its `## ` sections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub id: String,
+    pub title: String,
+}
+
+/// How far the user has got through a tutorial
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TutorialProgress {
+    pub tutorial_name: String,
+    /// The step being worked on, or `None` once every step is complete
+    pub current_step: Option<String>,
+    pub completed_steps: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub last_active: DateTime<Utc>,
+}
+
+impl TutorialProgress {
+    pub fn start(tutorial_name: &str, steps: &[Step]) -> Self {
+        let now = Utc::now();
+        Self {
+            tutorial_name: tutorial_name.to_string(),
+            current_step: steps.first().map(|step| step.id.clone()),
+            completed_steps: Vec::new(),
+            started_at: now,
+            last_active: now,
+        }
+    }
+
+    /// Mark the current step done and move to the first step after it that is not done,
+    /// returning whether there was a step to complete
+    pub fn complete_current_step(&mut self, steps: &[Step]) -> bool {
+        let Some(current) = self.current_step.take() else {
+            return false;
+        };
+        let position = steps.iter().position(|step| step.id == current);
+        if !self.completed_steps.contains(&current) {
+            self.completed_steps.push(current);
+        }
+        self.current_step = steps
+            .iter()
+            .skip(position.map_or(0, |position| position + 1))
+            .chain(steps)
+            .find(|step| !self.completed_steps.contains(&step.id))
+            .map(|step| step.id.clone());
+        self.last_active = Utc::now();
+        true
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current_step.is_none()
+    }
+
+    /// The percentage of `steps` that are complete, rounded to a whole number
+    pub fn percent_complete(&self, steps: &[Step]) -> u32 {
+        if steps.is_empty() {
+            return 100;
+        }
+        let done = steps
+            .iter()
+            .filter(|step| self.completed_steps.contains(&step.id))
+            .count();
+        (done as f64 * 100.0 / steps.len() as f64).round() as u32
+    }
+}
+
+/// The steps of a tutorial: its `## ` sections, apart from those addressed to the agent
+pub fn parse_steps(markdown: &str) -> Vec<Step> {
+    let mut in_code_block = false;
+    markdown
+        .lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            !in_code_block
+        })
+        .filter_map(|line| line.strip_prefix("## "))
+        .map(str::trim)
+        .filter(|title| !title.contains("Agent"))
+        .map(|title| Step {
+            id: step_id(title),
+            title: title.to_string(),
+        })
+        .collect()
+}
+
+/// A step id from its title, e.g. `environment-setup` for "Environment Setup"
+fn step_id(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Load the progress of every started tutorial, which is empty when none was saved
+pub fn load(path: &Path) -> Vec<TutorialProgress> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        tracing::warn!(
+            "Ignoring unreadable tutorial progress {}: {}",
+            path.display(),
+            e
+        );
+        Vec::new()
+    })
+}
+
+pub fn save(path: &Path, progress: &[TutorialProgress]) -> Result<(), ToolError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to create progress directory: {}", e))
+        })?;
+    }
+    let json = serde_json::to_string_pretty(progress)
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to serialize progress: {}", e)))?;
+    fs::write(path, json)
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to save progress: {}", e)))
+}
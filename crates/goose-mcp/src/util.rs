@@ -0,0 +1,67 @@
+//! Helpers shared by the routers of web APIs
+
+use mcp_core::{handler::ToolError, Content};
+use reqwest::{RequestBuilder, StatusCode};
+use serde_json::Value;
+
+/// Get a string parameter of a tool call, which must be present
+pub fn required_str<'a>(params: &'a Value, name: &str) -> Result<&'a str, ToolError> {
+    params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters(format!("Missing '{}' parameter", name)))
+}
+
+/// Get the `limit` parameter of a tool call, which is at least 1
+pub fn limit(params: &Value, default: u64) -> usize {
+    params["limit"].as_u64().unwrap_or(default).max(1) as usize
+}
+
+/// Format a value as pretty printed JSON
+pub fn json_content(value: &Value) -> Content {
+    Content::text(serde_json::to_string_pretty(value).unwrap_or_default())
+}
+
+/// Send a request to a web API and parse the JSON response, which is `Null` when empty
+///
+/// The status is returned along with the body, for the caller to report errors the way the API
+/// describes them. `service` names the API in the errors of failed requests.
+pub async fn send_json(
+    request: RequestBuilder,
+    service: &str,
+) -> Result<(StatusCode, Value), ToolError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("{} request failed: {}", service, e)))?;
+
+    let status = response.status();
+    let invalid = |e: &dyn std::fmt::Display| {
+        ToolError::ExecutionError(format!("Invalid response from {}: {}", service, e))
+    };
+    let text = response.text().await.map_err(|e| invalid(&e))?;
+    // Some successful requests, like updates and deletions, have no content
+    let body = if text.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(&text).map_err(|e| invalid(&e))?
+    };
+    Ok((status, body))
+}
+
+/// Helpers for the tests of routers
+#[cfg(test)]
+pub mod testing {
+    use mcp_core::Content;
+    use serde_json::Value;
+
+    /// The text of a tool result
+    pub fn text(result: Vec<Content>) -> String {
+        result[0].as_text().unwrap().to_string()
+    }
+
+    /// The JSON of a tool result
+    pub fn parse(result: Vec<Content>) -> Value {
+        serde_json::from_str(result[0].as_text().unwrap()).unwrap()
+    }
+}
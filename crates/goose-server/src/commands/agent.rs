@@ -7,6 +7,9 @@ use goose::agents::Agent;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
+/// The secret key when GOOSE_SERVER__SECRET_KEY is not set
+const DEFAULT_SECRET_KEY: &str = "test";
+
 pub async fn run() -> Result<()> {
     // Initialize logging
     crate::logging::setup_logging(Some("goosed"))?;
@@ -15,13 +18,26 @@ pub async fn run() -> Result<()> {
     let settings = configuration::Settings::new()?;
 
     // load secret key from GOOSE_SERVER__SECRET_KEY environment variable
-    let secret_key =
-        std::env::var("GOOSE_SERVER__SECRET_KEY").unwrap_or_else(|_| "test".to_string());
+    let secret_key = std::env::var("GOOSE_SERVER__SECRET_KEY")
+        .unwrap_or_else(|_| DEFAULT_SECRET_KEY.to_string());
+
+    // Require tokens signed with GOOSE_SERVER_SECRET when it is set
+    let jwt_secret = crate::routes::jwt::secret_from_env();
+
+    // Tokens are issued to holders of the secret key, so the well-known default would let
+    // anyone get one
+    if jwt_secret.is_some() && secret_key == DEFAULT_SECRET_KEY {
+        anyhow::bail!(
+            "GOOSE_SERVER__SECRET_KEY must be set to a key other than the default when JWT \
+             authentication is enabled with {}",
+            crate::routes::jwt::JWT_SECRET_ENV
+        );
+    }
 
     let new_agent = Agent::new();
 
     // Create app state with agent
-    let state = state::AppState::new(Arc::new(new_agent), secret_key.clone()).await;
+    let state = state::AppState::new(Arc::new(new_agent), secret_key, jwt_secret).await;
 
     // Create router with CORS support
     let cors = CorsLayer::new()
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use goose_mcp::{
-    ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, JetBrainsRouter, MemoryRouter,
-    TutorialRouter,
+    AzureBlobRouter, ComputerControllerRouter, ConfluenceRouter, DeveloperRouter, EmailRouter,
+    GoogleDriveRouter, GraphQLRouter, JetBrainsRouter, JiraRouter, KubernetesRouter, MemoryRouter,
+    NotionRouter, SlackRouter, TutorialRouter,
 };
 use mcp_server::router::RouterService;
 use mcp_server::{BoundedService, ByteTransport, Server};
@@ -13,14 +14,22 @@ pub async fn run(name: &str) -> Result<()> {
 
     tracing::info!("Starting MCP server");
     let router: Option<Box<dyn BoundedService>> = match name {
+        "azure_blob" => Some(Box::new(RouterService(AzureBlobRouter::new()))),
         "developer" => Some(Box::new(RouterService(DeveloperRouter::new()))),
         "computercontroller" => Some(Box::new(RouterService(ComputerControllerRouter::new()))),
+        "confluence" => Some(Box::new(RouterService(ConfluenceRouter::new()))),
+        "email" => Some(Box::new(RouterService(EmailRouter::new()))),
+        "graphql" => Some(Box::new(RouterService(GraphQLRouter::new()))),
         "jetbrains" => Some(Box::new(RouterService(JetBrainsRouter::new()))),
+        "jira" => Some(Box::new(RouterService(JiraRouter::new()))),
+        "kubernetes" => Some(Box::new(RouterService(KubernetesRouter::new()))),
         "google_drive" | "googledrive" => {
             let router = GoogleDriveRouter::new().await;
             Some(Box::new(RouterService(router)))
         }
         "memory" => Some(Box::new(RouterService(MemoryRouter::new()))),
+        "notion" => Some(Box::new(RouterService(NotionRouter::new()))),
+        "slack" => Some(Box::new(RouterService(SlackRouter::new()))),
         "tutorial" => Some(Box::new(RouterService(TutorialRouter::new()))),
         _ => None,
     };
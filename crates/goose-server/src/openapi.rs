@@ -1,6 +1,9 @@
 use goose::agents::extension::Envs;
 use goose::agents::extension::ToolInfo;
-use goose::agents::ExtensionConfig;
+use goose::agents::{
+    AgentCapabilities, ExtensionCapability, ExtensionConfig, ProviderCapability, ToolCallRecord,
+    ToolCapability, ToolStats,
+};
 use goose::config::permission::PermissionLevel;
 use goose::config::ExtensionEntry;
 use goose::permission::permission_confirmation::PrincipalType;
@@ -24,6 +27,7 @@ use utoipa::OpenApi;
         super::routes::config_management::providers,
         super::routes::config_management::upsert_permissions,
         super::routes::agent::get_tools,
+        super::routes::agent::get_capabilities,
         super::routes::reply::confirm_permission,
     ),
     components(schemas(
@@ -48,6 +52,12 @@ use utoipa::OpenApi;
         PermissionLevel,
         PrincipalType,
         ModelInfo,
+        AgentCapabilities,
+        ToolCapability,
+        ExtensionCapability,
+        ProviderCapability,
+        ToolCallRecord,
+        ToolStats,
     ))
 )]
 pub struct ApiDoc;
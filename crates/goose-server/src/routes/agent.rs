@@ -11,7 +11,7 @@ use goose::config::PermissionManager;
 use goose::model::ModelConfig;
 use goose::providers::create;
 use goose::{
-    agents::{extension::ToolInfo, extension_manager::get_parameter_names},
+    agents::{extension::ToolInfo, extension_manager::get_parameter_names, AgentCapabilities},
     config::permission::PermissionLevel,
 };
 use serde::{Deserialize, Serialize};
@@ -173,6 +173,29 @@ async fn get_tools(
     Ok(Json(tools))
 }
 
+#[utoipa::path(
+    get,
+    path = "/agent/capabilities",
+    responses(
+        (status = 200, description = "Capabilities retrieved successfully", body = AgentCapabilities),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 412, description = "Agent not initialized")
+    )
+)]
+async fn get_capabilities(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<AgentCapabilities>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    Ok(Json(agent.capabilities().await))
+}
+
 #[utoipa::path(
     post,
     path = "/agent/update_provider",
@@ -186,15 +209,7 @@ async fn update_agent_provider(
     headers: HeaderMap,
     Json(payload): Json<UpdateProviderRequest>,
 ) -> Result<StatusCode, StatusCode> {
-    // Verify secret key
-    let secret_key = headers
-        .get("X-Secret-Key")
-        .and_then(|value| value.to_str().ok())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
-
-    if secret_key != state.secret_key {
-        return Err(StatusCode::UNAUTHORIZED);
-    }
+    verify_secret_key(&headers, &state)?;
 
     let agent = state
         .get_agent()
@@ -223,6 +238,7 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/agent/providers", get(list_providers))
         .route("/agent/prompt", post(extend_prompt))
         .route("/agent/tools", get(get_tools))
+        .route("/agent/capabilities", get(get_capabilities))
         .route("/agent/update_provider", post(update_agent_provider))
         .with_state(state)
 }
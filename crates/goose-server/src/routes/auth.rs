@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{extract::State, routing::get, Json, Router};
+use goose_mcp::google_drive::auth_status::{auth_status_path, AuthStatus};
+use http::{HeaderMap, StatusCode};
+
+/// Report the Google Drive credential status recorded after the last token refresh
+///
+/// Before any refresh has been recorded, the credentials are reported as not authenticated
+/// without requiring re-authentication.
+async fn auth_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<AuthStatus>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(
+        AuthStatus::read(&auth_status_path()).unwrap_or_default(),
+    ))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/auth-status", get(auth_status))
+        .with_state(state)
+}
@@ -13,5 +13,7 @@ async fn status() -> Json<StatusResponse> {
 
 /// Configure health check routes
 pub fn routes() -> Router {
-    Router::new().route("/status", get(status))
+    Router::new()
+        .route("/health", get(status))
+        .route("/status", get(status))
 }
@@ -0,0 +1,319 @@
+use super::utils::has_secret_key;
+use crate::state::AppState;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, Request, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use futures::future::BoxFuture;
+use jsonwebtoken::{
+    get_current_timestamp, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// Environment variable holding the HS256 secret tokens are signed with
+pub const JWT_SECRET_ENV: &str = "GOOSE_SERVER_SECRET";
+
+/// How long issued tokens are valid for
+pub const TOKEN_TTL_SECONDS: u64 = 15 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    iat: u64,
+    exp: u64,
+}
+
+/// The secret tokens are signed with, when JWT authentication is enabled
+pub fn secret_from_env() -> Option<String> {
+    std::env::var(JWT_SECRET_ENV)
+        .ok()
+        .filter(|secret| !secret.is_empty())
+}
+
+fn issue_token(secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = get_current_timestamp();
+    let claims = Claims {
+        sub: "goose".to_string(),
+        iat: now,
+        exp: now + TOKEN_TTL_SECONDS,
+    };
+    jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+/// Whether the request carries an unexpired `Authorization: Bearer` token signed with the key
+pub fn is_authorized(headers: &HeaderMap, key: &DecodingKey) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| {
+            jsonwebtoken::decode::<Claims>(token, key, &Validation::new(Algorithm::HS256)).is_ok()
+        })
+}
+
+/// Rejects requests without a valid JWT with 401, or lets every request through when no
+/// secret is configured
+#[derive(Clone)]
+pub struct JwtMiddleware {
+    key: Option<Arc<DecodingKey>>,
+}
+
+impl JwtMiddleware {
+    pub fn new(secret: Option<&str>) -> Self {
+        Self {
+            key: secret.map(|secret| Arc::new(DecodingKey::from_secret(secret.as_bytes()))),
+        }
+    }
+}
+
+impl<S> Layer<S> for JwtMiddleware {
+    type Service = JwtService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JwtService {
+            inner,
+            key: self.key.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct JwtService<S> {
+    inner: S,
+    key: Option<Arc<DecodingKey>>,
+}
+
+impl<S> Service<Request<Body>> for JwtService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if let Some(key) = &self.key {
+            if !is_authorized(request.headers(), key) {
+                return Box::pin(async { Ok(StatusCode::UNAUTHORIZED.into_response()) });
+            }
+        }
+        Box::pin(self.inner.call(request))
+    }
+}
+
+#[derive(Serialize)]
+struct TokenResponse {
+    token: String,
+    expires_in: u64,
+}
+
+// Issue a short-lived token, for callers holding the server's secret key. Tokens are not
+// accepted in place of the key here, so they cannot be renewed without it.
+async fn create_token(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<TokenResponse>, StatusCode> {
+    if !has_secret_key(&headers, &state) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let secret = state
+        .jwt_secret
+        .as_deref()
+        .ok_or(StatusCode::PRECONDITION_FAILED)?;
+    let token = issue_token(secret).map_err(|e| {
+        tracing::error!("Failed to issue token: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(TokenResponse {
+        token,
+        expires_in: TOKEN_TTL_SECONDS,
+    }))
+}
+
+/// Routes to get tokens, which are not behind [`JwtMiddleware`]
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/auth/token", post(create_token))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::agents::Agent;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "jwt-secret";
+
+    async fn app() -> Router {
+        let state = AppState::new(
+            Arc::new(Agent::new()),
+            "test-secret".to_string(),
+            Some(SECRET.to_string()),
+        )
+        .await;
+        super::super::configure(state)
+    }
+
+    fn get(uri: &str, token: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder()
+            .uri(uri)
+            .header("x-secret-key", "test-secret");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_routes_require_token() {
+        let app = app().await;
+
+        for health_check in ["/health", "/status"] {
+            let response = app.clone().oneshot(get(health_check, None)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(get("/auth-status", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let forged = issue_token("another-secret").unwrap();
+        let response = app
+            .clone()
+            .oneshot(get("/auth-status", Some(&forged)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/auth/token")
+                    .method("POST")
+                    .header("x-secret-key", "test-secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["expires_in"], TOKEN_TTL_SECONDS);
+        let token = body["token"].as_str().unwrap();
+
+        let response = app.oneshot(get("/auth-status", Some(token))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_token_replaces_secret_key() {
+        let app = app().await;
+        let request = |token: Option<&str>| {
+            let mut request = Request::builder().uri("/agent/capabilities");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        let response = app.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let token = issue_token(SECRET).unwrap();
+        let response = app.oneshot(request(Some(&token))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_token_does_not_replace_secret_key_for_new_tokens() {
+        let token = issue_token(SECRET).unwrap();
+        let response = app()
+            .await
+            .oneshot(
+                Request::builder()
+                    .uri("/auth/token")
+                    .method("POST")
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_token_requires_secret_key() {
+        let response = app()
+            .await
+            .oneshot(
+                Request::builder()
+                    .uri("/auth/token")
+                    .method("POST")
+                    .header("x-secret-key", "wrong")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_expired_token_is_rejected() {
+        let key = DecodingKey::from_secret(SECRET.as_bytes());
+        let claims = Claims {
+            sub: "goose".to_string(),
+            iat: 0,
+            exp: 1,
+        };
+        let token = jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        assert!(!is_authorized(&headers, &key));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", issue_token(SECRET).unwrap())
+                .parse()
+                .unwrap(),
+        );
+        assert!(is_authorized(&headers, &key));
+    }
+}
@@ -1,28 +1,42 @@
 // Export route modules
 pub mod agent;
+pub mod auth;
 pub mod config_management;
 pub mod configs;
 pub mod context;
 pub mod extension;
 pub mod health;
+pub mod jwt;
 pub mod recipe;
 pub mod reply;
 pub mod session;
 pub mod utils;
+pub mod ws;
 use std::sync::Arc;
 
 use axum::Router;
 
 // Function to configure all routes
+//
+// With a JWT secret in the state, every route but the health check and token routes requires
+// a token, which is then enough to use them without the secret key.
 pub fn configure(state: Arc<crate::state::AppState>) -> Router {
-    Router::new()
-        .merge(health::routes())
+    let jwt_secret = state.jwt_secret.clone();
+    let protected = Router::new()
         .merge(reply::routes(state.clone()))
         .merge(agent::routes(state.clone()))
+        .merge(auth::routes(state.clone()))
         .merge(context::routes(state.clone()))
         .merge(extension::routes(state.clone()))
         .merge(configs::routes(state.clone()))
         .merge(config_management::routes(state.clone()))
         .merge(recipe::routes(state.clone()))
         .merge(session::routes(state.clone()))
+        .merge(ws::routes(state.clone()))
+        .layer(jwt::JwtMiddleware::new(jwt_secret.as_deref()));
+
+    Router::new()
+        .merge(health::routes())
+        .merge(jwt::routes(state))
+        .merge(protected)
 }
@@ -18,7 +18,7 @@ use goose::{
     permission::{Permission, PermissionConfirmation},
     session,
 };
-use mcp_core::{role::Role, Content, ToolResult};
+use mcp_core::{protocol::ToolProgressEvent, role::Role, Content, ToolResult};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
@@ -101,6 +101,17 @@ async fn stream_event(
     tx.send(format!("data: {}\n\n", json)).await
 }
 
+// Stream tool progress as a named SSE event, so clients that only handle the default
+// message events can ignore it
+async fn stream_progress(
+    event: &ToolProgressEvent,
+    tx: &mpsc::Sender<String>,
+) -> Result<(), mpsc::error::SendError<String>> {
+    let json = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
+    tx.send(format!("event: progress\ndata: {}\n\n", json))
+        .await
+}
+
 async fn handler(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -209,6 +220,14 @@ async fn handler(
                 response = timeout(Duration::from_millis(500), stream.next()) => {
                     match response {
                         Ok(Some(Ok(message))) => {
+                            // Progress of running tools is forwarded but not stored
+                            if let Some(event) = message.content.first().and_then(|c| c.as_tool_progress()) {
+                                if stream_progress(event, &tx).await.is_err() {
+                                    break;
+                                }
+                                continue;
+                            }
+
                             all_messages.push(message.clone());
                             if let Err(e) = stream_event(MessageEvent::Message { message }, &tx).await {
                                 tracing::error!("Error sending message through channel: {}", e);
@@ -334,6 +353,9 @@ async fn ask_handler(
             Ok(message) => {
                 if message.role == Role::Assistant {
                     for content in &message.content {
+                        if let MessageContent::ToolProgress(_) = content {
+                            continue;
+                        }
                         if let MessageContent::Text(text) = content {
                             response_text.push_str(&text.text);
                             response_text.push('\n');
@@ -517,6 +539,23 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_stream_progress_uses_progress_event() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let event = ToolProgressEvent {
+            tool_name: "developer__shell".to_string(),
+            progress_percent: None,
+            message: "line 1".to_string(),
+        };
+
+        stream_progress(&event, &tx).await.unwrap();
+
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            "event: progress\ndata: {\"toolName\":\"developer__shell\",\"message\":\"line 1\"}\n\n"
+        );
+    }
+
     mod integration_tests {
         use super::*;
         use axum::{body::Body, http::Request};
@@ -533,7 +572,7 @@ mod tests {
             });
             let agent = Agent::new();
             let _ = agent.update_provider(mock_provider).await;
-            let state = AppState::new(Arc::new(agent), "test-secret".to_string()).await;
+            let state = AppState::new(Arc::new(agent), "test-secret".to_string(), None).await;
 
             // Build router
             let app = routes(state);
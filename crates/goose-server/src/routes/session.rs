@@ -1,9 +1,9 @@
-use super::utils::verify_secret_key;
+use super::utils::{session_path, verify_secret_key};
 use std::sync::Arc;
 
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     routing::get,
     Json, Router,
@@ -11,11 +11,42 @@ use axum::{
 use goose::message::Message;
 use goose::session;
 use goose::session::info::{get_session_info, SessionInfo, SortOrder};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+struct SessionListQuery {
+    /// Number of sessions to skip, the most recently modified come first
+    offset: Option<usize>,
+    /// Maximum number of sessions to return, all of them when not set
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct SessionListItem {
+    #[serde(flatten)]
+    info: SessionInfo,
+    created: String,
+}
 
 #[derive(Serialize)]
 struct SessionListResponse {
-    sessions: Vec<SessionInfo>,
+    sessions: Vec<SessionListItem>,
+    /// Number of sessions before pagination
+    total: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CreateSessionRequest {
+    working_dir: Option<PathBuf>,
+    description: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CreateSessionResponse {
+    session_id: String,
+    path: PathBuf,
 }
 
 #[derive(Serialize)]
@@ -25,17 +56,109 @@ struct SessionHistoryResponse {
     messages: Vec<Message>,
 }
 
-// List all available sessions
+/// When a session file was created, falling back to its last modification
+fn created_time(path: &std::path::Path) -> String {
+    path.metadata()
+        .and_then(|m| m.created().or_else(|_| m.modified()))
+        .map(|time| {
+            chrono::DateTime::<chrono::Utc>::from(time)
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string()
+        })
+        .unwrap_or_else(|_| "Unknown".to_string())
+}
+
+/// The page of `items` selected by the query
+fn paginate<T>(items: Vec<T>, query: &SessionListQuery) -> Vec<T> {
+    items
+        .into_iter()
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+// List available sessions, the most recently modified first
 async fn list_sessions(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    Query(query): Query<SessionListQuery>,
 ) -> Result<Json<SessionListResponse>, StatusCode> {
     verify_secret_key(&headers, &state)?;
 
     let sessions =
         get_session_info(SortOrder::Descending).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let total = sessions.len();
+    let sessions = paginate(sessions, &query)
+        .into_iter()
+        .map(|info| SessionListItem {
+            created: created_time(std::path::Path::new(&info.path)),
+            info,
+        })
+        .collect();
 
-    Ok(Json(SessionListResponse { sessions }))
+    Ok(Json(SessionListResponse {
+        sessions,
+        total,
+        offset: query.offset.unwrap_or(0),
+    }))
+}
+
+// Create an empty session
+async fn create_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request: Option<Json<CreateSessionRequest>>,
+) -> Result<(StatusCode, Json<CreateSessionResponse>), StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+
+    // Ids are timestamps, so sessions created within the same second get a suffix
+    let base_id = session::generate_session_id();
+    let (session_id, path) = std::iter::once(base_id.clone())
+        .chain((1..).map(|n| format!("{}_{}", base_id, n)))
+        .map(|id| {
+            let path = session::get_path(session::Identifier::Name(id.clone()));
+            (id, path)
+        })
+        .find(|(_, path)| !path.exists())
+        .unwrap();
+
+    let working_dir = request
+        .working_dir
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let mut metadata = session::SessionMetadata::new(working_dir);
+    metadata.description = request.description.unwrap_or_default();
+    session::storage::save_messages_with_metadata(&path, &metadata, &[]).map_err(|e| {
+        tracing::error!("Failed to create session: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateSessionResponse { session_id, path }),
+    ))
+}
+
+// Delete a session
+async fn delete_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session_path(&session_id)?;
+    if !session_path.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    session::remove_session(&session_path).map_err(|e| {
+        tracing::error!("Failed to delete session: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 // Get a specific session's history
@@ -46,7 +169,11 @@ async fn get_session_history(
 ) -> Result<Json<SessionHistoryResponse>, StatusCode> {
     verify_secret_key(&headers, &state)?;
 
-    let session_path = session::get_path(session::Identifier::Name(session_id.clone()));
+    let session_path = session_path(&session_id)?;
+    // Reading the messages would create a missing session
+    if !session_path.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
 
     // Read metadata
     let metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
@@ -69,7 +196,129 @@ async fn get_session_history(
 // Configure routes for this module
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
-        .route("/sessions", get(list_sessions))
-        .route("/sessions/:session_id", get(get_session_history))
+        .route("/sessions", get(list_sessions).post(create_session))
+        .route(
+            "/sessions/:session_id",
+            get(get_session_history).delete(delete_session),
+        )
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use goose::agents::Agent;
+    use serial_test::serial;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_paginate() {
+        let items: Vec<u32> = (0..5).collect();
+        assert_eq!(paginate(items.clone(), &SessionListQuery::default()), items);
+        let query = SessionListQuery {
+            offset: Some(1),
+            limit: Some(2),
+        };
+        assert_eq!(paginate(items.clone(), &query), vec![1, 2]);
+        let query = SessionListQuery {
+            offset: Some(10),
+            limit: None,
+        };
+        assert!(paginate(items, &query).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_requires_valid_session() {
+        let state = AppState::new(Arc::new(Agent::new()), "test-secret".to_string(), None).await;
+        let app = routes(state);
+
+        let delete = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .method("DELETE")
+                .header("x-secret-key", "test-secret")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = app
+            .clone()
+            .oneshot(delete("/sessions/..%2Fconfig"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let missing = format!("/sessions/missing_{}", std::process::id());
+        let response = app.oneshot(delete(&missing)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_list_and_delete_session() {
+        let dir = tempfile::TempDir::new().unwrap();
+        temp_env::async_with_vars([(session::SESSION_DIR_ENV, Some(dir.path()))], async {
+            let state =
+                AppState::new(Arc::new(Agent::new()), "test-secret".to_string(), None).await;
+            let app = routes(state);
+            let request = |method: &str, uri: &str| {
+                Request::builder()
+                    .uri(uri)
+                    .method(method)
+                    .header("x-secret-key", "test-secret")
+                    .body(Body::empty())
+                    .unwrap()
+            };
+            let json = |response: axum::response::Response| async move {
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            };
+
+            let response = app
+                .clone()
+                .oneshot(request("POST", "/sessions"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let created = json(response).await["session_id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+
+            // Sessions named on the command line can contain dots and spaces
+            session::storage::save_messages_with_metadata(
+                &dir.path().join("release v1.2.jsonl"),
+                &session::SessionMetadata::new(dir.path().to_path_buf()),
+                &[],
+            )
+            .unwrap();
+
+            let response = app
+                .clone()
+                .oneshot(request("GET", "/sessions"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let list = json(response).await;
+            assert_eq!(list["total"], 2);
+
+            for uri in [
+                format!("/sessions/{}", created),
+                "/sessions/release%20v1.2".into(),
+            ] {
+                let response = app.clone().oneshot(request("GET", &uri)).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+                let response = app.clone().oneshot(request("DELETE", &uri)).await.unwrap();
+                assert_eq!(response.status(), StatusCode::NO_CONTENT, "{}", uri);
+                let response = app.clone().oneshot(request("GET", &uri)).await.unwrap();
+                assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+            }
+
+            let response = app.oneshot(request("GET", "/sessions")).await.unwrap();
+            assert_eq!(json(response).await["total"], 0);
+        })
+        .await;
+    }
+}
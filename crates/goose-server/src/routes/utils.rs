@@ -1,10 +1,14 @@
 use crate::state::AppState;
 use goose::config::Config;
 use goose::providers::base::{ConfigKey, ProviderMetadata};
+use goose::session;
 use http::{HeaderMap, StatusCode};
+use jsonwebtoken::DecodingKey;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::error::Error;
+use std::path::{Component, Path, PathBuf};
+use subtle::ConstantTimeEq;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum KeyLocation {
@@ -23,18 +27,45 @@ pub struct KeyInfo {
     pub value: Option<String>, // Only populated for non-secret keys that are set
 }
 
+/// Check the `X-Secret-Key` header, or with JWT authentication enabled, the token that
+/// [`super::jwt::JwtMiddleware`] requires, which stands in for the secret key
 pub fn verify_secret_key(headers: &HeaderMap, state: &AppState) -> Result<StatusCode, StatusCode> {
-    // Verify secret key
-    let secret_key = headers
-        .get("X-Secret-Key")
-        .and_then(|value| value.to_str().ok())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+    if let Some(secret) = &state.jwt_secret {
+        if super::jwt::is_authorized(headers, &DecodingKey::from_secret(secret.as_bytes())) {
+            return Ok(StatusCode::OK);
+        }
+    }
 
-    if secret_key != state.secret_key {
-        Err(StatusCode::UNAUTHORIZED)
-    } else {
+    if has_secret_key(headers, state) {
         Ok(StatusCode::OK)
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Whether the `X-Secret-Key` header holds the server's secret key, compared in constant time
+pub fn has_secret_key(headers: &HeaderMap, state: &AppState) -> bool {
+    headers
+        .get("X-Secret-Key")
+        .is_some_and(|value| bool::from(value.as_bytes().ct_eq(state.secret_key.as_bytes())))
+}
+
+/// The file of a session, rejecting ids that could point outside the session directory
+///
+/// Sessions named on the command line can contain any character, so ids are only required to
+/// be a single file name.
+pub fn session_path(session_id: &str) -> Result<PathBuf, StatusCode> {
+    let mut components = Path::new(session_id).components();
+    let valid = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(name)), None) if name == session_id
+    );
+    if !valid {
+        return Err(StatusCode::BAD_REQUEST);
     }
+    Ok(session::get_path(session::Identifier::Name(
+        session_id.to_string(),
+    )))
 }
 
 /// Inspects a configuration key to determine if it's set, its location, and value (for non-secret keys)
@@ -154,3 +185,22 @@ pub fn check_provider_configured(metadata: &ProviderMetadata) -> bool {
         is_set_in_env || is_set_in_config
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_path_rejects_other_directories() {
+        assert!(session_path("20250101_120000").is_ok());
+        assert!(session_path("my-session_2").is_ok());
+        assert!(session_path("release v1.2").is_ok());
+        assert_eq!(session_path(""), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(session_path("."), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(session_path(".."), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(session_path("../config"), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(session_path("a/b"), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(session_path("a/"), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(session_path("/etc"), Err(StatusCode::BAD_REQUEST));
+    }
+}
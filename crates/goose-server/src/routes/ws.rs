@@ -0,0 +1,303 @@
+use super::utils::{session_path, verify_secret_key};
+use crate::state::AppState;
+use axum::{
+    extract::{
+        ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use futures::StreamExt;
+use goose::{agents::SessionConfig, message::Message, session};
+use mcp_core::protocol::ToolProgressEvent;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
+
+// A message to send to the agent, as a text frame
+#[derive(Debug, Deserialize)]
+struct AgentRequest {
+    session_id: String,
+    message: String,
+    session_working_dir: Option<String>,
+}
+
+// Frames sent back while the agent replies, ending with `done`
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum AgentEvent {
+    Message { message: Message },
+    Progress { progress: ToolProgressEvent },
+    Error { error: String },
+    Done,
+}
+
+async fn send_event(socket: &mut WebSocket, event: AgentEvent) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(&event).unwrap_or_else(|e| {
+        format!(
+            r#"{{"type":"error","error":"Failed to serialize event: {}"}}"#,
+            e
+        )
+    });
+    socket.send(WsMessage::Text(json)).await
+}
+
+/// Reply to one request, continuing the conversation of its session
+///
+/// Errors of the agent are sent as `error` frames, only failures to send end the connection.
+async fn stream_reply(
+    socket: &mut WebSocket,
+    state: &AppState,
+    request: AgentRequest,
+) -> Result<(), axum::Error> {
+    let agent = match state.get_agent().await {
+        Ok(agent) => agent,
+        Err(e) => {
+            return send_event(
+                socket,
+                AgentEvent::Error {
+                    error: e.to_string(),
+                },
+            )
+            .await
+        }
+    };
+    let provider = match agent.provider().await {
+        Ok(provider) => provider,
+        Err(_) => {
+            let error = "No provider configured".to_string();
+            return send_event(socket, AgentEvent::Error { error }).await;
+        }
+    };
+
+    let Ok(session_path) = session_path(&request.session_id) else {
+        let error = format!("Invalid session id: {}", request.session_id);
+        return send_event(socket, AgentEvent::Error { error }).await;
+    };
+    let identifier = session::Identifier::Name(request.session_id);
+    let mut messages = if session_path.exists() {
+        session::read_messages(&session_path).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    messages.push(Message::user().with_text(request.message));
+
+    let working_dir = request
+        .session_working_dir
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let mut stream = match agent
+        .reply(
+            &messages,
+            Some(SessionConfig {
+                id: identifier,
+                working_dir,
+            }),
+        )
+        .await
+    {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::error!("Failed to start reply stream: {:?}", e);
+            return send_event(
+                socket,
+                AgentEvent::Error {
+                    error: e.to_string(),
+                },
+            )
+            .await;
+        }
+    };
+
+    let mut result = Ok(());
+    while let Some(response) = stream.next().await {
+        let event = match response {
+            Ok(message) => match message.content.first().and_then(|c| c.as_tool_progress()) {
+                // Progress of running tools is forwarded but not stored
+                Some(progress) => AgentEvent::Progress {
+                    progress: progress.clone(),
+                },
+                None => {
+                    messages.push(message.clone());
+                    AgentEvent::Message { message }
+                }
+            },
+            Err(e) => {
+                tracing::error!("Error processing message: {}", e);
+                AgentEvent::Error {
+                    error: e.to_string(),
+                }
+            }
+        };
+        let is_error = matches!(event, AgentEvent::Error { .. });
+        result = send_event(socket, event).await;
+        if result.is_err() || is_error {
+            break;
+        }
+    }
+    drop(stream);
+
+    if let Err(e) = session::persist_messages(&session_path, &messages, Some(provider)).await {
+        tracing::error!("Failed to store session history: {:?}", e);
+    }
+    result
+}
+
+async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
+    while let Some(Ok(frame)) = socket.recv().await {
+        let text = match frame {
+            WsMessage::Text(text) => text,
+            WsMessage::Close(_) => break,
+            _ => continue,
+        };
+
+        let sent = match serde_json::from_str::<AgentRequest>(&text) {
+            Ok(request) => stream_reply(&mut socket, &state, request).await,
+            Err(e) => {
+                let error = format!("Invalid request, expected {{session_id, message}}: {}", e);
+                send_event(&mut socket, AgentEvent::Error { error }).await
+            }
+        };
+        if sent.is_err() || send_event(&mut socket, AgentEvent::Done).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Stream the agent's replies to messages sent over a WebSocket
+async fn agent_socket(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, state)))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/ws/agent", get(agent_socket))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::SinkExt;
+    use goose::{
+        agents::Agent,
+        model::ModelConfig,
+        providers::{
+            base::{Provider, ProviderMetadata, ProviderUsage, Usage},
+            errors::ProviderError,
+        },
+    };
+    use mcp_core::tool::Tool;
+    use serde_json::{json, Value};
+    use serial_test::serial;
+    use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message as ClientMessage};
+
+    struct MockProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("test-model".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text("Mock response"),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    /// Send a text frame and collect the frames received up to `done`
+    async fn exchange<S>(socket: &mut S, text: String) -> Vec<Value>
+    where
+        S: futures::Stream<Item = Result<ClientMessage, tokio_tungstenite::tungstenite::Error>>
+            + futures::Sink<ClientMessage>
+            + Unpin,
+        S::Error: std::fmt::Debug,
+    {
+        socket.send(ClientMessage::Text(text)).await.unwrap();
+        let mut frames = Vec::new();
+        while let Some(Ok(ClientMessage::Text(text))) = socket.next().await {
+            let frame: Value = serde_json::from_str(&text).unwrap();
+            let done = frame["type"] == "done";
+            frames.push(frame);
+            if done {
+                break;
+            }
+        }
+        frames
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_agent_socket_streams_reply() {
+        let dir = tempfile::TempDir::new().unwrap();
+        temp_env::async_with_vars(
+            [(session::SESSION_DIR_ENV, Some(dir.path()))],
+            agent_socket_streams_reply(),
+        )
+        .await;
+    }
+
+    async fn agent_socket_streams_reply() {
+        let agent = Agent::new();
+        agent.update_provider(Arc::new(MockProvider)).await.unwrap();
+        let state = AppState::new(Arc::new(agent), "test-secret".to_string(), None).await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, routes(state)).await });
+
+        let url = format!("ws://{}/ws/agent", addr);
+        assert!(tokio_tungstenite::connect_async(url.as_str())
+            .await
+            .is_err());
+
+        let mut request = url.into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("X-Secret-Key", "test-secret".parse().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+
+        let frames = exchange(&mut socket, "not json".to_string()).await;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "error");
+
+        let session_id = session::generate_session_id();
+        let request = json!({"session_id": session_id, "message": "Hello"});
+        let frames = exchange(&mut socket, request.to_string()).await;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "message");
+        assert_eq!(frames[0]["message"]["role"], "assistant");
+        assert_eq!(frames[0]["message"]["content"][0]["text"], "Mock response");
+        assert_eq!(frames[1], json!({"type": "done"}));
+
+        let session_path = session::get_path(session::Identifier::Name(session_id));
+        assert_eq!(session::read_messages(&session_path).unwrap().len(), 2);
+
+        let request = json!({"session_id": "../../escaped", "message": "Hello"});
+        let frames = exchange(&mut socket, request.to_string()).await;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "error");
+        assert_eq!(frames[0]["error"], "Invalid session id: ../../escaped");
+    }
+}
@@ -16,13 +16,20 @@ pub struct AppState {
     // agent: SharedAgentStore,
     agent: Option<AgentRef>,
     pub secret_key: String,
+    /// The secret tokens are signed with, when JWT authentication is enabled
+    pub jwt_secret: Option<String>,
 }
 
 impl AppState {
-    pub async fn new(agent: AgentRef, secret_key: String) -> Arc<AppState> {
+    pub async fn new(
+        agent: AgentRef,
+        secret_key: String,
+        jwt_secret: Option<String>,
+    ) -> Arc<AppState> {
         Arc::new(Self {
             agent: Some(agent.clone()),
             secret_key,
+            jwt_secret,
         })
     }
 
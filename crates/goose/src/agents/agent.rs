@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, Result};
 use futures::stream::BoxStream;
@@ -12,6 +13,7 @@ use crate::permission::PermissionConfirmation;
 use crate::providers::base::Provider;
 use crate::providers::errors::ProviderError;
 use crate::recipe::{Author, Recipe};
+use crate::session;
 use regex::Regex;
 use serde_json::Value;
 use tokio::sync::{mpsc, Mutex};
@@ -19,15 +21,21 @@ use tracing::{debug, error, instrument};
 
 use crate::agents::extension::{ExtensionConfig, ExtensionResult, ToolInfo};
 use crate::agents::extension_manager::{get_parameter_names, ExtensionManager};
+use crate::agents::hooks::{FileAuditHook, ToolCallHook, ToolCallRecord};
 use crate::agents::platform_tools::{
     PLATFORM_LIST_RESOURCES_TOOL_NAME, PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME,
     PLATFORM_READ_RESOURCE_TOOL_NAME, PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME,
 };
 use crate::agents::prompt_manager::PromptManager;
+use crate::agents::retry::{complete_with_retry, ProviderRetryConfig};
+use crate::agents::tool_history::{ToolCallHistory, DEFAULT_TOOL_HISTORY_LIMIT};
 use crate::agents::types::SessionConfig;
-use crate::agents::types::{FrontendTool, ToolResultReceiver};
+use crate::agents::types::{FrontendTool, ToolRestrictions, ToolResultReceiver};
 use mcp_core::{
-    prompt::Prompt, protocol::GetPromptResult, tool::Tool, Content, ToolError, ToolResult,
+    prompt::Prompt,
+    protocol::{GetPromptResult, ToolProgressEvent},
+    tool::Tool,
+    Content, ToolError, ToolResult,
 };
 
 use super::platform_tools;
@@ -44,6 +52,11 @@ pub struct Agent {
     pub(super) confirmation_rx: Mutex<mpsc::Receiver<(String, PermissionConfirmation)>>,
     pub(super) tool_result_tx: mpsc::Sender<(String, ToolResult<Vec<Content>>)>,
     pub(super) tool_result_rx: ToolResultReceiver,
+    pub(super) tool_restrictions: Mutex<ToolRestrictions>,
+    pub(super) tool_progress_tx: mpsc::Sender<ToolProgressEvent>,
+    pub(super) tool_progress_rx: Mutex<mpsc::Receiver<ToolProgressEvent>>,
+    pub(super) tool_hooks: Mutex<Vec<Box<dyn ToolCallHook + Send + Sync>>>,
+    pub(super) tool_call_history: Mutex<ToolCallHistory>,
 }
 
 impl Agent {
@@ -51,6 +64,15 @@ impl Agent {
         // Create channels with buffer size 32 (adjust if needed)
         let (confirm_tx, confirm_rx) = mpsc::channel(32);
         let (tool_tx, tool_rx) = mpsc::channel(32);
+        let (progress_tx, progress_rx) = mpsc::channel(32);
+
+        let mut tool_hooks: Vec<Box<dyn ToolCallHook + Send + Sync>> = Vec::new();
+        if let Some(audit_hook) = FileAuditHook::from_config(Config::global()) {
+            tool_hooks.push(Box::new(audit_hook));
+        }
+        let tool_history_limit = Config::global()
+            .get_param::<usize>("GOOSE_TOOL_HISTORY_LIMIT")
+            .unwrap_or(DEFAULT_TOOL_HISTORY_LIMIT);
 
         Self {
             provider: Mutex::new(None),
@@ -62,6 +84,11 @@ impl Agent {
             confirmation_rx: Mutex::new(confirm_rx),
             tool_result_tx: tool_tx,
             tool_result_rx: Arc::new(Mutex::new(tool_rx)),
+            tool_restrictions: Mutex::new(ToolRestrictions::default()),
+            tool_progress_tx: progress_tx,
+            tool_progress_rx: Mutex::new(progress_rx),
+            tool_hooks: Mutex::new(tool_hooks),
+            tool_call_history: Mutex::new(ToolCallHistory::new(tool_history_limit)),
         }
     }
 }
@@ -110,11 +137,56 @@ impl Agent {
     }
 
     /// Dispatch a single tool call to the appropriate client
-    #[instrument(skip(self, tool_call, request_id), fields(input, output))]
+    ///
+    /// The call is recorded in the tool call history, and registered tool hooks are notified
+    /// once it completes.
+    #[instrument(skip(self, tool_call, request_id, session_id), fields(input, output))]
     pub(super) async fn dispatch_tool_call(
         &self,
         tool_call: mcp_core::tool::ToolCall,
         request_id: String,
+        session_id: Option<String>,
+    ) -> (String, Result<Vec<Content>, ToolError>) {
+        let started = Instant::now();
+        let (request_id, result) = self.execute_tool_call(&tool_call, request_id).await;
+        self.record_tool_call(&tool_call, session_id, &result, started)
+            .await;
+        (request_id, result)
+    }
+
+    async fn record_tool_call(
+        &self,
+        tool_call: &mcp_core::tool::ToolCall,
+        session_id: Option<String>,
+        result: &Result<Vec<Content>, ToolError>,
+        started: Instant,
+    ) {
+        if result.as_ref().is_err_and(is_timeout) {
+            return;
+        }
+
+        let record = ToolCallRecord {
+            session_id,
+            tool_name: tool_call.name.clone(),
+            extension: tool_call
+                .name
+                .split_once("__")
+                .map(|(extension, _)| extension.to_string()),
+            arguments: tool_call.arguments.clone(),
+            result_ok: result.is_ok(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            timestamp: chrono::Utc::now(),
+        };
+        for hook in self.tool_hooks.lock().await.iter() {
+            hook.on_tool_call(&record);
+        }
+        self.tool_call_history.lock().await.push(record);
+    }
+
+    async fn execute_tool_call(
+        &self,
+        tool_call: &mcp_core::tool::ToolCall,
+        request_id: String,
     ) -> (String, Result<Vec<Content>, ToolError>) {
         if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
             let extension_name = tool_call
@@ -153,7 +225,7 @@ impl Agent {
             ))
         } else {
             extension_manager
-                .dispatch_tool_call(tool_call.clone())
+                .dispatch_tool_call(tool_call.clone(), Some(self.tool_progress_tx.clone()))
                 .await
         };
 
@@ -326,10 +398,17 @@ impl Agent {
             self.prepare_tools_and_prompt().await?;
 
         let goose_mode = config.get_param("GOOSE_MODE").unwrap_or("auto".to_string());
+        let retry_config = ProviderRetryConfig::from_config(config);
 
         let (tools_with_readonly_annotation, tools_without_annotation) =
             Self::categorize_tools_by_annotation(&tools);
 
+        // Identify the session in tool call records, by name or by session file
+        let session_id = session.as_ref().map(|session| match &session.id {
+            session::Identifier::Name(name) => name.clone(),
+            session::Identifier::Path(path) => path.display().to_string(),
+        });
+
         if let Some(content) = messages
             .last()
             .and_then(|msg| msg.content.first())
@@ -343,6 +422,7 @@ impl Agent {
             loop {
                 match Self::generate_response_from_provider(
                     self.provider().await?,
+                    &retry_config,
                     &system_prompt,
                     &messages,
                     &tools,
@@ -387,6 +467,13 @@ impl Agent {
                             yield msg;
                         }
 
+                        // Deny tool calls excluded by the session's tool restrictions, reporting
+                        // the denial as a tool response so the model can adjust
+                        let remaining_requests = self.apply_tool_restrictions(
+                            remaining_requests,
+                            message_tool_response.clone(),
+                        ).await;
+
                         // Clone goose_mode once before the match to avoid move issues
                         let mode = goose_mode.clone();
                         if mode.as_str() == "chat" {
@@ -417,7 +504,7 @@ impl Agent {
                             // Skip the confirmation for approved tools
                             for request in &permission_check_result.approved {
                                 if let Ok(tool_call) = request.tool_call.clone() {
-                                    let tool_future = self.dispatch_tool_call(tool_call, request.id.clone());
+                                    let tool_future = self.dispatch_tool_call(tool_call, request.id.clone(), session_id.clone());
                                     tool_futures.push(Box::pin(tool_future));
                                 }
                             }
@@ -439,6 +526,7 @@ impl Agent {
                                 tool_futures_arc.clone(),
                                 &mut permission_manager,
                                 message_tool_response.clone(),
+                                session_id.clone(),
                             );
 
                             // We have a stream of tool_approval_requests to handle
@@ -456,8 +544,22 @@ impl Agent {
                                 futures_lock.drain(..).collect::<Vec<_>>()
                             };
 
-                            // Wait for all tool calls to complete
-                            let results = futures::future::join_all(tool_futures).await;
+                            // Wait for all tool calls to complete, yielding the progress they
+                            // report in the meantime
+                            let mut progress_rx = self.tool_progress_rx.lock().await;
+                            let all_tools = futures::future::join_all(tool_futures);
+                            tokio::pin!(all_tools);
+                            let results = loop {
+                                let event = tokio::select! {
+                                    results = &mut all_tools => break results,
+                                    Some(event) = progress_rx.recv() => event,
+                                };
+                                yield Message::assistant().with_tool_progress(event);
+                            };
+                            while let Ok(event) = progress_rx.try_recv() {
+                                yield Message::assistant().with_tool_progress(event);
+                            }
+                            drop(progress_rx);
                             let mut all_install_successful = true;
 
                             for (request_id, output) in results.into_iter() {
@@ -509,6 +611,23 @@ impl Agent {
         prompt_manager.add_system_prompt_extra(instruction);
     }
 
+    /// Replace the instructions given with the conversation, such as its system messages,
+    /// keeping those added with extend_system_prompt
+    pub async fn set_system_prompt_extras(&self, instructions: Vec<String>) {
+        let mut prompt_manager = self.prompt_manager.lock().await;
+        prompt_manager.set_system_prompt_extras(instructions);
+    }
+
+    /// Register a hook that is notified after every completed tool call
+    pub async fn add_tool_hook(&self, hook: Box<dyn ToolCallHook + Send + Sync>) {
+        self.tool_hooks.lock().await.push(hook);
+    }
+
+    /// Restrict which tools the agent may call
+    pub async fn set_tool_restrictions(&self, restrictions: ToolRestrictions) {
+        *self.tool_restrictions.lock().await = restrictions;
+    }
+
     /// Update the provider used by this agent
     pub async fn update_provider(&self, provider: Arc<dyn Provider>) -> Result<()> {
         *self.provider.lock().await = Some(provider);
@@ -600,14 +719,14 @@ impl Agent {
 
         messages.push(Message::user().with_text(recipe_prompt));
 
-        let (result, _usage) = self
-            .provider
-            .lock()
-            .await
-            .as_ref()
-            .unwrap()
-            .complete(&system_prompt, &messages, &tools)
-            .await?;
+        let (result, _usage) = complete_with_retry(
+            provider.as_ref(),
+            &ProviderRetryConfig::from_config(Config::global()),
+            &system_prompt,
+            &messages,
+            &tools,
+        )
+        .await?;
 
         let content = result.as_concat_text();
 
@@ -704,3 +823,8 @@ impl Agent {
         Ok(recipe)
     }
 }
+
+/// Whether a tool call failed because the extension did not respond in time
+fn is_timeout(error: &ToolError) -> bool {
+    matches!(error, ToolError::Timeout(_))
+}
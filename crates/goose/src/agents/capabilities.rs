@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{Agent, ToolCallRecord, ToolStats};
+
+/// Name reported as the extension of tools provided by the frontend
+const FRONTEND_EXTENSION: &str = "frontend";
+
+/// A tool the agent can call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ToolCapability {
+    pub name: String,
+    pub description: String,
+    /// The extension providing the tool
+    pub extension: String,
+}
+
+/// An extension of the agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtensionCapability {
+    pub name: String,
+    /// How the extension is run (`builtin`, `stdio`, `sse` or `frontend`)
+    #[serde(rename = "type")]
+    pub extension_type: String,
+    /// False for extensions enabled in the config that could not be started
+    pub connected: bool,
+}
+
+/// The provider and model the agent completes with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProviderCapability {
+    pub name: String,
+    pub model: String,
+}
+
+/// What an agent can currently do: its tools, extensions and model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AgentCapabilities {
+    pub tools: Vec<ToolCapability>,
+    pub extensions: Vec<ExtensionCapability>,
+    /// None until a provider is set
+    pub provider: Option<ProviderCapability>,
+    /// Context window of the model in tokens, when known for the model
+    pub context_window: Option<u32>,
+    pub supports_vision: bool,
+    /// Whether the model calls tools natively, rather than through the toolshim
+    pub supports_tool_use: bool,
+    /// The most recent tool calls, oldest first
+    pub tool_call_history: Vec<ToolCallRecord>,
+    /// Statistics per tool over every call the agent has made
+    pub tool_call_stats: HashMap<String, ToolStats>,
+}
+
+impl Agent {
+    /// Describe the tools, extensions and model currently available to the agent
+    pub async fn capabilities(&self) -> AgentCapabilities {
+        let frontend_tools = self.frontend_tools.lock().await.clone();
+
+        let mut tools: Vec<ToolCapability> = self
+            .list_tools(None)
+            .await
+            .into_iter()
+            .map(|tool| ToolCapability {
+                extension: tool
+                    .name
+                    .split_once("__")
+                    .map(|(extension, _)| extension.to_string())
+                    .unwrap_or_default(),
+                name: tool.name,
+                description: tool.description,
+            })
+            .chain(frontend_tools.values().map(|frontend_tool| ToolCapability {
+                name: frontend_tool.name.clone(),
+                description: frontend_tool.tool.description.clone(),
+                extension: FRONTEND_EXTENSION.to_string(),
+            }))
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut extensions = self.extension_manager.lock().await.get_extension_statuses();
+        if !frontend_tools.is_empty() {
+            extensions.push(ExtensionCapability {
+                name: FRONTEND_EXTENSION.to_string(),
+                extension_type: FRONTEND_EXTENSION.to_string(),
+                connected: true,
+            });
+        }
+        extensions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let (provider, context_window, supports_vision, supports_tool_use) =
+            match self.provider().await {
+                Ok(provider) => {
+                    let model_config = provider.get_model_config();
+                    (
+                        Some(ProviderCapability {
+                            name: provider.get_name(),
+                            model: model_config.model_name.clone(),
+                        }),
+                        model_config
+                            .context_limit
+                            .map(|limit| u32::try_from(limit).unwrap_or(u32::MAX)),
+                        provider.supports_vision(),
+                        !model_config.toolshim,
+                    )
+                }
+                Err(_) => (None, None, false, false),
+            };
+
+        AgentCapabilities {
+            tools,
+            extensions,
+            provider,
+            context_window,
+            supports_vision,
+            supports_tool_use,
+            tool_call_history: self.tool_call_history().await,
+            tool_call_stats: self.tool_call_stats().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::ExtensionConfig;
+    use crate::message::Message;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::errors::ProviderError;
+    use mcp_core::tool::Tool;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    struct MockProvider {
+        model_config: ModelConfig,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        fn get_name(&self) -> String {
+            "openai".to_string()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text("done"),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_for_known_provider_and_model() {
+        let agent = Agent::new();
+        agent
+            .update_provider(Arc::new(MockProvider {
+                model_config: ModelConfig::new("gpt-4o".to_string()),
+            }))
+            .await
+            .unwrap();
+        agent
+            .add_extension(ExtensionConfig::Frontend {
+                name: "ui".to_string(),
+                tools: vec![Tool::new(
+                    "open_tab",
+                    "Open a browser tab",
+                    json!({"type": "object"}),
+                    None,
+                )],
+                instructions: None,
+                bundled: None,
+            })
+            .await
+            .unwrap();
+        let capabilities = agent.capabilities().await;
+
+        assert_eq!(
+            capabilities.provider,
+            Some(ProviderCapability {
+                name: "openai".to_string(),
+                model: "gpt-4o".to_string(),
+            })
+        );
+        assert_eq!(capabilities.context_window, Some(128_000));
+        assert!(capabilities.supports_vision);
+        assert!(capabilities.supports_tool_use);
+
+        assert!(capabilities.tools.contains(&ToolCapability {
+            name: "open_tab".to_string(),
+            description: "Open a browser tab".to_string(),
+            extension: "frontend".to_string(),
+        }));
+        assert!(capabilities
+            .tools
+            .iter()
+            .any(|tool| tool.name == "platform__search_available_extensions"
+                && tool.extension == "platform"));
+        assert!(capabilities.extensions.contains(&ExtensionCapability {
+            name: "frontend".to_string(),
+            extension_type: "frontend".to_string(),
+            connected: true,
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_without_provider() {
+        let capabilities = Agent::new().capabilities().await;
+
+        assert_eq!(capabilities.provider, None);
+        assert_eq!(capabilities.context_window, None);
+        assert!(!capabilities.supports_vision);
+        assert!(!capabilities.supports_tool_use);
+    }
+}
@@ -259,6 +259,16 @@ impl ExtensionConfig {
         name_to_key(&name)
     }
 
+    /// Get the extension type, as used in the config (`sse`, `stdio`, `builtin` or `frontend`)
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Sse { .. } => "sse",
+            Self::Stdio { .. } => "stdio",
+            Self::Builtin { .. } => "builtin",
+            Self::Frontend { .. } => "frontend",
+        }
+    }
+
     /// Get the extension name regardless of variant
     pub fn name(&self) -> String {
         match self {
@@ -3,21 +3,23 @@ use chrono::{DateTime, TimeZone, Utc};
 use futures::future;
 use futures::stream::{FuturesUnordered, StreamExt};
 use mcp_client::McpService;
-use mcp_core::protocol::GetPromptResult;
+use mcp_core::protocol::{GetPromptResult, JsonRpcMessage, ToolProgressEvent};
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::time::Duration;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::task;
 use tracing::{debug, error, warn};
 
+use super::capabilities::ExtensionCapability;
 use super::extension::{ExtensionConfig, ExtensionError, ExtensionInfo, ExtensionResult, ToolInfo};
 use crate::agents::extension::Envs;
 use crate::config::{Config, ExtensionConfigManager};
 use crate::prompt_template;
 use mcp_client::client::{ClientCapabilities, ClientInfo, McpClient, McpClientTrait};
-use mcp_client::transport::{SseTransport, StdioTransport, Transport};
+use mcp_client::transport::{SseTransport, StdioTransport, Transport, TransportHandle};
 use mcp_core::{prompt::Prompt, Content, Tool, ToolCall, ToolError, ToolResult};
 use serde_json::Value;
 
@@ -33,6 +35,7 @@ pub struct ExtensionManager {
     clients: HashMap<String, McpClientBox>,
     instructions: HashMap<String, String>,
     resource_capable_extensions: HashSet<String>,
+    extension_types: HashMap<String, &'static str>,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -68,6 +71,49 @@ impl ResourceItem {
     }
 }
 
+/// Await a tool call, forwarding the tool progress notifications the server sends in the
+/// meantime to `progress`, attributed to the prefixed `tool_name`.
+async fn forward_tool_progress<F: Future>(
+    call: F,
+    tool_name: &str,
+    notifications: Option<broadcast::Receiver<JsonRpcMessage>>,
+    progress: Option<&mpsc::Sender<ToolProgressEvent>>,
+) -> F::Output {
+    let (Some(mut notifications), Some(progress)) = (notifications, progress) else {
+        return call.await;
+    };
+
+    let forward = |message: JsonRpcMessage| async move {
+        if let JsonRpcMessage::Notification(notification) = message {
+            if let Some(mut event) = ToolProgressEvent::from_notification(&notification) {
+                event.tool_name = tool_name.to_string();
+                let _ = progress.send(event).await;
+            }
+        }
+    };
+
+    tokio::pin!(call);
+    let result = loop {
+        tokio::select! {
+            result = &mut call => break result,
+            message = notifications.recv() => match message {
+                Ok(message) => forward(message).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Dropped {} notifications from {}", skipped, tool_name);
+                }
+                Err(broadcast::error::RecvError::Closed) => break (&mut call).await,
+            },
+        }
+    };
+
+    // Notifications always precede the response, but may not have been received yet
+    while let Ok(message) = notifications.try_recv() {
+        forward(message).await;
+    }
+
+    result
+}
+
 /// Sanitizes a string by replacing invalid characters with underscores.
 /// Valid characters match [a-zA-Z0-9_-]
 fn normalize(input: String) -> String {
@@ -103,6 +149,7 @@ impl ExtensionManager {
             clients: HashMap::new(),
             instructions: HashMap::new(),
             resource_capable_extensions: HashSet::new(),
+            extension_types: HashMap::new(),
         }
     }
 
@@ -183,13 +230,14 @@ impl ExtensionManager {
                 let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
                 let transport = SseTransport::new(uri, all_envs);
                 let handle = transport.start().await?;
+                let notifications = handle.subscribe();
                 let service = McpService::with_timeout(
                     handle,
                     Duration::from_secs(
                         timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                     ),
                 );
-                Box::new(McpClient::new(service))
+                Box::new(McpClient::new(service).with_notifications(notifications))
             }
             ExtensionConfig::Stdio {
                 cmd,
@@ -202,13 +250,14 @@ impl ExtensionManager {
                 let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
                 let transport = StdioTransport::new(cmd, args.to_vec(), all_envs);
                 let handle = transport.start().await?;
+                let notifications = handle.subscribe();
                 let service = McpService::with_timeout(
                     handle,
                     Duration::from_secs(
                         timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                     ),
                 );
-                Box::new(McpClient::new(service))
+                Box::new(McpClient::new(service).with_notifications(notifications))
             }
             ExtensionConfig::Builtin {
                 name,
@@ -216,24 +265,31 @@ impl ExtensionManager {
                 timeout,
                 bundled: _,
             } => {
-                let cmd = std::env::current_exe()
-                    .expect("should find the current executable")
-                    .to_str()
-                    .expect("should resolve executable to string path")
-                    .to_string();
+                // Builtins are served by the goose binary itself, unless another one is
+                // configured, e.g. when goose is embedded in another process through the FFI
+                let cmd = Config::global()
+                    .get_param::<String>("GOOSE_BUILTIN_EXECUTABLE")
+                    .unwrap_or_else(|_| {
+                        std::env::current_exe()
+                            .expect("should find the current executable")
+                            .to_str()
+                            .expect("should resolve executable to string path")
+                            .to_string()
+                    });
                 let transport = StdioTransport::new(
                     &cmd,
                     vec!["mcp".to_string(), name.clone()],
                     HashMap::new(),
                 );
                 let handle = transport.start().await?;
+                let notifications = handle.subscribe();
                 let service = McpService::with_timeout(
                     handle,
                     Duration::from_secs(
                         timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                     ),
                 );
-                Box::new(McpClient::new(service))
+                Box::new(McpClient::new(service).with_notifications(notifications))
             }
             _ => unreachable!(),
         };
@@ -260,12 +316,47 @@ impl ExtensionManager {
                 .insert(sanitized_name.clone());
         }
 
+        self.extension_types
+            .insert(sanitized_name.clone(), config.type_name());
         self.clients
             .insert(sanitized_name.clone(), Arc::new(Mutex::new(client)));
 
         Ok(())
     }
 
+    /// List the connected extensions along with those enabled in the config that are not,
+    /// for example because they failed to start
+    pub fn get_extension_statuses(&self) -> Vec<ExtensionCapability> {
+        let mut statuses: Vec<ExtensionCapability> = self
+            .clients
+            .keys()
+            .map(|name| ExtensionCapability {
+                name: name.clone(),
+                extension_type: self
+                    .extension_types
+                    .get(name)
+                    .copied()
+                    .unwrap_or_default()
+                    .to_string(),
+                connected: true,
+            })
+            .collect();
+
+        let configured = ExtensionConfigManager::get_all().unwrap_or_default();
+        for entry in configured.into_iter().filter(|entry| entry.enabled) {
+            let name = normalize(entry.config.key());
+            if !self.clients.contains_key(&name) {
+                statuses.push(ExtensionCapability {
+                    name,
+                    extension_type: entry.config.type_name().to_string(),
+                    connected: false,
+                });
+            }
+        }
+
+        statuses
+    }
+
     /// Get extensions info
     pub async fn get_extensions_info(&self) -> Vec<ExtensionInfo> {
         self.clients
@@ -284,6 +375,7 @@ impl ExtensionManager {
 
         self.clients.remove(&sanitized_name);
         self.instructions.remove(&sanitized_name);
+        self.extension_types.remove(&sanitized_name);
         self.resource_capable_extensions.remove(&sanitized_name);
         Ok(())
     }
@@ -609,7 +701,15 @@ impl ExtensionManager {
         }
     }
 
-    pub async fn dispatch_tool_call(&self, tool_call: ToolCall) -> ToolResult<Vec<Content>> {
+    /// Dispatch a tool call to the extension that owns it
+    ///
+    /// When `progress` is set, progress the extension reports while the tool runs is forwarded
+    /// to it, before the call returns.
+    pub async fn dispatch_tool_call(
+        &self,
+        tool_call: ToolCall,
+        progress: Option<mpsc::Sender<ToolProgressEvent>>,
+    ) -> ToolResult<Vec<Content>> {
         // Dispatch tool call based on the prefix naming convention
         let (client_name, client) = self
             .get_client_for_tool(&tool_call.name)
@@ -624,11 +724,20 @@ impl ExtensionManager {
 
         let client_guard = client.lock().await;
 
-        let result = client_guard
-            .call_tool(tool_name, tool_call.clone().arguments)
+        // Subscribe before calling the tool so no progress sent during the call is missed
+        let notifications = progress.as_ref().and_then(|_| client_guard.subscribe());
+        let call = client_guard.call_tool(tool_name, tool_call.clone().arguments);
+
+        let result = forward_tool_progress(call, &tool_call.name, notifications, progress.as_ref())
             .await
             .map(|result| result.content)
-            .map_err(|e| ToolError::ExecutionError(e.to_string()));
+            .map_err(|e| {
+                if e.is_timeout() {
+                    ToolError::Timeout(e.to_string())
+                } else {
+                    ToolError::ExecutionError(e.to_string())
+                }
+            });
 
         debug!(
             "input" = serde_json::to_string(&tool_call).unwrap(),
@@ -793,8 +902,8 @@ mod tests {
     use mcp_client::client::Error;
     use mcp_client::client::McpClientTrait;
     use mcp_core::protocol::{
-        CallToolResult, GetPromptResult, InitializeResult, ListPromptsResult, ListResourcesResult,
-        ListToolsResult, ReadResourceResult,
+        CallToolResult, GetPromptResult, InitializeResult, JsonRpcResponse, ListPromptsResult,
+        ListResourcesResult, ListToolsResult, ReadResourceResult,
     };
     use serde_json::json;
 
@@ -925,7 +1034,7 @@ mod tests {
             arguments: json!({}),
         };
 
-        let result = extension_manager.dispatch_tool_call(tool_call).await;
+        let result = extension_manager.dispatch_tool_call(tool_call, None).await;
         assert!(result.is_ok());
 
         let tool_call = ToolCall {
@@ -933,7 +1042,7 @@ mod tests {
             arguments: json!({}),
         };
 
-        let result = extension_manager.dispatch_tool_call(tool_call).await;
+        let result = extension_manager.dispatch_tool_call(tool_call, None).await;
         assert!(result.is_ok());
 
         // verify a multiple underscores dispatch
@@ -942,7 +1051,7 @@ mod tests {
             arguments: json!({}),
         };
 
-        let result = extension_manager.dispatch_tool_call(tool_call).await;
+        let result = extension_manager.dispatch_tool_call(tool_call, None).await;
         assert!(result.is_ok());
 
         // Test unicode in tool name, "client 🚀" should become "client_"
@@ -951,7 +1060,7 @@ mod tests {
             arguments: json!({}),
         };
 
-        let result = extension_manager.dispatch_tool_call(tool_call).await;
+        let result = extension_manager.dispatch_tool_call(tool_call, None).await;
         assert!(result.is_ok());
 
         let tool_call = ToolCall {
@@ -959,7 +1068,7 @@ mod tests {
             arguments: json!({}),
         };
 
-        let result = extension_manager.dispatch_tool_call(tool_call).await;
+        let result = extension_manager.dispatch_tool_call(tool_call, None).await;
         assert!(result.is_ok());
 
         // this should error out, specifically for an ToolError::ExecutionError
@@ -969,7 +1078,7 @@ mod tests {
         };
 
         let result = extension_manager
-            .dispatch_tool_call(invalid_tool_call)
+            .dispatch_tool_call(invalid_tool_call, None)
             .await;
         assert!(matches!(
             result.err().unwrap(),
@@ -984,8 +1093,123 @@ mod tests {
         };
 
         let result = extension_manager
-            .dispatch_tool_call(invalid_tool_call)
+            .dispatch_tool_call(invalid_tool_call, None)
             .await;
         assert!(matches!(result.err().unwrap(), ToolError::NotFound(_)));
     }
+
+    #[tokio::test]
+    async fn test_forward_tool_progress_before_result() {
+        let (notification_tx, notifications) = broadcast::channel(16);
+        let (progress_tx, mut progress_rx) = mpsc::channel(16);
+
+        let call = async {
+            for i in 1..=3 {
+                let event = ToolProgressEvent {
+                    tool_name: "shell".to_string(),
+                    progress_percent: None,
+                    message: format!("line {}", i),
+                };
+                notification_tx
+                    .send(JsonRpcMessage::Notification(event.to_notification()))
+                    .unwrap();
+            }
+            "done"
+        };
+
+        let result = forward_tool_progress(
+            call,
+            "developer__shell",
+            Some(notifications),
+            Some(&progress_tx),
+        )
+        .await;
+        assert_eq!(result, "done");
+
+        // All progress is forwarded by the time the call returns, with the prefixed tool name
+        for i in 1..=3 {
+            let event = progress_rx.try_recv().unwrap();
+            assert_eq!(event.tool_name, "developer__shell");
+            assert_eq!(event.message, format!("line {}", i));
+        }
+        assert!(progress_rx.try_recv().is_err());
+    }
+
+    /// A connection to a server that completes initialization but never answers requests
+    #[derive(Clone)]
+    struct StalledTransport;
+
+    #[async_trait::async_trait]
+    impl TransportHandle for StalledTransport {
+        async fn send(
+            &self,
+            message: JsonRpcMessage,
+        ) -> Result<JsonRpcMessage, mcp_client::transport::Error> {
+            match message {
+                JsonRpcMessage::Request(request) if request.method == "initialize" => {
+                    Ok(JsonRpcMessage::Response(JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: request.id,
+                        result: Some(json!({
+                            "protocolVersion": "2025-03-26",
+                            "capabilities": {"tools": {}},
+                            "serverInfo": {"name": "slow", "version": "1.0.0"}
+                        })),
+                        error: None,
+                    }))
+                }
+                JsonRpcMessage::Notification(_) => Ok(JsonRpcMessage::Nil),
+                _ => future::pending().await,
+            }
+        }
+
+        fn subscribe(&self) -> broadcast::Receiver<JsonRpcMessage> {
+            broadcast::channel(1).1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_tool_calls_are_not_recorded() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("audit.jsonl");
+        let agent = crate::agents::Agent::new();
+        agent
+            .add_tool_hook(Box::new(crate::agents::hooks::FileAuditHook::new(
+                &log_path,
+            )))
+            .await;
+
+        let service = McpService::with_timeout(StalledTransport, Duration::from_millis(10));
+        let mut client = McpClient::new(service);
+        client
+            .initialize(
+                ClientInfo {
+                    name: "goose".to_string(),
+                    version: "1.0.0".to_string(),
+                },
+                ClientCapabilities::default(),
+            )
+            .await
+            .unwrap();
+        agent
+            .extension_manager
+            .lock()
+            .await
+            .clients
+            .insert("slow".to_string(), Arc::new(Mutex::new(Box::new(client))));
+
+        let (_, result) = agent
+            .dispatch_tool_call(
+                ToolCall::new("slow__tool", json!({})),
+                "request_1".to_string(),
+                None,
+            )
+            .await;
+        assert!(
+            matches!(&result, Err(ToolError::Timeout(message)) if message.starts_with("Call to 'slow' failed")),
+            "{:?}",
+            result
+        );
+        assert!(!log_path.exists());
+    }
 }
@@ -0,0 +1,133 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::config::Config;
+
+/// A completed tool call, as reported to every registered [`ToolCallHook`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ToolCallRecord {
+    /// The session the call was made in, when the agent is running one
+    pub session_id: Option<String>,
+    /// The full (prefixed) tool name, e.g. `developer__shell`
+    pub tool_name: String,
+    /// The extension providing the tool, when the name is prefixed with one
+    pub extension: Option<String>,
+    #[schema(value_type = Object)]
+    pub arguments: serde_json::Value,
+    pub result_ok: bool,
+    pub duration_ms: u64,
+    /// When the call completed
+    #[schema(value_type = String, format = DateTime)]
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Observes tool calls after they complete, e.g. for logging or auditing
+///
+/// Hooks run synchronously once a call returns a result. Calls that time out or are
+/// cancelled before completing are not reported.
+pub trait ToolCallHook {
+    fn on_tool_call(&self, record: &ToolCallRecord);
+}
+
+/// Appends every tool call as a JSON line to an audit log file
+pub struct FileAuditHook {
+    path: PathBuf,
+}
+
+impl FileAuditHook {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Create a hook writing to the path in `GOOSE_AUDIT_LOG`, if it is set
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .get_param::<String>("GOOSE_AUDIT_LOG")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(Self::new)
+    }
+
+    fn append(&self, record: &ToolCallRecord) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+impl ToolCallHook for FileAuditHook {
+    fn on_tool_call(&self, record: &ToolCallRecord) {
+        if let Err(e) = self.append(record) {
+            warn!(
+                "Failed to write tool call to audit log {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::platform_tools::PLATFORM_LIST_RESOURCES_TOOL_NAME;
+    use crate::agents::Agent;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_file_audit_hook_records_tool_calls() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("audit.jsonl");
+
+        let agent = Agent::new();
+        agent
+            .add_tool_hook(Box::new(FileAuditHook::new(&log_path)))
+            .await;
+
+        let (_, result) = agent
+            .dispatch_tool_call(
+                ToolCall::new(PLATFORM_LIST_RESOURCES_TOOL_NAME, json!({})),
+                "request_1".to_string(),
+                Some("session".to_string()),
+            )
+            .await;
+        assert!(result.is_ok());
+        let (_, result) = agent
+            .dispatch_tool_call(
+                ToolCall::new("missing__tool", json!({"path": "/tmp"})),
+                "request_2".to_string(),
+                Some("session".to_string()),
+            )
+            .await;
+        assert!(result.is_err());
+
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        let records: Vec<ToolCallRecord> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].tool_name, PLATFORM_LIST_RESOURCES_TOOL_NAME);
+        assert_eq!(records[0].extension.as_deref(), Some("platform"));
+        assert!(records[0].result_ok);
+
+        assert_eq!(records[1].tool_name, "missing__tool");
+        assert_eq!(records[1].extension.as_deref(), Some("missing"));
+        assert_eq!(records[1].arguments, json!({"path": "/tmp"}));
+        assert_eq!(records[1].session_id.as_deref(), Some("session"));
+        assert!(!records[1].result_ok);
+    }
+}
@@ -1,15 +1,24 @@
 mod agent;
+mod capabilities;
 mod context;
 pub mod extension;
 pub mod extension_manager;
+pub mod hooks;
 pub mod platform_tools;
 pub mod prompt_manager;
 mod reply_parts;
+mod retry;
 mod tool_execution;
+mod tool_history;
 mod types;
 
 pub use agent::Agent;
+pub use capabilities::{
+    AgentCapabilities, ExtensionCapability, ProviderCapability, ToolCapability,
+};
 pub use extension::ExtensionConfig;
 pub use extension_manager::ExtensionManager;
+pub use hooks::{FileAuditHook, ToolCallHook, ToolCallRecord};
 pub use prompt_manager::PromptManager;
-pub use types::{FrontendTool, SessionConfig};
+pub use tool_history::{ToolCallHistory, ToolStats, DEFAULT_TOOL_HISTORY_LIMIT};
+pub use types::{FrontendTool, PermissionPolicy, SessionConfig, ToolRestrictions};
@@ -9,6 +9,8 @@ use crate::{config::Config, prompt_template};
 pub struct PromptManager {
     system_prompt_override: Option<String>,
     system_prompt_extras: Vec<String>,
+    /// Instructions given with the conversation, such as its system messages
+    conversation_extras: Vec<String>,
 }
 
 impl Default for PromptManager {
@@ -22,6 +24,7 @@ impl PromptManager {
         PromptManager {
             system_prompt_override: None,
             system_prompt_extras: Vec::new(),
+            conversation_extras: Vec::new(),
         }
     }
 
@@ -30,6 +33,12 @@ impl PromptManager {
         self.system_prompt_extras.push(instruction);
     }
 
+    /// Replace the instructions given with the conversation, keeping those added with
+    /// add_system_prompt_extra
+    pub fn set_system_prompt_extras(&mut self, instructions: Vec<String>) {
+        self.conversation_extras = instructions;
+    }
+
     /// Override the system prompt with custom text
     pub fn set_system_prompt_override(&mut self, template: String) {
         self.system_prompt_override = Some(template);
@@ -113,6 +122,7 @@ impl PromptManager {
         };
 
         let mut system_prompt_extras = self.system_prompt_extras.clone();
+        system_prompt_extras.extend(self.conversation_extras.iter().cloned());
         let config = Config::global();
         let goose_mode = config.get_param("GOOSE_MODE").unwrap_or("auto".to_string());
         if goose_mode == "chat" {
@@ -147,6 +157,20 @@ impl PromptManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_set_system_prompt_extras_keeps_added_extras() {
+        let mut manager = PromptManager::new();
+        manager.set_system_prompt_override("Base prompt".to_string());
+        manager.add_system_prompt_extra("From the CLI".to_string());
+        manager.set_system_prompt_extras(vec!["First system message".to_string()]);
+        manager.set_system_prompt_extras(vec!["Second system message".to_string()]);
+
+        let prompt = manager.build_system_prompt(Vec::new(), None, Value::Null, None);
+        assert!(prompt.starts_with("Base prompt"));
+        assert!(prompt.contains("From the CLI\n\nSecond system message"));
+        assert!(!prompt.contains("First system message"));
+    }
+
     #[test]
     fn test_normalize_model_name() {
         assert_eq!(PromptManager::normalize_model_name("gpt-4.1"), "gpt_4_1");
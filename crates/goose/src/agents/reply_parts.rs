@@ -1,7 +1,12 @@
 use anyhow::Result;
 use std::collections::HashSet;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 
+use crate::config::Config;
+use crate::context_mgmt::instructions::{
+    summarise_extensions_instructions, DEFAULT_MAX_INSTRUCTION_TOKENS,
+};
 use crate::message::{Message, MessageContent, ToolRequest};
 use crate::providers::base::{Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
@@ -12,6 +17,7 @@ use crate::session;
 use mcp_core::tool::Tool;
 
 use super::super::agents::Agent;
+use super::retry::{complete_with_retry, ProviderRetryConfig};
 
 impl Agent {
     /// Prepares tools and system prompt for a provider request
@@ -33,6 +39,17 @@ impl Agent {
 
         // Get model name from provider
         let provider = self.provider().await?;
+
+        // Keep long extension instructions from crowding out the conversation
+        let max_instruction_tokens = Config::global()
+            .get_param::<usize>("GOOSE_MAX_INSTRUCTION_TOKENS")
+            .unwrap_or(DEFAULT_MAX_INSTRUCTION_TOKENS);
+        let extensions_info = summarise_extensions_instructions(
+            extensions_info,
+            max_instruction_tokens,
+            provider.clone(),
+        )
+        .await;
         let model_config = provider.get_model_config();
         let model_name = &model_config.model_name;
 
@@ -60,8 +77,10 @@ impl Agent {
 
     /// Categorize tools based on their annotations
     /// Returns:
-    /// - read_only_tools: Tools with read-only annotations
-    /// - non_read_tools: Tools without read-only annotations
+    /// - read_only_tools: Tools annotated as read-only and non-destructive
+    /// - non_read_tools: Tools without annotations, or annotated as non-destructive writes
+    ///
+    /// Tools annotated as destructive are in neither set, so they are never auto-approved
     pub(crate) fn categorize_tools_by_annotation(
         tools: &[Tool],
     ) -> (HashSet<String>, HashSet<String>) {
@@ -69,9 +88,12 @@ impl Agent {
             .iter()
             .fold((HashSet::new(), HashSet::new()), |mut acc, tool| {
                 match &tool.annotations {
-                    Some(annotations) if annotations.read_only_hint => {
+                    Some(annotations)
+                        if annotations.read_only_hint && !annotations.destructive_hint =>
+                    {
                         acc.0.insert(tool.name.clone());
                     }
+                    Some(annotations) if annotations.destructive_hint => {}
                     _ => {
                         acc.1.insert(tool.name.clone());
                     }
@@ -81,9 +103,10 @@ impl Agent {
     }
 
     /// Generate a response from the LLM provider
-    /// Handles toolshim transformations if needed
+    /// Handles toolshim transformations if needed and retries transient provider failures
     pub(crate) async fn generate_response_from_provider(
         provider: Arc<dyn Provider>,
+        retry_config: &ProviderRetryConfig,
         system_prompt: &str,
         messages: &[Message],
         tools: &[Tool],
@@ -92,7 +115,14 @@ impl Agent {
         let config = provider.get_model_config();
 
         // Call the provider to get a response
-        let (mut response, usage) = provider.complete(system_prompt, messages, tools).await?;
+        let (mut response, usage) = complete_with_retry(
+            provider.as_ref(),
+            retry_config,
+            system_prompt,
+            messages,
+            tools,
+        )
+        .await?;
 
         // Store the model information in the global store
         crate::providers::base::set_current_model(&usage.model);
@@ -182,6 +212,33 @@ impl Agent {
         (frontend_requests, other_requests, filtered_message)
     }
 
+    /// Remove tool requests that are not permitted by the tool restrictions
+    /// Denied requests are answered with an error in the tool response message,
+    /// the permitted requests are returned
+    pub(crate) async fn apply_tool_restrictions(
+        &self,
+        requests: Vec<ToolRequest>,
+        message_tool_response: Arc<Mutex<Message>>,
+    ) -> Vec<ToolRequest> {
+        let restrictions = self.tool_restrictions.lock().await;
+        let mut permitted = Vec::new();
+
+        for request in requests {
+            if let Ok(tool_call) = &request.tool_call {
+                if let Err(e) = restrictions.check(&tool_call.name) {
+                    let mut response = message_tool_response.lock().await;
+                    *response = response
+                        .clone()
+                        .with_tool_response(request.id.clone(), Err(e));
+                    continue;
+                }
+            }
+            permitted.push(request);
+        }
+
+        permitted
+    }
+
     /// Update session metrics after a response
     pub(crate) async fn update_session_metrics(
         session_config: crate::agents::types::SessionConfig,
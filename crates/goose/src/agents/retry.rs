@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use rand::Rng;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::message::Message;
+use crate::providers::base::{Provider, ProviderUsage};
+use crate::providers::errors::ProviderError;
+use mcp_core::tool::Tool;
+
+/// Default maximum number of retries for a provider call
+const DEFAULT_MAX_RETRIES: u32 = 3;
+/// Default delay before the first retry (in milliseconds)
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+/// Upper bound of the random jitter added to each delay (in milliseconds)
+const MAX_JITTER_MS: u64 = 200;
+
+/// Retry configuration for transient provider failures
+#[derive(Debug, Clone)]
+pub struct ProviderRetryConfig {
+    /// Maximum number of retries after the initial attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every subsequent retry
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for ProviderRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff_ms: DEFAULT_INITIAL_BACKOFF_MS,
+        }
+    }
+}
+
+impl ProviderRetryConfig {
+    /// Load the retry configuration from `GOOSE_PROVIDER_MAX_RETRIES` and
+    /// `GOOSE_PROVIDER_INITIAL_BACKOFF_MS`, falling back to the defaults.
+    pub fn from_config(config: &Config) -> Self {
+        let max_retries = config
+            .get_param::<u32>("GOOSE_PROVIDER_MAX_RETRIES")
+            .unwrap_or(DEFAULT_MAX_RETRIES);
+        let initial_backoff_ms = config
+            .get_param::<u64>("GOOSE_PROVIDER_INITIAL_BACKOFF_MS")
+            .unwrap_or(DEFAULT_INITIAL_BACKOFF_MS);
+
+        Self {
+            max_retries,
+            initial_backoff_ms,
+        }
+    }
+
+    /// Calculate the delay before retry number `attempt` (starting at 0), with jitter
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let backoff_ms = self
+            .initial_backoff_ms
+            .saturating_mul(2u64.saturating_pow(attempt));
+        let jitter_ms = rand::thread_rng().gen_range(0..=MAX_JITTER_MS);
+        Duration::from_millis(backoff_ms.saturating_add(jitter_ms))
+    }
+}
+
+/// Whether a provider error is transient and the call is worth retrying
+///
+/// Rate limits (429), unavailable services (503) and network failures such as timeouts or
+/// connection resets are retried. Other server errors, authentication and malformed request
+/// errors are not.
+pub fn is_retryable(error: &ProviderError) -> bool {
+    matches!(
+        error,
+        ProviderError::RateLimitExceeded(_)
+            | ProviderError::ServiceUnavailable(_)
+            | ProviderError::NetworkError(_)
+    )
+}
+
+/// Call `provider.complete()`, retrying transient failures with exponential backoff
+///
+/// Once the retries are exhausted the last error is returned unchanged.
+pub async fn complete_with_retry(
+    provider: &dyn Provider,
+    retry_config: &ProviderRetryConfig,
+    system: &str,
+    messages: &[Message],
+    tools: &[Tool],
+) -> Result<(Message, ProviderUsage), ProviderError> {
+    let mut attempt = 0;
+    loop {
+        match provider.complete(system, messages, tools).await {
+            Err(e) if is_retryable(&e) && attempt < retry_config.max_retries => {
+                let delay = retry_config.delay_for_attempt(attempt);
+                attempt += 1;
+                warn!(
+                    "Provider call failed (attempt {} of {}): {}. Retrying in {:?}",
+                    attempt,
+                    retry_config.max_retries + 1,
+                    e,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, Usage};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Provider that fails with the given error a number of times before succeeding
+    struct FlakyProvider {
+        failures: u32,
+        error: fn() -> ProviderError,
+        calls: AtomicU32,
+    }
+
+    impl FlakyProvider {
+        fn new(failures: u32, error: fn() -> ProviderError) -> Self {
+            Self {
+                failures,
+                error,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for FlakyProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("test-model".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                return Err((self.error)());
+            }
+            Ok((
+                Message::assistant().with_text("done"),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    fn fast_retries() -> ProviderRetryConfig {
+        ProviderRetryConfig {
+            max_retries: 3,
+            initial_backoff_ms: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_until_success() {
+        let provider =
+            FlakyProvider::new(2, || ProviderError::RateLimitExceeded("429".to_string()));
+
+        let result = complete_with_retry(&provider, &fast_retries(), "system", &[], &[]).await;
+
+        assert!(result.is_ok());
+        // The initial call plus exactly two retries
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_surfaces_error_after_max_retries() {
+        let provider =
+            FlakyProvider::new(10, || ProviderError::ServiceUnavailable("503".to_string()));
+
+        let result = complete_with_retry(&provider, &fast_retries(), "system", &[], &[]).await;
+
+        assert!(matches!(result, Err(ProviderError::ServiceUnavailable(_))));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_non_transient_errors() {
+        let provider = FlakyProvider::new(1, || ProviderError::Authentication("401".to_string()));
+        let result = complete_with_retry(&provider, &fast_retries(), "system", &[], &[]).await;
+        assert!(matches!(result, Err(ProviderError::Authentication(_))));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        let provider = FlakyProvider::new(1, || ProviderError::RequestFailed("400".to_string()));
+        let result = complete_with_retry(&provider, &fast_retries(), "system", &[], &[]).await;
+        assert!(matches!(result, Err(ProviderError::RequestFailed(_))));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        let provider = FlakyProvider::new(1, || ProviderError::ServerError("500".to_string()));
+        let result = complete_with_retry(&provider, &fast_retries(), "system", &[], &[]).await;
+        assert!(matches!(result, Err(ProviderError::ServerError(_))));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_delay_for_attempt() {
+        let config = ProviderRetryConfig {
+            max_retries: 3,
+            initial_backoff_ms: 500,
+        };
+        for (attempt, base) in [(0, 500), (1, 1000), (2, 2000)] {
+            let delay = config.delay_for_attempt(attempt).as_millis() as u64;
+            assert!(delay >= base && delay <= base + MAX_JITTER_MS);
+        }
+    }
+}
@@ -40,6 +40,7 @@ impl Agent {
         tool_futures: ToolFuturesVec<'a>,
         permission_manager: &'a mut PermissionManager,
         message_tool_response: Arc<Mutex<Message>>,
+        session_id: Option<String>,
     ) -> BoxStream<'a, anyhow::Result<Message>> {
         try_stream! {
             for request in tool_requests {
@@ -56,7 +57,7 @@ impl Agent {
                     while let Some((req_id, confirmation)) = rx.recv().await {
                         if req_id == request.id {
                             if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
-                                let tool_future = self.dispatch_tool_call(tool_call.clone(), request.id.clone());
+                                let tool_future = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), session_id.clone());
                                 let mut futures = tool_futures.lock().await;
                                 futures.push(Box::pin(tool_future));
 
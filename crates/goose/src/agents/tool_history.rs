@@ -0,0 +1,164 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::hooks::ToolCallRecord;
+use super::Agent;
+
+/// Number of tool calls kept in the history unless `GOOSE_TOOL_HISTORY_LIMIT` is set
+pub const DEFAULT_TOOL_HISTORY_LIMIT: usize = 100;
+
+/// How often a tool was called, and how it went
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ToolStats {
+    pub call_count: u32,
+    pub error_count: u32,
+    pub avg_duration_ms: f64,
+}
+
+/// The recent tool calls of an agent, along with statistics over every call it made
+#[derive(Debug)]
+pub struct ToolCallHistory {
+    records: VecDeque<ToolCallRecord>,
+    limit: usize,
+    /// Call count, error count and total duration in milliseconds per tool
+    totals: HashMap<String, (u32, u32, u64)>,
+}
+
+impl ToolCallHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            records: VecDeque::new(),
+            limit,
+            totals: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, record: ToolCallRecord) {
+        let (calls, errors, duration_ms) = self.totals.entry(record.tool_name.clone()).or_default();
+        *calls += 1;
+        if !record.result_ok {
+            *errors += 1;
+        }
+        *duration_ms += record.duration_ms;
+
+        self.records.push_back(record);
+        while self.records.len() > self.limit {
+            self.records.pop_front();
+        }
+    }
+
+    /// The most recent calls, oldest first
+    pub fn records(&self) -> Vec<ToolCallRecord> {
+        self.records.iter().cloned().collect()
+    }
+
+    pub fn stats(&self) -> HashMap<String, ToolStats> {
+        self.totals
+            .iter()
+            .map(|(tool_name, &(call_count, error_count, duration_ms))| {
+                (
+                    tool_name.clone(),
+                    ToolStats {
+                        call_count,
+                        error_count,
+                        avg_duration_ms: duration_ms as f64 / f64::from(call_count),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl Agent {
+    /// The most recent tool calls of the agent, oldest first, up to `GOOSE_TOOL_HISTORY_LIMIT`
+    pub async fn tool_call_history(&self) -> Vec<ToolCallRecord> {
+        self.tool_call_history.lock().await.records()
+    }
+
+    /// Statistics per tool over every call the agent has made
+    pub async fn tool_call_stats(&self) -> HashMap<String, ToolStats> {
+        self.tool_call_history.lock().await.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::platform_tools::PLATFORM_LIST_RESOURCES_TOOL_NAME;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn record(tool_name: &str, result_ok: bool, duration_ms: u64) -> ToolCallRecord {
+        ToolCallRecord {
+            session_id: None,
+            tool_name: tool_name.to_string(),
+            extension: None,
+            arguments: json!({}),
+            result_ok,
+            duration_ms,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = ToolCallHistory::new(2);
+        history.push(record("developer__shell", true, 10));
+        history.push(record("developer__shell", false, 30));
+        history.push(record("memory__remember", true, 5));
+
+        let names: Vec<_> = history
+            .records()
+            .into_iter()
+            .map(|record| (record.tool_name, record.result_ok))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("developer__shell".to_string(), false),
+                ("memory__remember".to_string(), true)
+            ]
+        );
+
+        // Calls that left the history still count
+        assert_eq!(
+            history.stats()["developer__shell"],
+            ToolStats {
+                call_count: 2,
+                error_count: 1,
+                avg_duration_ms: 20.0,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_records_tool_calls() {
+        let agent = Agent::new();
+        for request_id in ["request_1", "request_2"] {
+            let (_, result) = agent
+                .dispatch_tool_call(
+                    ToolCall::new(PLATFORM_LIST_RESOURCES_TOOL_NAME, json!({})),
+                    request_id.to_string(),
+                    None,
+                )
+                .await;
+            assert!(result.is_ok());
+        }
+
+        let history = agent.tool_call_history().await;
+        assert_eq!(history.len(), 2);
+        assert!(history
+            .iter()
+            .all(|record| record.tool_name == PLATFORM_LIST_RESOURCES_TOOL_NAME));
+
+        let stats = agent.tool_call_stats().await;
+        let stats = &stats[PLATFORM_LIST_RESOURCES_TOOL_NAME];
+        assert_eq!(stats.call_count, 2);
+        assert_eq!(stats.error_count, 0);
+        let expected_avg =
+            history.iter().map(|record| record.duration_ms).sum::<u64>() as f64 / 2.0;
+        assert_eq!(stats.avg_duration_ms, expected_avg);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::session;
-use mcp_core::{Content, Tool, ToolResult};
+use mcp_core::{Content, Tool, ToolError, ToolResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
@@ -23,3 +24,62 @@ pub struct SessionConfig {
     /// Working directory for the session
     pub working_dir: PathBuf,
 }
+
+/// Restrictions on which tools the agent may call
+///
+/// Entries match either a full tool name (e.g. `developer__shell`) or an extension
+/// name, which covers all tools of that extension (e.g. `developer`).
+#[derive(Debug, Clone, Default)]
+pub struct ToolRestrictions {
+    /// When set, only tools matching an entry may be called
+    pub whitelist: Option<HashSet<String>>,
+    /// Tools matching an entry may never be called
+    pub blacklist: HashSet<String>,
+}
+
+impl ToolRestrictions {
+    fn matches(entries: &HashSet<String>, tool_name: &str) -> bool {
+        entries.contains(tool_name)
+            || tool_name
+                .split_once("__")
+                .is_some_and(|(extension, _)| entries.contains(extension))
+    }
+
+    /// Check whether a tool may be called, returning the error to report to the model if not
+    pub fn check(&self, tool_name: &str) -> Result<(), ToolError> {
+        if let Some(whitelist) = &self.whitelist {
+            if !Self::matches(whitelist, tool_name) {
+                return Err(ToolError::ExecutionError(
+                    "tool not permitted by session whitelist".to_string(),
+                ));
+            }
+        }
+        if Self::matches(&self.blacklist, tool_name) {
+            return Err(ToolError::ExecutionError(
+                "tool not permitted by session blacklist".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How tool calls are confirmed, derived from the GOOSE_MODE setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionPolicy {
+    /// Every tool call needs confirmation unless the user granted a permission for it
+    AlwaysAsk,
+    /// Tools annotated as read-only and non-destructive run without confirmation
+    AutoApproveReadOnly,
+    /// Every tool call runs without confirmation
+    AlwaysApprove,
+}
+
+impl PermissionPolicy {
+    pub fn from_goose_mode(mode: &str) -> Self {
+        match mode {
+            "auto" => PermissionPolicy::AlwaysApprove,
+            "approve" | "smart_approve" => PermissionPolicy::AutoApproveReadOnly,
+            _ => PermissionPolicy::AlwaysAsk,
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use tracing::{debug, warn};
+
+use crate::agents::extension::ExtensionInfo;
+use crate::message::Message;
+use crate::providers::base::Provider;
+use crate::token_counter::TokenCounter;
+
+/// Token budget for the instructions of all extensions unless `GOOSE_MAX_INSTRUCTION_TOKENS` is set
+pub const DEFAULT_MAX_INSTRUCTION_TOKENS: usize = 4000;
+
+const SUMMARY_PROMPT: &str = "You are good at condensing instructions without losing what matters";
+
+/// Summaries already made, keyed by a hash of the instructions and their token budget
+static SUMMARY_CACHE: Lazy<Mutex<HashMap<u64, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cache_key(instructions: &str, max_tokens: usize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    instructions.hash(&mut hasher);
+    max_tokens.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Summarise instructions down to key bullet points when they are longer than `max_tokens`.
+///
+/// The instructions are returned unchanged when they fit, or when the provider fails to
+/// summarise them. Summaries are cached, so identical instructions are only summarised once.
+pub async fn summarise_instructions(
+    instructions: &str,
+    max_tokens: usize,
+    provider: Arc<dyn Provider>,
+) -> String {
+    // A token is at least one byte, so short instructions fit without counting
+    if instructions.len() <= max_tokens {
+        return instructions.to_string();
+    }
+    let token_counter = TokenCounter::new(provider.get_model_config().tokenizer_name());
+    let original_tokens = token_counter.count_tokens(instructions);
+    if original_tokens <= max_tokens {
+        return instructions.to_string();
+    }
+
+    let key = cache_key(instructions, max_tokens);
+    if let Some(summary) = SUMMARY_CACHE.lock().unwrap().get(&key) {
+        return summary.clone();
+    }
+
+    let request_text = format!(
+        "Summarise the following instructions for an AI agent into key bullet points, in at most {} tokens. Keep every tool name, rule and constraint the agent needs to follow, and drop examples and repetition. Reply with the bullet points only.\n\n```\n{}\n```",
+        max_tokens, instructions
+    );
+    let summary = match provider
+        .complete(
+            SUMMARY_PROMPT,
+            &[Message::user().with_text(request_text)],
+            &[],
+        )
+        .await
+    {
+        Ok((response, _)) => response.as_concat_text(),
+        Err(e) => {
+            warn!(
+                "Failed to summarise instructions, using them in full: {}",
+                e
+            );
+            return instructions.to_string();
+        }
+    };
+
+    let summary_tokens = token_counter.count_tokens(&summary);
+    debug!(
+        "Summarised instructions from {} to {} tokens (compression ratio {:.2})",
+        original_tokens,
+        summary_tokens,
+        summary_tokens as f64 / original_tokens as f64
+    );
+
+    SUMMARY_CACHE.lock().unwrap().insert(key, summary.clone());
+    summary
+}
+
+/// Summarise the instructions of each extension when together they are longer than `max_tokens`.
+///
+/// The budget is shared evenly, with extensions whose instructions are shorter than their share
+/// kept in full and what they leave over going to the others.
+pub async fn summarise_extensions_instructions(
+    extensions_info: Vec<ExtensionInfo>,
+    max_tokens: usize,
+    provider: Arc<dyn Provider>,
+) -> Vec<ExtensionInfo> {
+    let total_bytes: usize = extensions_info.iter().map(|e| e.instructions.len()).sum();
+    if total_bytes <= max_tokens {
+        return extensions_info;
+    }
+    let token_counter = TokenCounter::new(provider.get_model_config().tokenizer_name());
+    let token_counts: Vec<usize> = extensions_info
+        .iter()
+        .map(|e| token_counter.count_tokens(&e.instructions))
+        .collect();
+    if token_counts.iter().sum::<usize>() <= max_tokens {
+        return extensions_info;
+    }
+
+    let mut budgets = vec![0; extensions_info.len()];
+    let mut by_length: Vec<usize> = (0..extensions_info.len()).collect();
+    by_length.sort_by_key(|&i| token_counts[i]);
+    let mut remaining = max_tokens;
+    for (position, &i) in by_length.iter().enumerate() {
+        let share = remaining / (by_length.len() - position);
+        budgets[i] = token_counts[i].min(share);
+        remaining -= budgets[i];
+    }
+
+    let mut summarised = Vec::with_capacity(extensions_info.len());
+    for (mut extension, budget) in extensions_info.into_iter().zip(budgets) {
+        extension.instructions =
+            summarise_instructions(&extension.instructions, budget, provider.clone()).await;
+        summarised.push(extension);
+    }
+    summarised
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::errors::ProviderError;
+    use mcp_core::tool::Tool;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const SUMMARY: &str = "- Use drive_search to find files";
+
+    /// Replies to every request with the same summary
+    struct SummaryProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for SummaryProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("summariser".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok((
+                Message::assistant().with_text(SUMMARY),
+                ProviderUsage::new("summariser".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_instructions_are_summarised() {
+        let provider = Arc::new(SummaryProvider {
+            calls: AtomicUsize::new(0),
+        });
+        let long_instructions = "Search Google Drive with drive_search before reading files. "
+            .repeat(100)
+            + "This extension was tested by test_long_instructions_are_summarised.";
+        let extensions_info = vec![
+            ExtensionInfo::new("google_drive", &long_instructions, true),
+            ExtensionInfo::new("memory", "Remember things.", false),
+        ];
+
+        let summarised =
+            summarise_extensions_instructions(extensions_info.clone(), 100, provider.clone()).await;
+        assert_eq!(summarised[0].instructions, SUMMARY);
+        assert_eq!(summarised[1].instructions, "Remember things.");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        // Identical instructions come from the cache
+        let summarised =
+            summarise_extensions_instructions(extensions_info.clone(), 100, provider.clone()).await;
+        assert_eq!(summarised[0].instructions, SUMMARY);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        // Instructions within the budget are left alone
+        let unchanged =
+            summarise_extensions_instructions(extensions_info, 10_000, provider.clone()).await;
+        assert_eq!(unchanged[0].instructions, long_instructions);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,4 +1,5 @@
 mod common;
+pub mod instructions;
 pub mod summarize;
 pub mod truncate;
 
@@ -11,6 +11,7 @@ use chrono::Utc;
 use mcp_core::content::{Content, ImageContent, TextContent};
 use mcp_core::handler::ToolResult;
 use mcp_core::prompt::{PromptMessage, PromptMessageContent, PromptMessageRole};
+use mcp_core::protocol::ToolProgressEvent;
 use mcp_core::resource::ResourceContents;
 use mcp_core::role::Role;
 use mcp_core::tool::ToolCall;
@@ -97,6 +98,7 @@ pub enum MessageContent {
     Thinking(ThinkingContent),
     RedactedThinking(RedactedThinkingContent),
     ContextLengthExceeded(ContextLengthExceeded),
+    ToolProgress(ToolProgressEvent),
 }
 
 impl MessageContent {
@@ -165,6 +167,10 @@ impl MessageContent {
         MessageContent::ContextLengthExceeded(ContextLengthExceeded { msg: msg.into() })
     }
 
+    pub fn tool_progress(event: ToolProgressEvent) -> Self {
+        MessageContent::ToolProgress(event)
+    }
+
     pub fn as_tool_request(&self) -> Option<&ToolRequest> {
         if let MessageContent::ToolRequest(ref tool_request) = self {
             Some(tool_request)
@@ -189,6 +195,14 @@ impl MessageContent {
         }
     }
 
+    pub fn as_tool_progress(&self) -> Option<&ToolProgressEvent> {
+        if let MessageContent::ToolProgress(ref event) = self {
+            Some(event)
+        } else {
+            None
+        }
+    }
+
     pub fn as_tool_response_text(&self) -> Option<String> {
         if let Some(tool_response) = self.as_tool_response() {
             if let Ok(contents) = &tool_response.tool_result {
@@ -375,6 +389,11 @@ impl Message {
         self.with_content(MessageContent::context_length_exceeded(msg))
     }
 
+    /// Add progress of a running tool call to the message
+    pub fn with_tool_progress(self, event: ToolProgressEvent) -> Self {
+        self.with_content(MessageContent::tool_progress(event))
+    }
+
     /// Get the concatenated text content of the message, separated by newlines
     pub fn as_concat_text(&self) -> String {
         self.content
@@ -140,6 +140,33 @@ impl ModelConfig {
     pub fn context_limit(&self) -> usize {
         self.context_limit.unwrap_or(DEFAULT_CONTEXT_LIMIT)
     }
+
+    /// Whether the model accepts image input, inferred from the model name
+    pub fn supports_vision(&self) -> bool {
+        match self.model_name.as_str() {
+            // Text-only variants of otherwise multimodal families
+            name if name.contains("o1-mini") || name.contains("o3-mini") => false,
+            name if name.contains("gpt-3.5") => false,
+
+            // OpenAI models
+            name if name.contains("gpt-4o") => true,
+            name if name.contains("gpt-4-turbo") => true,
+            name if name.contains("gpt-4.1") || name.contains("gpt-4-1") => true,
+            name if name.contains("o1") || name.contains("o3") || name.contains("o4") => true,
+
+            // Anthropic models
+            name if name.contains("claude-3") => true,
+            name if name.contains("claude-sonnet-4") || name.contains("claude-opus-4") => true,
+
+            // Google models
+            name if name.contains("gemini") => true,
+
+            // Open models with a vision variant
+            name if name.contains("vision") || name.contains("llava") => true,
+            name if name.contains("pixtral") || name.contains("-vl") => true,
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -165,6 +192,27 @@ mod tests {
         assert_eq!(config.context_limit(), DEFAULT_CONTEXT_LIMIT);
     }
 
+    #[test]
+    fn test_model_config_supports_vision() {
+        for model in [
+            "gpt-4o",
+            "claude-3-5-sonnet-latest",
+            "gemini-2.0-flash",
+            "llava:13b",
+        ] {
+            assert!(
+                ModelConfig::new(model.to_string()).supports_vision(),
+                "{model}"
+            );
+        }
+        for model in ["o3-mini", "gpt-3.5-turbo", "llama3.3", "qwen2.5"] {
+            assert!(
+                !ModelConfig::new(model.to_string()).supports_vision(),
+                "{model}"
+            );
+        }
+    }
+
     #[test]
     fn test_model_config_settings() {
         let config = ModelConfig::new("test-model".to_string())
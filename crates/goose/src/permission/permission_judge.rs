@@ -1,4 +1,5 @@
 use crate::agents::platform_tools::PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME;
+use crate::agents::PermissionPolicy;
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
 use crate::message::{Message, MessageContent, ToolRequest};
@@ -10,6 +11,7 @@ use mcp_core::{tool::Tool, TextContent};
 use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::sync::Arc;
+use tracing::debug;
 
 /// Creates the tool definition for checking read-only permissions.
 fn create_read_only_tool() -> Tool {
@@ -139,7 +141,7 @@ pub async fn detect_read_only_tools(
         .complete(
             "You are a good analyst and can detect operations whether they have read-only operations.",
             &check_messages,
-            &[tool.clone()],
+            std::slice::from_ref(&tool),
         )
         .await;
 
@@ -171,12 +173,13 @@ pub async fn check_tool_permissions(
     let mut denied = vec![];
     let mut llm_detect_candidates = vec![];
     let mut extension_request_ids = vec![];
+    let policy = PermissionPolicy::from_goose_mode(mode);
 
     for request in candidate_requests {
         if let Ok(tool_call) = request.tool_call.clone() {
             if mode == "chat" {
                 continue;
-            } else if mode == "auto" {
+            } else if policy == PermissionPolicy::AlwaysApprove {
                 approved.push(request.clone());
             } else {
                 if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
@@ -193,11 +196,20 @@ pub async fn check_tool_permissions(
                     continue;
                 }
 
-                // 2. Fallback based on mode
+                // 2. Tools annotated as read-only and non-destructive
+                if policy == PermissionPolicy::AutoApproveReadOnly
+                    && tools_with_readonly_annotation.contains(&tool_call.name)
+                {
+                    debug!(
+                        tool = %tool_call.name,
+                        mode, "Auto-approving tool annotated as read-only"
+                    );
+                    approved.push(request.clone());
+                    continue;
+                }
+
+                // 3. Fallback based on mode
                 match mode {
-                    "approve" => {
-                        needs_approval.push(request.clone());
-                    }
                     "smart_approve" => {
                         if let Some(level) =
                             permission_manager.get_smart_approve_permission(&tool_call.name)
@@ -210,9 +222,7 @@ pub async fn check_tool_permissions(
                             continue;
                         }
 
-                        if tools_with_readonly_annotation.contains(&tool_call.name) {
-                            approved.push(request.clone());
-                        } else if tools_without_annotation.contains(&tool_call.name) {
+                        if tools_without_annotation.contains(&tool_call.name) {
                             llm_detect_candidates.push(request.clone());
                         } else {
                             needs_approval.push(request.clone());
@@ -226,7 +236,7 @@ pub async fn check_tool_permissions(
         }
     }
 
-    // 3. LLM detect
+    // 4. LLM detect
     if !llm_detect_candidates.is_empty() && mode == "smart_approve" {
         let detected_readonly_tools =
             detect_read_only_tools(provider, llm_detect_candidates.iter().collect()).await;
@@ -462,6 +472,82 @@ mod tests {
         assert!(enable_extension_request_ids.iter().any(|id| id == "tool_3"));
     }
 
+    fn annotated_tool(name: &str, read_only: bool, destructive: bool) -> Tool {
+        Tool::new(
+            name.to_string(),
+            String::new(),
+            json!({"type": "object"}),
+            Some(ToolAnnotations {
+                title: None,
+                read_only_hint: read_only,
+                destructive_hint: destructive,
+                idempotent_hint: false,
+                open_world_hint: false,
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_check_tool_permissions_read_only_annotations() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut permission_manager = PermissionManager::new(temp_file.path());
+        let provider = create_mock_provider();
+
+        let tools = vec![
+            annotated_tool("file_viewer", true, false),
+            annotated_tool("file_deleter", false, true),
+        ];
+        let (tools_with_readonly_annotation, tools_without_annotation) =
+            crate::agents::Agent::categorize_tools_by_annotation(&tools);
+
+        let read_request = ToolRequest {
+            id: "tool_1".to_string(),
+            tool_call: ToolResult::Ok(ToolCall {
+                name: "file_viewer".to_string(),
+                arguments: json!({"path": "/path/to/file"}),
+            }),
+        };
+        let delete_request = ToolRequest {
+            id: "tool_2".to_string(),
+            tool_call: ToolResult::Ok(ToolCall {
+                name: "file_deleter".to_string(),
+                arguments: json!({"path": "/path/to/file"}),
+            }),
+        };
+
+        for mode in ["smart_approve", "approve"] {
+            assert_eq!(
+                PermissionPolicy::from_goose_mode(mode),
+                PermissionPolicy::AutoApproveReadOnly
+            );
+
+            let (result, _) = check_tool_permissions(
+                std::slice::from_ref(&read_request),
+                mode,
+                tools_with_readonly_annotation.clone(),
+                tools_without_annotation.clone(),
+                &mut permission_manager,
+                provider.clone(),
+            )
+            .await;
+            assert_eq!(result.approved.len(), 1, "{mode}");
+            assert!(result.needs_approval.is_empty(), "{mode}");
+
+            let (result, _) = check_tool_permissions(
+                std::slice::from_ref(&delete_request),
+                mode,
+                tools_with_readonly_annotation.clone(),
+                tools_without_annotation.clone(),
+                &mut permission_manager,
+                provider.clone(),
+            )
+            .await;
+            assert!(result.approved.is_empty(), "{mode}");
+            assert_eq!(result.needs_approval.len(), 1, "{mode}");
+            assert_eq!(result.needs_approval[0].id, "tool_2");
+        }
+    }
+
     #[tokio::test]
     async fn test_check_tool_permissions_auto() {
         // Setup mocks
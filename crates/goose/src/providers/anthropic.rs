@@ -60,6 +60,20 @@ impl AnthropicProvider {
         })
     }
 
+    /// Create a new AnthropicProvider with the specified host and API key
+    pub fn from_params(host: String, api_key: String, model: ModelConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()?;
+
+        Ok(Self {
+            client,
+            host,
+            api_key,
+            model,
+        })
+    }
+
     async fn post(&self, headers: HeaderMap, payload: Value) -> Result<Value, ProviderError> {
         let base_url = url::Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
@@ -103,7 +117,10 @@ impl AnthropicProvider {
             StatusCode::TOO_MANY_REQUESTS => {
                 Err(ProviderError::RateLimitExceeded(format!("{:?}", payload)))
             }
-            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+            StatusCode::SERVICE_UNAVAILABLE => {
+                Err(ProviderError::ServiceUnavailable(format!("{:?}", payload)))
+            }
+            StatusCode::INTERNAL_SERVER_ERROR => {
                 Err(ProviderError::ServerError(format!("{:?}", payload)))
             }
             _ => {
@@ -142,6 +159,10 @@ impl Provider for AnthropicProvider {
         self.model.clone()
     }
 
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
+
     #[tracing::instrument(
         skip(self, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
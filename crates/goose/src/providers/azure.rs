@@ -116,6 +116,10 @@ impl Provider for AzureProvider {
         self.model.clone()
     }
 
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
+
     #[tracing::instrument(
         skip(self, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
@@ -178,6 +178,27 @@ pub trait Provider: Send + Sync {
 
     /// Get the model config from the provider
     fn get_model_config(&self) -> ModelConfig;
+
+    /// The name of the provider, as in its metadata
+    ///
+    /// Defaults to an empty name, which providers created by name should override.
+    fn get_name(&self) -> String {
+        String::new()
+    }
+
+    /// Whether the configured model accepts image input
+    ///
+    /// Defaults to a heuristic on the model name, providers that know better can override it.
+    fn supports_vision(&self) -> bool {
+        self.get_model_config().supports_vision()
+    }
+
+    /// Fetch the names of the models available from the provider's API
+    ///
+    /// Returns `None` for providers that cannot list their models.
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
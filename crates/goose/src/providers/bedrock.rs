@@ -95,6 +95,10 @@ impl Provider for BedrockProvider {
         self.model.clone()
     }
 
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
+
     #[tracing::instrument(
         skip(self, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
@@ -218,6 +222,12 @@ impl Provider for BedrockProvider {
                                 err
                             )));
                         }
+                        ConverseError::ServiceUnavailableException(err) => {
+                            return Err(ProviderError::ServiceUnavailable(format!(
+                                "Failed to call Bedrock: {:?}",
+                                err
+                            )));
+                        }
                         ConverseError::ModelErrorException(err) => {
                             return Err(ProviderError::ExecutionError(format!(
                                 "Failed to call Bedrock: {:?}",
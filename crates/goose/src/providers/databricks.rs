@@ -227,7 +227,10 @@ impl DatabricksProvider {
             StatusCode::TOO_MANY_REQUESTS => {
                 Err(ProviderError::RateLimitExceeded(format!("{:?}", payload)))
             }
-            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+            StatusCode::SERVICE_UNAVAILABLE => {
+                Err(ProviderError::ServiceUnavailable(format!("{:?}", payload)))
+            }
+            StatusCode::INTERNAL_SERVER_ERROR => {
                 Err(ProviderError::ServerError(format!("{:?}", payload)))
             }
             _ => {
@@ -261,6 +264,10 @@ impl Provider for DatabricksProvider {
         self.model.clone()
     }
 
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
+
     #[tracing::instrument(
         skip(self, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
@@ -295,4 +302,53 @@ impl Provider for DatabricksProvider {
 
         Ok((message, ProviderUsage::new(model, usage)))
     }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        let base_url = Url::parse(&self.host)
+            .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
+        let url = base_url.join("api/2.0/serving-endpoints").map_err(|e| {
+            ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
+        })?;
+
+        let auth_header = self.ensure_auth_header().await?;
+        let response = self
+            .client
+            .get(url)
+            .header("Authorization", auth_header)
+            .send()
+            .await?;
+
+        let status = response.status();
+        match status {
+            StatusCode::OK => {}
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(ProviderError::Authentication(format!(
+                    "Authentication failed while listing serving endpoints. Status: {}",
+                    status
+                )))
+            }
+            _ => {
+                return Err(ProviderError::RequestFailed(format!(
+                    "Listing serving endpoints failed with status: {}",
+                    status
+                )))
+            }
+        }
+
+        let payload: Value = response.json().await.map_err(|e| {
+            ProviderError::RequestFailed(format!("Response body is not valid JSON: {}", e))
+        })?;
+        let models = payload
+            .get("endpoints")
+            .and_then(|endpoints| endpoints.as_array())
+            .map(|endpoints| {
+                endpoints
+                    .iter()
+                    .filter_map(|endpoint| endpoint.get("name").and_then(|name| name.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Some(models))
+    }
 }
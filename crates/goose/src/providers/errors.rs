@@ -15,9 +15,15 @@ pub enum ProviderError {
     #[error("Server error: {0}")]
     ServerError(String),
 
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("Request failed: {0}")]
     RequestFailed(String),
 
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
     #[error("Execution error: {0}")]
     ExecutionError(String),
 
@@ -33,7 +39,12 @@ impl From<anyhow::Error> for ProviderError {
 
 impl From<reqwest::Error> for ProviderError {
     fn from(error: reqwest::Error) -> Self {
-        ProviderError::ExecutionError(error.to_string())
+        // Timeouts and connection failures are transient and worth retrying
+        if error.is_timeout() || error.is_connect() || error.is_request() {
+            ProviderError::NetworkError(error.to_string())
+        } else {
+            ProviderError::ExecutionError(error.to_string())
+        }
     }
 }
 
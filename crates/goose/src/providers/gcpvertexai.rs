@@ -516,6 +516,10 @@ impl Provider for GcpVertexAIProvider {
     fn get_model_config(&self) -> ModelConfig {
         self.model.clone()
     }
+
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::time::Duration;
@@ -80,6 +80,25 @@ impl OpenAiProvider {
         })
     }
 
+    /// Create a new OpenAiProvider with the specified host and API key, and the default
+    /// settings otherwise
+    pub fn from_params(host: String, api_key: String, model: ModelConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()?;
+
+        Ok(Self {
+            client,
+            host,
+            base_path: "v1/chat/completions".to_string(),
+            api_key,
+            organization: None,
+            project: None,
+            model,
+            custom_headers: None,
+        })
+    }
+
     async fn post(&self, payload: Value) -> Result<Value, ProviderError> {
         let base_url = url::Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
@@ -87,10 +106,15 @@ impl OpenAiProvider {
             ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
         })?;
 
-        let mut request = self
-            .client
-            .post(url)
-            .header("Authorization", format!("Bearer {}", self.api_key));
+        let request = self.client.post(url);
+        let response = self.with_headers(request).json(&payload).send().await?;
+
+        handle_response_openai_compat(response).await
+    }
+
+    /// Add the authorization, organization, project and custom headers to a request
+    fn with_headers(&self, mut request: RequestBuilder) -> RequestBuilder {
+        request = request.header("Authorization", format!("Bearer {}", self.api_key));
 
         // Add organization header if present
         if let Some(org) = &self.organization {
@@ -107,10 +131,7 @@ impl OpenAiProvider {
                 request = request.header(key, value);
             }
         }
-
-        let response = request.json(&payload).send().await?;
-
-        handle_response_openai_compat(response).await
+        request
     }
 }
 
@@ -140,6 +161,10 @@ impl Provider for OpenAiProvider {
         self.model.clone()
     }
 
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
+
     #[tracing::instrument(
         skip(self, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
@@ -169,6 +194,35 @@ impl Provider for OpenAiProvider {
         emit_debug_trace(&self.model, &payload, &response, &usage);
         Ok((message, ProviderUsage::new(model, usage)))
     }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        // The models endpoint is next to the chat completions one, such as v1/models
+        let models_path = self
+            .base_path
+            .strip_suffix("chat/completions")
+            .map(|prefix| format!("{}models", prefix))
+            .unwrap_or_else(|| "v1/models".to_string());
+        let base_url = url::Url::parse(&self.host)
+            .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
+        let url = base_url.join(&models_path).map_err(|e| {
+            ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
+        })?;
+
+        let response = self.with_headers(self.client.get(url)).send().await?;
+        let payload = handle_response_openai_compat(response).await?;
+        let mut models: Vec<String> = payload
+            .get("data")
+            .and_then(|data| data.as_array())
+            .map(|data| {
+                data.iter()
+                    .filter_map(|model| model.get("id").and_then(|id| id.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        models.sort();
+        Ok(Some(models))
+    }
 }
 
 fn parse_custom_headers(s: String) -> HashMap<String, String> {
@@ -181,3 +235,47 @@ fn parse_custom_headers(s: String) -> HashMap<String, String> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_fetch_supported_models() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("Authorization", "Bearer test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "list",
+                "data": [
+                    {"id": "gpt-4o-mini", "object": "model", "owned_by": "system"},
+                    {"id": "gpt-4o", "object": "model", "owned_by": "system"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = OpenAiProvider::from_params(
+            server.uri(),
+            "test-key".to_string(),
+            ModelConfig::new(OPEN_AI_DEFAULT_MODEL.to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            provider.fetch_supported_models().await.unwrap(),
+            Some(vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()])
+        );
+
+        let provider = OpenAiProvider::from_params(
+            server.uri(),
+            "wrong-key".to_string(),
+            ModelConfig::new(OPEN_AI_DEFAULT_MODEL.to_string()),
+        )
+        .unwrap();
+        assert!(provider.fetch_supported_models().await.is_err());
+    }
+}
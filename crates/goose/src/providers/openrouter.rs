@@ -114,7 +114,8 @@ impl OpenRouterProvider {
             match error_code {
                 401 | 403 => return Err(ProviderError::Authentication(error_message.to_string())),
                 429 => return Err(ProviderError::RateLimitExceeded(error_message.to_string())),
-                500 | 503 => return Err(ProviderError::ServerError(error_message.to_string())),
+                500 => return Err(ProviderError::ServerError(error_message.to_string())),
+                503 => return Err(ProviderError::ServiceUnavailable(error_message.to_string())),
                 _ => return Err(ProviderError::RequestFailed(error_message.to_string())),
             }
         }
@@ -247,6 +248,10 @@ impl Provider for OpenRouterProvider {
         self.model.clone()
     }
 
+    fn get_name(&self) -> String {
+        Self::metadata().name
+    }
+
     #[tracing::instrument(
         skip(self, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
@@ -77,7 +77,10 @@ pub async fn handle_response_openai_compat(response: Response) -> Result<Value,
         StatusCode::TOO_MANY_REQUESTS => {
             Err(ProviderError::RateLimitExceeded(format!("{:?}", payload)))
         }
-        StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+        StatusCode::SERVICE_UNAVAILABLE => {
+            Err(ProviderError::ServiceUnavailable(format!("{:?}", payload)))
+        }
+        StatusCode::INTERNAL_SERVER_ERROR => {
             Err(ProviderError::ServerError(format!("{:?}", payload)))
         }
         _ => {
@@ -168,7 +171,10 @@ pub async fn handle_response_google_compat(response: Response) -> Result<Value,
         StatusCode::TOO_MANY_REQUESTS => {
             Err(ProviderError::RateLimitExceeded(format!("{:?}", payload)))
         }
-        StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+        StatusCode::SERVICE_UNAVAILABLE => {
+            Err(ProviderError::ServiceUnavailable(format!("{:?}", payload)))
+        }
+        StatusCode::INTERNAL_SERVER_ERROR => {
             Err(ProviderError::ServerError(format!("{:?}", payload)))
         }
         _ => {
@@ -4,7 +4,7 @@ use std::cmp::Ordering;
 
 use crate::session::{self, SessionMetadata};
 
-#[derive(Clone, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SessionInfo {
     pub id: String,
     pub path: String,
@@ -3,9 +3,10 @@ pub mod storage;
 
 // Re-export common session types and functions
 pub use storage::{
-    ensure_session_dir, generate_description, generate_session_id, get_most_recent_session,
-    get_path, list_sessions, persist_messages, read_messages, read_metadata, update_metadata,
-    Identifier, SessionMetadata,
+    check_session_dir, checkpoints_path, ensure_session_dir, generate_description,
+    generate_session_id, get_most_recent_session, get_path, list_sessions, persist_messages,
+    read_messages, read_metadata, remove_session, update_metadata, Identifier, SessionMetadata,
+    SESSION_DIR_ENV,
 };
 
 pub use info::{get_session_info, SessionInfo};
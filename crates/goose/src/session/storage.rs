@@ -115,26 +115,82 @@ pub fn get_path(id: Identifier) -> PathBuf {
     }
 }
 
+/// The file the checkpoints of a session are kept in, next to the session file
+pub fn checkpoints_path(session_file: &Path) -> PathBuf {
+    session_file.with_extension("checkpoints.json")
+}
+
+/// Delete a session file along with its checkpoints
+pub fn remove_session(session_file: &Path) -> Result<()> {
+    fs::remove_file(session_file)?;
+    match fs::remove_file(checkpoints_path(session_file)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Environment variable overriding the directory session files are stored in
+pub const SESSION_DIR_ENV: &str = "GOOSE_SESSION_DIR";
+
 /// Ensure the session directory exists and return its path
+///
+/// This is `GOOSE_SESSION_DIR` when it is set, and otherwise the `sessions` directory
+/// in the goose data directory.
 pub fn ensure_session_dir() -> Result<PathBuf> {
-    let app_strategy = AppStrategyArgs {
-        top_level_domain: "Block".to_string(),
-        author: "Block".to_string(),
-        app_name: APP_NAME.to_string(),
+    let data_dir = match std::env::var_os(SESSION_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let app_strategy = AppStrategyArgs {
+                top_level_domain: "Block".to_string(),
+                author: "Block".to_string(),
+                app_name: APP_NAME.to_string(),
+            };
+            choose_app_strategy(app_strategy)
+                .expect("goose requires a home dir")
+                .data_dir()
+                .join("sessions")
+        }
     };
 
-    let data_dir = choose_app_strategy(app_strategy)
-        .expect("goose requires a home dir")
-        .data_dir()
-        .join("sessions");
-
     if !data_dir.exists() {
+        // Only create the directory under a parent we can write to, so a mistyped
+        // path fails with a clear error rather than halfway through
+        if let Some(parent) = data_dir.ancestors().skip(1).find(|dir| dir.exists()) {
+            if fs::metadata(parent)?.permissions().readonly() {
+                return Err(anyhow::anyhow!(
+                    "Cannot create session directory {}: {} is not writable",
+                    data_dir.display(),
+                    parent.display()
+                ));
+            }
+        }
         fs::create_dir_all(&data_dir)?;
+    } else if !data_dir.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Session directory {} is not a directory",
+            data_dir.display()
+        ));
     }
 
     Ok(data_dir)
 }
 
+/// Ensure the session directory exists and that session files can be written to it
+pub fn check_session_dir() -> Result<PathBuf> {
+    let data_dir = ensure_session_dir()?;
+    let probe = data_dir.join(format!(".goose-write-check-{}", std::process::id()));
+    File::create(&probe)
+        .and_then(|_| fs::remove_file(&probe))
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Session directory {} is not writable: {}",
+                data_dir.display(),
+                e
+            )
+        })?;
+    Ok(data_dir)
+}
+
 /// Get the path to the most recently modified session file
 pub fn get_most_recent_session() -> Result<PathBuf> {
     let session_dir = ensure_session_dir()?;
@@ -369,6 +425,7 @@ pub async fn update_metadata(session_file: &Path, metadata: &SessionMetadata) ->
 mod tests {
     use super::*;
     use crate::message::MessageContent;
+    use serial_test::serial;
     use tempfile::tempdir;
 
     #[tokio::test]
@@ -419,6 +476,66 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_session_dir_from_env() -> Result<()> {
+        let dir = tempdir()?;
+        let session_dir = dir.path().join("project").join("sessions");
+        std::env::set_var(SESSION_DIR_ENV, &session_dir);
+
+        // The directory is created on first use, and session files are written to it
+        let session_file = get_path(Identifier::Name("local".to_string()));
+        persist_messages(&session_file, &[Message::user().with_text("Hello")], None).await?;
+        let sessions = list_sessions();
+        std::env::remove_var(SESSION_DIR_ENV);
+
+        assert_eq!(session_file, session_dir.join("local.jsonl"));
+        assert!(session_file.exists());
+        assert_eq!(sessions?, vec![("local".to_string(), session_file)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_session_with_checkpoints() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("release v1.2.jsonl");
+        let checkpoints = checkpoints_path(&session_file);
+        assert_eq!(
+            checkpoints,
+            dir.path().join("release v1.2.checkpoints.json")
+        );
+
+        fs::write(&session_file, "")?;
+        fs::write(&checkpoints, "[]")?;
+        remove_session(&session_file)?;
+        assert!(!session_file.exists());
+        assert!(!checkpoints.exists());
+
+        // Sessions without checkpoints are removed too
+        fs::write(&session_file, "")?;
+        remove_session(&session_file)?;
+        assert!(!session_file.exists());
+        assert!(remove_session(&session_file).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_session_dir_must_be_a_directory() -> Result<()> {
+        let dir = tempdir()?;
+        let file = dir.path().join("sessions");
+        fs::write(&file, "")?;
+        std::env::set_var(SESSION_DIR_ENV, &file);
+        let result = check_session_dir();
+        std::env::remove_var(SESSION_DIR_ENV);
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("is not a directory"));
+        Ok(())
+    }
+
     #[test]
     fn test_generate_session_id() {
         let id = generate_session_id();
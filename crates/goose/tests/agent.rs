@@ -307,3 +307,106 @@ mod tests {
         .await
     }
 }
+
+#[cfg(test)]
+mod tool_restriction_tests {
+    use super::*;
+    use goose::agents::ToolRestrictions;
+    use goose::message::MessageContent;
+    use goose::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use mcp_core::tool::{Tool, ToolCall};
+    use mcp_core::ToolError;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    /// Provider that requests two tool calls and then finishes with a text reply
+    struct ToolCallingProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for ToolCallingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("test-model".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let usage = ProviderUsage::new("mock".to_string(), Usage::default());
+            if messages.len() == 1 {
+                Ok((
+                    Message::assistant()
+                        .with_tool_request(
+                            "allowed",
+                            Ok(ToolCall::new("platform__list_resources", json!({}))),
+                        )
+                        .with_tool_request(
+                            "denied",
+                            Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+                        ),
+                    usage,
+                ))
+            } else {
+                Ok((Message::assistant().with_text("done"), usage))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_whitelist_denies_other_tools() -> Result<()> {
+        let agent = Agent::new();
+        agent.update_provider(Arc::new(ToolCallingProvider)).await?;
+        agent
+            .set_tool_restrictions(ToolRestrictions {
+                whitelist: Some(HashSet::from(["platform__list_resources".to_string()])),
+                blacklist: HashSet::new(),
+            })
+            .await;
+
+        let messages = vec![Message::user().with_text("list resources and files")];
+        let reply_stream = agent.reply(&messages, None).await?;
+        tokio::pin!(reply_stream);
+
+        let mut tool_responses = Vec::new();
+        while let Some(message) = reply_stream.next().await {
+            for content in message?.content {
+                if let MessageContent::ToolResponse(response) = content {
+                    tool_responses.push(response);
+                }
+            }
+        }
+
+        assert_eq!(tool_responses.len(), 2);
+        for response in tool_responses {
+            match response.id.as_str() {
+                "allowed" => assert!(response.tool_result.is_ok()),
+                "denied" => assert_eq!(
+                    response.tool_result.unwrap_err(),
+                    ToolError::ExecutionError("tool not permitted by session whitelist".into())
+                ),
+                other => panic!("Unexpected tool response {}", other),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tool_blacklist_matches_tools_and_extensions() {
+        let restrictions = ToolRestrictions {
+            whitelist: None,
+            blacklist: HashSet::from(["developer".to_string(), "memory__remove".to_string()]),
+        };
+
+        assert!(restrictions.check("developer__shell").is_err());
+        assert!(restrictions.check("memory__remove").is_err());
+        assert!(restrictions.check("memory__retrieve").is_ok());
+    }
+}
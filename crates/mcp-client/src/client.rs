@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::atomic::{AtomicU64, Ordering};
 use thiserror::Error;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use tower::{Service, ServiceExt}; // for Service::ready()
 
 pub type BoxError = Box<dyn std::error::Error + Sync + Send>;
@@ -55,6 +55,23 @@ impl From<BoxError> for Error {
     }
 }
 
+impl Error {
+    /// Whether the request failed because the server did not respond in time, which tower
+    /// reports as a boxed `Elapsed` that may be wrapped in other errors
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Timeout(_) => true,
+            Error::ServerBoxError(source) | Error::McpServerError { source, .. } => {
+                source.is::<tower::timeout::error::Elapsed>()
+                    || source
+                        .downcast_ref::<Error>()
+                        .is_some_and(Error::is_timeout)
+            }
+            _ => false,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ClientInfo {
     pub name: String,
@@ -97,6 +114,13 @@ pub trait McpClientTrait: Send + Sync {
     async fn list_prompts(&self, next_cursor: Option<String>) -> Result<ListPromptsResult, Error>;
 
     async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult, Error>;
+
+    /// Subscribe to notifications sent by the server, such as tool progress
+    ///
+    /// Returns None when the client was not connected to the server's notifications.
+    fn subscribe(&self) -> Option<broadcast::Receiver<JsonRpcMessage>> {
+        None
+    }
 }
 
 /// The MCP client is the interface for MCP operations.
@@ -110,6 +134,7 @@ where
     next_id: AtomicU64,
     server_capabilities: Option<ServerCapabilities>,
     server_info: Option<Implementation>,
+    notifications: Option<broadcast::Receiver<JsonRpcMessage>>,
 }
 
 impl<S> McpClient<S>
@@ -124,9 +149,20 @@ where
             next_id: AtomicU64::new(1),
             server_capabilities: None,
             server_info: None,
+            notifications: None,
         }
     }
 
+    /// Receive notifications from the server through a transport subscription,
+    /// see [`crate::TransportHandle::subscribe`]
+    pub fn with_notifications(
+        mut self,
+        notifications: broadcast::Receiver<JsonRpcMessage>,
+    ) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
     /// Send a JSON-RPC request and check we don't get an error response.
     async fn send_request<R>(&self, method: &str, params: Value) -> Result<R, Error>
     where
@@ -388,4 +424,8 @@ where
 
         self.send_request("prompts/get", params).await
     }
+
+    fn subscribe(&self) -> Option<broadcast::Receiver<JsonRpcMessage>> {
+        self.notifications.as_ref().map(|rx| rx.resubscribe())
+    }
 }
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use mcp_core::protocol::JsonRpcMessage;
 use std::collections::HashMap;
 use thiserror::Error;
-use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
 
 pub type BoxError = Box<dyn std::error::Error + Sync + Send>;
 /// A generic error type for transport operations.
@@ -58,6 +58,38 @@ pub trait Transport {
 #[async_trait]
 pub trait TransportHandle: Send + Sync + Clone + 'static {
     async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error>;
+
+    /// Subscribe to notifications sent by the server
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcMessage>;
+}
+
+/// Number of server notifications buffered per subscriber before older ones are dropped
+pub const NOTIFICATION_BUFFER_SIZE: usize = 256;
+
+/// Route a message received from the server to the pending request it answers, or to
+/// notification subscribers.
+pub async fn dispatch_incoming(
+    message: JsonRpcMessage,
+    pending_requests: &PendingRequests,
+    notifications: &broadcast::Sender<JsonRpcMessage>,
+) {
+    match &message {
+        JsonRpcMessage::Response(response) => {
+            if let Some(id) = &response.id {
+                pending_requests.respond(&id.to_string(), Ok(message)).await;
+            }
+        }
+        JsonRpcMessage::Error(error) => {
+            if let Some(id) = &error.id {
+                pending_requests.respond(&id.to_string(), Ok(message)).await;
+            }
+        }
+        JsonRpcMessage::Notification(_) => {
+            // No subscribers is not an error, the notification is simply dropped
+            let _ = notifications.send(message);
+        }
+        _ => {} // TODO: Handle other variants (Request, etc.)
+    }
 }
 
 // Helper function that contains the common send implementation
@@ -6,12 +6,14 @@ use mcp_core::protocol::{JsonRpcMessage, JsonRpcRequest};
 use reqwest::Client as HttpClient;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio::time::{timeout, Duration};
 use tracing::warn;
 use url::Url;
 
-use super::{send_message, Transport, TransportHandle};
+use super::{
+    dispatch_incoming, send_message, Transport, TransportHandle, NOTIFICATION_BUFFER_SIZE,
+};
 
 // Timeout for the endpoint discovery
 const ENDPOINT_TIMEOUT_SECS: u64 = 5;
@@ -24,6 +26,8 @@ pub struct SseActor {
     receiver: mpsc::Receiver<TransportMessage>,
     /// Map of request-id -> oneshot sender
     pending_requests: Arc<PendingRequests>,
+    /// Subscribers to notifications sent by the server
+    notifications: broadcast::Sender<JsonRpcMessage>,
     /// Base SSE URL
     sse_url: String,
     /// For sending HTTP POST requests
@@ -36,12 +40,14 @@ impl SseActor {
     pub fn new(
         receiver: mpsc::Receiver<TransportMessage>,
         pending_requests: Arc<PendingRequests>,
+        notifications: broadcast::Sender<JsonRpcMessage>,
         sse_url: String,
         post_endpoint: Arc<RwLock<Option<String>>>,
     ) -> Self {
         Self {
             receiver,
             pending_requests,
+            notifications,
             sse_url,
             post_endpoint,
             http_client: HttpClient::new(),
@@ -56,6 +62,7 @@ impl SseActor {
             Self::handle_incoming_messages(
                 self.sse_url.clone(),
                 Arc::clone(&self.pending_requests),
+                self.notifications.clone(),
                 Arc::clone(&self.post_endpoint)
             ),
             Self::handle_outgoing_messages(
@@ -70,10 +77,12 @@ impl SseActor {
     /// Continuously reads SSE events from `sse_url`.
     /// - If an `endpoint` event is received, store it in `post_endpoint`.
     /// - If a `message` event is received, parse it as `JsonRpcMessage`
-    ///   and respond to pending requests if it's a `Response`.
+    ///   and respond to pending requests if it's a `Response`, or forward it to
+    ///   subscribers if it's a `Notification`.
     async fn handle_incoming_messages(
         sse_url: String,
         pending_requests: Arc<PendingRequests>,
+        notifications: broadcast::Sender<JsonRpcMessage>,
         post_endpoint: Arc<RwLock<Option<String>>>,
     ) {
         let client = match eventsource_client::ClientBuilder::for_url(&sse_url) {
@@ -111,23 +120,7 @@ impl SseActor {
                     // Attempt to parse the SSE data as a JsonRpcMessage
                     match serde_json::from_str::<JsonRpcMessage>(&e.data) {
                         Ok(message) => {
-                            match &message {
-                                JsonRpcMessage::Response(response) => {
-                                    if let Some(id) = &response.id {
-                                        pending_requests
-                                            .respond(&id.to_string(), Ok(message))
-                                            .await;
-                                    }
-                                }
-                                JsonRpcMessage::Error(error) => {
-                                    if let Some(id) = &error.id {
-                                        pending_requests
-                                            .respond(&id.to_string(), Ok(message))
-                                            .await;
-                                    }
-                                }
-                                _ => {} // TODO: Handle other variants (Request, etc.)
-                            }
+                            dispatch_incoming(message, &pending_requests, &notifications).await;
                         }
                         Err(err) => {
                             warn!("Failed to parse SSE message: {err}");
@@ -223,6 +216,7 @@ impl SseActor {
 #[derive(Clone)]
 pub struct SseTransportHandle {
     sender: mpsc::Sender<TransportMessage>,
+    notifications: broadcast::Sender<JsonRpcMessage>,
 }
 
 #[async_trait::async_trait]
@@ -230,6 +224,10 @@ impl TransportHandle for SseTransportHandle {
     async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error> {
         send_message(&self.sender, message).await
     }
+
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcMessage> {
+        self.notifications.subscribe()
+    }
 }
 
 #[derive(Clone)]
@@ -279,6 +277,7 @@ impl Transport for SseTransport {
 
         // Create a channel for outgoing TransportMessages
         let (tx, rx) = mpsc::channel(32);
+        let (notification_tx, _) = broadcast::channel(NOTIFICATION_BUFFER_SIZE);
 
         let post_endpoint: Arc<RwLock<Option<String>>> = Arc::new(RwLock::new(None));
         let post_endpoint_clone = Arc::clone(&post_endpoint);
@@ -287,6 +286,7 @@ impl Transport for SseTransport {
         let actor = SseActor::new(
             rx,
             Arc::new(PendingRequests::new()),
+            notification_tx.clone(),
             self.sse_url.clone(),
             post_endpoint,
         );
@@ -301,7 +301,10 @@ impl Transport for SseTransport {
         )
         .await
         {
-            Ok(_) => Ok(SseTransportHandle { sender: tx }),
+            Ok(_) => Ok(SseTransportHandle {
+                sender: tx,
+                notifications: notification_tx,
+            }),
             Err(e) => Err(Error::SseConnection(e.to_string())),
         }
     }
@@ -15,6 +15,8 @@ pub enum ToolError {
     SchemaError(String),
     #[error("Tool not found: {0}")]
     NotFound(String),
+    #[error("Execution timed out: {0}")]
+    Timeout(String),
 }
 
 pub type ToolResult<T> = std::result::Result<T, ToolError>;
@@ -237,6 +237,38 @@ pub struct GetPromptResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmptyResult {}
 
+/// Method of the notification a server sends while a tool call is still running
+pub const TOOL_PROGRESS_NOTIFICATION: &str = "notifications/tools/progress";
+
+/// Partial progress of a running tool call, sent as a `notifications/tools/progress` notification
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolProgressEvent {
+    pub tool_name: String,
+    /// Completion between 0 and 100, when the tool knows how much work remains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress_percent: Option<f32>,
+    pub message: String,
+}
+
+impl ToolProgressEvent {
+    pub fn to_notification(&self) -> JsonRpcNotification {
+        JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: TOOL_PROGRESS_NOTIFICATION.to_string(),
+            params: serde_json::to_value(self).ok(),
+        }
+    }
+
+    /// Parse a progress event from a notification, if it is one
+    pub fn from_notification(notification: &JsonRpcNotification) -> Option<Self> {
+        if notification.method != TOOL_PROGRESS_NOTIFICATION {
+            return None;
+        }
+        serde_json::from_value(notification.params.clone()?).ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,4 +318,31 @@ mod tests {
             _ => panic!("Expected Request"),
         }
     }
+
+    #[test]
+    fn test_tool_progress_notification_round_trip() {
+        let event = ToolProgressEvent {
+            tool_name: "shell".to_string(),
+            progress_percent: Some(50.0),
+            message: "halfway".to_string(),
+        };
+
+        let notification = event.to_notification();
+        assert_eq!(notification.method, TOOL_PROGRESS_NOTIFICATION);
+        assert_eq!(
+            notification.params,
+            Some(json!({"toolName": "shell", "progressPercent": 50.0, "message": "halfway"}))
+        );
+        assert_eq!(
+            ToolProgressEvent::from_notification(&notification),
+            Some(event)
+        );
+
+        let other = JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: "notifications/initialized".to_string(),
+            params: None,
+        };
+        assert_eq!(ToolProgressEvent::from_notification(&other), None);
+    }
 }
@@ -4,9 +4,12 @@ use std::{
 };
 
 use futures::{Future, Stream};
-use mcp_core::protocol::{JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
+use mcp_core::protocol::{
+    JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, ToolProgressEvent,
+};
 use pin_project::pin_project;
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
 use tower_service::Service;
 
 mod errors;
@@ -15,6 +18,9 @@ pub use errors::{BoxError, RouterError, ServerError, TransportError};
 pub mod router;
 pub use router::Router;
 
+mod progress;
+pub use progress::report_progress;
+
 /// A transport layer that handles JSON-RPC messages over byte
 #[pin_project]
 pub struct ByteTransport<R, W> {
@@ -159,8 +165,25 @@ where
                                 "Received request"
                             );
 
-                            // Process the request using our service
-                            let response = match service.call(request).await {
+                            // Process the request using our service, forwarding any tool progress
+                            // reported in the meantime as notifications ahead of the response
+                            let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+                            let call =
+                                progress::with_progress_sender(progress_tx, service.call(request));
+                            tokio::pin!(call);
+                            let result = loop {
+                                tokio::select! {
+                                    result = &mut call => break result,
+                                    Some(event) = progress_rx.recv() => {
+                                        Self::write_progress(&mut transport, event).await?;
+                                    }
+                                }
+                            };
+                            while let Ok(event) = progress_rx.try_recv() {
+                                Self::write_progress(&mut transport, event).await?;
+                            }
+
+                            let response = match result {
                                 Ok(resp) => resp,
                                 Err(e) => {
                                     let error_msg = e.into().to_string();
@@ -241,6 +264,21 @@ where
 
         Ok(())
     }
+
+    async fn write_progress<R, W>(
+        transport: &mut ByteTransport<R, W>,
+        event: ToolProgressEvent,
+    ) -> Result<(), ServerError>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        tracing::debug!(tool = %event.tool_name, message = %event.message, "Sending tool progress");
+        transport
+            .write_message(JsonRpcMessage::Notification(event.to_notification()))
+            .await
+            .map_err(|e| ServerError::Transport(TransportError::Io(e)))
+    }
 }
 
 // Define a specific service implementation that we need for any
@@ -0,0 +1,64 @@
+use std::future::Future;
+
+use mcp_core::protocol::ToolProgressEvent;
+use tokio::sync::mpsc;
+
+/// Where progress reported by the currently running tool call is sent
+#[derive(Clone)]
+struct ProgressContext {
+    tool_name: Option<String>,
+    sender: mpsc::UnboundedSender<ToolProgressEvent>,
+}
+
+tokio::task_local! {
+    static PROGRESS: ProgressContext;
+}
+
+/// Run a request, collecting any tool progress reported while it runs into `sender`
+pub(crate) async fn with_progress_sender<F: Future>(
+    sender: mpsc::UnboundedSender<ToolProgressEvent>,
+    future: F,
+) -> F::Output {
+    PROGRESS
+        .scope(
+            ProgressContext {
+                tool_name: None,
+                sender,
+            },
+            future,
+        )
+        .await
+}
+
+/// Run a tool call, attributing any progress it reports to `tool_name`
+pub(crate) async fn with_tool_name<F: Future>(tool_name: &str, future: F) -> F::Output {
+    match PROGRESS.try_with(|ctx| ctx.sender.clone()) {
+        Ok(sender) => {
+            PROGRESS
+                .scope(
+                    ProgressContext {
+                        tool_name: Some(tool_name.to_string()),
+                        sender,
+                    },
+                    future,
+                )
+                .await
+        }
+        Err(_) => future.await,
+    }
+}
+
+/// Report progress of the tool call currently being handled
+///
+/// The event is forwarded to the client as a `notifications/tools/progress` notification
+/// before the tool result. Outside of a tool call handled by [`crate::Server`] this is a no-op.
+pub fn report_progress<S: Into<String>>(progress_percent: Option<f32>, message: S) {
+    let _ = PROGRESS.try_with(|ctx| {
+        let event = ToolProgressEvent {
+            tool_name: ctx.tool_name.clone().unwrap_or_default(),
+            progress_percent,
+            message: message.into(),
+        };
+        let _ = ctx.sender.send(event);
+    });
+}
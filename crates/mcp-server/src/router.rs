@@ -21,7 +21,7 @@ use mcp_core::{
 use serde_json::Value;
 use tower_service::Service;
 
-use crate::{BoxError, RouterError};
+use crate::{progress, BoxError, RouterError};
 
 /// Builder for configuring and constructing capabilities
 pub struct CapabilitiesBuilder {
@@ -172,7 +172,8 @@ pub trait Router: Send + Sync + 'static {
 
             let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
 
-            let result = match self.call_tool(name, arguments).await {
+            let result = match progress::with_tool_name(name, self.call_tool(name, arguments)).await
+            {
                 Ok(result) => CallToolResult {
                     content: result,
                     is_error: None,